@@ -0,0 +1,43 @@
+//! 大量环境变量下的配置键构建基准
+//!
+//! 运行：`cargo bench --bench key_trie`
+//!
+//! 以数千个带前缀的环境变量构建嵌套配置字典，并查询每个叶子的来源。
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use figment::Provider;
+use quantum_config::providers::{EnvSnapshot, QuantumConfigEnvProvider};
+use std::hint::black_box;
+
+/// 生成 `sections` × `keys` 个形如 `BENCH_SECTION7__KEY42=value` 的变量
+fn env_vars(sections: usize, keys: usize) -> Vec<(String, String)> {
+    (0..sections)
+        .flat_map(|section| {
+            (0..keys).map(move |key| (format!("BENCH_SECTION{}__KEY{}", section, key), format!("value-{}", key)))
+        })
+        .collect()
+}
+
+fn bench_env_key_trie(c: &mut Criterion) {
+    let mut group = c.benchmark_group("env_key_trie");
+    for (sections, keys) in [(10, 100), (50, 100), (100, 200)] {
+        let vars = env_vars(sections, keys);
+        let provider = QuantumConfigEnvProvider::with_prefix("BENCH_").with_snapshot(EnvSnapshot::from_vars(vars));
+        let total = sections * keys;
+
+        group.bench_with_input(BenchmarkId::new("data", total), &provider, |b, provider| {
+            b.iter(|| black_box(provider.data().expect("env data")));
+        });
+        group.bench_with_input(BenchmarkId::new("key_trie+origins", total), &provider, |b, provider| {
+            b.iter(|| {
+                let trie = provider.key_trie().expect("env key trie");
+                black_box(trie.origin("section0.key0"));
+                black_box(trie.origins().len())
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_env_key_trie);
+criterion_main!(benches);
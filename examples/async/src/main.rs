@@ -220,9 +220,13 @@ impl ConfigManager {
                 Ok(new_config) => {
                     let current_config = self.get_config().await;
                     
-                    // 简单的配置比较（实际应用中可能需要更复杂的比较逻辑）
-                    if format!("{:?}", new_config) != format!("{:?}", current_config) {
+                    // 按键比较配置，列出发生变化的键
+                    let changes = quantum_config::diff::diff(&current_config, &new_config).unwrap_or_default();
+                    if !changes.is_empty() {
                         println!("检测到配置变更，正在更新...");
+                        for change in &changes {
+                            println!("  {}", change);
+                        }
                         self.update_config(new_config).await;
                         println!("配置已更新");
                     } else {
@@ -673,6 +677,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
     // 删除未使用的导入以消除警告
@@ -682,7 +687,7 @@ mod tests {
     fn test_async_app_config_new() {
         // Test that we can create an AsyncAppConfig using new()
         let config = AsyncAppConfig::new();
-        assert!(config.name.len() > 0, "name should have a default value");
+        assert!(!config.name.is_empty(), "name should have a default value");
         assert!(config.port > 0, "port should have a valid default value");
     }
 
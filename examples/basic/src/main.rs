@@ -109,6 +109,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 }
 
 #[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
 mod tests {
     use super::*;
 
@@ -116,7 +117,7 @@ mod tests {
     fn test_basic_config_new() {
         // Test that we can create a BasicConfig using new()
         let config = BasicConfig::new();
-        assert!(config.name.len() > 0, "name should have a default value");
+        assert!(!config.name.is_empty(), "name should have a default value");
         assert!(config.debug.is_some(), "debug field should be accessible");
         assert!(config.port > 0, "port should have a valid default value");
     }
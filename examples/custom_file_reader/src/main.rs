@@ -7,7 +7,7 @@ use std::sync::{Arc, Mutex};
 
 /// 自定义的内存文件读取器
 /// 这个实现展示了如何创建一个完全自定义的文件读取策略
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct MemoryFileReader {
     /// 内存中的文件存储，使用 Arc<Mutex<>> 来支持多线程访问
     files: Arc<Mutex<HashMap<String, String>>>,
@@ -26,10 +26,7 @@ impl MemoryFileReader {
         let path_str = path.as_ref().to_string_lossy().to_string();
         let mut files = self.files.lock().map_err(|_| {
             QuantumConfigError::Io {
-                source: std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Failed to acquire lock on memory files",
-                ),
+                source: std::io::Error::other("Failed to acquire lock on memory files"),
                 path: std::path::PathBuf::new(),
             }
         })?;
@@ -42,10 +39,7 @@ impl MemoryFileReader {
         let path_str = path.as_ref().to_string_lossy().to_string();
         let mut files = self.files.lock().map_err(|_| {
             QuantumConfigError::Io {
-                source: std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Failed to acquire lock on memory files",
-                ),
+                source: std::io::Error::other("Failed to acquire lock on memory files"),
                 path: std::path::PathBuf::new(),
             }
         })?;
@@ -57,10 +51,7 @@ impl MemoryFileReader {
     pub fn list_files(&self) -> Result<Vec<String>, QuantumConfigError> {
         let files = self.files.lock().map_err(|_| {
             QuantumConfigError::Io {
-                source: std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Failed to acquire lock on memory files",
-                ),
+                source: std::io::Error::other("Failed to acquire lock on memory files"),
                 path: std::path::PathBuf::new(),
             }
         })?;
@@ -74,10 +65,7 @@ impl FileReader for MemoryFileReader {
         let path_str = path.to_string_lossy().to_string();
         let files = self.files.lock().map_err(|_| {
             QuantumConfigError::Io {
-                source: std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Failed to acquire lock on memory files",
-                ),
+                source: std::io::Error::other("Failed to acquire lock on memory files"),
                 path: path.to_path_buf(),
             }
         })?;
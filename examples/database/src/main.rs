@@ -8,19 +8,21 @@
 //! - SSL/TLS 配置
 //! - 读写分离配置
 
-use quantum_config::Config;
+use quantum_config::{Config, Validate, ValidationError};
 use serde::{Deserialize, Serialize};
 use std::{error::Error, time::Duration};
 use tracing::{error, info, warn};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Config, Serialize, Deserialize, Debug, Default)]
-#[config(env_prefix = "DB_")]
+#[config(env_prefix = "DB_", validate)]
 struct DatabaseConfig {
     /// 主数据库配置
+    #[config(secret_keys("password"))]
     primary: DatabaseConnectionConfig,
 
     /// 只读副本数据库配置（可选）
+    #[config(secret_keys("password"))]
     replica: Option<ReplicaConfig>,
 
     /// 连接池配置
@@ -239,14 +241,15 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let config = DatabaseConfig::new();
 
     info!("数据库配置加载完成");
-    info!("配置详情: {:#?}", config);
+    info!("配置详情: {:#}", config.redacted());
 
     // 验证配置
     validate_config(&config)?;
 
     // 构建数据库连接字符串
     let connection_url = build_connection_url(&config.primary)?;
-    info!("数据库连接 URL: {}", mask_password(&connection_url));
+    // 加载时密码已登记为敏感值，输出前替换为 [REDACTED]
+    info!("数据库连接 URL: {}", quantum_config::scrub::scrub(&connection_url));
 
     // 模拟数据库连接测试
     test_database_connection(&config).await?;
@@ -260,50 +263,57 @@ async fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-fn validate_config(config: &DatabaseConfig) -> Result<(), Box<dyn Error>> {
-    info!("验证数据库配置...");
+/// 业务规则：加载时由 `#[config(validate)]` 调用，一次返回全部错误
+impl Validate for DatabaseConfig {
+    fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
 
-    // 验证数据库类型
-    match config.primary.db_type.as_str() {
-        "postgres" | "mysql" | "sqlite" => {}
-        _ => {
-            return Err(format!("不支持的数据库类型: {}", config.primary.db_type).into());
+        // 验证数据库类型
+        if !matches!(self.primary.db_type.as_str(), "postgres" | "mysql" | "sqlite") {
+            errors.push(ValidationError::new("primary.db_type", format!("不支持的数据库类型: {}", self.primary.db_type)));
+        }
+
+        // 连接池的有序关系
+        if self.pool.min_connections > self.pool.max_connections {
+            errors.push(ValidationError::new("pool.min_connections", "最小连接数不能大于最大连接数"));
+        }
+
+        // 主机、数据库、用户名不能为空
+        for (field, value) in [
+            ("primary.host", &self.primary.host),
+            ("primary.database", &self.primary.database),
+            ("primary.username", &self.primary.username),
+        ] {
+            if value.is_empty() {
+                errors.push(ValidationError::new(field, "不能为空"));
+            }
+        }
+
+        // SSL 配置的一致性
+        if self.ssl.enabled == Some(true) && self.ssl.mode == "disable" {
+            errors.push(ValidationError::new("ssl.mode", "SSL 启用状态与禁用模式矛盾"));
         }
-    }
 
-    // 验证连接池配置 - 零值作为警告，保持有序关系为错误
-    if config.pool.min_connections > config.pool.max_connections {
-        return Err("最小连接数不能大于最大连接数".into());
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
     }
+}
+
+fn validate_config(config: &DatabaseConfig) -> Result<(), Box<dyn Error>> {
+    info!("验证数据库配置...");
+
+    // 零值只作为警告
     if config.pool.max_connections == 0 {
         warn!("最大连接数为 0，这可能导致连接问题");
     }
     if config.pool.min_connections == 0 {
         warn!("最小连接数为 0，这可能导致性能问题");
     }
-
-    // 主机、数据库、用户名不能为空
-    if config.primary.host.is_empty() {
-        return Err("主机名不能为空".into());
-    }
-    if config.primary.database.is_empty() {
-        return Err("数据库名不能为空".into());
-    }
-    if config.primary.username.is_empty() {
-        return Err("用户名不能为空".into());
-    }
-
-    // 超时边界
     if config.primary.connect_timeout == 0 {
         warn!("连接超时设置为 0，可能导致连接永久阻塞");
     }
 
-    // SSL 配置的一致性
-    if let Some(true) = config.ssl.enabled {
-        if config.ssl.mode == "disable" {
-            return Err("SSL 启用状态与禁用模式矛盾".into());
-        }
-    }
+    // 字段约束与业务规则
+    config.validate()?;
 
     Ok(())
 }
@@ -342,18 +352,6 @@ fn build_connection_url(config: &DatabaseConnectionConfig) -> Result<String, Box
     Ok(url)
 }
 
-fn mask_password(url: &str) -> String {
-    // 简单的密码掩码，实际应用中应该使用更安全的方法
-    if let Some(at_pos) = url.find('@') {
-        if let Some(colon_pos) = url[..at_pos].rfind(':') {
-            let mut masked = url.to_string();
-            masked.replace_range(colon_pos + 1..at_pos, "****");
-            return masked;
-        }
-    }
-    url.to_string()
-}
-
 async fn test_database_connection(config: &DatabaseConfig) -> Result<(), Box<dyn Error>> {
     info!("测试数据库连接...");
 
@@ -456,7 +454,7 @@ mod tests {
     fn test_database_config_new() {
         // Test that we can create a DatabaseConfig using new()
         let config = DatabaseConfig::new();
-        assert!(config.primary.host.len() > 0, "primary db host should have a default value");
+        assert!(!config.primary.host.is_empty(), "primary db host should have a default value");
         assert!(config.primary.port > 0, "primary db port should have a valid default value");
         assert!(config.pool.max_connections >= 1, "pool max_connections should be >= 1");
     }
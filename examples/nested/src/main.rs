@@ -439,9 +439,9 @@ mod tests {
     fn test_nested_app_config_new() {
         // Test that we can create an AppConfig using new()
         let config = AppConfig::new();
-        assert!(config.server.host.len() > 0, "server host should have a default value");
+        assert!(!config.server.host.is_empty(), "server host should have a default value");
         assert!(config.server.port > 0, "server port should have a valid default value");
-        assert!(config.database.host.len() > 0, "database host should have a default value");
+        assert!(!config.database.host.is_empty(), "database host should have a default value");
     }
 
     #[test]
@@ -960,9 +960,9 @@ mod tests {
     #[test]
     fn test_template_config_default() {
         let config = TemplateConfig::default();
-        assert!(config.app.app_name.len() > 0, "app name should have a default value");
+        assert!(!config.app.app_name.is_empty(), "app name should have a default value");
         assert!(config.server.port > 0, "server port should have a valid default value");
-        assert!(config.database.username.len() > 0, "database username should have a default value");
+        assert!(!config.database.username.is_empty(), "database username should have a default value");
     }
 
     #[test]
@@ -17,10 +17,10 @@ use std::{collections::HashMap, error::Error, net::SocketAddr};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use quantum_config::Config;
+use quantum_config::{Config, LoadOptions, Validate, ValidationError};
 
 #[derive(Config, Serialize, Deserialize, Debug, Default)]
-#[config(env_prefix = "WEB_")]
+#[config(env_prefix = "WEB_", validate)]
 struct ServerConfig {
     /// 服务器基本配置
     server: HttpServerConfig,
@@ -312,8 +312,15 @@ async fn health_handler() -> Json<ApiResponse<&'static str>> {
     })
 }
 
+/// 处理函数中读取当前配置
+///
+/// 启用加载缓存后，未变化的配置文件不会在每个请求中重新读取和解析。
+fn current_config() -> ServerConfig {
+    ServerConfig::load_with_options(LoadOptions::new().with_cache(true)).unwrap_or_default()
+}
+
 async fn info_handler() -> Json<ApiResponse<ServerInfo>> {
-    let config = ServerConfig::new(); // 在实际应用中，这应该从应用状态中获取
+    let config = current_config();
 
     let info = ServerInfo {
         name: "QuantumConfig Web Server Example".to_string(),
@@ -337,7 +344,7 @@ async fn info_handler() -> Json<ApiResponse<ServerInfo>> {
 }
 
 async fn config_handler() -> Json<ApiResponse<ServerConfig>> {
-    let config = ServerConfig::new();
+    let config = current_config();
 
     Json(ApiResponse {
         success: true,
@@ -370,6 +377,22 @@ async fn docs_handler() -> Json<ApiResponse<HashMap<&'static str, &'static str>>
     })
 }
 
+/// 矛盾的配置：加载时由 `#[config(validate)]` 调用
+impl Validate for ServerConfig {
+    fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        if self.tls.enabled.unwrap_or(false) {
+            if self.tls.cert_file.as_deref().unwrap_or("").is_empty() {
+                errors.push(ValidationError::new("tls.cert_file", "TLS 已启用但证书路径为空"));
+            }
+            if self.tls.key_file.as_deref().unwrap_or("").is_empty() {
+                errors.push(ValidationError::new("tls.key_file", "TLS 已启用但私钥路径为空"));
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
 fn validate_config(config: &ServerConfig) -> Result<(), Box<dyn Error>> {
     // Server 基本参数：以警告为主
     if config.server.port == 0 {
@@ -385,13 +408,9 @@ fn validate_config(config: &ServerConfig) -> Result<(), Box<dyn Error>> {
         eprintln!("警告: 最大请求体大小为 0，将无法接收任何请求体");
     }
 
-    // TLS 配置：矛盾为错误
+    // TLS 配置：矛盾为错误，由 Validate 实现检查
+    config.validate()?;
     if config.tls.enabled.unwrap_or(false) {
-        if config.tls.cert_file.as_deref().unwrap_or("").is_empty()
-            || config.tls.key_file.as_deref().unwrap_or("").is_empty()
-        {
-            return Err("TLS 已启用但证书或私钥路径为空".into());
-        }
         // 最低版本仅做提示
         let v = config.tls.min_version.trim();
         if v != "1.2" && v != "1.3" {
@@ -442,9 +461,9 @@ mod tests {
     #[test]
     fn test_server_config_new() {
         let config = ServerConfig::new();
-        assert!(config.server.host.len() > 0, "server host should have a default value");
+        assert!(!config.server.host.is_empty(), "server host should have a default value");
         assert!(config.server.port > 0, "server port should have a valid default value");
-        assert!(config.logging.level.len() > 0, "log level should have a default value");
+        assert!(!config.logging.level.is_empty(), "log level should have a default value");
     }
 
     #[test]
@@ -458,7 +477,7 @@ mod tests {
     #[test]
     fn test_logging_and_cors_defaults() {
         let logging = LoggingConfig::default();
-        assert!(logging.format.len() > 0);
+        assert!(!logging.format.is_empty());
         
         let cors = CorsConfig::default();
         // 原断言为 len() >= 0，恒为真；改为检查字符串非空和 max_age 合理范围
@@ -0,0 +1,47 @@
+//! 错误类型
+
+use alloc::string::String;
+use core::fmt;
+
+/// 解析或提取配置时的错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// 配置文本解析失败
+    Parse {
+        /// 格式名称，例如 `TOML`
+        format: &'static str,
+        /// 出错的行号（从 1 开始）
+        line: usize,
+        /// 出错的列号（从 1 开始）
+        column: usize,
+        /// 错误原因
+        message: String,
+    },
+    /// 默认值无法序列化为配置值
+    Serialize(String),
+    /// 合并后的值无法转换为目标类型
+    Extract {
+        /// 提取的键路径，整个配置时为空
+        path: String,
+        /// 错误原因
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                format,
+                line,
+                column,
+                message,
+            } => write!(f, "{} parse error at line {}, column {}\n{}", format, line, column, message),
+            Error::Serialize(message) => write!(f, "failed to serialize defaults: {}", message),
+            Error::Extract { path, message } if path.is_empty() => write!(f, "failed to extract config: {}", message),
+            Error::Extract { path, message } => write!(f, "failed to extract `{}`: {}", path, message),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
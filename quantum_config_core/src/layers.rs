@@ -0,0 +1,157 @@
+//! 按优先级叠加的配置层
+
+use crate::error::Error;
+use crate::merge::merge;
+use alloc::string::{String, ToString};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// 按合并顺序叠加的配置值，后合并的层优先
+///
+/// 对应主库中 `QuantumConfigBuilder` 的合并过程，但只接受已经在内存中的内容。
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layers {
+    /// 目前为止的合并结果
+    value: Value,
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Layers {
+    /// 创建空的配置
+    pub fn new() -> Self {
+        Self {
+            value: Value::Object(Map::new()),
+        }
+    }
+
+    /// 以结构体的序列化结果作为最底层的默认值
+    pub fn with_defaults<T: Serialize>(defaults: &T) -> Result<Self, Error> {
+        let value = serde_json::to_value(defaults).map_err(|e| Error::Serialize(e.to_string()))?;
+        Ok(Self::new().merge_value(value))
+    }
+
+    /// 合并 TOML 文本
+    pub fn merge_toml(self, content: &str) -> Result<Self, Error> {
+        Ok(self.merge_value(crate::toml::parse(content)?))
+    }
+
+    /// 合并 JSON 文本
+    pub fn merge_json(self, content: &str) -> Result<Self, Error> {
+        let value = serde_json::from_str(content).map_err(|e| Error::Parse {
+            format: "JSON",
+            line: e.line(),
+            column: e.column(),
+            message: e.to_string(),
+        })?;
+        Ok(self.merge_value(value))
+    }
+
+    /// 合并已经构造好的值
+    pub fn merge_value(mut self, value: Value) -> Self {
+        merge(&mut self.value, value);
+        self
+    }
+
+    /// 合并结果
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    /// 按点分路径读取值，例如 `server.port`
+    pub fn find(&self, path: &str) -> Option<&Value> {
+        path.split('.').try_fold(&self.value, |value, key| value.get(key))
+    }
+
+    /// 把整个配置提取为目标类型
+    pub fn extract<T: DeserializeOwned>(&self) -> Result<T, Error> {
+        T::deserialize(&self.value).map_err(|e| Error::Extract {
+            path: String::new(),
+            message: e.to_string(),
+        })
+    }
+
+    /// 把点分路径处的值提取为目标类型
+    pub fn extract_inner<T: DeserializeOwned>(&self, path: &str) -> Result<T, Error> {
+        let value = self.find(path).ok_or_else(|| Error::Extract {
+            path: path.to_string(),
+            message: "missing value".to_string(),
+        })?;
+        T::deserialize(value).map_err(|e| Error::Extract {
+            path: path.to_string(),
+            message: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use serde::Deserialize;
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Uart {
+        baud: u32,
+        pins: Vec<u8>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct Firmware {
+        name: String,
+        uart: Uart,
+    }
+
+    fn defaults() -> Firmware {
+        Firmware {
+            name: "node".to_string(),
+            uart: Uart {
+                baud: 9600,
+                pins: vec![1, 2],
+            },
+        }
+    }
+
+    #[test]
+    fn test_layers_merge_in_order_and_extract() {
+        let layers = Layers::with_defaults(&defaults())
+            .unwrap()
+            .merge_toml("[uart]\nbaud = 115200")
+            .unwrap()
+            .merge_json(r#"{"uart": {"pins": [4]}}"#)
+            .unwrap();
+
+        assert_eq!(
+            layers.extract::<Firmware>().unwrap(),
+            Firmware {
+                name: "node".to_string(),
+                uart: Uart {
+                    baud: 115200,
+                    pins: vec![4],
+                },
+            }
+        );
+        assert_eq!(layers.extract_inner::<u32>("uart.baud").unwrap(), 115200);
+        assert_eq!(layers.find("uart.stop_bits"), None);
+    }
+
+    #[test]
+    fn test_errors_describe_the_failure() {
+        let error = Layers::new().merge_json("{\n  \"baud\": }").unwrap_err();
+        assert!(matches!(error, Error::Parse { format: "JSON", line: 2, .. }), "{:?}", error);
+
+        let layers = Layers::new().merge_toml("[uart]\nbaud = \"fast\"").unwrap();
+        let error = layers.extract_inner::<u32>("uart.baud").unwrap_err();
+        assert!(error.to_string().starts_with("failed to extract `uart.baud`: invalid type"), "{}", error);
+        assert_eq!(
+            layers.extract_inner::<u32>("uart.parity").unwrap_err().to_string(),
+            "failed to extract `uart.parity`: missing value"
+        );
+    }
+}
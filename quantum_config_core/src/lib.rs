@@ -0,0 +1,42 @@
+//! # Quantum Config Core
+//!
+//! `quantum_config` 中与平台无关的部分：解析预先加载的配置文本、按优先级深度合并、
+//! 提取为强类型结构体。本 crate 为 `no_std + alloc`，不读取环境变量、命令行参数或文件系统，
+//! 可以在嵌入式固件中使用，与主机端工具共享同一份配置结构体定义。
+//!
+//! ```
+//! use quantum_config_core::Layers;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Sensor {
+//!     name: String,
+//!     interval_ms: u32,
+//! }
+//!
+//! let sensor: Sensor = Layers::with_defaults(&Sensor { name: "temp".into(), interval_ms: 1000 })
+//!     .unwrap()
+//!     .merge_toml("interval_ms = 250")
+//!     .unwrap()
+//!     .extract()
+//!     .unwrap();
+//! assert_eq!(sensor.name, "temp");
+//! assert_eq!(sensor.interval_ms, 250);
+//! ```
+//!
+//! 合并规则与主库一致：映射逐键递归合并，其余值（包括数组）由后合并的层整体替换。
+//! 主库展开配置文件的 `include` 指令时直接使用本 crate 的 [`merge`]。
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod error;
+pub mod layers;
+pub mod merge;
+pub mod toml;
+
+pub use error::Error;
+pub use layers::Layers;
+pub use merge::merge;
+pub use serde_json::{Map, Value};
@@ -0,0 +1,49 @@
+//! 值的深度合并
+
+use serde_json::Value;
+
+/// 把 `overlay` 合并进 `base`
+///
+/// 两边都是映射时逐键递归合并；否则 `overlay` 整体替换 `base`，数组不做拼接。
+pub fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_recurses_into_maps_and_replaces_the_rest() {
+        let mut base = json!({
+            "server": {"host": "0.0.0.0", "port": 80},
+            "peers": ["a", "b"],
+            "debug": false
+        });
+        merge(
+            &mut base,
+            json!({"server": {"port": 8080}, "peers": ["c"], "debug": {"level": 2}}),
+        );
+        assert_eq!(
+            base,
+            json!({
+                "server": {"host": "0.0.0.0", "port": 8080},
+                "peers": ["c"],
+                "debug": {"level": 2}
+            })
+        );
+    }
+}
@@ -0,0 +1,625 @@
+//! 轻量 TOML 解析器
+//!
+//! 单遍扫描源文本并直接构造 `serde_json::Value`，不保留格式与位置信息，
+//! 峰值内存大致为结果本身的大小。主库启用 `toml-lite` 特性时同样使用本解析器代替 `toml` 库。
+//!
+//! 与 `toml` 库的差异：
+//! - 日期时间按原文返回字符串（`toml` 库返回带内部标记键的对象）
+//! - 只检查重复键与重复的表头，不检查规范中其余的重定义约束（例如向内联表追加键）
+//! - 错误信息只包含行列号与原因，不包含源码片段
+
+use crate::error::Error;
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use serde_json::{Map, Number, Value};
+
+/// 结束未加引号的值的字符
+const VALUE_TERMINATORS: [char; 8] = [' ', '\t', '\r', '\n', ',', ']', '}', '#'];
+
+/// 数组与内联表的默认嵌套深度上限
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// 解析 TOML 文本，错误包含行列号；数组与内联表最多嵌套 [`DEFAULT_MAX_DEPTH`] 层
+pub fn parse(content: &str) -> Result<Value, Error> {
+    parse_with_max_depth(content, DEFAULT_MAX_DEPTH)
+}
+
+/// 解析 TOML 文本，数组与内联表的嵌套超过 `max_depth` 层时返回错误
+///
+/// 解析器按嵌套层次递归，限制深度可以避免恶意构造的文件耗尽调用栈。
+pub fn parse_with_max_depth(content: &str, max_depth: usize) -> Result<Value, Error> {
+    let mut parser = Parser {
+        src: content,
+        pos: 0,
+        depth: 0,
+        max_depth,
+    };
+    parser.parse_document().map_err(|message| {
+        let (line, column) = parser.location();
+        Error::Parse {
+            format: "TOML",
+            line,
+            column,
+            message,
+        }
+    })
+}
+
+/// 单遍扫描的解析器状态
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    /// 当前所在的数组与内联表的层数
+    depth: usize,
+    /// 嵌套深度上限
+    max_depth: usize,
+}
+
+impl Parser<'_> {
+    /// 当前位置的行列号（从 1 开始）
+    fn location(&self) -> (usize, usize) {
+        let consumed = &self.src[..self.pos.min(self.src.len())];
+        let line = consumed.matches('\n').count() + 1;
+        let column = consumed.rsplit('\n').next().map_or(0, |s| s.chars().count()) + 1;
+        (line, column)
+    }
+
+    fn rest(&self) -> &str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        if self.rest().starts_with(prefix) {
+            self.pos += prefix.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        match self.peek() {
+            Some(found) if found == c => {
+                self.pos += c.len_utf8();
+                Ok(())
+            }
+            Some(found) => Err(format!("expected `{}`, found `{}`", c, found)),
+            None => Err(format!("expected `{}`, found end of input", c)),
+        }
+    }
+
+    /// 跳过行内空白
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    /// 跳过空白、换行与注释（用于数组内部与行之间）
+    fn skip_ws_comments_newlines(&mut self) {
+        loop {
+            match self.peek() {
+                Some(' ' | '\t' | '\r' | '\n') => self.pos += 1,
+                Some('#') => self.skip_comment(),
+                _ => return,
+            }
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        let end = self.rest().find('\n').unwrap_or(self.rest().len());
+        self.pos += end;
+    }
+
+    /// 一行的剩余部分只能是空白与注释
+    fn expect_line_end(&mut self) -> Result<(), String> {
+        self.skip_ws();
+        if self.peek() == Some('#') {
+            self.skip_comment();
+        }
+        self.eat("\r");
+        match self.peek() {
+            None => Ok(()),
+            Some('\n') => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(format!("expected newline, found `{}`", c)),
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<Value, String> {
+        let mut root = Map::new();
+        let mut current: Vec<String> = Vec::new();
+        let mut defined_tables: BTreeSet<Vec<String>> = BTreeSet::new();
+
+        loop {
+            self.skip_ws_comments_newlines();
+            match self.peek() {
+                None => break,
+                Some('[') => {
+                    let array = self.eat("[[");
+                    if !array {
+                        self.pos += 1;
+                    }
+                    self.skip_ws();
+                    let path = self.parse_key()?;
+                    self.skip_ws();
+                    self.expect(']')?;
+                    if array {
+                        self.expect(']')?;
+                        push_array_table(&mut root, &path)?;
+                    } else {
+                        if !defined_tables.insert(path.clone()) {
+                            return Err(format!("duplicate table `{}`", path.join(".")));
+                        }
+                        table_mut(&mut root, &path)?;
+                    }
+                    current = path;
+                    self.expect_line_end()?;
+                }
+                Some(_) => {
+                    let table = table_mut(&mut root, &current)?;
+                    self.parse_key_value(table)?;
+                    self.expect_line_end()?;
+                }
+            }
+        }
+        Ok(Value::Object(root))
+    }
+
+    /// 解析 `key = value` 并插入表中，点分键会创建中间表
+    fn parse_key_value(&mut self, table: &mut Map<String, Value>) -> Result<(), String> {
+        let mut path = self.parse_key()?;
+        self.skip_ws();
+        self.expect('=')?;
+        self.skip_ws();
+        let value = self.parse_value()?;
+        let last = path.pop().ok_or("empty key")?;
+        let table = table_mut(table, &path)?;
+        if table.contains_key(&last) {
+            return Err(format!("duplicate key `{}`", last));
+        }
+        table.insert(last, value);
+        Ok(())
+    }
+
+    /// 解析可能带点分隔的键
+    fn parse_key(&mut self) -> Result<Vec<String>, String> {
+        let mut path = Vec::new();
+        loop {
+            self.skip_ws();
+            let segment = match self.peek() {
+                Some('"') => {
+                    self.pos += 1;
+                    self.parse_basic_string()?
+                }
+                Some('\'') => {
+                    self.pos += 1;
+                    self.parse_literal_string()?
+                }
+                _ => {
+                    let len = self
+                        .rest()
+                        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+                        .unwrap_or(self.rest().len());
+                    if len == 0 {
+                        return Err(match self.peek() {
+                            Some(c) => format!("invalid key character `{}`", c),
+                            None => "expected a key, found end of input".to_string(),
+                        });
+                    }
+                    let segment = self.rest()[..len].to_string();
+                    self.pos += len;
+                    segment
+                }
+            };
+            path.push(segment);
+            self.skip_ws();
+            if !self.eat(".") {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        match self.peek() {
+            Some('"') => {
+                if self.eat("\"\"\"") {
+                    self.parse_multiline_basic_string().map(Value::String)
+                } else {
+                    self.pos += 1;
+                    self.parse_basic_string().map(Value::String)
+                }
+            }
+            Some('\'') => {
+                if self.eat("'''") {
+                    self.parse_multiline_literal_string().map(Value::String)
+                } else {
+                    self.pos += 1;
+                    self.parse_literal_string().map(Value::String)
+                }
+            }
+            Some('[') => {
+                self.pos += 1;
+                self.nested(Self::parse_array)
+            }
+            Some('{') => {
+                self.pos += 1;
+                self.nested(Self::parse_inline_table)
+            }
+            Some(_) => self.parse_scalar(),
+            None => Err("expected a value, found end of input".to_string()),
+        }
+    }
+
+    /// 进入一层数组或内联表，超过深度上限时返回错误
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Value, String>) -> Result<Value, String> {
+        if self.depth >= self.max_depth {
+            return Err(format!("arrays and inline tables nest deeper than {} levels", self.max_depth));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn parse_array(&mut self) -> Result<Value, String> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_ws_comments_newlines();
+            if self.eat("]") {
+                return Ok(Value::Array(items));
+            }
+            items.push(self.parse_value()?);
+            self.skip_ws_comments_newlines();
+            if !self.eat(",") {
+                self.skip_ws_comments_newlines();
+                self.expect(']')?;
+                return Ok(Value::Array(items));
+            }
+        }
+    }
+
+    fn parse_inline_table(&mut self) -> Result<Value, String> {
+        let mut table = Map::new();
+        self.skip_ws();
+        if self.eat("}") {
+            return Ok(Value::Object(table));
+        }
+        loop {
+            self.parse_key_value(&mut table)?;
+            self.skip_ws();
+            if self.eat("}") {
+                return Ok(Value::Object(table));
+            }
+            self.expect(',')?;
+        }
+    }
+
+    /// 解析单行基本字符串（起始引号已消费）
+    fn parse_basic_string(&mut self) -> Result<String, String> {
+        let mut value = String::new();
+        loop {
+            let Some(c) = self.peek().filter(|&c| c != '\n') else {
+                return Err("unterminated string".to_string());
+            };
+            self.pos += c.len_utf8();
+            match c {
+                '"' => return Ok(value),
+                '\\' => value.push(self.parse_escape()?),
+                c => value.push(c),
+            }
+        }
+    }
+
+    /// 解析多行基本字符串（起始引号已消费）
+    fn parse_multiline_basic_string(&mut self) -> Result<String, String> {
+        self.eat("\r");
+        self.eat("\n");
+        let mut value = String::new();
+        loop {
+            if self.eat("\"\"\"") {
+                // 结束分隔符前最多允许两个引号属于内容
+                while self.peek() == Some('"') && !self.rest().starts_with("\"\"\"") {
+                    self.pos += 1;
+                    value.push('"');
+                }
+                return Ok(value);
+            }
+            match self.bump() {
+                Some('\\') => {
+                    let after = self.rest();
+                    let trimmed = after.trim_start_matches([' ', '\t']);
+                    if trimmed.starts_with('\n') || trimmed.starts_with("\r\n") {
+                        // 行尾反斜杠：去掉换行及后续空白
+                        self.pos += after.len() - trimmed.len();
+                        self.skip_ws_newlines_only();
+                    } else {
+                        value.push(self.parse_escape()?);
+                    }
+                }
+                Some(c) => value.push(c),
+                None => return Err("unterminated multi-line string".to_string()),
+            }
+        }
+    }
+
+    fn skip_ws_newlines_only(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\r' | '\n')) {
+            self.pos += 1;
+        }
+    }
+
+    /// 解析单行字面量字符串（起始引号已消费）
+    fn parse_literal_string(&mut self) -> Result<String, String> {
+        let end = self
+            .rest()
+            .find(['\'', '\n'])
+            .filter(|&end| self.rest()[end..].starts_with('\''))
+            .ok_or("unterminated string")?;
+        let value = self.rest()[..end].to_string();
+        self.pos += end + 1;
+        Ok(value)
+    }
+
+    /// 解析多行字面量字符串（起始引号已消费）
+    fn parse_multiline_literal_string(&mut self) -> Result<String, String> {
+        self.eat("\r");
+        self.eat("\n");
+        let end = self.rest().find("'''").ok_or("unterminated multi-line string")?;
+        let mut value = self.rest()[..end].to_string();
+        self.pos += end + 3;
+        while self.peek() == Some('\'') && !self.rest().starts_with("'''") {
+            self.pos += 1;
+            value.push('\'');
+        }
+        Ok(value)
+    }
+
+    /// 解析反斜杠之后的转义序列
+    fn parse_escape(&mut self) -> Result<char, String> {
+        match self.bump() {
+            Some('b') => Ok('\u{8}'),
+            Some('t') => Ok('\t'),
+            Some('n') => Ok('\n'),
+            Some('f') => Ok('\u{c}'),
+            Some('r') => Ok('\r'),
+            Some('e') => Ok('\u{1b}'),
+            Some('"') => Ok('"'),
+            Some('\\') => Ok('\\'),
+            Some('u') => self.parse_unicode_escape(4),
+            Some('U') => self.parse_unicode_escape(8),
+            Some(c) => Err(format!("invalid escape sequence `\\{}`", c)),
+            None => Err("unterminated string".to_string()),
+        }
+    }
+
+    fn parse_unicode_escape(&mut self, digits: usize) -> Result<char, String> {
+        let hex = self.rest().get(..digits).ok_or("incomplete unicode escape")?;
+        let code = u32::from_str_radix(hex, 16).map_err(|_| format!("invalid unicode escape `{}`", hex))?;
+        let c = char::from_u32(code).ok_or_else(|| format!("invalid unicode scalar value `{}`", hex))?;
+        self.pos += digits;
+        Ok(c)
+    }
+
+    /// 解析布尔值、数字与日期时间
+    fn parse_scalar(&mut self) -> Result<Value, String> {
+        let mut len = self
+            .rest()
+            .find(VALUE_TERMINATORS)
+            .unwrap_or(self.rest().len());
+        // `1979-05-27 07:32:00` 形式的日期时间中间含有空格
+        if is_date(&self.rest()[..len]) && self.rest()[len..].starts_with(' ') {
+            let time = &self.rest()[len + 1..];
+            if time.len() >= 2 && time.as_bytes()[..2].iter().all(u8::is_ascii_digit) {
+                let time_len = time
+                    .find(VALUE_TERMINATORS)
+                    .unwrap_or(time.len());
+                len += 1 + time_len;
+            }
+        }
+        let token = &self.rest()[..len];
+        let value = match token {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => parse_number(token)
+                .or_else(|| is_datetime(token).then(|| Value::String(token.to_string())))
+                .ok_or_else(|| format!("invalid value `{}`", token))?,
+        };
+        self.pos += len;
+        Ok(value)
+    }
+}
+
+/// 解析整数与浮点数，支持下划线分隔与十六进制、八进制、二进制整数
+fn parse_number(token: &str) -> Option<Value> {
+    if token.starts_with('_') || token.ends_with('_') || token.contains("__") {
+        return None;
+    }
+    let digits = token.replace('_', "");
+    let unsigned = digits.trim_start_matches(['+', '-']);
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(body) = digits.strip_prefix(prefix) {
+            return i64::from_str_radix(body, radix).ok().map(Value::from);
+        }
+    }
+    match unsigned {
+        "inf" | "nan" => {
+            let value = if unsigned == "nan" {
+                f64::NAN
+            } else if digits.starts_with('-') {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            };
+            return Some(Number::from_f64(value).map_or(Value::Null, Value::Number));
+        }
+        "" => return None,
+        _ => {}
+    }
+    if !unsigned.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    // 不允许前导零（`0` 与 `0.x` 除外）
+    if unsigned.len() > 1 && unsigned.starts_with('0') && unsigned.as_bytes()[1].is_ascii_digit() {
+        return None;
+    }
+    if unsigned.contains(['.', 'e', 'E']) {
+        digits.parse::<f64>().ok().and_then(Number::from_f64).map(Value::Number)
+    } else {
+        digits.parse::<i64>().ok().map(Value::from)
+    }
+}
+
+/// `YYYY-MM-DD`
+fn is_date(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| if i == 4 || i == 7 { *b == b'-' } else { b.is_ascii_digit() })
+}
+
+/// 日期、时间或日期时间（只检查字符集与基本形状）
+fn is_datetime(token: &str) -> bool {
+    let starts_with_date = token.get(..10).is_some_and(is_date);
+    let is_time = token.len() >= 8 && token.as_bytes()[2] == b':' && token.as_bytes()[5] == b':';
+    (starts_with_date || is_time)
+        && token
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, '-' | ':' | '.' | 'T' | 't' | 'Z' | 'z' | '+' | ' '))
+}
+
+/// 沿路径进入（必要时创建）表，数组表进入最后一个元素
+fn table_mut<'m>(table: &'m mut Map<String, Value>, path: &[String]) -> Result<&'m mut Map<String, Value>, String> {
+    let mut table = table;
+    for segment in path {
+        let entry = table.entry(segment.clone()).or_insert_with(|| Value::Object(Map::new()));
+        table = match entry {
+            Value::Object(map) => map,
+            Value::Array(items) => match items.last_mut() {
+                Some(Value::Object(map)) => map,
+                _ => return Err(format!("key `{}` is not a table", segment)),
+            },
+            _ => return Err(format!("key `{}` is not a table", segment)),
+        };
+    }
+    Ok(table)
+}
+
+/// 处理 `[[path]]`：向数组表追加一个新表
+fn push_array_table(root: &mut Map<String, Value>, path: &[String]) -> Result<(), String> {
+    let (last, parent) = path.split_last().ok_or("empty table name")?;
+    let parent = table_mut(root, parent)?;
+    match parent.entry(last.clone()).or_insert_with(|| Value::Array(Vec::new())) {
+        Value::Array(items) => {
+            items.push(Value::Object(Map::new()));
+            Ok(())
+        }
+        _ => Err(format!("key `{}` is not an array of tables", last)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_default_parser() {
+        let content = r#"
+# comment
+title = "TOML \"lite\"\tparser \u00e9"
+literal = 'C:\path\n'
+multi = """
+first \
+    second
+"""
+raw = '''
+line one
+line two'''
+int = 1_000
+hex = 0xff
+neg = -17
+float = 6.02e23
+half = 0.5
+flag = true
+list = [1, 2, 3,]
+nested = [["a", 'b'], [
+    { x = 1 }, # trailing comment
+]]
+point = { x = 1, y.z = "deep" }
+"quoted key" = 1
+a.b.c = "dotted"
+
+[server]
+host = "localhost"
+port = 8080
+
+[server.tls]
+enabled = false
+
+[[workers]]
+name = "first"
+
+[[workers]]
+name = "second"
+tags = []
+
+[workers.limits]
+cpu = 2
+"#;
+        let expected: Value = toml::from_str(content).unwrap();
+        assert_eq!(parse(content).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_datetimes_are_strings() {
+        let value = parse("a = 1979-05-27T07:32:00Z\nb = 1979-05-27 07:32:00\nc = 07:32:00\nd = 1979-05-27\n").unwrap();
+        assert_eq!(value["a"], "1979-05-27T07:32:00Z");
+        assert_eq!(value["b"], "1979-05-27 07:32:00");
+        assert_eq!(value["c"], "07:32:00");
+        assert_eq!(value["d"], "1979-05-27");
+    }
+
+    #[test]
+    fn test_errors_report_location() {
+        let cases = [
+            ("a = 1\na = 2\n", "line 2", "duplicate key `a`"),
+            ("[t]\n[t]\n", "line 2", "duplicate table `t`"),
+            ("a = \"open\n", "line 1", "unterminated string"),
+            ("a = 1 2\n", "line 1", "expected newline"),
+            ("a = 012\n", "line 1", "invalid value `012`"),
+            ("a = 1\na.b = 2\n", "line 2", "key `a` is not a table"),
+            ("a = \"\\q\"\n", "line 1", "invalid escape"),
+        ];
+        for (content, line, message) in cases {
+            let error = parse(content).unwrap_err().to_string();
+            assert!(error.starts_with("TOML parse error at "), "{}", error);
+            assert!(error.contains(line), "{}: {}", content, error);
+            assert!(error.contains(message), "{}: {}", content, error);
+            assert!(toml::from_str::<Value>(content).is_err(), "{}", content);
+        }
+    }
+
+    #[test]
+    fn test_nesting_depth_is_limited() {
+        assert_eq!(parse_with_max_depth("a = [[1], { b = [2] }]\n", 3).unwrap()["a"][1]["b"][0], 2);
+        let error = parse_with_max_depth("a = [[[1]]]\n", 2).unwrap_err().to_string();
+        assert!(error.contains("deeper than 2 levels"), "{}", error);
+
+        // 深度远超调用栈容量的输入返回错误而不是栈溢出
+        let deep = format!("a = {}{}\n", "[".repeat(200_000), "]".repeat(200_000));
+        assert!(parse(&deep).is_err());
+    }
+}
@@ -32,52 +32,971 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, DeriveInput, Attribute, Meta};
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_macro_input, Data, DeriveInput, Attribute, Fields};
 
-/// 解析 #[config(...)] 属性中的 env_prefix 参数
-fn parse_config_attributes(attrs: &[Attribute]) -> Option<String> {
+/// 结构体级 #[config(...)] 属性
+#[derive(Default)]
+struct StructAttributes {
+    /// #[config(env_prefix = "...")] 指定的环境变量前缀
+    env_prefix: Option<String>,
+    /// #[config(namespace = "...")] 指定的配置命名空间
+    namespace: Option<String>,
+    /// #[config(env_file_suffix = "...")] 指定的文件间接引用后缀
+    env_file_suffix: Option<String>,
+    /// #[config(env_scan = "schema")]：只查询由默认值结构推导出的环境变量
+    env_scan_schema: bool,
+    /// #[config(env_separator = "_")] 指定的环境变量嵌套键分隔符
+    env_separator: Option<String>,
+    /// #[config(env_case = "snake")] 指定的键名大小写规则，值为 `EnvCase` 的变体名
+    env_case: Option<&'static str>,
+    /// #[config(cli_nested_style = "dot")] 指定的嵌套字段命令行参数风格，值为 `CliNestedStyle` 的变体名
+    cli_nested_style: Option<&'static str>,
+    /// #[config(cli = "ignore_unknown")] / #[config(cli = false)] 指定的命令行参数解析方式，值为 `CliMode` 的变体名
+    cli_mode: Option<&'static str>,
+    /// #[config(secret_references)]：解析 `secret_file:` 与 `${file:...}` 密钥引用
+    secret_references: bool,
+    /// #[config(config_d)]：在每个配置文件之后合并其 `{stem}.d/` 片段目录
+    config_d: bool,
+    /// #[config(tenant_source = "...")] 指定的租户专属来源，可重复
+    tenant_sources: Vec<String>,
+    /// #[config(includes)]：展开配置文件顶层的 `include` 指令
+    includes: bool,
+    /// #[config(encrypted)]：将所有配置文件视为加密文件
+    encrypted: bool,
+    /// #[config(validate)]：加载后调用 `quantum_config::validate::Validate`
+    validate: bool,
+    /// #[config(migrate)]：合并前应用 `quantum_config::migrate::Migrate` 提供的版本迁移
+    migrate: bool,
+    /// #[config(deny_unknown)] 或 #[config(deny_unknown = "warn")]：合并后检查未知键，
+    /// 值为 `UnknownKeyPolicy` 的变体名
+    unknown_key_policy: Option<&'static str>,
+    /// #[config(behavior_version = N)] 指定的行为版本
+    behavior_version: Option<u32>,
+    /// #[config(profile_env = "...")] 指定的配置剖面环境变量
+    profile_env: Option<String>,
+    /// #[config(no_defaults)]：不以 `Default` 实现作为最低优先级的配置层
+    no_defaults: bool,
+    /// #[config(priority(env, cli, file))] 指定的来源类别合并顺序（低 -> 高）
+    priority: Option<Vec<syn::Ident>>,
+    /// #[config(qualifier = "...")] 指定的平台目录限定符
+    qualifier: Option<String>,
+    /// #[config(organization = "...")] 指定的平台目录组织名
+    organization: Option<String>,
+    /// #[config(path_strategy = "xdg")]：在所有平台上按 XDG 基础目录规范查找配置目录
+    xdg_paths: bool,
+    /// #[config(redact_debug)]：生成敏感键已脱敏的 `Debug` 实现
+    redact_debug: bool,
+    /// #[config(patch)]：生成补丁结构体 `{Name}Patch` 与 `apply_patch()`、`load_patch_from_file()`
+    patch: bool,
+}
+
+/// 行为版本常量，需与 `quantum_config::compat` 中的同名常量保持一致
+const DEFAULT_BEHAVIOR_VERSION: u32 = 1;
+const MAX_BEHAVIOR_VERSION: u32 = 2;
+const LEGACY_NAMES_REMOVED_IN: u32 = 2;
+
+/// 是否为本宏的属性：`#[config(...)]` 或旧名称 `#[lingo(...)]`
+fn is_config_attr(attr: &Attribute) -> bool {
+    attr.path().is_ident("config") || attr.path().is_ident("lingo")
+}
+
+/// 查找结构体或字段上的第一个旧名称属性 `#[lingo(...)]`
+fn find_legacy_attribute(input: &DeriveInput) -> Option<&Attribute> {
+    let field_attrs = match &input.data {
+        Data::Struct(data) => data.fields.iter().flat_map(|field| field.attrs.iter()).collect(),
+        _ => Vec::new(),
+    };
+    input.attrs.iter().chain(field_attrs).find(|attr| attr.path().is_ident("lingo"))
+}
+
+/// 未识别的 #[config(...)] 键
+///
+/// 拼写错误（例如 `secrte`）会悄悄关闭脱敏、命令行排除或来源限制，因此报告为编译错误。
+fn unknown_attribute(meta: &syn::meta::ParseNestedMeta) -> syn::Error {
+    let name = meta.path.to_token_stream().to_string().replace(' ', "");
+    meta.error(format!("unknown config attribute `{}`", name))
+}
+
+/// 解析结构体上的 #[config(...)] 属性
+fn parse_config_attributes(attrs: &[Attribute]) -> syn::Result<StructAttributes> {
+    let mut struct_attrs = StructAttributes::default();
     for attr in attrs {
-        if attr.path().is_ident("config") {
-            if let Meta::List(meta_list) = &attr.meta {
-                let tokens_str = meta_list.tokens.to_string();
-                // 解析 env_prefix = "VALUE" 模式 (简化版本)
-                if let Some(start) = tokens_str.find("env_prefix") {
-                    let after_prefix = &tokens_str[start + "env_prefix".len()..];
-                    if let Some(eq_pos) = after_prefix.find('=') {
-                        let value_part = after_prefix[eq_pos + 1..].trim();
-                        if value_part.starts_with('"') && value_part.ends_with('"') {
-                            let prefix = value_part[1..value_part.len()-1].to_string();
-                            return Some(prefix);
+        if !is_config_attr(attr) {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("env_prefix") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                struct_attrs.env_prefix = Some(value.value());
+            } else if meta.path.is_ident("namespace") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                let namespace = value.value();
+                let valid_segment = |segment: &str| {
+                    !segment.is_empty() && segment.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+                };
+                if !namespace.split('.').all(valid_segment) {
+                    return Err(meta.error("namespace must be a key path of lowercase letters, digits and '_', e.g. \"mylib\""));
+                }
+                struct_attrs.namespace = Some(namespace);
+            } else if meta.path.is_ident("env_file_suffix") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if value.value().is_empty() {
+                    return Err(meta.error("env_file_suffix must not be empty"));
+                }
+                struct_attrs.env_file_suffix = Some(value.value());
+            } else if meta.path.is_ident("profile_env") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if value.value().is_empty() {
+                    return Err(meta.error("profile_env must not be empty"));
+                }
+                struct_attrs.profile_env = Some(value.value());
+            } else if meta.path.is_ident("secret_references") {
+                struct_attrs.secret_references = true;
+            } else if meta.path.is_ident("redact_debug") {
+                struct_attrs.redact_debug = true;
+            } else if meta.path.is_ident("patch") {
+                struct_attrs.patch = true;
+            } else if meta.path.is_ident("config_d") {
+                struct_attrs.config_d = true;
+            } else if meta.path.is_ident("tenant_source") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if !value.value().contains("{tenant}") {
+                    return Err(meta.error("tenant_source must contain the {tenant} placeholder"));
+                }
+                struct_attrs.tenant_sources.push(value.value());
+            } else if meta.path.is_ident("includes") {
+                struct_attrs.includes = true;
+            } else if meta.path.is_ident("encrypted") {
+                struct_attrs.encrypted = true;
+            } else if meta.path.is_ident("deny_unknown") {
+                struct_attrs.unknown_key_policy = if meta.input.peek(syn::Token![=]) {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    match value.value().as_str() {
+                        "deny" => Some("Deny"),
+                        "warn" => Some("Warn"),
+                        _ => return Err(meta.error("deny_unknown must be one of \"deny\", \"warn\"")),
+                    }
+                } else {
+                    Some("Deny")
+                };
+            } else if meta.path.is_ident("validate") {
+                struct_attrs.validate = true;
+            } else if meta.path.is_ident("migrate") {
+                struct_attrs.migrate = true;
+            } else if meta.path.is_ident("no_defaults") {
+                struct_attrs.no_defaults = true;
+            } else if meta.path.is_ident("behavior_version") {
+                let value: syn::LitInt = meta.value()?.parse()?;
+                let version: u32 = value.base10_parse()?;
+                if !(DEFAULT_BEHAVIOR_VERSION..=MAX_BEHAVIOR_VERSION).contains(&version) {
+                    return Err(syn::Error::new(
+                        value.span(),
+                        format!("behavior_version must be between {} and {}", DEFAULT_BEHAVIOR_VERSION, MAX_BEHAVIOR_VERSION),
+                    ));
+                }
+                struct_attrs.behavior_version = Some(version);
+            } else if meta.path.is_ident("priority") {
+                let mut kinds: Vec<syn::Ident> = Vec::new();
+                meta.parse_nested_meta(|kind| {
+                    let variant = match kind.path.get_ident().map(ToString::to_string).as_deref() {
+                        Some("file") => "File",
+                        Some("remote") => "Remote",
+                        Some("env") => "Env",
+                        Some("cli") => "Cli",
+                        _ => return Err(kind.error("priority entries must be one of `file`, `remote`, `env`, `cli`")),
+                    };
+                    if kinds.iter().any(|existing| existing == variant) {
+                        return Err(kind.error("priority entries must not repeat"));
+                    }
+                    kinds.push(syn::Ident::new(variant, syn::spanned::Spanned::span(&kind.path)));
+                    Ok(())
+                })?;
+                struct_attrs.priority = Some(kinds);
+            } else if meta.path.is_ident("qualifier") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                struct_attrs.qualifier = Some(value.value());
+            } else if meta.path.is_ident("organization") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                struct_attrs.organization = Some(value.value());
+            } else if meta.path.is_ident("path_strategy") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                struct_attrs.xdg_paths = match value.value().as_str() {
+                    "xdg" => true,
+                    "native" => false,
+                    _ => return Err(meta.error("path_strategy must be one of \"native\", \"xdg\"")),
+                };
+            } else if meta.path.is_ident("env_scan") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                struct_attrs.env_scan_schema = match value.value().as_str() {
+                    "schema" => true,
+                    "full" => false,
+                    _ => return Err(meta.error("env_scan must be one of \"full\", \"schema\"")),
+                };
+            } else if meta.path.is_ident("env_separator") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if value.value().is_empty() {
+                    return Err(meta.error("env_separator must not be empty"));
+                }
+                struct_attrs.env_separator = Some(value.value());
+            } else if meta.path.is_ident("env_case") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                struct_attrs.env_case = Some(match value.value().as_str() {
+                    "snake" => "Snake",
+                    "screaming_snake" => "ScreamingSnake",
+                    "kebab" => "Kebab",
+                    "camel" => "Camel",
+                    "preserve" => "Preserve",
+                    _ => {
+                        return Err(meta.error(
+                            "env_case must be one of \"snake\", \"screaming_snake\", \"kebab\", \"camel\", \"preserve\"",
+                        ))
+                    }
+                });
+            } else if meta.path.is_ident("cli_nested_style") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                struct_attrs.cli_nested_style = Some(match value.value().as_str() {
+                    "dash" => "Dash",
+                    "dot" => "Dot",
+                    _ => return Err(meta.error("cli_nested_style must be one of \"dash\", \"dot\"")),
+                });
+            } else if meta.path.is_ident("cli") {
+                let mode = match meta.value()?.parse::<syn::Lit>()? {
+                    syn::Lit::Bool(value) => (if value.value { "strict" } else { "disabled" }).to_string(),
+                    syn::Lit::Str(value) => value.value(),
+                    _ => String::new(),
+                };
+                struct_attrs.cli_mode = Some(match mode.as_str() {
+                    "strict" => "Strict",
+                    "ignore_unknown" => "IgnoreUnknown",
+                    "disabled" => "Disabled",
+                    _ => {
+                        return Err(meta.error(
+                            "cli must be one of \"strict\", \"ignore_unknown\", \"disabled\", true, false",
+                        ))
+                    }
+                });
+            } else {
+                return Err(unknown_attribute(&meta));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(struct_attrs)
+}
+
+/// 字段级 #[config(...)] 属性
+#[derive(Default)]
+struct FieldAttributes {
+    /// 是否标注了 #[config(secret)]
+    secret: bool,
+    /// #[config(secret_keys("password"))] 指定的嵌套敏感键（相对于该字段）
+    secret_keys: Vec<String>,
+    /// #[config(severity = "...")] 指定的漂移严重级别
+    severity: Option<syn::Ident>,
+    /// #[config(transform = "...")] 指定的内置转换器（可用逗号分隔多个）
+    transforms: Vec<String>,
+    /// #[config(max_len = N)] 指定的字符串最大长度
+    max_len: Option<usize>,
+    /// #[config(health = "...")] 指定的健康检查类型
+    health: Option<HealthAttr>,
+    /// #[config(description = "...")] 指定的描述，没有文档注释时用作命令行帮助
+    description: Option<String>,
+    /// #[config(range(min = .., max = ..))] 指定的数值区间
+    range: Option<(Option<f64>, Option<f64>)>,
+    /// #[config(regex = "...")] 指定的正则表达式
+    regex: Option<String>,
+    /// #[config(one_of("a", "b"))] 指定的允许取值
+    one_of: Option<Vec<String>>,
+    /// 是否标注了 #[config(non_empty)]
+    non_empty: bool,
+    /// #[config(env_list_separator = ",")] 指定的环境变量列表分隔符
+    env_list_separator: Option<String>,
+    /// #[config(deprecated = "...")] 指定的迁移提示
+    deprecated: Option<String>,
+    /// 是否标注了 #[config(cli_positional)]
+    cli_positional: bool,
+    /// #[config(sources(...))] 或 #[config(no_cli)] 指定的允许来源（`SourceKind` 变体名）
+    allowed_sources: Option<Vec<&'static str>>,
+    /// 是否标注了 #[config(nested)]，字段类型同样派生了 `Config`
+    nested: bool,
+}
+
+/// 解析数值字面量（允许负号），用于 range 的 min / max
+fn parse_number(input: syn::parse::ParseStream) -> syn::Result<f64> {
+    let negative = input.peek(syn::Token![-]);
+    if negative {
+        input.parse::<syn::Token![-]>()?;
+    }
+    let value = match input.parse::<syn::Lit>()? {
+        syn::Lit::Int(lit) => lit.base10_parse::<f64>()?,
+        syn::Lit::Float(lit) => lit.base10_parse::<f64>()?,
+        other => return Err(syn::Error::new(other.span(), "expected a number")),
+    };
+    Ok(if negative { -value } else { value })
+}
+
+/// #[config(health = "...")] 支持的检查类型
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum HealthAttr {
+    /// 路径存在
+    Path,
+    /// URL 格式正确
+    Url,
+    /// URL 格式正确且可连接
+    Endpoint,
+}
+
+/// 内置值转换器名称，需与 `quantum_config::transform::BuiltinTransform::NAMES` 保持一致
+const BUILTIN_TRANSFORMS: &[&str] = &["trim", "lowercase", "uppercase", "strip_trailing_slash"];
+
+/// 解析字段上的 #[config(...)] 属性
+fn parse_field_attributes(attrs: &[Attribute]) -> syn::Result<FieldAttributes> {
+    let mut field_attrs = FieldAttributes::default();
+    for attr in attrs {
+        if !is_config_attr(attr) {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("secret") {
+                field_attrs.secret = true;
+            } else if meta.path.is_ident("secret_keys") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let values = syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated(&content)?;
+                if values.is_empty() || values.iter().any(|value| value.value().is_empty()) {
+                    return Err(meta.error("secret_keys requires at least one non-empty key"));
+                }
+                field_attrs.secret_keys = values.iter().map(syn::LitStr::value).collect();
+            } else if meta.path.is_ident("severity") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                let variant = match value.value().to_lowercase().as_str() {
+                    "info" => "Info",
+                    "warning" | "warn" => "Warning",
+                    "critical" => "Critical",
+                    _ => return Err(meta.error("severity must be one of \"info\", \"warning\", \"critical\"")),
+                };
+                field_attrs.severity = Some(syn::Ident::new(variant, value.span()));
+            } else if meta.path.is_ident("transform") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                for name in value.value().split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                    if !BUILTIN_TRANSFORMS.contains(&name) {
+                        return Err(syn::Error::new(
+                            value.span(),
+                            format!("unknown transform '{}', expected one of: {}", name, BUILTIN_TRANSFORMS.join(", ")),
+                        ));
+                    }
+                    field_attrs.transforms.push(name.to_string());
+                }
+            } else if meta.path.is_ident("max_len") {
+                let value: syn::LitInt = meta.value()?.parse()?;
+                field_attrs.max_len = Some(value.base10_parse()?);
+            } else if meta.path.is_ident("range") {
+                let (mut min, mut max) = (None, None);
+                meta.parse_nested_meta(|bound| {
+                    if bound.path.is_ident("min") {
+                        min = Some(parse_number(bound.value()?)?);
+                    } else if bound.path.is_ident("max") {
+                        max = Some(parse_number(bound.value()?)?);
+                    } else {
+                        return Err(bound.error("range supports only `min` and `max`"));
+                    }
+                    Ok(())
+                })?;
+                if min.is_none() && max.is_none() {
+                    return Err(meta.error("range requires `min`, `max` or both"));
+                }
+                if let (Some(min), Some(max)) = (min, max) {
+                    if min > max {
+                        return Err(meta.error("range `min` must not exceed `max`"));
+                    }
+                }
+                field_attrs.range = Some((min, max));
+            } else if meta.path.is_ident("regex") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                // 在编译期检查语法，运行时不会因为写错的正则而失败
+                if let Err(e) = regex_syntax::Parser::new().parse(&value.value()) {
+                    return Err(syn::Error::new_spanned(&value, format!("invalid regex: {}", e)));
+                }
+                field_attrs.regex = Some(value.value());
+            } else if meta.path.is_ident("one_of") {
+                let content;
+                syn::parenthesized!(content in meta.input);
+                let values = syn::punctuated::Punctuated::<syn::LitStr, syn::Token![,]>::parse_terminated(&content)?;
+                if values.is_empty() {
+                    return Err(meta.error("one_of requires at least one value"));
+                }
+                field_attrs.one_of = Some(values.iter().map(syn::LitStr::value).collect());
+            } else if meta.path.is_ident("non_empty") {
+                field_attrs.non_empty = true;
+            } else if meta.path.is_ident("cli_positional") {
+                field_attrs.cli_positional = true;
+            } else if meta.path.is_ident("nested") {
+                field_attrs.nested = true;
+            } else if meta.path.is_ident("sources") || meta.path.is_ident("no_cli") {
+                if field_attrs.allowed_sources.is_some() {
+                    return Err(meta.error("use either `sources(...)` or `no_cli`, not both"));
+                }
+                let mut kinds = Vec::new();
+                if meta.path.is_ident("no_cli") {
+                    kinds.extend(["File", "Remote", "Env"]);
+                } else {
+                    meta.parse_nested_meta(|kind| {
+                        let variant = match kind.path.get_ident().map(ToString::to_string).as_deref() {
+                            Some("file") => "File",
+                            Some("remote") => "Remote",
+                            Some("env") => "Env",
+                            Some("cli") => "Cli",
+                            _ => return Err(kind.error("sources entries must be one of `file`, `remote`, `env`, `cli`")),
+                        };
+                        if kinds.contains(&variant) {
+                            return Err(kind.error("sources entries must not repeat"));
                         }
+                        kinds.push(variant);
+                        Ok(())
+                    })?;
+                    if kinds.is_empty() {
+                        return Err(meta.error("sources requires at least one of `file`, `remote`, `env`, `cli`"));
+                    }
+                }
+                field_attrs.allowed_sources = Some(kinds);
+            } else if meta.path.is_ident("env_list_separator") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if value.value().is_empty() {
+                    return Err(meta.error("env_list_separator must not be empty"));
+                }
+                field_attrs.env_list_separator = Some(value.value());
+            } else if meta.path.is_ident("deprecated") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                if value.value().trim().is_empty() {
+                    return Err(meta.error("deprecated requires a non-empty migration hint"));
+                }
+                field_attrs.deprecated = Some(value.value());
+            } else if meta.path.is_ident("health") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                field_attrs.health = Some(match value.value().as_str() {
+                    "path" => HealthAttr::Path,
+                    "url" => HealthAttr::Url,
+                    "endpoint" => HealthAttr::Endpoint,
+                    _ => return Err(meta.error("health must be one of \"path\", \"url\", \"endpoint\"")),
+                });
+            } else if meta.path.is_ident("description") {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                field_attrs.description = Some(value.value());
+            } else {
+                return Err(unknown_attribute(&meta));
+            }
+            Ok(())
+        })?;
+    }
+    let cli_allowed = field_attrs.allowed_sources.as_ref().is_none_or(|kinds| kinds.contains(&"Cli"));
+    if field_attrs.cli_positional && !cli_allowed {
+        let attr = attrs.iter().rev().find(|attr| is_config_attr(attr)).expect("cli_positional comes from a config attribute");
+        return Err(syn::Error::new_spanned(attr, "cli_positional requires the field to allow the `cli` source"));
+    }
+    Ok(field_attrs)
+}
+
+/// 合并字段上的 `///` 文档注释，每行去掉前导的一个空格
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(doc), .. }),
+                ..
+            }) => Some(doc.value()),
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').unwrap_or(&line).trim_end().to_string())
+        .collect();
+    let doc = lines.join("\n");
+    let doc = doc.trim_matches('\n');
+    (!doc.is_empty()).then(|| doc.to_string())
+}
+
+/// 将可选字符串转换为 `Option<&'static str>` 表达式
+fn option_str_tokens(value: Option<String>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
+    }
+}
+
+/// 字段类型是否为 `Option<T>`
+fn is_option_type(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(path) if path.path.segments.last().is_some_and(|segment| segment.ident == "Option"))
+}
+
+/// `Option<T>` 的内层类型，其余类型原样返回
+fn option_inner_type(ty: &syn::Type) -> &syn::Type {
+    if let syn::Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last().filter(|segment| segment.ident == "Option") {
+            if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                    return inner;
+                }
+            }
+        }
+    }
+    ty
+}
+
+/// 补丁结构体中一个字段的生成代码
+struct PatchField {
+    /// 字段定义（含 serde 属性）
+    definition: proc_macro2::TokenStream,
+    /// `apply_patch()` 中应用该字段的语句
+    apply: proc_macro2::TokenStream,
+    /// `is_empty()` 中该字段不修改任何值的条件
+    is_empty: proc_macro2::TokenStream,
+    /// 自定义（反）序列化函数的包装，生成在补丁结构体的 impl 中
+    helpers: Vec<proc_macro2::TokenStream>,
+}
+
+/// 生成补丁结构体的字段：普通字段为 `Option<T>`，嵌套字段为 `Option<{Inner}Patch>`
+fn patch_field(
+    patch_name: &syn::Ident,
+    field: &syn::Field,
+    serde_attrs: &SerdeFieldAttrs,
+    serde_patch: &SerdePatchAttrs,
+    nested: bool,
+    crate_ident: &proc_macro2::TokenStream,
+) -> syn::Result<PatchField> {
+    let (ident, ty, field_vis) = (field.ident.as_ref().expect("named field"), &field.ty, &field.vis);
+    let key_attrs = &serde_patch.key_attrs;
+    // 展平的字段没有自己的键，`default` 与 `skip_serializing_if` 不能与 `flatten` 同时使用
+    let presence_attrs = if serde_attrs.flatten {
+        quote! { #[serde(flatten)] }
+    } else {
+        quote! { #[serde(default, skip_serializing_if = "Option::is_none")] }
+    };
+
+    if nested {
+        let patch_ty = patch_type_of(option_inner_type(ty))?;
+        // `Option` 嵌套字段为 `None` 时先以默认值补全，再应用嵌套补丁
+        let target = if is_option_type(ty) {
+            quote! { self.#ident.get_or_insert_with(::core::default::Default::default) }
+        } else {
+            quote! { self.#ident }
+        };
+        return Ok(PatchField {
+            definition: quote! {
+                #( #key_attrs )*
+                #presence_attrs
+                #field_vis #ident: ::core::option::Option<#patch_ty>,
+            },
+            apply: quote! { if let Some(patch) = patch.#ident { #target.apply_patch(patch); } },
+            is_empty: quote! { match &self.#ident { Some(patch) => patch.is_empty(), None => true } },
+            helpers: Vec::new(),
+        });
+    }
+
+    // 自定义函数作用于 `T`，补丁字段为 `Option<T>`，通过局部包装类型转接
+    let serde_crate = quote! { #crate_ident::serde };
+    let mut with_attrs = Vec::new();
+    let mut helpers = Vec::new();
+    if let Some(serialize_with) = &serde_patch.serialize_with {
+        let helper = format_ident!("__serialize_{}", ident);
+        let helper_path = format!("{}::{}", patch_name, helper);
+        with_attrs.push(quote! { #[serde(serialize_with = #helper_path)] });
+        helpers.push(quote! {
+            #[doc(hidden)]
+            fn #helper<S: #serde_crate::Serializer>(value: &::core::option::Option<#ty>, serializer: S) -> Result<S::Ok, S::Error> {
+                struct Value<'a>(&'a #ty);
+                impl #serde_crate::Serialize for Value<'_> {
+                    fn serialize<S: #serde_crate::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                        #serialize_with(self.0, serializer)
+                    }
+                }
+                #serde_crate::Serialize::serialize(&value.as_ref().map(Value), serializer)
+            }
+        });
+    }
+    if let Some(deserialize_with) = &serde_patch.deserialize_with {
+        let helper = format_ident!("__deserialize_{}", ident);
+        let helper_path = format!("{}::{}", patch_name, helper);
+        with_attrs.push(quote! { #[serde(deserialize_with = #helper_path)] });
+        helpers.push(quote! {
+            #[doc(hidden)]
+            fn #helper<'de, D: #serde_crate::Deserializer<'de>>(deserializer: D) -> Result<::core::option::Option<#ty>, D::Error> {
+                struct Value(#ty);
+                impl<'de> #serde_crate::Deserialize<'de> for Value {
+                    fn deserialize<D: #serde_crate::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                        #deserialize_with(deserializer).map(Value)
                     }
                 }
+                let value: ::core::option::Option<Value> = #serde_crate::Deserialize::deserialize(deserializer)?;
+                Ok(value.map(|Value(value)| value))
+            }
+        });
+    }
+    Ok(PatchField {
+        definition: quote! {
+            #( #key_attrs )*
+            #presence_attrs
+            #( #with_attrs )*
+            #field_vis #ident: ::core::option::Option<#ty>,
+        },
+        apply: quote! { if let Some(value) = patch.#ident { self.#ident = value; } },
+        is_empty: quote! { self.#ident.is_none() },
+        helpers,
+    })
+}
+
+/// 判断类型是否为映射（`HashMap`、`BTreeMap`、`IndexMap`，允许外层包裹 `Option`）
+fn is_map_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    if segment.ident == "Option" {
+        if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+            if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                return is_map_type(inner);
+            }
+        }
+        return false;
+    }
+    ["HashMap", "BTreeMap", "IndexMap"].iter().any(|name| segment.ident == name)
+}
+
+/// 判断类型是否为 `SecretString`（允许外层包裹 `Option`）
+fn is_secret_string_type(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return false;
+    };
+    if segment.ident == "Option" {
+        if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+            if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                return is_secret_string_type(inner);
             }
         }
+        return false;
+    }
+    segment.ident == "SecretString"
+}
+
+/// 补丁结构体字段需要从原字段复制的 serde 属性
+#[derive(Default)]
+struct SerdePatchAttrs {
+    /// 决定键名的属性（`rename`、`alias`），补丁结构体的键与原结构体一致
+    key_attrs: Vec<proc_macro2::TokenStream>,
+    /// `serialize_with` 或 `with` 指定的序列化函数
+    serialize_with: Option<syn::ExprPath>,
+    /// `deserialize_with` 或 `with` 指定的反序列化函数
+    deserialize_with: Option<syn::ExprPath>,
+}
+
+/// 收集 `names` 列出的 serde 属性，原样复制到生成的结构体上
+fn copy_serde_attrs(attrs: &[Attribute], names: &[&str]) -> syn::Result<Vec<proc_macro2::TokenStream>> {
+    let mut copied = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if names.iter().any(|name| meta.path.is_ident(name)) {
+                let path = meta.path.clone();
+                if meta.input.peek(syn::Token![=]) {
+                    let value: syn::LitStr = meta.value()?.parse()?;
+                    copied.push(quote! { #[serde(#path = #value)] });
+                } else {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let nested: proc_macro2::TokenStream = content.parse()?;
+                    copied.push(quote! { #[serde(#path(#nested))] });
+                }
+            } else if meta.input.peek(syn::Token![=]) {
+                let _: syn::Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let skipped;
+                syn::parenthesized!(skipped in meta.input);
+                let _: proc_macro2::TokenStream = skipped.parse()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(copied)
+}
+
+/// 解析字段上补丁结构体需要的 serde 属性：键名属性与自定义（反）序列化函数
+fn parse_serde_patch_attrs(attrs: &[Attribute]) -> syn::Result<SerdePatchAttrs> {
+    let mut patch_attrs = SerdePatchAttrs {
+        key_attrs: copy_serde_attrs(attrs, &["rename", "alias"])?,
+        ..SerdePatchAttrs::default()
+    };
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("with") {
+                let module: syn::Path = meta.value()?.parse::<syn::LitStr>()?.parse()?;
+                patch_attrs.serialize_with = Some(syn::parse_quote!(#module::serialize));
+                patch_attrs.deserialize_with = Some(syn::parse_quote!(#module::deserialize));
+            } else if meta.path.is_ident("serialize_with") {
+                patch_attrs.serialize_with = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.path.is_ident("deserialize_with") {
+                patch_attrs.deserialize_with = Some(meta.value()?.parse::<syn::LitStr>()?.parse()?);
+            } else if meta.input.peek(syn::Token![=]) {
+                let _: syn::Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let skipped;
+                syn::parenthesized!(skipped in meta.input);
+                let _: proc_macro2::TokenStream = skipped.parse()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(patch_attrs)
+}
+
+/// 嵌套结构体的补丁类型：将类型路径的最后一段 `Name` 替换为 `NamePatch`
+fn patch_type_of(ty: &syn::Type) -> syn::Result<syn::Type> {
+    let mut patch_ty = ty.clone();
+    match &mut patch_ty {
+        syn::Type::Path(path) if path.qself.is_none() => {
+            if let Some(segment) = path.path.segments.last_mut() {
+                segment.ident = format_ident!("{}Patch", segment.ident);
+                return Ok(patch_ty);
+            }
+            Err(syn::Error::new_spanned(ty, "nested field type must be a struct path"))
+        }
+        _ => Err(syn::Error::new_spanned(ty, "nested field type must be a struct path")),
+    }
+}
+
+/// 字段上影响键名的 serde 属性
+#[derive(Default)]
+struct SerdeFieldAttrs {
+    /// `#[serde(rename = "...")]` 或 `#[serde(rename(serialize = "..."))]` 指定的键名
+    rename: Option<String>,
+    /// 是否标注了 `#[serde(skip)]`、`skip_serializing` 或 `skip_deserializing`
+    skip: bool,
+    /// 是否标注了 `#[serde(flatten)]`
+    flatten: bool,
+}
+
+/// 读取 `#[serde(rename = "...")]` 或 `#[serde(rename(serialize = "...", deserialize = "..."))]` 中的名称，
+/// 两者不同时取序列化名称：脱敏与写回文件作用于序列化后的值
+fn parse_serde_name(meta: &syn::meta::ParseNestedMeta) -> syn::Result<Option<String>> {
+    if meta.input.peek(syn::Token![=]) {
+        let value: syn::LitStr = meta.value()?.parse()?;
+        return Ok(Some(value.value()));
+    }
+    let (mut serialize, mut deserialize) = (None, None);
+    meta.parse_nested_meta(|name| {
+        let value: syn::LitStr = name.value()?.parse()?;
+        if name.path.is_ident("serialize") {
+            serialize = Some(value.value());
+        } else if name.path.is_ident("deserialize") {
+            deserialize = Some(value.value());
+        }
+        Ok(())
+    })?;
+    Ok(serialize.or(deserialize))
+}
+
+/// 解析字段上的 `#[serde(...)]` 属性中影响键名的部分，其余属性被跳过
+fn parse_serde_field_attrs(attrs: &[Attribute]) -> syn::Result<SerdeFieldAttrs> {
+    let mut serde_attrs = SerdeFieldAttrs::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                serde_attrs.rename = parse_serde_name(&meta)?;
+            } else if meta.path.is_ident("skip")
+                || meta.path.is_ident("skip_serializing")
+                || meta.path.is_ident("skip_deserializing")
+            {
+                serde_attrs.skip = true;
+            } else if meta.path.is_ident("flatten") {
+                serde_attrs.flatten = true;
+            } else if meta.input.peek(syn::Token![=]) {
+                let _: syn::Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let skipped;
+                syn::parenthesized!(skipped in meta.input);
+                let _: proc_macro2::TokenStream = skipped.parse()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(serde_attrs)
+}
+
+/// 解析结构体上的 `#[serde(rename_all = "...")]`
+fn parse_serde_rename_all(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let mut rename_all = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let span = meta.path.clone();
+                rename_all = parse_serde_name(&meta)?;
+                if let Some(rule) = &rename_all {
+                    if apply_rename_rule(rule, "field").is_none() {
+                        return Err(syn::Error::new_spanned(span, format!("unknown rename rule `{}`", rule)));
+                    }
+                }
+            } else if meta.input.peek(syn::Token![=]) {
+                let _: syn::Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let skipped;
+                syn::parenthesized!(skipped in meta.input);
+                let _: proc_macro2::TokenStream = skipped.parse()?;
+            }
+            Ok(())
+        })?;
+    }
+    Ok(rename_all)
+}
+
+/// 按 serde 的 `rename_all` 规则转换 snake_case 字段名，规则未知时返回 `None`
+fn apply_rename_rule(rule: &str, field: &str) -> Option<String> {
+    let pascal = || {
+        let mut pascal = String::new();
+        let mut capitalize = true;
+        for ch in field.chars() {
+            if ch == '_' {
+                capitalize = true;
+            } else if capitalize {
+                pascal.push(ch.to_ascii_uppercase());
+                capitalize = false;
+            } else {
+                pascal.push(ch);
+            }
+        }
+        pascal
+    };
+    Some(match rule {
+        "lowercase" | "snake_case" => field.to_string(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" => pascal(),
+        "camelCase" => {
+            let pascal = pascal();
+            let mut chars = pascal.chars();
+            chars.next().map(|first| first.to_ascii_lowercase().to_string() + chars.as_str()).unwrap_or_default()
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_ascii_uppercase().replace('_', "-"),
+        _ => return None,
+    })
+}
+
+/// 字段在序列化结果与配置来源中的键名：`#[serde(rename)]` 优先，其次为结构体的 `rename_all` 规则
+fn serde_key_name(ident: &syn::Ident, serde_attrs: &SerdeFieldAttrs, rename_all: Option<&str>) -> String {
+    let name = syn::ext::IdentExt::unraw(ident).to_string();
+    match (&serde_attrs.rename, rename_all) {
+        (Some(rename), _) => rename.clone(),
+        (None, Some(rule)) => apply_rename_rule(rule, &name).unwrap_or(name),
+        (None, None) => name,
+    }
+}
+
+/// 将可选数值转换为 `Option<f64>` 表达式
+fn option_tokens(value: Option<f64>) -> proc_macro2::TokenStream {
+    match value {
+        Some(value) => quote! { Some(#value) },
+        None => quote! { None },
     }
-    None
 }
 
 /// 为结构体自动实现配置管理功能的派生宏
 ///
-/// 该宏会为标注的结构体自动实现以下方法：
-/// - `load()`: 从多种来源加载配置（文件 -> 环境变量 -> 命令行参数）
-/// - `new()`: 创建新的配置实例
-/// - `load_from_file()`: 从指定文件加载并反序列化
-/// - `generate_template()`: 生成配置模板
+/// 该宏会为标注的结构体实现 `quantum_config::Config` 与 `quantum_config::ConfigHealth` trait：
+/// - `load()` / `load_with_args()`: 从多种来源加载配置（`Default` 值 -> 文件 -> 环境变量 -> 命令行参数），
+///   配置文件只需包含与默认值不同的键
+/// - `load_or_default()` / `load_lossy()`: 没有任何来源时回退到 `Default` 值，
+///   或将出错的键恢复为默认值并以警告列表返回，参见 `quantum_config::lossy`
+/// - 命令行参数：除内置参数外，每个字段（含嵌套字段，例如 `--server-port`）对应一个参数，
+///   帮助信息取自 `///` 文档注释，取值按字段类型校验，参见 `quantum_config::cli`
+/// - `load_from_file()`、`generate_template()`、`generate_template_as()` 等使用 trait 的默认实现
+/// - `struct_meta()`：字段名称、类型与 `///` 文档注释，文档注释在生成模板时作为注释写入
+/// - 由字段属性决定的 `transform_registry()`、`drift_severities()`、`register_secrets()`
+///
+/// 另外保留内联的 `new()` 方法以保持向后兼容。
 ///
 /// 支持的属性：
 /// - `#[config(env_prefix = "PREFIX_")]`: 自定义环境变量前缀
-#[proc_macro_derive(Config, attributes(config))]
+/// - `#[config(behavior_version = 2)]`: 宏行为版本（默认 1），从 2 起不再接受旧名称属性 `#[lingo(...)]`，参见 `quantum_config::compat`
+/// - `#[config(priority(env, cli, file))]`: 按低 -> 高优先级调整来源类别的合并顺序（`file`、`remote`、`env`、`cli`），参见 `QuantumConfigBuilder::with_priority`
+/// - `#[config(organization = "Example", qualifier = "com")]`: 平台配置目录使用的组织名与限定符，参见 `QuantumConfigAppMeta`
+/// - `#[config(path_strategy = "xdg")]`: 在所有平台上按 XDG 基础目录规范查找配置目录（默认 `"native"`），参见 `quantum_config::PathStrategy`
+/// - `#[config(env_file_suffix = "_FILE")]`: 以该后缀结尾的环境变量表示从文件读取值（Docker secrets 约定）
+/// - `#[config(profile_env = "APP_ENV")]`: 按该环境变量指定的配置剖面，在 `config.toml` 之后依次合并 `config.{profile}.toml` 与 `config.local.toml`
+/// - `#[config(env_scan = "schema")]`: 只查询由默认值结构推导出的环境变量，不遍历整个进程环境
+/// - `#[config(env_separator = "_", env_case = "snake")]`: 环境变量的嵌套键分隔符（默认 `__`）与键名大小写规则
+///   （`snake`、`screaming_snake`、`kebab`、`camel`、`preserve`），变量名按字段结构匹配，
+///   分隔符为单个 `_` 时 `APP_DATABASE_MAX_CONNS` 对应 `database.max_conns`，参见 `quantum_config::providers::EnvCase`
+/// - `#[config(cli_nested_style = "dot")]`: 嵌套字段的命令行参数写作 `--server.port`（默认 `"dash"`，即 `--server-port`），
+///   参见 `quantum_config::cli::CliNestedStyle`
+/// - `#[config(cli = "ignore_unknown")]`: 加载时忽略无法识别的命令行参数而不是报错退出，适合应用另有参数解析器的情形；
+///   `#[config(cli = false)]` 完全不读取命令行参数（默认 `"strict"`），参见 `quantum_config::cli::CliMode`
+/// - `#[config(namespace = "mylib")]`: 库的配置命名空间，`LibConfig::load_nested_from(&host_figment)` 从宿主应用的
+///   `[mylib]` 表与 `APP_MYLIB__*` 环境变量加载，参见 `quantum_config::namespace`
+/// - `#[config(no_defaults)]`: 不合并 `Default` 值，来源中缺少的非可选字段导致加载失败
+/// - `#[config(validate)]`: 加载后调用结构体的 `quantum_config::validate::Validate` 实现，失败时返回全部出错字段
+/// - `#[config(migrate)]`: 读取配置文件后按结构体的 `quantum_config::migrate::Migrate` 实现，从文件的 `config_version` 迁移到最新版本
+/// - `#[config(config_d)]`: 在 `config.toml` 之后按文件名字典序合并 `config.d/` 中的片段文件，参见 `QuantumConfigBuilder::with_config_fragments`
+/// - `#[config(tenant_source = "https://config.internal/{tenant}/app.toml")]`: `T::load_for("tenant-a")` 时合并的租户专属来源，
+///   可重复；租户文件 `config.tenant-a.toml` 与 `APP__TENANT_A__` 前缀的环境变量无需声明，参见 `quantum_config::LoadOptions::with_tenant`
+/// - `#[config(includes)]`: 展开配置文件顶层的 `include = ["extra/*.toml"]` 指令（相对于该文件），参见 `QuantumConfigBuilder::with_config_includes`
+/// - `#[config(encrypted)]`: 所有配置文件均为加密文件，加载时解密（需要 `encryption` 特性），参见 `quantum_config::providers::encryption`
+/// - `#[config(secret_references)]`: 将 `secret_file:/path` 与 `${file:/path}` 替换为文件内容，参见 `quantum_config::providers::SecretsProvider`
+/// - 字段上的 `#[config(secret)]`: 加载后将字段值登记为敏感值（参见 `quantum_config::scrub`），
+///   在 `redacted()` 视图中脱敏，并且不写入模板与 `save_to_file()` 的输出，参见 `quantum_config::redact`
+/// - 字段上的 `#[config(secret_keys("password"))]`: 将嵌套结构体中的键按同样规则视为敏感键
+/// - `#[config(redact_debug)]`: 生成与 `redacted()` 相同输出的 `Debug` 实现，结构体不应再派生 `Debug`
+/// - `#[config(patch)]`: 生成补丁结构体 `{Name}Patch`（每个字段为 `Option`）以及 `apply_patch()`、`load_patch_from_file()`，
+///   用于只包含修改键的覆盖文件与运行时局部更新。补丁沿用结构体的 `rename_all` 与字段的 `rename`、`alias`、`with`、
+///   `flatten`，`#[serde(skip)]` 字段不在补丁中；缺少的键始终为 `None`，字段的 `default` 不参与。
+///   `#[config(nested)]` 字段的补丁类型为嵌套结构体的 `{Inner}Patch`，嵌套结构体同样需要 `#[config(patch)]`
+/// - 所有键路径（敏感键、转换规则、校验与健康检查的错误信息等）使用 serde 可见的键名，
+///   即遵循字段的 `#[serde(rename)]` 与结构体的 `#[serde(rename_all)]`；`#[serde(skip)]` 字段不参与
+/// - 字段上的 `#[config(transform = "trim")]`: 合并后对字段值应用内置转换器，参见 `quantum_config::transform`
+/// - 字段上的 `#[config(severity = "critical")]`: 漂移检测时该字段的严重级别，参见 `quantum_config::drift`
+/// - 字段上的 `#[config(sources(env, file))]`: 字段的值只能来自列出的来源类别（`file`、`remote`、`env`、`cli`），
+///   来自其它来源时加载失败；`#[config(no_cli)]` 允许除命令行参数外的全部类别且不生成该字段的参数，参见 `quantum_config::restrict`
+/// - 字段上的 `#[config(nested)]`: 字段类型（或 `Option` 的内层类型）同样派生了 `Config`，
+///   其字段上的来源限制、环境变量列表分隔符与健康检查以该字段的键为前缀并入外层结构体
+/// - 字段上的 `#[config(deprecated = "use server.port instead")]`: 任一来源提供该键时输出带来源位置的弃用警告，参见 `quantum_config::deprecated`
+/// - `HashMap` / `BTreeMap` 字段可由嵌套环境变量（`APP_LABELS__REGION=eu`）或 JSON 对象字面量（`APP_LABELS='{"region":"eu"}'`）设置
+/// - 字段上的 `#[config(env_list_separator = ",")]`: 该字段的环境变量值按分隔符拆分为数组（也接受 JSON 数组字面量）
+/// - 字段上的 `#[config(max_len = 255)]`: 字符串最大长度，加载后校验，同时作为该字段环境变量值的长度上限
+/// - 字段上的 `#[config(range(min = 1, max = 65535))]`、`#[config(regex = "...")]`、`#[config(one_of("json", "text"))]`、
+///   `#[config(non_empty)]`: 加载后校验字段值，报告全部出错字段，错误信息包含字段路径与出错值的来源，参见 `quantum_config::validate`；
+///   `regex` 需要 `regex` 特性，正则语法在编译期检查
+/// - 字段上的 `#[config(health = "path" | "url" | "endpoint")]`: 健康检查时校验路径存在、URL 格式或端点可连接，参见 `quantum_config::health`
+/// - 字段上的 `#[config(cli_positional)]`: 该字段绑定到位置参数（按字段顺序编号）而不是 `--option`，
+///   仍可由配置文件与环境变量提供，命令行给出时优先
+#[proc_macro_derive(Config, attributes(config, lingo))]
 pub fn derive_config(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
 
-    // 解析自定义环境变量前缀
-    let custom_env_prefix = parse_config_attributes(&input.attrs);
+    // 解析结构体级属性（环境变量前缀、文件间接引用后缀）
+    let struct_attrs = match parse_config_attributes(&input.attrs) {
+        Ok(attrs) => attrs,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    // 旧名称属性 #[lingo(...)]：行为版本 1 时可用并产生弃用警告，之后的版本拒绝
+    let behavior_version = struct_attrs.behavior_version.unwrap_or(DEFAULT_BEHAVIOR_VERSION);
+    let legacy_attribute = find_legacy_attribute(&input);
+    if let Some(attr) = legacy_attribute {
+        if behavior_version >= LEGACY_NAMES_REMOVED_IN {
+            return syn::Error::new_spanned(
+                attr,
+                format!("#[lingo(...)] attributes were removed in behavior_version {}; use #[config(...)]", LEGACY_NAMES_REMOVED_IN),
+            )
+            .to_compile_error()
+            .into();
+        }
+    }
 
-    // 使用 proc-macro-crate 动态解析依赖方对 `quantum_config` 的重命名
-    let crate_ident = match proc_macro_crate::crate_name("quantum_config") {
+    // 使用 proc-macro-crate 动态解析依赖方对 `quantum_config` 的重命名，依赖方只引入旧包名 `lingo` 时使用该包名
+    let crate_name = proc_macro_crate::crate_name("quantum_config").or_else(|_| proc_macro_crate::crate_name("lingo"));
+    let crate_ident = match crate_name {
         Ok(proc_macro_crate::FoundCrate::Itself) => quote! { crate },
         Ok(proc_macro_crate::FoundCrate::Name(name)) => {
             let ident = syn::Ident::new(&name, proc_macro2::Span::call_site());
@@ -86,137 +1005,830 @@ pub fn derive_config(input: TokenStream) -> TokenStream {
         Err(_) => quote! { quantum_config }, // 回退：直接使用 quantum_config
     };
 
+    // 收集字段级属性：#[config(secret)]、#[config(severity = "...")]、#[config(transform = "...")]、校验器与健康检查
+    let mut secret_fields = Vec::new();
+    let mut severity_keys = Vec::new();
+    let mut severity_variants = Vec::new();
+    let mut deprecated_keys = Vec::new();
+    let mut deprecated_messages = Vec::new();
+    let mut transform_patterns = Vec::new();
+    let mut transform_names = Vec::new();
+    let mut max_len_fields = Vec::new();
+    let mut max_len_keys = Vec::new();
+    let mut max_len_values = Vec::new();
+    let mut max_len_secrets = Vec::new();
+    let mut list_separator_keys = Vec::new();
+    let mut list_separators = Vec::new();
+    let mut json_value_keys = Vec::new();
+    let mut raw_string_keys = Vec::new();
+    let mut validator_calls = Vec::new();
+    let mut health_path_fields = Vec::new();
+    let mut health_path_keys = Vec::new();
+    let mut health_path_secrets = Vec::new();
+    let mut health_url_fields = Vec::new();
+    let mut health_url_keys = Vec::new();
+    let mut health_url_secrets = Vec::new();
+    let mut health_endpoint_fields = Vec::new();
+    let mut health_endpoint_keys = Vec::new();
+    let mut health_endpoint_secrets = Vec::new();
+    let mut meta_names = Vec::new();
+    let mut meta_types = Vec::new();
+    let mut meta_docs = Vec::new();
+    let mut meta_descriptions = Vec::new();
+    let mut meta_options = Vec::new();
+    let mut meta_secrets = Vec::new();
+    let mut meta_positionals = Vec::new();
+    let mut meta_allowed_sources = Vec::new();
+    let mut secret_key_paths = Vec::new();
+    let mut nested_secret_keys = Vec::new();
+    let mut patch_field_defs = Vec::new();
+    let mut patch_applies = Vec::new();
+    let mut patch_empty_checks = Vec::new();
+    let mut patch_helpers = Vec::new();
+    let vis = &input.vis;
+    let patch_name = format_ident!("{}Patch", name);
+    let mut meta_name_overrides = Vec::new();
+    let mut meta_skipped = Vec::new();
+    let mut meta_flatten = Vec::new();
+    let mut nested_fields = Vec::new();
+    let mut nested_types = Vec::new();
+    let mut nested_prefixes = Vec::new();
+    let mut nested_refs = Vec::new();
+    let rename_all = match parse_serde_rename_all(&input.attrs) {
+        Ok(rename_all) => rename_all,
+        Err(e) => return e.to_compile_error().into(),
+    };
+    if let Data::Struct(data) = &input.data {
+        if let Fields::Named(fields) = &data.fields {
+            for field in &fields.named {
+                let mut attrs = match parse_field_attributes(&field.attrs) {
+                    Ok(attrs) => attrs,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                let serde_attrs = match parse_serde_field_attrs(&field.attrs) {
+                    Ok(serde_attrs) => serde_attrs,
+                    Err(e) => return e.to_compile_error().into(),
+                };
+                let Some(ident) = &field.ident else {
+                    continue;
+                };
+                let ty = &field.ty;
+                // 键名与 serde 一致，重命名的字段仍按来源与序列化结果中的键匹配
+                let key = serde_key_name(ident, &serde_attrs, rename_all.as_deref());
+                meta_names.push(ident.to_string());
+                meta_name_overrides.push(option_str_tokens((*ident != key).then(|| key.clone())));
+                meta_skipped.push(serde_attrs.skip);
+                meta_flatten.push(serde_attrs.flatten);
+                // `SecretString` 字段无需标注即视为敏感字段
+                attrs.secret |= is_secret_string_type(ty);
+                meta_types.push(quote!(#ty).to_string());
+                meta_docs.push(option_str_tokens(doc_comment(&field.attrs)));
+                meta_descriptions.push(option_str_tokens(attrs.description.clone()));
+                meta_options.push(is_option_type(ty));
+                meta_secrets.push(attrs.secret);
+                meta_positionals.push(attrs.cli_positional);
+                meta_allowed_sources.push(match &attrs.allowed_sources {
+                    Some(kinds) => {
+                        let kinds = kinds.iter().map(|kind| format_ident!("{}", kind));
+                        quote! { Some(&[ #( #crate_ident::SourceKind::#kinds ),* ]) }
+                    }
+                    None => quote! { None },
+                });
+                if serde_attrs.skip {
+                    // 不经过 serde 的字段没有键，只记录元数据
+                    continue;
+                }
+                if struct_attrs.patch {
+                    let serde_patch = match parse_serde_patch_attrs(&field.attrs) {
+                        Ok(serde_patch) => serde_patch,
+                        Err(e) => return e.to_compile_error().into(),
+                    };
+                    match patch_field(&patch_name, field, &serde_attrs, &serde_patch, attrs.nested, &crate_ident) {
+                        Ok(patch) => {
+                            patch_field_defs.push(patch.definition);
+                            patch_applies.push(patch.apply);
+                            patch_empty_checks.push(patch.is_empty);
+                            patch_helpers.extend(patch.helpers);
+                        }
+                        Err(e) => return e.to_compile_error().into(),
+                    }
+                }
+                if attrs.secret {
+                    secret_fields.push(ident.clone());
+                }
+                if attrs.nested {
+                    nested_fields.push(ident.clone());
+                    nested_types.push(option_inner_type(ty).clone());
+                    // 未设置的 `Option` 嵌套字段没有敏感值，也不做校验
+                    nested_refs.push(if is_option_type(ty) {
+                        quote! { self.#ident.as_ref() }
+                    } else {
+                        quote! { ::core::option::Option::Some(&self.#ident) }
+                    });
+                    // `#[serde(flatten)]` 的字段没有自己的键，嵌套键直接位于外层
+                    nested_prefixes.push(if serde_attrs.flatten { String::new() } else { key.clone() });
+                }
+                if let Some(severity) = attrs.severity {
+                    severity_keys.push(key.clone());
+                    severity_variants.push(severity);
+                }
+                if let Some(message) = &attrs.deprecated {
+                    deprecated_keys.push(key.clone());
+                    deprecated_messages.push(message.clone());
+                }
+                if attrs.secret {
+                    secret_key_paths.push(key.clone());
+                }
+                for nested_key in &attrs.secret_keys {
+                    let path = format!("{}.{}", key, nested_key);
+                    secret_key_paths.push(path.clone());
+                    nested_secret_keys.push(path);
+                }
+                for transform in attrs.transforms {
+                    // 作用于字段本身及其全部子键
+                    transform_patterns.push(format!("{}.**", key));
+                    transform_names.push(transform);
+                }
+                if let Some(max_len) = attrs.max_len {
+                    max_len_fields.push(ident.clone());
+                    max_len_keys.push(key.clone());
+                    max_len_values.push(max_len);
+                    max_len_secrets.push(attrs.secret);
+                }
+                if is_map_type(ty) {
+                    json_value_keys.push(key.clone());
+                }
+                if is_secret_string_type(ty) {
+                    raw_string_keys.push(key.clone());
+                }
+                if let Some(separator) = attrs.env_list_separator {
+                    list_separator_keys.push(key.clone());
+                    list_separators.push(separator);
+                }
+                let secret = attrs.secret;
+                if attrs.non_empty {
+                    validator_calls.push(quote! { #crate_ident::validate::check_non_empty(#key, &self.#ident) });
+                }
+                if let Some((min, max)) = attrs.range {
+                    let min = option_tokens(min);
+                    let max = option_tokens(max);
+                    validator_calls.push(quote! { #crate_ident::validate::check_range(#key, &self.#ident, #min, #max, #secret) });
+                }
+                if let Some(pattern) = &attrs.regex {
+                    // 每个字段的正则只编译一次，语法已在上面检查
+                    validator_calls.push(quote! {
+                        {
+                            static REGEX: ::std::sync::LazyLock<#crate_ident::validate::FieldRegex> = ::std::sync::LazyLock::new(|| {
+                                #crate_ident::validate::FieldRegex::new(#pattern).expect("regex syntax is checked by #[derive(Config)]")
+                            });
+                            #crate_ident::validate::check_regex(#key, &self.#ident, &REGEX, #secret)
+                        }
+                    });
+                }
+                if let Some(allowed) = &attrs.one_of {
+                    validator_calls.push(quote! { #crate_ident::validate::check_one_of(#key, &self.#ident, &[#(#allowed),*], #secret) });
+                }
+                match attrs.health {
+                    Some(HealthAttr::Path) => {
+                        health_path_fields.push(ident.clone());
+                        health_path_keys.push(key.clone());
+                        health_path_secrets.push(attrs.secret);
+                    }
+                    Some(health) => {
+                        health_url_fields.push(ident.clone());
+                        health_url_keys.push(key.clone());
+                        health_url_secrets.push(attrs.secret);
+                        if health == HealthAttr::Endpoint {
+                            health_endpoint_fields.push(ident.clone());
+                            health_endpoint_keys.push(key.clone());
+                            health_endpoint_secrets.push(attrs.secret);
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
     // 生成环境变量前缀逻辑
-    let env_prefix_expr = if let Some(prefix) = custom_env_prefix {
+    let env_prefix_expr = if let Some(prefix) = &struct_attrs.env_prefix {
         quote! { Some(#prefix.to_string()) }
     } else {
         quote! { Some(format!("{}_", app_name.to_uppercase())) }
     };
 
+    // 生成环境变量读取范围逻辑
+    let env_scan_expr = if struct_attrs.env_scan_schema {
+        quote! {
+            .with_env_scan_scope(#crate_ident::providers::EnvScanScope::from_value(&<Self as ::core::default::Default>::default())?)
+        }
+    } else {
+        quote! {}
+    };
+
+    // 生成环境变量命名规则逻辑：按已知键匹配变量名，避免分隔符与字段名中的 `_` 混淆
+    let env_naming_expr = {
+        let separator = struct_attrs.env_separator.as_ref().map(|separator| quote! { .with_env_separator(#separator) });
+        let case = struct_attrs.env_case.map(|case| {
+            let case = syn::Ident::new(case, proc_macro2::Span::call_site());
+            quote! { .with_env_case(#crate_ident::providers::EnvCase::#case) }
+        });
+        if separator.is_some() || case.is_some() {
+            quote! {
+                #separator
+                #case
+                .with_env_key_paths(#crate_ident::unknown::known_keys(&<Self as #crate_ident::Config>::key_shape()?))
+            }
+        } else {
+            quote! {}
+        }
+    };
+
+    // 生成密钥引用解析逻辑
+    let secret_references_expr = if struct_attrs.secret_references {
+        quote! { .with_secret_references(true) }
+    } else {
+        quote! {}
+    };
+
+    let tenant_sources = &struct_attrs.tenant_sources;
+
+    // 生成片段目录合并逻辑
+    let config_d_expr = if struct_attrs.config_d {
+        quote! { .with_config_fragments(true) }
+    } else {
+        quote! {}
+    };
+
+    // 生成包含指令展开逻辑
+    let includes_expr = if struct_attrs.includes {
+        quote! { .with_config_includes(true) }
+    } else {
+        quote! {}
+    };
+
+    // 生成版本迁移逻辑
+    let migrate_expr = if struct_attrs.migrate {
+        quote! { .with_migrations(<Self as #crate_ident::migrate::Migrate>::migrations()) }
+    } else {
+        quote! {}
+    };
+
+    // 生成加密文件逻辑
+    let encrypted_expr = if struct_attrs.encrypted {
+        quote! { .with_encrypted_files(true) }
+    } else {
+        quote! {}
+    };
+
+    let (unknown_keys_expr, unknown_key_policy_expr) = match struct_attrs.unknown_key_policy {
+        Some(policy) => {
+            let policy = syn::Ident::new(policy, proc_macro2::Span::call_site());
+            (
+                quote! {
+                    // 在提取之前报告拼错或多余的键，避免它们被静默忽略
+                    #crate_ident::unknown::enforce::<Self>(figment, #crate_ident::unknown::UnknownKeyPolicy::#policy)?;
+                },
+                quote! { Some(#crate_ident::unknown::UnknownKeyPolicy::#policy) },
+            )
+        }
+        None => (quote! {}, quote! { None }),
+    };
+
+    // 生成自定义校验逻辑
+    let custom_validate_expr = if struct_attrs.validate {
+        quote! { errors.extend(<Self as #crate_ident::validate::Validate>::validate_rules(self).err().map(#crate_ident::QuantumConfigError::from)); }
+    } else {
+        quote! {}
+    };
+
+    // 生成默认值层逻辑
+    let defaults_expr = if struct_attrs.no_defaults {
+        quote! {}
+    } else {
+        quote! { .with_defaults(<Self as ::core::default::Default>::default()) }
+    };
+
+    // 生成命名空间逻辑：从共享合并结果中提取时同样遵循 no_defaults
+    let namespace_fn = struct_attrs.namespace.as_ref().map(|namespace| {
+        quote! {
+            fn namespace() -> Option<&'static str> {
+                Some(#namespace)
+            }
+        }
+    });
+    let nested_defaults_expr = if struct_attrs.no_defaults {
+        quote! { None }
+    } else {
+        quote! { Some(<Self as ::core::default::Default>::default()) }
+    };
+
+    // 生成配置剖面逻辑
+    let profile_env_expr = match &struct_attrs.profile_env {
+        Some(var) => quote! { .with_profile_env(#var) },
+        None => quote! {},
+    };
+
+    // 生成配置目录查找逻辑
+    let option_string = |value: &Option<String>| match value {
+        Some(value) => quote! { Some(#value.to_string()) },
+        None => quote! { None },
+    };
+    let qualifier_expr = option_string(&struct_attrs.qualifier);
+    let organization_expr = option_string(&struct_attrs.organization);
+    let path_strategy_expr = if struct_attrs.xdg_paths {
+        quote! { #crate_ident::PathStrategy::Xdg }
+    } else {
+        quote! { #crate_ident::PathStrategy::Native }
+    };
+
+    // 生成来源优先级逻辑
+    let cli_nested_style_expr = struct_attrs.cli_nested_style.map(|style| {
+        let style = format_ident!("{}", style);
+        quote! { meta.cli_nested_style = #crate_ident::cli::CliNestedStyle::#style; }
+    });
+    let cli_mode_expr = struct_attrs.cli_mode.map(|mode| {
+        let mode = format_ident!("{}", mode);
+        quote! { meta.cli_mode = #crate_ident::cli::CliMode::#mode; }
+    });
+
+    let priority_expr = match &struct_attrs.priority {
+        Some(kinds) => quote! { .with_priority([#( #crate_ident::SourceKind::#kinds ),*]) },
+        None => quote! {},
+    };
+
+    // 生成环境变量文件间接引用逻辑
+    let env_file_suffix_expr = match &struct_attrs.env_file_suffix {
+        Some(suffix) => quote! { .with_env_file_suffix(#suffix) },
+        None => quote! {},
+    };
+
+    // 补丁结构体：每个字段包装为 Option，未出现的键为 None
+    let patch_impl = if struct_attrs.patch {
+        let patch_doc = format!(
+            "[`{}`] 的补丁：每个字段为 `Option`，只包含需要修改的键。\n\n`#[config(nested)]` 字段为嵌套结构体的补丁，\
+             逐键合并；其余字段整体替换。由 `{}::load_patch_from_file` 读取，通过 `{}::apply_patch` 应用。",
+            name, name, name
+        );
+        let serde_path = format!("{}::serde", crate_ident);
+        let container_attrs = match copy_serde_attrs(&input.attrs, &["rename_all"]) {
+            Ok(container_attrs) => container_attrs,
+            Err(e) => return e.to_compile_error().into(),
+        };
+        quote! {
+            #[doc = #patch_doc]
+            #[derive(::core::default::Default, #crate_ident::Serialize, #crate_ident::Deserialize)]
+            #[serde(crate = #serde_path)]
+            #( #container_attrs )*
+            #vis struct #patch_name {
+                #( #patch_field_defs )*
+            }
+
+            impl #patch_name {
+                /// 补丁是否不修改任何字段
+                pub fn is_empty(&self) -> bool {
+                    true #( && #patch_empty_checks )*
+                }
+
+                #( #patch_helpers )*
+            }
+
+            impl #name {
+                /// 应用补丁：补丁中为 `Some` 的字段覆盖当前值，其余字段保持不变
+                pub fn apply_patch(&mut self, patch: #patch_name) {
+                    #( #patch_applies )*
+                }
+
+                /// 从文件读取补丁（格式按扩展名推断），文件只需包含需要修改的键
+                pub fn load_patch_from_file<P: AsRef<::std::path::Path>>(path: P) -> Result<#patch_name, #crate_ident::QuantumConfigError> {
+                    let provider = #crate_ident::providers::QuantumConfigFileProvider::from_path(
+                        path.as_ref(),
+                        true,
+                        Self::__quantum_config_app_meta().max_parse_depth,
+                    )?;
+                    let figment = <Self as #crate_ident::Config>::transform_registry()?
+                        .apply_to_figment(#crate_ident::Figment::from(provider))?;
+                    Ok(figment.extract()?)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    // 使用旧名称属性时引用已弃用的常量，在依赖方产生弃用警告
+    let legacy_warning = match legacy_attribute {
+        Some(attr) => {
+            let span = syn::spanned::Spanned::span(attr);
+            // 整个常量路径使用属性的位置，派生宏展开位置上的弃用警告会被编译器忽略
+            let warning = quote::quote_spanned! {span=> __quantum_config_compat::LINGO_ATTRIBUTE };
+            quote! {
+                const _: () = {
+                    use #crate_ident::compat as __quantum_config_compat;
+                    #warning
+                };
+            }
+        }
+        None => quote! {},
+    };
+
+    // 脱敏的 Debug 实现：与 `redacted()` 的输出相同
+    let redact_debug_impl = if struct_attrs.redact_debug {
+        quote! {
+            impl ::core::fmt::Debug for #name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    ::core::fmt::Debug::fmt(&#crate_ident::Config::redacted(self), f)
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     // 生成的实现：基于 quantum_config 暴露的公共 API 与 figment 进行合并
     let expanded = quote! {
+        #legacy_warning
+
+        #redact_debug_impl
+
+        #patch_impl
+
         impl #name {
-            /// 从多种来源加载配置
-            ///
-            /// 加载顺序（低 -> 高优先级覆盖）：
-            /// 1. 文件（系统级、用户级、以及 --config 指定的文件）
-            /// 2. 环境变量（可选使用前缀，默认使用结构体名大写并加下划线）
-            /// 3. 命令行参数（clap 提供者）
-            pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-                // 构造应用元数据（默认值）：
-                // app_name 使用类型名，env_prefix 使用自定义或默认格式，行为版本与深度使用默认
-                let cmd_name: &'static str = stringify!(#name);
-                let app_name = cmd_name.to_string();
-                let env_prefix = #env_prefix_expr;
-                let app_meta = #crate_ident::QuantumConfigAppMeta { app_name, env_prefix, behavior_version: 1, max_parse_depth: 128 };
-
-                // 解析候选配置文件路径（宽容处理目录缺失场景）
-                let mut config_file_paths = match #crate_ident::resolve_config_files(&app_meta) {
-                    Ok(v) => v,
-                    Err(#crate_ident::QuantumConfigError::NoConfigFilesFoundInDir { .. }) |
-                    Err(#crate_ident::QuantumConfigError::ConfigDirNotFound { .. }) => Vec::new(),
-                    Err(e) => return Err(e.into()),
-                };
+            /// 创建新的配置实例（使用 Default），保持向后兼容
+            pub fn new() -> Self { Self::default() }
 
-                // 尝试从命令行解析 --config 以追加必选文件
-                let clap_matches = #crate_ident::Command::new(cmd_name)
-                    .arg(#crate_ident::Arg::new("config").long("config").short('c').num_args(1))
-                    .arg(#crate_ident::Arg::new("config-dir").long("config-dir").num_args(1))
-                    .arg(#crate_ident::Arg::new("log-level").long("log-level").num_args(1))
-                    .arg(#crate_ident::Arg::new("verbose").long("verbose").short('v').action(#crate_ident::ArgAction::SetTrue))
-                    .arg(#crate_ident::Arg::new("quiet").long("quiet").short('q').action(#crate_ident::ArgAction::SetTrue))
-                    .arg(#crate_ident::Arg::new("output").long("output").short('o').num_args(1))
-                    .arg(#crate_ident::Arg::new("format").long("format").num_args(1))
-                    // Removed allow_external_subcommands(true) to prevent command injection
-                    .get_matches_from(std::env::args());
-
-                if let Some(cfg) = clap_matches.get_one::<String>("config") {
-                    let path = std::path::PathBuf::from(cfg);
-                    #crate_ident::add_specified_config_file(&mut config_file_paths, path)?;
-                }
-
-                let mut fig = #crate_ident::Figment::new();
-                for cfg in config_file_paths {
-                    let provider = #crate_ident::providers::QuantumConfigFileProvider::from_path(&cfg.path, cfg.is_required, app_meta.max_parse_depth)?;
-                    fig = fig.merge(provider);
-                }
-                if let Some(prefix) = app_meta.env_prefix.clone() {
-                    let env_provider = #crate_ident::providers::QuantumConfigEnvProvider::with_prefix(prefix);
-                    fig = fig.merge(env_provider);
-                }
-                let clap_provider = #crate_ident::providers::clap_provider::with_common_mappings(clap_matches);
-                fig = fig.merge(clap_provider);
-                Ok(fig.extract()?)
-            }
-
-            /// 从多种来源加载配置（测试辅助：可注入命令行参数）
-            pub fn load_with_args(args: Vec<String>) -> Result<Self, Box<dyn std::error::Error>> {
-                let cmd_name: &'static str = stringify!(#name);
-                let app_name = cmd_name.to_string();
+            /// 构造应用元数据：app_name 使用类型名，env_prefix 使用自定义或默认格式
+            #[doc(hidden)]
+            fn __quantum_config_app_meta() -> #crate_ident::QuantumConfigAppMeta {
+                let app_name = stringify!(#name).to_string();
                 let env_prefix = #env_prefix_expr;
-                let app_meta = #crate_ident::QuantumConfigAppMeta { app_name, env_prefix, behavior_version: 1, max_parse_depth: 128 };
+                #crate_ident::QuantumConfigAppMeta {
+                    app_name,
+                    env_prefix,
+                    behavior_version: #behavior_version,
+                    max_parse_depth: 128,
+                    qualifier: #qualifier_expr,
+                    organization: #organization_expr,
+                    path_strategy: #path_strategy_expr,
+                }
+            }
 
-                let mut config_file_paths = match #crate_ident::resolve_config_files(&app_meta) {
-                    Ok(v) => v,
-                    Err(#crate_ident::QuantumConfigError::NoConfigFilesFoundInDir { .. }) |
-                    Err(#crate_ident::QuantumConfigError::ConfigDirNotFound { .. }) => Vec::new(),
-                    Err(e) => return Err(e.into()),
-                };
+            /// 命令行参数定义：内置参数加上每个字段（含嵌套字段）的参数
+            #[doc(hidden)]
+            fn __quantum_config_command() -> Result<#crate_ident::Command, #crate_ident::QuantumConfigError> {
+                #crate_ident::cli::config_command::<Self>(stringify!(#name))
+            }
+
+            /// 解析进程的命令行参数；指定 `--generate-completions`、`--generate-man` 或 `--validate-config` 时输出后退出
+            #[doc(hidden)]
+            fn __quantum_config_process_matches() -> Result<#crate_ident::ArgMatches, #crate_ident::QuantumConfigError> {
+                // Removed allow_external_subcommands(true) to prevent command injection
+                let clap_matches = #crate_ident::cli::process_matches::<Self>(Self::__quantum_config_command()?);
+                // --generate-completions / --generate-man 不需要加载配置
+                #crate_ident::cli::handle_generate(&clap_matches, Self::__quantum_config_command)?;
+                #crate_ident::cli::handle_validate_config(&clap_matches, || {
+                    Self::__quantum_config_load_with_report_from_matches(clap_matches.clone())
+                });
+                Ok(clap_matches)
+            }
 
-                let clap_matches = #crate_ident::Command::new(cmd_name)
-                    .arg(#crate_ident::Arg::new("config").long("config").short('c').num_args(1))
-                    .arg(#crate_ident::Arg::new("config-dir").long("config-dir").num_args(1))
-                    .arg(#crate_ident::Arg::new("log-level").long("log-level").num_args(1))
-                    .arg(#crate_ident::Arg::new("verbose").long("verbose").short('v').action(#crate_ident::ArgAction::SetTrue))
-                    .arg(#crate_ident::Arg::new("quiet").long("quiet").short('q').action(#crate_ident::ArgAction::SetTrue))
-                    .arg(#crate_ident::Arg::new("output").long("output").short('o').num_args(1))
-                    .arg(#crate_ident::Arg::new("format").long("format").num_args(1))
-                    .allow_external_subcommands(true)
-                    .try_get_matches_from(args)
-                    .map_err(|e| #crate_ident::QuantumConfigError::Internal(format!("Failed to parse CLI args: {}", e)))?;
+            /// 解析给定的命令行参数，`--help` 等由 clap 处理的错误转为加载错误
+            #[doc(hidden)]
+            fn __quantum_config_matches_from_args(args: Vec<String>) -> Result<#crate_ident::ArgMatches, #crate_ident::QuantumConfigError> {
+                #crate_ident::cli::matches_from_args::<Self>(Self::__quantum_config_command()?, args)
+            }
 
-                if let Some(cfg) = clap_matches.get_one::<String>("config") {
-                    let path = std::path::PathBuf::from(cfg);
-                    #crate_ident::add_specified_config_file(&mut config_file_paths, path)?;
+            /// 按 默认值 -> 文件 -> 环境变量 -> 命令行参数 的顺序合并各来源，同时返回各键的来源信息
+            #[doc(hidden)]
+            fn __quantum_config_figment_from_matches(
+                clap_matches: #crate_ident::ArgMatches,
+                load_options: #crate_ident::LoadOptions,
+            ) -> Result<(#crate_ident::Figment, #crate_ident::report::ConfigSources), #crate_ident::QuantumConfigError> {
+                let mut builder = #crate_ident::QuantumConfigBuilder::<Self>::from_app_meta(Self::__quantum_config_app_meta())
+                    .with_transforms(<Self as #crate_ident::Config>::transform_registry()?)
+                    .with_load_options(load_options)
+                    #defaults_expr
+                    // 工作目录中存在 .env 时按环境变量规则合并
+                    .with_dotenv(".env")
+                    #env_file_suffix_expr
+                    #profile_env_expr
+                    #env_scan_expr
+                    #env_naming_expr
+                    #priority_expr
+                    #secret_references_expr
+                    #config_d_expr
+                    #( .with_tenant_source(#tenant_sources) )*
+                    #includes_expr
+                    #encrypted_expr
+                    #migrate_expr
+                    // max_len 按字符计，环境变量长度按字节限制，取 UTF-8 最大字节数作为上限
+                    #( .with_env_key_max_value_len(#max_len_keys, #max_len_values.saturating_mul(4)) )*
+                    // 映射字段接受 JSON 对象字面量，例如 APP_LABELS='{"region":"eu"}'
+                    #( .with_env_key_json_values(#json_value_keys) )*;
+                for (key, separator) in <Self as #crate_ident::Config>::env_list_separators() {
+                    builder = builder.with_env_key_list_separator(key, separator);
+                }
+                // 密码等敏感字符串按原文读取，`007` 不会变成 `7`
+                for key in <Self as #crate_ident::Config>::raw_string_keys() {
+                    builder = builder.with_raw_string_key(key);
                 }
+                #crate_ident::cli::apply_matches(builder, clap_matches)?.figment_with_sources()
+            }
 
-                let mut fig = #crate_ident::Figment::new();
-                for cfg in config_file_paths {
-                    let provider = #crate_ident::providers::QuantumConfigFileProvider::from_path(&cfg.path, cfg.is_required, app_meta.max_parse_depth)?;
-                    fig = fig.merge(provider);
+            /// 从合并结果中提取并校验配置
+            #[doc(hidden)]
+            fn __quantum_config_extract(
+                figment: &mut #crate_ident::Figment,
+                sources: &#crate_ident::report::ConfigSources,
+            ) -> Result<Self, #crate_ident::QuantumConfigError> {
+                #unknown_keys_expr
+                // 值来自字段不允许的来源（例如密码出现在命令行参数中）时拒绝加载
+                #crate_ident::restrict::enforce::<Self>(sources)?;
+                #crate_ident::deprecated::warn_deprecated::<Self>(sources);
+                // 环境变量与命令行参数中的枚举变体名不区分大小写；
+                // 缺少字段时一次性报告全部缺失的键与查找过的来源
+                let config: Self = #crate_ident::enums::extract_coercing_variants(figment, sources)?;
+                #crate_ident::Config::register_secrets(&config);
+                // 校验错误附带出错值的来源（文件、环境变量或命令行参数）
+                #crate_ident::Config::validate(&config).map_err(|e| {
+                    let e = e.with_origin(figment);
+                    #crate_ident::observe::notify_validation_failed(&e);
+                    e
+                })?;
+                Ok(config)
+            }
+
+            /// 合并各来源并提取配置，同时返回各键的来源信息
+            #[doc(hidden)]
+            fn __quantum_config_load_from_matches(
+                clap_matches: #crate_ident::ArgMatches,
+                load_options: #crate_ident::LoadOptions,
+            ) -> Result<(Self, #crate_ident::report::ConfigSources), #crate_ident::QuantumConfigError> {
+                let last_known_good = load_options.last_known_good.clone();
+                let (mut figment, sources) = Self::__quantum_config_figment_from_matches(clap_matches, load_options)?;
+                let config = Self::__quantum_config_extract(&mut figment, &sources)?;
+                // 通过校验的配置写入快照，供之后来源不可用时回退
+                if let Some(path) = &last_known_good {
+                    #crate_ident::last_known_good::record(&config, path);
                 }
-                if let Some(prefix) = app_meta.env_prefix.clone() {
-                    let env_provider = #crate_ident::providers::QuantumConfigEnvProvider::with_prefix(prefix);
-                    fig = fig.merge(env_provider);
+                Ok((config, sources))
+            }
+
+            /// 加载配置并生成来源报告，报告中包含已弃用键的警告
+            #[doc(hidden)]
+            fn __quantum_config_load_with_report_from_matches(
+                clap_matches: #crate_ident::ArgMatches,
+            ) -> Result<(Self, #crate_ident::ConfigReport), #crate_ident::QuantumConfigError> {
+                let (config, sources) =
+                    Self::__quantum_config_load_from_matches(clap_matches, #crate_ident::LoadOptions::default())?;
+                let mut report = sources.report(&config)?;
+                report.deprecations =
+                    #crate_ident::deprecated::detect(&<Self as #crate_ident::Config>::deprecated_keys(), &sources);
+                Ok((config, report))
+            }
+
+            /// 没有任何来源提供值而缺少必需键时回退到 `Default`
+            #[doc(hidden)]
+            fn __quantum_config_load_or_default_from_matches(
+                clap_matches: #crate_ident::ArgMatches,
+            ) -> Result<Self, #crate_ident::QuantumConfigError> {
+                let (mut figment, sources) =
+                    Self::__quantum_config_figment_from_matches(clap_matches, #crate_ident::LoadOptions::default())?;
+                match Self::__quantum_config_extract(&mut figment, &sources) {
+                    Err(error) if error.is_missing() && !sources.has_values() => Ok(<Self as ::core::default::Default>::default()),
+                    result => result,
                 }
-                let clap_provider = #crate_ident::providers::clap_provider::with_common_mappings(clap_matches);
-                fig = fig.merge(clap_provider);
-                Ok(fig.extract()?)
             }
 
-            /// 创建新的配置实例（使用 Default），保持向后兼容
-            pub fn new() -> Self { Self::default() }
+            /// 尽力加载：可恢复的错误对应的键恢复为默认值并记为警告
+            #[doc(hidden)]
+            fn __quantum_config_load_lossy_from_matches(
+                clap_matches: #crate_ident::ArgMatches,
+            ) -> Result<(Self, Vec<#crate_ident::QuantumConfigError>), #crate_ident::QuantumConfigError> {
+                let (mut figment, sources) =
+                    Self::__quantum_config_figment_from_matches(clap_matches, #crate_ident::LoadOptions::default())?;
+                // 来源限制不属于可恢复的错误
+                #crate_ident::restrict::enforce::<Self>(&sources)?;
+                #crate_ident::deprecated::warn_deprecated::<Self>(&sources);
+                #crate_ident::lossy::extract_lossy::<Self>(&mut figment, &sources, #unknown_key_policy_expr)
+            }
+        }
+
+        impl #crate_ident::Config for #name {
+            fn load() -> Result<Self, #crate_ident::QuantumConfigError> {
+                let clap_matches = Self::__quantum_config_process_matches()?;
+                let (config, _) =
+                    Self::__quantum_config_load_from_matches(clap_matches.clone(), #crate_ident::LoadOptions::default())?;
+                // --print-config 输出生效配置后退出
+                #crate_ident::cli::handle_print_config(&clap_matches, &config)?;
+                Ok(config)
+            }
+
+            fn load_with_report() -> Result<(Self, #crate_ident::ConfigReport), #crate_ident::QuantumConfigError> {
+                let clap_matches = Self::__quantum_config_process_matches()?;
+                let (config, report) = Self::__quantum_config_load_with_report_from_matches(clap_matches.clone())?;
+                #crate_ident::cli::handle_print_config(&clap_matches, &config)?;
+                Ok((config, report))
+            }
+
+            fn load_with_report_with_args(
+                args: Vec<String>,
+            ) -> Result<(Self, #crate_ident::ConfigReport), #crate_ident::QuantumConfigError> {
+                Self::__quantum_config_load_with_report_from_matches(Self::__quantum_config_matches_from_args(args)?)
+            }
+
+            fn load_with_options(load_options: #crate_ident::LoadOptions) -> Result<Self, #crate_ident::QuantumConfigError> {
+                let clap_matches = Self::__quantum_config_process_matches()?;
+                let (config, _) = Self::__quantum_config_load_from_matches(clap_matches.clone(), load_options)?;
+                #crate_ident::cli::handle_print_config(&clap_matches, &config)?;
+                Ok(config)
+            }
+
+            #namespace_fn
+
+            fn extract_at(host: &#crate_ident::Figment, key: Option<&str>) -> Result<Self, #crate_ident::QuantumConfigError> {
+                let (mut figment, sources) = #crate_ident::namespace::nested_figment::<Self>(host, key, #nested_defaults_expr)?;
+                Self::__quantum_config_extract(&mut figment, &sources)
+            }
+
+            fn load_figment() -> Result<(#crate_ident::Figment, #crate_ident::report::ConfigSources), #crate_ident::QuantumConfigError> {
+                let clap_matches = Self::__quantum_config_process_matches()?;
+                Self::__quantum_config_figment_from_matches(clap_matches, #crate_ident::LoadOptions::default())
+            }
 
-            /// 从指定文件加载配置（仅文件，不合并其他来源），保持向后兼容
-            pub fn load_from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
-                let path = path.as_ref();
-                let provider = #crate_ident::providers::QuantumConfigFileProvider::from_path(path, true, 128)?;
-                let config: Self = #crate_ident::Figment::new().merge(provider).extract()?;
+            fn load_with_matches(clap_matches: &#crate_ident::ArgMatches) -> Result<Self, #crate_ident::QuantumConfigError> {
+                #crate_ident::cli::handle_validate_config(clap_matches, || {
+                    Self::__quantum_config_load_with_report_from_matches(clap_matches.clone())
+                });
+                let (config, _) =
+                    Self::__quantum_config_load_from_matches(clap_matches.clone(), #crate_ident::LoadOptions::default())?;
+                #crate_ident::cli::handle_print_config(clap_matches, &config)?;
                 Ok(config)
             }
 
-            /// 生成配置模板文件
-            pub fn generate_template() -> Result<(), Box<dyn std::error::Error>> {
-                let template = Self::default();
-                // 通过 serde 序列化为 TOML（依赖主库中的 toml 依赖）
-                use #crate_ident::serde::Serialize;
-                let toml_content = #crate_ident::toml::to_string_pretty(&template)?;
-                std::fs::write("config.toml.example", toml_content)?;
-                println!("Configuration template generated: config.toml.example");
-                Ok(())
+            fn load_with_args(args: Vec<String>) -> Result<Self, #crate_ident::QuantumConfigError> {
+                let clap_matches = Self::__quantum_config_matches_from_args(args)?;
+                Self::__quantum_config_load_from_matches(clap_matches, #crate_ident::LoadOptions::default())
+                    .map(|(config, _)| config)
+            }
+
+            fn load_or_default() -> Result<Self, #crate_ident::QuantumConfigError> {
+                let clap_matches = Self::__quantum_config_process_matches()?;
+                let config = Self::__quantum_config_load_or_default_from_matches(clap_matches.clone())?;
+                #crate_ident::cli::handle_print_config(&clap_matches, &config)?;
+                Ok(config)
+            }
+
+            fn load_or_default_with_args(args: Vec<String>) -> Result<Self, #crate_ident::QuantumConfigError> {
+                Self::__quantum_config_load_or_default_from_matches(Self::__quantum_config_matches_from_args(args)?)
+            }
+
+            fn load_lossy() -> Result<(Self, Vec<#crate_ident::QuantumConfigError>), #crate_ident::QuantumConfigError> {
+                let clap_matches = Self::__quantum_config_process_matches()?;
+                Self::__quantum_config_load_lossy_from_matches(clap_matches)
+            }
+
+            fn load_lossy_with_args(
+                args: Vec<String>,
+            ) -> Result<(Self, Vec<#crate_ident::QuantumConfigError>), #crate_ident::QuantumConfigError> {
+                Self::__quantum_config_load_lossy_from_matches(Self::__quantum_config_matches_from_args(args)?)
+            }
+
+            fn explain_with_args(args: Vec<String>) -> Result<#crate_ident::Explanation, #crate_ident::QuantumConfigError> {
+                let clap_matches = Self::__quantum_config_matches_from_args(args)?;
+                let (config, sources) =
+                    Self::__quantum_config_load_from_matches(clap_matches, #crate_ident::LoadOptions::default())?;
+                let report = sources.report(&config)?;
+                #crate_ident::explain::explain(&config, &report, &<Self as #crate_ident::Config>::secret_keys())
+            }
+
+            fn transform_registry() -> Result<#crate_ident::transform::TransformRegistry, #crate_ident::QuantumConfigError> {
+                let mut registry = #crate_ident::transform::TransformRegistry::from_global();
+                #( registry.register_builtin(#transform_patterns, #transform_names)?; )*
+                Ok(registry)
+            }
+
+            fn struct_meta() -> #crate_ident::StructMeta {
+                let mut meta = #crate_ident::StructMeta::new(stringify!(#name), true);
+                #cli_nested_style_expr
+                #cli_mode_expr
+                #(
+                    let mut field = #crate_ident::FieldMeta::new(#meta_names, #meta_types);
+                    field.config_name_override = #meta_name_overrides;
+                    field.is_skipped = #meta_skipped;
+                    field.is_flatten = #meta_flatten;
+                    field.doc_comment = #meta_docs;
+                    field.description = #meta_descriptions;
+                    field.is_option = #meta_options;
+                    field.is_secret = #meta_secrets;
+                    field.is_positional = #meta_positionals;
+                    field.allowed_sources = #meta_allowed_sources;
+                    meta.add_field(field);
+                )*
+                meta
+            }
+
+            fn key_shape() -> Result<#crate_ident::serde_json::Value, #crate_ident::QuantumConfigError> {
+                let mut shape = <Self as #crate_ident::Config>::defaults_as_value()?;
+                #crate_ident::unknown::apply_field_meta(&mut shape, &<Self as #crate_ident::Config>::struct_meta());
+                #( #crate_ident::unknown::merge_nested_shape(&mut shape, #nested_prefixes, <#nested_types as #crate_ident::Config>::key_shape()?); )*
+                Ok(shape)
+            }
+
+            fn source_restrictions() -> Vec<(String, &'static [#crate_ident::SourceKind])> {
+                let mut restrictions = #crate_ident::restrict::field_restrictions(&<Self as #crate_ident::Config>::struct_meta());
+                #( restrictions.extend(#crate_ident::restrict::nested_restrictions::<#nested_types>(#nested_prefixes)); )*
+                restrictions
+            }
+
+            fn env_list_separators() -> Vec<(String, &'static str)> {
+                let mut separators = vec![ #( (#list_separator_keys.to_string(), #list_separators) ),* ];
+                #(
+                    separators.extend(<#nested_types as #crate_ident::Config>::env_list_separators().into_iter().map(
+                        |(key, separator)| (#crate_ident::meta::nested_key_path(#nested_prefixes, &key), separator),
+                    ));
+                )*
+                separators
+            }
+
+            fn raw_string_keys() -> Vec<String> {
+                let mut keys = vec![ #( #raw_string_keys.to_string() ),* ];
+                #(
+                    keys.extend(<#nested_types as #crate_ident::Config>::raw_string_keys().into_iter().map(
+                        |key| #crate_ident::meta::nested_key_path(#nested_prefixes, &key),
+                    ));
+                )*
+                keys
+            }
+
+            fn drift_severities() -> Vec<(&'static str, #crate_ident::drift::DriftSeverity)> {
+                vec![ #( (#severity_keys, #crate_ident::drift::DriftSeverity::#severity_variants) ),* ]
+            }
+
+            fn deprecated_keys() -> Vec<(&'static str, &'static str)> {
+                vec![ #( (#deprecated_keys, #deprecated_messages) ),* ]
+            }
+
+            fn register_secrets(&self) {
+                let mut secrets = #crate_ident::scrub::Secrets::new();
+                <Self as #crate_ident::Config>::collect_secrets(self, &mut secrets);
+                #crate_ident::scrub::replace_secrets(::std::any::type_name::<Self>(), secrets);
+            }
+
+            fn collect_secrets(&self, secrets: &mut #crate_ident::scrub::Secrets) {
+                #( secrets.add_value(&self.#secret_fields); )*
+                #( #crate_ident::redact::collect_secret_key(self, #nested_secret_keys, secrets); )*
+                #(
+                    if let ::core::option::Option::Some(nested) = #nested_refs {
+                        <#nested_types as #crate_ident::Config>::collect_secrets(nested, secrets);
+                    }
+                )*
+            }
+
+            fn secret_keys() -> Vec<String> {
+                let mut keys = vec![ #( #secret_key_paths.to_string() ),* ];
+                #(
+                    keys.extend(<#nested_types as #crate_ident::Config>::secret_keys().into_iter().map(
+                        |key| #crate_ident::meta::nested_key_path(#nested_prefixes, &key),
+                    ));
+                )*
+                keys
+            }
+
+            fn validate(&self) -> Result<(), #crate_ident::QuantumConfigError> {
+                // 收集全部错误，一次报告所有出错字段
+                #[allow(unused_mut)]
+                let mut errors: Vec<#crate_ident::QuantumConfigError> = Vec::new();
+                #( errors.extend(#crate_ident::validate::check_max_len(#max_len_keys, &self.#max_len_fields, #max_len_values, #max_len_secrets).err()); )*
+                #( errors.extend(#validator_calls.err()); )*
+                #(
+                    if let ::core::option::Option::Some(nested) = #nested_refs {
+                        errors.extend(
+                            <#nested_types as #crate_ident::Config>::validate(nested)
+                                .err()
+                                .map(|error| #crate_ident::validate::nested_error(#nested_prefixes, error)),
+                        );
+                    }
+                )*
+                #custom_validate_expr
+                #crate_ident::validate::collect_errors(errors)
+            }
+        }
+
+        impl #crate_ident::ConfigHealth for #name {
+            fn health_check_with(&self, reachability_timeout: Option<::std::time::Duration>) -> #crate_ident::HealthReport {
+                let mut report = #crate_ident::HealthReport::default();
+                #( #crate_ident::health::check_path(#health_path_keys, &self.#health_path_fields, #health_path_secrets, &mut report); )*
+                #( #crate_ident::health::check_url(#health_url_keys, &self.#health_url_fields, #health_url_secrets, &mut report); )*
+                if let Some(timeout) = reachability_timeout {
+                    #( #crate_ident::health::check_reachable(#health_endpoint_keys, &self.#health_endpoint_fields, timeout, #health_endpoint_secrets, &mut report); )*
+                    let _ = timeout;
+                }
+                #( #crate_ident::health::check_nested(#nested_prefixes, &self.#nested_fields, reachability_timeout, &mut report); )*
+                report
             }
         }
     };
 
     TokenStream::from(expanded)
-}
\ No newline at end of file
+}
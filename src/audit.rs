@@ -0,0 +1,130 @@
+//! 生效配置审计日志
+//!
+//! 合规要求记录服务运行时使用的配置时，在启动阶段调用 [`log_effective_config`]，
+//! 以一条 info 级别的日志输出 JSON 记录，列出每个非敏感键的最终值及其来源：
+//!
+//! ```ignore
+//! let (config, report) = AppConfig::load_with_report()?;
+//! quantum_config::audit::log_effective_config(&config, &report)?;
+//! ```
+//!
+//! ```json
+//! {"event":"effective_config","config":"AppConfig","keys":[{"key":"database.url","value":"pg://db","source":"config file /etc/app/config.toml"}]}
+//! ```
+//!
+//! 敏感键（[`Config::secret_keys`]）及其下的所有键不出现在记录中，连同来源一并省略。
+//! 日志经由 `tracing-support` 或 `log-facade` 输出，两者都未启用时不输出；
+//! 需要写入其它位置时使用 [`effective_config`] 取得记录自行处理。
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::logging::log_info;
+use crate::report::ConfigReport;
+use serde::Serialize;
+use serde_json::{json, Value as JsonValue};
+
+/// 审计记录的事件名
+pub const EVENT: &str = "effective_config";
+
+/// 以一条 JSON 日志记录输出生效配置
+///
+/// # Errors
+/// 配置无法序列化时返回错误
+pub fn log_effective_config<T: Config>(config: &T, report: &ConfigReport) -> Result<(), QuantumConfigError> {
+    let record = effective_config(config, report)?;
+    log_info!("{}", record);
+    Ok(())
+}
+
+/// 生效配置的审计记录
+///
+/// # Errors
+/// 配置无法序列化时返回错误
+pub fn effective_config<T: Config>(config: &T, report: &ConfigReport) -> Result<JsonValue, QuantumConfigError> {
+    let name = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+    record(config, name, report, &T::secret_keys())
+}
+
+fn record<T: Serialize>(
+    config: &T,
+    name: &str,
+    report: &ConfigReport,
+    secret_keys: &[String],
+) -> Result<JsonValue, QuantumConfigError> {
+    let value = serde_json::to_value(config)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize configuration for audit: {}", e)))?;
+    let mut keys = Vec::new();
+    if let JsonValue::Object(map) = &value {
+        for (key, child) in map {
+            collect(key.clone(), child, report, secret_keys, &mut keys);
+        }
+    }
+    Ok(json!({ "event": EVENT, "config": name, "keys": keys }))
+}
+
+/// 收集非敏感的叶子键
+fn collect(
+    path: String,
+    value: &JsonValue,
+    report: &ConfigReport,
+    secret_keys: &[String],
+    keys: &mut Vec<JsonValue>,
+) {
+    if secret_keys.contains(&path) {
+        return;
+    }
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                collect(format!("{}.{}", path, key), child, report, secret_keys, keys);
+            }
+        }
+        _ => {
+            let source = report.source(&path).map(ToString::to_string);
+            keys.push(json!({ "key": path, "value": value, "source": source }));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{KeySource, ValueSource};
+
+    #[test]
+    fn test_record_lists_non_secret_keys_with_sources() {
+        let config = json!({
+            "name": "svc",
+            "database": {"url": "pg://db", "password": "hunter2"},
+            "tls": {"key": "-----BEGIN", "cert": "-----BEGIN"}
+        });
+        let report = ConfigReport {
+            keys: vec![
+                KeySource {
+                    key: "database.url".to_string(),
+                    source: ValueSource::Env("APP_DATABASE__URL".to_string()),
+                },
+                KeySource {
+                    key: "name".to_string(),
+                    source: ValueSource::Default,
+                },
+            ],
+            ..ConfigReport::default()
+        };
+        let secrets = ["database.password".to_string(), "tls".to_string()];
+
+        let record = record(&config, "AppConfig", &report, &secrets).unwrap();
+        assert_eq!(
+            record,
+            json!({
+                "event": "effective_config",
+                "config": "AppConfig",
+                "keys": [
+                    {"key": "database.url", "value": "pg://db", "source": "environment variable APP_DATABASE__URL"},
+                    {"key": "name", "value": "svc", "source": "default"}
+                ]
+            })
+        );
+        assert!(!record.to_string().contains("hunter2"));
+    }
+}
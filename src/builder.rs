@@ -0,0 +1,1863 @@
+//! 编程式配置加载构建器
+//!
+//! 为无法使用 `#[derive(Config)]` 的场景（泛型、外部类型等）提供与派生宏一致的加载流程：
+//!
+//! ```ignore
+//! use quantum_config::QuantumConfigBuilder;
+//!
+//! let config: AppConfig = QuantumConfigBuilder::new("myapp")
+//!     .with_file("custom.toml")
+//!     .with_env_prefix("APP_")
+//!     .with_cli(matches)
+//!     .with_defaults(AppConfig::default())
+//!     .load()?;
+//! ```
+//!
+//! 合并顺序（低 -> 高优先级覆盖）：
+//! 1. `with_defaults` 提供的默认值
+//! 2. 系统级、用户级配置文件，以及 `with_file` / `with_config_source` 指定的本地文件
+//! 3. `with_config_source` 指定的远程来源
+//! 4. `with_dotenv` 指定的 `.env` 文件
+//! 5. 环境变量
+//! 6. 命令行参数
+//!
+//! 文件、远程来源、环境变量与命令行参数之间的顺序可以用 [`QuantumConfigBuilder::with_priority`] 调整。
+//!
+//! 存在远程来源时默认并行读取各来源，但始终按上述顺序合并，结果与顺序读取一致；
+//! [`QuantumConfigBuilder::load_with_report`] 额外返回每个来源的读取耗时，
+//! [`QuantumConfigBuilder::load_with_config_report`] 额外返回每个键的值来源。
+
+use crate::cache::CachedFile;
+use crate::compat;
+use crate::error::QuantumConfigError;
+use crate::logging::{log_info, log_warn, trace_debug, trace_span};
+use crate::meta::QuantumConfigAppMeta;
+use crate::options::LoadOptions;
+use crate::paths::{
+    add_specified_config_file, fragment_config_files, profile_config_files, resolve_config_files, tenant_config_file, tenant_env_prefix,
+    user_config_dir, validate_profile_name, validate_tenant_name, ConfigFilePath, PathStrategy,
+};
+use crate::providers::source::SourceProvider;
+use crate::migrate::Migrations;
+use crate::observe::{LoadObserver, Observers, OverrideTracker};
+use crate::providers::file_provider::FileFormat;
+use crate::providers::{
+    ConfigSource, DotenvProvider, EnvCase, EnvScanScope, EnvSource, QuantumConfigEnvProvider, QuantumConfigFileProvider,
+    SecretsProvider, StandardFileReader,
+};
+#[cfg(feature = "cli")]
+use crate::providers::{clap_provider, QuantumConfigClapProvider};
+use crate::resolution::{ResolutionContext, DEFAULT_MAX_SOURCES};
+#[cfg(feature = "async")]
+use crate::providers::file_provider::infer_format;
+#[cfg(feature = "async")]
+use crate::providers::file_reader::PrefetchedFileReader;
+#[cfg(feature = "async")]
+use crate::providers::{AsyncFileReader, QuantumConfigFileProviderGeneric};
+use crate::report::{ConfigReport, ConfigSources, LayerKind, LoadReport, SourceLoad};
+use crate::transform::TransformRegistry;
+#[cfg(feature = "cli")]
+use clap::ArgMatches;
+use figment::providers::Serialized;
+use figment::value::{Dict, Map};
+use figment::{Figment, Metadata, Profile, Provider};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// 构建器默认的最大解析深度，与派生宏保持一致
+const DEFAULT_MAX_PARSE_DEPTH: u32 = 128;
+
+/// 来源类别，供 [`QuantumConfigBuilder::with_priority`] 调整合并顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SourceKind {
+    /// 配置文件：系统级、用户级以及显式指定的本地文件
+    File,
+    /// `with_config_source` 指定的远程来源
+    Remote,
+    /// `.env` 文件与环境变量
+    Env,
+    /// 命令行参数
+    Cli,
+}
+
+impl SourceKind {
+    /// 默认的合并顺序（低 -> 高）
+    pub const DEFAULT_PRIORITY: [SourceKind; 4] = [SourceKind::File, SourceKind::Remote, SourceKind::Env, SourceKind::Cli];
+}
+
+/// 计算完整的合并顺序：未列出的类别按默认顺序排在前面，其后为列出的类别
+fn source_kind_order(priority: &[SourceKind]) -> Result<Vec<SourceKind>, QuantumConfigError> {
+    for (i, kind) in priority.iter().enumerate() {
+        if priority[..i].contains(kind) {
+            return Err(QuantumConfigError::ValidationError(format!(
+                "Source kind {:?} appears more than once in the priority order",
+                kind
+            )));
+        }
+    }
+    let mut order: Vec<SourceKind> =
+        SourceKind::DEFAULT_PRIORITY.into_iter().filter(|kind| !priority.contains(kind)).collect();
+    order.extend_from_slice(priority);
+    Ok(order)
+}
+
+/// 显式指定的配置来源
+enum SourceSpec {
+    /// 本地文件路径
+    Path(PathBuf),
+    /// 按协议分派的来源描述，参见 [`ConfigSource::parse`]
+    Spec(String),
+}
+
+/// 编程式配置加载构建器
+pub struct QuantumConfigBuilder<T> {
+    /// 应用元数据（应用名、环境变量前缀、解析深度）
+    app_meta: QuantumConfigAppMeta,
+    /// 是否查找系统级与用户级配置文件
+    default_paths: bool,
+    /// 显式指定的配置来源，按添加顺序合并
+    sources: Vec<SourceSpec>,
+    /// 租户专属的来源，`{tenant}` 替换为当前租户
+    tenant_sources: Vec<String>,
+    /// 环境变量文件间接引用后缀
+    env_file_suffix: Option<String>,
+    /// 环境变量值的最大长度，为 `None` 时使用默认限制
+    env_max_value_len: Option<usize>,
+    /// 按配置键覆盖的环境变量值最大长度
+    env_key_max_value_lens: Vec<(String, usize)>,
+    /// 环境变量列表分隔符
+    env_list_separator: Option<String>,
+    /// 按配置键覆盖的环境变量列表分隔符
+    env_key_list_separators: Vec<(String, String)>,
+    /// 是否把环境变量中的 JSON 字面量解析为结构化值
+    env_json_values: bool,
+    /// 按配置键启用的环境变量 JSON 字面量解析
+    env_json_keys: Vec<String>,
+    /// 环境变量与命令行参数中按原文作为字符串的配置键
+    raw_string_keys: Vec<String>,
+    /// 环境变量读取范围
+    env_scan_scope: EnvScanScope,
+    /// 环境变量嵌套键分隔符
+    env_separator: Option<String>,
+    /// 环境变量键名的大小写转换规则
+    env_case: Option<EnvCase>,
+    /// 用于匹配环境变量名的已知配置键
+    env_key_paths: Vec<String>,
+    /// 环境变量来源，为 `None` 时读取进程环境
+    env_source: Option<Arc<dyn EnvSource>>,
+    /// `.env` 文件路径，文件不存在时跳过
+    dotenv: Option<PathBuf>,
+    /// 是否解析 `secret_file:` 与 `${file:...}` 密钥引用
+    secret_references: bool,
+    /// 是否在每个配置文件之后合并其 `{stem}.d/` 片段目录
+    config_fragments: bool,
+    /// 是否展开配置文件顶层的 `include` 指令
+    config_includes: bool,
+    /// 是否将所有配置文件视为加密文件
+    encrypted_files: bool,
+    /// 解密配置文件使用的密钥，为 `None` 时从环境变量读取
+    #[cfg(feature = "encryption")]
+    encryption_key: Option<crate::providers::EncryptionKey>,
+    /// 解密行内加密值使用的主密钥查找链，为 `None` 时不解密
+    #[cfg(feature = "encryption")]
+    value_keys: Option<crate::providers::KeyChain>,
+    /// 是否并行读取来源，为 `None` 时仅在存在远程来源时并行
+    parallel: Option<bool>,
+    /// 加载选项
+    load_options: LoadOptions,
+    /// 允许加载的来源总数
+    max_sources: usize,
+    /// 命令行参数
+    #[cfg(feature = "cli")]
+    cli: Option<ArgMatches>,
+    /// 命令行参数 id 到配置键路径的额外映射
+    #[cfg(feature = "cli")]
+    cli_mappings: Vec<(String, String)>,
+    /// 读取命令行参数的子命令路径
+    #[cfg(feature = "cli")]
+    cli_subcommand: Vec<String>,
+    /// 是否只读取已映射的命令行参数
+    #[cfg(feature = "cli")]
+    cli_mapped_only: bool,
+    /// 由 `load_async` 异步读取的配置文件
+    #[cfg(feature = "async")]
+    async_files: Vec<(PathBuf, Arc<dyn AsyncFileReader>)>,
+    /// 异步读取完成的配置文件内容，作为必需的配置文件合并
+    #[cfg(feature = "async")]
+    prefetched_files: Vec<(PathBuf, Vec<u8>)>,
+    /// 显式指定的配置剖面
+    profile: Option<String>,
+    /// 读取当前配置剖面的环境变量名
+    profile_env: Option<String>,
+    /// 默认值
+    defaults: Option<T>,
+    /// 合并后应用的值转换规则，为 `None` 时使用全局规则
+    transforms: Option<TransformRegistry>,
+    /// 只对本构建器生效的观察者，与全局观察者一起通知
+    observers: Vec<Arc<dyn LoadObserver>>,
+    /// 合并前应用于文件与远程来源的版本迁移规则
+    migrations: Option<Migrations>,
+    /// 来源类别的合并顺序（低 -> 高），为 `None` 时使用默认顺序
+    priority: Option<Vec<SourceKind>>,
+}
+
+impl<T> QuantumConfigBuilder<T> {
+    /// 创建构建器，环境变量前缀默认为 `{APP_NAME}_`
+    pub fn new<S: Into<String>>(app_name: S) -> Self {
+        let app_name = app_name.into();
+        let env_prefix = Some(format!("{}_", app_name.to_uppercase()));
+        Self::from_app_meta(QuantumConfigAppMeta {
+            app_name,
+            env_prefix,
+            behavior_version: compat::DEFAULT_BEHAVIOR_VERSION,
+            max_parse_depth: DEFAULT_MAX_PARSE_DEPTH,
+            ..QuantumConfigAppMeta::default()
+        })
+    }
+
+    /// 基于已有的应用元数据创建构建器
+    pub fn from_app_meta(app_meta: QuantumConfigAppMeta) -> Self {
+        Self {
+            app_meta,
+            default_paths: true,
+            sources: Vec::new(),
+            tenant_sources: Vec::new(),
+            env_file_suffix: None,
+            env_max_value_len: None,
+            env_key_max_value_lens: Vec::new(),
+            env_list_separator: None,
+            env_key_list_separators: Vec::new(),
+            env_json_values: false,
+            env_json_keys: Vec::new(),
+            raw_string_keys: Vec::new(),
+            env_scan_scope: EnvScanScope::Full,
+            env_separator: None,
+            env_case: None,
+            env_key_paths: Vec::new(),
+            env_source: None,
+            dotenv: None,
+            secret_references: false,
+            config_fragments: false,
+            config_includes: false,
+            encrypted_files: false,
+            #[cfg(feature = "encryption")]
+            encryption_key: None,
+            #[cfg(feature = "encryption")]
+            value_keys: None,
+            parallel: None,
+            load_options: LoadOptions::default(),
+            max_sources: DEFAULT_MAX_SOURCES,
+            #[cfg(feature = "cli")]
+            cli: None,
+            #[cfg(feature = "cli")]
+            cli_mappings: Vec::new(),
+            #[cfg(feature = "cli")]
+            cli_subcommand: Vec::new(),
+            #[cfg(feature = "cli")]
+            cli_mapped_only: false,
+            #[cfg(feature = "async")]
+            async_files: Vec::new(),
+            #[cfg(feature = "async")]
+            prefetched_files: Vec::new(),
+            profile: None,
+            profile_env: None,
+            defaults: None,
+            transforms: None,
+            observers: Vec::new(),
+            migrations: None,
+            priority: None,
+        }
+    }
+
+    /// 添加必需的本地配置文件，优先级高于系统级与用户级文件
+    pub fn with_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(SourceSpec::Path(path.into()));
+        self
+    }
+
+    /// 添加按协议分派的配置来源（本地路径、`file://`、`https://`、`sftp://` 等）
+    ///
+    /// 来源在 `load` 时解析，解析失败时由 `load` 返回错误。
+    pub fn with_config_source<S: Into<String>>(mut self, spec: S) -> Self {
+        self.sources.push(SourceSpec::Spec(spec.into()));
+        self
+    }
+
+    /// 添加租户专属的配置来源，`spec` 中的 `{tenant}` 替换为当前租户
+    ///
+    /// 只在加载选项指定了租户时合并（参见 [`LoadOptions::with_tenant`]），位于其余显式来源之后，
+    /// 例如 `with_tenant_source("https://config.internal/{tenant}/app.toml")`。
+    pub fn with_tenant_source<S: Into<String>>(mut self, spec: S) -> Self {
+        self.tenant_sources.push(spec.into());
+        self
+    }
+
+    /// 设置是否查找系统级与用户级配置文件（默认启用）
+    pub fn with_default_paths(mut self, enabled: bool) -> Self {
+        self.default_paths = enabled;
+        self
+    }
+
+    /// 设置配置目录的查找策略（默认为 [`PathStrategy::Native`]）
+    pub fn with_path_strategy(mut self, strategy: PathStrategy) -> Self {
+        self.app_meta.path_strategy = strategy;
+        self
+    }
+
+    /// 设置平台目录使用的限定符（如 `com`）
+    pub fn with_qualifier<S: Into<String>>(mut self, qualifier: S) -> Self {
+        self.app_meta.qualifier = Some(qualifier.into());
+        self
+    }
+
+    /// 设置平台目录使用的组织名
+    pub fn with_organization<S: Into<String>>(mut self, organization: S) -> Self {
+        self.app_meta.organization = Some(organization.into());
+        self
+    }
+
+    /// 设置环境变量前缀
+    pub fn with_env_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.app_meta.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// 不读取环境变量
+    pub fn without_env(mut self) -> Self {
+        self.app_meta.env_prefix = None;
+        self
+    }
+
+    /// 启用环境变量文件间接引用，参见 [`QuantumConfigEnvProvider::with_file_suffix`]
+    pub fn with_env_file_suffix<S: Into<String>>(mut self, suffix: S) -> Self {
+        self.env_file_suffix = Some(suffix.into());
+        self
+    }
+
+    /// 设置环境变量值的最大长度，参见 [`QuantumConfigEnvProvider::with_max_value_len`]
+    pub fn with_env_max_value_len(mut self, max_value_len: usize) -> Self {
+        self.env_max_value_len = Some(max_value_len);
+        self
+    }
+
+    /// 为指定配置键单独设置环境变量值的最大长度，参见 [`QuantumConfigEnvProvider::with_key_max_value_len`]
+    pub fn with_env_key_max_value_len<S: Into<String>>(mut self, key_path: S, max_value_len: usize) -> Self {
+        self.env_key_max_value_lens.push((key_path.into(), max_value_len));
+        self
+    }
+
+    /// 启用环境变量列表解析，参见 [`QuantumConfigEnvProvider::with_list_separator`]
+    pub fn with_env_list_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.env_list_separator = Some(separator.into());
+        self
+    }
+
+    /// 为指定配置键设置环境变量列表分隔符，参见 [`QuantumConfigEnvProvider::with_key_list_separator`]
+    pub fn with_env_key_list_separator<K: Into<String>, S: Into<String>>(mut self, key_path: K, separator: S) -> Self {
+        self.env_key_list_separators.push((key_path.into(), separator.into()));
+        self
+    }
+
+    /// 设置是否把环境变量中的 JSON 字面量解析为结构化值，参见 [`QuantumConfigEnvProvider::with_json_values`]
+    pub fn with_env_json_values(mut self, enabled: bool) -> Self {
+        self.env_json_values = enabled;
+        self
+    }
+
+    /// 为指定配置键启用环境变量 JSON 字面量解析，参见 [`QuantumConfigEnvProvider::with_key_json_values`]
+    pub fn with_env_key_json_values<K: Into<String>>(mut self, key_path: K) -> Self {
+        self.env_json_keys.push(key_path.into());
+        self
+    }
+
+    /// 指定配置键在环境变量与命令行参数中的值按原文作为字符串，不推断为布尔值或数字，
+    /// 参见 [`QuantumConfigEnvProvider::with_key_raw_string`]
+    pub fn with_raw_string_key<K: Into<String>>(mut self, key_path: K) -> Self {
+        self.raw_string_keys.push(key_path.into());
+        self
+    }
+
+    /// 调整来源类别的合并顺序，按低 -> 高优先级列出
+    ///
+    /// 例如 `[SourceKind::Env, SourceKind::Cli, SourceKind::File]` 让配置文件覆盖环境变量与命令行参数。
+    /// 未列出的类别保持默认的相对顺序，优先级低于所有列出的类别；默认值始终是最低优先级，
+    /// 同一类别内的来源（例如多个配置文件）保持原有顺序。类别重复时 `load` 返回错误。
+    pub fn with_priority<I: IntoIterator<Item = SourceKind>>(mut self, order: I) -> Self {
+        self.priority = Some(order.into_iter().collect());
+        self
+    }
+
+    /// 设置环境变量读取范围，参见 [`EnvScanScope`]
+    pub fn with_env_scan_scope(mut self, scan_scope: EnvScanScope) -> Self {
+        self.env_scan_scope = scan_scope;
+        self
+    }
+
+    /// 设置环境变量嵌套键分隔符，参见 [`QuantumConfigEnvProvider::with_separator`]
+    pub fn with_env_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.env_separator = Some(separator.into());
+        self
+    }
+
+    /// 设置环境变量键名的大小写转换规则，参见 [`EnvCase`]
+    pub fn with_env_case(mut self, case: EnvCase) -> Self {
+        self.env_case = Some(case);
+        self
+    }
+
+    /// 提供用于匹配环境变量名的已知配置键，参见 [`QuantumConfigEnvProvider::with_key_paths`]
+    pub fn with_env_key_paths<I, S>(mut self, key_paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.env_key_paths.extend(key_paths.into_iter().map(Into::into));
+        self
+    }
+
+    /// 从给定来源而非进程环境读取环境变量（包括剖面环境变量），参见 [`EnvSource`]
+    pub fn with_env_source<E: EnvSource + 'static>(mut self, source: E) -> Self {
+        self.env_source = Some(Arc::new(source));
+        self
+    }
+
+    /// 设置是否解析文件、环境变量与命令行参数中的密钥文件引用，参见 [`SecretsProvider`]
+    pub fn with_secret_references(mut self, enabled: bool) -> Self {
+        self.secret_references = enabled;
+        self
+    }
+
+    /// 设置是否合并片段目录（默认关闭）
+    ///
+    /// 启用后每个配置文件（例如 `config.toml`）之后按文件名字典序合并同目录下 `config.d/` 中的
+    /// `*.toml`、`*.json`、`*.ini` 等受支持格式的文件（`*.yaml` / `*.yml` 需要启用 `yaml` 特性），再合并剖面文件。
+    /// 参见 [`fragment_config_files`]。
+    pub fn with_config_fragments(mut self, enabled: bool) -> Self {
+        self.config_fragments = enabled;
+        self
+    }
+
+    /// 设置是否展开配置文件顶层的 `include = ["extra/*.toml"]` 指令（默认关闭）
+    ///
+    /// 路径相对于包含它的文件，被包含的文件先于该文件合并，
+    /// 参见 [`crate::providers::QuantumConfigFileProviderGeneric::with_includes`]。
+    pub fn with_config_includes(mut self, enabled: bool) -> Self {
+        self.config_includes = enabled;
+        self
+    }
+
+    /// 设置是否将所有配置文件视为加密文件（默认只解密 `.enc` 结尾的文件）
+    ///
+    /// 解密需要启用 `encryption` 特性，参见 [`crate::providers::QuantumConfigFileProviderGeneric::with_encrypted`]。
+    pub fn with_encrypted_files(mut self, encrypted: bool) -> Self {
+        self.encrypted_files = encrypted;
+        self
+    }
+
+    /// 设置解密配置文件使用的密钥，未设置时从环境变量 `QUANTUM_CONFIG_KEY` 或 `QUANTUM_CONFIG_KEY_FILE` 读取
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption_key(mut self, key: crate::providers::EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// 解密文件、远程来源、环境变量与命令行参数中 `ENC[AES256_GCM,data:...,iv:...]` 形式的行内加密值
+    ///
+    /// 主密钥由 `keys` 在遇到第一个加密值时查找，参见 [`crate::providers::EncryptedValuesProvider`]。
+    #[cfg(feature = "encryption")]
+    pub fn with_encrypted_values(mut self, keys: crate::providers::KeyChain) -> Self {
+        self.value_keys = Some(keys);
+        self
+    }
+
+    /// 设置是否并行读取各来源（默认仅在存在远程来源时并行），合并顺序不受影响
+    pub fn with_parallel_loading(mut self, enabled: bool) -> Self {
+        self.parallel = Some(enabled);
+        self
+    }
+
+    /// 设置加载选项，参见 [`LoadOptions`]
+    pub fn with_load_options(mut self, load_options: LoadOptions) -> Self {
+        self.load_options = load_options;
+        self
+    }
+
+    /// 设置允许加载的来源总数（每个文件、远程来源、`.env` 文件、环境变量与命令行参数各计一个），
+    /// 默认为 [`DEFAULT_MAX_SOURCES`]
+    pub fn with_max_sources(mut self, max_sources: usize) -> Self {
+        self.max_sources = max_sources;
+        self
+    }
+
+    /// 合并 `.env` 文件中的变量，规则与环境变量相同，优先级低于进程环境变量
+    ///
+    /// 文件不存在时跳过；`without_env` 时同样不读取。
+    pub fn with_dotenv<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.dotenv = Some(path.into());
+        self
+    }
+
+    /// 合并命令行参数（使用 [`clap_provider::with_common_mappings`] 的参数映射）
+    #[cfg(feature = "cli")]
+    pub fn with_cli(mut self, matches: ArgMatches) -> Self {
+        self.cli = Some(matches);
+        self
+    }
+
+    /// 将命令行参数 `arg` 的值写入配置键路径 `key`（以 `.` 分隔），例如 `server-port` -> `server.port`
+    ///
+    /// 派生宏为字段生成的参数通过 [`crate::cli::cli_mappings`] 注册。
+    #[cfg(feature = "cli")]
+    pub fn with_cli_mapping<A: Into<String>, K: Into<String>>(mut self, arg: A, key: K) -> Self {
+        self.cli_mappings.push((arg.into(), key.into()));
+        self
+    }
+
+    /// 只读取指定子命令的命令行参数，例如 `myapp serve --port 9090` 中的 `serve`；多次调用时逐级进入嵌套子命令
+    ///
+    /// 命令行中实际运行的不是该子命令时不合并任何命令行参数，参见 [`QuantumConfigClapProvider::with_subcommand`]。
+    #[cfg(feature = "cli")]
+    pub fn with_cli_subcommand<S: Into<String>>(mut self, name: S) -> Self {
+        self.cli_subcommand.push(name.into());
+        self
+    }
+
+    /// 只合并已映射的命令行参数（内置参数与 [`QuantumConfigBuilder::with_cli_mapping`] 注册的参数），
+    /// 忽略应用自己定义的其它参数，参见 [`QuantumConfigClapProvider::with_mapped_args_only`]
+    #[cfg(feature = "cli")]
+    pub fn with_cli_mapped_args_only(mut self) -> Self {
+        self.cli_mapped_only = true;
+        self
+    }
+
+    /// 添加由异步读取器读取的必需配置文件，例如 HTTP 或对象存储中的文件，格式按扩展名推断
+    ///
+    /// 这些文件只能通过 [`QuantumConfigBuilder::load_async`] 加载：读取在异步运行时中并发进行，
+    /// 不占用阻塞线程；结果在其他本地文件之后合并。同步加载时返回错误。
+    #[cfg(feature = "async")]
+    pub fn with_async_file<P: Into<PathBuf>, R: AsyncFileReader + 'static>(mut self, path: P, reader: R) -> Self {
+        self.async_files.push((path.into(), Arc::new(reader)));
+        self
+    }
+
+    /// 启用配置剖面并指定当前剖面，例如 `production`，优先于 [`QuantumConfigBuilder::with_profile_env`]
+    ///
+    /// 启用剖面后每个配置文件（例如 `config.toml`）之后依次合并同目录下存在的 `config.production.toml`
+    /// 与本地覆盖文件 `config.local.toml`，合并后的 Figment 选中该剖面（[`Figment::profile`]）。
+    pub fn with_profile<S: Into<String>>(mut self, profile: S) -> Self {
+        self.profile = Some(profile.into());
+        self
+    }
+
+    /// 启用配置剖面，当前剖面取自环境变量 `var`（例如 `APP_ENV=production`）
+    ///
+    /// 环境变量未设置或为空时只合并本地覆盖文件 `config.local.toml`。
+    pub fn with_profile_env<S: Into<String>>(mut self, var: S) -> Self {
+        self.profile_env = Some(var.into());
+        self
+    }
+
+    /// 当前配置剖面：[`QuantumConfigBuilder::with_profile`] 指定的剖面，或剖面环境变量的值
+    pub fn active_profile(&self) -> Result<Option<String>, QuantumConfigError> {
+        let profile = self.profile.clone().or_else(|| {
+            self.profile_env
+                .as_ref()
+                .and_then(|var| match &self.env_source {
+                    Some(source) => source.var(var),
+                    None => crate::context::env_source().var(var),
+                })
+                .filter(|value| !value.is_empty())
+        });
+        if let Some(profile) = &profile {
+            validate_profile_name(profile)?;
+        }
+        Ok(profile)
+    }
+
+    /// 设置默认值，优先级最低
+    pub fn with_defaults(mut self, defaults: T) -> Self {
+        self.defaults = Some(defaults);
+        self
+    }
+
+    /// 设置配置文件解析深度限制
+    pub fn with_max_parse_depth(mut self, max_parse_depth: u32) -> Self {
+        self.app_meta.max_parse_depth = max_parse_depth;
+        self
+    }
+
+    /// 设置合并后应用的值转换规则，替代默认的全局规则
+    pub fn with_transforms(mut self, transforms: TransformRegistry) -> Self {
+        self.transforms = Some(transforms);
+        self
+    }
+
+    /// 添加观察者，与 [`crate::observe::register`] 注册的全局观察者一起接收加载过程的回调
+    pub fn with_observer<O: LoadObserver + 'static>(mut self, observer: O) -> Self {
+        self.observers.push(Arc::new(observer));
+        self
+    }
+
+    /// 设置版本迁移规则：文件与远程来源在合并前从声明的版本迁移到最新版本，参见 [`crate::migrate`]
+    pub fn with_migrations(mut self, migrations: Migrations) -> Self {
+        self.migrations = Some(migrations);
+        self
+    }
+
+    /// 构建器使用的应用元数据
+    pub fn app_meta(&self) -> &QuantumConfigAppMeta {
+        &self.app_meta
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> QuantumConfigBuilder<T> {
+    /// 按优先级合并所有来源并应用值转换，返回合并后的 Figment
+    pub fn figment(self) -> Result<Figment, QuantumConfigError> {
+        self.merge(false).map(|(figment, _, _)| figment)
+    }
+
+    /// 按优先级合并所有来源并应用值转换，同时返回各来源的加载报告
+    pub fn figment_with_report(self) -> Result<(Figment, LoadReport), QuantumConfigError> {
+        self.merge(false).map(|(figment, report, _)| (figment, report))
+    }
+
+    /// 按优先级合并所有来源并应用值转换，同时返回用于生成 [`ConfigReport`] 的来源信息
+    pub fn figment_with_sources(self) -> Result<(Figment, ConfigSources), QuantumConfigError> {
+        self.merge(true).map(|(figment, _, sources)| (figment, sources))
+    }
+
+    /// 合并所有来源，返回合并结果、加载报告与来源信息
+    ///
+    /// `track_sources` 为 `false` 时来源信息为空，省去复制整个合并结果。
+    fn merge(self, track_sources: bool) -> Result<(Figment, LoadReport, ConfigSources), QuantumConfigError> {
+        #[cfg(feature = "async")]
+        if !self.async_files.is_empty() {
+            return Err(QuantumConfigError::Internal(
+                "Async file sources can only be loaded with load_async".to_string(),
+            ));
+        }
+        let profiles_enabled = self.profile.is_some() || self.profile_env.is_some();
+        let profile = self.active_profile()?;
+        let tenant = self.load_options.tenant.clone();
+        if let Some(tenant) = &tenant {
+            validate_tenant_name(tenant)?;
+        }
+        let app_meta = self.app_meta;
+        let _span = trace_span!("quantum_config.load", app = %app_meta.app_name, profile = ?profile);
+        compat::check_behavior_version(app_meta.behavior_version)?;
+
+        // 解析候选配置文件路径（宽容处理目录缺失场景）
+        let mut config_file_paths = if self.default_paths && !crate::testing::is_isolated() {
+            match resolve_config_files(&app_meta) {
+                Ok(v) => v,
+                Err(QuantumConfigError::NoConfigFilesFoundInDir { .. })
+                | Err(QuantumConfigError::ConfigDirNotFound { .. }) => Vec::new(),
+                Err(e) => return Err(e),
+            }
+        } else {
+            Vec::new()
+        };
+
+        // 显式来源按协议分派：本地路径并入文件列表，其余来源在文件之后合并
+        let wait_deadline = self
+            .load_options
+            .wait_for_files
+            .map(|wait| (Instant::now() + wait.timeout, wait.poll_interval));
+        let mut remote_sources = Vec::new();
+        // 启用快照回退时，缺失的必需文件推迟到确认快照可用之后再报错
+        let mut missing_file = None;
+        let tenant_sources = tenant.iter().flat_map(|tenant| {
+            self.tenant_sources
+                .iter()
+                .map(move |spec| SourceSpec::Spec(spec.replace("{tenant}", tenant)))
+        });
+        for spec in self.sources.into_iter().chain(tenant_sources) {
+            let source = match spec {
+                SourceSpec::Path(path) => ConfigSource::File(path),
+                SourceSpec::Spec(spec) => ConfigSource::parse(&spec)?,
+            };
+            match source {
+                ConfigSource::File(path) => match add_required_file(&mut config_file_paths, path, wait_deadline) {
+                    Err(e @ QuantumConfigError::SpecifiedFileNotFound { .. })
+                        if self.load_options.last_known_good.is_some() =>
+                    {
+                        missing_file = Some(e)
+                    }
+                    result => result?,
+                },
+                source if self.load_options.cache => {
+                    remote_sources.push(source.into_cached_provider(app_meta.max_parse_depth)?)
+                }
+                source => remote_sources.push(source.into_lazy_provider(app_meta.max_parse_depth)?),
+            }
+        }
+        let parallel = self.parallel.unwrap_or(!remote_sources.is_empty());
+
+        // 按合并顺序收集各层来源，读取推迟到 fetch_layers
+        let wrapping = LayerWrapping {
+            secret_references: self.secret_references,
+            #[cfg(feature = "encryption")]
+            value_keys: self.value_keys,
+        };
+        // 被包含的文件与解密密钥不在文件指纹中，启用包含或加密时不缓存文件
+        let cache_files = self.load_options.cache && !self.config_includes && !self.encrypted_files;
+        let user_dir = user_config_dir(&app_meta);
+        let mut layers = Vec::new();
+        let mut kinds = Vec::new();
+        let mut groups = Vec::new();
+        for cfg in config_file_paths {
+            // 启用剖面时，剖面文件与本地覆盖文件紧随其基础文件合并
+            let profile_files = if profiles_enabled {
+                profile_config_files(&cfg, profile.as_deref())
+            } else {
+                Vec::new()
+            };
+            // 片段目录紧随基础文件，位于剖面文件之前
+            let fragment_files = if self.config_fragments {
+                fragment_config_files(&cfg)?
+            } else {
+                Vec::new()
+            };
+            // 租户文件在剖面文件之后合并
+            let tenant_file = tenant.as_deref().and_then(|tenant| tenant_config_file(&cfg, tenant));
+            for file in std::iter::once(cfg.clone()).chain(fragment_files).chain(profile_files).chain(tenant_file) {
+                let provider =
+                    QuantumConfigFileProvider::from_path(&file.path, file.is_required, app_meta.max_parse_depth)?
+                        .with_includes(self.config_includes)
+                        .with_encrypted(self.encrypted_files);
+                #[cfg(feature = "encryption")]
+                let provider = match &self.encryption_key {
+                    Some(key) => provider.with_encryption_key(key.clone()),
+                    None => provider,
+                };
+                layers.push(if cache_files {
+                    layer(CachedFile::new(provider, &file.path, app_meta.max_parse_depth), &wrapping)
+                } else {
+                    layer(provider, &wrapping)
+                });
+                groups.push(SourceKind::File);
+                // 显式指定的文件（及其片段与剖面文件）为配置文件，其余为按默认路径发现的系统级或用户级文件
+                kinds.push(if cfg.is_required {
+                    LayerKind::ConfigFile(file.path)
+                } else if user_dir.as_ref().is_some_and(|dir| file.path.starts_with(dir)) {
+                    LayerKind::UserFile(file.path)
+                } else {
+                    LayerKind::SystemFile(file.path)
+                });
+            }
+        }
+        #[cfg(feature = "async")]
+        for (path, bytes) in self.prefetched_files {
+            let format = infer_format(&path)?;
+            let reader = PrefetchedFileReader::new(bytes);
+            let provider =
+                QuantumConfigFileProviderGeneric::new(&path, format, true, app_meta.max_parse_depth, reader);
+            layers.push(layer(provider, &wrapping));
+            groups.push(SourceKind::File);
+            kinds.push(LayerKind::ConfigFile(path));
+        }
+        for remote in remote_sources {
+            layers.push(layer(remote, &wrapping));
+            groups.push(SourceKind::Remote);
+            kinds.push(LayerKind::Remote);
+        }
+        if let Some(prefix) = app_meta.env_prefix {
+            let mut env_provider = QuantumConfigEnvProvider::with_prefix(prefix.clone())
+                .with_scan_scope(self.env_scan_scope)
+                .with_key_paths(self.env_key_paths);
+            if let Some(source) = self.env_source {
+                env_provider = env_provider.with_env_source(source);
+            }
+            if let Some(separator) = self.env_separator {
+                env_provider = env_provider.with_separator(separator);
+            }
+            if let Some(case) = self.env_case {
+                env_provider = env_provider.with_case(case);
+            }
+            if let Some(suffix) = self.env_file_suffix {
+                env_provider = env_provider.with_file_suffix(suffix);
+            }
+            if let Some(max_value_len) = self.env_max_value_len {
+                env_provider = env_provider.with_max_value_len(max_value_len);
+            }
+            for (key_path, max_value_len) in self.env_key_max_value_lens {
+                env_provider = env_provider.with_key_max_value_len(key_path, max_value_len);
+            }
+            if let Some(separator) = self.env_list_separator {
+                env_provider = env_provider.with_list_separator(separator);
+            }
+            for (key_path, separator) in self.env_key_list_separators {
+                env_provider = env_provider.with_key_list_separator(key_path, separator);
+            }
+            env_provider = env_provider.with_json_values(self.env_json_values);
+            for key_path in self.env_json_keys {
+                env_provider = env_provider.with_key_json_values(key_path);
+            }
+            for key_path in &self.raw_string_keys {
+                env_provider = env_provider.with_key_raw_string(key_path.clone());
+            }
+            // 指定租户时，租户专属变量（例如 `APP__TENANT_A__PORT`）由租户层读取，基础层跳过整个租户命名空间；
+            // 未指定租户时 `APP__` 开头的变量照常由基础前缀读取
+            let tenant_namespace = format!("{}__", prefix.trim_end_matches('_'));
+            if tenant.is_some() && !prefix.is_empty() && tenant_namespace.len() > prefix.len() {
+                env_provider = env_provider.with_excluded_prefix(tenant_namespace);
+            }
+            if let Some(path) = self.dotenv.map(|path| crate::context::resolve_relative(&path)) {
+                if path.is_file() {
+                    let dotenv = DotenvProvider::new(&path, "").with_env_provider(env_provider.clone());
+                    layers.push(layer(dotenv, &wrapping));
+                    groups.push(SourceKind::Env);
+                    kinds.push(LayerKind::Dotenv(path));
+                } else {
+                    trace_debug!(path = %path.display(), "skipped missing .env file");
+                }
+            }
+            if let Some(tenant) = &tenant {
+                let tenant_prefix = tenant_env_prefix(&prefix, tenant);
+                layers.push(layer(env_provider.clone(), &wrapping));
+                groups.push(SourceKind::Env);
+                kinds.push(LayerKind::Env);
+                env_provider = env_provider.with_replaced_prefix(tenant_prefix);
+            }
+            layers.push(layer(env_provider, &wrapping));
+            groups.push(SourceKind::Env);
+            kinds.push(LayerKind::Env);
+        }
+        #[cfg(feature = "cli")]
+        if let Some(matches) = self.cli {
+            let provider = self
+                .cli_mappings
+                .into_iter()
+                .fold(clap_provider::with_common_mappings(matches), |provider, (arg, key)| provider.map_arg(arg, key));
+            let provider = self.cli_subcommand.into_iter().fold(provider, QuantumConfigClapProvider::with_subcommand);
+            let provider = if self.cli_mapped_only { provider.with_mapped_args_only() } else { provider };
+            let provider = self.raw_string_keys.into_iter().fold(provider, QuantumConfigClapProvider::with_raw_string_key);
+            layers.push(layer(provider, &wrapping));
+            groups.push(SourceKind::Cli);
+            kinds.push(LayerKind::Cli);
+        }
+
+        // 按来源类别的优先级重排，同一类别内保持原有顺序
+        if let Some(priority) = &self.priority {
+            let order = source_kind_order(priority)?;
+            let rank = |group: &SourceKind| order.iter().position(|kind| kind == group).unwrap_or(0);
+            let mut entries: Vec<_> = groups.into_iter().zip(layers).zip(kinds).collect();
+            entries.sort_by_key(|((group, _), _)| rank(group));
+            (layers, kinds) = entries.into_iter().map(|((_, layer), kind)| (layer, kind)).unzip();
+        }
+
+        // 所有来源经过同一解析上下文，超过来源总数上限时在读取任何来源之前失败
+        let mut context = ResolutionContext::new().with_max_sources(self.max_sources);
+        for layer in &layers {
+            let metadata = layer.metadata();
+            let source = metadata.source.map_or_else(|| metadata.name.to_string(), |source| source.to_string());
+            context.resolve(source, |_| Ok(()))?;
+        }
+
+        let mut observers = Observers::from_global();
+        for observer in self.observers {
+            observers.push(observer);
+        }
+
+        let started = Stopwatch::start();
+        let mut fetched = Vec::with_capacity(layers.len() + 1);
+        if let Some(defaults) = self.defaults {
+            fetched.push(FetchedLayer::fetch(&Serialized::defaults(defaults), &observers));
+            kinds.insert(0, LayerKind::Default);
+        }
+        fetched.extend(fetch_layers(layers, parallel, &observers));
+        if let Some(snapshot) = &self.load_options.last_known_good {
+            fall_back_to_last_known_good(
+                snapshot,
+                &mut fetched,
+                &mut kinds,
+                &mut missing_file,
+                app_meta.max_parse_depth,
+                &observers,
+            );
+        }
+        if let Some(e) = missing_file {
+            return Err(e);
+        }
+
+        let mut report = LoadReport {
+            sources: Vec::with_capacity(fetched.len()),
+            total: started.elapsed(),
+            parallel,
+        };
+        let mut fig = Figment::new();
+        let mut source_layers = Vec::with_capacity(fetched.len());
+        let mut overrides = OverrideTracker::default();
+        for (mut layer, kind) in fetched.into_iter().zip(kinds) {
+            if let Some(migrations) = &self.migrations {
+                layer.migrate(migrations, &kind)?;
+            }
+            trace_debug!(
+                source = %layer.metadata.name,
+                elapsed = ?layer.elapsed,
+                succeeded = layer.data.is_ok(),
+                keys = layer.data.as_ref().map_or(0, |data| data.values().map(Dict::len).sum::<usize>()),
+                "fetched configuration source"
+            );
+            if let (false, Ok(data)) = (observers.is_empty(), &layer.data) {
+                for dict in data.values() {
+                    overrides.record(&observers, &layer.metadata.name, dict);
+                }
+            }
+            report.sources.push(SourceLoad {
+                name: layer.metadata.name.to_string(),
+                elapsed: layer.elapsed,
+                succeeded: layer.data.is_ok(),
+            });
+            source_layers.push((layer.metadata.clone(), kind));
+            fig = fig.merge(layer.into_merged());
+        }
+
+        if let Some(profile) = profile {
+            fig = fig.select(profile);
+        }
+
+        // 来源基于值转换之前的合并结果，转换只改变值而不改变来源
+        let sources = if track_sources {
+            ConfigSources::new(fig.clone(), source_layers)
+        } else {
+            ConfigSources::new(Figment::new(), Vec::new())
+        };
+        let transforms = self.transforms.unwrap_or_else(TransformRegistry::from_global);
+        trace_debug!(
+            sources = report.sources.len(),
+            parallel = report.parallel,
+            elapsed = ?report.total,
+            "merged configuration sources"
+        );
+        Ok((transforms.apply_to_figment(fig)?, report, sources))
+    }
+
+    /// 合并所有来源并提取配置
+    pub fn load(self) -> Result<T, QuantumConfigError> {
+        Ok(self.figment()?.extract()?)
+    }
+
+    /// 合并所有来源并提取配置，同时返回各来源的加载报告
+    pub fn load_with_report(self) -> Result<(T, LoadReport), QuantumConfigError> {
+        let (figment, report) = self.figment_with_report()?;
+        Ok((figment.extract()?, report))
+    }
+
+    /// 合并所有来源并提取配置，同时返回记录每个键来源的报告
+    pub fn load_with_config_report(self) -> Result<(T, ConfigReport), QuantumConfigError> {
+        let (figment, sources) = self.figment_with_sources()?;
+        let config = figment.extract()?;
+        let report = sources.report(&config)?;
+        Ok((config, report))
+    }
+
+    /// 异步加载配置，避免阻塞异步运行时
+    ///
+    /// [`QuantumConfigBuilder::with_async_file`] 添加的文件先在运行时中并发读取，其余来源在 tokio 阻塞线程池中读取并合并。
+    #[cfg(feature = "async")]
+    pub async fn load_async(mut self) -> Result<(T, LoadReport), QuantumConfigError>
+    where
+        T: Send + 'static,
+    {
+        let reads: Vec<_> = std::mem::take(&mut self.async_files)
+            .into_iter()
+            .map(|(path, reader)| {
+                tokio::spawn(async move {
+                    let bytes = reader.read_bytes_async(&path).await;
+                    (path, bytes)
+                })
+            })
+            .collect();
+        for read in reads {
+            let (path, bytes) = read
+                .await
+                .map_err(|e| QuantumConfigError::Internal(format!("File reading task failed: {}", e)))?;
+            self.prefetched_files.push((path, bytes?));
+        }
+
+        tokio::task::spawn_blocking(move || self.load_with_report())
+            .await
+            .map_err(|e| QuantumConfigError::Internal(format!("Configuration loading task failed: {}", e)))?
+    }
+}
+
+/// 加载计时器
+///
+/// `wasm32-unknown-unknown` 上没有可用的单调时钟（`Instant::now` 会 panic），计时结果记为零。
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch(Option<Instant>);
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        return Self(None);
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        Self(Some(Instant::now()))
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.0.map_or(Duration::ZERO, |started| started.elapsed())
+    }
+}
+
+/// 添加必需的本地文件，设置了等待策略时轮询直到文件出现或超时
+fn add_required_file(
+    config_files: &mut Vec<ConfigFilePath>,
+    path: PathBuf,
+    wait_deadline: Option<(Instant, Duration)>,
+) -> Result<(), QuantumConfigError> {
+    let path = crate::context::resolve_relative(&path);
+    let Some((deadline, poll_interval)) = wait_deadline else {
+        return add_specified_config_file(config_files, path);
+    };
+    let started = Instant::now();
+    loop {
+        match add_specified_config_file(config_files, path.clone()) {
+            Err(QuantumConfigError::SpecifiedFileNotFound { .. }) if Instant::now() < deadline => {
+                log_info!(
+                    "Waiting for configuration file {} ({:?} elapsed)",
+                    path.display(),
+                    started.elapsed()
+                );
+                std::thread::sleep(poll_interval.min(deadline.saturating_duration_since(Instant::now())));
+            }
+            Err(e @ QuantumConfigError::SpecifiedFileNotFound { .. }) => {
+                log_warn!(
+                    "Configuration file {} did not appear within {:?}",
+                    path.display(),
+                    started.elapsed()
+                );
+                return Err(e);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// 文件或远程来源不可用时以最后一次成功加载的快照代替，参见 [`crate::last_known_good`]
+///
+/// 只有无法读取或请求失败的来源才会回退，内容无法解析的来源保留原始错误。失败的来源改为不提供任何值，快照插入到默认值之后；快照无法读取时保持原样，由调用方返回原始错误。
+fn fall_back_to_last_known_good(
+    snapshot: &Path,
+    fetched: &mut Vec<FetchedLayer>,
+    kinds: &mut Vec<LayerKind>,
+    missing_file: &mut Option<QuantumConfigError>,
+    max_parse_depth: u32,
+    observers: &Observers,
+) {
+    let failed: Vec<usize> = fetched
+        .iter()
+        .zip(kinds.iter())
+        .enumerate()
+        .filter(|(_, (layer, kind))| {
+            layer.data.as_ref().is_err_and(crate::error::is_unavailable_error)
+                && matches!(
+                    kind,
+                    LayerKind::SystemFile(_) | LayerKind::UserFile(_) | LayerKind::ConfigFile(_) | LayerKind::Remote
+                )
+        })
+        .map(|(index, _)| index)
+        .collect();
+    if failed.is_empty() && missing_file.is_none() {
+        return;
+    }
+    let provider =
+        QuantumConfigFileProvider::new(snapshot, FileFormat::Json, true, max_parse_depth, StandardFileReader::new());
+    let snapshot_layer = FetchedLayer::fetch(&provider, observers);
+    if snapshot_layer.data.is_err() {
+        return;
+    }
+
+    if let Some(e) = missing_file.take() {
+        log_warn!(
+            "{}; falling back to the last known good configuration {}",
+            e,
+            snapshot.display()
+        );
+    }
+    for index in failed {
+        let layer = &mut fetched[index];
+        if let Err(e) = std::mem::replace(&mut layer.data, Ok(Map::new())) {
+            log_warn!(
+                "Configuration source {} is unavailable, falling back to the last known good configuration {}: {}",
+                layer.metadata.name,
+                snapshot.display(),
+                e
+            );
+        }
+    }
+    let position = kinds.iter().take_while(|kind| matches!(kind, LayerKind::Default)).count();
+    fetched.insert(position, snapshot_layer);
+    kinds.insert(position, LayerKind::ConfigFile(snapshot.to_path_buf()));
+}
+
+/// 每一层来源共用的值解析设置
+struct LayerWrapping {
+    /// 是否解析密钥文件引用
+    secret_references: bool,
+    /// 解密行内加密值使用的主密钥查找链
+    #[cfg(feature = "encryption")]
+    value_keys: Option<crate::providers::KeyChain>,
+}
+
+/// 包装为延迟读取的来源，按设置先解密行内加密值，再用 [`SecretsProvider`] 解析密钥引用
+fn layer<P: Provider + Send + Sync + 'static>(provider: P, wrapping: &LayerWrapping) -> SourceProvider {
+    #[cfg(feature = "encryption")]
+    if let Some(keys) = &wrapping.value_keys {
+        let provider = crate::providers::EncryptedValuesProvider::with_key_chain(provider, keys.clone());
+        return secret_layer(provider, wrapping.secret_references);
+    }
+    secret_layer(provider, wrapping.secret_references)
+}
+
+/// 包装为延迟读取的来源，启用密钥引用时先用 [`SecretsProvider`] 包装
+fn secret_layer<P: Provider + Send + Sync + 'static>(provider: P, secret_references: bool) -> SourceProvider {
+    if secret_references {
+        SourceProvider::new(SecretsProvider::new(provider))
+    } else {
+        SourceProvider::new(provider)
+    }
+}
+
+/// 读取各层来源，并行时每个来源使用独立线程，结果保持原有顺序
+fn fetch_layers(layers: Vec<SourceProvider>, parallel: bool, observers: &Observers) -> Vec<FetchedLayer> {
+    if !parallel || layers.len() < 2 {
+        return layers.iter().map(|provider| FetchedLayer::fetch(provider, observers)).collect();
+    }
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = layers
+            .iter()
+            .map(|provider| scope.spawn(move || FetchedLayer::fetch(provider, observers)))
+            .collect();
+        handles
+            .into_iter()
+            .zip(&layers)
+            .map(|(handle, provider)| {
+                handle.join().unwrap_or_else(|_| FetchedLayer {
+                    metadata: provider.metadata(),
+                    profile: None,
+                    data: Err(figment::Error::from("configuration source panicked while loading".to_string())),
+                    elapsed: Duration::ZERO,
+                })
+            })
+            .collect()
+    })
+}
+
+/// 已读取的来源，保留原始来源信息，合并前可以迁移其中的值
+struct FetchedLayer {
+    metadata: Metadata,
+    profile: Option<Profile>,
+    data: Result<Map<Profile, Dict>, figment::Error>,
+    elapsed: Duration,
+}
+
+impl FetchedLayer {
+    /// 读取来源并计时，前后通知观察者
+    fn fetch<P: Provider>(provider: &P, observers: &Observers) -> Self {
+        let metadata = provider.metadata();
+        observers.provider_started(&metadata.name);
+        let started = Stopwatch::start();
+        let data = provider.data();
+        let elapsed = started.elapsed();
+        observers.provider_finished(&metadata.name, elapsed, data.is_ok());
+        Self {
+            metadata,
+            profile: provider.profile(),
+            data,
+            elapsed,
+        }
+    }
+}
+
+impl FetchedLayer {
+    /// 将文件与远程来源的值迁移到最新版本
+    fn migrate(&mut self, migrations: &Migrations, kind: &LayerKind) -> Result<(), QuantumConfigError> {
+        let source = match kind {
+            LayerKind::SystemFile(path) | LayerKind::UserFile(path) | LayerKind::ConfigFile(path) => {
+                path.display().to_string()
+            }
+            LayerKind::Remote => self.metadata.name.to_string(),
+            _ => return Ok(()),
+        };
+        if let Ok(data) = &mut self.data {
+            for dict in data.values_mut() {
+                migrations.apply(dict, &source)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FetchedLayer {
+    /// 转换为合并用的来源
+    fn into_merged(self) -> MergedLayer {
+        MergedLayer {
+            metadata: self.metadata,
+            profile: self.profile,
+            data: Cell::new(Some(self.data)),
+        }
+    }
+}
+
+/// 合并时把数据移交给 Figment 的来源，避免为每一层复制整份数据
+///
+/// Figment 合并每个来源时只读取一次数据，之后再读取得到空数据。
+struct MergedLayer {
+    metadata: Metadata,
+    profile: Option<Profile>,
+    data: Cell<Option<Result<Map<Profile, Dict>, figment::Error>>>,
+}
+
+impl Provider for MergedLayer {
+    fn metadata(&self) -> Metadata {
+        self.metadata.clone()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
+        match self.data.take() {
+            Some(data) => data,
+            None => Ok(Map::new()),
+        }
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.profile.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ValueSource;
+    #[cfg(feature = "cli")]
+    use clap::{Arg, Command};
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct ServerConfig {
+        host: String,
+        port: u16,
+        log_level: String,
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_load_merges_sources_in_priority_order() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        fs::write(&path, "host = \"file-host\"\nport = 8080\n").unwrap();
+
+        let matches = Command::new("app")
+            .arg(Arg::new("log-level").long("log-level").num_args(1))
+            .get_matches_from(["app", "--log-level", "debug"]);
+
+        let result = QuantumConfigBuilder::new("qc-builder-test")
+            .with_default_paths(false)
+            .with_defaults(ServerConfig {
+                host: "default-host".to_string(),
+                port: 80,
+                log_level: "info".to_string(),
+            })
+            .with_file(&path)
+            .with_env_prefix("QC_BUILDER_")
+            .with_env_source(crate::providers::MapEnv::new().with_var("QC_BUILDER_PORT", "9090"))
+            .with_cli(matches)
+            .load();
+
+        assert_eq!(
+            result.unwrap(),
+            ServerConfig {
+                host: "file-host".to_string(),
+                port: 9090,
+                log_level: "debug".to_string(),
+            }
+        );
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_priority_lets_files_override_env_and_cli() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        fs::write(&path, "host = \"file-host\"\nport = 8080\n").unwrap();
+
+        let builder = |priority: Vec<SourceKind>| {
+            let matches = Command::new("app")
+                .arg(Arg::new("host").long("host").num_args(1))
+                .get_matches_from(["app", "--host", "cli-host"]);
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .with_defaults(ServerConfig::default())
+                .with_file(&path)
+                .with_env_prefix("QC_PRIORITY_")
+                .with_env_source(crate::providers::MapEnv::new().with_var("QC_PRIORITY_PORT", "9090"))
+                .with_cli(matches)
+                .with_priority(priority)
+        };
+        let inverted = builder(vec![SourceKind::Env, SourceKind::Cli, SourceKind::File]).load();
+        // 未列出的类别排在列出的类别之前
+        let partial = builder(vec![SourceKind::File]).load();
+        let duplicated = builder(vec![SourceKind::File, SourceKind::File]).load();
+
+        let inverted = inverted.unwrap();
+        assert_eq!((inverted.host.as_str(), inverted.port), ("file-host", 8080));
+        let partial = partial.unwrap();
+        assert_eq!((partial.host.as_str(), partial.port), ("file-host", 8080));
+        assert!(matches!(duplicated, Err(QuantumConfigError::ValidationError(_))));
+        assert_eq!(
+            source_kind_order(&[SourceKind::Cli]).unwrap(),
+            vec![SourceKind::File, SourceKind::Remote, SourceKind::Env, SourceKind::Cli]
+        );
+    }
+
+    #[test]
+    fn test_load_reports_missing_and_unsupported_sources() {
+        let result: Result<ServerConfig, _> = QuantumConfigBuilder::new("qc-builder-test")
+            .with_default_paths(false)
+            .with_file("/nonexistent/custom.toml")
+            .load();
+        assert!(matches!(result, Err(QuantumConfigError::SpecifiedFileNotFound { .. })));
+
+        let result: Result<ServerConfig, _> = QuantumConfigBuilder::new("qc-builder-test")
+            .with_default_paths(false)
+            .with_config_source("ftp://example.com/config.toml")
+            .load();
+        assert!(matches!(result, Err(QuantumConfigError::UnsupportedScheme { .. })));
+    }
+
+    #[test]
+    fn test_profile_files_layer_over_base_file() {
+        let dir = tempdir().unwrap();
+        let base = dir.path().join("config.toml");
+        fs::write(&base, "host = \"base\"\nport = 80\nlog_level = \"info\"\n").unwrap();
+        fs::write(dir.path().join("config.production.toml"), "host = \"prod\"\nport = 443\n").unwrap();
+        fs::write(dir.path().join("config.local.toml"), "port = 8443\n").unwrap();
+
+        let builder = || {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .with_env_prefix("QC_PROFILE_")
+                .with_file(&base)
+        };
+        // 未启用剖面时不读取剖面文件
+        assert_eq!(builder().load().unwrap().port, 80);
+
+        let from_env = builder()
+            .with_env_source(crate::providers::MapEnv::new().with_var("QC_PROFILE_TEST_ENV", "production"))
+            .with_profile_env("QC_PROFILE_TEST_ENV");
+        assert_eq!(from_env.active_profile().unwrap().as_deref(), Some("production"));
+        let (figment, sources) = from_env.figment_with_sources().unwrap();
+
+        assert_eq!(figment.profile().as_str(), "production");
+        let config: ServerConfig = figment.extract().unwrap();
+        assert_eq!((config.host.as_str(), config.port, config.log_level.as_str()), ("prod", 8443, "info"));
+        let report = sources.report(&config).unwrap();
+        assert!(matches!(report.source("host"), Some(ValueSource::ConfigFile(path)) if path.ends_with("config.production.toml")));
+
+        let staging = builder().with_profile("staging").load().unwrap();
+        assert_eq!((staging.host.as_str(), staging.port), ("base", 8443));
+
+        let invalid = builder().with_profile("../prod").load();
+        assert!(matches!(invalid, Err(QuantumConfigError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_tenant_layers_override_shared_base() {
+        let dir = tempdir().unwrap();
+        let base = dir.path().join("config.toml");
+        fs::write(&base, "host = \"base\"\nport = 80\nlog_level = \"info\"\n").unwrap();
+        fs::write(dir.path().join("config.tenant-a.toml"), "host = \"tenant-a\"\n").unwrap();
+        fs::write(dir.path().join("tenant-a.remote.toml"), "port = 8081\n").unwrap();
+        let tenant_source = dir.path().join("{tenant}.remote.toml").to_string_lossy().into_owned();
+
+        let env = crate::providers::MapEnv::new()
+            .with_var("QC_TENANT_LOG_LEVEL", "warn")
+            .with_var("QC_TENANT__TENANT_A__LOG_LEVEL", "debug")
+            .with_var("QC_TENANT__TENANT_B__PORT", "9999");
+        let load = |options: LoadOptions| {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-tenant-test")
+                .with_default_paths(false)
+                .with_file(&base)
+                .with_tenant_source(tenant_source.as_str())
+                .with_env_prefix("QC_TENANT_")
+                .with_env_source(env.clone())
+                .with_load_options(options)
+                .load()
+        };
+
+        // 未指定租户时只合并共享配置
+        let shared = load(LoadOptions::new()).unwrap();
+        assert_eq!((shared.host.as_str(), shared.port, shared.log_level.as_str()), ("base", 80, "warn"));
+
+        let tenant = load(LoadOptions::new().with_tenant("tenant-a")).unwrap();
+        assert_eq!((tenant.host.as_str(), tenant.port, tenant.log_level.as_str()), ("tenant-a", 8081, "debug"));
+
+        let invalid = load(LoadOptions::new().with_tenant("../tenant-a"));
+        assert!(matches!(invalid, Err(QuantumConfigError::InvalidValue { .. })));
+        // 与 `tenant-a` 对应同一组环境变量的名称被拒绝
+        let colliding = load(LoadOptions::new().with_tenant("Tenant_A"));
+        assert!(matches!(colliding, Err(QuantumConfigError::InvalidValue { .. })));
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_cli_subcommand_binds_its_own_args() {
+        let command = Command::new("app")
+            .subcommand(Command::new("serve").arg(Arg::new("port").long("port").num_args(1)))
+            .subcommand(Command::new("worker").arg(Arg::new("port").long("port").num_args(1)));
+        let matches = command.try_get_matches_from(["app", "serve", "--port", "9090"]).unwrap();
+
+        let load = |subcommand: &str| {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .with_env_prefix("QC_SUBCOMMAND_")
+                .with_defaults(ServerConfig::default())
+                .with_cli(matches.clone())
+                .with_cli_subcommand(subcommand)
+                .load()
+                .unwrap()
+        };
+        assert_eq!(load("serve").port, 9090);
+        assert_eq!(load("worker").port, 0);
+    }
+
+    #[test]
+    fn test_unsupported_behavior_version_is_rejected() {
+        let mut app_meta = QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test").app_meta().clone();
+        app_meta.behavior_version = compat::MAX_BEHAVIOR_VERSION + 1;
+        let result = QuantumConfigBuilder::<ServerConfig>::from_app_meta(app_meta)
+            .with_default_paths(false)
+            .with_defaults(ServerConfig::default())
+            .load();
+        assert!(matches!(result, Err(QuantumConfigError::UnsupportedBehaviorVersion { .. })));
+    }
+
+    #[test]
+    fn test_max_sources_caps_loaded_layers() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.toml");
+        let second = dir.path().join("second.toml");
+        fs::write(&first, "host = \"a\"\n").unwrap();
+        fs::write(&second, "port = 1\n").unwrap();
+
+        let builder = || {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .with_env_prefix("QC_MAX_SOURCES_")
+                .with_defaults(ServerConfig::default())
+                .with_file(&first)
+                .with_file(&second)
+        };
+        match builder().with_max_sources(2).load() {
+            Err(QuantumConfigError::TooManySources { limit, chain }) => {
+                assert_eq!(limit, 2);
+                assert_eq!(chain, ["Quantum Config Environment Provider (prefix: QC_MAX_SOURCES_)"]);
+            }
+            other => panic!("Expected TooManySources, got {:?}", other),
+        }
+        assert!(builder().with_max_sources(3).load().is_ok());
+    }
+
+    #[test]
+    fn test_dotenv_ranks_below_process_env() {
+        let dir = tempdir().unwrap();
+        let dotenv = dir.path().join(".env");
+        fs::write(&dotenv, "QC_DOTENV_HOST=dotenv-host\nQC_DOTENV_PORT=7000\nQC_DOTENV_LOG_LEVEL='warn'\n").unwrap();
+
+        let builder = |dotenv: PathBuf| {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .with_env_prefix("QC_DOTENV_")
+                .with_env_source(crate::providers::MapEnv::new().with_var("QC_DOTENV_PORT", "7001"))
+                .with_defaults(ServerConfig::default())
+                .with_dotenv(dotenv)
+        };
+        let result = builder(dotenv).load();
+        let missing = builder(dir.path().join("missing.env")).load();
+
+        assert_eq!(
+            result.unwrap(),
+            ServerConfig {
+                host: "dotenv-host".to_string(),
+                port: 7001,
+                log_level: "warn".to_string(),
+            }
+        );
+        assert_eq!(missing.unwrap().host, "");
+    }
+
+    #[test]
+    fn test_env_source_replaces_process_env() {
+        let mut env_guard = crate::testing::EnvGuard::new();
+        env_guard.set("QC_ENV_SOURCE_PORT", "7001");
+        let env = crate::providers::MapEnv::new()
+            .with_var("QC_ENV_SOURCE_HOST", "map-host")
+            .with_var("QC_ENV_SOURCE_PROFILE", "staging");
+        let builder = QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+            .with_default_paths(false)
+            .with_env_prefix("QC_ENV_SOURCE_")
+            .with_profile_env("QC_ENV_SOURCE_PROFILE")
+            .with_defaults(ServerConfig::default())
+            .with_env_source(env);
+        let profile = builder.active_profile();
+        let result = builder.load();
+        drop(env_guard);
+
+        assert_eq!(profile.unwrap().as_deref(), Some("staging"));
+        let config = result.unwrap();
+        assert_eq!(config.host, "map-host");
+        // 进程环境中的变量不可见
+        assert_eq!(config.port, 0);
+    }
+
+    #[test]
+    fn test_secret_references_are_opt_in() {
+        let dir = tempdir().unwrap();
+        let secret_path = dir.path().join("host");
+        fs::write(&secret_path, "secret-host\n").unwrap();
+        let path = dir.path().join("custom.toml");
+        fs::write(&path, format!("host = \"secret_file:{}\"\nport = 1\nlog_level = \"info\"\n", secret_path.display())).unwrap();
+
+        let builder = || {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .without_env()
+                .with_file(&path)
+        };
+        assert!(builder().load().unwrap().host.starts_with("secret_file:"));
+        assert_eq!(builder().with_secret_references(true).load().unwrap().host, "secret-host");
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_values_are_decrypted_across_sources() {
+        use crate::providers::encryption::encrypt_value;
+        use crate::providers::{EncryptionKey, KeyChain};
+
+        let key = EncryptionKey::generate().unwrap();
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        let host = encrypt_value("encrypted-host", &key, "host").unwrap();
+        fs::write(&path, format!("host = \"{}\"\nport = 1\nlog_level = \"info\"\n", host)).unwrap();
+        let env = crate::providers::MapEnv::new()
+            .with_var("QC_ENC_VALUES_LOG_LEVEL", encrypt_value("debug", &key, "log_level").unwrap());
+
+        let builder = || {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .with_file(&path)
+                .with_env_prefix("QC_ENC_VALUES_")
+                .with_env_source(env.clone())
+        };
+        assert_eq!(builder().load().unwrap().host, host);
+
+        let config = builder().with_encrypted_values(KeyChain::new().with_key(key)).load().unwrap();
+        assert_eq!(config.host, "encrypted-host");
+        assert_eq!(config.log_level, "debug");
+
+        let wrong_key = KeyChain::new().with_key(EncryptionKey::generate().unwrap());
+        assert!(builder().with_encrypted_values(wrong_key).load().is_err());
+    }
+
+    #[test]
+    fn test_parallel_loading_merges_in_declared_order() {
+        let dir = tempdir().unwrap();
+        let first = dir.path().join("first.toml");
+        let second = dir.path().join("second.json");
+        fs::write(&first, "host = \"first\"\nport = 1\nlog_level = \"info\"\n").unwrap();
+        fs::write(&second, r#"{"host": "second"}"#).unwrap();
+
+        let (config, report) = QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+            .with_default_paths(false)
+            .without_env()
+            .with_file(&first)
+            .with_file(&second)
+            .with_parallel_loading(true)
+            .load_with_report()
+            .unwrap();
+
+        assert_eq!(config.host, "second");
+        assert_eq!(config.port, 1);
+        assert!(report.parallel);
+        assert_eq!(report.sources.len(), 2);
+        assert!(report.sources[0].name.contains("first.toml"));
+        assert!(report.sources[1].name.contains("second.json"));
+        assert!(report.sources.iter().all(|source| source.succeeded));
+    }
+
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_config_report_records_value_sources() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        fs::write(&path, "host = \"file-host\"\n").unwrap();
+
+        let builder = || {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .with_defaults(ServerConfig::default())
+                .with_file(&path)
+                .with_env_prefix("QC_SOURCES_")
+                .with_env_source(crate::providers::MapEnv::new().with_var("QC_SOURCES_PORT", "9090"))
+        };
+        let matches = Command::new("app")
+            .arg(Arg::new("log-level").long("log-level").num_args(1))
+            .get_matches_from(["app", "--log-level", "debug"]);
+        let with_cli = builder().with_cli(matches).load_with_config_report();
+        let without_cli = builder().load_with_config_report();
+
+        let (config, report) = with_cli.unwrap();
+        assert_eq!(config.port, 9090);
+        assert!(matches!(report.source("host"), Some(ValueSource::ConfigFile(p)) if p.ends_with("custom.toml")));
+        assert_eq!(report.source("port"), Some(&ValueSource::Env("QC_SOURCES_PORT".to_string())));
+        assert_eq!(report.source("log_level"), Some(&ValueSource::Cli("--log-level".to_string())));
+        assert!(report.to_string().contains("port       <- environment variable QC_SOURCES_PORT"));
+        assert_eq!(report.layers.len(), 4);
+        assert_eq!(report.layers[0], "default");
+        assert_eq!(report.layers[3], "command-line arguments");
+        assert!(report.to_mermaid().contains("L2 -->|\"QC_SOURCES_PORT\"|"));
+
+        let (_, report) = without_cli.unwrap();
+        assert_eq!(report.source("log_level"), Some(&ValueSource::Default));
+        assert_eq!(report.overridden().count(), 2);
+    }
+
+    #[test]
+    fn test_extraction_errors_keep_source_metadata() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        fs::write(&path, "host = \"h\"\nport = \"not a port\"\nlog_level = \"info\"\n").unwrap();
+
+        let result: Result<ServerConfig, _> = QuantumConfigBuilder::new("qc-builder-test")
+            .with_default_paths(false)
+            .without_env()
+            .with_file(&path)
+            .load();
+        match result {
+            Err(QuantumConfigError::Extraction { key_path, origin, .. }) => {
+                assert_eq!(key_path, "port");
+                assert!(origin.contains("Quantum Config File Provider"));
+            }
+            other => panic!("Expected Extraction error, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_load_async_returns_report() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        fs::write(&path, "host = \"async\"\nport = 2\nlog_level = \"info\"\n").unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let (config, report) = runtime
+            .block_on(
+                QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                    .with_default_paths(false)
+                    .without_env()
+                    .with_file(&path)
+                    .load_async(),
+            )
+            .unwrap();
+
+        assert_eq!(config.host, "async");
+        assert!(!report.parallel);
+        assert_eq!(report.sources.len(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_load_async_reads_async_files() {
+        use crate::providers::{AsyncFileReader, ReadFuture};
+        use std::path::Path;
+
+        /// 在内存中提供内容的异步读取器
+        struct MemoryReader(&'static str);
+
+        impl AsyncFileReader for MemoryReader {
+            fn read_bytes_async<'a>(&'a self, _path: &'a Path) -> ReadFuture<'a, Vec<u8>> {
+                Box::pin(async move { Ok(self.0.as_bytes().to_vec()) })
+            }
+        }
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("custom.toml");
+        fs::write(&path, "host = \"file\"\nport = 1\nlog_level = \"info\"\n").unwrap();
+        let builder = || {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-builder-test")
+                .with_default_paths(false)
+                .without_env()
+                .with_file(&path)
+                .with_async_file("remote/app.json", MemoryReader(r#"{"port": 7000}"#))
+        };
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        let (config, report) = runtime.block_on(builder().load_async()).unwrap();
+        assert_eq!((config.host.as_str(), config.port), ("file", 7000));
+        assert_eq!(report.sources.len(), 2);
+
+        assert!(matches!(builder().load(), Err(QuantumConfigError::Internal(_))));
+    }
+
+    #[test]
+    fn test_wait_for_files_retries_until_file_appears() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("late.toml");
+        let writer_path = path.clone();
+        let writer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(150));
+            fs::write(writer_path, "host = \"late\"\nport = 3\nlog_level = \"info\"\n").unwrap();
+        });
+
+        let config: ServerConfig = QuantumConfigBuilder::new("qc-builder-test")
+            .with_default_paths(false)
+            .without_env()
+            .with_file(&path)
+            .with_load_options(LoadOptions::new().with_wait_for_files(Duration::from_secs(10), Duration::from_millis(20)))
+            .load()
+            .unwrap();
+        writer.join().unwrap();
+        assert_eq!(config.host, "late");
+
+        let started = Instant::now();
+        let result: Result<ServerConfig, _> = QuantumConfigBuilder::new("qc-builder-test")
+            .with_default_paths(false)
+            .without_env()
+            .with_file(dir.path().join("never.toml"))
+            .with_load_options(LoadOptions::new().with_wait_for_files(Duration::from_millis(100), Duration::from_millis(20)))
+            .load();
+        assert!(matches!(result, Err(QuantumConfigError::SpecifiedFileNotFound { .. })));
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_last_known_good_replaces_unavailable_sources() {
+        let dir = tempdir().unwrap();
+        let snapshot = dir.path().join("last-known-good.json");
+        let broken = dir.path().join("broken.toml");
+        fs::write(&broken, "host = ").unwrap();
+
+        let load = |file: &Path| {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-lkg-test")
+                .with_default_paths(false)
+                .with_file(file)
+                .with_env_prefix("QC_LKG_")
+                .with_env_source(crate::providers::MapEnv::new().with_var("QC_LKG_PORT", "9090"))
+                .with_load_options(LoadOptions::new().with_last_known_good(&snapshot))
+                .load()
+        };
+
+        // 快照不存在时返回原始错误
+        let missing = dir.path().join("missing.toml");
+        assert!(matches!(load(&missing), Err(QuantumConfigError::SpecifiedFileNotFound { .. })));
+        assert!(load(&broken).is_err());
+
+        fs::write(&snapshot, r#"{"host": "cached", "port": 80, "log_level": "warn"}"#).unwrap();
+        let config = load(&missing).unwrap();
+        // 仍然可用的环境变量覆盖快照中的值
+        assert_eq!((config.host.as_str(), config.port, config.log_level.as_str()), ("cached", 9090, "warn"));
+
+        // 无法解析的文件不是暂时不可用，不回退到快照
+        let error = load(&broken).unwrap_err().to_string();
+        assert!(error.contains("broken.toml"), "{}", error);
+        assert!(!error.contains("unavailable"), "{}", error);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let unreadable = dir.path().join("unreadable.toml");
+            fs::write(&unreadable, "host = \"file\"\n").unwrap();
+            fs::set_permissions(&unreadable, fs::Permissions::from_mode(0o000)).unwrap();
+            // 以 root 运行时权限不起作用，此时文件可以正常读取
+            if fs::read(&unreadable).is_err() {
+                assert_eq!(load(&unreadable).unwrap().host, "cached");
+            }
+        }
+    }
+
+    #[test]
+    fn test_new_uses_uppercase_env_prefix() {
+        let builder = QuantumConfigBuilder::<ServerConfig>::new("myapp");
+        assert_eq!(builder.app_meta().env_prefix.as_deref(), Some("MYAPP_"));
+
+        let builder = builder.without_env();
+        assert_eq!(builder.app_meta().env_prefix, None);
+    }
+
+    #[test]
+    fn test_cached_load_reparses_changed_files_and_rereads_env() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("cached.toml");
+        fs::write(&path, "host = \"first\"\nport = 80\nlog_level = \"info\"\n").unwrap();
+
+        let load = |port: &str| {
+            QuantumConfigBuilder::<ServerConfig>::new("qc-cache-test")
+                .with_default_paths(false)
+                .with_file(&path)
+                .with_env_prefix("QC_CACHE_")
+                .with_env_source(crate::providers::MapEnv::new().with_var("QC_CACHE_PORT", port))
+                .with_load_options(LoadOptions::new().with_cache(true))
+                .load()
+                .unwrap()
+        };
+
+        assert_eq!((load("81").host, load("82").port), ("first".to_string(), 82));
+        fs::write(&path, "host = \"second\"\nport = 80\nlog_level = \"info\"\n").unwrap();
+        assert_eq!(load("83").host, "second");
+    }
+
+    #[test]
+    fn test_observer_sees_sources_and_overrides() {
+        use crate::observe::LoadObserver;
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Recorder(Mutex<Vec<String>>);
+
+        impl LoadObserver for Arc<Recorder> {
+            fn provider_started(&self, source: &str) {
+                self.0.lock().unwrap().push(format!("started {}", source));
+            }
+
+            fn provider_finished(&self, source: &str, _elapsed: Duration, succeeded: bool) {
+                self.0.lock().unwrap().push(format!("finished {} {}", source, succeeded));
+            }
+
+            fn key_overridden(&self, key: &str, _previous: &str, source: &str) {
+                self.0.lock().unwrap().push(format!("overridden {} by {}", key, source));
+            }
+        }
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("observed.toml");
+        fs::write(&path, "host = \"file-host\"\n").unwrap();
+        let recorder = Arc::new(Recorder::default());
+
+        QuantumConfigBuilder::new("qc-observer-test")
+            .with_default_paths(false)
+            .with_defaults(ServerConfig::default())
+            .with_file(&path)
+            .with_env_prefix("QC_OBSERVER_")
+            .with_env_source(crate::providers::MapEnv::new().with_var("QC_OBSERVER_PORT", "9090"))
+            .with_observer(recorder.clone())
+            .load()
+            .unwrap();
+
+        let events = recorder.0.lock().unwrap();
+        let file = format!("Quantum Config File Provider ({})", path.display());
+        let env = "Quantum Config Environment Provider (prefix: QC_OBSERVER_)";
+        assert_eq!(events.iter().filter(|event| event.starts_with("started")).count(), 3);
+        assert!(events.contains(&format!("finished {} true", file)), "{:?}", events);
+        assert!(events.contains(&format!("overridden host by {}", file)), "{:?}", events);
+        assert!(events.contains(&format!("overridden port by {}", env)), "{:?}", events);
+        assert!(!events.iter().any(|event| event.starts_with("overridden log_level")), "{:?}", events);
+    }
+}
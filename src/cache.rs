@@ -0,0 +1,202 @@
+//! 配置加载缓存
+//!
+//! 频繁调用 `T::load()` 的程序（例如每个请求处理函数都重新加载配置）可以通过
+//! [`LoadOptions::with_cache`](crate::LoadOptions::with_cache) 启用进程级缓存，避免每次都重新读取并解析未变化的来源：
+//!
+//! ```ignore
+//! let options = LoadOptions::new().with_cache(true);
+//! let config = AppConfig::load_with_options(options)?;
+//! ```
+//!
+//! - 本地配置文件按路径缓存解析结果，以文件大小与修改时间作为指纹，任一变化即重新读取并解析；
+//! - HTTP(S) 远程来源缓存响应正文与 `ETag`，再次加载时发送 `If-None-Match`，
+//!   服务器返回 `304 Not Modified` 时复用缓存的内容。
+//!
+//! 环境变量、`.env` 文件与命令行参数每次加载都重新读取，密钥引用每次都重新解析。
+//! 启用文件包含或加密文件时不缓存文件：被包含的文件与密钥的变化无法从指纹中发现。
+//!
+//! 修改时间的精度取决于文件系统；在同一时间戳内写入大小相同的新内容不会被发现，必要时调用 [`clear`]。
+
+use figment::value::{Dict, Map};
+use figment::{Error, Metadata, Profile, Provider};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+/// 按路径与解析深度缓存的文件解析结果
+static FILES: LazyLock<Mutex<HashMap<(PathBuf, u32), CachedData>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 按 URL 缓存的响应正文
+#[cfg(feature = "http")]
+static RESPONSES: LazyLock<Mutex<HashMap<String, CachedResponse>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// 文件指纹
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Fingerprint {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl Fingerprint {
+    /// 读取文件的大小与修改时间，文件不存在或不是普通文件时返回 `None`
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok().filter(|metadata| metadata.is_file())?;
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// 缓存的文件解析结果
+#[derive(Debug, Clone)]
+struct CachedData {
+    fingerprint: Fingerprint,
+    data: Map<Profile, Dict>,
+}
+
+/// 缓存的 HTTP 响应
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+pub(crate) struct CachedResponse {
+    /// 响应的 `ETag` 头
+    pub(crate) etag: String,
+    /// 响应正文
+    pub(crate) body: Vec<u8>,
+}
+
+/// 清空所有缓存
+pub fn clear() {
+    lock(&FILES).clear();
+    #[cfg(feature = "http")]
+    lock(&RESPONSES).clear();
+}
+
+/// 缓存的文件与远程响应数量
+pub fn len() -> usize {
+    let files = lock(&FILES).len();
+    #[cfg(feature = "http")]
+    return files + lock(&RESPONSES).len();
+    #[cfg(not(feature = "http"))]
+    files
+}
+
+/// 缓存是否为空
+pub fn is_empty() -> bool {
+    len() == 0
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// 查询 URL 缓存的响应
+#[cfg(feature = "http")]
+pub(crate) fn cached_response(url: &str) -> Option<CachedResponse> {
+    lock(&RESPONSES).get(url).cloned()
+}
+
+/// 保存带 `ETag` 的响应
+#[cfg(feature = "http")]
+pub(crate) fn store_response(url: &str, etag: String, body: Vec<u8>) {
+    lock(&RESPONSES).insert(url.to_string(), CachedResponse { etag, body });
+}
+
+/// 带缓存的文件来源
+///
+/// 读取前先取文件指纹：与缓存一致时直接返回缓存的解析结果，否则读取并解析文件，成功后更新缓存。
+/// 指纹在读取之前获取，读取期间文件发生变化时下一次加载会因指纹不一致而重新解析。
+#[derive(Debug, Clone)]
+pub(crate) struct CachedFile<P> {
+    inner: P,
+    path: PathBuf,
+    max_parse_depth: u32,
+}
+
+impl<P> CachedFile<P> {
+    pub(crate) fn new<Q: Into<PathBuf>>(inner: P, path: Q, max_parse_depth: u32) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+            max_parse_depth,
+        }
+    }
+}
+
+impl<P: Provider> Provider for CachedFile<P> {
+    fn metadata(&self) -> Metadata {
+        self.inner.metadata()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let Some(fingerprint) = Fingerprint::of(&self.path) else {
+            return self.inner.data();
+        };
+        let key = (self.path.clone(), self.max_parse_depth);
+        if let Some(cached) = lock(&FILES).get(&key).filter(|cached| cached.fingerprint == fingerprint) {
+            return Ok(cached.data.clone());
+        }
+        let data = self.inner.data()?;
+        lock(&FILES).insert(
+            key,
+            CachedData {
+                fingerprint,
+                data: data.clone(),
+            },
+        );
+        Ok(data)
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.inner.profile()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// 统计读取次数的来源
+    #[derive(Debug, Clone, Default)]
+    struct Counting(Arc<AtomicUsize>);
+
+    impl Provider for Counting {
+        fn metadata(&self) -> Metadata {
+            Metadata::named("counting")
+        }
+
+        fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+            let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            let mut dict = Dict::new();
+            dict.insert("reads".to_string(), count.into());
+            Ok(Profile::Default.collect(dict))
+        }
+    }
+
+    #[test]
+    fn test_cached_file_reparses_only_when_fingerprint_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.toml");
+        std::fs::write(&path, "a = 1").unwrap();
+
+        let counting = Counting::default();
+        let provider = CachedFile::new(counting.clone(), &path, 128);
+        provider.data().unwrap();
+        provider.data().unwrap();
+        assert_eq!(counting.0.load(Ordering::SeqCst), 1);
+
+        // 大小变化即重新读取
+        std::fs::write(&path, "a = 10").unwrap();
+        provider.data().unwrap();
+        assert_eq!(counting.0.load(Ordering::SeqCst), 2);
+
+        // 文件不存在时不缓存
+        std::fs::remove_file(&path).unwrap();
+        provider.data().unwrap();
+        provider.data().unwrap();
+        assert_eq!(counting.0.load(Ordering::SeqCst), 4);
+    }
+}
@@ -0,0 +1,266 @@
+//! 配置文件检查
+//!
+//! [`check`] 按配置结构体解析并校验单个配置文件，而不启动应用本身，
+//! 返回可序列化为 JSON 的 [`CheckReport`]（错误、警告与未知键），
+//! 适合在 CI 流水线或应用的 `--check-config` 参数中使用：
+//!
+//! ```ignore
+//! let report = quantum_config::check::<AppConfig>("config.toml");
+//! println!("{}", serde_json::to_string_pretty(&report)?);
+//! std::process::exit(if report.is_ok() { 0 } else { 1 });
+//! ```
+//!
+//! 文件未设置的键使用 `Default` 提供的默认值，与加载时的行为一致；
+//! 环境变量与命令行参数不参与检查。
+//!
+//! 需要检查实际部署环境中的全部来源时，派生类型的 `load()` 内置处理 `--validate-config`：
+//! 合并并校验所有来源后输出 [`LoadCheckReport`]，通过时以状态码 0 退出，否则以 1 退出。
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::providers::QuantumConfigFileProvider;
+use crate::report::ConfigReport;
+use crate::unknown;
+use figment::providers::Serialized;
+use figment::Figment;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// 检查文件时允许的最大解析深度
+const CHECK_MAX_PARSE_DEPTH: u32 = 128;
+
+/// 单个检查结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckIssue {
+    /// 以 `.` 分隔的键路径，无法定位到键时为 `None`
+    pub key_path: Option<String>,
+    /// 稳定的问题代码，错误使用 [`QuantumConfigError::code`]
+    pub code: String,
+    /// 问题描述
+    pub message: String,
+}
+
+impl fmt::Display for CheckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.key_path {
+            Some(key_path) => write!(f, "{}: {}", key_path, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+/// 配置文件检查报告
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CheckReport {
+    /// 被检查的文件
+    pub path: PathBuf,
+    /// 导致配置无法加载的错误
+    pub errors: Vec<CheckIssue>,
+    /// 不影响加载但需要关注的问题
+    pub warnings: Vec<CheckIssue>,
+    /// 配置结构体中不存在、加载时会被忽略的键
+    pub unknown_keys: Vec<String>,
+}
+
+impl CheckReport {
+    /// 没有错误（可以有警告）
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// 既没有错误也没有警告
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+
+    fn error(&mut self, error: QuantumConfigError) {
+        self.errors.extend(issues(error));
+    }
+}
+
+/// 将加载错误拆分为检查结果：校验失败与缺少键时每个键一条
+fn issues(error: QuantumConfigError) -> Vec<CheckIssue> {
+    let code = error.code().to_string();
+    match error {
+        QuantumConfigError::ValidationFailed { errors } => errors
+            .into_iter()
+            .map(|e| CheckIssue {
+                key_path: Some(e.field),
+                code: code.clone(),
+                message: e.message,
+            })
+            .collect(),
+        QuantumConfigError::MissingKeys { keys, .. } => keys
+            .into_iter()
+            .map(|key| CheckIssue {
+                key_path: Some(key),
+                code: code.clone(),
+                message: "missing required key".to_string(),
+            })
+            .collect(),
+        other => {
+            let key_path = match &other {
+                QuantumConfigError::MissingValue { key_path }
+                | QuantumConfigError::Extraction { key_path, .. }
+                | QuantumConfigError::ConstraintViolation { key_path, .. } => Some(key_path.clone()),
+                _ => None,
+            };
+            vec![CheckIssue { key_path, code, message: other.to_string() }]
+        }
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} error(s), {} warning(s)",
+            self.path.display(),
+            self.errors.len(),
+            self.warnings.len()
+        )?;
+        for issue in &self.errors {
+            writeln!(f, "  error: {}", issue)?;
+        }
+        for issue in &self.warnings {
+            writeln!(f, "  warning: {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// 加载全部来源后的检查报告，由 `--validate-config` 输出
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LoadCheckReport {
+    /// 按合并顺序（低 -> 高优先级）排列的来源层，加载失败时为空
+    pub sources: Vec<String>,
+    /// 导致配置无法加载的错误
+    pub errors: Vec<CheckIssue>,
+    /// 不影响加载但需要关注的问题（例如已弃用的键）
+    pub warnings: Vec<CheckIssue>,
+}
+
+impl LoadCheckReport {
+    /// 由加载结果生成报告
+    pub fn from_result<T>(result: Result<(T, ConfigReport), QuantumConfigError>) -> Self {
+        match result {
+            Ok((_, report)) => Self {
+                sources: report.layers,
+                errors: Vec::new(),
+                warnings: report
+                    .deprecations
+                    .into_iter()
+                    .map(|warning| CheckIssue {
+                        key_path: Some(warning.key.clone()),
+                        code: "deprecated_key".to_string(),
+                        message: warning.to_string(),
+                    })
+                    .collect(),
+            },
+            Err(error) => Self {
+                errors: issues(error),
+                ..Self::default()
+            },
+        }
+    }
+
+    /// 没有错误（可以有警告）
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for LoadCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            writeln!(f, "configuration is valid: {} warning(s)", self.warnings.len())?;
+        } else {
+            writeln!(
+                f,
+                "configuration is invalid: {} error(s), {} warning(s)",
+                self.errors.len(),
+                self.warnings.len()
+            )?;
+        }
+        for source in &self.sources {
+            writeln!(f, "  source: {}", source)?;
+        }
+        for issue in &self.errors {
+            writeln!(f, "  error: {}", issue)?;
+        }
+        for issue in &self.warnings {
+            writeln!(f, "  warning: {}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// 按配置结构体 `T` 解析并校验配置文件
+///
+/// 文件无法读取或解析、值无法转换为字段类型、字段约束或 [`Config::validate`] 未通过时记为错误；
+/// 结构体中不存在的键记入 [`CheckReport::unknown_keys`] 并产生一条警告。
+pub fn check<T: Config>(path: impl AsRef<Path>) -> CheckReport {
+    let path = path.as_ref();
+    let mut report = CheckReport {
+        path: path.to_path_buf(),
+        errors: Vec::new(),
+        warnings: Vec::new(),
+        unknown_keys: Vec::new(),
+    };
+
+    let provider = match QuantumConfigFileProvider::from_path(path, true, CHECK_MAX_PARSE_DEPTH) {
+        Ok(provider) => provider,
+        Err(e) => {
+            report.error(e);
+            return report;
+        }
+    };
+    let file = Figment::from(provider);
+    let file_value: JsonValue = match file.extract() {
+        Ok(value) => value,
+        Err(e) => {
+            report.error(e.into());
+            return report;
+        }
+    };
+
+    match T::key_shape() {
+        Ok(shape) => {
+            let known = unknown::known_keys(&shape);
+            report.unknown_keys = unknown::find_unknown_keys(&file_value, &shape);
+            for key in &report.unknown_keys {
+                let message = match unknown::suggest(key, &known) {
+                    Some(suggestion) => format!("unknown key, did you mean '{}'?", suggestion),
+                    None => "unknown key, it will be ignored".to_string(),
+                };
+                report.warnings.push(CheckIssue {
+                    key_path: Some(key.clone()),
+                    code: "unknown_key".to_string(),
+                    message,
+                });
+            }
+        }
+        Err(e) => report.error(e),
+    }
+
+    let figment = match T::transform_registry()
+        .and_then(|registry| registry.apply_to_figment(Figment::from(Serialized::defaults(T::default())).merge(file)))
+    {
+        Ok(figment) => figment,
+        Err(e) => {
+            report.error(e);
+            return report;
+        }
+    };
+    match figment.extract::<T>() {
+        Ok(config) => {
+            if let Err(e) = config.validate() {
+                report.error(e.with_origin(&figment));
+            }
+        }
+        Err(e) => report.error(e.into()),
+    }
+    report
+}
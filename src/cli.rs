@@ -0,0 +1,1162 @@
+//! 由结构体字段生成命令行参数
+//!
+//! `#[derive(Config)]` 生成的命令除了内置参数（`--config`、`--log-level` 等）外，
+//! 为每个字段生成一个参数：
+//! - 参数名由键路径得到，`_` 替换为 `-`；嵌套字段按 [`CliNestedStyle`] 以 `-`（默认，`--server-port`）
+//!   或 `.`（`--server.port`）连接各段，派生宏中以 `#[config(cli_nested_style = "dot")]` 选择
+//! - 帮助信息取自字段的 `///` 文档注释（见 [`crate::FieldMeta::doc_comment`]），首行为简短帮助，完整文档用于 `--help`
+//! - 值解析器按字段类型校验取值（整数范围、浮点数、布尔值、字符），`value_name` 按类型取 `NUM`、`BOOL`、`PATH` 等
+//! - `bool` 字段可以单独使用 `--debug` 表示 `true`，`Vec<T>` 字段可以重复出现
+//!
+//! 嵌套结构体的字段由默认值的结构推导，类型按默认值推断，没有文档注释。
+//! 与内置参数同名的字段（例如 `log_level`）沿用内置参数，不再重复生成。
+//! 解析后的值仍以字符串保存，由 [`crate::providers::QuantumConfigClapProvider`] 按 [`cli_mappings`] 写入对应键路径。
+//!
+//! 内置的 `--print-config[=FORMAT]` 由派生类型的 `load()` 处理：合并并校验后按 FORMAT（默认 `toml`，
+//! 另有 `json`、`yaml`、`ini` 及启用特性后的 `json5`、`hcl`、`xml`）输出生效配置并退出进程，
+//! 敏感键显示为 `[REDACTED]`，参见 [`handle_print_config`]。
+//! `--validate-config` 只加载并校验全部来源，输出报告后以状态码 0（通过）或 1（失败）退出，
+//! 参见 [`handle_validate_config`]。
+//!
+//! 启用 `completions` 特性后，`--generate-completions <SHELL>` 输出 bash、zsh、fish、PowerShell 或 elvish 的补全脚本；
+//! 启用 `man` 特性后，`--generate-man` 输出 roff 格式的手册页。两者都在加载配置之前处理，
+//! 输出到标准输出后退出进程，参见 [`handle_generate`]：
+//!
+//! ```text
+//! myapp --generate-completions bash > /etc/bash_completion.d/myapp
+//! myapp --generate-man > /usr/share/man/man1/myapp.1
+//! ```
+
+use crate::builder::QuantumConfigBuilder;
+#[cfg(feature = "cli")]
+use crate::builder::SourceKind;
+#[cfg(feature = "cli")]
+use crate::check::LoadCheckReport;
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+#[cfg(feature = "cli")]
+use crate::meta::StructMeta;
+use crate::providers::file_provider::FileFormat;
+use crate::redact::Redacted;
+use crate::report::ConfigReport;
+#[cfg(feature = "cli")]
+use clap::error::{ContextKind, ContextValue};
+#[cfg(feature = "cli")]
+use clap::{Arg, ArgAction, ArgMatches, Command};
+#[cfg(feature = "cli")]
+use serde_json::Value as JsonValue;
+#[cfg(feature = "cli")]
+use std::fmt::Display;
+#[cfg(feature = "cli")]
+use std::str::FromStr;
+
+/// 内置参数的长选项名，同名字段不再生成参数
+pub const BUILTIN_ARGS: &[&str] = &[
+    "config", "config-dir", "log-level", "verbose", "quiet", "output", "format",
+    "print-config",
+    "validate-config",
+    "generate-completions",
+    "generate-man",
+    "help",
+];
+
+/// 控制 `load()` 行为而不提供配置值的内置参数，命令行提供器跳过它们
+pub const CONTROL_ARGS: &[&str] = &["print-config", "validate-config", "generate-completions", "generate-man"];
+
+/// `--print-config` 接受的格式
+#[cfg(feature = "cli")]
+const PRINT_FORMATS: &[&str] = &[
+    "toml",
+    "json",
+    "yaml",
+    "ini",
+    #[cfg(feature = "json5")]
+    "json5",
+    #[cfg(feature = "hcl")]
+    "hcl",
+    #[cfg(feature = "xml")]
+    "xml",
+];
+
+/// 嵌套字段的命令行参数风格
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CliNestedStyle {
+    /// 各段以 `-` 连接：`--server-port`
+    #[default]
+    Dash,
+    /// 各段以 `.` 连接：`--server.port`
+    Dot,
+}
+
+impl CliNestedStyle {
+    /// 连接各段的分隔符
+    pub fn separator(self) -> &'static str {
+        match self {
+            Self::Dash => "-",
+            Self::Dot => ".",
+        }
+    }
+
+    /// 键路径转换为长选项名
+    pub fn long_name(self, key: &str) -> String {
+        key.split('.').map(long_name).collect::<Vec<_>>().join(self.separator())
+    }
+}
+
+/// 派生类型加载时解析命令行参数的方式
+///
+/// 应用有自己的参数解析器时，默认的 `Strict` 会在遇到应用自己的参数时报错退出，
+/// 此时可用 `IgnoreUnknown` 只读取能识别的参数，或用 `Disabled` 完全不读取命令行参数。
+/// `IgnoreUnknown` 无法区分应用自己选项的取值与位置参数，因此 `cli_positional` 字段在该方式下改为长选项。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum CliMode {
+    /// 无法识别的参数是错误
+    #[default]
+    Strict,
+    /// 跳过无法识别的参数；不提供 `--help`，留给应用自己的解析器。位置参数不绑定到字段
+    IgnoreUnknown,
+    /// 不解析命令行参数，只保留程序名
+    Disabled,
+}
+
+impl CliMode {
+    /// 按该方式解析参数（第一个元素为程序名）
+    ///
+    /// # Errors
+    /// 参数无法解析时返回 clap 的错误；`IgnoreUnknown` 时无法识别的参数不是错误，但取值无效等错误照常返回
+    #[cfg(feature = "cli")]
+    pub fn parse(self, command: Command, mut args: Vec<String>) -> Result<ArgMatches, clap::Error> {
+        match self {
+            Self::Strict => command.try_get_matches_from(args),
+            Self::Disabled => command.try_get_matches_from(args.into_iter().take(1)),
+            Self::IgnoreUnknown => {
+                let mut command = command.disable_help_flag(true);
+                loop {
+                    let error = match command.try_get_matches_from_mut(args.iter()) {
+                        Ok(matches) => return Ok(matches),
+                        Err(error) if error.kind() == clap::error::ErrorKind::UnknownArgument => error,
+                        Err(error) => return Err(error),
+                    };
+                    // 逐个移除 clap 报告的参数（`--name` 或 `--name=value`）后重新解析
+                    let position = match error.get(ContextKind::InvalidArg) {
+                        Some(ContextValue::String(unknown)) => args.iter().skip(1).position(|arg| {
+                            arg == unknown || arg.strip_prefix(unknown.as_str()).is_some_and(|rest| rest.starts_with('='))
+                        }),
+                        _ => None,
+                    };
+                    match position {
+                        Some(position) => args.remove(position + 1),
+                        None => return Err(error),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// 由字段生成的命令行参数
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone)]
+pub struct FieldArg {
+    /// 对应的配置键路径，例如 `server.port`
+    pub key: String,
+    /// 参数定义，参数 id 与长选项名相同
+    pub arg: Arg,
+}
+
+#[cfg(feature = "cli")]
+impl FieldArg {
+    /// 参数 id（即长选项名，例如 `server-port`）
+    pub fn id(&self) -> &str {
+        self.arg.get_id().as_str()
+    }
+}
+
+/// 构建包含内置参数的命令
+#[cfg(feature = "cli")]
+pub fn builtin_command(name: impl Into<clap::builder::Str>) -> Command {
+    let command = Command::new(name)
+        .arg(Arg::new("config").long("config").short('c').num_args(1))
+        .arg(Arg::new("config-dir").long("config-dir").num_args(1))
+        .arg(Arg::new("log-level").long("log-level").num_args(1))
+        .arg(Arg::new("verbose").long("verbose").short('v').action(ArgAction::SetTrue))
+        .arg(Arg::new("quiet").long("quiet").short('q').action(ArgAction::SetTrue))
+        .arg(Arg::new("output").long("output").short('o').num_args(1))
+        .arg(Arg::new("format").long("format").num_args(1))
+        .arg(
+            Arg::new("print-config")
+                .long("print-config")
+                .value_name("FORMAT")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("toml")
+                .value_parser(clap::builder::PossibleValuesParser::new(PRINT_FORMATS))
+                .help("Print the effective configuration (secrets redacted) and exit"),
+        )
+        .arg(
+            Arg::new("validate-config")
+                .long("validate-config")
+                .action(ArgAction::SetTrue)
+                .help("Load and validate all configuration sources, print a report and exit"),
+        );
+    #[cfg(feature = "completions")]
+    let command = command.arg(
+        Arg::new("generate-completions")
+            .long("generate-completions")
+            .value_name("SHELL")
+            .num_args(1)
+            .value_parser(clap::value_parser!(clap_complete::Shell))
+            .help("Print a shell completion script and exit"),
+    );
+    #[cfg(feature = "man")]
+    let command = command.arg(
+        Arg::new("generate-man")
+            .long("generate-man")
+            .action(ArgAction::SetTrue)
+            .help("Print a man page in roff format and exit"),
+    );
+    command
+}
+
+/// 处理 `--generate-completions` 与 `--generate-man`：指定时由 `command` 生成补全脚本或手册页，
+/// 写入标准输出后以状态码 0 退出进程，否则直接返回；未启用对应特性时总是直接返回
+///
+/// 补全脚本中的命令名取自程序路径的文件名（`argv[0]`）。
+///
+/// # Errors
+/// 构建命令或写入标准输出失败时返回错误
+#[cfg(feature = "cli")]
+pub fn handle_generate<F>(matches: &ArgMatches, command: F) -> Result<(), QuantumConfigError>
+where
+    F: FnOnce() -> Result<Command, QuantumConfigError>,
+{
+    #[cfg(feature = "completions")]
+    if let Some(shell) = matches.try_get_one::<clap_complete::Shell>("generate-completions").ok().flatten() {
+        let bin_name = crate::context::args()
+            .first()
+            .and_then(|program| std::path::Path::new(program).file_name()?.to_str().map(str::to_string))
+            .unwrap_or_else(|| "app".to_string());
+        write_completions(command()?, *shell, &bin_name, &mut std::io::stdout())?;
+        std::process::exit(0);
+    }
+    #[cfg(feature = "man")]
+    if matches.try_get_one::<bool>("generate-man").ok().flatten().copied().unwrap_or(false) {
+        write_man(command()?, &mut std::io::stdout())?;
+        std::process::exit(0);
+    }
+    let _ = (matches, command);
+    Ok(())
+}
+
+/// 为 `command` 生成 `shell` 的补全脚本
+///
+/// # Errors
+/// 写入失败时返回错误
+#[cfg(feature = "completions")]
+pub fn write_completions(
+    mut command: Command,
+    shell: clap_complete::Shell,
+    bin_name: &str,
+    out: &mut dyn std::io::Write,
+) -> Result<(), QuantumConfigError> {
+    let mut buffer = Vec::new();
+    clap_complete::generate(shell, &mut command, bin_name, &mut buffer);
+    out.write_all(&buffer).map_err(stdout_error)
+}
+
+/// 为 `command` 生成 roff 格式的手册页
+///
+/// # Errors
+/// 写入失败时返回错误
+#[cfg(feature = "man")]
+pub fn write_man(command: Command, out: &mut dyn std::io::Write) -> Result<(), QuantumConfigError> {
+    clap_mangen::Man::new(command).render(out).map_err(stdout_error)
+}
+
+#[cfg(any(feature = "completions", feature = "man"))]
+fn stdout_error(source: std::io::Error) -> QuantumConfigError {
+    QuantumConfigError::Io {
+        source,
+        path: std::path::PathBuf::from("<stdout>"),
+    }
+}
+
+/// 处理 `--validate-config`：指定时调用 `load` 加载并校验全部来源，输出报告后退出进程，否则直接返回
+///
+/// 校验通过（可以有警告）时以状态码 0 退出，否则以 1 退出。
+#[cfg(feature = "cli")]
+pub fn handle_validate_config<T, F>(matches: &ArgMatches, load: F)
+where
+    F: FnOnce() -> Result<(T, ConfigReport), QuantumConfigError>,
+{
+    if !matches.try_get_one::<bool>("validate-config").ok().flatten().copied().unwrap_or(false) {
+        return;
+    }
+    let report = LoadCheckReport::from_result(load());
+    print!("{}", report);
+    std::process::exit(if report.is_ok() { 0 } else { 1 });
+}
+
+/// 处理 `--print-config`：指定时输出生效配置并以状态码 0 退出进程，否则直接返回
+///
+/// # Errors
+/// 配置无法按所选格式渲染时返回错误
+#[cfg(feature = "cli")]
+pub fn handle_print_config<T: Config>(matches: &ArgMatches, config: &T) -> Result<(), QuantumConfigError> {
+    let Some(format) = matches.try_get_one::<String>("print-config").ok().flatten() else {
+        return Ok(());
+    };
+    print!("{}", render_effective_config(config, format)?);
+    std::process::exit(0);
+}
+
+/// 按格式名渲染生效配置，敏感键显示为 `[REDACTED]`
+///
+/// # Errors
+/// 格式名无法识别或配置无法按该格式渲染时返回错误
+pub fn render_effective_config<T: Config>(config: &T, format: &str) -> Result<String, QuantumConfigError> {
+    let value = Redacted::new(config).to_value()?;
+    if matches!(format, "yaml" | "yml") {
+        return Ok(crate::template::render_yaml(&value, &[]));
+    }
+    let file_format = FileFormat::from_extension(format)
+        .ok_or_else(|| QuantumConfigError::Internal(format!("Unsupported output format: {}", format)))?;
+    match file_format {
+        FileFormat::Json => crate::template::render_template(&value, file_format, &[]).map(|json| json + "\n"),
+        // TOML 与 INI 无法表示 null
+        _ => crate::template::render_template(&crate::persist::strip_nulls(value), file_format, &[]),
+    }
+}
+
+/// 构建包含内置参数与 `T` 各字段参数的命令
+#[cfg(feature = "cli")]
+pub fn config_command<T: Config>(name: impl Into<clap::builder::Str>) -> Result<Command, QuantumConfigError> {
+    Ok(builtin_command(name).args(field_args::<T>()?.into_iter().map(|field| field.arg)))
+}
+
+/// 向应用已有的命令添加内置参数与 `T` 各字段的参数
+///
+/// 与已有参数的 id、长选项或短选项相同的内置参数不添加，应用自己的定义优先。
+/// `--generate-completions` 与 `--generate-man` 也不添加：它们需要应用完整的命令，
+/// 应用可以用自己的命令调用 [`write_completions`] / [`write_man`]。
+///
+/// # Errors
+/// 字段参数与已有参数冲突（id、长选项、短选项或位置参数序号相同）时返回 [`QuantumConfigError::InvalidValue`]，
+/// 否则该字段将无法从命令行设置。可用 `#[serde(rename = "...")]` 改名或 `#[config(no_cli)]` 排除该字段。
+#[cfg(feature = "cli")]
+pub fn augment_command<T: Config>(command: Command) -> Result<Command, QuantumConfigError> {
+    let builtin = builtin_command("")
+        .get_arguments()
+        .filter(|arg| !matches!(arg.get_id().as_str(), "generate-completions" | "generate-man"))
+        .cloned()
+        .collect::<Vec<_>>();
+    let command = builtin
+        .into_iter()
+        .fold(command, |command, arg| if conflicts(&command, &arg) { command } else { command.arg(arg) });
+    field_args::<T>()?.into_iter().try_fold(command, |command, field| {
+        if conflicts(&command, &field.arg) {
+            return Err(QuantumConfigError::InvalidValue {
+                message: format!("command line argument '{}' conflicts with an existing argument of the command", field.id()),
+                key_path: field.key,
+            });
+        }
+        Ok(command.arg(field.arg))
+    })
+}
+
+/// `arg` 的 id、长选项、短选项或位置参数序号是否已被命令中的参数使用
+#[cfg(feature = "cli")]
+fn conflicts(command: &Command, arg: &Arg) -> bool {
+    command.get_arguments().any(|existing| {
+        existing.get_id() == arg.get_id()
+            || (arg.get_long().is_some() && existing.get_long() == arg.get_long())
+            || (arg.get_short().is_some() && existing.get_short() == arg.get_short())
+            || (arg.get_index().is_some() && existing.get_index() == arg.get_index())
+    })
+}
+
+/// `T` 各字段的参数 id 到配置键路径的映射
+#[cfg(feature = "cli")]
+pub fn cli_mappings<T: Config>() -> Result<Vec<(String, String)>, QuantumConfigError> {
+    Ok(field_args::<T>()?
+        .into_iter()
+        .map(|field| (field.id().to_string(), field.key))
+        .collect())
+}
+
+/// 按 `T` 的 [`CliMode`] 解析进程的命令行参数（取自 [`crate::context::args`]）
+///
+/// 参数无法解析或指定了 `--help`、`--version` 时由 clap 输出信息后退出进程。
+#[cfg(feature = "cli")]
+pub fn process_matches<T: Config>(command: Command) -> ArgMatches {
+    T::struct_meta()
+        .cli_mode
+        .parse(command, crate::context::args())
+        .unwrap_or_else(|e| e.exit())
+}
+
+/// 按 `T` 的 [`CliMode`] 解析给定的命令行参数（第一个元素为程序名），允许未定义的子命令
+///
+/// # Errors
+/// 参数无法解析（包括 `--help` 等由 clap 处理的参数）时返回 [`QuantumConfigError::Internal`]
+#[cfg(feature = "cli")]
+pub fn matches_from_args<T: Config>(command: Command, args: Vec<String>) -> Result<ArgMatches, QuantumConfigError> {
+    T::struct_meta()
+        .cli_mode
+        .parse(command.allow_external_subcommands(true), args)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to parse CLI args: {}", e)))
+}
+
+/// 将解析结果加入构建器：注册 `T` 各字段参数的映射，`--config` 按协议分派，只合并已映射的参数
+///
+/// # Errors
+/// `Default` 值无法序列化时返回错误
+#[cfg(feature = "cli")]
+pub fn apply_matches<T: Config>(
+    mut builder: QuantumConfigBuilder<T>,
+    matches: ArgMatches,
+) -> Result<QuantumConfigBuilder<T>, QuantumConfigError> {
+    for (arg, key) in cli_mappings::<T>()? {
+        builder = builder.with_cli_mapping(arg, key);
+    }
+    // --config 按协议分派：本地路径（含 file://）并入文件列表，其余来源单独合并
+    if let Some(cfg) = matches.get_one::<String>("config") {
+        builder = builder.with_config_source(cfg.clone());
+    }
+    // 解析结果可能来自应用自己的命令（load_with_matches），只读取宏生成的参数
+    Ok(builder.with_cli(matches).with_cli_mapped_args_only())
+}
+
+/// 未启用 `cli` 特性时代替 [`clap::ArgMatches`] 的占位类型，不包含任何参数
+#[cfg(not(feature = "cli"))]
+#[derive(Debug, Clone, Default)]
+pub struct ArgMatches;
+
+/// 未启用 `cli` 特性时代替 [`clap::Command`] 的占位类型
+#[cfg(not(feature = "cli"))]
+#[derive(Debug, Clone, Default)]
+pub struct Command;
+
+/// 未启用 `cli` 特性时不生成命令
+#[cfg(not(feature = "cli"))]
+pub fn config_command<T: Config>(name: &str) -> Result<Command, QuantumConfigError> {
+    let _ = name;
+    Ok(Command)
+}
+
+/// 未启用 `cli` 特性时不读取进程的命令行参数
+#[cfg(not(feature = "cli"))]
+pub fn process_matches<T: Config>(command: Command) -> ArgMatches {
+    let _ = command;
+    ArgMatches
+}
+
+/// 未启用 `cli` 特性时只接受程序名
+///
+/// # Errors
+/// 除程序名外还有其它参数时返回 [`QuantumConfigError::Internal`]
+#[cfg(not(feature = "cli"))]
+pub fn matches_from_args<T: Config>(command: Command, args: Vec<String>) -> Result<ArgMatches, QuantumConfigError> {
+    let _ = command;
+    if args.len() > 1 {
+        return Err(QuantumConfigError::Internal(
+            "Failed to parse CLI args: command line arguments require the `cli` feature of quantum_config".to_string(),
+        ));
+    }
+    Ok(ArgMatches)
+}
+
+/// 未启用 `cli` 特性时不合并命令行参数
+#[cfg(not(feature = "cli"))]
+pub fn apply_matches<T: Config>(
+    builder: QuantumConfigBuilder<T>,
+    matches: ArgMatches,
+) -> Result<QuantumConfigBuilder<T>, QuantumConfigError> {
+    let _ = matches;
+    Ok(builder)
+}
+
+/// 未启用 `cli` 特性时没有 `--generate-completions` 与 `--generate-man`
+#[cfg(not(feature = "cli"))]
+pub fn handle_generate<F>(matches: &ArgMatches, command: F) -> Result<(), QuantumConfigError>
+where
+    F: FnOnce() -> Result<Command, QuantumConfigError>,
+{
+    let _ = (matches, command);
+    Ok(())
+}
+
+/// 未启用 `cli` 特性时没有 `--validate-config`
+#[cfg(not(feature = "cli"))]
+pub fn handle_validate_config<T, F>(matches: &ArgMatches, load: F)
+where
+    F: FnOnce() -> Result<(T, ConfigReport), QuantumConfigError>,
+{
+    let _ = (matches, load);
+}
+
+/// 未启用 `cli` 特性时没有 `--print-config`
+///
+/// # Errors
+/// 不返回错误
+#[cfg(not(feature = "cli"))]
+pub fn handle_print_config<T: Config>(matches: &ArgMatches, config: &T) -> Result<(), QuantumConfigError> {
+    let _ = (matches, config);
+    Ok(())
+}
+
+/// 由 `T` 的字段元数据与默认值生成字段参数
+///
+/// 嵌套结构体中不允许来自命令行参数的键（参见 [`Config::source_restrictions`]）同样不生成参数。
+#[cfg(feature = "cli")]
+pub fn field_args<T: Config>() -> Result<Vec<FieldArg>, QuantumConfigError> {
+    let no_cli: Vec<String> = T::source_restrictions()
+        .into_iter()
+        .filter(|(_, allowed)| !allowed.contains(&SourceKind::Cli))
+        .map(|(key, _)| key)
+        .collect();
+    let mut args = field_args_from_meta(&T::struct_meta(), &T::defaults_as_value()?);
+    args.retain(|arg| !no_cli.iter().any(|key| crate::restrict::covers(key, &arg.key)));
+    Ok(args)
+}
+
+/// 由字段元数据与默认值（`Self::default()` 的 JSON 表示）生成字段参数
+///
+/// [`CliMode::IgnoreUnknown`] 时 `cli_positional` 字段仍生成长选项：被跳过的应用选项的取值会留在参数中，
+/// 绑定为位置参数会被误当作字段的值。
+#[cfg(feature = "cli")]
+pub fn field_args_from_meta(meta: &StructMeta, defaults: &JsonValue) -> Vec<FieldArg> {
+    let style = meta.cli_nested_style;
+    let bind_positionals = meta.cli_mode != CliMode::IgnoreUnknown;
+    let mut args = Vec::new();
+    let mut positionals = 0;
+    // 不允许来自命令行参数的字段不生成参数
+    for field in meta.non_skipped_fields().filter(|field| !field.is_flatten && field.allows_source(SourceKind::Cli)) {
+        let key = field.config_key_name();
+        let default = defaults.get(key).unwrap_or(&JsonValue::Null);
+        let long = field.clap_long_override.map(str::to_string).unwrap_or_else(|| style.long_name(key));
+        let doc = field.doc_comment.or(field.description);
+        if let JsonValue::Object(nested) = default {
+            nested_args(key, &long, nested, style, &mut args);
+        } else if let Some(kind) =
+            ValueKind::from_type_name(field.type_name_str).or_else(|| ValueKind::from_value(default))
+        {
+            push_arg(&mut args, key.to_string(), long, kind, doc);
+            if let Some(last) = args.last_mut().filter(|last| bind_positionals && field.is_positional && last.key == key) {
+                positionals += 1;
+                last.arg = positional(last.arg.clone(), positionals, kind.repeated);
+            }
+        }
+    }
+    args
+}
+
+/// 把参数改为第 `index` 个位置参数（从 1 开始），参数 id 不变；`Vec<T>` 字段接受多个值
+#[cfg(feature = "cli")]
+fn positional(arg: Arg, index: usize, repeated: bool) -> Arg {
+    let arg = arg.long(None).index(index);
+    if repeated {
+        arg.num_args(1..)
+    } else {
+        arg.num_args(1).default_missing_value(None)
+    }
+}
+
+/// 为嵌套结构体的字段生成参数，类型按默认值推断
+#[cfg(feature = "cli")]
+fn nested_args(
+    key: &str,
+    long: &str,
+    nested: &serde_json::Map<String, JsonValue>,
+    style: CliNestedStyle,
+    args: &mut Vec<FieldArg>,
+) {
+    for (child, value) in nested {
+        let child_key = format!("{}.{}", key, child);
+        let child_long = format!("{}{}{}", long, style.separator(), long_name(child));
+        match value {
+            JsonValue::Object(grandchildren) => nested_args(&child_key, &child_long, grandchildren, style, args),
+            value => {
+                if let Some(kind) = ValueKind::from_value(value) {
+                    push_arg(args, child_key, child_long, kind, None);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+fn push_arg(args: &mut Vec<FieldArg>, key: String, long: String, kind: ValueKind, doc: Option<&'static str>) {
+    if BUILTIN_ARGS.contains(&long.as_str()) || args.iter().any(|field| field.id() == long) {
+        return;
+    }
+    let value_name = kind
+        .scalar
+        .value_name()
+        .map(str::to_string)
+        .unwrap_or_else(|| key.rsplit('.').next().unwrap_or(&key).to_uppercase());
+    let mut arg = kind.scalar.apply(Arg::new(long.clone()).long(long).value_name(value_name));
+    arg = if kind.repeated {
+        arg.action(ArgAction::Append)
+    } else if kind.scalar == Scalar::Bool {
+        // 单独的 `--flag` 等价于 `--flag true`
+        arg.num_args(0..=1).default_missing_value("true")
+    } else {
+        arg.num_args(1)
+    };
+    arg = match doc {
+        Some(doc) => {
+            let summary = doc.lines().next().unwrap_or_default().to_string();
+            let arg = arg.help(summary);
+            if doc.contains('\n') { arg.long_help(doc) } else { arg }
+        }
+        None => arg.help(format!("Sets the `{}` configuration value", key)),
+    };
+    args.push(FieldArg { key, arg });
+}
+
+/// 键名片段转换为长选项名
+fn long_name(key: &str) -> String {
+    key.replace('_', "-")
+}
+
+/// 参数取值类型
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ValueKind {
+    scalar: Scalar,
+    /// 是否为 `Vec<T>`，参数可以重复出现
+    repeated: bool,
+}
+
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Scalar {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    Usize,
+    I8,
+    I16,
+    I32,
+    I64,
+    Isize,
+    Float,
+    Char,
+    Path,
+    Text,
+}
+
+#[cfg(feature = "cli")]
+impl ValueKind {
+    /// 由字段类型的字符串表示推断，`Option<T>` 取 `T`；无法识别的类型返回 `None`
+    fn from_type_name(type_name: &str) -> Option<Self> {
+        let compact: String = type_name.chars().filter(|c| !c.is_whitespace()).collect();
+        let inner = generic_argument(&compact, "Option").unwrap_or(&compact);
+        match generic_argument(inner, "Vec") {
+            Some(element) => Scalar::from_type_name(element).map(|scalar| Self { scalar, repeated: true }),
+            None => Scalar::from_type_name(inner).map(|scalar| Self { scalar, repeated: false }),
+        }
+    }
+
+    /// 由默认值推断（用于嵌套字段及无法识别类型的字段）；`null`、空数组与对象数组返回 `None`
+    fn from_value(value: &JsonValue) -> Option<Self> {
+        match value {
+            JsonValue::Array(items) => items
+                .first()
+                .and_then(Scalar::from_value)
+                .map(|scalar| Self { scalar, repeated: true }),
+            value => Scalar::from_value(value).map(|scalar| Self { scalar, repeated: false }),
+        }
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Scalar {
+    fn from_type_name(type_name: &str) -> Option<Self> {
+        let name = type_name.rsplit("::").next().unwrap_or(type_name);
+        Some(match name {
+            "bool" => Self::Bool,
+            "u8" => Self::U8,
+            "u16" => Self::U16,
+            "u32" => Self::U32,
+            "u64" => Self::U64,
+            "usize" => Self::Usize,
+            "i8" => Self::I8,
+            "i16" => Self::I16,
+            "i32" => Self::I32,
+            "i64" => Self::I64,
+            "isize" => Self::Isize,
+            "f32" | "f64" => Self::Float,
+            "char" => Self::Char,
+            "PathBuf" => Self::Path,
+            "String" => Self::Text,
+            _ => return None,
+        })
+    }
+
+    fn from_value(value: &JsonValue) -> Option<Self> {
+        match value {
+            JsonValue::Bool(_) => Some(Self::Bool),
+            JsonValue::Number(n) if n.is_u64() => Some(Self::U64),
+            JsonValue::Number(n) if n.is_i64() => Some(Self::I64),
+            JsonValue::Number(_) => Some(Self::Float),
+            JsonValue::String(_) => Some(Self::Text),
+            _ => None,
+        }
+    }
+
+    /// 按类型取 `value_name`，字符串类型返回 `None`（使用字段名）
+    fn value_name(self) -> Option<&'static str> {
+        match self {
+            Self::Bool => Some("BOOL"),
+            Self::Float => Some("FLOAT"),
+            Self::Char => Some("CHAR"),
+            Self::Path => Some("PATH"),
+            Self::Text => None,
+            _ => Some("NUM"),
+        }
+    }
+
+    /// 设置值解析器：按类型校验，校验通过后仍保存原始字符串
+    fn apply(self, arg: Arg) -> Arg {
+        match self {
+            Self::Bool => arg.value_parser(validated::<bool>),
+            Self::U8 => arg.value_parser(validated::<u8>),
+            Self::U16 => arg.value_parser(validated::<u16>),
+            Self::U32 => arg.value_parser(validated::<u32>),
+            Self::U64 => arg.value_parser(validated::<u64>),
+            Self::Usize => arg.value_parser(validated::<usize>),
+            Self::I8 => arg.value_parser(validated::<i8>),
+            Self::I16 => arg.value_parser(validated::<i16>),
+            Self::I32 => arg.value_parser(validated::<i32>),
+            Self::I64 => arg.value_parser(validated::<i64>),
+            Self::Isize => arg.value_parser(validated::<isize>),
+            Self::Float => arg.value_parser(validated::<f64>),
+            Self::Char => arg.value_parser(validated::<char>),
+            Self::Path => arg.value_hint(clap::ValueHint::AnyPath),
+            Self::Text => arg,
+        }
+    }
+}
+
+/// 取出 `Wrapper<T>` 中的 `T`
+#[cfg(feature = "cli")]
+fn generic_argument<'a>(type_name: &'a str, wrapper: &str) -> Option<&'a str> {
+    let (path, argument) = type_name.split_once('<')?;
+    (path.rsplit("::").next() == Some(wrapper)).then_some(())?;
+    argument.strip_suffix('>')
+}
+
+/// 校验取值可以解析为 `T`
+#[cfg(feature = "cli")]
+fn validated<T>(value: &str) -> Result<String, String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    value.parse::<T>().map(|_| value.to_string()).map_err(|e| e.to_string())
+}
+
+#[cfg(all(test, feature = "cli"))]
+mod tests {
+    use super::*;
+    use crate::meta::FieldMeta;
+    use serde_json::json;
+
+    fn sample_meta() -> StructMeta {
+        let mut meta = StructMeta::new("AppConfig", true);
+        let mut port = FieldMeta::new("port", "u16");
+        port.doc_comment = Some("Port to listen on\n\nMust be free.");
+        meta.add_field(port);
+        meta.add_field(FieldMeta::new("debug", "bool"));
+        meta.add_field(FieldMeta::new("log_level", "String"));
+        meta.add_field(FieldMeta::new("tags", "Vec < String >"));
+        meta.add_field(FieldMeta::new("timeout", "Option < f64 >"));
+        meta.add_field(FieldMeta::new("server", "ServerConfig"));
+        let mut skipped = FieldMeta::new("internal", "String");
+        skipped.is_skipped = true;
+        meta.add_field(skipped);
+        meta
+    }
+
+    fn command() -> (Command, Vec<FieldArg>) {
+        let defaults = json!({
+            "port": 8080,
+            "debug": false,
+            "log_level": "info",
+            "tags": [],
+            "timeout": null,
+            "server": {"host": "localhost", "max_connections": 100, "tls": {"enabled": false}},
+            "internal": "x",
+        });
+        let args = field_args_from_meta(&sample_meta(), &defaults);
+        let command = builtin_command("app").args(args.iter().map(|field| field.arg.clone()));
+        (command, args)
+    }
+
+    #[test]
+    fn test_field_args_cover_nested_paths() {
+        let (command, args) = command();
+        command.clone().debug_assert();
+        let ids: Vec<(&str, &str)> = args.iter().map(|field| (field.id(), field.key.as_str())).collect();
+        assert_eq!(
+            ids,
+            [
+                ("port", "port"),
+                ("debug", "debug"),
+                ("tags", "tags"),
+                ("timeout", "timeout"),
+                ("server-host", "server.host"),
+                ("server-max-connections", "server.max_connections"),
+                ("server-tls-enabled", "server.tls.enabled"),
+            ]
+        );
+
+        let port = command.get_arguments().find(|arg| arg.get_id() == "port").unwrap();
+        assert_eq!(port.get_help().unwrap().to_string(), "Port to listen on");
+        assert_eq!(port.get_long_help().unwrap().to_string(), "Port to listen on\n\nMust be free.");
+        assert_eq!(port.get_value_names().unwrap()[0].as_str(), "NUM");
+        let host = command.get_arguments().find(|arg| arg.get_id() == "server-host").unwrap();
+        assert_eq!(host.get_value_names().unwrap()[0].as_str(), "HOST");
+        assert_eq!(host.get_help().unwrap().to_string(), "Sets the `server.host` configuration value");
+    }
+
+    #[test]
+    fn test_value_parsers_validate_by_type() {
+        let (command, _) = command();
+        let matches = command
+            .clone()
+            .try_get_matches_from(["app", "--port", "9090", "--debug", "--tags", "a", "--tags", "b", "--server-tls-enabled", "true"])
+            .unwrap();
+        assert_eq!(matches.get_one::<String>("port").map(String::as_str), Some("9090"));
+        assert_eq!(matches.get_one::<String>("debug").map(String::as_str), Some("true"));
+        assert_eq!(matches.get_many::<String>("tags").unwrap().collect::<Vec<_>>(), ["a", "b"]);
+
+        assert!(command.clone().try_get_matches_from(["app", "--port", "70000"]).is_err());
+        assert!(command.clone().try_get_matches_from(["app", "--timeout", "soon"]).is_err());
+        assert!(command.try_get_matches_from(["app", "--server-max-connections", "-1"]).is_err());
+    }
+
+    #[test]
+    fn test_value_kind_from_type_name() {
+        let kind = |name| ValueKind::from_type_name(name);
+        assert_eq!(kind("Option < std :: path :: PathBuf >"), Some(ValueKind { scalar: Scalar::Path, repeated: false }));
+        assert_eq!(kind("Vec < u8 >"), Some(ValueKind { scalar: Scalar::U8, repeated: true }));
+        assert_eq!(kind("std :: vec :: Vec < std :: path :: PathBuf >"), Some(ValueKind { scalar: Scalar::Path, repeated: true }));
+        assert_eq!(kind("HashMap < String , String >"), None);
+        assert_eq!(kind("ServerConfig"), None);
+    }
+
+    #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+    struct PrintedConfig {
+        name: String,
+        password: String,
+        timeout: Option<u32>,
+        hosts: Vec<String>,
+        server: PrintedServer,
+    }
+
+    #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+    struct PrintedServer {
+        port: u16,
+        labels: std::collections::BTreeMap<String, String>,
+    }
+
+    impl Config for PrintedConfig {
+        fn load() -> Result<Self, QuantumConfigError> {
+            Ok(Self::default())
+        }
+
+        fn load_with_args(_args: Vec<String>) -> Result<Self, QuantumConfigError> {
+            Ok(Self::default())
+        }
+
+        fn secret_keys() -> Vec<String> {
+            vec!["password".to_string()]
+        }
+    }
+
+    #[test]
+    fn test_render_effective_config_redacts_secrets() {
+        let config = PrintedConfig {
+            name: "yes".to_string(),
+            password: "hunter2".to_string(),
+            timeout: None,
+            hosts: vec!["a".to_string(), "b".to_string()],
+            server: PrintedServer {
+                port: 8080,
+                labels: [("app.kubernetes.io/name".to_string(), "svc".to_string())].into_iter().collect(),
+            },
+        };
+
+        let yaml = render_effective_config(&config, "yaml").unwrap();
+        assert_eq!(
+            yaml,
+            "hosts:\n  - \"a\"\n  - \"b\"\nname: \"yes\"\npassword: \"[REDACTED]\"\n\
+             server:\n  labels:\n    \"app.kubernetes.io/name\": \"svc\"\n  port: 8080\ntimeout: null\n"
+        );
+
+        let toml = render_effective_config(&config, "toml").unwrap();
+        assert!(toml.contains("password = \"[REDACTED]\"") && !toml.contains("timeout"), "{}", toml);
+        let json: JsonValue = serde_json::from_str(&render_effective_config(&config, "json").unwrap()).unwrap();
+        assert_eq!(json["server"]["port"], 8080);
+        assert!(!render_effective_config(&config, "json").unwrap().contains("hunter2"));
+        assert!(render_effective_config(&config, "yml").is_ok());
+        assert!(render_effective_config(&config, "csv").is_err());
+    }
+
+    #[test]
+    fn test_yaml_output_quotes_ambiguous_keys() {
+        #[derive(Default, serde::Serialize, serde::Deserialize)]
+        struct Flags {
+            #[serde(flatten)]
+            flags: std::collections::BTreeMap<String, String>,
+        }
+
+        impl Config for Flags {
+            fn load() -> Result<Self, QuantumConfigError> {
+                Ok(Self::default())
+            }
+
+            fn load_with_args(_args: Vec<String>) -> Result<Self, QuantumConfigError> {
+                Self::load()
+            }
+        }
+
+        let keys = ["yes", "No", "on", "off", "null", "1", "2fa", "y", "_private", "log-level", "offset"];
+        let config = Flags { flags: keys.iter().map(|key| (key.to_string(), "v".to_string())).collect() };
+        let yaml = render_effective_config(&config, "yaml").unwrap();
+        assert_eq!(
+            yaml,
+            "\"1\": \"v\"\n\"2fa\": \"v\"\n\"No\": \"v\"\n_private: \"v\"\nlog-level: \"v\"\n\"null\": \"v\"\n\
+             \"off\": \"v\"\noffset: \"v\"\n\"on\": \"v\"\n\"y\": \"v\"\n\"yes\": \"v\"\n"
+        );
+    }
+
+    #[test]
+    fn test_print_config_flag_defaults_to_toml() {
+        let command = || builtin_command("app");
+        let matches = command().try_get_matches_from(["app", "--print-config"]).unwrap();
+        assert_eq!(matches.get_one::<String>("print-config").map(String::as_str), Some("toml"));
+        let matches = command().try_get_matches_from(["app", "--print-config=yaml"]).unwrap();
+        assert_eq!(matches.get_one::<String>("print-config").map(String::as_str), Some("yaml"));
+        let matches = command().try_get_matches_from(["app"]).unwrap();
+        assert!(handle_print_config(&matches, &PrintedConfig::default()).is_ok());
+        handle_validate_config(&matches, || -> Result<(PrintedConfig, ConfigReport), _> {
+            panic!("sources are only loaded for --validate-config")
+        });
+        assert!(command().try_get_matches_from(["app", "--print-config=csv"]).is_err());
+    }
+
+    #[test]
+    fn test_load_check_report_from_result() {
+        let mut report = ConfigReport {
+            layers: vec!["default".to_string(), "environment variables".to_string()],
+            ..ConfigReport::default()
+        };
+        report.deprecations.push(crate::deprecated::DeprecationWarning {
+            key: "old_port".to_string(),
+            message: "use `port`".to_string(),
+            source: crate::ValueSource::Env("APP_OLD_PORT".to_string()),
+            location: None,
+        });
+        let valid = LoadCheckReport::from_result(Ok((PrintedConfig::default(), report)));
+        assert!(valid.is_ok());
+        assert_eq!(valid.warnings[0].code, "deprecated_key");
+        assert!(valid.to_string().starts_with("configuration is valid: 1 warning(s)\n  source: default\n"), "{}", valid);
+
+        let error = QuantumConfigError::ValidationFailed {
+            errors: vec![crate::ValidationError::new("port", "must be between 1 and 65535")],
+        };
+        let invalid = LoadCheckReport::from_result::<PrintedConfig>(Err(error));
+        assert!(!invalid.is_ok() && invalid.sources.is_empty());
+        assert_eq!(invalid.errors[0].key_path.as_deref(), Some("port"));
+        assert!(invalid.to_string().contains("error: port: must be between 1 and 65535"), "{}", invalid);
+    }
+
+    #[cfg(feature = "completions")]
+    #[test]
+    fn test_generate_completions_for_field_flags() {
+        let (command, _) = command();
+        let matches = command.clone().try_get_matches_from(["app", "--generate-completions", "bash"]).unwrap();
+        let shell = *matches.get_one::<clap_complete::Shell>("generate-completions").unwrap();
+        let mut script = Vec::new();
+        write_completions(command.clone(), shell, "myapp", &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains("myapp") && script.contains("--server-max-connections"), "{}", script);
+        assert!(command.try_get_matches_from(["app", "--generate-completions", "cmd.exe"]).is_err());
+    }
+
+    #[cfg(feature = "man")]
+    #[test]
+    fn test_generate_man_page_lists_field_flags() {
+        let (command, _) = command();
+        let mut page = Vec::new();
+        write_man(command, &mut page).unwrap();
+        let page = String::from_utf8(page).unwrap();
+        assert!(page.starts_with(".ie"), "{}", page);
+        assert!(page.contains("Port to listen on") && page.contains("server\\-host"), "{}", page);
+    }
+
+    #[test]
+    fn test_dot_style_nested_args_map_to_key_paths() {
+        let mut meta = sample_meta();
+        meta.cli_nested_style = CliNestedStyle::Dot;
+        let defaults = json!({"log_level": "info", "server": {"host": "localhost", "tls": {"cert_file": "a.pem"}}});
+        let args = field_args_from_meta(&meta, &defaults);
+        let ids: Vec<(&str, &str)> = args.iter().map(|field| (field.id(), field.key.as_str())).collect();
+        assert_eq!(
+            ids,
+            [
+                ("port", "port"),
+                ("debug", "debug"),
+                ("tags", "tags"),
+                ("timeout", "timeout"),
+                ("server.host", "server.host"),
+                ("server.tls.cert-file", "server.tls.cert_file"),
+            ]
+        );
+
+        let command = builtin_command("app").args(args.iter().map(|field| field.arg.clone()));
+        command.clone().debug_assert();
+        let matches = command.try_get_matches_from(["app", "--server.tls.cert-file", "b.pem"]).unwrap();
+        let mapping = args.iter().map(|field| (field.id().to_string(), field.key.clone())).collect();
+        let value: JsonValue = figment::Figment::from(crate::providers::QuantumConfigClapProvider::new(
+            matches,
+            mapping,
+            ".".to_string(),
+        ))
+        .extract()
+        .unwrap();
+        assert_eq!(value, json!({"server": {"tls": {"cert_file": "b.pem"}}}));
+        assert_eq!(CliNestedStyle::Dash.long_name("server.max_connections"), "server-max-connections");
+    }
+
+    #[test]
+    fn test_positional_fields_bind_to_indexed_args() {
+        let mut meta = StructMeta::new("AppConfig", true);
+        let mut input = FieldMeta::new("input_file", "PathBuf");
+        input.is_positional = true;
+        meta.add_field(input);
+        meta.add_field(FieldMeta::new("port", "u16"));
+        let mut extra = FieldMeta::new("extra", "Vec < String >");
+        extra.is_positional = true;
+        meta.add_field(extra);
+        let args = field_args_from_meta(&meta, &json!({"input_file": "", "port": 80, "extra": []}));
+        let indexes: Vec<Option<usize>> = args.iter().map(|field| field.arg.get_index()).collect();
+        assert_eq!(indexes, [Some(1), None, Some(2)]);
+
+        let command = builtin_command("app").args(args.iter().map(|field| field.arg.clone()));
+        command.clone().debug_assert();
+        let matches = command
+            .clone()
+            .try_get_matches_from(["app", "in.txt", "--port", "9", "a", "b"])
+            .unwrap();
+        let mapping = args.iter().map(|field| (field.id().to_string(), field.key.clone())).collect();
+        let value: JsonValue = figment::Figment::from(crate::providers::QuantumConfigClapProvider::new(
+            matches,
+            mapping,
+            ".".to_string(),
+        ))
+        .extract()
+        .unwrap();
+        assert_eq!(value, json!({"input_file": "in.txt", "port": 9, "extra": ["a", "b"]}));
+
+        // 位置参数可以省略，交由其他来源提供
+        let matches = command.try_get_matches_from(["app", "--port", "9"]).unwrap();
+        assert!(!matches.contains_id("input-file"));
+    }
+
+    #[test]
+    fn test_ignore_unknown_mode_does_not_bind_positionals() {
+        let mut meta = StructMeta::new("AppConfig", true);
+        meta.cli_mode = CliMode::IgnoreUnknown;
+        let mut input = FieldMeta::new("input_file", "PathBuf");
+        input.is_positional = true;
+        meta.add_field(input);
+        let args = field_args_from_meta(&meta, &json!({"input_file": ""}));
+        assert_eq!(args[0].arg.get_index(), None);
+        assert_eq!(args[0].arg.get_long(), Some("input-file"));
+
+        let command = || builtin_command("app").args(args.iter().map(|field| field.arg.clone()));
+        let to_args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        // 应用自己选项的取值不会成为字段的值
+        let matches = CliMode::IgnoreUnknown
+            .parse(command(), to_args(&["app", "--app-flag", "value"]))
+            .unwrap();
+        assert!(!matches.contains_id("input-file"));
+        let matches = CliMode::IgnoreUnknown
+            .parse(command(), to_args(&["app", "--app-flag", "value", "--input-file", "in.txt"]))
+            .unwrap();
+        assert_eq!(matches.get_one::<String>("input-file").map(String::as_str), Some("in.txt"));
+    }
+
+    #[test]
+    fn test_augment_command_keeps_existing_args() {
+        let existing = Command::new("app")
+            .arg(Arg::new("input").index(1))
+            .arg(Arg::new("verbosity").short('v').action(ArgAction::Count))
+            .arg(Arg::new("format").long("format").num_args(1));
+        let command = augment_command::<PrintedConfig>(existing).unwrap();
+        command.clone().debug_assert();
+
+        let ids: Vec<&str> = command.get_arguments().map(|arg| arg.get_id().as_str()).collect();
+        assert!(ids.contains(&"config") && ids.contains(&"print-config"));
+        assert!(!ids.contains(&"generate-completions") && !ids.contains(&"generate-man"));
+        // 与应用已有参数冲突的内置参数不添加
+        assert!(!ids.contains(&"verbose"));
+        assert_eq!(ids.iter().filter(|id| **id == "format").count(), 1);
+        let verbose = command.get_arguments().find(|arg| arg.get_id() == "verbosity").unwrap();
+        assert!(matches!(verbose.get_action(), ArgAction::Count));
+    }
+
+    #[test]
+    fn test_cli_mode_ignore_unknown_skips_only_unknown_args() {
+        let port = Arg::new("port").long("port").num_args(1).value_parser(clap::value_parser!(u16));
+        let command = || builtin_command("app").arg(port.clone());
+        let args = |list: &[&str]| list.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        let matches = CliMode::IgnoreUnknown
+            .parse(command(), args(&["app", "--app-only=x", "--help", "--port", "9", "--trailing"]))
+            .unwrap();
+        assert_eq!(matches.get_one::<u16>("port"), Some(&9));
+        assert!(CliMode::Strict.parse(command(), args(&["app", "--app-only"])).is_err());
+        // 取值无效仍是错误
+        assert!(CliMode::IgnoreUnknown.parse(command(), args(&["app", "--port", "x"])).is_err());
+        let matches = CliMode::Disabled.parse(command(), args(&["app", "--port", "9"])).unwrap();
+        assert!(!matches.contains_id("port"));
+    }
+}
+
+#[cfg(all(test, not(feature = "cli")))]
+mod tests {
+    use crate::context::{FixedDirs, LoadContext};
+    use crate::providers::MapEnv;
+    use crate::Config;
+    use serde::{Deserialize, Serialize};
+    use tempfile::TempDir;
+
+    #[derive(Config, Serialize, Deserialize, Debug, Default)]
+    #[config(env_prefix = "QC_NO_CLI_")]
+    struct NoCliConfig {
+        port: u16,
+    }
+
+    #[test]
+    fn test_load_without_cli_feature_ignores_process_args() {
+        let root = TempDir::new().unwrap();
+        let context = LoadContext::new()
+            .with_args(vec!["app".to_string(), "--port".to_string(), "9".to_string()])
+            .with_env(MapEnv::new().with_var("QC_NO_CLI_PORT", "8080"))
+            .with_dirs(FixedDirs::new(root.path()));
+        assert_eq!(NoCliConfig::load_in(&context).unwrap().port, 8080);
+
+        // 明确给出的参数无法解析时报错，而不是静默忽略
+        let args = vec!["app".to_string(), "--port".to_string(), "9".to_string()];
+        assert!(context.run(|| NoCliConfig::load_with_args(args)).is_err());
+        assert!(context.run(|| NoCliConfig::load_with_args(vec!["app".to_string()])).is_ok());
+    }
+}
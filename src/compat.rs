@@ -0,0 +1,127 @@
+//! Lingo 旧名称兼容层
+//!
+//! 项目曾以 Lingo 命名（`LingoError`、`LingoBuilder`、`#[lingo(...)]` 等），
+//! 本模块为迁移中的下游代码保留这些名称，两套名称指向同一类型，可以混用：
+//!
+//! ```ignore
+//! #[allow(deprecated)]
+//! use quantum_config::compat::LingoError;
+//!
+//! fn load() -> Result<AppConfig, LingoError> {
+//!     // `QuantumConfigError` 与 `LingoError` 是同一类型
+//!     AppConfig::load()
+//! }
+//! ```
+//!
+//! 派生宏同样接受 `#[lingo(...)]` 属性（与 `#[config(...)]` 等价），并在依赖方只引入了 `lingo` 包名时
+//! 使用该包名生成代码。
+//!
+//! ## 移除计划
+//!
+//! 旧名称随行为版本（[`crate::QuantumConfigAppMeta::behavior_version`]，派生宏中由
+//! `#[config(behavior_version = N)]` 指定）移除：
+//! - 行为版本 1（默认）：旧名称可用，使用时产生弃用警告
+//! - 行为版本 [`LEGACY_NAMES_REMOVED_IN`] 起：派生宏拒绝 `#[lingo(...)]` 属性，本模块的别名将在下一个次版本中删除
+
+use crate::builder::QuantumConfigBuilder;
+use crate::error::QuantumConfigError;
+use crate::meta::QuantumConfigAppMeta;
+#[cfg(feature = "cli")]
+use crate::providers::QuantumConfigClapProvider;
+use crate::providers::{QuantumConfigEnvProvider, QuantumConfigFileProvider, QuantumConfigFileProviderGeneric};
+
+/// 未指定时使用的行为版本
+pub const DEFAULT_BEHAVIOR_VERSION: u32 = 1;
+
+/// 支持的最高行为版本
+pub const MAX_BEHAVIOR_VERSION: u32 = 2;
+
+/// 不再接受 Lingo 旧名称的行为版本
+pub const LEGACY_NAMES_REMOVED_IN: u32 = 2;
+
+/// 行为版本是否仍接受 Lingo 旧名称
+pub fn legacy_names_allowed(behavior_version: u32) -> bool {
+    behavior_version < LEGACY_NAMES_REMOVED_IN
+}
+
+/// 检查行为版本是否受支持
+pub fn check_behavior_version(behavior_version: u32) -> Result<(), QuantumConfigError> {
+    if (DEFAULT_BEHAVIOR_VERSION..=MAX_BEHAVIOR_VERSION).contains(&behavior_version) {
+        Ok(())
+    } else {
+        Err(QuantumConfigError::UnsupportedBehaviorVersion {
+            version: behavior_version,
+            max: MAX_BEHAVIOR_VERSION,
+        })
+    }
+}
+
+/// [`QuantumConfigError`] 的旧名称
+#[deprecated(since = "0.2.1", note = "use `QuantumConfigError`; Lingo names are removed in behavior_version 2")]
+pub type LingoError = QuantumConfigError;
+
+/// [`QuantumConfigBuilder`] 的旧名称
+#[deprecated(since = "0.2.1", note = "use `QuantumConfigBuilder`; Lingo names are removed in behavior_version 2")]
+pub type LingoBuilder<T> = QuantumConfigBuilder<T>;
+
+/// [`QuantumConfigAppMeta`] 的旧名称
+#[deprecated(since = "0.2.1", note = "use `QuantumConfigAppMeta`; Lingo names are removed in behavior_version 2")]
+pub type LingoAppMeta = QuantumConfigAppMeta;
+
+/// [`QuantumConfigFileProvider`] 的旧名称
+#[deprecated(since = "0.2.1", note = "use `QuantumConfigFileProvider`; Lingo names are removed in behavior_version 2")]
+pub type LingoFileProvider = QuantumConfigFileProvider;
+
+/// [`QuantumConfigFileProviderGeneric`] 的旧名称
+#[deprecated(
+    since = "0.2.1",
+    note = "use `QuantumConfigFileProviderGeneric`; Lingo names are removed in behavior_version 2"
+)]
+pub type LingoFileProviderGeneric<R> = QuantumConfigFileProviderGeneric<R>;
+
+/// [`QuantumConfigEnvProvider`] 的旧名称
+#[deprecated(since = "0.2.1", note = "use `QuantumConfigEnvProvider`; Lingo names are removed in behavior_version 2")]
+pub type LingoEnvProvider = QuantumConfigEnvProvider;
+
+/// [`QuantumConfigClapProvider`] 的旧名称
+#[deprecated(since = "0.2.1", note = "use `QuantumConfigClapProvider`; Lingo names are removed in behavior_version 2")]
+#[cfg(feature = "cli")]
+pub type LingoClapProvider = QuantumConfigClapProvider;
+
+/// 派生宏在结构体使用 `#[lingo(...)]` 属性时引用此常量，以便在依赖方产生弃用警告
+#[doc(hidden)]
+#[deprecated(
+    since = "0.2.1",
+    note = "`#[lingo(...)]` attributes are deprecated, use `#[config(...)]`; they are rejected from behavior_version 2"
+)]
+pub const LINGO_ATTRIBUTE: () = ();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_legacy_names_are_interchangeable() {
+        fn fail() -> Result<(), LingoError> {
+            Err(QuantumConfigError::Internal("boom".to_string()))
+        }
+        let error: QuantumConfigError = fail().unwrap_err();
+        assert_eq!(error.code(), "internal");
+
+        let meta: LingoAppMeta = QuantumConfigAppMeta::default();
+        let builder: LingoBuilder<()> = QuantumConfigBuilder::from_app_meta(meta.clone());
+        assert_eq!(builder.app_meta(), &meta);
+    }
+
+    #[test]
+    fn test_behavior_version_range() {
+        assert!(legacy_names_allowed(DEFAULT_BEHAVIOR_VERSION));
+        assert!(!legacy_names_allowed(LEGACY_NAMES_REMOVED_IN));
+        assert!(check_behavior_version(MAX_BEHAVIOR_VERSION).is_ok());
+
+        let error = check_behavior_version(MAX_BEHAVIOR_VERSION + 1).unwrap_err();
+        assert_eq!(error.code(), "unsupported_behavior_version");
+        assert!(check_behavior_version(0).is_err());
+    }
+}
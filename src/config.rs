@@ -0,0 +1,471 @@
+//! Quantum Config 配置 trait
+//!
+//! `#[derive(Config)]` 会为结构体实现 [`Config`] trait，
+//! 使库代码可以接受任意可配置类型，例如 `fn run<T: quantum_config::Config>()`。
+//!
+//! 派生宏只需实现与来源合并相关的 `load` / `load_with_args`，
+//! 以及由字段属性决定的方法；其余方法由 trait 的默认实现提供。
+
+use crate::builder::SourceKind;
+use crate::context::LoadContext;
+use crate::drift::{self, DriftReport, DriftSeverity};
+use crate::error::QuantumConfigError;
+use crate::explain::{self, Explanation};
+use crate::logging::log_info;
+use crate::meta::StructMeta;
+use crate::namespace;
+use crate::options::LoadOptions;
+use crate::persist;
+use crate::providers::file_provider::FileFormat;
+use crate::providers::QuantumConfigFileProvider;
+use crate::redact::{self, Redacted};
+use crate::report::{ConfigReport, ConfigSources};
+use crate::template;
+use crate::unknown;
+use crate::transform::TransformRegistry;
+use crate::ArgMatches;
+#[cfg(feature = "cli")]
+use clap::Command;
+use figment::Figment;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::Path;
+
+/// `load_from_file` 使用的最大解析深度
+const LOAD_FROM_FILE_MAX_PARSE_DEPTH: u32 = 128;
+
+/// 模板文件的默认输出路径
+const TEMPLATE_PATH: &str = "config.toml.example";
+
+/// 可配置类型
+pub trait Config: Sized + Default + Serialize + DeserializeOwned {
+    /// 从多种来源加载配置
+    ///
+    /// 加载顺序（低 -> 高优先级覆盖）：
+    /// 1. `Default` 实现提供的默认值（派生宏中可用 `#[config(no_defaults)]` 关闭）
+    /// 2. 文件（系统级、用户级、以及 --config 指定的本地或远程文件）
+    /// 3. 工作目录中的 `.env` 文件（存在时）
+    /// 4. 环境变量
+    /// 5. 命令行参数
+    ///
+    /// 派生宏中可用 `#[config(priority(...))]` 调整文件、环境变量与命令行参数之间的顺序。
+    fn load() -> Result<Self, QuantumConfigError>;
+
+    /// 从多种来源加载配置，使用给定的命令行参数（第一个元素为程序名）
+    fn load_with_args(args: Vec<String>) -> Result<Self, QuantumConfigError>;
+
+    /// 从多种来源加载配置，命令行参数取自应用已解析的结果
+    ///
+    /// 用于已有 clap 命令的应用：先用 [`Config::augment_command`] 向命令添加参数，解析后把结果传给本方法，
+    /// 应用自己定义的其它参数被忽略。`--validate-config` 与 `--print-config` 的处理与 [`Config::load`] 相同。
+    ///
+    /// 默认实现无法读取解析结果，忽略 `matches` 并以不含参数的命令行调用 [`Config::load_with_args`]。
+    fn load_with_matches(matches: &ArgMatches) -> Result<Self, QuantumConfigError> {
+        let _ = matches;
+        Self::load_with_args(crate::context::args().into_iter().take(1).collect())
+    }
+
+    /// 向应用已有的命令添加内置参数与各字段的参数，与已有参数冲突的内置参数不添加，参见 [`crate::cli::augment_command`]
+    ///
+    /// # Panics
+    /// `Default` 值无法序列化或字段参数与已有参数冲突时 panic
+    #[cfg(feature = "cli")]
+    fn augment_command(command: Command) -> Command {
+        crate::cli::augment_command::<Self>(command)
+            .unwrap_or_else(|e| panic!("failed to build command line arguments: {}", e))
+    }
+
+    /// 在给定的加载上下文中调用 [`Config::load`]，命令行参数、环境变量与目录均来自上下文，参见 [`crate::context`]
+    fn load_in(context: &LoadContext) -> Result<Self, QuantumConfigError> {
+        context.run(Self::load)
+    }
+
+    /// 从多种来源加载配置，同时返回记录每个键的值来源（默认值、配置文件、环境变量名或命令行参数）的报告
+    ///
+    /// 默认实现无法追踪来源，调用 [`Config::load`] 并返回空报告。
+    fn load_with_report() -> Result<(Self, ConfigReport), QuantumConfigError> {
+        Ok((Self::load()?, ConfigReport::default()))
+    }
+
+    /// 与 [`Config::load_with_report`] 相同，但使用给定的命令行参数
+    fn load_with_report_with_args(args: Vec<String>) -> Result<(Self, ConfigReport), QuantumConfigError> {
+        Ok((Self::load_with_args(args)?, ConfigReport::default()))
+    }
+
+    /// 从多种来源加载配置，没有任何来源提供值而缺少必需键时返回 `Default` 值
+    ///
+    /// 文件解析、类型转换与校验错误照常返回。适合无需任何配置即可运行的命令行工具。
+    fn load_or_default() -> Result<Self, QuantumConfigError> {
+        fallback_to_default(Self::load())
+    }
+
+    /// 与 [`Config::load_or_default`] 相同，但使用给定的命令行参数
+    ///
+    /// 默认实现无法判断是否有来源提供了值，缺少必需键时总是返回 `Default` 值；
+    /// 派生宏生成的实现只在没有任何来源提供值时回退。
+    fn load_or_default_with_args(args: Vec<String>) -> Result<Self, QuantumConfigError> {
+        fallback_to_default(Self::load_with_args(args))
+    }
+
+    /// 尽力加载配置：可恢复的错误对应的键使用默认值，错误作为警告一并返回，参见 [`crate::lossy`]
+    fn load_lossy() -> Result<(Self, Vec<QuantumConfigError>), QuantumConfigError> {
+        lossy_from(Self::load())
+    }
+
+    /// 与 [`Config::load_lossy`] 相同，但使用给定的命令行参数
+    ///
+    /// 默认实现无法逐键恢复，出现可恢复的错误时整体返回 `Default` 值；
+    /// 派生宏生成的实现只将出错的键恢复为默认值。
+    fn load_lossy_with_args(args: Vec<String>) -> Result<(Self, Vec<QuantumConfigError>), QuantumConfigError> {
+        lossy_from(Self::load_with_args(args))
+    }
+
+    /// 在 tokio 阻塞线程池中调用 [`Config::load`]，避免在异步运行时中阻塞读取文件与远程来源
+    ///
+    /// 需要读取自定义异步来源时使用 [`crate::QuantumConfigBuilder::with_async_file`] 与
+    /// [`crate::QuantumConfigBuilder::load_async`]。
+    #[cfg(feature = "async")]
+    fn load_async() -> impl std::future::Future<Output = Result<Self, QuantumConfigError>> + Send
+    where
+        Self: Send + 'static,
+    {
+        async {
+            tokio::task::spawn_blocking(Self::load)
+                .await
+                .map_err(|e| QuantumConfigError::Internal(format!("Configuration loading task failed: {}", e)))?
+        }
+    }
+
+    /// 按给定的加载选项加载配置，例如等待配置卷挂载，参见 [`LoadOptions`]
+    ///
+    /// 默认实现忽略选项并调用 [`Config::load`]。
+    fn load_with_options(options: LoadOptions) -> Result<Self, QuantumConfigError> {
+        let _ = options;
+        Self::load()
+    }
+
+    /// 为指定租户加载配置：在共享配置之上合并租户专属的文件、环境变量与来源，参见 [`LoadOptions::with_tenant`]
+    ///
+    /// 默认实现调用 [`Config::load_with_options`]。
+    fn load_for(tenant: &str) -> Result<Self, QuantumConfigError> {
+        Self::load_with_options(LoadOptions::new().with_tenant(tenant))
+    }
+
+    /// 从指定文件加载配置（仅文件，不合并其他来源）
+    fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self, QuantumConfigError> {
+        let provider = QuantumConfigFileProvider::from_path(path.as_ref(), true, LOAD_FROM_FILE_MAX_PARSE_DEPTH)?;
+        let figment = Self::transform_registry()?.apply_to_figment(Figment::from(provider))?;
+        extract_validated(&figment)
+    }
+
+    /// 配置命名空间（`#[config(namespace = "...")]`），默认没有命名空间，参见 [`crate::namespace`]
+    fn namespace() -> Option<&'static str> {
+        None
+    }
+
+    /// 从宿主应用的合并结果中加载命名空间下的配置，宿主中的值覆盖 `Default` 值，参见 [`crate::namespace`]
+    ///
+    /// 没有命名空间时使用整个合并结果。
+    fn load_nested_from(figment: &Figment) -> Result<Self, QuantumConfigError> {
+        Self::extract_at(figment, Self::namespace())
+    }
+
+    /// 从合并结果中 `key` 下的子树（`None` 表示整个合并结果）提取配置：子树中的值覆盖 `Default` 值，
+    /// 之后按本类型的规则应用值转换并校验，参见 [`crate::Loader`]
+    fn extract_at(figment: &Figment, key: Option<&str>) -> Result<Self, QuantumConfigError> {
+        let (figment, _) = namespace::nested_figment(figment, key, Some(Self::default()))?;
+        extract_validated(&figment)
+    }
+
+    /// 按 [`Config::load`] 的规则合并各来源但不提取配置，同时返回各键的来源信息
+    ///
+    /// 默认实现只包含 `Default` 值。
+    fn load_figment() -> Result<(Figment, ConfigSources), QuantumConfigError> {
+        namespace::nested_figment(&Figment::new(), None, Some(Self::default()))
+    }
+
+    /// 基于默认值生成 TOML 配置模板 `config.toml.example`
+    ///
+    /// 库不向标准输出写入内容，生成结果以 info 级别日志记录。
+    fn generate_template() -> Result<(), QuantumConfigError> {
+        Self::generate_template_as(FileFormat::Toml, TEMPLATE_PATH)?;
+        log_info!("Configuration template generated: {}", TEMPLATE_PATH);
+        Ok(())
+    }
+
+    /// 基于默认值生成指定格式的配置模板并写入 `path`
+    ///
+    /// [`Config::field_docs`] 中的字段文档按格式的注释语法写在对应键之前，参见 [`crate::template`]。
+    ///
+    /// 敏感键（[`Config::secret_keys`]）不会出现在模板中。
+    fn generate_template_as<P: AsRef<Path>>(format: FileFormat, path: P) -> Result<(), QuantumConfigError> {
+        let secret_keys = Self::secret_keys();
+        let content = if secret_keys.is_empty() {
+            // 直接序列化结构体，保持字段的声明顺序
+            template::render_template(&Self::default(), format, &Self::field_docs())?
+        } else {
+            let mut defaults = Self::defaults_as_value()?;
+            for key in &secret_keys {
+                redact::remove_key(&mut defaults, key);
+            }
+            template::render_template(&defaults, format, &Self::field_docs())?
+        };
+        let path = path.as_ref();
+        std::fs::write(path, content).map_err(|e| QuantumConfigError::Io {
+            source: e,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// 将当前配置写回 `path`
+    ///
+    /// 敏感键（[`Config::secret_keys`]）不会写入文件；目标为已存在的 TOML 文件时就地更新并保留注释，
+    /// 否则按 [`Config::generate_template_as`] 的方式渲染，参见 [`crate::persist`]。
+    fn save_to_file<P: AsRef<Path>>(&self, path: P, format: FileFormat) -> Result<(), QuantumConfigError> {
+        let meta = Self::struct_meta();
+        let secret_keys = Self::secret_keys();
+        let omitted: Vec<&str> = meta
+            .fields
+            .iter()
+            .filter(|field| field.is_skipped)
+            .map(|field| field.config_key_name())
+            .chain(secret_keys.iter().map(String::as_str))
+            .collect();
+        persist::save_to_file(self, path.as_ref(), format, &omitted, &Self::field_docs())
+    }
+
+    /// 敏感键（以 `.` 分隔的键路径），默认为 [`Config::struct_meta`] 中标注了 `#[config(secret)]` 的字段
+    fn secret_keys() -> Vec<String> {
+        Self::struct_meta()
+            .secret_fields()
+            .map(|field| field.config_key_name().to_string())
+            .collect()
+    }
+
+    /// 限制了来源的键及其允许的来源类别，默认取自 [`Config::struct_meta`] 中标注了
+    /// `#[config(sources(...))]` 或 `#[config(no_cli)]` 的字段，派生宏另外并入 `#[config(nested)]` 字段的限制，
+    /// 参见 [`crate::restrict`]
+    fn source_restrictions() -> Vec<(String, &'static [SourceKind])> {
+        crate::restrict::field_restrictions(&Self::struct_meta())
+    }
+
+    /// 敏感键已脱敏的视图，`Debug` 与 `Display` 输出可以直接写入日志，参见 [`crate::redact`]
+    fn redacted(&self) -> Redacted<'_, Self> {
+        Redacted::new(self)
+    }
+
+    /// 字段元数据，派生宏生成每个字段的名称、类型与 `///` 文档注释，默认没有字段
+    fn struct_meta() -> StructMeta {
+        StructMeta::new(std::any::type_name::<Self>(), true)
+    }
+
+    /// 字段文档（键路径 -> 文本），取自 [`Config::struct_meta`] 中字段的文档注释
+    fn field_docs() -> Vec<(&'static str, &'static str)> {
+        Self::struct_meta()
+            .non_skipped_fields()
+            .filter_map(|field| {
+                let key = field.config_name_override.unwrap_or(field.rust_name);
+                field.doc_comment.map(|doc| (key, doc))
+            })
+            .collect()
+    }
+
+    /// 以结构化数据返回默认值层（即 `Self::default()`），独立于合并结果
+    ///
+    /// 供文档工具或来源展示界面逐键对比“默认值 / 被覆盖的值”，无需借助反射。
+    fn defaults_as_value() -> Result<serde_json::Value, QuantumConfigError> {
+        serde_json::to_value(Self::default())
+            .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize default configuration: {}", e)))
+    }
+
+    /// 来源中可以出现的键的结构，供未知键检测与环境变量键名匹配使用
+    ///
+    /// 以默认值的序列化结果为基础，按字段元数据补全：序列化时省略的字段（`skip_serializing`、
+    /// 在默认值上成立的 `skip_serializing_if`）补为接受任意子键的 `null`。
+    /// 派生宏生成的实现还会代入 `#[config(nested)]` 字段类型自身的键结构。
+    fn key_shape() -> Result<serde_json::Value, QuantumConfigError> {
+        let mut shape = Self::defaults_as_value()?;
+        unknown::apply_field_meta(&mut shape, &Self::struct_meta());
+        Ok(shape)
+    }
+
+    /// 合并后应用的值转换规则，默认仅包含全局规则
+    fn transform_registry() -> Result<TransformRegistry, QuantumConfigError> {
+        Ok(TransformRegistry::from_global())
+    }
+
+    /// 各字段的漂移严重级别，默认全部使用 [`DriftSeverity::Warning`]
+    fn drift_severities() -> Vec<(&'static str, DriftSeverity)> {
+        Vec::new()
+    }
+
+    /// 环境变量值按分隔符拆分为数组的键（`#[config(env_list_separator = ",")]`），派生宏并入 `#[config(nested)]` 字段中的键，
+    /// 参见 [`crate::QuantumConfigBuilder::with_env_key_list_separator`]
+    fn env_list_separators() -> Vec<(String, &'static str)> {
+        Vec::new()
+    }
+
+    /// 环境变量与命令行参数中按原文作为字符串的键，派生宏生成 `SecretString` 字段的键并入 `#[config(nested)]` 字段中的键，
+    /// 参见 [`crate::QuantumConfigBuilder::with_raw_string_key`]
+    fn raw_string_keys() -> Vec<String> {
+        Vec::new()
+    }
+
+    /// 已弃用的键及其迁移提示（`#[config(deprecated = "...")]`），默认没有已弃用的键，参见 [`crate::deprecated`]
+    fn deprecated_keys() -> Vec<(&'static str, &'static str)> {
+        Vec::new()
+    }
+
+    /// 加载配置并说明每个字段的最终值及其来源（敏感值已脱敏），参见 [`crate::explain`]
+    fn explain() -> Result<Explanation, QuantumConfigError> {
+        let (config, report) = Self::load_with_report()?;
+        explain::explain(&config, &report, &Self::secret_keys())
+    }
+
+    /// 与 [`Config::explain`] 相同，但使用给定的命令行参数
+    ///
+    /// 默认实现不记录来源；派生宏生成的实现会记录每个值的来源。
+    fn explain_with_args(args: Vec<String>) -> Result<Explanation, QuantumConfigError> {
+        let config = Self::load_with_args(args)?;
+        explain::explain(&config, &ConfigReport::default(), &Self::secret_keys())
+    }
+
+    /// 将敏感字段的值登记到 [`crate::scrub`]，默认没有敏感字段
+    ///
+    /// 派生宏生成的实现以配置类型为作用域整体替换，重新加载后旧值不再被擦除。
+    fn register_secrets(&self) {}
+
+    /// 将敏感字段（包括 `#[config(nested)]` 字段中的敏感字段）的值加入 `secrets`，默认没有敏感字段
+    fn collect_secrets(&self, _secrets: &mut crate::scrub::Secrets) {}
+
+    /// 校验字段约束（例如 `#[config(max_len = N)]`）与 `#[config(validate)]` 指定的自定义校验，
+    /// 在加载完成后调用，默认不做校验
+    fn validate(&self) -> Result<(), QuantumConfigError> {
+        Ok(())
+    }
+
+    /// 将当前配置与基线文件比较，生成漂移报告，敏感键的取值替换为 `[REDACTED]`
+    fn compare_to_baseline<P: AsRef<Path>>(&self, baseline_file: P) -> Result<DriftReport, QuantumConfigError> {
+        let report = drift::compare_with_severities(self, baseline_file, &Self::drift_severities())?;
+        Ok(report.redact_secrets(&Self::secret_keys()))
+    }
+}
+
+/// 提取配置并校验，登记敏感值；校验失败时通知观察者
+pub(crate) fn extract_validated<T: Config>(figment: &Figment) -> Result<T, QuantumConfigError> {
+    let config: T = figment.extract()?;
+    config.register_secrets();
+    config.validate().map_err(|e| {
+        let e = e.with_origin(figment);
+        crate::observe::notify_validation_failed(&e);
+        e
+    })?;
+    Ok(config)
+}
+
+/// `load_or_default` 的默认实现：缺少必需键时使用 `Default` 值
+fn fallback_to_default<T: Config>(result: Result<T, QuantumConfigError>) -> Result<T, QuantumConfigError> {
+    match result {
+        Err(error) if error.is_missing() => Ok(T::default()),
+        result => result,
+    }
+}
+
+/// `load_lossy` 的默认实现：出现可恢复的错误时整体使用 `Default` 值
+fn lossy_from<T: Config>(
+    result: Result<T, QuantumConfigError>,
+) -> Result<(T, Vec<QuantumConfigError>), QuantumConfigError> {
+    match result {
+        Ok(config) => Ok((config, Vec::new())),
+        Err(
+            error @ (QuantumConfigError::MissingKeys { .. }
+            | QuantumConfigError::MissingValue { .. }
+            | QuantumConfigError::Extraction { .. }
+            | QuantumConfigError::ConstraintViolation { .. }
+            | QuantumConfigError::ValidationFailed { .. }
+            | QuantumConfigError::UnknownKeys { .. }),
+        ) => Ok((T::default(), vec![error])),
+        Err(error) => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// 手动实现 trait 的配置类型
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct ManualConfig {
+        name: String,
+        port: u16,
+    }
+
+    impl Config for ManualConfig {
+        fn load() -> Result<Self, QuantumConfigError> {
+            Ok(Self::default())
+        }
+
+        fn load_with_args(_args: Vec<String>) -> Result<Self, QuantumConfigError> {
+            Ok(Self::default())
+        }
+    }
+
+    /// 接受任意可配置类型的泛型函数
+    fn load_any<T: Config>(path: &Path) -> Result<T, QuantumConfigError> {
+        T::load_from_file(path)
+    }
+
+    #[test]
+    fn test_defaults_as_value_is_independent_of_loaded_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "name = \"loaded\"\nport = 8080\n").unwrap();
+        let loaded = ManualConfig::load_from_file(&path).unwrap();
+
+        let defaults = ManualConfig::defaults_as_value().unwrap();
+        assert_eq!(defaults, serde_json::json!({ "name": "", "port": 0 }));
+        assert_ne!(serde_json::to_value(&loaded).unwrap()["port"], defaults["port"]);
+    }
+
+    #[test]
+    fn test_default_load_from_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "name = \"manual\"\nport = 8080\n").unwrap();
+
+        let config: ManualConfig = load_any(&path).unwrap();
+        assert_eq!(config, ManualConfig { name: "manual".to_string(), port: 8080 });
+    }
+
+    #[test]
+    fn test_default_load_from_file_reports_extract_error() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "name = \"manual\"\nport = \"not a port\"\n").unwrap();
+
+        let result: Result<ManualConfig, _> = load_any(&path);
+        assert!(matches!(result, Err(QuantumConfigError::Extraction { key_path, .. }) if key_path == "port"));
+    }
+
+    #[test]
+    fn test_default_fallback_and_lossy_implementations() {
+        let missing = || QuantumConfigError::MissingValue { key_path: "name".to_string() };
+        assert_eq!(fallback_to_default::<ManualConfig>(Err(missing())).unwrap(), ManualConfig::default());
+        let internal = fallback_to_default::<ManualConfig>(Err(QuantumConfigError::Internal("boom".to_string())));
+        assert!(matches!(internal, Err(QuantumConfigError::Internal(_))));
+
+        let (config, warnings) = lossy_from::<ManualConfig>(Err(missing())).unwrap();
+        assert_eq!(config, ManualConfig::default());
+        assert_eq!(warnings.len(), 1);
+        assert!(lossy_from::<ManualConfig>(Err(QuantumConfigError::Watch("boom".to_string()))).is_err());
+    }
+
+    #[test]
+    fn test_default_drift_severities() {
+        assert!(ManualConfig::drift_severities().is_empty());
+        assert!(ManualConfig::transform_registry().unwrap().is_empty());
+    }
+}
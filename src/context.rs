@@ -0,0 +1,322 @@
+//! 可注入的加载上下文
+//!
+//! `load()` 默认读取进程的命令行参数、环境变量、当前目录与主目录。[`LoadContext`] 可以替换这些来源，
+//! 使整个加载流程在单元测试或 WASI 等沙箱环境中以确定的输入运行：
+//!
+//! ```ignore
+//! use quantum_config::context::{FixedDirs, LoadContext};
+//! use quantum_config::providers::MapEnv;
+//!
+//! let context = LoadContext::new()
+//!     .with_args(vec!["app".to_string(), "--port".to_string(), "9090".to_string()])
+//!     .with_env(MapEnv::new().with_var("APP_HOST", "0.0.0.0"))
+//!     .with_dirs(FixedDirs::new("/sandbox"));
+//! let config = AppConfig::load_in(&context)?;
+//! ```
+//!
+//! - [`ArgsSource`]：`load()` 等不带参数的加载函数使用的命令行参数；
+//! - [`EnvSource`]：环境变量提供器、剖面环境变量与 XDG 目录变量；
+//! - [`DirsSource`]：相对路径（`--config`、`.env`）所基于的当前目录，以及系统级、用户级配置目录。
+//!
+//! 上下文只在 [`LoadContext::run`] 的调用线程内生效，未设置的来源仍使用进程的值；
+//! 文件内容通过 [`crate::providers::FileReader`] 读取，不属于上下文。
+
+use crate::meta::QuantumConfigAppMeta;
+use crate::providers::{EnvSource, ProcessEnv};
+use std::cell::RefCell;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 命令行参数来源trait
+pub trait ArgsSource: Send + Sync + fmt::Debug {
+    /// 包含程序名的参数列表
+    fn args(&self) -> Vec<String>;
+}
+
+/// 进程的命令行参数
+///
+/// 这是默认的参数来源。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessArgs;
+
+impl ArgsSource for ProcessArgs {
+    fn args(&self) -> Vec<String> {
+        std::env::args().collect()
+    }
+}
+
+impl ArgsSource for Vec<String> {
+    fn args(&self) -> Vec<String> {
+        self.clone()
+    }
+}
+
+/// 目录来源trait
+pub trait DirsSource: Send + Sync + fmt::Debug {
+    /// 当前工作目录，相对路径基于该目录解析
+    fn current_dir(&self) -> Option<PathBuf>;
+
+    /// 用户主目录，XDG 策略在 `XDG_CONFIG_HOME` 未设置时使用 `~/.config`
+    fn home_dir(&self) -> Option<PathBuf>;
+
+    /// 按平台惯例的系统级配置目录，参见 [`crate::paths::PathStrategy::Native`]
+    fn system_config_dir(&self, app_meta: &QuantumConfigAppMeta) -> Option<PathBuf>;
+
+    /// 按平台惯例的用户级配置目录，参见 [`crate::paths::PathStrategy::Native`]
+    fn user_config_dir(&self, app_meta: &QuantumConfigAppMeta) -> Option<PathBuf>;
+}
+
+/// 进程的当前目录与平台目录
+///
+/// 这是默认的目录来源。平台目录需要 `platform-dirs` 特性（默认启用）；未启用时（例如 WASM 目标）
+/// 主目录取自 `HOME` 环境变量，不查找系统级与用户级配置目录。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessDirs;
+
+#[cfg(feature = "platform-dirs")]
+impl ProcessDirs {
+    fn project_dirs(app_meta: &QuantumConfigAppMeta) -> Option<directories::ProjectDirs> {
+        directories::ProjectDirs::from(
+            app_meta.qualifier.as_deref().unwrap_or(""),
+            app_meta.organization.as_deref().unwrap_or(""),
+            &app_meta.app_name,
+        )
+    }
+}
+
+impl DirsSource for ProcessDirs {
+    fn current_dir(&self) -> Option<PathBuf> {
+        std::env::current_dir().ok()
+    }
+
+    #[cfg(feature = "platform-dirs")]
+    fn home_dir(&self) -> Option<PathBuf> {
+        directories::BaseDirs::new().map(|dirs| dirs.home_dir().to_path_buf())
+    }
+
+    #[cfg(not(feature = "platform-dirs"))]
+    fn home_dir(&self) -> Option<PathBuf> {
+        std::env::var_os("HOME").filter(|home| !home.is_empty()).map(PathBuf::from)
+    }
+
+    #[cfg(feature = "platform-dirs")]
+    fn system_config_dir(&self, app_meta: &QuantumConfigAppMeta) -> Option<PathBuf> {
+        // 在 Windows 上通常是 C:\ProgramData\{app_name}
+        // 在 Unix 上通常是 /etc/{app_name}
+        let project_dirs = Self::project_dirs(app_meta)?;
+        project_dirs.config_dir().parent().and_then(|p| p.parent()).map(|_root| {
+            #[cfg(windows)]
+            {
+                _root.join("ProgramData").join(&app_meta.app_name)
+            }
+            #[cfg(not(windows))]
+            {
+                PathBuf::from("/etc").join(&app_meta.app_name)
+            }
+        })
+    }
+
+    #[cfg(feature = "platform-dirs")]
+    fn user_config_dir(&self, app_meta: &QuantumConfigAppMeta) -> Option<PathBuf> {
+        Self::project_dirs(app_meta).map(|dirs| dirs.config_dir().to_path_buf())
+    }
+
+    #[cfg(not(feature = "platform-dirs"))]
+    fn system_config_dir(&self, _app_meta: &QuantumConfigAppMeta) -> Option<PathBuf> {
+        None
+    }
+
+    #[cfg(not(feature = "platform-dirs"))]
+    fn user_config_dir(&self, _app_meta: &QuantumConfigAppMeta) -> Option<PathBuf> {
+        None
+    }
+}
+
+/// 以给定根目录模拟的目录布局
+///
+/// 当前目录为 `{root}`，主目录为 `{root}/home`，系统级配置目录为 `{root}/etc/{app}`，
+/// 用户级配置目录为 `{root}/home/.config/{app}`，在所有平台上一致。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixedDirs {
+    /// 当前目录
+    current_dir: PathBuf,
+    /// 主目录
+    home_dir: PathBuf,
+    /// 系统级配置目录的上级目录
+    system_root: PathBuf,
+}
+
+impl FixedDirs {
+    /// 以 `root` 为根目录创建目录布局
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        let root = root.into();
+        Self {
+            current_dir: root.clone(),
+            home_dir: root.join("home"),
+            system_root: root.join("etc"),
+        }
+    }
+
+    /// 设置当前目录
+    pub fn with_current_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.current_dir = dir.into();
+        self
+    }
+
+    /// 设置主目录，用户级配置目录随之变为 `{home}/.config/{app}`
+    pub fn with_home_dir<P: Into<PathBuf>>(mut self, dir: P) -> Self {
+        self.home_dir = dir.into();
+        self
+    }
+}
+
+impl DirsSource for FixedDirs {
+    fn current_dir(&self) -> Option<PathBuf> {
+        Some(self.current_dir.clone())
+    }
+
+    fn home_dir(&self) -> Option<PathBuf> {
+        Some(self.home_dir.clone())
+    }
+
+    fn system_config_dir(&self, app_meta: &QuantumConfigAppMeta) -> Option<PathBuf> {
+        Some(self.system_root.join(&app_meta.app_name))
+    }
+
+    fn user_config_dir(&self, app_meta: &QuantumConfigAppMeta) -> Option<PathBuf> {
+        Some(self.home_dir.join(".config").join(&app_meta.app_name))
+    }
+}
+
+/// 加载上下文：替换命令行参数、环境变量与目录来源
+#[derive(Debug, Clone, Default)]
+pub struct LoadContext {
+    /// 命令行参数来源
+    args: Option<Arc<dyn ArgsSource>>,
+    /// 环境变量来源
+    env: Option<Arc<dyn EnvSource>>,
+    /// 目录来源
+    dirs: Option<Arc<dyn DirsSource>>,
+}
+
+thread_local! {
+    /// 当前线程生效的上下文，嵌套调用时内层优先
+    static CURRENT: RefCell<Vec<LoadContext>> = const { RefCell::new(Vec::new()) };
+}
+
+impl LoadContext {
+    /// 创建不替换任何来源的上下文
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置命令行参数来源
+    pub fn with_args<A: ArgsSource + 'static>(mut self, args: A) -> Self {
+        self.args = Some(Arc::new(args));
+        self
+    }
+
+    /// 设置环境变量来源
+    pub fn with_env<E: EnvSource + 'static>(mut self, env: E) -> Self {
+        self.env = Some(Arc::new(env));
+        self
+    }
+
+    /// 设置目录来源
+    pub fn with_dirs<D: DirsSource + 'static>(mut self, dirs: D) -> Self {
+        self.dirs = Some(Arc::new(dirs));
+        self
+    }
+
+    /// 在当前线程中以该上下文执行 `f`，返回（包括 panic 展开）后恢复之前的上下文
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        /// 弹出本次压入的上下文
+        struct Pop;
+
+        impl Drop for Pop {
+            fn drop(&mut self) {
+                CURRENT.with(|current| current.borrow_mut().pop());
+            }
+        }
+
+        CURRENT.with(|current| current.borrow_mut().push(self.clone()));
+        let _pop = Pop;
+        f()
+    }
+}
+
+/// 在当前上下文中查找来源，内层上下文未设置时使用外层的
+fn current<T>(select: impl Fn(&LoadContext) -> Option<T>) -> Option<T> {
+    CURRENT.with(|current| current.borrow().iter().rev().find_map(select))
+}
+
+/// 当前上下文的命令行参数，未设置时为进程的参数
+pub fn args() -> Vec<String> {
+    match current(|context| context.args.clone()) {
+        Some(args) => args.args(),
+        None => ProcessArgs.args(),
+    }
+}
+
+/// 当前上下文的环境变量来源，未设置时为进程环境
+pub fn env_source() -> Arc<dyn EnvSource> {
+    current(|context| context.env.clone()).unwrap_or_else(|| Arc::new(ProcessEnv))
+}
+
+/// 当前上下文的目录来源，未设置时为进程的目录
+pub fn dirs_source() -> Arc<dyn DirsSource> {
+    current(|context| context.dirs.clone()).unwrap_or_else(|| Arc::new(ProcessDirs))
+}
+
+/// 将相对路径解析到上下文的当前目录；未设置目录来源时保持不变，由操作系统按进程当前目录解析
+pub(crate) fn resolve_relative(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    match current(|context| context.dirs.clone()).and_then(|dirs| dirs.current_dir()) {
+        Some(dir) => dir.join(path),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::MapEnv;
+
+    #[test]
+    fn test_context_scopes_sources_to_run() {
+        let outer = LoadContext::new()
+            .with_args(vec!["app".to_string(), "--port".to_string()])
+            .with_dirs(FixedDirs::new("/sandbox"));
+        let inner = LoadContext::new().with_env(MapEnv::new().with_var("QC_CONTEXT_ONLY", "1"));
+
+        outer.run(|| {
+            inner.run(|| {
+                // 内层未设置的来源沿用外层
+                assert_eq!(args(), ["app", "--port"]);
+                assert_eq!(env_source().var("QC_CONTEXT_ONLY").as_deref(), Some("1"));
+                assert_eq!(resolve_relative(Path::new("config.toml")), Path::new("/sandbox/config.toml"));
+            });
+            assert_eq!(env_source().var("QC_CONTEXT_ONLY"), None);
+        });
+        assert_eq!(resolve_relative(Path::new("config.toml")), Path::new("config.toml"));
+        assert_eq!(args(), std::env::args().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_fixed_dirs_layout() {
+        let meta = QuantumConfigAppMeta {
+            app_name: "myapp".to_string(),
+            ..Default::default()
+        };
+        let dirs = FixedDirs::new("/sandbox").with_current_dir("/sandbox/work");
+        assert_eq!(dirs.current_dir(), Some(PathBuf::from("/sandbox/work")));
+        assert_eq!(dirs.system_config_dir(&meta), Some(PathBuf::from("/sandbox/etc/myapp")));
+        assert_eq!(dirs.user_config_dir(&meta), Some(PathBuf::from("/sandbox/home/.config/myapp")));
+
+        let dirs = dirs.with_home_dir("/users/me");
+        assert_eq!(dirs.user_config_dir(&meta), Some(PathBuf::from("/users/me/.config/myapp")));
+    }
+}
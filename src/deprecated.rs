@@ -0,0 +1,96 @@
+//! 已弃用的配置键
+//!
+//! 字段上的 `#[config(deprecated = "use server.port instead")]` 将该键标记为已弃用，用于分阶段迁移配置格式：
+//! 旧键仍然有效，但只要任一来源（配置文件、环境变量、命令行参数等）提供了该键，
+//! 加载时就输出一条警告日志，说明迁移提示与提供该值的位置（文件时附带行号）：
+//!
+//! ```text
+//! configuration key `port` is deprecated: use server.port instead (set by config file /etc/app/config.toml:3)
+//! ```
+//!
+//! 只来自默认值的键不产生警告。[`crate::Config::load_with_report`] 返回的报告在
+//! [`crate::ConfigReport::deprecations`] 中收集同样的警告，便于在启动时汇总输出或在测试中断言。
+
+use crate::config::Config;
+use crate::explain::{Locator, SourceLocation};
+use crate::logging::log_warn;
+use crate::report::{ConfigSources, ValueSource};
+use std::fmt;
+
+/// 一条弃用警告
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeprecationWarning {
+    /// 以 `.` 分隔的键路径
+    pub key: String,
+    /// `#[config(deprecated = "...")]` 给出的迁移提示
+    pub message: String,
+    /// 提供该键的来源
+    pub source: ValueSource,
+    /// 来源为文件时键所在的位置
+    pub location: Option<SourceLocation>,
+}
+
+impl fmt::Display for DeprecationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "configuration key `{}` is deprecated: {} (set by {}", self.key, self.message, self.source)?;
+        if let Some(location) = &self.location {
+            write!(f, ":{}", location.line)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// 检查已弃用的键（键路径，迁移提示）是否由默认值以外的来源提供
+pub fn detect(deprecated: &[(&str, &str)], sources: &ConfigSources) -> Vec<DeprecationWarning> {
+    let mut locator = Locator::default();
+    deprecated
+        .iter()
+        .filter_map(|(key, message)| {
+            let (key_path, source) = sources.provided_source(key)?;
+            Some(DeprecationWarning {
+                key: key.to_string(),
+                message: message.to_string(),
+                location: locator.location(&source, &key_path),
+                source,
+            })
+        })
+        .collect()
+}
+
+/// 检查 `T` 的已弃用键，为每条警告输出 warn 级别日志并返回这些警告
+pub fn warn_deprecated<T: Config>(sources: &ConfigSources) -> Vec<DeprecationWarning> {
+    let warnings = detect(&T::deprecated_keys(), sources);
+    for warning in &warnings {
+        log_warn!("{}", warning);
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_display() {
+        let warning = DeprecationWarning {
+            key: "port".to_string(),
+            message: "use server.port instead".to_string(),
+            source: ValueSource::ConfigFile("/etc/app/config.toml".into()),
+            location: Some(SourceLocation {
+                file: "/etc/app/config.toml".into(),
+                line: 3,
+            }),
+        };
+        assert_eq!(
+            warning.to_string(),
+            "configuration key `port` is deprecated: use server.port instead (set by config file /etc/app/config.toml:3)"
+        );
+
+        let warning = DeprecationWarning {
+            source: ValueSource::Env("APP_PORT".to_string()),
+            location: None,
+            ..warning
+        };
+        assert!(warning.to_string().ends_with("(set by environment variable APP_PORT)"));
+    }
+}
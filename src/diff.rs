@@ -0,0 +1,167 @@
+//! 配置差异比较
+//!
+//! 比较两份可序列化的配置，按键路径列出发生变化的叶子值，供热重载时判断需要重启哪些子系统：
+//!
+//! ```ignore
+//! let changes = quantum_config::diff::diff(&current, &reloaded)?;
+//! if quantum_config::diff::touches(&changes, "database") {
+//!     pool.reconnect(&reloaded.database)?;
+//! }
+//! ```
+//!
+//! 嵌套对象展开为以 `.` 分隔的叶子键，数组作为整体比较，`null`（未设置的可选值）视为不存在。
+
+use crate::drift::flatten_leaves;
+use crate::error::QuantumConfigError;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+/// 单个发生变化的键
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChangedKey {
+    /// 以 `.` 分隔的键路径
+    pub path: String,
+    /// 旧值，新增的键为 `None`
+    pub old: Option<JsonValue>,
+    /// 新值，移除的键为 `None`
+    pub new: Option<JsonValue>,
+}
+
+impl ChangedKey {
+    /// 是否为新增的键
+    pub fn is_added(&self) -> bool {
+        self.old.is_none()
+    }
+
+    /// 是否为移除的键
+    pub fn is_removed(&self) -> bool {
+        self.new.is_none()
+    }
+
+    /// 键是否等于 `prefix` 或位于其下
+    pub fn is_under(&self, prefix: &str) -> bool {
+        prefix.is_empty()
+            || self
+                .path
+                .strip_prefix(prefix)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    }
+}
+
+impl fmt::Display for ChangedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 启用 `secret-scrub` 特性时值经过脱敏，已登记的敏感值不会出现在日志中
+        let render = |value: &Option<JsonValue>| match value {
+            Some(value) => crate::scrub::scrub(&value.to_string()),
+            None => "<unset>".to_string(),
+        };
+        write!(f, "{}: {} -> {}", self.path, render(&self.old), render(&self.new))
+    }
+}
+
+/// 比较两份配置，按键路径排序返回发生变化的键
+///
+/// # Errors
+/// 配置无法序列化为 JSON 时返回错误
+pub fn diff<T: Serialize>(old: &T, new: &T) -> Result<Vec<ChangedKey>, QuantumConfigError> {
+    let old = serde_json::to_value(old)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize old config: {}", e)))?;
+    let new = serde_json::to_value(new)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize new config: {}", e)))?;
+    Ok(diff_values(&old, &new))
+}
+
+/// 比较两个 JSON 值，按键路径排序返回发生变化的键
+pub fn diff_values(old: &JsonValue, new: &JsonValue) -> Vec<ChangedKey> {
+    let mut old_leaves = flatten_leaves(old);
+    let new_leaves = flatten_leaves(new);
+
+    let mut changes = Vec::new();
+    for (path, new_value) in new_leaves {
+        match old_leaves.remove(&path) {
+            Some(old_value) if old_value == new_value => {}
+            old_value => changes.push(ChangedKey {
+                path,
+                old: old_value,
+                new: Some(new_value),
+            }),
+        }
+    }
+    changes.extend(old_leaves.into_iter().map(|(path, old_value)| ChangedKey {
+        path,
+        old: Some(old_value),
+        new: None,
+    }));
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes
+}
+
+/// 是否有变化的键等于 `prefix` 或位于其下
+pub fn touches(changes: &[ChangedKey], prefix: &str) -> bool {
+    changes.iter().any(|change| change.is_under(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[derive(Serialize)]
+    struct Database {
+        url: String,
+        pool_size: u32,
+    }
+
+    #[derive(Serialize)]
+    struct AppConfig {
+        name: String,
+        tags: Vec<String>,
+        token: Option<String>,
+        database: Database,
+    }
+
+    fn config() -> AppConfig {
+        AppConfig {
+            name: "app".to_string(),
+            tags: vec!["a".to_string()],
+            token: None,
+            database: Database { url: "postgres://db".to_string(), pool_size: 5 },
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_changed_added_and_removed_keys() {
+        assert!(diff(&config(), &config()).unwrap().is_empty());
+
+        let mut new = config();
+        new.tags.push("b".to_string());
+        new.token = Some("diff-token".to_string());
+        new.database.pool_size = 10;
+        let changes = diff(&config(), &new).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                ChangedKey { path: "database.pool_size".to_string(), old: Some(json!(5)), new: Some(json!(10)) },
+                ChangedKey { path: "tags".to_string(), old: Some(json!(["a"])), new: Some(json!(["a", "b"])) },
+                ChangedKey { path: "token".to_string(), old: None, new: Some(json!("diff-token")) },
+            ]
+        );
+        assert!(changes[2].is_added());
+        assert!(touches(&changes, "database"));
+        assert!(!touches(&changes, "name"));
+        assert!(!touches(&changes, "data"));
+
+        let removed = diff(&new, &config()).unwrap();
+        assert!(removed.iter().any(|change| change.path == "token" && change.is_removed()));
+        assert_eq!(removed[0].to_string(), "database.pool_size: 10 -> 5");
+    }
+
+    #[cfg(feature = "secret-scrub")]
+    #[test]
+    fn test_display_scrubs_registered_secrets() {
+        crate::scrub::register_secret("diff-secret-value");
+        let changes = diff_values(&json!({ "password": "old" }), &json!({ "password": "diff-secret-value" }));
+        assert!(!changes[0].to_string().contains("diff-secret-value"));
+    }
+}
@@ -0,0 +1,382 @@
+//! Quantum Config 配置漂移检测模块
+//!
+//! 将当前生效的配置与经过审批的"黄金"基线文件逐键比较，生成漂移报告，
+//! 供巡检代理确认主机配置没有偏离生产基线。
+//!
+//! 每个差异都带有严重级别：派生宏会读取字段上的 `#[config(severity = "critical")]`
+//! 属性，未标注的键使用 [`DriftSeverity::Warning`]。
+//!
+//! [`crate::Config::compare_to_baseline`] 生成的报告中，敏感键（[`crate::Config::secret_keys`]）
+//! 的取值替换为 `[REDACTED]`，输出与序列化报告时不会泄露明文。
+
+use crate::error::QuantumConfigError;
+use crate::providers::QuantumConfigFileProvider;
+use figment::Figment;
+use serde::Serialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// 基线文件允许的最大解析深度
+const BASELINE_MAX_PARSE_DEPTH: u32 = 128;
+
+/// 漂移严重级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftSeverity {
+    /// 仅供参考
+    Info,
+    /// 需要关注（默认）
+    #[default]
+    Warning,
+    /// 必须处理
+    Critical,
+}
+
+impl DriftSeverity {
+    /// 从字符串解析严重级别（不区分大小写）
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warning" | "warn" => Some(Self::Warning),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DriftSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+/// 单个键的漂移类型
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DriftKind {
+    /// 两边都存在但取值不同
+    Changed {
+        current: JsonValue,
+        baseline: JsonValue,
+    },
+    /// 只存在于基线中
+    OnlyInBaseline { baseline: JsonValue },
+    /// 只存在于当前配置中
+    OnlyInCurrent { current: JsonValue },
+}
+
+/// 单个键的漂移记录
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DriftEntry {
+    /// 以 `.` 分隔的键路径
+    pub key: String,
+    /// 漂移类型
+    #[serde(flatten)]
+    pub kind: DriftKind,
+    /// 严重级别
+    pub severity: DriftSeverity,
+}
+
+/// 漂移报告
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DriftReport {
+    /// 按键路径排序的漂移记录
+    pub entries: Vec<DriftEntry>,
+}
+
+impl DriftReport {
+    /// 是否没有任何漂移
+    pub fn is_clean(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 取值不同的键
+    pub fn changed(&self) -> impl Iterator<Item = &DriftEntry> {
+        self.entries.iter().filter(|e| matches!(e.kind, DriftKind::Changed { .. }))
+    }
+
+    /// 只存在于基线中的键
+    pub fn only_in_baseline(&self) -> impl Iterator<Item = &DriftEntry> {
+        self.entries.iter().filter(|e| matches!(e.kind, DriftKind::OnlyInBaseline { .. }))
+    }
+
+    /// 只存在于当前配置中的键
+    pub fn only_in_current(&self) -> impl Iterator<Item = &DriftEntry> {
+        self.entries.iter().filter(|e| matches!(e.kind, DriftKind::OnlyInCurrent { .. }))
+    }
+
+    /// 报告中的最高严重级别
+    pub fn max_severity(&self) -> Option<DriftSeverity> {
+        self.entries.iter().map(|e| e.severity).max()
+    }
+
+    /// 严重级别不低于 `min` 的记录
+    pub fn at_least(&self, min: DriftSeverity) -> impl Iterator<Item = &DriftEntry> {
+        self.entries.iter().filter(move |e| e.severity >= min)
+    }
+
+    /// 将 `secret_keys` 中的键及其子键的取值替换为 `[REDACTED]`，漂移记录本身保留
+    pub fn redact_secrets(mut self, secret_keys: &[String]) -> Self {
+        let redacted = || JsonValue::String(crate::scrub::REDACTED.to_string());
+        for entry in &mut self.entries {
+            if !secret_keys.iter().any(|secret| is_under(&entry.key, secret)) {
+                continue;
+            }
+            match &mut entry.kind {
+                DriftKind::Changed { current, baseline } => {
+                    *current = redacted();
+                    *baseline = redacted();
+                }
+                DriftKind::OnlyInBaseline { baseline } => *baseline = redacted(),
+                DriftKind::OnlyInCurrent { current } => *current = redacted(),
+            }
+        }
+        self
+    }
+}
+
+impl fmt::Display for DriftReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return writeln!(f, "no drift from baseline");
+        }
+        for entry in &self.entries {
+            match &entry.kind {
+                DriftKind::Changed { current, baseline } => {
+                    writeln!(f, "[{}] {}: {} (baseline: {})", entry.severity, entry.key, current, baseline)?
+                }
+                DriftKind::OnlyInBaseline { baseline } => {
+                    writeln!(f, "[{}] {}: missing (baseline: {})", entry.severity, entry.key, baseline)?
+                }
+                DriftKind::OnlyInCurrent { current } => {
+                    writeln!(f, "[{}] {}: {} (not in baseline)", entry.severity, entry.key, current)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 将当前配置与基线文件比较，所有键使用默认严重级别
+///
+/// # Errors
+/// 当前配置无法序列化、基线文件无法读取或解析时返回错误
+pub fn compare<T: Serialize>(current: &T, baseline_file: impl AsRef<Path>) -> Result<DriftReport, QuantumConfigError> {
+    compare_with_severities(current, baseline_file, &[])
+}
+
+/// 将当前配置与基线文件比较，并按给定的键路径前缀划分严重级别
+///
+/// `severities` 中的键同时作用于其下的所有子键，最长匹配优先。
+///
+/// # Errors
+/// 当前配置无法序列化、基线文件无法读取或解析时返回错误
+pub fn compare_with_severities<T: Serialize>(
+    current: &T,
+    baseline_file: impl AsRef<Path>,
+    severities: &[(&str, DriftSeverity)],
+) -> Result<DriftReport, QuantumConfigError> {
+    let current = serde_json::to_value(current)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize current config: {}", e)))?;
+
+    let baseline_file = baseline_file.as_ref();
+    if !baseline_file.is_file() {
+        return Err(QuantumConfigError::SpecifiedFileNotFound {
+            path: baseline_file.to_path_buf(),
+        });
+    }
+
+    let provider = QuantumConfigFileProvider::from_path(baseline_file, true, BASELINE_MAX_PARSE_DEPTH)?;
+    let baseline: JsonValue = Figment::from(provider).extract()?;
+
+    Ok(diff_values(&current, &baseline, severities))
+}
+
+/// 比较两个 JSON 值并生成漂移报告
+pub fn diff_values(current: &JsonValue, baseline: &JsonValue, severities: &[(&str, DriftSeverity)]) -> DriftReport {
+    let current_leaves = flatten_leaves(current);
+    let baseline_leaves = flatten_leaves(baseline);
+
+    let mut entries = Vec::new();
+    for (key, current_value) in &current_leaves {
+        let kind = match baseline_leaves.get(key) {
+            Some(baseline_value) if baseline_value == current_value => continue,
+            Some(baseline_value) => DriftKind::Changed {
+                current: current_value.clone(),
+                baseline: baseline_value.clone(),
+            },
+            None => DriftKind::OnlyInCurrent {
+                current: current_value.clone(),
+            },
+        };
+        entries.push(DriftEntry {
+            key: key.clone(),
+            kind,
+            severity: severity_for(key, severities),
+        });
+    }
+    for (key, baseline_value) in &baseline_leaves {
+        if !current_leaves.contains_key(key) {
+            entries.push(DriftEntry {
+                key: key.clone(),
+                kind: DriftKind::OnlyInBaseline {
+                    baseline: baseline_value.clone(),
+                },
+                severity: severity_for(key, severities),
+            });
+        }
+    }
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+
+    DriftReport { entries }
+}
+
+/// 将值展开为以 `.` 分隔的叶子键及其取值
+pub(crate) fn flatten_leaves(value: &JsonValue) -> BTreeMap<String, JsonValue> {
+    let mut leaves = BTreeMap::new();
+    flatten("", value, &mut leaves);
+    leaves
+}
+
+/// 将嵌套对象展开为以 `.` 分隔的叶子键，`null` 视为未设置
+fn flatten(prefix: &str, value: &JsonValue, out: &mut BTreeMap<String, JsonValue>) {
+    match value {
+        JsonValue::Object(map) if !map.is_empty() || prefix.is_empty() => flatten_object(prefix, map, out),
+        JsonValue::Null => {}
+        other => {
+            out.insert(prefix.to_string(), other.clone());
+        }
+    }
+}
+
+/// 展开对象的各个字段
+fn flatten_object(prefix: &str, map: &JsonMap<String, JsonValue>, out: &mut BTreeMap<String, JsonValue>) {
+    for (key, value) in map {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        flatten(&path, value, out);
+    }
+}
+
+/// 键是否等于 `prefix` 或位于其下
+fn is_under(key: &str, prefix: &str) -> bool {
+    key == prefix || key.starts_with(&format!("{}.", prefix))
+}
+
+/// 按最长前缀匹配确定键的严重级别
+fn severity_for(key: &str, severities: &[(&str, DriftSeverity)]) -> DriftSeverity {
+    severities
+        .iter()
+        .filter(|(prefix, _)| is_under(key, prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, severity)| *severity)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[derive(Serialize)]
+    struct ServerConfig {
+        host: String,
+        port: u16,
+        tls: Option<bool>,
+    }
+
+    #[test]
+    fn test_diff_values_classifies_keys() {
+        let current = json!({"host": "0.0.0.0", "port": 8080, "debug": true});
+        let baseline = json!({"host": "127.0.0.1", "port": 8080, "workers": 4});
+
+        let report = diff_values(&current, &baseline, &[]);
+
+        assert_eq!(report.entries.len(), 3);
+        assert_eq!(report.changed().map(|e| e.key.as_str()).collect::<Vec<_>>(), vec!["host"]);
+        assert_eq!(report.only_in_baseline().map(|e| e.key.as_str()).collect::<Vec<_>>(), vec!["workers"]);
+        assert_eq!(report.only_in_current().map(|e| e.key.as_str()).collect::<Vec<_>>(), vec!["debug"]);
+    }
+
+    #[test]
+    fn test_nested_keys_and_severity_prefixes() {
+        let current = json!({"database": {"url": "postgres://prod", "pool": {"size": 10}}, "log": "debug"});
+        let baseline = json!({"database": {"url": "postgres://approved", "pool": {"size": 20}}, "log": "info"});
+        let severities = [
+            ("database", DriftSeverity::Critical),
+            ("database.pool", DriftSeverity::Info),
+        ];
+
+        let report = diff_values(&current, &baseline, &severities);
+        let by_key: BTreeMap<_, _> = report.entries.iter().map(|e| (e.key.as_str(), e.severity)).collect();
+
+        assert_eq!(by_key["database.url"], DriftSeverity::Critical);
+        assert_eq!(by_key["database.pool.size"], DriftSeverity::Info);
+        assert_eq!(by_key["log"], DriftSeverity::Warning);
+        assert_eq!(report.max_severity(), Some(DriftSeverity::Critical));
+        assert_eq!(report.at_least(DriftSeverity::Warning).count(), 2);
+    }
+
+    #[test]
+    fn test_redact_secrets_hides_values() {
+        let current = json!({"database": {"auth": {"password": "drift-current-pw"}}, "token": "drift-token", "log": "debug"});
+        let baseline = json!({"database": {"auth": {"password": "drift-baseline-pw"}}, "log": "info"});
+
+        let report = diff_values(&current, &baseline, &[]).redact_secrets(&["database.auth".to_string(), "token".to_string()]);
+        let display = report.to_string();
+        assert!(!display.contains("drift-current-pw") && !display.contains("drift-baseline-pw"), "{}", display);
+        assert!(display.contains("database.auth.password: \"[REDACTED]\" (baseline: \"[REDACTED]\")"), "{}", display);
+        assert!(display.contains("token: \"[REDACTED]\" (not in baseline)"), "{}", display);
+        assert!(display.contains("log: \"debug\" (baseline: \"info\")"), "{}", display);
+        assert!(!serde_json::to_string(&report).unwrap().contains("drift-token"));
+    }
+
+    #[test]
+    fn test_compare_against_baseline_file() {
+        let dir = tempdir().unwrap();
+        let baseline = dir.path().join("baseline.toml");
+        fs::write(&baseline, "host = \"127.0.0.1\"\nport = 8080\n").unwrap();
+
+        let current = ServerConfig {
+            host: "127.0.0.1".to_string(),
+            port: 8080,
+            tls: None,
+        };
+        assert!(compare(&current, &baseline).unwrap().is_clean());
+
+        let drifted = ServerConfig {
+            host: "0.0.0.0".to_string(),
+            port: 8080,
+            tls: Some(true),
+        };
+        let report = compare_with_severities(&drifted, &baseline, &[("host", DriftSeverity::Critical)]).unwrap();
+        assert_eq!(report.entries.len(), 2);
+        assert_eq!(report.max_severity(), Some(DriftSeverity::Critical));
+        assert!(report.to_string().contains("[critical] host: \"0.0.0.0\" (baseline: \"127.0.0.1\")"));
+    }
+
+    #[test]
+    fn test_missing_baseline_file() {
+        let current = json!({"port": 8080});
+        let result = compare(&current, "/nonexistent/baseline.toml");
+        assert!(matches!(result, Err(QuantumConfigError::SpecifiedFileNotFound { .. })));
+    }
+
+    #[test]
+    fn test_severity_parse() {
+        assert_eq!(DriftSeverity::parse("CRITICAL"), Some(DriftSeverity::Critical));
+        assert_eq!(DriftSeverity::parse("warn"), Some(DriftSeverity::Warning));
+        assert_eq!(DriftSeverity::parse("urgent"), None);
+    }
+}
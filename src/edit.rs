@@ -0,0 +1,284 @@
+//! 保留格式的 TOML 配置编辑
+//!
+//! `toml::to_string` 重新生成整个文件，用户写下的注释、键顺序与排版都会丢失。
+//! [`TomlDocument`] 基于 `toml_edit` 在原文档上就地修改，只改写发生变化的值：
+//!
+//! ```ignore
+//! use quantum_config::edit::TomlDocument;
+//!
+//! let mut document = TomlDocument::open("config.toml")?;
+//! document.apply(&patch)?;            // 结构体或 `{Name}Patch`，未出现的键保持原样
+//! document.set("server.port", &9090)?;
+//! document.remove("legacy.option");
+//! document.save()?;
+//! ```
+//!
+//! 合并规则：对象递归合并到同名表中，标量与数组整体替换并保留原有的行尾注释，
+//! 值为 `null` 的键被移除，序列化结果中没有出现的键保持原样。
+
+use crate::error::QuantumConfigError;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use toml_edit::{Array, DocumentMut, InlineTable, Item, Table, Value};
+
+/// 保留注释与排版的 TOML 文档
+#[derive(Debug, Clone, Default)]
+pub struct TomlDocument {
+    /// 打开时的文件路径，[`TomlDocument::save`] 写回该路径
+    path: Option<PathBuf>,
+    /// 文档内容
+    document: DocumentMut,
+}
+
+impl TomlDocument {
+    /// 读取并解析 TOML 文件，文件不存在时得到空文档
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, QuantumConfigError> {
+        let path = path.as_ref();
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(QuantumConfigError::Io {
+                    source: e,
+                    path: path.to_path_buf(),
+                })
+            }
+        };
+        let mut document = Self::parse_at(&content, path)?;
+        document.path = Some(path.to_path_buf());
+        Ok(document)
+    }
+
+    /// 解析 TOML 文本
+    pub fn parse(content: &str) -> Result<Self, QuantumConfigError> {
+        Self::parse_at(content, Path::new("<string>"))
+    }
+
+    fn parse_at(content: &str, path: &Path) -> Result<Self, QuantumConfigError> {
+        let document = content.parse::<DocumentMut>().map_err(|e| QuantumConfigError::FileParse {
+            format_name: "TOML".to_string(),
+            path: path.to_path_buf(),
+            source_error: e.to_string(),
+        })?;
+        Ok(Self { path: None, document })
+    }
+
+    /// 打开时的文件路径
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// 底层的 `toml_edit` 文档，供需要直接操作注释等细节的场景使用
+    pub fn document(&self) -> &DocumentMut {
+        &self.document
+    }
+
+    /// 底层的可变 `toml_edit` 文档
+    pub fn document_mut(&mut self) -> &mut DocumentMut {
+        &mut self.document
+    }
+
+    /// 将结构体或补丁合并到文档中
+    ///
+    /// # Errors
+    /// 值无法序列化或不是对象时返回错误
+    pub fn apply<T: Serialize>(&mut self, value: &T) -> Result<(), QuantumConfigError> {
+        match to_json(value)? {
+            JsonValue::Object(map) => {
+                merge_table(self.document.as_table_mut(), &map);
+                Ok(())
+            }
+            other => Err(QuantumConfigError::InvalidValue {
+                key_path: String::new(),
+                message: format!("expected a table, found {}", json_kind(&other)),
+            }),
+        }
+    }
+
+    /// 设置以 `.` 分隔的键路径上的值，缺失的中间表会被创建，`None` 等 `null` 值移除该键
+    pub fn set<T: Serialize>(&mut self, key_path: &str, value: &T) -> Result<(), QuantumConfigError> {
+        let mut nested = to_json(value)?;
+        for segment in key_path.split('.').rev() {
+            let mut map = serde_json::Map::new();
+            map.insert(segment.to_string(), nested);
+            nested = JsonValue::Object(map);
+        }
+        self.apply(&nested)
+    }
+
+    /// 移除以 `.` 分隔的键路径上的条目，返回条目是否存在
+    pub fn remove(&mut self, key_path: &str) -> bool {
+        let segments: Vec<&str> = key_path.split('.').collect();
+        let Some((last, parents)) = segments.split_last() else {
+            return false;
+        };
+        let mut table = self.document.as_table_mut();
+        for segment in parents {
+            match table.get_mut(segment) {
+                Some(Item::Table(child)) => table = child,
+                _ => return false,
+            }
+        }
+        table.remove(last).is_some()
+    }
+
+    /// 写回打开时的文件
+    ///
+    /// # Errors
+    /// 文档不是通过 [`TomlDocument::open`] 得到或写入失败时返回错误
+    pub fn save(&self) -> Result<(), QuantumConfigError> {
+        let path = self
+            .path
+            .as_deref()
+            .ok_or_else(|| QuantumConfigError::Internal("TOML document has no associated file path".to_string()))?;
+        self.save_to(path)
+    }
+
+    /// 写入指定文件，先写临时文件再重命名
+    pub fn save_to<P: AsRef<Path>>(&self, path: P) -> Result<(), QuantumConfigError> {
+        crate::persist::write_atomically(path.as_ref(), &self.document.to_string())
+    }
+}
+
+impl fmt::Display for TomlDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.document.fmt(f)
+    }
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<JsonValue, QuantumConfigError> {
+    serde_json::to_value(value)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize configuration: {}", e)))
+}
+
+fn json_kind(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "a boolean",
+        JsonValue::Number(_) => "a number",
+        JsonValue::String(_) => "a string",
+        JsonValue::Array(_) => "an array",
+        JsonValue::Object(_) => "a table",
+    }
+}
+
+/// 将 JSON 对象合并进 TOML 表，已有条目的注释与排版保持不变
+fn merge_table(table: &mut Table, map: &serde_json::Map<String, JsonValue>) {
+    for (key, value) in map {
+        match value {
+            JsonValue::Null => {
+                table.remove(key);
+            }
+            JsonValue::Object(child) => match table.get_mut(key) {
+                Some(Item::Table(existing)) => merge_table(existing, child),
+                Some(Item::Value(Value::InlineTable(existing))) => {
+                    let decor = existing.decor().clone();
+                    let mut inline = to_inline_table(child);
+                    *inline.decor_mut() = decor;
+                    *existing = inline;
+                }
+                _ => {
+                    let mut new_table = Table::new();
+                    merge_table(&mut new_table, child);
+                    table.insert(key, Item::Table(new_table));
+                }
+            },
+            value => {
+                let Some(mut new_value) = to_toml_value(value) else {
+                    continue;
+                };
+                match table.get_mut(key) {
+                    Some(Item::Value(existing)) => {
+                        // 保留行尾注释等装饰
+                        *new_value.decor_mut() = existing.decor().clone();
+                        *existing = new_value;
+                    }
+                    _ => {
+                        table.insert(key, Item::Value(new_value));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 将 JSON 值转换为 TOML 值，`null` 无法表示，返回 `None`
+fn to_toml_value(value: &JsonValue) -> Option<Value> {
+    match value {
+        JsonValue::Null => None,
+        JsonValue::Bool(b) => Some(Value::from(*b)),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Some(Value::from(i)),
+            None => n.as_f64().map(Value::from),
+        },
+        JsonValue::String(s) => Some(Value::from(s.as_str())),
+        JsonValue::Array(items) => Some(Value::Array(items.iter().filter_map(to_toml_value).collect::<Array>())),
+        JsonValue::Object(map) => Some(Value::InlineTable(to_inline_table(map))),
+    }
+}
+
+fn to_inline_table(map: &serde_json::Map<String, JsonValue>) -> InlineTable {
+    let mut table = InlineTable::new();
+    for (key, value) in map {
+        if let Some(value) = to_toml_value(value) {
+            table.insert(key, value);
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    const ORIGINAL: &str = "# 服务配置\n[server]\nhost = \"localhost\" # 本地调试\nport = 8080\n\n# 旧选项\n[legacy]\noption = true\n";
+
+    #[derive(Serialize)]
+    struct ServerPatch {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        host: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        port: Option<u16>,
+    }
+
+    #[test]
+    fn test_apply_set_and_remove_preserve_formatting() {
+        let mut document = TomlDocument::parse(ORIGINAL).unwrap();
+        document
+            .apply(&json!({ "server": ServerPatch { host: None, port: Some(9090) } }))
+            .unwrap();
+        document.set("server.host", &"0.0.0.0").unwrap();
+        document.set("logging.level", &"debug").unwrap();
+        assert!(document.remove("legacy.option"));
+        assert!(!document.remove("missing.option"));
+
+        assert_eq!(
+            document.to_string(),
+            "# 服务配置\n[server]\nhost = \"0.0.0.0\" # 本地调试\nport = 9090\n\n# 旧选项\n[legacy]\n\n[logging]\nlevel = \"debug\"\n"
+        );
+
+        document.set("server.host", &Option::<String>::None).unwrap();
+        assert!(document.document()["server"].get("host").is_none());
+    }
+
+    #[test]
+    fn test_open_save_and_errors() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+
+        let mut document = TomlDocument::open(&path).unwrap();
+        assert_eq!(document.path(), Some(path.as_path()));
+        document.set("name", &"app").unwrap();
+        document.save().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "name = \"app\"\n");
+
+        assert!(TomlDocument::parse("name = ").is_err());
+        assert!(TomlDocument::default().save().is_err());
+        let error = TomlDocument::default().apply(&vec![1, 2]).unwrap_err();
+        assert!(error.to_string().contains("expected a table, found an array"));
+    }
+}
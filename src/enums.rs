@@ -0,0 +1,117 @@
+//! 枚举字段
+//!
+//! 枚举字段按 serde 的表示方式从各来源读取：
+//!
+//! - 单元变体写成字符串：文件中 `mode = "fast"`，环境变量 `APP_MODE=fast`，命令行 `--mode fast`；
+//! - 外部标记（serde 默认）的结构体变体写成以变体名为键的表：文件中 `[cache.redis]` 下写 `url = "..."`，
+//!   环境变量 `APP_CACHE__REDIS__URL=...`；
+//! - 内部标记（`#[serde(tag = "type")]`）的变体把标记与字段写在同一层：文件中 `[store]` 下写
+//!   `type = "redis"` 与 `url = "..."`，环境变量 `APP_STORE__TYPE=redis`、`APP_STORE__URL=...`；
+//! - 相邻标记（`#[serde(tag = "type", content = "config")]`）与内部标记类似，字段位于 `content` 指定的键下。
+//!
+//! 环境变量（含 `.env` 文件）与命令行参数中的单元变体名不区分大小写，并忽略 `-` 与 `_`：
+//! 变体 `InMemory` 可以写成 `inmemory`、`IN_MEMORY` 或 `in-memory`。
+//! 文件中的变体名仍需与 serde 名称完全一致，避免同一文件在不同版本间含义不同。
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::report::{ConfigSources, ValueSource};
+use crate::required;
+use figment::providers::Serialized;
+use figment::value::{Dict, Map};
+use figment::{Figment, Metadata, Profile, Provider};
+
+/// 单次提取最多纠正的变体数量
+const MAX_COERCIONS: usize = 64;
+
+/// 提取配置，环境变量与命令行参数中大小写或分隔符不符的单元变体名会被纠正后重试
+///
+/// 纠正后的值合并到 `figment` 中（保留原有来源信息），以便随后的校验错误仍能指出来源。
+///
+/// # Errors
+/// 无法纠正的提取错误经 [`required::explain_extract_error`] 转换后返回
+pub fn extract_coercing_variants<T: Config>(
+    figment: &mut Figment,
+    sources: &ConfigSources,
+) -> Result<T, QuantumConfigError> {
+    let mut coerced: Vec<String> = Vec::new();
+    loop {
+        let error = match figment.extract::<T>() {
+            Ok(config) => return Ok(config),
+            Err(error) => error,
+        };
+        let coercion = coercion(&error, sources)
+            .filter(|(key_path, _)| !coerced.contains(key_path) && coerced.len() < MAX_COERCIONS);
+        let Some((key_path, variant)) = coercion else {
+            return Err(required::explain_extract_error::<T>(figment, error, sources));
+        };
+        let metadata = figment.find_metadata(&key_path).cloned().unwrap_or_else(|| Metadata::named("coerced variant"));
+        *figment = figment.clone().merge(CoercedValue {
+            metadata,
+            key_path: key_path.clone(),
+            variant,
+        });
+        coerced.push(key_path);
+    }
+}
+
+/// 错误是来自环境变量或命令行参数的未知变体、且能唯一匹配到某个变体时，返回（键路径，变体名）
+fn coercion(error: &figment::Error, sources: &ConfigSources) -> Option<(String, String)> {
+    let figment::error::Kind::UnknownVariant(found, expected) = &error.kind else {
+        return None;
+    };
+    let key_path = error.path.join(".");
+    if !matches!(
+        sources.source(&key_path),
+        ValueSource::Env(_) | ValueSource::Dotenv { .. } | ValueSource::Cli(_)
+    ) {
+        return None;
+    }
+    match_variant(found, expected).map(|variant| (key_path, variant.to_string()))
+}
+
+/// 忽略大小写与 `-`、`_` 后唯一匹配的变体名
+pub fn match_variant<'a>(found: &str, variants: &[&'a str]) -> Option<&'a str> {
+    let normalized = normalize(found);
+    let mut matches = variants.iter().filter(|variant| normalize(variant) == normalized);
+    let first = matches.next()?;
+    matches.next().is_none().then_some(*first)
+}
+
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| *c != '-' && *c != '_').flat_map(char::to_lowercase).collect()
+}
+
+/// 纠正后的变体名，沿用原始值的来源信息
+struct CoercedValue {
+    metadata: Metadata,
+    key_path: String,
+    variant: String,
+}
+
+impl Provider for CoercedValue {
+    fn metadata(&self) -> Metadata {
+        self.metadata.clone()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
+        Serialized::default(&self.key_path, &self.variant).data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_variant_ignores_case_and_separators() {
+        let variants = ["InMemory", "Redis", "disk_cache"];
+        assert_eq!(match_variant("inmemory", &variants), Some("InMemory"));
+        assert_eq!(match_variant("IN_MEMORY", &variants), Some("InMemory"));
+        assert_eq!(match_variant("in-memory", &variants), Some("InMemory"));
+        assert_eq!(match_variant("DISK-CACHE", &variants), Some("disk_cache"));
+        assert_eq!(match_variant("memcached", &variants), None);
+        // 规范化后有歧义时不纠正
+        assert_eq!(match_variant("ab", &["a_b", "A-B"]), None);
+    }
+}
@@ -57,6 +57,15 @@ pub enum TemplateFormat {
     Json,
     /// INI 格式模板
     Ini,
+    /// YAML 格式模板
+    #[cfg(feature = "yaml")]
+    Yaml,
+    /// HCL 格式模板
+    #[cfg(feature = "hcl")]
+    Hcl,
+    /// XML 格式模板
+    #[cfg(feature = "xml")]
+    Xml,
 }
 
 impl TemplateFormat {
@@ -66,6 +75,12 @@ impl TemplateFormat {
             TemplateFormat::Toml => "toml",
             TemplateFormat::Json => "json",
             TemplateFormat::Ini => "ini",
+            #[cfg(feature = "yaml")]
+            TemplateFormat::Yaml => "yaml",
+            #[cfg(feature = "hcl")]
+            TemplateFormat::Hcl => "hcl",
+            #[cfg(feature = "xml")]
+            TemplateFormat::Xml => "xml",
         }
     }
     
@@ -75,6 +90,12 @@ impl TemplateFormat {
             TemplateFormat::Toml => "TOML",
             TemplateFormat::Json => "JSON",
             TemplateFormat::Ini => "INI",
+            #[cfg(feature = "yaml")]
+            TemplateFormat::Yaml => "YAML",
+            #[cfg(feature = "hcl")]
+            TemplateFormat::Hcl => "HCL",
+            #[cfg(feature = "xml")]
+            TemplateFormat::Xml => "XML",
         }
     }
 }
@@ -111,6 +132,7 @@ pub enum QuantumConfigError {
     Figment(#[from] Box<figment::Error>),
 
     /// 命令行参数解析错误
+    #[cfg(feature = "cli")]
     #[error("Command line argument parsing error: {0}")]
     Clap(#[from] clap::Error),
 
@@ -118,10 +140,30 @@ pub enum QuantumConfigError {
     #[error("A required value was missing for key: {key_path}")]
     MissingValue { key_path: String },
 
+    /// 缺少多个必需的配置键，`searched` 为查找过的来源，
+    /// `hints` 为疑似拼写错误的环境变量提示
+    #[error("Missing required configuration keys: {}{}{}", keys.join(", "), format_searched(searched), format_hints(hints))]
+    MissingKeys { keys: Vec<String>, searched: Vec<String>, hints: Vec<String> },
+
     /// 无效值错误
     #[error("Invalid value for key '{key_path}': {message}")]
     InvalidValue { key_path: String, message: String },
 
+    /// 配置值提取失败，附带出错值的来源（文件、环境变量或命令行参数）
+    #[error("Invalid value for key '{key_path}' ({origin_key} from {origin}): {message}")]
+    Extraction {
+        /// 以 `.` 分隔的配置键路径
+        key_path: String,
+        /// 提供该值的来源名称
+        origin: String,
+        /// 该值在来源中的原始键，例如环境变量名或命令行参数
+        origin_key: String,
+        /// 错误描述
+        message: String,
+        #[source]
+        source: Box<figment::Error>,
+    },
+
     /// 配置目录未找到错误
     #[error("Configuration directory for {dir_type} not found. Expected at: {}", 
         expected_path.as_ref().map(|p| sanitize_path_for_display(p)).unwrap_or_else(|| "<unknown>".to_string()))]
@@ -145,6 +187,18 @@ pub enum QuantumConfigError {
     #[error("Unsupported configuration file format for: {}", sanitize_path_for_display(path))]
     UnsupportedFormat { path: PathBuf },
 
+    /// 不支持的文件编码
+    #[error("Unsupported encoding {encoding} in configuration file: {}", sanitize_path_for_display(path))]
+    UnsupportedEncoding { path: PathBuf, encoding: String },
+
+    /// 无效的配置来源地址
+    #[error("Invalid configuration source URL '{url}': {reason}")]
+    InvalidSourceUrl { url: String, reason: String },
+
+    /// 不支持（或未启用）的配置来源协议
+    #[error("Unsupported configuration source scheme '{scheme}': {reason}")]
+    UnsupportedScheme { scheme: String, reason: String },
+
     /// 模板生成错误
     #[error("Error generating {format:?} template: {reason}")]
     TemplateGeneration {
@@ -167,6 +221,306 @@ pub enum QuantumConfigError {
     /// 验证错误
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    /// 配置值违反字段约束（例如 `#[config(max_len = N)]`），敏感字段的值不会出现在错误信息中
+    #[error("Constraint violated for key '{key_path}'{}: {message}", format_origin(origin))]
+    ConstraintViolation {
+        /// 以 `.` 分隔的配置键路径
+        key_path: String,
+        /// 错误描述
+        message: String,
+        /// 出错值的来源，例如 `MYAPP_PORT from Quantum Config Environment Provider (prefix: MYAPP_)`
+        origin: Option<String>,
+    },
+
+    /// 自定义校验（[`crate::validate::Validate`]）未通过，包含全部出错字段
+    #[error("Configuration validation failed: {}", format_validation_errors(errors))]
+    ValidationFailed { errors: Vec<crate::validate::ValidationError> },
+
+    /// 读取远程配置服务（Vault、etcd、Consul、AWS、GCP、Azure、ZooKeeper）失败
+    #[error("{service} error for '{resource}': {reason}")]
+    Remote {
+        /// 服务名称，同时作为错误代码，例如 `vault`、`etcd`
+        service: &'static str,
+        /// 出错的密钥、键前缀或请求的资源
+        resource: String,
+        /// 失败原因
+        reason: String,
+    },
+
+    /// 运行命令读取配置值失败，参见 [`crate::providers::exec_provider`]
+    #[error("Command error for '{command}': {reason}")]
+    Exec { command: String, reason: String },
+
+    /// 配置文件监视错误
+    #[error("Config watch error: {0}")]
+    Watch(String),
+
+    /// 配置来源之间存在循环引用，`chain` 为从顶层来源开始的完整引用链
+    #[error("Configuration source cycle detected: {}", chain.join(" → "))]
+    SourceCycle { chain: Vec<String> },
+
+    /// 行为版本不受支持，参见 [`crate::compat`]
+    #[error("Unsupported behavior_version {version} (supported: 1..={max})")]
+    UnsupportedBehaviorVersion { version: u32, max: u32 },
+
+    /// 加载的配置来源总数超过上限
+    #[error("Too many configuration sources (limit {limit}) while loading {}", chain.join(" → "))]
+    TooManySources { limit: usize, chain: Vec<String> },
+
+    /// 加密配置文件无法解密
+    #[error("Failed to decrypt configuration file {}: {reason}", sanitize_path_for_display(path))]
+    Decryption { path: PathBuf, reason: String },
+
+    /// 配置来源的嵌套层数超过上限，`chain` 为从顶层来源开始的引用链
+    #[error("Configuration sources nested too deeply (limit {limit}): {}", chain.join(" → "))]
+    SourceDepthExceeded { limit: usize, chain: Vec<String> },
+
+    /// 合并结果中存在无法对应到任何字段的键，参见 [`crate::unknown`]
+    #[error("Unknown configuration keys: {}", format_unknown_keys(keys))]
+    UnknownKeys { keys: Vec<crate::unknown::UnknownKey> },
+
+    /// 来源中的配置无法迁移到最新版本，参见 [`crate::migrate`]
+    #[error("Failed to migrate configuration from {origin}: {reason}")]
+    Migration { origin: String, reason: String },
+
+    /// 字段的值来自 `#[config(sources(...))]` 或 `#[config(no_cli)]` 不允许的来源，参见 [`crate::restrict`]
+    #[error("Configuration key '{key_path}' must not be set by {origin}; allowed sources: {allowed}")]
+    SourceNotAllowed {
+        /// 以 `.` 分隔的配置键路径
+        key_path: String,
+        /// 提供该值的来源，例如 `command-line argument --password`
+        origin: String,
+        /// 允许的来源类别
+        allowed: String,
+    },
+}
+
+impl QuantumConfigError {
+    /// 稳定的错误代码（snake_case），便于测试与日志按错误类别匹配
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io { .. } => "io",
+            Self::FileReadError { .. } => "file_read",
+            Self::FileParse { .. } => "file_parse",
+            Self::Figment(_) => "figment",
+            #[cfg(feature = "cli")]
+            Self::Clap(_) => "clap",
+            Self::MissingValue { .. } => "missing_value",
+            Self::MissingKeys { .. } => "missing_keys",
+            Self::InvalidValue { .. } => "invalid_value",
+            Self::Extraction { .. } => "extraction",
+            Self::ConfigDirNotFound { .. } => "config_dir_not_found",
+            Self::NoConfigFilesFoundInDir { .. } => "no_config_files_found",
+            Self::SpecifiedFileNotFound { .. } => "specified_file_not_found",
+            Self::UnsupportedFormat { .. } => "unsupported_format",
+            Self::UnsupportedEncoding { .. } => "unsupported_encoding",
+            Self::InvalidSourceUrl { .. } => "invalid_source_url",
+            Self::UnsupportedScheme { .. } => "unsupported_scheme",
+            Self::TemplateGeneration { .. } => "template_generation",
+            Self::Internal(_) => "internal",
+            Self::AppNameResolution { .. } => "app_name_resolution",
+            Self::SecurityViolation { .. } => "security_violation",
+            Self::ValidationError(_) => "validation",
+            Self::ConstraintViolation { .. } => "constraint_violation",
+            Self::ValidationFailed { .. } => "validation_failed",
+            Self::Remote { service, .. } => service,
+            Self::Exec { .. } => "exec",
+            Self::Watch(_) => "watch",
+            Self::SourceCycle { .. } => "source_cycle",
+            Self::TooManySources { .. } => "too_many_sources",
+            Self::SourceDepthExceeded { .. } => "source_depth_exceeded",
+            Self::Decryption { .. } => "decryption",
+            Self::UnknownKeys { .. } => "unknown_keys",
+            Self::Migration { .. } => "migration",
+            Self::SourceNotAllowed { .. } => "source_not_allowed",
+            Self::UnsupportedBehaviorVersion { .. } => "unsupported_behavior_version",
+        }
+    }
+}
+
+impl QuantumConfigError {
+    /// 是否为缺少必需键的错误
+    pub fn is_missing(&self) -> bool {
+        matches!(self, Self::MissingKeys { .. } | Self::MissingValue { .. })
+    }
+
+    /// 是否表示来源暂时不可用（文件不存在或无法读取、网络请求失败），而不是来源的内容有误
+    pub fn is_unavailable(&self) -> bool {
+        match self {
+            Self::Io { source, .. } | Self::FileReadError { source, .. } => {
+                source.kind() != std::io::ErrorKind::InvalidData
+            }
+            Self::SpecifiedFileNotFound { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// 出错的配置键路径，错误与某个键无关时返回 `None`
+    pub fn key_path(&self) -> Option<&str> {
+        match self {
+            Self::MissingValue { key_path }
+            | Self::InvalidValue { key_path, .. }
+            | Self::Extraction { key_path, .. }
+            | Self::ConstraintViolation { key_path, .. }
+            | Self::SourceNotAllowed { key_path, .. } => Some(key_path),
+            _ => None,
+        }
+    }
+
+    /// 转换为提供器返回的 figment 错误，`context` 为信息开头的提供器说明
+    ///
+    /// 来源不可用的错误改用固定前缀，调用方通过 [`is_unavailable_error`] 识别；
+    /// 带有键路径的错误同时设置 figment 错误的路径，提取时的错误仍能指出出错的键。
+    pub(crate) fn into_provider_error(self, context: Option<&str>) -> figment::Error {
+        let key_path = self.key_path().map(|key_path| key_path.split('.').map(str::to_string).collect());
+        let mut error = match context {
+            _ if self.is_unavailable() => figment::Error::from(format!("{}{}", UNAVAILABLE_PREFIX, self)),
+            Some(context) => figment::Error::from(format!("{}: {}", context, self)),
+            None => figment::Error::from(self.to_string()),
+        };
+        if let Some(key_path) = key_path {
+            error.path = key_path;
+        }
+        error
+    }
+
+    /// 为字段约束与校验错误补充出错值的来源（文件、环境变量或命令行参数），其余错误原样返回
+    pub fn with_origin(self, figment: &figment::Figment) -> Self {
+        let find_origin = |key_path: &str| {
+            figment.find_metadata(key_path).map(|metadata| {
+                let path: Vec<&str> = key_path.split('.').collect();
+                let (origin, origin_key) = describe_origin(metadata, figment.profile(), &path);
+                format!("{} from {}", origin_key, origin)
+            })
+        };
+        match self {
+            QuantumConfigError::ConstraintViolation { key_path, message, origin: None } => {
+                let origin = find_origin(&key_path);
+                QuantumConfigError::ConstraintViolation { key_path, message, origin }
+            }
+            QuantumConfigError::ValidationFailed { mut errors } => {
+                for error in errors.iter_mut().filter(|error| error.origin.is_none()) {
+                    error.origin = find_origin(&error.field);
+                }
+                QuantumConfigError::ValidationFailed { errors }
+            }
+            other => other,
+        }
+    }
+}
+
+/// 描述值的来源，返回（来源名称，该值在来源中的原始键）
+/// 来源不可用时提供器错误信息的前缀，参见 [`QuantumConfigError::into_provider_error`]
+const UNAVAILABLE_PREFIX: &str = "Configuration source unavailable: ";
+
+/// 提供器错误是否表示来源暂时不可用
+pub(crate) fn is_unavailable_error(error: &figment::Error) -> bool {
+    matches!(&error.kind, figment::error::Kind::Message(message) if message.starts_with(UNAVAILABLE_PREFIX))
+}
+
+pub(crate) fn describe_origin<K: AsRef<str>>(
+    metadata: &figment::Metadata,
+    profile: &figment::Profile,
+    path: &[K],
+) -> (String, String) {
+    let origin_key = metadata.interpolate(profile, path);
+    let origin = match metadata.source.as_ref().and_then(|s| s.file_path()) {
+        Some(path) => format!("file {}", sanitize_path_for_display(path)),
+        None => metadata.name.to_string(),
+    };
+    (origin, origin_key)
+}
+
+/// 格式化查找过的来源列表
+fn format_searched(searched: &[String]) -> String {
+    if searched.is_empty() {
+        " (no configuration sources were found)".to_string()
+    } else {
+        format!(" (searched: {})", searched.join(", "))
+    }
+}
+
+/// 格式化拼写提示
+fn format_hints(hints: &[String]) -> String {
+    if hints.is_empty() {
+        String::new()
+    } else {
+        format!("; {}", hints.join("; "))
+    }
+}
+
+/// 格式化可选的来源描述
+fn format_origin(origin: &Option<String>) -> String {
+    origin.as_ref().map(|origin| format!(" ({})", origin)).unwrap_or_default()
+}
+
+impl From<Vec<crate::validate::ValidationError>> for QuantumConfigError {
+    fn from(errors: Vec<crate::validate::ValidationError>) -> Self {
+        QuantumConfigError::ValidationFailed { errors }
+    }
+}
+
+/// 描述提取错误的原因，枚举相关的错误给出更明确的说明
+fn describe_kind(kind: &figment::error::Kind) -> String {
+    use figment::error::{Actual, Kind};
+    match kind {
+        Kind::UnknownVariant(found, expected) => {
+            let expected: Vec<String> = expected.iter().map(|variant| format!("`{}`", variant)).collect();
+            format!("unknown variant `{}`, expected one of {}", found, expected.join(", "))
+        }
+        // 只写了变体名，但该变体带有字段
+        Kind::InvalidType(Actual::UnitVariant, expected) if expected.ends_with(" variant") => format!(
+            "expected {}; variants with fields must be written as a table keyed by the variant name \
+             (e.g. `[key.variant]` in files, `KEY__VARIANT__FIELD` in environment variables)",
+            expected
+        ),
+        other => other.to_string(),
+    }
+}
+
+/// 以 `; ` 连接各未知键
+fn format_unknown_keys(keys: &[crate::unknown::UnknownKey]) -> String {
+    keys.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+/// 以 `; ` 连接各字段的校验错误
+fn format_validation_errors(errors: &[crate::validate::ValidationError]) -> String {
+    errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ")
+}
+
+impl From<figment::Error> for QuantumConfigError {
+    /// 将 figment 提取错误转换为带来源信息的错误
+    ///
+    /// 缺少字段转换为 `MissingValue`；能定位到来源的值错误转换为 `Extraction`；
+    /// 其余情况保留为 `Figment`。
+    fn from(error: figment::Error) -> Self {
+        if let figment::error::Kind::MissingField(field) = &error.kind {
+            let key_path = error
+                .path
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(field.as_ref()))
+                .collect::<Vec<_>>()
+                .join(".");
+            return QuantumConfigError::MissingValue { key_path };
+        }
+
+        match &error.metadata {
+            Some(metadata) if !error.path.is_empty() => {
+                let profile = error.profile.clone().unwrap_or_default();
+                let (origin, origin_key) = describe_origin(metadata, &profile, &error.path);
+                QuantumConfigError::Extraction {
+                    key_path: error.path.join("."),
+                    origin,
+                    origin_key,
+                    message: describe_kind(&error.kind),
+                    source: Box::new(error),
+                }
+            }
+            _ => QuantumConfigError::Figment(Box::new(error)),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -226,6 +580,35 @@ mod tests {
         assert!(error_msg.contains("A required value was missing for key: database.host"));
     }
 
+    #[test]
+    fn test_figment_error_conversion_carries_origin() {
+        use figment::providers::Serialized;
+
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Server {
+            port: u16,
+            host: String,
+        }
+
+        let figment = figment::Figment::from(Serialized::defaults(serde_json::json!({
+            "port": "not a port",
+            "host": "localhost",
+        })));
+        let error: QuantumConfigError = figment.extract::<Server>().unwrap_err().into();
+        match error {
+            QuantumConfigError::Extraction { key_path, origin_key, .. } => {
+                assert_eq!(key_path, "port");
+                assert!(origin_key.ends_with("port"));
+            }
+            other => panic!("Expected Extraction error, got {:?}", other),
+        }
+
+        let figment = figment::Figment::from(Serialized::defaults(serde_json::json!({ "port": 8080 })));
+        let error: QuantumConfigError = figment.extract::<Server>().unwrap_err().into();
+        assert!(matches!(error, QuantumConfigError::MissingValue { key_path } if key_path == "host"));
+    }
+
     #[test]
     fn test_invalid_value_error_display() {
         let error = QuantumConfigError::InvalidValue {
@@ -285,6 +668,18 @@ mod tests {
         assert!(error_msg.contains("/custom/config.toml"));
     }
 
+    #[test]
+    fn test_error_codes() {
+        let error = QuantumConfigError::MissingValue { key_path: "server.port".to_string() };
+        assert_eq!(error.code(), "missing_value");
+        let error = QuantumConfigError::ConstraintViolation {
+            key_path: "name".to_string(),
+            message: "too long".to_string(),
+            origin: None,
+        };
+        assert_eq!(error.code(), "constraint_violation");
+    }
+
     #[test]
     fn test_unsupported_format_error_display() {
         let error = QuantumConfigError::UnsupportedFormat {
@@ -296,6 +691,30 @@ mod tests {
         assert!(error_msg.contains("/config/app.xml"));
     }
 
+    #[test]
+    fn test_unsupported_encoding_error_display() {
+        let error = QuantumConfigError::UnsupportedEncoding {
+            path: PathBuf::from("/config/app.toml"),
+            encoding: "UTF-32LE".to_string(),
+        };
+
+        let error_msg = error.to_string();
+        assert!(error_msg.contains("Unsupported encoding UTF-32LE"));
+        assert!(error_msg.contains("app.toml"));
+    }
+
+    #[test]
+    fn test_unsupported_scheme_error_display() {
+        let error = QuantumConfigError::UnsupportedScheme {
+            scheme: "sftp".to_string(),
+            reason: "enable the `sftp` feature".to_string(),
+        };
+
+        let error_msg = error.to_string();
+        assert!(error_msg.contains("Unsupported configuration source scheme 'sftp'"));
+        assert!(error_msg.contains("`sftp` feature"));
+    }
+
     #[test]
     fn test_template_generation_error_display() {
         let error = QuantumConfigError::TemplateGeneration {
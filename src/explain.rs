@@ -0,0 +1,327 @@
+//! 生效配置说明
+//!
+//! [`crate::Config::explain`] 加载配置并生成 [`Explanation`]：按结构体层级列出每个字段的最终值
+//! 与提供该值的来源（默认值、文件及其行号、环境变量、命令行参数等），
+//! 类似 `git config --list --show-origin`。敏感键（[`crate::Config::secret_keys`]）的值显示为 `[REDACTED]`。
+//!
+//! [`Explanation`] 可以序列化为 JSON 供工具使用，`Display` 输出适合控制台阅读的树形视图：
+//!
+//! ```text
+//! database
+//! ├── password = "[REDACTED]"  <- environment variable APP_DATABASE__PASSWORD
+//! └── url = "pg://db"          <- config file /etc/app/config.toml:3
+//! name = "svc"                 <- default
+//! ```
+//!
+//! 文件行号按键名在文件中逐段查找得到，属于尽力而为：无法定位（例如加密文件）时省略。
+
+use crate::error::QuantumConfigError;
+use crate::redact;
+use crate::report::{ConfigReport, ValueSource};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// 值在文件中的位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceLocation {
+    /// 文件路径
+    pub file: PathBuf,
+    /// 从 1 开始的行号
+    pub line: usize,
+}
+
+/// 配置树中的一个节点
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExplainNode {
+    /// 键名（路径的最后一段）
+    pub key: String,
+    /// 以 `.` 分隔的完整键路径
+    pub path: String,
+    /// 叶子节点的最终值，敏感值为 `"[REDACTED]"`；映射节点为 `None`
+    pub value: Option<JsonValue>,
+    /// 是否为敏感键
+    pub secret: bool,
+    /// 提供最终值的来源描述
+    pub source: Option<String>,
+    /// 值来自文件时的位置
+    pub location: Option<SourceLocation>,
+    /// 子节点，按键名排序
+    pub children: Vec<ExplainNode>,
+}
+
+/// 生效配置说明
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Explanation {
+    /// 顶层字段
+    pub fields: Vec<ExplainNode>,
+}
+
+impl Explanation {
+    /// 按键路径查找节点
+    pub fn find(&self, path: &str) -> Option<&ExplainNode> {
+        let mut nodes = &self.fields;
+        let mut found = None;
+        for segment in path.split('.') {
+            let node = nodes.iter().find(|node| node.key == segment)?;
+            nodes = &node.children;
+            found = Some(node);
+        }
+        found
+    }
+}
+
+/// 根据提取出的配置与来源报告生成说明
+///
+/// # Errors
+/// 配置无法序列化时返回错误
+pub fn explain<T: Serialize>(
+    config: &T,
+    report: &ConfigReport,
+    secret_keys: &[String],
+) -> Result<Explanation, QuantumConfigError> {
+    let mut value = serde_json::to_value(config)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize configuration for explain: {}", e)))?;
+    for key in secret_keys {
+        redact::redact_key(&mut value, key);
+    }
+    let mut locator = Locator::default();
+    let fields = match &value {
+        JsonValue::Object(map) => map
+            .iter()
+            .map(|(key, child)| build_node(key, key.clone(), child, report, secret_keys, &mut locator))
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(Explanation { fields })
+}
+
+fn build_node(
+    key: &str,
+    path: String,
+    value: &JsonValue,
+    report: &ConfigReport,
+    secret_keys: &[String],
+    locator: &mut Locator,
+) -> ExplainNode {
+    let secret = secret_keys.contains(&path);
+    if let JsonValue::Object(map) = value {
+        if !map.is_empty() {
+            let children = map
+                .iter()
+                .map(|(child_key, child)| {
+                    build_node(child_key, format!("{}.{}", path, child_key), child, report, secret_keys, locator)
+                })
+                .collect();
+            return ExplainNode {
+                key: key.to_string(),
+                path,
+                value: None,
+                secret,
+                source: None,
+                location: None,
+                children,
+            };
+        }
+    }
+
+    let source = report.source(&path);
+    let location = source.and_then(|source| locator.location(source, &path));
+    ExplainNode {
+        key: key.to_string(),
+        source: source.map(ToString::to_string),
+        path,
+        value: Some(value.clone()),
+        secret,
+        location,
+        children: Vec::new(),
+    }
+}
+
+/// 缓存已读取文件内容的行号查找器
+#[derive(Default)]
+pub(crate) struct Locator {
+    files: Vec<(PathBuf, Option<String>)>,
+}
+
+impl Locator {
+    /// 来源为文件时键所在的位置
+    pub(crate) fn location(&mut self, source: &ValueSource, key_path: &str) -> Option<SourceLocation> {
+        match source {
+            ValueSource::SystemFile(file) | ValueSource::UserFile(file) | ValueSource::ConfigFile(file) => {
+                self.locate(file, key_path).map(|line| SourceLocation { file: file.clone(), line })
+            }
+            _ => None,
+        }
+    }
+
+    fn locate(&mut self, file: &Path, key_path: &str) -> Option<usize> {
+        let index = match self.files.iter().position(|(path, _)| path == file) {
+            Some(index) => index,
+            None => {
+                self.files.push((file.to_path_buf(), std::fs::read_to_string(file).ok()));
+                self.files.len() - 1
+            }
+        };
+        let content = self.files[index].1.as_deref()?;
+        find_key_line(content, key_path)
+    }
+}
+
+/// 逐段查找键在文件中的行号（从 1 开始）
+///
+/// 每一段在上一段所在行之后查找：形如 `key = `、`key: `、`"key": ` 的行，
+/// 或与接下来若干段一致的表头（如 `[server]`、`[server.tls]`）。
+fn find_key_line(content: &str, key_path: &str) -> Option<usize> {
+    let lines: Vec<&str> = content.lines().collect();
+    let segments: Vec<&str> = key_path.split('.').collect();
+    let (mut start, mut consumed) = (0, 0);
+    let mut found = None;
+    while consumed < segments.len() {
+        let (offset, count) = lines[start..]
+            .iter()
+            .enumerate()
+            .find_map(|(offset, line)| match_line(line, &segments[consumed..]).map(|count| (offset, count)))?;
+        found = Some(start + offset);
+        start += offset + 1;
+        consumed += count;
+    }
+    found.map(|index| index + 1)
+}
+
+/// 该行定义了 `segments` 开头的几段，返回段数
+fn match_line(line: &str, segments: &[&str]) -> Option<usize> {
+    let line = line.trim_start();
+    if let Some(header) = line.strip_prefix('[') {
+        let header = header.trim_start_matches('[').split(']').next().unwrap_or("");
+        let parts: Vec<&str> = header.split('.').map(|part| part.trim().trim_matches('"')).collect();
+        if parts.len() <= segments.len() && parts == segments[..parts.len()] {
+            return Some(parts.len());
+        }
+        // 嵌套表头的完整路径包含已匹配的上级段，例如在 `app` 之后查找 `[app.server]`
+        return (parts.last() == segments.first()).then_some(1);
+    }
+    let key = segments.first()?;
+    let rest = if let Some(quoted) = line.strip_prefix('"') {
+        quoted.strip_prefix(key).and_then(|rest| rest.strip_prefix('"'))
+    } else {
+        line.strip_prefix(key)
+    };
+    rest.is_some_and(|rest| {
+        let rest = rest.trim_start();
+        rest.starts_with('=') || rest.starts_with(':')
+    })
+    .then_some(1)
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut rows = Vec::new();
+        for node in &self.fields {
+            collect_rows(node, "", "", &mut rows);
+        }
+        let width = rows.iter().map(|(label, _)| label.chars().count()).max().unwrap_or(0);
+        for (label, origin) in rows {
+            match origin {
+                Some(origin) => {
+                    let padding = width - label.chars().count();
+                    writeln!(f, "{}{}  <- {}", label, " ".repeat(padding), origin)?
+                }
+                None => writeln!(f, "{}", label)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 生成树形视图的每一行：（带缩进的键与值，来源）
+fn collect_rows(node: &ExplainNode, prefix: &str, child_prefix: &str, rows: &mut Vec<(String, Option<String>)>) {
+    match &node.value {
+        Some(value) => {
+            let origin = node.source.as_ref().map(|source| match &node.location {
+                Some(location) => format!("{}:{}", source, location.line),
+                None => source.clone(),
+            });
+            rows.push((format!("{}{} = {}", prefix, node.key, value), origin));
+        }
+        None => rows.push((format!("{}{}", prefix, node.key), None)),
+    }
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len();
+        let (branch, continuation) = if last { ("└── ", "    ") } else { ("├── ", "│   ") };
+        collect_rows(
+            child,
+            &format!("{}{}", child_prefix, branch),
+            &format!("{}{}", child_prefix, continuation),
+            rows,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::KeySource;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_key_line_in_common_formats() {
+        let toml = "name = \"svc\"\n\n[database]\n# comment\nurl = \"pg://db\"\n\n[server.tls]\ncert = \"c\"\n";
+        assert_eq!(find_key_line(toml, "name"), Some(1));
+        assert_eq!(find_key_line(toml, "database.url"), Some(5));
+        assert_eq!(find_key_line(toml, "server.tls.cert"), Some(8));
+        assert_eq!(find_key_line(toml, "database.password"), None);
+
+        let json = "{\n  \"database\": {\n    \"url\": \"pg://db\"\n  }\n}\n";
+        assert_eq!(find_key_line(json, "database.url"), Some(3));
+
+        let yaml = "database:\n  url: pg://db\n";
+        assert_eq!(find_key_line(yaml, "database.url"), Some(2));
+    }
+
+    #[test]
+    fn test_explain_builds_redacted_tree() {
+        let config = json!({
+            "name": "svc",
+            "database": {"url": "pg://db", "password": "hunter2"}
+        });
+        let report = ConfigReport {
+            keys: vec![
+                KeySource {
+                    key: "database.password".to_string(),
+                    source: ValueSource::Env("APP_DATABASE__PASSWORD".to_string()),
+                },
+                KeySource {
+                    key: "database.url".to_string(),
+                    source: ValueSource::Cli("--database-url".to_string()),
+                },
+                KeySource {
+                    key: "name".to_string(),
+                    source: ValueSource::Default,
+                },
+            ],
+            layers: Vec::new(),
+            deprecations: Vec::new(),
+        };
+
+        let explanation = explain(&config, &report, &["database.password".to_string()]).unwrap();
+        let password = explanation.find("database.password").unwrap();
+        assert!(password.secret);
+        assert_eq!(password.value, Some(json!("[REDACTED]")));
+        assert_eq!(password.source.as_deref(), Some("environment variable APP_DATABASE__PASSWORD"));
+        assert!(explanation.find("database").unwrap().value.is_none());
+
+        let rendered = explanation.to_string();
+        assert_eq!(
+            rendered,
+            concat!(
+                "database\n",
+                "├── password = \"[REDACTED]\"  <- environment variable APP_DATABASE__PASSWORD\n",
+                "└── url = \"pg://db\"          <- command-line argument --database-url\n",
+                "name = \"svc\"                 <- default\n",
+            )
+        );
+        assert!(!rendered.contains("hunter2"));
+    }
+}
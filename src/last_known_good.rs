@@ -0,0 +1,102 @@
+//! 最后一次成功加载的配置快照
+//!
+//! 边缘部署等网络不稳定的环境中，启动时远程来源或配置文件可能暂时不可用。通过
+//! [`LoadOptions::with_last_known_good`](crate::LoadOptions::with_last_known_good) 指定快照文件后：
+//!
+//! ```ignore
+//! let options = LoadOptions::new().with_last_known_good("/var/cache/app/last-known-good.json");
+//! let config = AppConfig::load_with_options(options)?;
+//! ```
+//!
+//! - 每次成功加载并通过校验后，派生类型把生效配置以 JSON 写入快照文件（先写临时文件再重命名）；
+//! - 之后的加载中，远程来源请求失败、配置文件无法读取、或 `--config` 指定的文件不存在时，
+//!   输出警告日志，失败的来源不提供任何值，快照作为最低优先级的来源（仅高于默认值）参与合并，
+//!   其余正常的来源、环境变量与命令行参数仍然覆盖快照中的值；
+//! - 来源的内容无法解析时不回退，按原样返回解析错误，避免错误的配置被快照掩盖；
+//! - 快照文件不存在或无法读取时按原样返回加载错误。
+//!
+//! 敏感字段（`#[config(secret)]`）不写入快照，回退时需要由环境变量等仍然可用的来源提供。
+//! 直接使用 [`crate::QuantumConfigBuilder`] 时，在加载成功后自行调用 [`save`] 写入快照。
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::logging::log_warn;
+use std::path::Path;
+
+/// 把配置写入快照文件，省略敏感字段
+///
+/// 快照所在的目录不存在时自动创建。
+///
+/// # Errors
+/// 配置无法序列化或写入失败时返回错误
+pub fn save<T: Config>(config: &T, path: &Path) -> Result<(), QuantumConfigError> {
+    let mut json = serde_json::to_value(config)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize configuration snapshot: {}", e)))?;
+    for key in T::secret_keys() {
+        crate::redact::remove_key(&mut json, &key);
+    }
+    let content = serde_json::to_string_pretty(&crate::persist::strip_nulls(json))
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize configuration snapshot: {}", e)))?;
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|source| QuantumConfigError::Io {
+            source,
+            path: parent.to_path_buf(),
+        })?;
+    }
+    crate::persist::write_atomically(path, &content)
+}
+
+/// 把配置写入快照文件，失败时只输出警告日志而不影响加载结果
+pub fn record<T: Config>(config: &T, path: &Path) {
+    if let Err(e) = save(config, path) {
+        log_warn!(
+            "Failed to save the last known good configuration to {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use tempfile::tempdir;
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct SnapshotConfig {
+        name: String,
+        password: String,
+        timeout: Option<u64>,
+    }
+
+    impl Config for SnapshotConfig {
+        fn load() -> Result<Self, QuantumConfigError> {
+            Ok(Self::default())
+        }
+
+        fn load_with_args(_args: Vec<String>) -> Result<Self, QuantumConfigError> {
+            Self::load()
+        }
+
+        fn secret_keys() -> Vec<String> {
+            vec!["password".to_string()]
+        }
+    }
+
+    #[test]
+    fn test_save_writes_json_without_secrets() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("cache/last-known-good.json");
+        let config = SnapshotConfig {
+            name: "svc".to_string(),
+            password: "hunter2".to_string(),
+            timeout: None,
+        };
+        save(&config, &path).unwrap();
+
+        let snapshot: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(snapshot, serde_json::json!({ "name": "svc" }));
+        assert!(!path.with_file_name("last-known-good.json.tmp").exists());
+    }
+}
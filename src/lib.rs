@@ -8,7 +8,7 @@
 //!
 //! ## Features
 //!
-//! - **Multiple Configuration Sources**: Load from TOML, JSON, YAML files, environment variables, and CLI arguments
+//! - **Multiple Configuration Sources**: Load from TOML, JSON and INI files (JSON5, YAML, HCL and XML behind the `json5`, `yaml`, `hcl` and `xml` features), environment variables, and CLI arguments
 //! - **Hierarchical Configuration**: Support for nested configuration structures
 //! - **Type Safety**: Automatic deserialization with compile-time type checking
 //! - **Flexible Priority**: Configurable precedence for different configuration sources
@@ -35,23 +35,85 @@
 //! }
 //! ```
 
+mod logging;
+
+pub mod audit;
+pub mod builder;
+pub mod cache;
+pub mod check;
+pub mod cli;
+pub mod compat;
+pub mod config;
+pub mod context;
+pub mod deprecated;
+pub mod diff;
+pub mod drift;
+pub mod edit;
+pub mod enums;
 pub mod error;
+pub mod explain;
+pub mod health;
+pub mod last_known_good;
+pub mod loader;
+pub mod lossy;
 pub mod meta;
+pub mod migrate;
+pub mod namespace;
+pub mod observe;
+pub mod options;
 pub mod path_conversion;
 pub mod paths;
+pub mod persist;
+#[cfg(feature = "watch")]
+pub mod poll;
 pub mod providers;
+pub mod redact;
+#[cfg(feature = "watch")]
+pub mod reload;
+pub mod report;
+pub mod required;
+pub mod resolution;
+pub mod restrict;
+pub mod scrub;
+pub mod secret;
+#[cfg(all(unix, feature = "signals"))]
+pub mod signals;
+pub mod template;
+pub mod testing;
+pub mod transform;
+pub mod types;
+pub mod unknown;
+pub mod validate;
+#[cfg(feature = "watch")]
+pub mod watch;
 
-#[cfg(test)]
+// 派生类型的测试通过命令行参数指定配置文件
+#[cfg(all(test, feature = "cli"))]
 mod integration_tests;
 
 #[cfg(test)]
 mod security_tests;
 
 // Re-export main types
+pub use builder::{QuantumConfigBuilder, SourceKind};
+pub use check::{check, CheckReport, LoadCheckReport};
+pub use health::{ConfigHealth, HealthReport};
+pub use loader::Loader;
+pub use observe::LoadObserver;
+pub use options::{LoadOptions, WaitForFiles};
+pub use report::{ConfigReport, LoadReport, ValueSource};
+pub use explain::Explanation;
+pub use secret::SecretString;
+#[cfg(feature = "watch")]
+pub use reload::Reloadable;
+pub use validate::{Validate, ValidationError};
 pub use error::{ConfigDirType, QuantumConfigError};
 pub use meta::{ClapAttrsMeta, FieldMeta, QuantumConfigAppMeta, StructMeta};
 // PathConverter and PathFormat are internal utilities, not exposed to users
-pub use paths::{add_specified_config_file, resolve_config_files, ConfigFilePath, ConfigFileType};
+pub use paths::{
+    add_specified_config_file, candidate_config_dirs, candidate_config_files, resolve_config_files, ConfigDir,
+    ConfigFilePath, ConfigFileType, PathStrategy,
+};
 
 // 对外重导出 Serde 常用 traits
 pub use serde::{Deserialize, Serialize};
@@ -60,9 +122,16 @@ pub use ::serde as serde;
 
 // 新增：对外重导出 figment 与 clap 常用类型，供 derive 宏下游直接使用
 pub use figment::Figment;
+#[cfg(feature = "cli")]
 pub use clap::{Arg, ArgAction, ArgMatches, Command};
+// 未启用 `cli` 特性时派生宏生成的代码使用占位类型
+#[cfg(not(feature = "cli"))]
+pub use cli::{ArgMatches, Command};
 // 兼容派生宏生成代码：在 crate 根下提供 `toml` 模块路径
 pub use ::toml as toml;
+// 兼容派生宏生成代码：在 crate 根下提供 `serde_json` 模块路径
+pub use ::serde_json as serde_json;
 
-// 对外重导出 derive 宏
+// 对外重导出 Config trait 与同名 derive 宏（分属类型与宏命名空间）
+pub use config::Config;
 pub use quantum_config_derive::Config;
@@ -0,0 +1,162 @@
+//! 共享合并结果的配置加载器
+//!
+//! 应用有多个配置结构体时，分别调用各自的 `load()` 会重复查找配置文件、读取环境变量与解析命令行参数。
+//! [`Loader`] 只合并一次来源，之后从同一个合并结果中提取任意多个结构体：
+//!
+//! ```ignore
+//! use quantum_config::Loader;
+//!
+//! let loader = Loader::for_config::<AppConfig>()?;
+//! let app: AppConfig = loader.extract()?;
+//! let database: DbConfig = loader.extract_at("database")?;
+//! let cache: CacheConfig = loader.extract_at("cache")?;
+//! ```
+//!
+//! 每次提取都以目标类型的 `Default` 值为底（`#[config(no_defaults)]` 除外），
+//! 并按目标类型自己的规则应用值转换与校验，与 [`Config::extract_at`] 相同。
+
+use crate::builder::QuantumConfigBuilder;
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::report::ConfigSources;
+use figment::Figment;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// 只合并一次来源、可多次提取配置的加载器
+#[derive(Debug, Clone)]
+pub struct Loader {
+    /// 合并结果
+    figment: Figment,
+    /// 合并结果中各键的来源
+    sources: ConfigSources,
+}
+
+impl Loader {
+    /// 按 `T` 的派生规则（配置文件路径、环境变量前缀、命令行参数等）合并来源，参见 [`Config::load_figment`]
+    ///
+    /// # Errors
+    /// 读取或合并来源失败时返回错误
+    pub fn for_config<T: Config>() -> Result<Self, QuantumConfigError> {
+        let (figment, sources) = T::load_figment()?;
+        Ok(Self { figment, sources })
+    }
+
+    /// 按构建器的设置合并来源
+    ///
+    /// # Errors
+    /// 读取或合并来源失败时返回错误
+    pub fn from_builder<T: Serialize + DeserializeOwned>(builder: QuantumConfigBuilder<T>) -> Result<Self, QuantumConfigError> {
+        let (figment, sources) = builder.figment_with_sources()?;
+        Ok(Self { figment, sources })
+    }
+
+    /// 合并结果
+    pub fn figment(&self) -> &Figment {
+        &self.figment
+    }
+
+    /// 合并结果中各键的来源，可用于为提取出的顶层配置生成 [`crate::ConfigReport`]
+    pub fn sources(&self) -> &ConfigSources {
+        &self.sources
+    }
+
+    /// 提取 `T`：有命名空间（`#[config(namespace = "...")]`）时取命名空间下的子树，否则取整个合并结果
+    ///
+    /// # Errors
+    /// 提取或校验失败时返回错误
+    pub fn extract<T: Config>(&self) -> Result<T, QuantumConfigError> {
+        T::load_nested_from(&self.figment)
+    }
+
+    /// 提取 `key`（以 `.` 分隔的键路径）下的子树为 `T`，子树不存在时使用 `T` 的默认值
+    ///
+    /// # Errors
+    /// 提取或校验失败时返回错误
+    pub fn extract_at<T: Config>(&self, key: &str) -> Result<T, QuantumConfigError> {
+        T::extract_at(&self.figment, Some(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct DbConfig {
+        url: String,
+        max_conns: u32,
+    }
+
+    impl Config for DbConfig {
+        fn load() -> Result<Self, QuantumConfigError> {
+            Ok(Self::default())
+        }
+
+        fn load_with_args(_args: Vec<String>) -> Result<Self, QuantumConfigError> {
+            Self::load()
+        }
+
+        fn validate(&self) -> Result<(), QuantumConfigError> {
+            if self.max_conns > 100 {
+                return Err(QuantumConfigError::ValidationError("max_conns must not exceed 100".to_string()));
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct CacheConfig {
+        ttl: u64,
+    }
+
+    impl Config for CacheConfig {
+        fn load() -> Result<Self, QuantumConfigError> {
+            Ok(Self::default())
+        }
+
+        fn load_with_args(_args: Vec<String>) -> Result<Self, QuantumConfigError> {
+            Self::load()
+        }
+    }
+
+    #[test]
+    fn test_extract_at_reads_sections_of_one_merge() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.toml");
+        fs::write(&path, "[database]\nurl = \"postgres://db\"\nmax_conns = 10\n\n[cache]\nttl = 60\n").unwrap();
+
+        let loader = Loader::from_builder(
+            QuantumConfigBuilder::<serde_json::Value>::new("qc-loader-test")
+                .with_default_paths(false)
+                .with_file(&path)
+                .with_env_prefix("QC_LOADER_")
+                .with_env_source(crate::providers::MapEnv::new().with_var("QC_LOADER_DATABASE__MAX_CONNS", "20")),
+        )
+        .unwrap();
+        // 合并结果已在内存中，删除文件不影响之后的提取
+        fs::remove_file(&path).unwrap();
+
+        let database: DbConfig = loader.extract_at("database").unwrap();
+        assert_eq!(database, DbConfig { url: "postgres://db".to_string(), max_conns: 20 });
+        assert_eq!(loader.extract_at::<CacheConfig>("cache").unwrap().ttl, 60);
+        // 不存在的子树使用默认值
+        assert_eq!(loader.extract_at::<CacheConfig>("missing").unwrap(), CacheConfig::default());
+        assert!(matches!(
+            loader.sources().source("database.max_conns"),
+            crate::ValueSource::Env(name) if name == "QC_LOADER_DATABASE__MAX_CONNS"
+        ));
+
+        let invalid = Loader::from_builder(
+            QuantumConfigBuilder::<serde_json::Value>::new("qc-loader-test")
+                .with_default_paths(false)
+                .with_env_prefix("QC_LOADER_")
+                .with_env_source(crate::providers::MapEnv::new().with_var("QC_LOADER_DATABASE__MAX_CONNS", "500")),
+        )
+        .unwrap();
+        assert!(matches!(invalid.extract_at::<DbConfig>("database"), Err(QuantumConfigError::ValidationError(_))));
+    }
+}
@@ -0,0 +1,127 @@
+//! 内部日志宏
+//!
+//! 启用 `tracing-support` 时通过 tracing 输出，仅启用 `log-facade` 时通过 log 输出，
+//! 两者都未启用时不输出。
+//!
+//! 加载过程的结构化诊断（`trace_debug!` 事件与 `trace_span!` span）只在启用 `tracing-support` 时输出，
+//! 目标均为 `quantum_config`，例如 `RUST_LOG=quantum_config=debug` 即可看到：
+//!
+//! - `quantum_config.load` span：应用名与剖面；结束时输出来源数量与总耗时
+//! - 按默认路径探测的每个候选文件及其是否存在
+//! - 每个来源的读取耗时、是否成功与顶层键数量，被跳过的来源（可选文件缺失、`.env` 不存在）
+//! - `quantum_config.file` span：每个文件的格式、字节数与解析耗时
+//! - 环境变量提供器扫描的变量数与匹配前缀的变量数，命令行提供器匹配的参数数
+
+/// 输出 info 级别日志
+macro_rules! log_info {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing-support")]
+        tracing::info!($($arg)*);
+        #[cfg(all(feature = "log-facade", not(feature = "tracing-support")))]
+        log::info!($($arg)*);
+        #[cfg(not(feature = "log-facade"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+/// 输出 warn 级别日志
+macro_rules! log_warn {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing-support")]
+        tracing::warn!($($arg)*);
+        #[cfg(all(feature = "log-facade", not(feature = "tracing-support")))]
+        log::warn!($($arg)*);
+        #[cfg(not(feature = "log-facade"))]
+        let _ = format_args!($($arg)*);
+    }};
+}
+
+/// 输出结构化的 debug 级别 tracing 事件，未启用 `tracing-support` 时不输出也不求值
+macro_rules! trace_debug {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing-support")]
+        tracing::debug!(target: "quantum_config", $($arg)*);
+    }};
+}
+
+/// 进入 debug 级别的 tracing span，返回的守卫离开作用域时退出；未启用 `tracing-support` 时为空操作
+macro_rules! trace_span {
+    ($($arg:tt)*) => {{
+        #[cfg(feature = "tracing-support")]
+        let span = tracing::debug_span!(target: "quantum_config", $($arg)*).entered();
+        #[cfg(not(feature = "tracing-support"))]
+        let span = $crate::logging::NoSpan;
+        span
+    }};
+}
+
+/// 未启用 `tracing-support` 时 `trace_span!` 返回的空守卫
+#[cfg(not(feature = "tracing-support"))]
+pub(crate) struct NoSpan;
+
+pub(crate) use log_info;
+pub(crate) use log_warn;
+pub(crate) use trace_debug;
+pub(crate) use trace_span;
+
+#[cfg(all(test, feature = "tracing-support"))]
+mod tests {
+    use crate::providers::MapEnv;
+    use crate::QuantumConfigBuilder;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    /// 把格式化后的日志写入共享缓冲区
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_load_pipeline_emits_structured_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.toml");
+        std::fs::write(&path, "port = 8080\n[db]\nurl = \"pg://db\"\n").unwrap();
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, || {
+            QuantumConfigBuilder::<serde_json::Value>::new("trace-app")
+                .with_default_paths(false)
+                .with_file(&path)
+                .with_env_prefix("TRACE_")
+                .with_env_source(MapEnv::new().with_var("TRACE_PORT", "9090").with_var("OTHER", "1"))
+                .with_dotenv(dir.path().join(".env"))
+                .load()
+                .unwrap();
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        for expected in [
+            "quantum_config.load{app=trace-app",
+            "quantum_config.file{path=",
+            "parsed config file bytes=",
+            "skipped missing .env file",
+            "collected environment variables prefix=TRACE_ scanned=2 matched=1",
+            "fetched configuration source",
+            "keys=2",
+            "merged configuration sources sources=2",
+        ] {
+            assert!(output.contains(expected), "missing `{}` in:\n{}", expected, output);
+        }
+    }
+}
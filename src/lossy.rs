@@ -0,0 +1,117 @@
+//! 尽力加载
+//!
+//! [`crate::Config::load_lossy`] 在遇到可恢复的错误时不中止加载，而是将出错的键恢复为默认值，
+//! 把错误收集为警告列表，最终返回尽可能完整的配置。适合必须在零配置下也能运行的命令行工具。
+//!
+//! 可恢复的错误：
+//!
+//! - 值无法转换为字段类型（例如 `APP_PORT=abc`）：该键使用默认值；
+//! - 缺少必需键（`#[config(no_defaults)]` 时）：缺失的键使用默认值；
+//! - 字段约束或自定义校验未通过且能定位到键：这些键使用默认值；
+//! - 未知键（`#[config(deny_unknown)]` 时）：忽略这些键。
+//!
+//! 文件无法读取或解析、命令行参数无效等错误仍然直接返回，
+//! 恢复为默认值后仍然出错的键也不再重试。
+
+use crate::config::Config;
+use crate::enums;
+use crate::error::QuantumConfigError;
+use crate::report::ConfigSources;
+use crate::unknown::{self, UnknownKeyPolicy};
+use figment::providers::Serialized;
+use figment::Figment;
+use serde_json::Value as JsonValue;
+
+/// 单次加载最多恢复为默认值的键数量
+const MAX_RESET_KEYS: usize = 256;
+
+/// 提取并校验配置，可恢复的错误对应的键恢复为默认值，返回配置与收集到的错误
+///
+/// `unknown_keys` 为 `Some` 时，未知键以 [`QuantumConfigError::UnknownKeys`] 记入警告。
+///
+/// # Errors
+/// 无法定位到键、或相关键恢复为默认值后仍然出错时返回该错误
+pub fn extract_lossy<T: Config>(
+    figment: &mut Figment,
+    sources: &ConfigSources,
+    unknown_keys: Option<UnknownKeyPolicy>,
+) -> Result<(T, Vec<QuantumConfigError>), QuantumConfigError> {
+    let mut warnings = Vec::new();
+    if unknown_keys.is_some() {
+        let keys = unknown::detect::<T>(figment)?;
+        if !keys.is_empty() {
+            warnings.push(QuantumConfigError::UnknownKeys { keys });
+        }
+    }
+
+    let defaults = T::defaults_as_value()?;
+    let mut reset: Vec<String> = Vec::new();
+    loop {
+        let error = match enums::extract_coercing_variants::<T>(figment, sources) {
+            Ok(config) => match config.validate() {
+                Ok(()) => {
+                    config.register_secrets();
+                    return Ok((config, warnings));
+                }
+                Err(error) => error.with_origin(figment),
+            },
+            Err(error) => error,
+        };
+        let keys = recoverable_keys(&error);
+        if keys.is_empty() || reset.len() + keys.len() > MAX_RESET_KEYS {
+            return Err(error);
+        }
+        for key in keys {
+            let default = lookup(&defaults, &key).cloned();
+            let Some(default) = default.filter(|_| !reset.contains(&key)) else {
+                return Err(error);
+            };
+            *figment = figment.clone().merge(Serialized::default(&key, default));
+            reset.push(key);
+        }
+        warnings.push(error);
+    }
+}
+
+/// 错误涉及的键路径；无法定位到键的错误返回空列表
+fn recoverable_keys(error: &QuantumConfigError) -> Vec<String> {
+    match error {
+        QuantumConfigError::MissingKeys { keys, .. } => keys.clone(),
+        QuantumConfigError::MissingValue { key_path }
+        | QuantumConfigError::Extraction { key_path, .. }
+        | QuantumConfigError::ConstraintViolation { key_path, .. } => vec![key_path.clone()],
+        QuantumConfigError::ValidationFailed { errors } => errors.iter().map(|e| e.field.clone()).collect(),
+        _ => Vec::new(),
+    }
+    .into_iter()
+    .filter(|key| !key.is_empty())
+    .collect()
+}
+
+/// 按以 `.` 分隔的键路径查找 JSON 值
+fn lookup<'a>(value: &'a JsonValue, key: &str) -> Option<&'a JsonValue> {
+    key.split('.').try_fold(value, |value, segment| value.get(segment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validate::ValidationError;
+
+    #[test]
+    fn test_recoverable_keys() {
+        let error = QuantumConfigError::MissingKeys {
+            keys: vec!["name".to_string(), "database.url".to_string()],
+            searched: Vec::new(),
+            hints: Vec::new(),
+        };
+        assert_eq!(recoverable_keys(&error), ["name", "database.url"]);
+
+        let error = QuantumConfigError::ValidationFailed {
+            errors: vec![ValidationError::new("port", "must not be 0"), ValidationError::new("", "inconsistent")],
+        };
+        assert_eq!(recoverable_keys(&error), ["port"]);
+
+        assert!(recoverable_keys(&QuantumConfigError::Internal("boom".to_string())).is_empty());
+    }
+}
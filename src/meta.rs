@@ -2,6 +2,9 @@
 //!
 //! 定义了用于在运行时表示从编译时收集的配置信息的数据结构。
 
+use crate::builder::SourceKind;
+use crate::cli::{CliMode, CliNestedStyle};
+use crate::paths::PathStrategy;
 use std::collections::HashMap;
 
 /// 应用程序级别的元数据
@@ -11,10 +14,16 @@ pub struct QuantumConfigAppMeta {
     pub app_name: String,
     /// 全局环境变量前缀
     pub env_prefix: Option<String>,
-    /// 宏行为版本（内部语义版本，随库版本演进）
+    /// 宏行为版本（内部语义版本，随库版本演进），旧名称的移除计划参见 [`crate::compat`]
     pub behavior_version: u32,
     /// 配置文件解析深度限制（由内部默认策略与 QuantumConfigFileProvider 控制）
     pub max_parse_depth: u32,
+    /// 平台目录使用的限定符（如 `com`），macOS 上组成 `~/Library/Application Support/{qualifier}.{organization}.{app}`
+    pub qualifier: Option<String>,
+    /// 平台目录使用的组织名，Windows 上组成 `%APPDATA%\{organization}\{app}`
+    pub organization: Option<String>,
+    /// 配置目录的查找策略
+    pub path_strategy: PathStrategy,
 }
 
 impl Default for QuantumConfigAppMeta {
@@ -22,9 +31,12 @@ impl Default for QuantumConfigAppMeta {
         Self {
             app_name: "app".to_string(),
             env_prefix: None,
-            behavior_version: 1,
+            behavior_version: crate::compat::DEFAULT_BEHAVIOR_VERSION,
             // 降低默认解析深度以防止深度嵌套攻击
             max_parse_depth: 32,
+            qualifier: None,
+            organization: None,
+            path_strategy: PathStrategy::Native,
         }
     }
 }
@@ -59,6 +71,8 @@ pub struct FieldMeta {
     pub clap_short_override: Option<char>,
     /// 来自 #[config(description = "...")] 的描述
     pub description: Option<&'static str>,
+    /// 来自字段 `///` 文档注释的文本，生成模板时作为注释写入
+    pub doc_comment: Option<&'static str>,
     /// 指向宏生成的默认值函数的完整路径字符串
     pub default_fn_path_str: Option<&'static str>,
     /// 字段类型的字符串表示
@@ -69,6 +83,12 @@ pub struct FieldMeta {
     pub is_flatten: bool,
     /// 标记该字段是否有 #[config(skip)]
     pub is_skipped: bool,
+    /// 标记该字段是否有 #[config(secret)]
+    pub is_secret: bool,
+    /// 标记该字段是否有 #[config(cli_positional)]，命令行上以位置参数给出（`CliMode::IgnoreUnknown` 时仍为长选项）
+    pub is_positional: bool,
+    /// 来自 #[config(sources(...))] 或 #[config(no_cli)] 的允许来源，`None` 表示不限制，参见 [`crate::restrict`]
+    pub allowed_sources: Option<&'static [SourceKind]>,
     /// 结构化表示来自 #[config(clap(...))] 的原生 clap 属性
     pub clap_direct_attrs_meta: Option<ClapAttrsMeta>,
 }
@@ -83,11 +103,15 @@ impl FieldMeta {
             clap_long_override: None,
             clap_short_override: None,
             description: None,
+            doc_comment: None,
             default_fn_path_str: None,
             type_name_str,
             is_option: false,
             is_flatten: false,
             is_skipped: false,
+            is_secret: false,
+            is_positional: false,
+            allowed_sources: None,
             clap_direct_attrs_meta: None,
         }
     }
@@ -106,6 +130,11 @@ impl FieldMeta {
     pub fn clap_long_name(&self) -> &str {
         self.clap_long_override.unwrap_or(self.rust_name)
     }
+
+    /// 该字段的值是否可以来自 `kind` 类别的来源
+    pub fn allows_source(&self, kind: SourceKind) -> bool {
+        self.allowed_sources.is_none_or(|allowed| allowed.contains(&kind))
+    }
 }
 
 /// 结构体级别的元数据
@@ -120,6 +149,10 @@ pub struct StructMeta {
     /// 用于存储此结构体中类型为其他配置结构体的字段的元数据
     /// 键是字段的 rust_name，值是对应嵌套结构体的 StructMeta 引用
     pub nested_struct_meta_map: HashMap<&'static str, &'static StructMeta>,
+    /// 嵌套字段的命令行参数风格
+    pub cli_nested_style: CliNestedStyle,
+    /// 加载时解析命令行参数的方式
+    pub cli_mode: CliMode,
 }
 
 impl StructMeta {
@@ -130,6 +163,8 @@ impl StructMeta {
             fields: Vec::new(),
             is_top_level_config,
             nested_struct_meta_map: HashMap::new(),
+            cli_nested_style: CliNestedStyle::default(),
+            cli_mode: CliMode::default(),
         }
     }
 
@@ -153,12 +188,31 @@ impl StructMeta {
         self.fields.iter().filter(|f| !f.is_skipped)
     }
 
+    /// 获取所有敏感字段
+    pub fn secret_fields(&self) -> impl Iterator<Item=&FieldMeta> {
+        self.fields.iter().filter(|f| f.is_secret)
+    }
+
+    /// 获取所有限制了来源的字段
+    pub fn restricted_fields(&self) -> impl Iterator<Item=&FieldMeta> {
+        self.fields.iter().filter(|f| f.allowed_sources.is_some())
+    }
+
     /// 获取所有扁平化的字段
     pub fn flattened_fields(&self) -> impl Iterator<Item=&FieldMeta> {
         self.fields.iter().filter(|f| f.is_flatten)
     }
 }
 
+/// 嵌套结构体中的键 `key` 在外层结构体中的键路径，`prefix` 为外层字段的键（`#[serde(flatten)]` 字段为空）
+pub fn nested_key_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,11 +233,16 @@ mod tests {
             env_prefix: Some("MYAPP".to_string()),
             behavior_version: 2,
             max_parse_depth: 256,
+            qualifier: Some("com".to_string()),
+            organization: Some("Example".to_string()),
+            path_strategy: PathStrategy::Xdg,
         };
         assert_eq!(meta.app_name, "myapp");
         assert_eq!(meta.env_prefix, Some("MYAPP".to_string()));
         assert_eq!(meta.behavior_version, 2);
         assert_eq!(meta.max_parse_depth, 256);
+        assert_eq!(meta.organization.as_deref(), Some("Example"));
+        assert_eq!(meta.path_strategy, PathStrategy::Xdg);
     }
 
     #[test]
@@ -344,11 +403,15 @@ mod tests {
             clap_long_override: Some("host-name"),
             clap_short_override: Some('h'),
             description: Some("The server hostname"),
+            doc_comment: Some("Address the server binds to"),
             default_fn_path_str: Some("crate::defaults::default_host"),
             type_name_str: "String",
             is_option: true,
             is_flatten: false,
             is_skipped: false,
+            is_secret: false,
+            is_positional: false,
+            allowed_sources: Some(&[SourceKind::File, SourceKind::Env]),
             clap_direct_attrs_meta: Some(clap_attrs),
         };
 
@@ -357,6 +420,7 @@ mod tests {
         assert_eq!(field.clap_long_name(), "host-name");
         assert_eq!(field.description, Some("The server hostname"));
         assert!(field.is_option);
+        assert!(field.allows_source(SourceKind::Env) && !field.allows_source(SourceKind::Cli));
         assert!(field.clap_direct_attrs_meta.is_some());
     }
 }
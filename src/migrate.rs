@@ -0,0 +1,444 @@
+//! 配置格式迁移
+//!
+//! 配置格式需要演进（重命名键、移动段、更换单位）时，为每个旧版本注册一次 [`Migration`]，
+//! 旧文件无需修改即可继续使用。文件在顶层用 `config_version` 键声明自身的版本：
+//!
+//! ```ignore
+//! use quantum_config::migrate::{Migrate, Migration, Migrations};
+//!
+//! #[derive(Config, Serialize, Deserialize, Default)]
+//! #[config(migrate)]
+//! struct AppConfig {
+//!     config_version: u32,
+//!     server: Server,
+//! }
+//!
+//! impl Migrate for AppConfig {
+//!     fn migrations() -> Migrations {
+//!         Migrations::new()
+//!             // 版本 1 -> 2：`port` 移入 `[server]`，超时由秒改为毫秒
+//!             .with(Migration::new(1).rename("port", "server.port").rename("timeout", "server.timeout_ms").scale("server.timeout_ms", 1000.0))
+//!             // 版本 2 -> 3：删除不再使用的段
+//!             .with(Migration::new(2).remove("legacy"))
+//!     }
+//! }
+//! ```
+//!
+//! 迁移在读取各配置文件（以及远程来源）之后、合并之前逐个来源执行：从来源声明的版本开始，
+//! 依次应用各版本的迁移直到最新版本，并把版本键改写为最新版本。环境变量与命令行参数始终使用最新的键名。
+//! 没有版本键的来源视为版本 1（可通过 [`Migrations::with_unversioned`] 修改）；
+//! 声明的版本高于最新版本时以 [`QuantumConfigError::Migration`] 报错。
+//!
+//! 迁移后的值保留原来源的信息，错误信息与来源报告仍然指向原文件。
+
+use crate::error::QuantumConfigError;
+use crate::logging::log_info;
+use crate::transform::ValueTransformer;
+use figment::value::{Dict, Num, Tag, Value};
+use std::fmt;
+use std::sync::Arc;
+
+/// 默认的版本键
+pub const DEFAULT_VERSION_KEY: &str = "config_version";
+
+/// 为派生类型提供迁移规则，配合结构体上的 `#[config(migrate)]` 使用
+pub trait Migrate {
+    /// 各版本的迁移规则
+    fn migrations() -> Migrations;
+}
+
+/// 迁移中的单个操作
+#[derive(Clone)]
+enum Step {
+    /// 移动键或段
+    Rename { from: String, to: String },
+    /// 删除键或段
+    Remove(String),
+    /// 转换键的值
+    Convert { key: String, transformer: Arc<dyn ValueTransformer> },
+    /// 自定义修改
+    Custom(Arc<dyn Fn(&mut Dict) + Send + Sync>),
+}
+
+impl fmt::Debug for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Rename { from, to } => write!(f, "rename {} -> {}", from, to),
+            Step::Remove(key) => write!(f, "remove {}", key),
+            Step::Convert { key, .. } => write!(f, "convert {}", key),
+            Step::Custom(_) => write!(f, "custom"),
+        }
+    }
+}
+
+/// 从某个版本迁移到下一个版本的操作序列，按添加顺序执行
+#[derive(Debug, Clone)]
+pub struct Migration {
+    /// 迁移前的版本
+    from: u32,
+    /// 操作序列
+    steps: Vec<Step>,
+}
+
+impl Migration {
+    /// 创建从 `from` 迁移到 `from + 1` 的迁移
+    pub fn new(from: u32) -> Self {
+        Self { from, steps: Vec::new() }
+    }
+
+    /// 迁移前的版本
+    pub fn from_version(&self) -> u32 {
+        self.from
+    }
+
+    /// 将键或整个段移动到新的键路径（以 `.` 分隔）
+    ///
+    /// 新键路径已有值时保留已有的值：两者都是段时逐键合并，已有的键优先。
+    pub fn rename(mut self, from: &str, to: &str) -> Self {
+        self.steps.push(Step::Rename {
+            from: from.to_string(),
+            to: to.to_string(),
+        });
+        self
+    }
+
+    /// 删除键或整个段
+    pub fn remove(mut self, key: &str) -> Self {
+        self.steps.push(Step::Remove(key.to_string()));
+        self
+    }
+
+    /// 转换键的值，键不存在时不执行
+    pub fn convert<T: ValueTransformer + 'static>(mut self, key: &str, transformer: T) -> Self {
+        self.steps.push(Step::Convert {
+            key: key.to_string(),
+            transformer: Arc::new(transformer),
+        });
+        self
+    }
+
+    /// 将数值乘以 `factor`，用于更换单位（例如秒改为毫秒时为 `1000.0`）
+    ///
+    /// 整数结果仍为整数；非数值保持不变。
+    pub fn scale(self, key: &str, factor: f64) -> Self {
+        self.convert(key, move |value| scale_value(value, factor))
+    }
+
+    /// 对来源的顶层值执行自定义修改
+    pub fn map<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&mut Dict) + Send + Sync + 'static,
+    {
+        self.steps.push(Step::Custom(Arc::new(f)));
+        self
+    }
+
+    /// 按顺序执行全部操作
+    fn apply(&self, dict: &mut Dict) {
+        for step in &self.steps {
+            match step {
+                Step::Rename { from, to } => {
+                    if let Some(value) = take(dict, &segments(from)) {
+                        insert(dict, &segments(to), value);
+                    }
+                }
+                Step::Remove(key) => {
+                    take(dict, &segments(key));
+                }
+                Step::Convert { key, transformer } => {
+                    if let Some(value) = get_mut(dict, &segments(key)) {
+                        let current = std::mem::replace(value, Value::from(false));
+                        *value = transformer.transform(current);
+                    }
+                }
+                Step::Custom(f) => f(dict),
+            }
+        }
+    }
+}
+
+/// 各版本的迁移规则
+#[derive(Debug, Clone)]
+pub struct Migrations {
+    /// 声明版本的顶层键
+    version_key: String,
+    /// 没有版本键的来源视为的版本
+    unversioned: u32,
+    /// 按迁移前版本排序的迁移
+    migrations: Vec<Migration>,
+}
+
+impl Default for Migrations {
+    fn default() -> Self {
+        Self {
+            version_key: DEFAULT_VERSION_KEY.to_string(),
+            unversioned: 1,
+            migrations: Vec::new(),
+        }
+    }
+}
+
+impl Migrations {
+    /// 创建空的迁移规则，版本键为 `config_version`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置声明版本的顶层键
+    pub fn with_version_key(mut self, key: &str) -> Self {
+        self.version_key = key.to_string();
+        self
+    }
+
+    /// 设置没有版本键的来源视为的版本，默认为 1
+    pub fn with_unversioned(mut self, version: u32) -> Self {
+        self.unversioned = version;
+        self
+    }
+
+    /// 添加一个版本的迁移；同一版本的迁移会替换之前添加的
+    pub fn with(mut self, migration: Migration) -> Self {
+        self.migrations.retain(|existing| existing.from != migration.from);
+        let index = self.migrations.partition_point(|existing| existing.from < migration.from);
+        self.migrations.insert(index, migration);
+        self
+    }
+
+    /// 声明版本的顶层键
+    pub fn version_key(&self) -> &str {
+        &self.version_key
+    }
+
+    /// 最新版本：最后一个迁移的目标版本，没有迁移时为未声明版本时的默认版本
+    pub fn latest_version(&self) -> u32 {
+        self.migrations
+            .last()
+            .map_or(self.unversioned, |migration| migration.from.saturating_add(1).max(self.unversioned))
+    }
+
+    /// 是否没有任何迁移
+    pub fn is_empty(&self) -> bool {
+        self.migrations.is_empty()
+    }
+
+    /// 将单个来源的值迁移到最新版本，返回迁移前的版本
+    ///
+    /// `source` 用于错误信息与日志。
+    ///
+    /// # Errors
+    /// 版本键不是非负整数、或声明的版本高于最新版本时返回 [`QuantumConfigError::Migration`]
+    pub fn apply(&self, dict: &mut Dict, source: &str) -> Result<u32, QuantumConfigError> {
+        let latest = self.latest_version();
+        let version = match dict.get(&self.version_key) {
+            None => self.unversioned,
+            Some(value) => parse_version(value).ok_or_else(|| QuantumConfigError::Migration {
+                origin: source.to_string(),
+                reason: format!("`{}` must be a non-negative integer", self.version_key),
+            })?,
+        };
+        if version > latest {
+            return Err(QuantumConfigError::Migration {
+                origin: source.to_string(),
+                reason: format!(
+                    "`{}` is {}, but the latest supported version is {}",
+                    self.version_key, version, latest
+                ),
+            });
+        }
+        if version == latest {
+            return Ok(version);
+        }
+        for migration in self.migrations.iter().filter(|migration| migration.from >= version) {
+            migration.apply(dict);
+        }
+        dict.insert(self.version_key.clone(), Value::from(latest));
+        log_info!("Migrated configuration from {} from version {} to {}", source, version, latest);
+        Ok(version)
+    }
+}
+
+/// 版本值：整数或由数字组成的字符串
+fn parse_version(value: &Value) -> Option<u32> {
+    match value {
+        Value::Num(_, num) => num
+            .to_u128()
+            .or_else(|| num.to_i128().and_then(|version| u128::try_from(version).ok()))
+            .and_then(|version| u32::try_from(version).ok()),
+        Value::String(_, s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// 将数值乘以系数，整数结果保持为整数
+fn scale_value(value: Value, factor: f64) -> Value {
+    let Value::Num(tag, num) = value else {
+        return value;
+    };
+    let float = matches!(num, Num::F32(_) | Num::F64(_));
+    let original = if float {
+        num.to_f64()
+    } else {
+        num.to_i128().map(|n| n as f64).or_else(|| num.to_u128().map(|n| n as f64))
+    };
+    let Some(scaled) = original.map(|n| n * factor) else {
+        return Value::Num(tag, num);
+    };
+    let integral = !float && scaled.fract() == 0.0;
+    let num = if integral && scaled >= 0.0 && scaled <= u64::MAX as f64 {
+        Num::U64(scaled as u64)
+    } else if integral && scaled < 0.0 && scaled >= i64::MIN as f64 {
+        Num::I64(scaled as i64)
+    } else {
+        Num::F64(scaled)
+    };
+    Value::Num(tag, num)
+}
+
+fn segments(key: &str) -> Vec<&str> {
+    key.split('.').filter(|segment| !segment.is_empty()).collect()
+}
+
+/// 取出键路径上的值
+fn take(dict: &mut Dict, path: &[&str]) -> Option<Value> {
+    match path {
+        [] => None,
+        [last] => dict.remove(*last),
+        [first, rest @ ..] => match dict.get_mut(*first)? {
+            Value::Dict(_, child) => take(child, rest),
+            _ => None,
+        },
+    }
+}
+
+/// 键路径上的值的可变引用
+fn get_mut<'a>(dict: &'a mut Dict, path: &[&str]) -> Option<&'a mut Value> {
+    match path {
+        [] => None,
+        [last] => dict.get_mut(*last),
+        [first, rest @ ..] => match dict.get_mut(*first)? {
+            Value::Dict(_, child) => get_mut(child, rest),
+            _ => None,
+        },
+    }
+}
+
+/// 在键路径上写入值，按需创建中间段；已有的值优先
+fn insert(dict: &mut Dict, path: &[&str], value: Value) {
+    let Some((first, rest)) = path.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        match dict.get_mut(*first) {
+            Some(existing) => merge_missing(existing, value),
+            None => {
+                dict.insert(first.to_string(), value);
+            }
+        }
+        return;
+    }
+    let entry = dict
+        .entry(first.to_string())
+        .or_insert_with(|| Value::Dict(Tag::Default, Dict::new()));
+    if let Value::Dict(_, child) = entry {
+        insert(child, rest, value);
+    }
+}
+
+/// 两者都是段时把 `incoming` 中缺少的键合并进 `existing`，否则保留 `existing`
+fn merge_missing(existing: &mut Value, incoming: Value) {
+    if let (Value::Dict(_, existing), Value::Dict(_, incoming)) = (existing, incoming) {
+        for (key, value) in incoming {
+            match existing.get_mut(&key) {
+                Some(current) => merge_missing(current, value),
+                None => {
+                    existing.insert(key, value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::providers::{Format, Toml};
+    use figment::{Figment, Profile, Provider};
+
+    fn dict(content: &str) -> Dict {
+        Toml::string(content).data().unwrap().remove(&Profile::Default).unwrap()
+    }
+
+    fn migrations() -> Migrations {
+        Migrations::new()
+            .with(Migration::new(2).remove("legacy"))
+            .with(
+                Migration::new(1)
+                    .rename("port", "server.port")
+                    .rename("timeout", "server.timeout_ms")
+                    .scale("server.timeout_ms", 1000.0),
+            )
+    }
+
+    #[test]
+    fn test_apply_migrates_to_latest_version() {
+        let migrations = migrations();
+        assert_eq!(migrations.latest_version(), 3);
+
+        let mut values = dict("port = 8080\ntimeout = 2\n[server]\nhost = \"0.0.0.0\"\n[legacy]\nflag = true\n");
+        assert_eq!(migrations.apply(&mut values, "old.toml").unwrap(), 1);
+        let value: serde_json::Value = Figment::from(figment::providers::Serialized::defaults(values))
+            .extract()
+            .unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "config_version": 3,
+                "server": {"host": "0.0.0.0", "port": 8080, "timeout_ms": 2000}
+            })
+        );
+
+        // 已是最新版本的来源不做修改
+        let mut values = dict("config_version = 3\nport = 1\n");
+        assert_eq!(migrations.apply(&mut values, "new.toml").unwrap(), 3);
+        assert!(values.contains_key("port"));
+    }
+
+    #[test]
+    fn test_apply_starts_from_declared_version() {
+        // 版本 2 的文件只执行 2 -> 3 的迁移
+        let mut values = dict("config_version = \"2\"\nport = 8080\n[legacy]\nflag = true\n");
+        assert_eq!(migrations().apply(&mut values, "app.toml").unwrap(), 2);
+        assert!(values.contains_key("port"));
+        assert!(!values.contains_key("legacy"));
+    }
+
+    #[test]
+    fn test_rename_keeps_existing_values() {
+        let migration = Migration::new(1).rename("db", "database");
+        let mut values = dict("[db]\nurl = \"old\"\npool = 4\n[database]\nurl = \"new\"\n");
+        migration.apply(&mut values);
+        let database = values.get("database").unwrap().as_dict().unwrap();
+        assert_eq!(database.get("url").unwrap().as_str(), Some("new"));
+        assert_eq!(database.get("pool").unwrap().to_i128(), Some(4));
+        assert!(!values.contains_key("db"));
+    }
+
+    #[test]
+    fn test_invalid_and_future_versions() {
+        let error = migrations().apply(&mut dict("config_version = 7\n"), "app.toml").unwrap_err();
+        assert_eq!(error.code(), "migration");
+        assert!(error.to_string().contains("latest supported version is 3"), "{}", error);
+
+        let error = migrations().apply(&mut dict("config_version = \"v1\"\n"), "app.toml").unwrap_err();
+        assert!(error.to_string().contains("must be a non-negative integer"), "{}", error);
+    }
+
+    #[test]
+    fn test_scale_value() {
+        let scaled = scale_value(Value::from(3u32), 1000.0);
+        assert_eq!(scaled.to_u128(), Some(3000));
+        let scaled = scale_value(Value::from(1500u32), 0.001);
+        assert_eq!(scaled.to_f64(), Some(1.5));
+        assert_eq!(scale_value(Value::from("3s"), 1000.0).as_str(), Some("3s"));
+    }
+}
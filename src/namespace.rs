@@ -0,0 +1,106 @@
+//! 供嵌入式库使用的配置命名空间
+//!
+//! 库可以定义自己的配置结构体并标注 `#[config(namespace = "mylib")]`，由宿主应用的配置来源提供它的值：
+//!
+//! ```ignore
+//! // 库中
+//! #[derive(Config, Serialize, Deserialize, Default)]
+//! #[config(namespace = "mylib")]
+//! pub struct LibConfig {
+//!     pool_size: u32,
+//! }
+//!
+//! // 应用中
+//! let figment = QuantumConfigBuilder::<AppConfig>::new("app").figment()?;
+//! let lib_config = LibConfig::load_nested_from(&figment)?;
+//! ```
+//!
+//! 使用 [`crate::Loader`] 时，`loader.extract::<LibConfig>()` 同样按命名空间提取。
+//!
+//! 宿主合并结果中命名空间下的值（配置文件中的 `[mylib]` 表、以 `__` 分隔嵌套键的环境变量
+//! `APP_MYLIB__POOL_SIZE`、命令行映射到 `mylib.*` 的参数）覆盖库的默认值，
+//! 之后按库自己的规则应用值转换并校验。宿主启用 `#[config(deny_unknown)]` 时，
+//! 命名空间下的键需要由宿主结构体中的字段（例如 `mylib: LibConfig`）接受。
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::report::{ConfigSources, LayerKind};
+use figment::providers::Serialized;
+use figment::{Figment, Provider};
+
+/// 取出宿主合并结果中 `key` 下的子树，合并在 `defaults` 之上并应用 `T` 的值转换规则
+///
+/// `key` 为 `None` 时使用整个合并结果。返回的来源信息中，宿主提供的值按提供器名称记录。
+///
+/// # Errors
+/// 值转换失败时返回错误
+pub fn nested_figment<T: Config>(
+    host: &Figment,
+    key: Option<&str>,
+    defaults: Option<T>,
+) -> Result<(Figment, ConfigSources), QuantumConfigError> {
+    let nested = match key {
+        Some(key) => host.focus(key),
+        None => host.clone(),
+    };
+    let mut figment = Figment::new();
+    let mut layers = Vec::new();
+    if let Some(defaults) = defaults {
+        let defaults = Serialized::defaults(defaults);
+        layers.push((defaults.metadata(), LayerKind::Default));
+        figment = figment.merge(defaults);
+    }
+    let figment = figment.merge(nested);
+    let sources = ConfigSources::new(figment.clone(), layers);
+    Ok((T::transform_registry()?.apply_to_figment(figment)?, sources))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::providers::{Format, Toml};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct LibConfig {
+        pool_size: u32,
+        name: String,
+    }
+
+    impl Default for LibConfig {
+        fn default() -> Self {
+            Self {
+                pool_size: 4,
+                name: "lib".to_string(),
+            }
+        }
+    }
+
+    impl Config for LibConfig {
+        fn load() -> Result<Self, QuantumConfigError> {
+            Ok(Self::default())
+        }
+
+        fn load_with_args(_args: Vec<String>) -> Result<Self, QuantumConfigError> {
+            Self::load()
+        }
+
+        fn namespace() -> Option<&'static str> {
+            Some("mylib")
+        }
+    }
+
+    #[test]
+    fn test_nested_figment_focuses_namespace_over_defaults() {
+        let host = Figment::from(Toml::string("port = 80\n[mylib]\npool_size = 16\n"));
+        let (figment, sources) = nested_figment(&host, LibConfig::namespace(), Some(LibConfig::default())).unwrap();
+        let config: LibConfig = figment.extract().unwrap();
+        assert_eq!(config, LibConfig { pool_size: 16, name: "lib".to_string() });
+        assert!(figment.find_value("port").is_err());
+        assert!(sources.has_values());
+
+        // 默认 load_nested_from 同样合并默认值，宿主中没有命名空间时使用默认值
+        assert_eq!(LibConfig::load_nested_from(&Figment::new()).unwrap(), LibConfig::default());
+        assert!(nested_figment::<LibConfig>(&Figment::new(), Some("mylib"), None).unwrap().0.extract::<LibConfig>().is_err());
+    }
+}
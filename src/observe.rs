@@ -0,0 +1,166 @@
+//! 配置加载观察者
+//!
+//! 实现 [`LoadObserver`] 即可在加载过程中收到回调，用于导出 Prometheus 指标或写入审计日志：
+//!
+//! ```ignore
+//! struct Metrics;
+//!
+//! impl LoadObserver for Metrics {
+//!     fn provider_finished(&self, source: &str, elapsed: Duration, succeeded: bool) {
+//!         LOAD_SECONDS.with_label_values(&[source]).observe(elapsed.as_secs_f64());
+//!     }
+//!
+//!     fn validation_failed(&self, error: &QuantumConfigError) {
+//!         VALIDATION_FAILURES.inc();
+//!     }
+//! }
+//!
+//! quantum_config::observe::register(Metrics);
+//! ```
+//!
+//! 全局注册的观察者对所有加载生效（包括派生类型的 `load`），
+//! [`QuantumConfigBuilder::with_observer`](crate::QuantumConfigBuilder::with_observer) 只对单个构建器生效。
+//! 来源名称与 [`LoadReport`](crate::LoadReport) 中的一致。并行读取来源时，
+//! `provider_started` 与 `provider_finished` 可能在不同线程上并发调用。
+
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::time::Duration;
+
+/// 配置加载观察者，所有回调都有空的默认实现
+pub trait LoadObserver: Send + Sync {
+    /// 开始读取来源
+    fn provider_started(&self, source: &str) {
+        let _ = source;
+    }
+
+    /// 来源读取结束
+    fn provider_finished(&self, source: &str, elapsed: Duration, succeeded: bool) {
+        let _ = (source, elapsed, succeeded);
+    }
+
+    /// 键的值被优先级更高的来源覆盖，`key` 为 `server.port` 形式的键路径
+    fn key_overridden(&self, key: &str, previous: &str, source: &str) {
+        let _ = (key, previous, source);
+    }
+
+    /// 提取出的配置未通过校验
+    fn validation_failed(&self, error: &QuantumConfigError) {
+        let _ = error;
+    }
+}
+
+/// 全局观察者
+static GLOBAL: LazyLock<RwLock<Vec<Arc<dyn LoadObserver>>>> = LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// 注册全局观察者
+pub fn register<O: LoadObserver + 'static>(observer: O) {
+    GLOBAL.write().unwrap_or_else(|e| e.into_inner()).push(Arc::new(observer));
+}
+
+/// 清空全局观察者
+pub fn clear_global() {
+    GLOBAL.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+/// 通知全局观察者校验失败，供派生宏生成的代码调用
+#[doc(hidden)]
+pub fn notify_validation_failed(error: &QuantumConfigError) {
+    Observers::from_global().validation_failed(error);
+}
+
+/// 一次加载使用的观察者集合
+#[derive(Clone, Default)]
+pub(crate) struct Observers(Vec<Arc<dyn LoadObserver>>);
+
+impl Observers {
+    /// 以全局观察者为起点
+    pub(crate) fn from_global() -> Self {
+        Self(GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone())
+    }
+
+    pub(crate) fn push(&mut self, observer: Arc<dyn LoadObserver>) {
+        self.0.push(observer);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn provider_started(&self, source: &str) {
+        self.0.iter().for_each(|observer| observer.provider_started(source));
+    }
+
+    pub(crate) fn provider_finished(&self, source: &str, elapsed: Duration, succeeded: bool) {
+        self.0.iter().for_each(|observer| observer.provider_finished(source, elapsed, succeeded));
+    }
+
+    pub(crate) fn validation_failed(&self, error: &QuantumConfigError) {
+        self.0.iter().for_each(|observer| observer.validation_failed(error));
+    }
+}
+
+/// 记录每个叶子键当前由哪个来源提供，发现覆盖时通知观察者
+#[derive(Default)]
+pub(crate) struct OverrideTracker {
+    owners: HashMap<String, String>,
+}
+
+impl OverrideTracker {
+    /// 记录一层来源提供的键
+    pub(crate) fn record(&mut self, observers: &Observers, source: &str, dict: &Dict) {
+        self.record_dict(observers, source, "", dict);
+    }
+
+    fn record_dict(&mut self, observers: &Observers, source: &str, prefix: &str, dict: &Dict) {
+        for (key, value) in dict {
+            let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            match value {
+                Value::Dict(_, child) => self.record_dict(observers, source, &path, child),
+                _ => {
+                    if let Some(previous) = self.owners.insert(path.clone(), source.to_string()) {
+                        observers.0.iter().for_each(|observer| observer.key_overridden(&path, &previous, source));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl LoadObserver for Arc<Recorder> {
+        fn key_overridden(&self, key: &str, previous: &str, source: &str) {
+            self.0.lock().unwrap().push(format!("{} {} -> {}", key, previous, source));
+        }
+    }
+
+    #[test]
+    fn test_override_tracker_reports_leaf_overrides() {
+        let recorder = Arc::new(Recorder::default());
+        let mut observers = Observers::default();
+        observers.push(Arc::new(recorder.clone()));
+
+        let dict = |value: serde_json::Value| match Value::serialize(value).unwrap() {
+            Value::Dict(_, dict) => dict,
+            _ => unreachable!(),
+        };
+        let mut tracker = OverrideTracker::default();
+        tracker.record(&observers, "defaults", &dict(serde_json::json!({"port": 80, "db": {"url": "a", "pool": 1}})));
+        tracker.record(&observers, "file", &dict(serde_json::json!({"db": {"pool": 4}, "debug": true})));
+        tracker.record(&observers, "env", &dict(serde_json::json!({"port": 8080})));
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            ["db.pool defaults -> file", "port defaults -> env"]
+        );
+    }
+}
@@ -0,0 +1,71 @@
+//! 配置加载选项
+//!
+//! 通过 [`crate::QuantumConfigBuilder::with_load_options`] 或 [`crate::Config::load_with_options`] 传入，
+//! 调整加载过程本身的行为，或为多租户服务选择租户专属的附加来源。
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 等待必需配置文件出现的策略
+///
+/// 容器启动时配置卷可能尚未挂载，此时对 `--config` 或 `with_file` 指定的必需文件
+/// 按 `poll_interval` 轮询，直到文件出现或超过 `timeout`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitForFiles {
+    /// 最长等待时间
+    pub timeout: Duration,
+    /// 轮询间隔
+    pub poll_interval: Duration,
+}
+
+/// 配置加载选项
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// 必需文件缺失时的等待策略，为 `None` 时立即报错
+    pub wait_for_files: Option<WaitForFiles>,
+    /// 是否启用进程级加载缓存，参见 [`crate::cache`]
+    pub cache: bool,
+    /// 最后一次成功加载的配置快照文件，参见 [`crate::last_known_good`]
+    pub last_known_good: Option<PathBuf>,
+    /// 租户（实例）名称，参见 [`LoadOptions::with_tenant`]
+    pub tenant: Option<String>,
+}
+
+impl LoadOptions {
+    /// 创建默认选项
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 必需文件缺失时按给定间隔轮询，最多等待 `timeout`
+    pub fn with_wait_for_files(mut self, timeout: Duration, poll_interval: Duration) -> Self {
+        self.wait_for_files = Some(WaitForFiles { timeout, poll_interval });
+        self
+    }
+
+    /// 启用进程级加载缓存：未变化的配置文件不再重新解析，HTTP(S) 来源按 `ETag` 重新校验
+    pub fn with_cache(mut self, enabled: bool) -> Self {
+        self.cache = enabled;
+        self
+    }
+
+    /// 在共享配置之上合并租户专属的来源
+    ///
+    /// 以租户 `tenant-a`、环境变量前缀 `APP_` 为例：
+    /// - 每个配置文件（例如 `config.toml`）之后合并同目录的 `config.tenant-a.toml`（存在时）；
+    /// - 基础环境变量之后合并 `APP__TENANT_A__` 前缀的变量，例如 `APP__TENANT_A__PORT` 对应 `port`；
+    /// - 合并 [`crate::QuantumConfigBuilder::with_tenant_source`] 添加的来源，其中的 `{tenant}` 替换为租户名称。
+    ///
+    /// 租户名称只能包含小写字母、数字与 `-`，保证不同的租户名称对应不同的环境变量前缀。
+    /// 指定租户时 `APP__` 开头的变量属于租户命名空间，不会被基础前缀读取。
+    pub fn with_tenant<S: Into<String>>(mut self, tenant: S) -> Self {
+        self.tenant = Some(tenant.into());
+        self
+    }
+
+    /// 成功加载后把配置快照写入 `path`，远程来源或配置文件不可用时回退到该快照
+    pub fn with_last_known_good<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.last_known_good = Some(path.into());
+        self
+    }
+}
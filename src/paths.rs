@@ -2,7 +2,8 @@
 //!
 //! 实现配置文件的路径解析逻辑，根据应用程序名称和系统约定确定配置文件的查找路径。
 
-use crate::error::QuantumConfigError;
+use crate::error::{ConfigDirType, QuantumConfigError};
+use crate::logging::trace_debug;
 use crate::meta::QuantumConfigAppMeta;
 use crate::path_conversion::PathConverter;
 use std::path::{PathBuf, Path};
@@ -17,6 +18,18 @@ pub enum ConfigFileType {
     Json,
     /// INI 格式
     Ini,
+    /// JSON5 格式
+    #[cfg(feature = "json5")]
+    Json5,
+    /// YAML 格式
+    #[cfg(feature = "yaml")]
+    Yaml,
+    /// HCL 格式
+    #[cfg(feature = "hcl")]
+    Hcl,
+    /// XML 格式
+    #[cfg(feature = "xml")]
+    Xml,
 }
 
 impl ConfigFileType {
@@ -26,6 +39,14 @@ impl ConfigFileType {
             ConfigFileType::Toml => "toml",
             ConfigFileType::Json => "json",
             ConfigFileType::Ini => "ini",
+            #[cfg(feature = "json5")]
+            ConfigFileType::Json5 => "json5",
+            #[cfg(feature = "yaml")]
+            ConfigFileType::Yaml => "yaml",
+            #[cfg(feature = "hcl")]
+            ConfigFileType::Hcl => "hcl",
+            #[cfg(feature = "xml")]
+            ConfigFileType::Xml => "xml",
         }
     }
 
@@ -35,9 +56,25 @@ impl ConfigFileType {
             "toml" => Some(ConfigFileType::Toml),
             "json" => Some(ConfigFileType::Json),
             "ini" => Some(ConfigFileType::Ini),
+            #[cfg(feature = "json5")]
+            "json5" => Some(ConfigFileType::Json5),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Some(ConfigFileType::Yaml),
+            #[cfg(feature = "hcl")]
+            "hcl" => Some(ConfigFileType::Hcl),
+            #[cfg(feature = "xml")]
+            "xml" => Some(ConfigFileType::Xml),
             _ => None,
         }
     }
+
+    /// 从文件路径推断配置文件类型，加密文件（例如 `config.toml.enc`）按去掉 `.enc` 后的扩展名推断
+    pub fn from_path(path: &Path) -> Option<Self> {
+        crate::providers::file_provider::plaintext_name(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(Self::from_extension)
+    }
 }
 
 /// 配置文件路径信息
@@ -69,6 +106,33 @@ impl ConfigFilePath {
     // 路径转换方法已移除 - 路径转换现在在内部自动处理，不对外暴露接口
 }
 
+/// 配置目录的查找策略
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PathStrategy {
+    /// 按平台惯例查找（默认）：
+    /// - 系统级：Unix 上为 `/etc/{app}`，Windows 上为 `C:\ProgramData\{app}`
+    /// - 用户级：Linux 上为 `$XDG_CONFIG_HOME/{app}`（默认 `~/.config/{app}`），
+    ///   macOS 上为 `~/Library/Application Support/{qualifier}.{organization}.{app}`，
+    ///   Windows 上为 `%APPDATA%\{organization}\{app}\config`
+    #[default]
+    Native,
+    /// 在所有平台上使用 XDG 基础目录规范，适合希望在 macOS 上也读取 `~/.config` 的命令行工具：
+    /// - 系统级：`$XDG_CONFIG_DIRS` 中的每个目录（默认 `/etc/xdg`）下的 `{app}`
+    /// - 用户级：`$XDG_CONFIG_HOME/{app}`（默认 `~/.config/{app}`）
+    Xdg,
+    /// 只在给定目录中查找，按低 -> 高优先级排列，均视为系统级目录
+    Dirs(Vec<PathBuf>),
+}
+
+/// 候选配置目录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDir {
+    /// 目录路径
+    pub path: PathBuf,
+    /// 目录类别
+    pub dir_type: ConfigDirType,
+}
+
 /// 解析配置文件路径
 ///
 /// 根据应用程序元数据确定配置文件的查找路径，按照预定义的优先级顺序返回。
@@ -76,93 +140,157 @@ impl ConfigFilePath {
 /// 1. 系统级配置目录
 /// 2. 用户级配置目录
 ///
+/// 目录由 [`QuantumConfigAppMeta::path_strategy`] 决定，参见 [`PathStrategy`]。
 /// 在每个目录中，按以下模式查找文件：
 /// - `config.{ext}`
 /// - `{app_name}.{ext}`
 ///
-/// 其中 `ext` 为 `toml`, `json`, `ini`
+/// 其中 `ext` 为 `toml`, `json`, `ini`；启用 `encryption` 特性时另查找对应的 `.enc` 加密文件
 pub fn resolve_config_files(app_meta: &QuantumConfigAppMeta) -> Result<Vec<ConfigFilePath>, QuantumConfigError> {
-    let mut config_files = Vec::new();
-    let app_name = &app_meta.app_name;
+    let config_dirs = get_config_directories(app_meta)?;
+    Ok(candidate_files_in(app_meta, config_dirs.into_iter().map(|dir| dir.path))
+        .into_iter()
+        .filter(|file| {
+            let exists = file.exists();
+            trace_debug!(path = %file.path.display(), exists, "probed config file");
+            exists
+        })
+        .collect())
+}
+
+/// 列出全部候选配置文件（不检查是否存在），按优先级从低到高排列，便于排查配置为何没有生效
+pub fn candidate_config_files(app_meta: &QuantumConfigAppMeta) -> Vec<ConfigFilePath> {
+    candidate_files_in(app_meta, candidate_config_dirs(app_meta).into_iter().map(|dir| dir.path))
+}
 
+/// 在给定目录中按文件名模式与扩展名生成候选文件
+fn candidate_files_in<I: IntoIterator<Item = PathBuf>>(app_meta: &QuantumConfigAppMeta, dirs: I) -> Vec<ConfigFilePath> {
     // 支持的文件扩展名，按优先级排序
-    let extensions = [ConfigFileType::Toml, ConfigFileType::Json, ConfigFileType::Ini];
+    let extensions = [
+        ConfigFileType::Toml,
+        ConfigFileType::Json,
+        #[cfg(feature = "json5")]
+        ConfigFileType::Json5,
+        #[cfg(feature = "yaml")]
+        ConfigFileType::Yaml,
+        ConfigFileType::Ini,
+        #[cfg(feature = "hcl")]
+        ConfigFileType::Hcl,
+        #[cfg(feature = "xml")]
+        ConfigFileType::Xml,
+    ];
 
     // 文件名模式
-    let file_patterns = ["config", app_name.as_str()];
+    let file_patterns = ["config", app_meta.app_name.as_str()];
 
-    // 获取配置目录
-    let config_dirs = get_config_directories(app_name)?;
-
-    // 遍历配置目录（按优先级从低到高）
-    for config_dir in config_dirs {
-        // 在每个目录中查找配置文件
+    let mut files = Vec::new();
+    for config_dir in dirs {
         for pattern in &file_patterns {
             for &file_type in &extensions {
                 let filename = format!("{}.{}", pattern, file_type.extension());
-                let file_path = config_dir.join(&filename);
-
-                if file_path.exists() && file_path.is_file() {
-                    config_files.push(ConfigFilePath::new(
-                        file_path,
-                        file_type,
-                        false, // 系统级和用户级文件默认不是必需的
-                    ));
-                }
+                // 系统级和用户级文件默认不是必需的
+                files.push(ConfigFilePath::new(config_dir.join(&filename), file_type, false));
+                // 启用加密支持时同时查找加密文件，例如 `config.toml.enc`，合并在明文文件之后
+                #[cfg(feature = "encryption")]
+                files.push(ConfigFilePath::new(
+                    config_dir.join(format!("{}.{}", filename, crate::providers::file_provider::ENCRYPTED_EXTENSION)),
+                    file_type,
+                    false,
+                ));
             }
         }
     }
+    files
+}
+
+/// 按查找策略列出候选配置目录（不检查是否存在），按优先级从低到高排列
+pub fn candidate_config_dirs(app_meta: &QuantumConfigAppMeta) -> Vec<ConfigDir> {
+    let app_name = &app_meta.app_name;
+    match &app_meta.path_strategy {
+        PathStrategy::Native => {
+            // 目录由加载上下文决定，默认按平台惯例，参见 [`crate::context::DirsSource`]
+            let dirs = crate::context::dirs_source();
+            let Some(user_dir) = dirs.user_config_dir(app_meta) else {
+                return Vec::new();
+            };
+            // 系统级配置目录（低优先级），用户级配置目录（高优先级）
+            dirs.system_config_dir(app_meta)
+                .map(|path| ConfigDir { path, dir_type: ConfigDirType::System })
+                .into_iter()
+                .chain(std::iter::once(ConfigDir { path: user_dir, dir_type: ConfigDirType::User }))
+                .collect()
+        }
+        PathStrategy::Xdg => {
+            let env = crate::context::env_source();
+            xdg_config_dirs(
+                app_name,
+                env.var("XDG_CONFIG_HOME").map(PathBuf::from),
+                env.var("XDG_CONFIG_DIRS"),
+                crate::context::dirs_source().home_dir(),
+            )
+        }
+        PathStrategy::Dirs(dirs) => dirs
+            .iter()
+            .map(|path| ConfigDir { path: path.clone(), dir_type: ConfigDirType::System })
+            .collect(),
+    }
+}
+
+/// 按 XDG 基础目录规范列出配置目录，相对路径与空值按规范忽略
+fn xdg_config_dirs(
+    app_name: &str,
+    config_home: Option<PathBuf>,
+    config_dirs: Option<String>,
+    home: Option<PathBuf>,
+) -> Vec<ConfigDir> {
+    let config_dirs = config_dirs.filter(|dirs| !dirs.is_empty()).unwrap_or_else(|| "/etc/xdg".to_string());
+    // XDG_CONFIG_DIRS 按重要性从高到低排列，这里需要从低到高
+    let mut dirs: Vec<ConfigDir> = config_dirs
+        .split(':')
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .rev()
+        .map(|dir| ConfigDir { path: dir.join(app_name), dir_type: ConfigDirType::System })
+        .collect();
+    let config_home = config_home
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home.map(|home| home.join(".config")));
+    if let Some(config_home) = config_home {
+        dirs.push(ConfigDir { path: config_home.join(app_name), dir_type: ConfigDirType::User });
+    }
+    dirs
+}
 
-    Ok(config_files)
+/// 按应用元数据中的限定符与组织名构造平台目录
+/// 用户级配置目录（不检查是否存在）
+pub(crate) fn user_config_dir(app_meta: &QuantumConfigAppMeta) -> Option<PathBuf> {
+    candidate_config_dirs(app_meta)
+        .into_iter()
+        .find(|dir| dir.dir_type == ConfigDirType::User)
+        .map(|dir| dir.path)
 }
 
 /// 获取配置目录列表
 ///
-/// 返回按优先级排序的配置目录列表（低优先级在前）：
+/// 返回按优先级排序、实际存在的配置目录列表（低优先级在前）：
 /// 1. 系统级配置目录
 /// 2. 用户级配置目录
-fn get_config_directories(app_name: &str) -> Result<Vec<PathBuf>, QuantumConfigError> {
-    let mut dirs = Vec::new();
-
-    // 使用 directories crate 获取标准配置目录
-    if let Some(project_dirs) = directories::ProjectDirs::from("", "", app_name) {
-        // 系统级配置目录（低优先级）
-        // 在 Windows 上通常是 C:\ProgramData\{app_name}
-        // 在 Unix 上通常是 /etc/{app_name}
-        let system_config_dir = project_dirs.config_dir().parent()
-            .and_then(|p| p.parent())
-            .map(|p| {
-                #[cfg(windows)]
-                { p.join("ProgramData").join(app_name) }
-                #[cfg(not(windows))]
-                { PathBuf::from("/etc").join(app_name) }
-            });
-
-        if let Some(system_dir) = system_config_dir {
-            if system_dir.exists() {
-                dirs.push(system_dir);
-            }
-        }
-
-        // 用户级配置目录（高优先级）
-        // 在 Windows 上通常是 %APPDATA%\{app_name}
-        // 在 Unix 上通常是 ~/.config/{app_name}
-        let user_config_dir = project_dirs.config_dir();
-        if user_config_dir.exists() {
-            dirs.push(user_config_dir.to_path_buf());
-        }
-    } else {
+fn get_config_directories(app_meta: &QuantumConfigAppMeta) -> Result<Vec<ConfigDir>, QuantumConfigError> {
+    let candidates = candidate_config_dirs(app_meta);
+    if candidates.is_empty() && app_meta.path_strategy == PathStrategy::Native {
         return Err(QuantumConfigError::ConfigDirNotFound {
-            dir_type: crate::error::ConfigDirType::User,
+            dir_type: ConfigDirType::User,
             expected_path: None,
         });
     }
 
+    let dirs: Vec<ConfigDir> = candidates.into_iter().filter(|dir| dir.path.exists()).collect();
+
     // 如果没有找到任何配置目录，返回错误
     if dirs.is_empty() {
         return Err(QuantumConfigError::NoConfigFilesFoundInDir {
-            dir_type: crate::error::ConfigDirType::User,
-            path: std::path::PathBuf::from(format!("No valid config directories found for app: {}", app_name)),
+            dir_type: ConfigDirType::User,
+            path: std::path::PathBuf::from(format!("No valid config directories found for app: {}", app_meta.app_name)),
         });
     }
 
@@ -191,8 +319,9 @@ pub fn validate_path_security(path: &Path) -> Result<PathBuf, QuantumConfigError
         })?
     } else {
         // 如果文件不存在，使用绝对路径进行检查
-        std::env::current_dir()
-            .map_err(|_| QuantumConfigError::SecurityViolation {
+        crate::context::dirs_source()
+            .current_dir()
+            .ok_or_else(|| QuantumConfigError::SecurityViolation {
                 message: "Cannot determine current directory".to_string(),
             })?
             .join(path)
@@ -256,10 +385,7 @@ pub fn add_specified_config_file(
     }
 
     // 从文件扩展名推断文件类型
-    let file_type = safe_path
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .and_then(ConfigFileType::from_extension)
+    let file_type = ConfigFileType::from_path(&safe_path)
         .ok_or_else(|| QuantumConfigError::UnsupportedFormat {
             path: file_path.clone(),
         })?;
@@ -270,12 +396,184 @@ pub fn add_specified_config_file(
     Ok(())
 }
 
+/// 本地覆盖文件使用的配置剖面名称，例如 `config.local.toml`
+pub const LOCAL_PROFILE: &str = "local";
+
+/// 校验配置剖面名称：只允许字母、数字、`-` 与 `_`，防止通过剖面名称构造任意路径
+pub fn validate_profile_name(profile: &str) -> Result<(), QuantumConfigError> {
+    validate_layer_name("profile", profile)
+}
+
+/// 校验租户名称：只允许小写字母、数字与 `-`
+///
+/// 租户名称转换为环境变量前缀时 `-` 变为 `_` 并转为大写，限制字符集后不同的租户名称不会对应同一个前缀
+/// （否则 `tenant-a`、`tenant_a` 与 `Tenant-A` 会读取同一组 `APP__TENANT_A__` 变量）。
+pub fn validate_tenant_name(tenant: &str) -> Result<(), QuantumConfigError> {
+    if !tenant.is_empty() && tenant.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+        Ok(())
+    } else {
+        Err(QuantumConfigError::InvalidValue {
+            key_path: "tenant".to_string(),
+            message: format!("invalid tenant name '{}': only lowercase letters, digits and '-' are allowed", tenant),
+        })
+    }
+}
+
+/// 租户专属环境变量的前缀，例如基础前缀 `APP_`、租户 `tenant-a` 对应 `APP__TENANT_A__`
+pub fn tenant_env_prefix(prefix: &str, tenant: &str) -> String {
+    format!("{}__{}__", prefix.trim_end_matches('_'), tenant.to_uppercase().replace('-', "_"))
+}
+
+/// 剖面与租户名称会拼入文件名，只允许字母、数字、`-` 与 `_`
+fn validate_layer_name(kind: &str, name: &str) -> Result<(), QuantumConfigError> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        Ok(())
+    } else {
+        Err(QuantumConfigError::InvalidValue {
+            key_path: kind.to_string(),
+            message: format!("invalid {} name '{}': only letters, digits, '-' and '_' are allowed", kind, name),
+        })
+    }
+}
+
+/// 配置文件的剖面层：与 `base` 同目录的 `{stem}.{profile}.{ext}` 与 `{stem}.local.{ext}`，只返回存在的文件
+///
+/// 剖面层合并在 `base` 之后，本地覆盖文件最后合并；剖面层均不是必需文件。
+pub fn profile_config_files(base: &ConfigFilePath, profile: Option<&str>) -> Vec<ConfigFilePath> {
+    let (Some(stem), Some(ext)) = (
+        base.path.file_stem().and_then(|stem| stem.to_str()),
+        base.path.extension().and_then(|ext| ext.to_str()),
+    ) else {
+        return Vec::new();
+    };
+    profile
+        .filter(|profile| *profile != LOCAL_PROFILE)
+        .into_iter()
+        .chain(std::iter::once(LOCAL_PROFILE))
+        .map(|layer| base.path.with_file_name(format!("{}.{}.{}", stem, layer, ext)))
+        .filter(|path| path.is_file())
+        .map(|path| ConfigFilePath::new(path, base.file_type, false))
+        .collect()
+}
+
+/// 配置文件的租户层：与 `base` 同目录的 `{stem}.{tenant}.{ext}`（例如 `config.tenant-a.toml`），文件不存在时返回 `None`
+pub fn tenant_config_file(base: &ConfigFilePath, tenant: &str) -> Option<ConfigFilePath> {
+    let stem = base.path.file_stem()?.to_str()?;
+    let ext = base.path.extension()?.to_str()?;
+    let path = base.path.with_file_name(format!("{}.{}.{}", stem, tenant, ext));
+    path.is_file().then(|| ConfigFilePath::new(path, base.file_type, false))
+}
+
+/// 配置文件的片段目录：与 `base` 同目录的 `{stem}.d/`（例如 `config.d/`）中受支持格式的文件，按文件名字典序返回
+///
+/// 片段合并在 `base` 之后，均不是必需文件；以 `.` 开头的隐藏文件与扩展名不受支持的文件（例如编辑器备份）被忽略。
+/// 目录不存在时返回空列表。
+pub fn fragment_config_files(base: &ConfigFilePath) -> Result<Vec<ConfigFilePath>, QuantumConfigError> {
+    let Some(stem) = base.path.file_stem() else {
+        return Ok(Vec::new());
+    };
+    let mut dir_name = stem.to_os_string();
+    dir_name.push(".d");
+    let dir = base.path.with_file_name(dir_name);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = std::fs::read_dir(&dir).map_err(|e| QuantumConfigError::Io { source: e, path: dir.clone() })?;
+
+    let mut fragments = Vec::new();
+    for entry in entries {
+        let path = entry.map_err(|e| QuantumConfigError::Io { source: e, path: dir.clone() })?.path();
+        let hidden = path.file_name().and_then(|name| name.to_str()).is_none_or(|name| name.starts_with('.'));
+        let file_type = ConfigFileType::from_path(&path);
+        if let (false, Some(file_type)) = (hidden, file_type) {
+            if path.is_file() {
+                fragments.push(ConfigFilePath::new(path, file_type, false));
+            }
+        }
+    }
+    fragments.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(fragments)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_profile_config_files_follow_base() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["config.toml", "config.production.toml", "config.local.toml", "config.staging.json"] {
+            fs::write(temp_dir.path().join(name), "").unwrap();
+        }
+        let base = ConfigFilePath::new(temp_dir.path().join("config.toml"), ConfigFileType::Toml, true);
+        let names = |profile| {
+            profile_config_files(&base, profile)
+                .into_iter()
+                .map(|file| {
+                    assert!(!file.is_required);
+                    file.path.file_name().unwrap().to_string_lossy().into_owned()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(Some("production")), ["config.production.toml", "config.local.toml"]);
+        assert_eq!(names(Some("staging")), ["config.local.toml"]);
+        assert_eq!(names(Some("local")), ["config.local.toml"]);
+        assert_eq!(names(None), ["config.local.toml"]);
+
+        assert!(validate_profile_name("prod_eu-1").is_ok());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(validate_profile_name("").is_err());
+
+        fs::write(temp_dir.path().join("config.tenant-a.toml"), "").unwrap();
+        assert!(tenant_config_file(&base, "tenant-a").is_some_and(|file| !file.is_required));
+        assert!(tenant_config_file(&base, "tenant-b").is_none());
+        assert!(validate_tenant_name("tenant/a").is_err());
+        // 会对应同一个环境变量前缀的写法只接受一种
+        assert!(validate_tenant_name("tenant-a").is_ok());
+        assert!(validate_tenant_name("tenant_a").is_err());
+        assert!(validate_tenant_name("Tenant-A").is_err());
+        assert_eq!(tenant_env_prefix("APP_", "tenant-a"), "APP__TENANT_A__");
+    }
+
+    #[test]
+    fn test_fragment_config_files_are_sorted_and_filtered() {
+        let temp_dir = TempDir::new().unwrap();
+        let fragment_dir = temp_dir.path().join("config.d");
+        fs::create_dir_all(fragment_dir.join("nested.toml")).unwrap();
+        for name in ["20-db.json", "10-log.toml", ".10-hidden.toml", "30-cache.toml.bak", "README"] {
+            fs::write(fragment_dir.join(name), "").unwrap();
+        }
+        let base = ConfigFilePath::new(temp_dir.path().join("config.toml"), ConfigFileType::Toml, true);
+
+        let fragments = fragment_config_files(&base).unwrap();
+        let names: Vec<_> = fragments
+            .iter()
+            .map(|file| {
+                assert!(!file.is_required);
+                file.path.file_name().unwrap().to_string_lossy().into_owned()
+            })
+            .collect();
+        assert_eq!(names, ["10-log.toml", "20-db.json"]);
+        assert_eq!(fragments[1].file_type, ConfigFileType::Json);
+
+        #[cfg(feature = "yaml")]
+        {
+            fs::write(fragment_dir.join("15-cache.yaml"), "").unwrap();
+            fs::write(fragment_dir.join("25-queue.yml"), "").unwrap();
+            let types: Vec<_> = fragment_config_files(&base).unwrap().into_iter().map(|file| file.file_type).collect();
+            assert_eq!(
+                types,
+                [ConfigFileType::Toml, ConfigFileType::Yaml, ConfigFileType::Json, ConfigFileType::Yaml]
+            );
+        }
+
+        let other = ConfigFilePath::new(temp_dir.path().join("myapp.toml"), ConfigFileType::Toml, false);
+        assert!(fragment_config_files(&other).unwrap().is_empty());
+    }
+
     #[test]
     fn test_config_file_type_extension() {
         assert_eq!(ConfigFileType::Toml.extension(), "toml");
@@ -292,7 +590,12 @@ mod tests {
         assert_eq!(ConfigFileType::from_extension("ini"), Some(ConfigFileType::Ini));
         assert_eq!(ConfigFileType::from_extension("INI"), Some(ConfigFileType::Ini));
         assert_eq!(ConfigFileType::from_extension("txt"), None);
+        #[cfg(not(feature = "yaml"))]
         assert_eq!(ConfigFileType::from_extension("yaml"), None);
+        #[cfg(feature = "yaml")]
+        assert_eq!(ConfigFileType::from_extension("yml"), Some(ConfigFileType::Yaml));
+        assert_eq!(ConfigFileType::from_path(Path::new("config.toml.enc")), Some(ConfigFileType::Toml));
+        assert_eq!(ConfigFileType::from_path(Path::new("config.enc")), None);
     }
 
     #[test]
@@ -328,6 +631,7 @@ mod tests {
             env_prefix: None,
             behavior_version: 1,
             max_parse_depth: 32,
+            ..Default::default()
         };
 
         // 这个测试依赖于系统环境，所以我们只检查函数不会 panic
@@ -349,6 +653,71 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_xdg_config_dirs_order_and_fallbacks() {
+        let dirs = xdg_config_dirs(
+            "myapp",
+            Some(PathBuf::from("/home/u/.cfg")),
+            Some("/opt/xdg:relative:/etc/xdg".to_string()),
+            Some(PathBuf::from("/home/u")),
+        );
+        assert_eq!(
+            dirs,
+            vec![
+                ConfigDir { path: PathBuf::from("/etc/xdg/myapp"), dir_type: ConfigDirType::System },
+                ConfigDir { path: PathBuf::from("/opt/xdg/myapp"), dir_type: ConfigDirType::System },
+                ConfigDir { path: PathBuf::from("/home/u/.cfg/myapp"), dir_type: ConfigDirType::User },
+            ]
+        );
+
+        // 未设置或为相对路径时使用规范的默认值
+        let dirs = xdg_config_dirs("myapp", Some(PathBuf::from("cfg")), Some(String::new()), Some(PathBuf::from("/home/u")));
+        assert_eq!(
+            dirs.iter().map(|dir| dir.path.clone()).collect::<Vec<_>>(),
+            vec![PathBuf::from("/etc/xdg/myapp"), PathBuf::from("/home/u/.config/myapp")]
+        );
+    }
+
+    #[test]
+    fn test_custom_dirs_strategy_lists_candidates_and_resolves_existing() {
+        let low = TempDir::new().unwrap();
+        let high = TempDir::new().unwrap();
+        fs::write(high.path().join("config.toml"), "name = \"high\"").unwrap();
+        fs::write(low.path().join("myapp.json"), "{}").unwrap();
+        let app_meta = QuantumConfigAppMeta {
+            app_name: "myapp".to_string(),
+            path_strategy: PathStrategy::Dirs(vec![low.path().to_path_buf(), high.path().to_path_buf()]),
+            ..Default::default()
+        };
+
+        let candidates = candidate_config_files(&app_meta);
+        assert_eq!(candidates.len(), 2 * candidate_files_in(&app_meta, [PathBuf::new()]).len());
+        assert_eq!(candidates[0].path, low.path().join("config.toml"));
+        assert!(candidates.iter().any(|file| file.path == high.path().join("myapp.ini")));
+        assert_eq!(user_config_dir(&app_meta), None);
+
+        let resolved = resolve_config_files(&app_meta).unwrap();
+        assert_eq!(
+            resolved.iter().map(|file| file.path.clone()).collect::<Vec<_>>(),
+            vec![low.path().join("myapp.json"), high.path().join("config.toml")]
+        );
+    }
+
+    #[cfg(feature = "platform-dirs")]
+    #[test]
+    fn test_native_strategy_uses_organization_for_user_dir() {
+        let app_meta = QuantumConfigAppMeta {
+            app_name: "myapp".to_string(),
+            qualifier: Some("com".to_string()),
+            organization: Some("Example".to_string()),
+            ..Default::default()
+        };
+        if let Some(project_dirs) = directories::ProjectDirs::from("com", "Example", "myapp") {
+            assert_eq!(user_config_dir(&app_meta), Some(project_dirs.config_dir().to_path_buf()));
+            assert_eq!(candidate_config_dirs(&app_meta).last().unwrap().dir_type, ConfigDirType::User);
+        }
+    }
+
     #[test]
     fn test_add_specified_config_file_success() {
         let temp_dir = TempDir::new().unwrap();
@@ -388,7 +757,7 @@ mod tests {
     #[test]
     fn test_add_specified_config_file_unsupported_format() {
         let temp_dir = TempDir::new().unwrap();
-        let temp_file = temp_dir.path().join("config.yaml");
+        let temp_file = temp_dir.path().join("config.conf");
         fs::write(&temp_file, "key: value").unwrap();
 
         let mut config_files = Vec::new();
@@ -407,16 +776,19 @@ mod tests {
 
     #[test]
     fn test_get_config_directories() {
-        let app_name = "test_app_for_dirs";
+        let app_meta = QuantumConfigAppMeta {
+            app_name: "test_app_for_dirs".to_string(),
+            ..Default::default()
+        };
 
         // 这个测试依赖于系统环境
-        let result = get_config_directories(app_name);
+        let result = get_config_directories(&app_meta);
 
         match result {
             Ok(dirs) => {
                 // 验证返回的目录都是绝对路径
                 for dir in dirs {
-                    assert!(dir.is_absolute());
+                    assert!(dir.path.is_absolute());
                 }
             }
             Err(e) => {
@@ -447,6 +819,7 @@ mod tests {
             env_prefix: None,
             behavior_version: 1,
             max_parse_depth: 32,
+            ..Default::default()
         };
 
         let result = resolve_config_files(&app_meta);
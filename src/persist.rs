@@ -0,0 +1,167 @@
+//! 配置写回
+//!
+//! 将运行期修改后的配置序列化并写回文件，供 [`crate::Config::save_to_file`] 使用：
+//! - 敏感字段（`#[config(secret)]`）不写入，避免把密钥落盘；`#[serde(skip)]` 的字段本身不参与序列化
+//! - 目标为已存在的 TOML 文件时，通过 [`crate::edit::TomlDocument`] 就地更新：保留注释、键顺序与排版，
+//!   值为 `null`（`None`）的键被移除，未出现在序列化结果中的键（例如敏感字段）保持原样
+//! - 其余情况按 [`crate::template::render_template`] 渲染，字段文档写为注释（格式支持时）；
+//!   启用 `yaml` 特性后可写为 YAML
+//!
+//! 写入先落到同目录下的临时文件再重命名，进程中途退出不会留下半份配置。
+
+use crate::edit::TomlDocument;
+use crate::error::QuantumConfigError;
+use crate::providers::file_provider::FileFormat;
+use crate::template;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::io::Write;
+use std::path::Path;
+
+/// 序列化 `value` 并写入 `path`，省略 `omitted_keys` 中以 `.` 分隔的键路径
+///
+/// # Errors
+/// 配置无法序列化、已有的 TOML 文件无法解析或写入失败时返回错误
+pub fn save_to_file<T: Serialize>(
+    value: &T,
+    path: &Path,
+    format: FileFormat,
+    omitted_keys: &[&str],
+    docs: &[(&str, &str)],
+) -> Result<(), QuantumConfigError> {
+    let mut json = serde_json::to_value(value)
+        .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize configuration: {}", e)))?;
+    for key in omitted_keys {
+        crate::redact::remove_key(&mut json, key);
+    }
+
+    if matches!(format, FileFormat::Toml) && path.exists() {
+        let mut document = TomlDocument::open(path)?;
+        document.apply(&json)?;
+        return document.save();
+    }
+    let content = template::render_template(&strip_nulls(json), format, docs)?;
+    write_atomically(path, &content)
+}
+
+/// 移除对象中值为 `null` 的键，TOML 与 INI 无法表示 `null`
+pub(crate) fn strip_nulls(value: JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => JsonValue::Object(
+            map.into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| (k, strip_nulls(v)))
+                .collect(),
+        ),
+        JsonValue::Array(items) => JsonValue::Array(items.into_iter().map(strip_nulls).collect()),
+        other => other,
+    }
+}
+
+/// 先写入同目录的临时文件，再重命名为目标文件
+///
+/// 目标文件已存在时，临时文件在写入内容之前获得与它相同的权限（例如 `0600`），重命名后权限不会放宽。
+pub(crate) fn write_atomically(path: &Path, content: &str) -> Result<(), QuantumConfigError> {
+    let mut tmp_name = path.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+    tmp_name.push(".tmp");
+    let tmp = path.with_file_name(tmp_name);
+    let io_error = |source| QuantumConfigError::Io {
+        source,
+        path: path.to_path_buf(),
+    };
+    let write = || -> std::io::Result<()> {
+        let mut file = std::fs::File::create(&tmp)?;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    };
+    write().map_err(|e| {
+        let _ = std::fs::remove_file(&tmp);
+        io_error(e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_toml_write_back_preserves_comments_and_omitted_keys() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "# 应用名称\nname = \"app\" # 行尾注释\ntoken = \"on-disk-secret\"\nlog_level = \"info\"\n\n# 数据库\n[database]\nhost = \"localhost\"\nport = 5432\n",
+        )
+        .unwrap();
+
+        let value = json!({
+            "name": "renamed",
+            "token": "in-memory-secret",
+            "log_level": null,
+            "tags": ["a", "b"],
+            "database": { "host": "db.internal", "port": 6432 }
+        });
+        save_to_file(&value, &path, FileFormat::Toml, &["token"], &[]).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            content,
+            "# 应用名称\nname = \"renamed\" # 行尾注释\ntoken = \"on-disk-secret\"\ntags = [\"a\", \"b\"]\n\n# 数据库\n[database]\nhost = \"db.internal\"\nport = 6432\n"
+        );
+        assert!(!dir.path().join("config.toml.tmp").exists());
+    }
+
+    #[test]
+    fn test_new_files_are_rendered_with_docs_and_without_omitted_keys() {
+        let dir = tempdir().unwrap();
+        let value = json!({ "name": "app", "token": "secret", "timeout": null });
+
+        let toml_path = dir.path().join("config.toml");
+        save_to_file(&value, &toml_path, FileFormat::Toml, &["token"], &[("name", "应用名称")]).unwrap();
+        assert_eq!(std::fs::read_to_string(&toml_path).unwrap(), "# 应用名称\nname = \"app\"\n");
+
+        let json_path = dir.path().join("config.json");
+        save_to_file(&value, &json_path, FileFormat::Json, &["token"], &[]).unwrap();
+        let written: JsonValue = serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(written, json!({ "name": "app" }));
+
+        #[cfg(feature = "yaml")]
+        {
+            let yaml_path = dir.path().join("config.yaml");
+            save_to_file(&value, &yaml_path, FileFormat::Yaml, &["token"], &[("name", "应用名称")]).unwrap();
+            assert_eq!(std::fs::read_to_string(&yaml_path).unwrap(), "# 应用名称\nname: \"app\"\n");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_back_keeps_existing_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        for (name, format) in [("config.toml", FileFormat::Toml), ("config.json", FileFormat::Json)] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, "").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+            save_to_file(&json!({ "name": "app" }), &path, format, &[], &[]).unwrap();
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_invalid_existing_toml_is_not_overwritten() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "name = ").unwrap();
+
+        let error = save_to_file(&json!({ "name": "app" }), &path, FileFormat::Toml, &[], &[]).unwrap_err();
+        assert_eq!(error.code(), "file_parse");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "name = ");
+    }
+}
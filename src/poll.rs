@@ -0,0 +1,358 @@
+//! 远程配置轮询
+//!
+//! 不提供变更通知的远程来源（HTTP、etcd、Consul 等）可以由 [`RemotePoller`] 定期重新构建 figment 并提取配置。
+//! 只有合并后的配置内容发生变化时才发布新配置，发布方式与 [`ConfigWatcher`] 相同（通道或 [`Reloadable`] 句柄）：
+//!
+//! ```ignore
+//! use quantum_config::poll::RemotePoller;
+//!
+//! let (_poller, config) = RemotePoller::new(|| Figment::from(EtcdProvider::new("http://etcd:2379", "/myapp/")))
+//!     .with_interval(Duration::from_secs(30))
+//!     .reloadable::<AppConfig>()?;
+//! // 之后 config.load() 返回最新的配置
+//! ```
+//!
+//! - 轮询失败时保留最后一次成功加载的配置（last-known-good），并按指数退避重试：首次重试等待
+//!   [`RemotePoller::with_initial_backoff`]，每次失败加倍，不超过 [`RemotePoller::with_max_backoff`]；
+//!   恢复后回到正常的轮询间隔；
+//! - 每次等待加上随机抖动（默认 ±10%），避免大量实例同时请求配置服务；
+//! - 每次加载与构建器一样检查字段的来源限制、登记敏感值并调用 [`Config::validate`]，校验失败的结果按轮询失败处理；
+//!   轮询 figment 中的各层视为 `remote` 类别，以 `Serialized::defaults(T::default())` 合并的默认值除外；
+//! - 返回的 [`ConfigWatcher`] 被丢弃时轮询线程退出。
+
+use crate::config::{extract_validated, Config};
+use crate::error::QuantumConfigError;
+use crate::logging::log_info;
+use crate::reload::Reloadable;
+use crate::report::{ConfigSources, LayerKind};
+use crate::watch::{reloadable_sink, ConfigWatcher, Sink};
+use figment::value::Value;
+use figment::Figment;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 默认的轮询间隔
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 默认的首次重试等待
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// 默认的最长重试等待
+const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// 默认的抖动比例
+const DEFAULT_JITTER: f64 = 0.1;
+
+/// 远程配置轮询器
+#[derive(Clone)]
+pub struct RemotePoller {
+    /// 每次轮询时用于构建 figment 的函数
+    figment_fn: Arc<dyn Fn() -> Figment + Send + Sync>,
+    /// 正常的轮询间隔
+    interval: Duration,
+    /// 首次重试等待
+    initial_backoff: Duration,
+    /// 最长重试等待
+    max_backoff: Duration,
+    /// 抖动比例，等待时间在 `[d * (1 - jitter), d * (1 + jitter)]` 内随机取值
+    jitter: f64,
+}
+
+impl std::fmt::Debug for RemotePoller {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemotePoller")
+            .field("interval", &self.interval)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("jitter", &self.jitter)
+            .finish()
+    }
+}
+
+impl RemotePoller {
+    /// 创建每次轮询调用 `figment_fn` 构建 figment 的轮询器
+    pub fn new<F>(figment_fn: F) -> Self
+    where
+        F: Fn() -> Figment + Send + Sync + 'static,
+    {
+        Self {
+            figment_fn: Arc::new(figment_fn),
+            interval: DEFAULT_INTERVAL,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_backoff: DEFAULT_MAX_BACKOFF,
+            jitter: DEFAULT_JITTER,
+        }
+    }
+
+    /// 设置正常的轮询间隔
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// 设置轮询失败后的首次重试等待
+    pub fn with_initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// 设置连续失败时重试等待的上限
+    pub fn with_max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// 设置抖动比例（0 到 1），0 表示不加抖动
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// 启动轮询，通过返回的监视器的通道投递配置
+    ///
+    /// 第一次轮询立即进行，成功时投递初始配置；之后只在配置内容变化时投递。
+    /// 每次轮询失败都投递错误，调用方继续使用最后一次收到的配置即可。
+    pub fn watch<T>(self) -> ConfigWatcher<T>
+    where
+        T: Config + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        self.spawn(None, Duration::ZERO, Sink::Channel(sender), Some(receiver))
+    }
+
+    /// 立即加载一次配置，启动轮询并把之后变化的配置写入 [`Reloadable`] 句柄
+    ///
+    /// 轮询失败时句柄保留最后一次成功加载的配置并输出警告日志。
+    ///
+    /// # Errors
+    /// 首次加载失败时返回错误
+    pub fn reloadable<T>(self) -> Result<(ConfigWatcher<T>, Reloadable<T>), QuantumConfigError>
+    where
+        T: Config + Send + Sync + 'static,
+    {
+        let figment = (self.figment_fn)();
+        let value = figment.extract::<Value>()?;
+        let reloadable = Reloadable::new(extract_checked::<T>(&figment)?);
+        let delay = self.jittered(self.interval);
+        let watcher = self.spawn(Some(value), delay, reloadable_sink(&reloadable), None);
+        Ok((watcher, reloadable))
+    }
+
+    /// 启动后台轮询线程
+    ///
+    /// `last_good` 为已发布配置的原始内容，用于判断轮询结果是否变化。
+    fn spawn<T>(
+        self,
+        mut last_good: Option<Value>,
+        first_delay: Duration,
+        mut sink: Sink<T>,
+        receiver: Option<Receiver<Result<T, QuantumConfigError>>>,
+    ) -> ConfigWatcher<T>
+    where
+        T: Config + Send + 'static,
+    {
+        let (stop, stopped) = mpsc::channel::<()>();
+        std::thread::spawn(move || {
+            let mut delay = first_delay;
+            let mut failures: u32 = 0;
+            loop {
+                // 等待期间监视器被丢弃时立即退出
+                if stopped.recv_timeout(delay) != Err(RecvTimeoutError::Timeout) {
+                    return;
+                }
+                let delivered = match self.poll(last_good.as_ref()) {
+                    Ok(update) => {
+                        if failures > 0 {
+                            log_info!("Remote configuration poll recovered after {} failed attempts", failures);
+                        }
+                        failures = 0;
+                        match update {
+                            Some((value, config)) => {
+                                last_good = Some(value);
+                                sink.deliver(Ok(config))
+                            }
+                            None => true,
+                        }
+                    }
+                    Err(e) => {
+                        failures = failures.saturating_add(1);
+                        sink.deliver(Err(e))
+                    }
+                };
+                if !delivered {
+                    return;
+                }
+                delay = self.jittered(self.next_delay(failures));
+            }
+        });
+        ConfigWatcher::from_poller(stop, receiver)
+    }
+
+    /// 轮询一次；配置与 `last_good` 相同时返回 `None`
+    fn poll<T: Config>(&self, last_good: Option<&Value>) -> Result<Option<(Value, T)>, QuantumConfigError> {
+        let figment = (self.figment_fn)();
+        let value = figment.extract::<Value>()?;
+        if last_good == Some(&value) {
+            return Ok(None);
+        }
+        let config = extract_checked::<T>(&figment)?;
+        Ok(Some((value, config)))
+    }
+
+    /// 连续失败 `failures` 次后的下一次等待（未加抖动）
+    fn next_delay(&self, failures: u32) -> Duration {
+        if failures == 0 {
+            return self.interval;
+        }
+        let factor = 2u32.saturating_pow(failures - 1);
+        self.initial_backoff.saturating_mul(factor).min(self.max_backoff)
+    }
+
+    /// 在 `[d * (1 - jitter), d * (1 + jitter)]` 内随机取值
+    fn jittered(&self, delay: Duration) -> Duration {
+        if self.jitter == 0.0 {
+            return delay;
+        }
+        // 每个 RandomState 使用不同的随机密钥，足以分散各实例的请求时间
+        let random = RandomState::new().build_hasher().finish() as f64 / u64::MAX as f64;
+        delay.mul_f64(1.0 - self.jitter + 2.0 * self.jitter * random)
+    }
+}
+
+/// 检查来源限制后提取并校验配置，登记敏感值
+///
+/// 名称为 `T` 类型名的层（`Serialized::defaults(T::default())`）视为默认值，其余各层视为远程来源。
+fn extract_checked<T: Config>(figment: &Figment) -> Result<T, QuantumConfigError> {
+    let layers = figment
+        .metadata()
+        .map(|metadata| {
+            let kind = if metadata.name == std::any::type_name::<T>() { LayerKind::Default } else { LayerKind::Remote };
+            (metadata.clone(), kind)
+        })
+        .collect();
+    crate::restrict::enforce::<T>(&ConfigSources::new(figment.clone(), layers))?;
+    extract_validated(figment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::providers::Serialized;
+    use serde::{Deserialize, Serialize};
+    use std::sync::Mutex;
+
+    #[derive(crate::Config, Serialize, Deserialize, Debug, Default, PartialEq)]
+    struct PollConfig {
+        #[config(range(min = 1, max = 60000))]
+        port: u16,
+    }
+
+    /// 返回当前端口的 figment，端口为 `None` 时模拟远程来源不可用
+    fn remote(port: &Arc<Mutex<Option<u16>>>) -> impl Fn() -> Figment + Send + Sync + 'static {
+        let port = port.clone();
+        move || match *port.lock().unwrap() {
+            Some(port) => Figment::from(Serialized::defaults(serde_json::json!({ "port": port }))),
+            None => Figment::from(Serialized::defaults(serde_json::json!({ "port": "unavailable" }))),
+        }
+    }
+
+    #[test]
+    fn test_reloadable_publishes_only_changes_and_keeps_last_good() {
+        let port = Arc::new(Mutex::new(Some(8080)));
+        let (_poller, config) = RemotePoller::new(remote(&port))
+            .with_interval(Duration::from_millis(20))
+            .with_initial_backoff(Duration::from_millis(20))
+            .reloadable::<PollConfig>()
+            .unwrap();
+        let updates = config.subscribe();
+        assert_eq!(config.load().port, 8080);
+
+        // 内容不变的轮询不产生新的一代
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(config.generation(), 0);
+
+        *port.lock().unwrap() = None;
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(config.load().port, 8080);
+        assert_eq!(config.generation(), 0);
+
+        *port.lock().unwrap() = Some(9090);
+        let update = updates.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(update.config.port, 9090);
+    }
+
+    #[test]
+    fn test_watch_delivers_initial_config_errors_and_changes() {
+        let port = Arc::new(Mutex::new(Some(8080)));
+        let watcher = RemotePoller::new(remote(&port))
+            .with_interval(Duration::from_millis(20))
+            .with_initial_backoff(Duration::from_millis(20))
+            .with_jitter(0.0)
+            .watch::<PollConfig>();
+        let timeout = Duration::from_secs(5);
+        assert_eq!(watcher.recv_timeout(timeout).unwrap().unwrap(), PollConfig { port: 8080 });
+
+        *port.lock().unwrap() = None;
+        assert!(watcher.recv_timeout(timeout).unwrap().is_err());
+
+        *port.lock().unwrap() = Some(9090);
+        let next = loop {
+            if let Ok(config) = watcher.recv_timeout(timeout).unwrap() {
+                break config;
+            }
+        };
+        assert_eq!(next, PollConfig { port: 9090 });
+    }
+
+    #[test]
+    fn test_polled_configs_that_fail_validation_are_rejected() {
+        let port = Arc::new(Mutex::new(Some(0)));
+        assert!(matches!(
+            RemotePoller::new(remote(&port)).reloadable::<PollConfig>(),
+            Err(QuantumConfigError::ConstraintViolation { .. })
+        ));
+
+        *port.lock().unwrap() = Some(8080);
+        let (_poller, config) = RemotePoller::new(remote(&port))
+            .with_interval(Duration::from_millis(20))
+            .with_initial_backoff(Duration::from_millis(20))
+            .reloadable::<PollConfig>()
+            .unwrap();
+        let updates = config.subscribe();
+
+        // 超出范围的端口不会替换最后一次成功加载的配置
+        *port.lock().unwrap() = Some(0);
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(config.load().port, 8080);
+        assert_eq!(config.generation(), 0);
+
+        *port.lock().unwrap() = Some(9090);
+        let update = updates.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(update.config.port, 9090);
+
+        let port = Arc::new(Mutex::new(Some(0)));
+        let watcher = RemotePoller::new(remote(&port)).with_jitter(0.0).watch::<PollConfig>();
+        let error = watcher.recv_timeout(Duration::from_secs(5)).unwrap().unwrap_err();
+        assert!(matches!(error, QuantumConfigError::ConstraintViolation { .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_maximum_and_jitter_stays_in_range() {
+        let poller = RemotePoller::new(Figment::new)
+            .with_interval(Duration::from_secs(30))
+            .with_initial_backoff(Duration::from_secs(1))
+            .with_max_backoff(Duration::from_secs(10));
+        let delays: Vec<u64> = (0..6).map(|failures| poller.next_delay(failures).as_secs()).collect();
+        assert_eq!(delays, [30, 1, 2, 4, 8, 10]);
+        assert_eq!(poller.next_delay(u32::MAX), Duration::from_secs(10));
+
+        let poller = poller.with_jitter(0.2);
+        for _ in 0..100 {
+            let delay = poller.jittered(Duration::from_secs(10));
+            assert!(delay >= Duration::from_secs(8) && delay <= Duration::from_secs(12), "{:?}", delay);
+        }
+    }
+}
@@ -0,0 +1,652 @@
+//! AWS SSM Parameter Store 与 Secrets Manager 配置提供器
+//!
+//! - [`SsmParameterProvider`] 递归读取某个路径前缀下的全部参数（`GetParametersByPath`，默认解密
+//!   `SecureString`），去除前缀后按 `/` 拆分为嵌套键，例如前缀 `/myapp/prod` 下的
+//!   `/myapp/prod/database/password` 对应 `database.password`
+//! - [`SecretsManagerProvider`] 读取单个密钥（`GetSecretValue`）：JSON 对象密钥按字段合并，
+//!   其余密钥需要通过 [`SecretsManagerProvider::with_nested_key`] 指定所在的配置键
+//!
+//! 请求使用 Signature Version 4 签名，凭证默认读取 `AWS_ACCESS_KEY_ID`、`AWS_SECRET_ACCESS_KEY`
+//! 与 `AWS_SESSION_TOKEN`；`SecureString` 参数与密钥值会登记到 [`crate::scrub`]。
+//!
+//! ```ignore
+//! use quantum_config::providers::{SecretsManagerProvider, SsmParameterProvider};
+//!
+//! let figment = figment
+//!     .merge(SsmParameterProvider::from_env("/myapp/prod"))
+//!     .merge(SecretsManagerProvider::from_env("myapp/prod/database").with_nested_key("database"));
+//! ```
+
+use super::env_provider::parse_scalar_value;
+use super::key_trie::KeyTrie;
+use super::remote::{self, describe_error};
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map, Tag, Value};
+use figment::{Error, Metadata, Profile, Provider};
+use serde_json::Value as JsonValue;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// 默认请求超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 未设置 `AWS_REGION` / `AWS_DEFAULT_REGION` 时使用的默认区域
+const DEFAULT_REGION: &str = "us-east-1";
+
+/// SSM 单页返回的最大参数数量（服务端上限）
+const SSM_PAGE_SIZE: u32 = 10;
+
+/// AWS 访问凭证
+#[derive(Clone)]
+pub struct AwsCredentials {
+    /// Access Key ID
+    pub access_key_id: String,
+    /// Secret Access Key
+    pub secret_access_key: String,
+    /// 临时凭证的 Session Token
+    pub session_token: Option<String>,
+}
+
+impl AwsCredentials {
+    /// 使用长期凭证
+    pub fn new<S: Into<String>>(access_key_id: S, secret_access_key: S) -> Self {
+        Self {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token: None,
+        }
+    }
+
+    /// 设置临时凭证的 Session Token
+    pub fn with_session_token<S: Into<String>>(mut self, session_token: S) -> Self {
+        self.session_token = Some(session_token.into());
+        self
+    }
+
+    /// 从 `AWS_ACCESS_KEY_ID`、`AWS_SECRET_ACCESS_KEY` 与 `AWS_SESSION_TOKEN` 读取凭证
+    pub fn from_env() -> Option<Self> {
+        let access_key_id = std::env::var("AWS_ACCESS_KEY_ID").ok()?;
+        let secret_access_key = std::env::var("AWS_SECRET_ACCESS_KEY").ok()?;
+        Some(Self {
+            access_key_id,
+            secret_access_key,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+impl std::fmt::Debug for AwsCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出 Secret Access Key 与 Session Token
+        f.debug_struct("AwsCredentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("secret_access_key", &"<redacted>")
+            .field("session_token", &self.session_token.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+/// 两个提供器共用的 AWS JSON 协议客户端设置
+#[derive(Debug, Clone)]
+struct AwsClient {
+    /// 服务名，用于签名与默认端点，例如 "ssm"
+    service: &'static str,
+    /// 区域
+    region: String,
+    /// 自定义端点，为 `None` 时使用 `https://{service}.{region}.amazonaws.com`
+    endpoint: Option<String>,
+    /// 访问凭证，为 `None` 时在请求时从环境变量读取
+    credentials: Option<AwsCredentials>,
+    /// 请求超时
+    timeout: Duration,
+}
+
+impl AwsClient {
+    fn new(service: &'static str, region: String) -> Self {
+        Self {
+            service,
+            region,
+            endpoint: None,
+            credentials: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// 从 `AWS_REGION` 或 `AWS_DEFAULT_REGION` 读取区域
+    fn from_env(service: &'static str) -> Self {
+        Self::new(service, region_from_env())
+    }
+
+    fn endpoint(&self) -> String {
+        match &self.endpoint {
+            Some(endpoint) => endpoint.trim_end_matches('/').to_string(),
+            None => format!("https://{}.{}.amazonaws.com", self.service, self.region),
+        }
+    }
+
+    /// 发送签名后的 JSON 协议请求，`target` 为 `X-Amz-Target` 头，例如 "AmazonSSM.GetParametersByPath"
+    fn call(&self, target: &str, payload: &JsonValue) -> Result<JsonValue, String> {
+        let credentials = self
+            .credentials
+            .clone()
+            .or_else(AwsCredentials::from_env)
+            .ok_or_else(|| "no AWS credentials configured".to_string())?;
+        let endpoint = self.endpoint();
+        let host = endpoint.split_once("://").map_or(endpoint.as_str(), |(_, rest)| rest);
+        let host = host.split('/').next().unwrap_or(host);
+
+        let body = payload.to_string();
+        let amz_date = format_amz_date(SystemTime::now());
+        let mut headers = vec![
+            ("content-type", "application/x-amz-json-1.1"),
+            ("host", host),
+            ("x-amz-date", amz_date.as_str()),
+        ];
+        if let Some(token) = &credentials.session_token {
+            headers.push(("x-amz-security-token", token));
+        }
+        headers.push(("x-amz-target", target));
+        let request = SignedRequest {
+            method: "POST",
+            canonical_uri: "/",
+            headers: &headers,
+            payload_hash: &hex(sha256(body.as_bytes()).as_ref()),
+        };
+        let authorization = sign_request(&credentials, &self.region, self.service, &request);
+
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .into();
+        let mut request = agent
+            .post(format!("{}/", endpoint))
+            .header("Content-Type", "application/x-amz-json-1.1")
+            .header("X-Amz-Date", &amz_date)
+            .header("X-Amz-Target", target)
+            .header("Authorization", &authorization);
+        if let Some(token) = &credentials.session_token {
+            request = request.header("X-Amz-Security-Token", token);
+        }
+        let response = request
+            .send(&body)
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| describe_error(e, STATUS_HINTS))?;
+        serde_json::from_str(&response).map_err(|e| format!("invalid response: {}", e))
+    }
+}
+
+/// SSM Parameter Store 配置提供器
+#[derive(Debug, Clone)]
+pub struct SsmParameterProvider {
+    /// 客户端设置
+    client: AwsClient,
+    /// 参数路径前缀，例如 "/myapp/prod"
+    path: String,
+    /// 是否解密 `SecureString` 参数
+    with_decryption: bool,
+}
+
+impl SsmParameterProvider {
+    /// 创建读取 `path` 下全部参数的提供器
+    pub fn new<R: Into<String>, P: Into<String>>(region: R, path: P) -> Self {
+        Self::with_client(AwsClient::new("ssm", region.into()), path.into())
+    }
+
+    /// 从 `AWS_REGION` 或 `AWS_DEFAULT_REGION` 读取区域创建提供器
+    pub fn from_env<P: Into<String>>(path: P) -> Self {
+        Self::with_client(AwsClient::from_env("ssm"), path.into())
+    }
+
+    fn with_client(client: AwsClient, path: String) -> Self {
+        let path = format!("/{}", path.trim_matches('/'));
+        Self {
+            client,
+            path,
+            with_decryption: true,
+        }
+    }
+
+    /// 设置访问凭证
+    pub fn with_credentials(mut self, credentials: AwsCredentials) -> Self {
+        self.client.credentials = Some(credentials);
+        self
+    }
+
+    /// 设置自定义端点（例如 VPC 端点或本地模拟服务）
+    pub fn with_endpoint<S: Into<String>>(mut self, endpoint: S) -> Self {
+        self.client.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// 设置是否解密 `SecureString` 参数（默认解密）
+    pub fn with_decryption(mut self, enabled: bool) -> Self {
+        self.with_decryption = enabled;
+        self
+    }
+
+    /// 设置请求超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client.timeout = timeout;
+        self
+    }
+
+    /// 读取路径下的全部参数并构建键前缀树，叶子来源为参数名
+    pub fn key_trie(&self) -> Result<KeyTrie, QuantumConfigError> {
+        let mut trie = KeyTrie::new();
+        let mut secrets = crate::scrub::Secrets::new();
+        let mut next_token: Option<String> = None;
+        loop {
+            let mut payload = serde_json::json!({
+                "Path": self.path,
+                "Recursive": true,
+                "WithDecryption": self.with_decryption,
+                "MaxResults": SSM_PAGE_SIZE,
+            });
+            if let Some(token) = next_token.take() {
+                payload["NextToken"] = JsonValue::String(token);
+            }
+            let response = self
+                .client
+                .call("AmazonSSM.GetParametersByPath", &payload)
+                .map_err(|reason| self.error(reason))?;
+
+            for parameter in response.get("Parameters").and_then(JsonValue::as_array).into_iter().flatten() {
+                let (Some(name), Some(value)) = (
+                    parameter.get("Name").and_then(JsonValue::as_str),
+                    parameter.get("Value").and_then(JsonValue::as_str),
+                ) else {
+                    continue;
+                };
+                let Some(relative) = name.strip_prefix(self.path.as_str()) else {
+                    continue;
+                };
+                let segments: Vec<&str> = relative.split('/').filter(|s| !s.is_empty()).collect();
+                if segments.is_empty() {
+                    continue;
+                }
+                let value = match parameter.get("Type").and_then(JsonValue::as_str) {
+                    // 密钥保持字符串，避免纯数字密码被解析为数字
+                    Some("SecureString") => {
+                        secrets.add(value);
+                        Value::String(Tag::Default, value.to_string())
+                    }
+                    Some("StringList") => Value::from(value.split(',').map(str::to_string).collect::<Vec<_>>()),
+                    _ => parse_scalar_value(value.to_string()),
+                };
+                trie.insert(segments, value, Some(name.to_string()))?;
+            }
+
+            match response.get("NextToken").and_then(JsonValue::as_str) {
+                Some(token) if !token.is_empty() => next_token = Some(token.to_string()),
+                _ => {
+                    crate::scrub::replace_secrets(&format!("aws:ssm:{}", self.path), secrets);
+                    return Ok(trie);
+                }
+            }
+        }
+    }
+
+    /// 构造带有参数路径的错误
+    fn error(&self, reason: String) -> QuantumConfigError {
+        remote::error("aws", format!("ssm:{}", self.path), reason)
+    }
+}
+
+impl Provider for SsmParameterProvider {
+    fn metadata(&self) -> Metadata {
+        // 错误信息中的键路径还原为参数名
+        let path = self.path.clone();
+        Metadata::named(format!("AWS SSM Parameter Store ({})", self.path))
+            .interpolater(move |_profile, keys| format!("{}/{}", path, keys.join("/")))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let trie = self.key_trie().map_err(|e| remote::provider_error(e, None))?;
+        let mut data = Map::new();
+        data.insert(Profile::Default, trie.into_dict());
+        Ok(data)
+    }
+}
+
+/// Secrets Manager 配置提供器
+#[derive(Debug, Clone)]
+pub struct SecretsManagerProvider {
+    /// 客户端设置
+    client: AwsClient,
+    /// 密钥名称或 ARN
+    secret_id: String,
+    /// 密钥版本阶段，例如 "AWSCURRENT"
+    version_stage: Option<String>,
+    /// 将密钥嵌套到的配置键，为 `None` 时合并到顶层
+    nested_key: Option<String>,
+}
+
+impl SecretsManagerProvider {
+    /// 创建读取 `secret_id` 的提供器
+    pub fn new<R: Into<String>, S: Into<String>>(region: R, secret_id: S) -> Self {
+        Self::with_client(AwsClient::new("secretsmanager", region.into()), secret_id.into())
+    }
+
+    /// 从 `AWS_REGION` 或 `AWS_DEFAULT_REGION` 读取区域创建提供器
+    pub fn from_env<S: Into<String>>(secret_id: S) -> Self {
+        Self::with_client(AwsClient::from_env("secretsmanager"), secret_id.into())
+    }
+
+    fn with_client(client: AwsClient, secret_id: String) -> Self {
+        Self {
+            client,
+            secret_id,
+            version_stage: None,
+            nested_key: None,
+        }
+    }
+
+    /// 设置访问凭证
+    pub fn with_credentials(mut self, credentials: AwsCredentials) -> Self {
+        self.client.credentials = Some(credentials);
+        self
+    }
+
+    /// 设置自定义端点（例如 VPC 端点或本地模拟服务）
+    pub fn with_endpoint<S: Into<String>>(mut self, endpoint: S) -> Self {
+        self.client.endpoint = Some(endpoint.into());
+        self
+    }
+
+    /// 读取指定版本阶段，例如 "AWSPREVIOUS"
+    pub fn with_version_stage<S: Into<String>>(mut self, version_stage: S) -> Self {
+        self.version_stage = Some(version_stage.into());
+        self
+    }
+
+    /// 将密钥嵌套到指定配置键下（以 `.` 分隔），例如 "database"
+    pub fn with_nested_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.nested_key = Some(key.into());
+        self
+    }
+
+    /// 设置请求超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client.timeout = timeout;
+        self
+    }
+
+    /// 读取密钥数据
+    fn fetch(&self) -> Result<Dict, QuantumConfigError> {
+        let mut payload = serde_json::json!({ "SecretId": self.secret_id });
+        if let Some(stage) = &self.version_stage {
+            payload["VersionStage"] = JsonValue::String(stage.clone());
+        }
+        let response = self
+            .client
+            .call("secretsmanager.GetSecretValue", &payload)
+            .map_err(|reason| self.error(reason))?;
+        let secret = response
+            .get("SecretString")
+            .and_then(JsonValue::as_str)
+            .ok_or_else(|| self.error("secret has no SecretString (binary secrets are not supported)".to_string()))?;
+
+        // JSON 对象密钥按字段合并，其余密钥作为单个字符串值
+        let mut secrets = crate::scrub::Secrets::new();
+        let value = match serde_json::from_str::<JsonValue>(secret) {
+            Ok(object @ JsonValue::Object(_)) => {
+                secrets.add_value(&object);
+                Value::serialize(&object).map_err(|e| self.error(e.to_string()))?
+            }
+            _ => {
+                secrets.add(secret);
+                Value::String(Tag::Default, secret.to_string())
+            }
+        };
+        crate::scrub::replace_secrets(&format!("aws:secretsmanager:{}", self.secret_id), secrets);
+
+        let Some(key) = &self.nested_key else {
+            return value.into_dict().ok_or_else(|| {
+                self.error("secret is not a JSON object; use with_nested_key to place it under a key".to_string())
+            });
+        };
+        let mut value = value;
+        for part in key.split('.').rev() {
+            let mut parent = Dict::new();
+            parent.insert(part.to_string(), value);
+            value = Value::from(parent);
+        }
+        Ok(value.into_dict().unwrap_or_default())
+    }
+
+    /// 构造带有密钥名称的错误
+    fn error(&self, reason: String) -> QuantumConfigError {
+        remote::error("aws", format!("secretsmanager:{}", self.secret_id), reason)
+    }
+}
+
+impl Provider for SecretsManagerProvider {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(format!("AWS Secrets Manager ({})", self.secret_id))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let dict = self.fetch().map_err(|e| remote::provider_error(e, self.nested_key.as_deref()))?;
+        let mut data = Map::new();
+        data.insert(Profile::Default, dict);
+        Ok(data)
+    }
+}
+
+/// 常见状态码的说明
+const STATUS_HINTS: &[(u16, &str)] = &[
+    (400, "request rejected, check the resource name and permissions"),
+    (403, "access denied"),
+];
+
+/// 从 `AWS_REGION` 或 `AWS_DEFAULT_REGION` 读取区域，均未设置时为 `us-east-1`
+pub(super) fn region_from_env() -> String {
+    std::env::var("AWS_REGION")
+        .or_else(|_| std::env::var("AWS_DEFAULT_REGION"))
+        .unwrap_or_else(|_| DEFAULT_REGION.to_string())
+}
+
+/// 参与签名的请求内容
+pub(super) struct SignedRequest<'a> {
+    /// 请求方法，例如 "POST"
+    pub method: &'a str,
+    /// 已编码的请求路径
+    pub canonical_uri: &'a str,
+    /// 按名称排序的小写头部，全部参与签名
+    pub headers: &'a [(&'a str, &'a str)],
+    /// 请求正文的 SHA-256 十六进制摘要
+    pub payload_hash: &'a str,
+}
+
+/// 计算 Signature Version 4 的 `Authorization` 头，`x-amz-date` 头给出签名时间
+pub(super) fn sign_request(credentials: &AwsCredentials, region: &str, service: &str, request: &SignedRequest<'_>) -> String {
+    let amz_date = request
+        .headers
+        .iter()
+        .find(|(name, _)| *name == "x-amz-date")
+        .map_or("", |(_, value)| *value);
+    let date = amz_date.get(..8).unwrap_or_default();
+
+    let canonical_headers: String = request
+        .headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+        .collect();
+    let signed_headers = request.headers.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(";");
+    let canonical_request = format!(
+        "{}\n{}\n\n{}\n{}\n{}",
+        request.method, request.canonical_uri, canonical_headers, signed_headers, request.payload_hash
+    );
+
+    let scope = format!("{}/{}/{}/aws4_request", date, region, service);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(sha256(canonical_request.as_bytes()).as_ref())
+    );
+    let key = signing_key(&credentials.secret_access_key, date, region, service);
+    let signature = hex(hmac(&key, string_to_sign.as_bytes()).as_ref());
+    format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        credentials.access_key_id, scope, signed_headers, signature
+    )
+}
+
+/// 派生签名密钥
+fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac(format!("AWS4{}", secret_access_key).as_bytes(), date.as_bytes());
+    let key = hmac(key.as_ref(), region.as_bytes());
+    let key = hmac(key.as_ref(), service.as_bytes());
+    hmac(key.as_ref(), b"aws4_request").as_ref().to_vec()
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> ring::hmac::Tag {
+    ring::hmac::sign(&ring::hmac::Key::new(ring::hmac::HMAC_SHA256, key), data)
+}
+
+pub(super) fn sha256(data: &[u8]) -> ring::digest::Digest {
+    ring::digest::digest(&ring::digest::SHA256, data)
+}
+
+pub(super) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 格式化为签名使用的 `YYYYMMDDTHHMMSSZ`（UTC）
+pub(super) fn format_amz_date(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+    // 公历换算（Howard Hinnant 的 civil_from_days）
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::test_server::serve;
+    use figment::Figment;
+    use serde::Deserialize;
+
+    fn credentials() -> AwsCredentials {
+        AwsCredentials::new("AKIDEXAMPLE", "aws-secret-access-key").with_session_token("aws-session-token")
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        password: String,
+        replicas: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        debug: bool,
+        database: Database,
+    }
+
+    #[test]
+    fn test_ssm_reads_paginated_parameters_under_path() {
+        let first = serde_json::json!({
+            "Parameters": [
+                { "Name": "/myapp/prod/debug", "Type": "String", "Value": "false" },
+                { "Name": "/myapp/prod/database/host", "Type": "String", "Value": "db.internal" },
+            ],
+            "NextToken": "page-2",
+        });
+        let second = serde_json::json!({
+            "Parameters": [
+                { "Name": "/myapp/prod/database/password", "Type": "SecureString", "Value": "12345" },
+                { "Name": "/myapp/prod/database/replicas", "Type": "StringList", "Value": "r1,r2" },
+            ],
+        });
+        let (address, requests) = serve(vec![(200, first.to_string()), (200, second.to_string())]);
+        let provider = SsmParameterProvider::new("eu-west-1", "myapp/prod/")
+            .with_endpoint(address.as_str())
+            .with_credentials(credentials());
+
+        let config: AppConfig = Figment::from(provider).extract().unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                debug: false,
+                database: Database {
+                    host: "db.internal".to_string(),
+                    password: "12345".to_string(),
+                    replicas: vec!["r1".to_string(), "r2".to_string()],
+                },
+            }
+        );
+
+        let first_request = requests.recv().unwrap();
+        let lower = first_request.to_ascii_lowercase();
+        assert!(lower.contains("x-amz-target: amazonssm.getparametersbypath"));
+        assert!(lower.contains("x-amz-security-token: aws-session-token"));
+        assert!(first_request.contains("Credential=AKIDEXAMPLE/"));
+        assert!(first_request.contains("/eu-west-1/ssm/aws4_request, SignedHeaders=content-type;host;x-amz-date;x-amz-security-token;x-amz-target, Signature="));
+        assert!(first_request.contains(r#""Path":"/myapp/prod""#));
+        assert!(first_request.contains(r#""WithDecryption":true"#));
+        assert!(requests.recv().unwrap().contains(r#""NextToken":"page-2""#));
+    }
+
+    #[test]
+    fn test_secrets_manager_reads_json_and_plain_secrets() {
+        let json_secret = serde_json::json!({ "SecretString": r#"{"host":"db","password":"sm-password","replicas":[]}"# });
+        let plain_secret = serde_json::json!({ "SecretString": "plain-secret-value" });
+        let (address, requests) = serve(vec![
+            (200, json_secret.to_string()),
+            (200, plain_secret.to_string()),
+            (200, plain_secret.to_string()),
+        ]);
+
+        let provider = SecretsManagerProvider::new("us-east-2", "myapp/prod/database")
+            .with_endpoint(address.as_str())
+            .with_credentials(credentials())
+            .with_version_stage("AWSCURRENT")
+            .with_nested_key("database");
+        let database: Database = Figment::from(provider).extract_inner("database").unwrap();
+        assert_eq!(database.password, "sm-password");
+        #[cfg(feature = "secret-scrub")]
+        assert!(!crate::scrub::scrub("sm-password").contains("sm-password"));
+        let request = requests.recv().unwrap();
+        assert!(request.to_ascii_lowercase().contains("x-amz-target: secretsmanager.getsecretvalue"));
+        assert!(request.contains(r#""VersionStage":"AWSCURRENT""#));
+
+        let provider = SecretsManagerProvider::new("us-east-2", "api-key")
+            .with_endpoint(address.as_str())
+            .with_credentials(credentials());
+        let nested: String = Figment::from(provider.clone().with_nested_key("api.key")).extract_inner("api.key").unwrap();
+        assert_eq!(nested, "plain-secret-value");
+        let error = provider.fetch().unwrap_err();
+        assert_eq!(error.code(), "aws");
+        assert!(error.to_string().contains("with_nested_key"));
+    }
+
+    #[test]
+    fn test_signing_helpers() {
+        // AWS 文档中的签名密钥示例
+        let key = signing_key("wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY", "20120215", "us-east-1", "iam");
+        assert_eq!(hex(&key), "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d");
+
+        assert_eq!(format_amz_date(UNIX_EPOCH + Duration::from_secs(1_700_000_000)), "20231114T221320Z");
+        assert_eq!(format_amz_date(UNIX_EPOCH + Duration::from_secs(951_782_400)), "20000229T000000Z");
+        assert!(format!("{:?}", credentials()).contains("<redacted>"));
+        assert!(!format!("{:?}", credentials()).contains("aws-secret-access-key"));
+    }
+}
@@ -0,0 +1,390 @@
+//! Azure Key Vault 配置提供器
+//!
+//! [`KeyVaultProvider`] 读取配置好的密钥（`Get Secret`），把每个密钥的值写入指定的配置键，
+//! 读取到的值会登记到 [`crate::scrub`]。
+//!
+//! 未指定认证方式时由 [`AzureAuth::from_env`] 决定：设置了 `AZURE_TENANT_ID`、`AZURE_CLIENT_ID` 与
+//! `AZURE_CLIENT_SECRET` 时使用服务主体，否则使用托管标识（App Service / Functions 的 `IDENTITY_ENDPOINT`，
+//! 或虚拟机与 AKS 的实例元数据服务）。
+//!
+//! ```ignore
+//! use quantum_config::providers::KeyVaultProvider;
+//!
+//! let figment = figment.merge(
+//!     KeyVaultProvider::new("https://myapp-prod.vault.azure.net")
+//!         .with_secret("db-password", "database.password"),
+//! );
+//! ```
+
+use super::key_trie::KeyTrie;
+use super::remote::{self, describe_error};
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map, Tag, Value};
+use figment::{Error, Metadata, Profile, Provider};
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+
+/// 默认请求超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Key Vault REST API 版本
+const API_VERSION: &str = "7.4";
+
+/// 访问令牌对应的资源
+const RESOURCE: &str = "https://vault.azure.net";
+
+/// 未设置 `AZURE_AUTHORITY_HOST` 时使用的 Microsoft Entra ID 地址
+const DEFAULT_AUTHORITY: &str = "https://login.microsoftonline.com";
+
+/// 实例元数据服务（IMDS）的令牌端点
+const IMDS_ENDPOINT: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+
+/// Azure 认证方式
+#[derive(Clone)]
+pub enum AzureAuth {
+    /// 直接使用访问令牌
+    Token(String),
+    /// 服务主体（客户端凭据授权）
+    ClientSecret {
+        /// 租户 ID
+        tenant_id: String,
+        /// 应用（客户端）ID
+        client_id: String,
+        /// 客户端密钥
+        client_secret: String,
+        /// Microsoft Entra ID 地址，例如 "https://login.microsoftonline.com"
+        authority: String,
+    },
+    /// 托管标识
+    ManagedIdentity {
+        /// 用户分配的托管标识的客户端 ID，为 `None` 时使用系统分配的标识
+        client_id: Option<String>,
+        /// 令牌端点
+        endpoint: String,
+        /// App Service 的 `IDENTITY_HEADER`，为 `None` 时按实例元数据服务的协议请求
+        identity_header: Option<String>,
+    },
+}
+
+impl AzureAuth {
+    /// 使用默认地址的服务主体
+    pub fn client_secret<S: Into<String>>(tenant_id: S, client_id: S, client_secret: S) -> Self {
+        AzureAuth::ClientSecret {
+            tenant_id: tenant_id.into(),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            authority: DEFAULT_AUTHORITY.to_string(),
+        }
+    }
+
+    /// 使用托管标识：设置了 `IDENTITY_ENDPOINT` 与 `IDENTITY_HEADER` 时按 App Service 的协议请求，
+    /// 否则使用实例元数据服务；`client_id` 指定用户分配的标识
+    pub fn managed_identity(client_id: Option<String>) -> Self {
+        match (std::env::var("IDENTITY_ENDPOINT"), std::env::var("IDENTITY_HEADER")) {
+            (Ok(endpoint), Ok(header)) => AzureAuth::ManagedIdentity {
+                client_id,
+                endpoint,
+                identity_header: Some(header),
+            },
+            _ => AzureAuth::ManagedIdentity {
+                client_id,
+                endpoint: IMDS_ENDPOINT.to_string(),
+                identity_header: None,
+            },
+        }
+    }
+
+    /// 从环境变量决定认证方式：`AZURE_TENANT_ID`、`AZURE_CLIENT_ID` 与 `AZURE_CLIENT_SECRET` 都已设置时使用服务主体，
+    /// 否则使用托管标识（`AZURE_CLIENT_ID` 指定用户分配的标识）
+    pub fn from_env() -> Self {
+        let client_id = std::env::var("AZURE_CLIENT_ID").ok();
+        match (std::env::var("AZURE_TENANT_ID"), &client_id, std::env::var("AZURE_CLIENT_SECRET")) {
+            (Ok(tenant_id), Some(client_id), Ok(client_secret)) => AzureAuth::ClientSecret {
+                tenant_id,
+                client_id: client_id.clone(),
+                client_secret,
+                authority: std::env::var("AZURE_AUTHORITY_HOST").unwrap_or_else(|_| DEFAULT_AUTHORITY.to_string()),
+            },
+            _ => Self::managed_identity(client_id),
+        }
+    }
+
+    /// 取得访问令牌
+    fn access_token(&self, agent: &ureq::Agent) -> Result<String, String> {
+        let response = match self {
+            AzureAuth::Token(token) => return Ok(token.clone()),
+            AzureAuth::ClientSecret {
+                tenant_id,
+                client_id,
+                client_secret,
+                authority,
+            } => agent
+                .post(format!("{}/{}/oauth2/v2.0/token", authority.trim_end_matches('/'), tenant_id))
+                .send_form([
+                    ("grant_type", "client_credentials"),
+                    ("client_id", client_id.as_str()),
+                    ("client_secret", client_secret.as_str()),
+                    ("scope", &format!("{}/.default", RESOURCE)),
+                ]),
+            AzureAuth::ManagedIdentity {
+                client_id,
+                endpoint,
+                identity_header,
+            } => {
+                let mut request = agent.get(endpoint).query("resource", RESOURCE);
+                request = match identity_header {
+                    Some(header) => request.query("api-version", "2019-08-01").header("X-IDENTITY-HEADER", header),
+                    None => request.query("api-version", "2018-02-01").header("Metadata", "true"),
+                };
+                if let Some(client_id) = client_id {
+                    request = request.query("client_id", client_id);
+                }
+                request.call()
+            }
+        };
+        let body = response
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| format!("failed to obtain an access token: {}", describe_error(e, STATUS_HINTS)))?;
+        let token = serde_json::from_str::<JsonValue>(&body)
+            .ok()
+            .and_then(|json| json.get("access_token")?.as_str().map(str::to_string))
+            .ok_or_else(|| "token response has no access_token".to_string())?;
+        crate::scrub::replace_secret("azure:access-token", token.clone());
+        Ok(token)
+    }
+}
+
+impl std::fmt::Debug for AzureAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出令牌、客户端密钥与 IDENTITY_HEADER
+        match self {
+            AzureAuth::Token(_) => f.debug_tuple("Token").field(&"<redacted>").finish(),
+            AzureAuth::ClientSecret {
+                tenant_id,
+                client_id,
+                authority,
+                ..
+            } => f
+                .debug_struct("ClientSecret")
+                .field("tenant_id", tenant_id)
+                .field("client_id", client_id)
+                .field("client_secret", &"<redacted>")
+                .field("authority", authority)
+                .finish(),
+            AzureAuth::ManagedIdentity {
+                client_id,
+                endpoint,
+                identity_header,
+            } => f
+                .debug_struct("ManagedIdentity")
+                .field("client_id", client_id)
+                .field("endpoint", endpoint)
+                .field("identity_header", &identity_header.as_ref().map(|_| "<redacted>"))
+                .finish(),
+        }
+    }
+}
+
+/// 一个密钥及其值写入的配置键
+#[derive(Debug, Clone)]
+struct SecretMapping {
+    /// 密钥名称
+    name: String,
+    /// 密钥版本，为 `None` 时读取当前版本
+    version: Option<String>,
+    /// 以 `.` 分隔的配置键路径
+    key: String,
+}
+
+/// Azure Key Vault 配置提供器
+#[derive(Debug, Clone)]
+pub struct KeyVaultProvider {
+    /// Key Vault 地址，例如 "https://myapp-prod.vault.azure.net"
+    vault_url: String,
+    /// 读取的密钥
+    secrets: Vec<SecretMapping>,
+    /// 认证方式，为 `None` 时在读取时由 [`AzureAuth::from_env`] 决定
+    auth: Option<AzureAuth>,
+    /// 请求超时
+    timeout: Duration,
+}
+
+impl KeyVaultProvider {
+    /// 创建读取 `vault_url` 中密钥的提供器
+    pub fn new<S: Into<String>>(vault_url: S) -> Self {
+        Self {
+            vault_url: vault_url.into().trim_end_matches('/').to_string(),
+            secrets: Vec::new(),
+            auth: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// 读取密钥 `name` 的当前版本，写入配置键 `key`（以 `.` 分隔）
+    pub fn with_secret<N: Into<String>, K: Into<String>>(mut self, name: N, key: K) -> Self {
+        self.secrets.push(SecretMapping {
+            name: name.into(),
+            version: None,
+            key: key.into(),
+        });
+        self
+    }
+
+    /// 读取密钥 `name` 的指定版本，写入配置键 `key`（以 `.` 分隔）
+    pub fn with_secret_version<N, V, K>(mut self, name: N, version: V, key: K) -> Self
+    where
+        N: Into<String>,
+        V: Into<String>,
+        K: Into<String>,
+    {
+        self.secrets.push(SecretMapping {
+            name: name.into(),
+            version: Some(version.into()),
+            key: key.into(),
+        });
+        self
+    }
+
+    /// 设置认证方式
+    pub fn with_auth(mut self, auth: AzureAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// 设置请求超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 读取全部密钥
+    fn fetch(&self) -> Result<Dict, QuantumConfigError> {
+        let mut trie = KeyTrie::new();
+        if self.secrets.is_empty() {
+            return Ok(trie.into_dict());
+        }
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .into();
+        let auth = self.auth.clone().unwrap_or_else(AzureAuth::from_env);
+        let token = auth.access_token(&agent).map_err(|reason| remote::error("azure", "authentication", reason))?;
+        for secret in &self.secrets {
+            let path = match &secret.version {
+                Some(version) => format!("secrets/{}/{}", secret.name, version),
+                None => format!("secrets/{}", secret.name),
+            };
+            let resource = format!("{}/{}", self.vault_url, path);
+            let error = |reason: String| remote::error("azure", resource.clone(), reason);
+            let body = agent
+                .get(&resource)
+                .query("api-version", API_VERSION)
+                .header("Authorization", format!("Bearer {}", token))
+                .call()
+                .and_then(|mut response| response.body_mut().read_to_string())
+                .map_err(|e| error(describe_error(e, STATUS_HINTS)))?;
+            let value = serde_json::from_str::<JsonValue>(&body)
+                .ok()
+                .and_then(|json| json.get("value")?.as_str().map(str::to_string))
+                .ok_or_else(|| error("response has no secret value".to_string()))?;
+            crate::scrub::replace_secret(&format!("azure:{}", resource), value.clone());
+            trie.insert(secret.key.split('.'), Value::String(Tag::Default, value), Some(resource.clone()))?;
+        }
+        Ok(trie.into_dict())
+    }
+}
+
+impl Provider for KeyVaultProvider {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(format!("Azure Key Vault ({})", self.vault_url))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let dict = self.fetch().map_err(|e| remote::provider_error(e, None))?;
+        let mut data = Map::new();
+        data.insert(Profile::Default, dict);
+        Ok(data)
+    }
+}
+
+/// 常见状态码的说明
+const STATUS_HINTS: &[(u16, &str)] = &[
+    (401, "unauthorized"),
+    (403, "forbidden, check the access policy or role assignment"),
+    (404, "not found, check the secret name and version"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::test_server::serve;
+    use figment::Figment;
+
+    #[test]
+    fn test_client_secret_auth_and_secret_mapping() {
+        let (address, requests) = serve(vec![
+            (200, r#"{"token_type": "Bearer", "access_token": "azure-access-token"}"#.to_string()),
+            (200, r#"{"value": "azure-db-password", "id": "x"}"#.to_string()),
+            (200, r#"{"value": "azure-api-key", "id": "y"}"#.to_string()),
+        ]);
+        let provider = KeyVaultProvider::new(format!("{}/", address))
+            .with_auth(AzureAuth::ClientSecret {
+                tenant_id: "tenant".to_string(),
+                client_id: "client".to_string(),
+                client_secret: "azure-client-secret".to_string(),
+                authority: address.clone(),
+            })
+            .with_secret("db-password", "database.password")
+            .with_secret_version("api-key", "abc123", "api.key");
+
+        let value: JsonValue = Figment::from(provider).extract().unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"database": {"password": "azure-db-password"}, "api": {"key": "azure-api-key"}})
+        );
+
+        let token_request = requests.recv().unwrap();
+        assert!(token_request.starts_with("POST /tenant/oauth2/v2.0/token"));
+        assert!(token_request.contains("grant_type=client_credentials"));
+        assert!(token_request.contains("scope=https%3A%2F%2Fvault.azure.net%2F.default"));
+
+        let secret_request = requests.recv().unwrap();
+        assert!(secret_request.starts_with("GET /secrets/db-password?api-version=7.4"));
+        assert!(secret_request.to_ascii_lowercase().contains("authorization: bearer azure-access-token"));
+        assert!(requests.recv().unwrap().starts_with("GET /secrets/api-key/abc123?api-version=7.4"));
+        #[cfg(feature = "secret-scrub")]
+        assert!(!crate::scrub::scrub("pw=azure-db-password").contains("azure-db-password"));
+    }
+
+    #[test]
+    fn test_managed_identity_token_and_forbidden_secret() {
+        let (address, requests) = serve(vec![
+            (200, r#"{"access_token": "msi-token"}"#.to_string()),
+            (403, "{}".to_string()),
+        ]);
+        let provider = KeyVaultProvider::new(address.as_str())
+            .with_auth(AzureAuth::ManagedIdentity {
+                client_id: Some("user-assigned".to_string()),
+                endpoint: format!("{}/msi/token", address),
+                identity_header: Some("identity-header".to_string()),
+            })
+            .with_secret("db-password", "database.password");
+
+        let error = provider.fetch().unwrap_err();
+        assert_eq!(error.code(), "azure");
+        assert!(error.to_string().contains("secrets/db-password") && error.to_string().contains("403"), "{}", error);
+
+        let token_request = requests.recv().unwrap();
+        assert!(token_request.starts_with("GET /msi/token?"));
+        assert!(token_request.contains("resource=https%3A%2F%2Fvault.azure.net"));
+        assert!(token_request.contains("api-version=2019-08-01") && token_request.contains("client_id=user-assigned"));
+        assert!(token_request.to_ascii_lowercase().contains("x-identity-header: identity-header"));
+    }
+
+    #[test]
+    fn test_debug_redacts_credentials() {
+        let auth = AzureAuth::client_secret("tenant", "client", "azure-client-secret");
+        let debug = format!("{:?}", auth);
+        assert!(debug.contains("tenant") && !debug.contains("azure-client-secret"), "{}", debug);
+    }
+}
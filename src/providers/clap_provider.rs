@@ -3,10 +3,12 @@
 //! 此模块实现了从 clap 解析的命令行参数读取数据的 figment Provider。
 //! 支持将命令行参数转换为配置值，并处理嵌套结构。
 
+use super::key_trie::{ConflictPolicy, KeyTrie};
 use crate::error::QuantumConfigError;
+use crate::logging::trace_debug;
 use clap::ArgMatches;
 use figment::{value::{Map, Value}, Error, Metadata, Profile, Provider};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 /// 命令行参数配置提供者
 ///
@@ -19,6 +21,12 @@ pub struct QuantumConfigClapProvider {
     arg_mapping: HashMap<String, String>,
     /// 分隔符，用于构造嵌套键
     separator: String,
+    /// 读取参数的子命令路径，为空时读取顶层命令的参数
+    subcommand: Vec<String>,
+    /// 是否只读取已映射的参数
+    mapped_only: bool,
+    /// 值按原文作为字符串的配置键，不推断为布尔值或数字
+    raw_string_keys: HashSet<String>,
 }
 
 impl QuantumConfigClapProvider {
@@ -37,6 +45,9 @@ impl QuantumConfigClapProvider {
             matches,
             arg_mapping,
             separator,
+            subcommand: Vec::new(),
+            mapped_only: false,
+            raw_string_keys: HashSet::new(),
         }
     }
 
@@ -75,44 +86,90 @@ impl QuantumConfigClapProvider {
         self
     }
 
+    /// 改为读取指定子命令的参数，例如 `myapp serve --port 9090` 中的 `serve`
+    ///
+    /// 多次调用时逐级进入嵌套子命令（`myapp db migrate` 依次调用 `with_subcommand("db")` 与
+    /// `with_subcommand("migrate")`）。命令行中实际运行的不是该子命令时不提供任何值，
+    /// 因此多命令程序可以为每个子命令绑定不同的配置结构体。
+    pub fn with_subcommand<S: Into<String>>(mut self, name: S) -> Self {
+        self.subcommand.push(name.into());
+        self
+    }
+
+    /// 只读取已映射的参数，忽略其它参数
+    ///
+    /// 用于读取应用自己构建的命令解析出的结果：应用定义的其它参数不会被当作配置键。
+    pub fn with_mapped_args_only(mut self) -> Self {
+        self.mapped_only = true;
+        self
+    }
+
+    /// 指定配置键的值按原文作为字符串，不推断为布尔值或数字
+    ///
+    /// 用于密码等必须逐字保留的值，例如 `--pin 007` 不会变成 `7`。
+    pub fn with_raw_string_key<S: Into<String>>(mut self, config_key: S) -> Self {
+        self.raw_string_keys.insert(config_key.into());
+        self
+    }
+
+    /// 子命令路径对应的参数匹配结果，该子命令未运行时返回 `None`
+    fn active_matches(&self) -> Option<&ArgMatches> {
+        self.subcommand
+            .iter()
+            .try_fold(&self.matches, |matches, name| matches.subcommand_matches(name))
+    }
+
     /// 读取并处理命令行参数
     fn read_clap_args(&self) -> Result<Map<String, Value>, QuantumConfigError> {
-        let mut args_map = Map::new();
+        Ok(self.key_trie()?.into_dict())
+    }
+
+    /// 读取命令行参数并构建配置键前缀树，叶子记录提供该值的参数（例如 `--port`），可用于来源查询
+    pub fn key_trie(&self) -> Result<KeyTrie, QuantumConfigError> {
+        // 后出现的参数覆盖先前的值或嵌套对象
+        let mut trie = KeyTrie::with_policy(ConflictPolicy::Replace);
+        let Some(matches) = self.active_matches() else {
+            return Ok(trie);
+        };
 
         // 遍历所有已解析的参数
-        for arg_id in self.matches.ids() {
+        for arg_id in matches.ids() {
             let arg_name = arg_id.as_str();
+            // 控制加载行为的内置参数不是配置值
+            if crate::cli::CONTROL_ARGS.contains(&arg_name) {
+                continue;
+            }
 
             // 获取配置键名（使用映射或原始名称）
-            let config_key = self.arg_mapping
-                .get(arg_name)
-                .cloned()
-                .unwrap_or_else(|| arg_name.to_string());
+            let config_key = match self.arg_mapping.get(arg_name) {
+                Some(key) => key.clone(),
+                None if self.mapped_only => continue,
+                None => arg_name.to_string(),
+            };
 
             // 根据参数名称判断类型
             let is_boolean_flag = matches!(arg_name, "verbose" | "quiet");
             
             if is_boolean_flag {
-                // 对于已知的布尔标志，直接使用 get_flag
-                if self.matches.get_flag(arg_name) {
+                // 对于已知的布尔标志读取布尔值，应用以其它动作定义的同名参数（例如计数）被忽略
+                if matches.try_get_one::<bool>(arg_name).ok().flatten().copied().unwrap_or(false) {
                     let figment_value = Value::Bool(figment::value::Tag::Default, true);
-                    self.insert_nested_value_direct(&mut args_map, &config_key, figment_value)?;
+                    trie.insert(config_key.split(&self.separator), figment_value, Some(format!("--{}", arg_name)))?;
                 }
             } else {
                 // 对于其他参数，尝试获取字符串值
-                let values = if let Some(values) = self.matches.get_many::<String>(arg_name) {
-                    values.cloned().collect()
-                } else if let Some(value) = self.matches.get_one::<String>(arg_name) {
-                    vec![value.clone()]
-                } else {
-                    continue; // 没有值，跳过
+                let values = match matches.try_get_many::<String>(arg_name) {
+                    Ok(Some(values)) => values.cloned().collect(),
+                    // 没有值，或是应用以其它类型定义的同名参数，跳过
+                    _ => continue,
                 };
                 
-                self.insert_nested_value(&mut args_map, &config_key, values)?;
+                self.insert_arg_values(&mut trie, &config_key, values, arg_name)?;
             }
         }
 
-        Ok(args_map)
+        trace_debug!(matched = trie.len(), "collected command line arguments");
+        Ok(trie)
     }
 
     /// 安全地检查某个参数是否作为布尔标志被设置
@@ -205,99 +262,42 @@ impl QuantumConfigClapProvider {
         }
     }
 
-    /// 将 figment Value 直接插入到嵌套的映射结构中
+    /// 解析参数的字符串值并插入前缀树，多个值作为数组
     ///
     /// # Arguments
-    /// * `map` - 目标映射
-    /// * `key` - 键名（可能包含分隔符）
-    /// * `value` - 要插入的 figment Value
-    fn insert_nested_value_direct(
-        &self,
-        map: &mut Map<String, Value>,
-        key: &str,
-        value: Value,
-    ) -> Result<(), QuantumConfigError> {
-        let parts: Vec<&str> = key.split(&self.separator).collect();
-        
-        if parts.is_empty() {
-            return Err(QuantumConfigError::Internal(
-                "Empty key provided for nested value insertion".to_string()
-            ));
-        }
-
-        if parts.len() == 1 {
-            map.insert(key.to_string(), value);
-            return Ok(());
-        }
-
-        self.try_insert_nested(map, &parts, value)
-    }
-    
-    #[allow(clippy::only_used_in_recursion)]
-    fn try_insert_nested(
-        &self,
-        map: &mut Map<String, Value>,
-        parts: &[&str],
-        value: Value,
-    ) -> Result<(), QuantumConfigError> {
-        if parts.is_empty() {
-            return Ok(());
-        }
-
-        if parts.len() == 1 {
-            map.insert(parts[0].to_string(), value);
-        } else {
-            let key = parts[0];
-            let remaining = &parts[1..];
-
-            match map.get_mut(key) {
-                Some(Value::Dict(_, ref mut nested_map)) => {
-                    self.try_insert_nested(nested_map, remaining, value)?;
-                }
-                Some(_) => {
-                    // 键已存在但不是字典，创建新字典并插入
-                    let mut new_map = Map::new();
-                    self.try_insert_nested(&mut new_map, remaining, value)?;
-                    map.insert(key.to_string(), Value::Dict(figment::value::Tag::Default, new_map));
-                }
-                None => {
-                    // 键不存在，创建新字典
-                    let mut new_map = Map::new();
-                    self.try_insert_nested(&mut new_map, remaining, value)?;
-                    map.insert(key.to_string(), Value::Dict(figment::value::Tag::Default, new_map));
-                }
-            }
-        }
-
-        Ok(())
-    }
-
-    /// 将字符串值列表插入到嵌套的映射结构中
-    ///
-    /// # Arguments
-    /// * `map` - 目标映射
+    /// * `trie` - 目标前缀树
     /// * `key` - 键名（可能包含分隔符）
     /// * `values` - 字符串值列表
-    fn insert_nested_value(
+    /// * `arg_name` - 提供该值的参数名
+    fn insert_arg_values(
         &self,
-        map: &mut Map<String, Value>,
+        trie: &mut KeyTrie,
         key: &str,
         values: Vec<String>,
+        arg_name: &str,
     ) -> Result<(), QuantumConfigError> {
+        let raw = self.raw_string_keys.contains(key);
+        let parse = |value: String| {
+            if raw {
+                Ok(Value::String(figment::value::Tag::Default, value))
+            } else {
+                self.parse_arg_value(value)
+            }
+        };
         let figment_value = if values.len() == 1 {
-            self.parse_arg_value(values[0].clone())?
+            parse(values.into_iter().next().unwrap_or_default())?
         } else {
             // 多个值作为数组处理
             let tag = figment::value::Tag::Default;
             let parsed_values: Result<Vec<Value>, _> = values
                 .into_iter()
-                .map(|v| self.parse_arg_value(v))
+                .map(parse)
                 .collect();
             
             Value::Array(tag, parsed_values?)
         };
 
-        self.insert_nested_value_direct(map, key, figment_value)
+        trie.insert(key.split(&self.separator), figment_value, Some(format!("--{}", arg_name)))
     }
 
     /// 解析参数值，自动推断类型
@@ -337,7 +337,23 @@ impl QuantumConfigClapProvider {
 
 impl Provider for QuantumConfigClapProvider {
     fn metadata(&self) -> Metadata {
-        Metadata::named("Quantum Config Command Line Provider")
+        // 将键路径还原为命令行参数名，便于错误信息指出是哪个参数出错
+        let reverse_mapping: HashMap<String, String> = self
+            .arg_mapping
+            .iter()
+            .map(|(arg, key)| (key.clone(), arg.clone()))
+            .collect();
+        let separator = self.separator.clone();
+        let name = if self.subcommand.is_empty() {
+            "Quantum Config Command Line Provider".to_string()
+        } else {
+            format!("Quantum Config Command Line Provider (subcommand: {})", self.subcommand.join(" "))
+        };
+        Metadata::named(name).interpolater(move |_profile, keys| {
+            let key = keys.join(&separator);
+            let arg = reverse_mapping.get(&key).cloned().unwrap_or(key);
+            format!("--{}", arg)
+        })
     }
 
     fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
@@ -383,16 +399,22 @@ pub fn from_clap_app(
 /// # Returns
 /// 返回配置好的 QuantumConfigClapProvider
 pub fn with_common_mappings(matches: ArgMatches) -> QuantumConfigClapProvider {
-    QuantumConfigClapProvider::from_matches(matches)
-        .map_arg("config", "config_file")
-        .map_arg("config-dir", "config_dir")
-        .map_arg("log-level", "log_level")
-        .map_arg("verbose", "verbose")
-        .map_arg("quiet", "quiet")
-        .map_arg("output", "output.file")
-        .map_arg("format", "output.format")
+    COMMON_MAPPINGS
+        .iter()
+        .fold(QuantumConfigClapProvider::from_matches(matches), |provider, (arg, key)| provider.map_arg(*arg, *key))
 }
 
+/// 内置命令行参数到配置键路径的映射
+pub(crate) const COMMON_MAPPINGS: &[(&str, &str)] = &[
+    ("config", "config_file"),
+    ("config-dir", "config_dir"),
+    ("log-level", "log_level"),
+    ("verbose", "verbose"),
+    ("quiet", "quiet"),
+    ("output", "output.file"),
+    ("format", "output.format"),
+];
+
 // 向后兼容别名
 
 
@@ -487,11 +509,11 @@ mod tests {
         let provider = QuantumConfigClapProvider::from_matches(matches);
 
         let int_val = provider.parse_arg_value("42".to_string()).unwrap();
-        let float_val = provider.parse_arg_value("3.14".to_string()).unwrap();
+        let float_val = provider.parse_arg_value("2.5".to_string()).unwrap();
 
         match (&int_val, &float_val) {
             (Value::Num(_, figment::value::Num::I64(42)), 
-             Value::Num(_, figment::value::Num::F64(f))) if (f - 3.14).abs() < 1e-6 => {},
+             Value::Num(_, figment::value::Num::F64(f))) if (f - 2.5).abs() < 1e-6 => {},
             _ => panic!("Number parsing failed: {:?}, {:?}", int_val, float_val),
         }
     }
@@ -585,4 +607,73 @@ mod tests {
             println!("Found log_level");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_with_subcommand_reads_subcommand_args() {
+        let app = Command::new("test")
+            .arg(Arg::new("verbose").long("verbose").action(ArgAction::SetTrue))
+            .subcommand(Command::new("serve").arg(Arg::new("port").long("port")))
+            .subcommand(
+                Command::new("db").subcommand(Command::new("migrate").arg(Arg::new("steps").long("steps"))),
+            );
+
+        let matches = app.clone().try_get_matches_from(["test", "--verbose", "serve", "--port", "9090"]).unwrap();
+        let serve = QuantumConfigClapProvider::from_matches(matches.clone()).with_subcommand("serve");
+        let data = serve.read_clap_args().unwrap();
+        assert_eq!(data.get("port").and_then(Value::to_i128), Some(9090));
+        assert!(!data.contains_key("verbose"));
+        assert!(serve.metadata().name.contains("subcommand: serve"));
+
+        // 未运行的子命令不提供值，顶层参数仍可单独读取
+        let db = QuantumConfigClapProvider::from_matches(matches.clone()).with_subcommand("db");
+        assert!(db.read_clap_args().unwrap().is_empty());
+        let top = QuantumConfigClapProvider::from_matches(matches).read_clap_args().unwrap();
+        assert!(top.contains_key("verbose"));
+        assert!(!top.contains_key("port"));
+
+        let matches = app.try_get_matches_from(["test", "db", "migrate", "--steps", "3"]).unwrap();
+        let migrate = QuantumConfigClapProvider::from_matches(matches)
+            .with_subcommand("db")
+            .with_subcommand("migrate")
+            .map_arg("steps", "migration.steps");
+        let trie = migrate.key_trie().unwrap();
+        assert_eq!(trie.origin("migration.steps"), Some("--steps"));
+    }
+
+    #[test]
+    fn test_raw_string_keys_keep_original_text() {
+        let app = create_test_app();
+        let matches = app.try_get_matches_from(["test", "--count", "007"]).unwrap();
+
+        let provider = QuantumConfigClapProvider::from_matches(matches.clone()).map_arg("count", "limits.count");
+        assert_eq!(provider.key_trie().unwrap().get("limits.count").and_then(Value::to_i128), Some(7));
+
+        let provider = provider.with_raw_string_key("limits.count");
+        assert_eq!(provider.key_trie().unwrap().get("limits.count").and_then(Value::as_str), Some("007"));
+    }
+
+    #[test]
+    fn test_key_trie_records_arg_origins() {
+        let app = create_test_app();
+        let matches = app.try_get_matches_from(["test", "--count", "3", "--verbose"]).unwrap();
+
+        let provider = QuantumConfigClapProvider::from_matches(matches).map_arg("count", "limits.count");
+        let trie = provider.key_trie().unwrap();
+
+        assert_eq!(trie.origin("limits.count"), Some("--count"));
+        assert_eq!(trie.origin("verbose"), Some("--verbose"));
+        assert_eq!(trie.get("verbose"), Some(&Value::Bool(figment::value::Tag::Default, true)));
+    }
+
+    #[test]
+    fn test_metadata_interpolates_arg_name() {
+        let app = create_test_app();
+        let matches = app.try_get_matches_from(["test", "--count", "many"]).unwrap();
+
+        let provider = QuantumConfigClapProvider::from_matches(matches).map_arg("count", "limits.count");
+        let metadata = provider.metadata();
+
+        assert_eq!(metadata.interpolate(&Profile::Default, &["limits", "count"]), "--count");
+        assert_eq!(metadata.interpolate(&Profile::Default, &["verbose"]), "--verbose");
+    }
+}
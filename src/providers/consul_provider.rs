@@ -0,0 +1,257 @@
+//! Consul KV 配置提供器
+//!
+//! 通过 Consul HTTP API（`/v1/kv/{prefix}?recurse`）读取某个键前缀下的 KV 树，
+//! 去除前缀后按 `/` 拆分为嵌套键，例如前缀 `myapp/prod/` 下的 `myapp/prod/database/host`
+//! 对应 `database.host`。值按环境变量的规则解析为布尔值、数字或字符串；
+//! 以 `/` 结尾的"目录"键没有值，直接跳过。
+//!
+//! 同一个配置结构体可以在生产环境从 Consul 读取，在本地从文件读取：
+//!
+//! ```ignore
+//! use quantum_config::providers::ConsulProvider;
+//!
+//! let consul = ConsulProvider::from_env("myapp/prod/").with_datacenter("dc1");
+//! let figment = figment.merge(consul);
+//! ```
+
+use super::env_provider::parse_scalar_value;
+use super::key_trie::KeyTrie;
+use super::remote::{self, describe_error};
+use crate::error::QuantumConfigError;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use figment::value::{Dict, Map};
+use figment::{Error, Metadata, Profile, Provider};
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+
+/// 默认请求超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 未设置 `CONSUL_HTTP_ADDR` 时使用的默认地址
+const DEFAULT_ADDRESS: &str = "http://127.0.0.1:8500";
+
+/// 默认的键层级分隔符
+const DEFAULT_SEPARATOR: &str = "/";
+
+/// Consul KV 配置提供器
+#[derive(Clone)]
+pub struct ConsulProvider {
+    /// Consul 地址，例如 "http://127.0.0.1:8500"
+    address: String,
+    /// 读取的键前缀，例如 "myapp/prod/"
+    prefix: String,
+    /// 键层级分隔符
+    separator: String,
+    /// 数据中心，为 `None` 时使用被访问的 agent 所在的数据中心
+    datacenter: Option<String>,
+    /// ACL Token
+    token: Option<String>,
+    /// 请求超时
+    timeout: Duration,
+}
+
+impl std::fmt::Debug for ConsulProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出 ACL Token
+        f.debug_struct("ConsulProvider")
+            .field("address", &self.address)
+            .field("prefix", &self.prefix)
+            .field("separator", &self.separator)
+            .field("datacenter", &self.datacenter)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl ConsulProvider {
+    /// 创建读取 `prefix` 下 KV 树的提供器
+    pub fn new<S: Into<String>, P: Into<String>>(address: S, prefix: P) -> Self {
+        Self {
+            address: address.into().trim_end_matches('/').to_string(),
+            prefix: prefix.into().trim_start_matches('/').to_string(),
+            separator: DEFAULT_SEPARATOR.to_string(),
+            datacenter: None,
+            token: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// 使用 `CONSUL_HTTP_ADDR` 与 `CONSUL_HTTP_TOKEN` 创建提供器
+    pub fn from_env<P: Into<String>>(prefix: P) -> Self {
+        let address = std::env::var("CONSUL_HTTP_ADDR").unwrap_or_else(|_| DEFAULT_ADDRESS.to_string());
+        // CONSUL_HTTP_ADDR 允许省略协议
+        let address = if address.contains("://") { address } else { format!("http://{}", address) };
+        let mut provider = Self::new(address, prefix);
+        provider.token = std::env::var("CONSUL_HTTP_TOKEN").ok();
+        provider
+    }
+
+    /// 设置数据中心
+    pub fn with_datacenter<S: Into<String>>(mut self, datacenter: S) -> Self {
+        self.datacenter = Some(datacenter.into());
+        self
+    }
+
+    /// 设置 ACL Token
+    pub fn with_token<S: Into<String>>(mut self, token: S) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// 设置键层级分隔符
+    pub fn with_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// 设置请求超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 读取前缀下的 KV 树并构建键前缀树，叶子来源为 Consul 中的完整键名
+    pub fn key_trie(&self) -> Result<KeyTrie, QuantumConfigError> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .into();
+
+        let url = format!("{}/v1/kv/{}", self.address, self.prefix);
+        let mut request = agent.get(&url).query("recurse", "true");
+        if let Some(datacenter) = &self.datacenter {
+            request = request.query("dc", datacenter);
+        }
+        if let Some(token) = &self.token {
+            request = request.header("X-Consul-Token", token);
+        }
+        let body = match request.call().and_then(|mut response| response.body_mut().read_to_string()) {
+            Ok(body) => body,
+            // 前缀下没有任何键时 Consul 返回 404
+            Err(ureq::Error::StatusCode(404)) => return Ok(KeyTrie::new()),
+            Err(e) => return Err(self.error(describe_error(e, STATUS_HINTS))),
+        };
+
+        let entries: Vec<JsonValue> =
+            serde_json::from_str(&body).map_err(|e| self.error(format!("invalid response: {}", e)))?;
+        let mut trie = KeyTrie::new();
+        for entry in &entries {
+            let Some(key) = entry.get("Key").and_then(JsonValue::as_str) else {
+                continue;
+            };
+            // 目录键的值为 null
+            let Some(encoded) = entry.get("Value").and_then(JsonValue::as_str) else {
+                continue;
+            };
+            let Some(relative) = key.strip_prefix(self.prefix.as_str()) else {
+                continue;
+            };
+            let segments: Vec<&str> = relative.split(self.separator.as_str()).filter(|s| !s.is_empty()).collect();
+            if segments.is_empty() {
+                continue;
+            }
+            let bytes = BASE64
+                .decode(encoded)
+                .map_err(|e| self.error(format!("invalid base64 value for '{}': {}", key, e)))?;
+            let value = String::from_utf8(bytes)
+                .map_err(|_| self.error(format!("value for '{}' is not valid UTF-8", key)))?;
+            trie.insert(segments, parse_scalar_value(value), Some(key.to_string()))?;
+        }
+        Ok(trie)
+    }
+
+    /// 构造带有键前缀的错误
+    fn error(&self, reason: String) -> QuantumConfigError {
+        remote::error("consul", self.prefix.clone(), reason)
+    }
+}
+
+/// 常见状态码的说明
+const STATUS_HINTS: &[(u16, &str)] = &[(403, "permission denied")];
+
+impl Provider for ConsulProvider {
+    fn metadata(&self) -> Metadata {
+        // 错误信息中的键路径还原为 Consul 中的完整键名
+        let prefix = self.prefix.clone();
+        let separator = self.separator.clone();
+        Metadata::named(format!("Consul ({}/{})", self.address, self.prefix))
+            .interpolater(move |_profile, keys| format!("{}{}", prefix, keys.join(&separator)))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let trie = self.key_trie().map_err(|e| remote::provider_error(e, None))?;
+        let mut data = Map::new();
+        data.insert(Profile::Default, trie.into_dict());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::test_server::serve;
+    use figment::Figment;
+    use serde::Deserialize;
+
+    fn kv_response(entries: &[(&str, Option<&str>)]) -> String {
+        let entries: Vec<JsonValue> = entries
+            .iter()
+            .map(|(key, value)| serde_json::json!({ "Key": key, "Value": value.map(|v| BASE64.encode(v)), "Flags": 0 }))
+            .collect();
+        JsonValue::Array(entries).to_string()
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        name: String,
+        database: Database,
+    }
+
+    #[test]
+    fn test_reads_kv_tree_with_datacenter_and_token() {
+        let body = kv_response(&[
+            ("myapp/prod/", None),
+            ("myapp/prod/name", Some("consul-app")),
+            ("myapp/prod/database/", None),
+            ("myapp/prod/database/host", Some("db.service.consul")),
+            ("myapp/prod/database/port", Some("5432")),
+        ]);
+        let (address, requests) = serve(vec![(200, body)]);
+        let provider = ConsulProvider::new(address.as_str(), "myapp/prod/")
+            .with_datacenter("dc2")
+            .with_token("consul-acl-token");
+
+        let config: AppConfig = Figment::from(provider.clone()).extract().unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                name: "consul-app".to_string(),
+                database: Database { host: "db.service.consul".to_string(), port: 5432 },
+            }
+        );
+        assert!(!format!("{:?}", provider).contains("consul-acl-token"));
+
+        let request = requests.recv().unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("get /v1/kv/myapp/prod/?recurse=true&dc=dc2 "));
+        assert!(request.contains("x-consul-token: consul-acl-token"));
+    }
+
+    #[test]
+    fn test_missing_prefix_is_empty_and_errors_are_reported() {
+        let (address, _requests) = serve(vec![(404, String::new()), (403, String::new())]);
+        let provider = ConsulProvider::new(address.as_str(), "/myapp/missing/");
+        assert!(provider.key_trie().unwrap().is_empty());
+
+        let error = provider.key_trie().unwrap_err();
+        assert_eq!(error.code(), "consul");
+        assert!(error.to_string().contains("'myapp/missing/': permission denied"));
+    }
+}
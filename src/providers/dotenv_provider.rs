@@ -0,0 +1,255 @@
+//! `.env` 文件提供器
+//!
+//! 读取 `.env` 文件中的 `KEY=VALUE` 行，并按与 [`QuantumConfigEnvProvider`] 相同的
+//! 前缀与分隔符规则合并，例如 `MYAPP_DATABASE__HOST=db` 对应 `database.host`。
+//!
+//! 支持的语法：
+//! - 空行与 `#` 开头的注释行，以及未加引号的值后以空白分隔的 `#` 行尾注释
+//! - 可选的 `export ` 前缀
+//! - 单引号值按字面量处理；双引号值支持 `\n`、`\t`、`\r`、`\"`、`\\`、`\$` 转义
+//! - 引号内的值可以跨越多行
+
+use super::env_provider::{EnvSnapshot, QuantumConfigEnvProvider};
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map};
+use figment::{Error, Metadata, Profile, Provider, Source};
+use std::path::PathBuf;
+
+/// `.env` 文件提供器
+#[derive(Debug, Clone)]
+pub struct DotenvProvider {
+    /// `.env` 文件路径
+    path: PathBuf,
+    /// 决定前缀、分隔符与校验规则的环境变量提供器
+    env: QuantumConfigEnvProvider,
+}
+
+impl DotenvProvider {
+    /// 创建提供器，使用 [`QuantumConfigEnvProvider::with_prefix`] 的默认规则
+    pub fn new<P: Into<PathBuf>, S: Into<String>>(path: P, prefix: S) -> Self {
+        Self {
+            path: path.into(),
+            env: QuantumConfigEnvProvider::with_prefix(prefix),
+        }
+    }
+
+    /// 使用给定环境变量提供器的前缀、分隔符与校验规则
+    pub fn with_env_provider(mut self, env: QuantumConfigEnvProvider) -> Self {
+        self.env = env;
+        self
+    }
+
+    /// 读取并解析 `.env` 文件
+    fn read_vars(&self) -> Result<Vec<(String, String)>, QuantumConfigError> {
+        let content =
+            std::fs::read_to_string(&self.path).map_err(|e| QuantumConfigError::FileReadError {
+                path: self.path.display().to_string(),
+                source: e,
+            })?;
+        parse_dotenv(&content).map_err(|source_error| QuantumConfigError::FileParse {
+            format_name: "dotenv".to_string(),
+            path: self.path.clone(),
+            source_error,
+        })
+    }
+}
+
+/// 解析 `.env` 内容，按出现顺序返回变量，重复的键以后出现的为准
+fn parse_dotenv(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+    let mut lines = content.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line_no = index + 1;
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+        let (key, rest) =
+            line.split_once('=').ok_or_else(|| format!("line {}: expected KEY=VALUE", line_no))?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+            return Err(format!("line {}: invalid key '{}'", line_no, key));
+        }
+
+        let value = match rest.trim_start().chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                // 引号内的值可以跨行，读取后续行直到找到闭合引号
+                let mut raw = rest.trim_start()[1..].to_string();
+                let end = loop {
+                    if let Some(end) = find_closing_quote(&raw, quote) {
+                        break end;
+                    }
+                    match lines.next() {
+                        Some((_, next)) => {
+                            raw.push('\n');
+                            raw.push_str(next);
+                        }
+                        None => return Err(format!("line {}: unterminated quoted value", line_no)),
+                    }
+                };
+                let trailing = raw[end + 1..].trim();
+                if !trailing.is_empty() && !trailing.starts_with('#') {
+                    return Err(format!("line {}: unexpected characters after quoted value", line_no));
+                }
+                raw.truncate(end);
+                if quote == '"' {
+                    unescape_double_quoted(&raw)
+                } else {
+                    raw
+                }
+            }
+            _ => strip_inline_comment(rest).trim().to_string(),
+        };
+        vars.push((key.to_string(), value));
+    }
+    Ok(vars)
+}
+
+/// 查找闭合引号的位置，双引号值中跳过被转义的字符
+fn find_closing_quote(raw: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in raw.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote == '"' {
+            escaped = true;
+        } else if c == quote {
+            return Some(i);
+        }
+    }
+    None
+}
+
+/// 处理双引号值中的转义序列，无法识别的转义原样保留
+fn unescape_double_quoted(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some(c @ ('"' | '\\' | '$')) => value.push(c),
+            Some(other) => {
+                value.push('\\');
+                value.push(other);
+            }
+            None => value.push('\\'),
+        }
+    }
+    value
+}
+
+/// 去除未加引号的值中以空白分隔的行尾注释
+fn strip_inline_comment(value: &str) -> &str {
+    let mut previous = '=';
+    for (i, c) in value.char_indices() {
+        if c == '#' && previous.is_whitespace() {
+            return &value[..i];
+        }
+        previous = c;
+    }
+    value
+}
+
+impl Provider for DotenvProvider {
+    fn metadata(&self) -> Metadata {
+        // 沿用环境变量提供器的插值规则，错误信息指出 .env 中的变量名
+        let mut metadata = self.env.metadata();
+        metadata.name = format!("Quantum Config Dotenv Provider ({})", self.path.display()).into();
+        metadata.source = Some(Source::File(self.path.clone()));
+        metadata
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let vars = self
+            .read_vars()
+            .map_err(|e| Error::from(format!("Dotenv provider error: {}", e)))?;
+        self.env.clone().with_snapshot(EnvSnapshot::from_vars(vars)).data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::Figment;
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_quoting_comments_and_multiline_values() {
+        let content = r#"
+# comment line
+export APP_HOST = example.com   # trailing comment
+APP_COLOR=#fff
+APP_COMMENTED= # only a comment
+APP_URL=http://host/#anchor
+APP_SINGLE='literal \n $HOME # not a comment'
+APP_DOUBLE="tab\tquote\"dollar\$"
+APP_CERT="-----BEGIN-----
+line two
+-----END-----"
+APP_EMPTY=
+"#;
+        let vars = parse_dotenv(content).unwrap();
+        let get = |key: &str| vars.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+
+        assert_eq!(get("APP_HOST"), Some("example.com"));
+        assert_eq!(get("APP_COLOR"), Some("#fff"));
+        assert_eq!(get("APP_COMMENTED"), Some(""));
+        assert_eq!(get("APP_URL"), Some("http://host/#anchor"));
+        assert_eq!(get("APP_SINGLE"), Some("literal \\n $HOME # not a comment"));
+        assert_eq!(get("APP_DOUBLE"), Some("tab\tquote\"dollar$"));
+        assert_eq!(get("APP_CERT"), Some("-----BEGIN-----\nline two\n-----END-----"));
+        assert_eq!(get("APP_EMPTY"), Some(""));
+    }
+
+    #[test]
+    fn test_parse_reports_line_numbers() {
+        assert!(parse_dotenv("A=1\nnot a pair\n").unwrap_err().contains("line 2"));
+        assert!(parse_dotenv("A=\"open\nstill open\n").unwrap_err().contains("unterminated"));
+        assert!(parse_dotenv("A='x' y\n").unwrap_err().contains("line 1"));
+        assert!(parse_dotenv("BAD KEY=1\n").unwrap_err().contains("invalid key"));
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        name: String,
+        database: Database,
+    }
+
+    #[test]
+    fn test_provider_applies_prefix_and_separator_rules() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join(".env");
+        fs::write(
+            &path,
+            "DOTENV_NAME=\"my app\"\nDOTENV_DATABASE__HOST=db\nDOTENV_DATABASE__PORT=5432\nOTHER_NAME=ignored\n",
+        )
+        .unwrap();
+
+        let config: AppConfig = Figment::from(DotenvProvider::new(&path, "DOTENV_")).extract().unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                name: "my app".to_string(),
+                database: Database { host: "db".to_string(), port: 5432 },
+            }
+        );
+
+        let missing = Figment::from(DotenvProvider::new(dir.path().join("missing.env"), "DOTENV_"));
+        assert!(missing.extract::<AppConfig>().is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! 行内加密值解密提供器
+//!
+//! 包装任意 figment Provider，在加载时把 `ENC[AES256_GCM,data:...,iv:...]` 形式的字符串值解密为明文，
+//! 只有敏感键需要加密，文件的其余部分保持可读、可审查：
+//!
+//! ```toml
+//! [database]
+//! url = "postgres://db/app"
+//! password = "ENC[AES256_GCM,data:3q2+7w...,iv:AAECAwQFBgcICQoL]"
+//! ```
+//!
+//! 加密时使用的键路径（此处为 `database.password`）参与认证，参见 [`super::encryption::encrypt_value`]。
+//! 带有 `tag:` 或 `type:` 字段的 `ENC[...]` 值是 SOPS 加密值，保持原样。
+//!
+//! 主密钥由 [`KeyChain`] 查找，只在遇到第一个加密值时查找一次；没有加密值的来源不需要密钥。
+//! 解密出的明文登记到 [`crate::scrub`] 以免出现在日志或 panic 信息中。
+
+use super::encryption::{decrypt_value, is_encrypted_value, EncryptionKey, KeyChain};
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map, Value};
+use figment::{Error, Metadata, Profile, Provider};
+
+/// 行内加密值解密提供器
+#[derive(Debug, Clone)]
+pub struct EncryptedValuesProvider<P> {
+    /// 被包装的提供器
+    inner: P,
+    /// 主密钥查找链
+    keys: KeyChain,
+}
+
+impl<P: Provider> EncryptedValuesProvider<P> {
+    /// 包装提供器，使用 [`KeyChain::standard`] 查找主密钥
+    pub fn new(inner: P) -> Self {
+        Self::with_key_chain(inner, KeyChain::standard())
+    }
+
+    /// 包装提供器，使用给定的主密钥查找链
+    pub fn with_key_chain(inner: P, keys: KeyChain) -> Self {
+        Self { inner, keys }
+    }
+
+    /// 递归解密值中的加密字符串
+    fn resolve_value(
+        &self,
+        path: &str,
+        value: Value,
+        key: &mut Option<EncryptionKey>,
+    ) -> Result<Value, QuantumConfigError> {
+        match value {
+            Value::String(tag, s) if is_encrypted_value(&s) => {
+                let key = match key {
+                    Some(key) => key,
+                    None => key.insert(self.keys.resolve()?),
+                };
+                let plaintext = decrypt_value(&s, key, path)?;
+                crate::scrub::replace_secret(&format!("encrypted:{}", path), plaintext.clone());
+                Ok(Value::String(tag, plaintext))
+            }
+            Value::Dict(tag, dict) => Ok(Value::Dict(tag, self.resolve_dict(path, dict, key)?)),
+            Value::Array(tag, items) => {
+                let items = items
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, item)| self.resolve_value(&format!("{}[{}]", path, i), item, key))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::Array(tag, items))
+            }
+            other => Ok(other),
+        }
+    }
+
+    /// 解密字典中的加密字符串
+    fn resolve_dict(
+        &self,
+        prefix: &str,
+        dict: Dict,
+        key: &mut Option<EncryptionKey>,
+    ) -> Result<Dict, QuantumConfigError> {
+        dict.into_iter()
+            .map(|(name, value)| {
+                let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+                Ok((name, self.resolve_value(&path, value, key)?))
+            })
+            .collect()
+    }
+}
+
+impl<P: Provider> Provider for EncryptedValuesProvider<P> {
+    fn metadata(&self) -> Metadata {
+        // 保留被包装提供器的来源信息，错误信息仍指向原始来源
+        self.inner.metadata()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut key = None;
+        let mut resolved = Map::new();
+        for (profile, dict) in self.inner.data()? {
+            let dict = self
+                .resolve_dict("", dict, &mut key)
+                .map_err(|e| Error::from(format!("Encrypted value error: {}", e)))?;
+            resolved.insert(profile, dict);
+        }
+        Ok(resolved)
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.inner.profile()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::encryption::encrypt_value;
+    use super::*;
+    use figment::providers::Serialized;
+    use figment::Figment;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_decrypts_inline_values_with_key_chain() {
+        let key = EncryptionKey::generate().unwrap();
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let counter = lookups.clone();
+        let keys = KeyChain::new().with_callback(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(Some(key.clone()))
+        });
+        let key = keys.resolve().unwrap();
+        lookups.store(0, Ordering::SeqCst);
+
+        let inner = Serialized::defaults(json!({
+            "database": {
+                "url": "postgres://db/app",
+                "password": encrypt_value("inline-pw", &key, "database.password").unwrap(),
+            },
+            "tokens": [encrypt_value("inline-token", &key, "tokens[0]").unwrap()],
+        }));
+        let value: serde_json::Value =
+            Figment::from(EncryptedValuesProvider::with_key_chain(inner, keys.clone())).extract().unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "database": {"url": "postgres://db/app", "password": "inline-pw"},
+                "tokens": ["inline-token"],
+            })
+        );
+        assert_eq!(lookups.load(Ordering::SeqCst), 1);
+        #[cfg(feature = "secret-scrub")]
+        assert!(!crate::scrub::scrub("pw=inline-pw").contains("inline-pw"));
+
+        // 没有加密值时不查找密钥
+        let plain = Serialized::defaults(json!({ "name": "svc" }));
+        Figment::from(EncryptedValuesProvider::with_key_chain(plain, KeyChain::new()))
+            .extract::<serde_json::Value>()
+            .unwrap();
+
+        let tampered = Serialized::defaults(json!({ "password": "ENC[AES256_GCM,data:AAAA,iv:AAAAAAAAAAAAAAAA]" }));
+        let error = Figment::from(EncryptedValuesProvider::with_key_chain(tampered, keys))
+            .extract::<serde_json::Value>()
+            .unwrap_err();
+        assert!(error.to_string().contains("password"), "{}", error);
+    }
+
+    #[test]
+    fn test_decrypts_documented_syntax_and_leaves_sops_values() {
+        use figment::providers::{Format, Toml};
+
+        let toml = r#"
+            sops_token = "ENC[AES256_GCM,data:abc=,iv:abc=,tag:abc=,type:str]"
+
+            [database]
+            url = "postgres://db/app"
+            password = "ENC[AES256_GCM,data:ce+FGXNs9DpE0Zf5lk81UgViM2PbZGGY3bQMszU=,iv:AAECAwQFBgcICQoL]"
+        "#;
+        let keys = KeyChain::new().with_key(EncryptionKey::from_bytes([7; 32]));
+        let value: serde_json::Value =
+            Figment::from(EncryptedValuesProvider::with_key_chain(Toml::string(toml), keys)).extract().unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "sops_token": "ENC[AES256_GCM,data:abc=,iv:abc=,tag:abc=,type:str]",
+                "database": {"url": "postgres://db/app", "password": "inline-s3cret"},
+            })
+        );
+    }
+}
@@ -0,0 +1,458 @@
+//! 加密配置文件
+//!
+//! 启用 `encryption` 特性后，文件提供器透明解密以 `.enc` 结尾的配置文件（例如 `config.toml.enc`，
+//! 格式由去掉 `.enc` 后的扩展名决定），或通过 [`crate::providers::QuantumConfigFileProviderGeneric::with_encrypted`]
+//! 声明为加密的文件，使密钥可以加密后提交到配置仓库：
+//!
+//! ```ignore
+//! use quantum_config::providers::encryption::{encrypt, EncryptionKey};
+//!
+//! let key = EncryptionKey::generate()?;
+//! println!("QUANTUM_CONFIG_KEY={}", key.to_base64());
+//! std::fs::write("config.toml.enc", encrypt(b"password = \"s3cret\"\n", &key)?)?;
+//! ```
+//!
+//! 文件内容为魔数 `QCENC1`、12 字节随机 nonce 与 AES-256-GCM 密文（含认证标签），整体按 base64 编码为文本；
+//! 也接受未编码的二进制内容。密钥为 32 字节，未在提供器上显式设置时按以下顺序查找：
+//! 1. 环境变量 [`KEY_ENV`]：base64 或 64 位十六进制文本
+//! 2. 环境变量 [`KEY_FILE_ENV`] 指向的密钥文件，内容格式同上
+//!
+//! 目前只支持 AES-256-GCM，age 格式的文件需要先用 `age` 命令解密。
+//!
+//! 只有少数键需要保密时，可以只加密这些值而不是整个文件。[`encrypt_value`] 生成的行内加密值形如
+//! `password = "ENC[AES256_GCM,data:...,iv:...]"`，由
+//! [`EncryptedValuesProvider`](super::encrypted_values::EncryptedValuesProvider) 在加载时解密，
+//! 也接受 `QCENC[AES256_GCM,...]` 作为别名。
+//! 值所在的键路径（例如 `database.password`）作为附加认证数据参与加密，密文被移到其他键下时无法解密。
+//! SOPS 加密值同样以 `ENC[AES256_GCM,` 开头，但总是带有 `tag:` 与 `type:` 字段；
+//! 带有这两个字段之一的 `ENC[...]` 值视为 SOPS 加密值，保持原样交给 SOPS 处理。
+//! 主密钥由 [`KeyChain`] 按顺序从环境变量、密钥文件或回调（例如向 KMS 请求数据密钥）中查找：
+//!
+//! ```ignore
+//! let keys = KeyChain::new()
+//!     .with_env("APP_MASTER_KEY")
+//!     .with_key_file("/run/secrets/master.key")
+//!     .with_callback(|| kms.decrypt_data_key().map(Some));
+//! ```
+
+use crate::error::QuantumConfigError;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 保存密钥的环境变量
+pub const KEY_ENV: &str = "QUANTUM_CONFIG_KEY";
+
+/// 保存密钥文件路径的环境变量
+pub const KEY_FILE_ENV: &str = "QUANTUM_CONFIG_KEY_FILE";
+
+/// 加密文件的魔数与格式版本
+const MAGIC: &[u8] = b"QCENC1";
+
+/// 密钥长度（字节）
+const KEY_LEN: usize = 32;
+
+/// 行内加密值的前缀
+const VALUE_PREFIX: &str = "ENC[AES256_GCM,";
+
+/// 行内加密值前缀的别名，带有任何字段都不会被当作 SOPS 加密值
+const VALUE_ALIAS_PREFIX: &str = "QCENC[AES256_GCM,";
+
+/// 行内加密值的后缀
+const VALUE_SUFFIX: &str = "]";
+
+/// AES-256-GCM 密钥，`Debug` 输出不包含密钥内容
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; KEY_LEN]);
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(<redacted>)")
+    }
+}
+
+impl EncryptionKey {
+    /// 由原始字节创建密钥
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    /// 生成随机密钥
+    pub fn generate() -> Result<Self, QuantumConfigError> {
+        let mut bytes = [0u8; KEY_LEN];
+        SystemRandom::new()
+            .fill(&mut bytes)
+            .map_err(|_| QuantumConfigError::Internal("Failed to generate encryption key".to_string()))?;
+        Ok(Self(bytes))
+    }
+
+    /// 解析 base64 或 64 位十六进制文本，忽略首尾空白
+    pub fn parse(text: &str) -> Result<Self, QuantumConfigError> {
+        let text = text.trim();
+        let bytes = if text.len() == KEY_LEN * 2 && text.bytes().all(|b| b.is_ascii_hexdigit()) {
+            (0..KEY_LEN)
+                .map(|i| u8::from_str_radix(&text[i * 2..i * 2 + 2], 16))
+                .collect::<Result<Vec<u8>, _>>()
+                .ok()
+        } else {
+            BASE64.decode(text).ok()
+        };
+        bytes
+            .and_then(|bytes| <[u8; KEY_LEN]>::try_from(bytes).ok())
+            .map(Self)
+            .ok_or_else(|| QuantumConfigError::InvalidValue {
+                key_path: KEY_ENV.to_string(),
+                message: format!("expected {} bytes encoded as base64 or hex", KEY_LEN),
+            })
+    }
+
+    /// 从密钥文件读取
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, QuantumConfigError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|source| QuantumConfigError::Io {
+            source,
+            path: path.to_path_buf(),
+        })?;
+        Self::parse(&content)
+    }
+
+    /// 从环境变量 [`KEY_ENV`] 或 [`KEY_FILE_ENV`] 读取
+    pub fn from_env() -> Result<Self, QuantumConfigError> {
+        if let Some(key) = std::env::var(KEY_ENV).ok().filter(|key| !key.is_empty()) {
+            return Self::parse(&key);
+        }
+        if let Some(path) = std::env::var_os(KEY_FILE_ENV).filter(|path| !path.is_empty()) {
+            return Self::from_file(path);
+        }
+        Err(QuantumConfigError::Internal(format!(
+            "No encryption key configured: set {} or {}",
+            KEY_ENV, KEY_FILE_ENV
+        )))
+    }
+
+    /// base64 编码的密钥文本，可直接写入 [`KEY_ENV`]
+    pub fn to_base64(&self) -> String {
+        BASE64.encode(self.0)
+    }
+
+    fn aead_key(&self) -> LessSafeKey {
+        // 长度固定为 32 字节，构造不会失败
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, &self.0).expect("AES-256-GCM key length"))
+    }
+}
+
+/// 加密明文，返回 base64 编码的文件内容
+pub fn encrypt(plaintext: &[u8], key: &EncryptionKey) -> Result<String, QuantumConfigError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| QuantumConfigError::Internal("Failed to generate nonce".to_string()))?;
+    let mut sealed = plaintext.to_vec();
+    key.aead_key()
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(MAGIC), &mut sealed)
+        .map_err(|_| QuantumConfigError::Internal("Failed to encrypt configuration".to_string()))?;
+
+    let mut content = Vec::with_capacity(MAGIC.len() + NONCE_LEN + sealed.len());
+    content.extend_from_slice(MAGIC);
+    content.extend_from_slice(&nonce);
+    content.extend_from_slice(&sealed);
+    Ok(BASE64.encode(content) + "\n")
+}
+
+/// 解密文件内容，`path` 只用于错误信息
+pub fn decrypt(content: &[u8], key: &EncryptionKey, path: &Path) -> Result<Vec<u8>, QuantumConfigError> {
+    let failed = |reason: &str| QuantumConfigError::Decryption {
+        path: path.to_path_buf(),
+        reason: reason.to_string(),
+    };
+    let decoded;
+    let content = if content.starts_with(MAGIC) {
+        content
+    } else {
+        let text = std::str::from_utf8(content).map_err(|_| failed("not an encrypted configuration file"))?;
+        decoded = BASE64
+            .decode(text.trim())
+            .map_err(|_| failed("not an encrypted configuration file"))?;
+        decoded.as_slice()
+    };
+    let rest = content
+        .strip_prefix(MAGIC)
+        .ok_or_else(|| failed("not an encrypted configuration file"))?;
+    if rest.len() < NONCE_LEN + AES_256_GCM.tag_len() {
+        return Err(failed("file is truncated"));
+    }
+    let (nonce, sealed) = rest.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce).map_err(|_| failed("invalid nonce"))?;
+    let mut sealed = sealed.to_vec();
+    let plaintext = key
+        .aead_key()
+        .open_in_place(nonce, Aad::from(MAGIC), &mut sealed)
+        .map_err(|_| failed("wrong key or corrupted file"))?;
+    Ok(plaintext.to_vec())
+}
+
+/// 值是否为 `ENC[AES256_GCM,...]` 或 `QCENC[AES256_GCM,...]` 形式的行内加密值，SOPS 加密值除外
+pub fn is_encrypted_value(value: &str) -> bool {
+    inline_fields(value).is_some()
+}
+
+/// 行内加密值中前缀与后缀之间的字段，不是行内加密值时返回 `None`
+fn inline_fields(value: &str) -> Option<&str> {
+    let strip = |prefix: &str| value.strip_prefix(prefix)?.strip_suffix(VALUE_SUFFIX);
+    if let Some(fields) = strip(VALUE_ALIAS_PREFIX) {
+        return Some(fields);
+    }
+    let fields = strip(VALUE_PREFIX)?;
+    let is_sops = fields
+        .split(',')
+        .any(|field| matches!(field.split_once(':'), Some(("tag" | "type", _))));
+    (!is_sops).then_some(fields)
+}
+
+/// 加密键 `key_path` 的值，返回 `ENC[AES256_GCM,data:...,iv:...]` 形式的文本
+///
+/// `data` 为 base64 编码的密文（含认证标签），`iv` 为 base64 编码的 12 字节随机 nonce。
+/// `key_path` 为以 `.` 分隔的键路径，数组元素写作 `tokens[0]`，作为附加认证数据，解密时必须一致。
+pub fn encrypt_value(plaintext: &str, key: &EncryptionKey, key_path: &str) -> Result<String, QuantumConfigError> {
+    let mut nonce = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce)
+        .map_err(|_| QuantumConfigError::Internal("Failed to generate nonce".to_string()))?;
+    let mut sealed = plaintext.as_bytes().to_vec();
+    key.aead_key()
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(key_path.as_bytes()), &mut sealed)
+        .map_err(|_| QuantumConfigError::Internal("Failed to encrypt configuration value".to_string()))?;
+    Ok(format!("{}data:{},iv:{}{}", VALUE_PREFIX, BASE64.encode(sealed), BASE64.encode(nonce), VALUE_SUFFIX))
+}
+
+/// 解密键 `key_path` 的行内加密值，`key_path` 须与加密时相同
+///
+/// `QCENC[...]` 别名除 `data` 与 `iv` 外也接受单独的 `tag` 字段，其余字段（如 `type`）被忽略。
+pub fn decrypt_value(value: &str, key: &EncryptionKey, key_path: &str) -> Result<String, QuantumConfigError> {
+    let failed = |reason: &str| QuantumConfigError::InvalidValue {
+        key_path: key_path.to_string(),
+        message: format!("failed to decrypt value: {}", reason),
+    };
+    let fields = inline_fields(value).ok_or_else(|| failed("not an ENC[AES256_GCM,...] value"))?;
+    let (mut data, mut iv, mut tag) = (None, None, None);
+    for field in fields.split(',') {
+        let (name, encoded) = field.split_once(':').ok_or_else(|| failed("malformed field"))?;
+        let slot = match name.trim() {
+            "data" => &mut data,
+            "iv" => &mut iv,
+            "tag" => &mut tag,
+            _ => continue,
+        };
+        *slot = Some(BASE64.decode(encoded.trim()).map_err(|_| failed("invalid base64"))?);
+    }
+    let mut sealed = data.ok_or_else(|| failed("missing data"))?;
+    let iv = iv.ok_or_else(|| failed("missing iv"))?;
+    sealed.extend(tag.unwrap_or_default());
+    let nonce = Nonce::try_assume_unique_for_key(&iv).map_err(|_| failed("invalid iv"))?;
+    let plaintext = key
+        .aead_key()
+        .open_in_place(nonce, Aad::from(key_path.as_bytes()), &mut sealed)
+        .map_err(|_| failed("wrong key, value moved from another key, or corrupted value"))?;
+    String::from_utf8(plaintext.to_vec()).map_err(|_| failed("plaintext is not valid UTF-8"))
+}
+
+/// 提供主密钥的回调，返回 `None` 时继续尝试下一个来源
+type KeyCallback = Arc<dyn Fn() -> Result<Option<EncryptionKey>, QuantumConfigError> + Send + Sync>;
+
+/// 主密钥来源
+#[derive(Clone)]
+enum KeySource {
+    Key(EncryptionKey),
+    Env(String),
+    File(PathBuf),
+    FileEnv(String),
+    Callback(KeyCallback),
+}
+
+impl fmt::Debug for KeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeySource::Key(key) => write!(f, "{:?}", key),
+            KeySource::Env(name) => write!(f, "Env({})", name),
+            KeySource::File(path) => write!(f, "File({})", path.display()),
+            KeySource::FileEnv(name) => write!(f, "FileEnv({})", name),
+            KeySource::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+/// 主密钥查找链
+///
+/// 来源按添加顺序依次尝试，第一个提供密钥的来源生效：未设置的环境变量、不存在的密钥文件
+/// 与返回 `None` 的回调被跳过；密钥格式错误或回调返回错误时立即失败。
+#[derive(Debug, Clone, Default)]
+pub struct KeyChain {
+    sources: Vec<KeySource>,
+}
+
+impl KeyChain {
+    /// 创建空的查找链
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 与加密文件相同的默认查找链：环境变量 [`KEY_ENV`]，然后是 [`KEY_FILE_ENV`] 指向的密钥文件
+    pub fn standard() -> Self {
+        Self::new().with_env(KEY_ENV).with_key_file_env(KEY_FILE_ENV)
+    }
+
+    /// 直接使用给定的密钥
+    pub fn with_key(mut self, key: EncryptionKey) -> Self {
+        self.sources.push(KeySource::Key(key));
+        self
+    }
+
+    /// 从环境变量读取 base64 或十六进制密钥
+    pub fn with_env<S: Into<String>>(mut self, name: S) -> Self {
+        self.sources.push(KeySource::Env(name.into()));
+        self
+    }
+
+    /// 从密钥文件读取
+    pub fn with_key_file<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.sources.push(KeySource::File(path.into()));
+        self
+    }
+
+    /// 从环境变量指向的密钥文件读取
+    pub fn with_key_file_env<S: Into<String>>(mut self, name: S) -> Self {
+        self.sources.push(KeySource::FileEnv(name.into()));
+        self
+    }
+
+    /// 调用回调获取密钥，例如通过 KMS 解密数据密钥
+    pub fn with_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn() -> Result<Option<EncryptionKey>, QuantumConfigError> + Send + Sync + 'static,
+    {
+        self.sources.push(KeySource::Callback(Arc::new(callback)));
+        self
+    }
+
+    /// 按顺序查找主密钥
+    pub fn resolve(&self) -> Result<EncryptionKey, QuantumConfigError> {
+        for source in &self.sources {
+            let key = match source {
+                KeySource::Key(key) => Some(key.clone()),
+                KeySource::Env(name) => match std::env::var(name).ok().filter(|key| !key.is_empty()) {
+                    Some(key) => Some(EncryptionKey::parse(&key)?),
+                    None => None,
+                },
+                KeySource::File(path) if path.is_file() => Some(EncryptionKey::from_file(path)?),
+                KeySource::File(_) => None,
+                KeySource::FileEnv(name) => match std::env::var_os(name).filter(|path| !path.is_empty()) {
+                    Some(path) => Some(EncryptionKey::from_file(path)?),
+                    None => None,
+                },
+                KeySource::Callback(callback) => callback()?,
+            };
+            if let Some(key) = key {
+                return Ok(key);
+            }
+        }
+        Err(QuantumConfigError::Internal(format!("No encryption key found in key chain {:?}", self.sources)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_tampering() {
+        let key = EncryptionKey::generate().unwrap();
+        let encrypted = encrypt(b"password = \"s3cret\"\n", &key).unwrap();
+        assert!(!encrypted.contains("s3cret"));
+        let path = Path::new("config.toml.enc");
+        assert_eq!(decrypt(encrypted.as_bytes(), &key, path).unwrap(), b"password = \"s3cret\"\n");
+
+        let binary = BASE64.decode(encrypted.trim()).unwrap();
+        assert_eq!(decrypt(&binary, &key, path).unwrap(), b"password = \"s3cret\"\n");
+
+        let mut tampered = binary.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        let error = decrypt(&tampered, &key, path).unwrap_err();
+        assert_eq!(error.code(), "decryption");
+        assert!(error.to_string().contains("wrong key or corrupted file"));
+
+        let other = EncryptionKey::generate().unwrap();
+        assert!(decrypt(&binary, &other, path).is_err());
+        assert!(decrypt(b"name = \"plain\"", &key, path).is_err());
+    }
+
+    #[test]
+    fn test_key_parsing() {
+        let key = EncryptionKey::from_bytes([7; KEY_LEN]);
+        assert_eq!(EncryptionKey::parse(&key.to_base64()).unwrap(), key);
+        assert_eq!(EncryptionKey::parse(&format!("{}\n", "07".repeat(KEY_LEN))).unwrap(), key);
+        assert!(EncryptionKey::parse("c2hvcnQ=").is_err());
+        assert_eq!(format!("{:?}", key), "EncryptionKey(<redacted>)");
+    }
+
+    #[test]
+    fn test_inline_values_round_trip() {
+        let key = EncryptionKey::generate().unwrap();
+        let encrypted = encrypt_value("s3cret", &key, "db.password").unwrap();
+        assert!(is_encrypted_value(&encrypted) && !encrypted.contains("s3cret"));
+        assert_eq!(decrypt_value(&encrypted, &key, "db.password").unwrap(), "s3cret");
+        // 键路径参与认证，密文不能挪到其他键下
+        let error = decrypt_value(&encrypted, &key, "admin.password").unwrap_err();
+        assert!(error.to_string().contains("moved from another key"), "{}", error);
+        assert!(encrypted.starts_with("ENC[AES256_GCM,data:"), "{}", encrypted);
+        // SOPS 的加密值不是行内加密值，别名前缀不受此限制
+        assert!(!is_encrypted_value("ENC[AES256_GCM,data:abc,iv:abc,tag:abc,type:str]"));
+        assert!(!is_encrypted_value("ENC[AES256_GCM,data:abc,type:str]"));
+        assert!(is_encrypted_value("QCENC[AES256_GCM,data:abc,iv:abc,tag:abc,type:str]"));
+        let alias = encrypted.replacen("ENC[", "QCENC[", 1);
+        assert_eq!(decrypt_value(&alias, &key, "db.password").unwrap(), "s3cret");
+
+        // 认证标签单独存放，附带类型字段
+        let fields = &encrypted[VALUE_PREFIX.len()..encrypted.len() - 1];
+        let (data, iv) = fields.split_once(",iv:").unwrap();
+        let sealed = BASE64.decode(data.strip_prefix("data:").unwrap()).unwrap();
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - AES_256_GCM.tag_len());
+        let split = format!(
+            "QCENC[AES256_GCM,data:{},iv:{},tag:{},type:str]",
+            BASE64.encode(ciphertext),
+            iv,
+            BASE64.encode(tag)
+        );
+        assert_eq!(decrypt_value(&split, &key, "db.password").unwrap(), "s3cret");
+
+        let error = decrypt_value(&encrypted, &EncryptionKey::generate().unwrap(), "db.password").unwrap_err();
+        assert_eq!(error.code(), "invalid_value");
+        assert!(error.to_string().contains("db.password"), "{}", error);
+        assert!(decrypt_value("QCENC[AES256_GCM,iv:AAAA]", &key, "db.password").is_err());
+    }
+
+    #[test]
+    fn test_key_chain_uses_first_available_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = EncryptionKey::from_bytes([3; KEY_LEN]);
+        let key_file = dir.path().join("master.key");
+        std::fs::write(&key_file, key.to_base64()).unwrap();
+
+        let chain = KeyChain::new()
+            .with_env("QC_KEY_CHAIN_TEST_UNSET")
+            .with_key_file(dir.path().join("missing.key"))
+            .with_callback(|| Ok(None))
+            .with_key_file(&key_file)
+            .with_callback(|| panic!("later sources are not consulted"));
+        assert_eq!(chain.resolve().unwrap(), key);
+
+        let kms = KeyChain::new().with_callback(|| Ok(Some(EncryptionKey::from_bytes([9; KEY_LEN]))));
+        assert_eq!(kms.resolve().unwrap(), EncryptionKey::from_bytes([9; KEY_LEN]));
+
+        let error = KeyChain::new().with_key_file(dir.path().join("missing.key")).resolve().unwrap_err();
+        assert!(error.to_string().contains("missing.key"), "{}", error);
+    }
+}
@@ -2,11 +2,226 @@
 //!
 //! 此模块实现了从环境变量读取数据的 figment Provider。
 //! 支持前缀过滤、分隔符配置和嵌套键构造。
-
+//! 可选支持 Docker secrets 风格的文件间接引用：`MYAPP_TLS_CERT_FILE=/run/secrets/cert`
+//! 表示从该文件读取 `tls_cert` 的值。
+//!
+//! 变量名去掉前缀后按分隔符（默认 `__`）拆分为嵌套键，各段按 [`EnvCase`] 转换大小写（默认转为小写）。
+//! 分隔符为单个 `_` 等同时出现在字段名中的字符时，通过 [`QuantumConfigEnvProvider::with_key_paths`]
+//! 提供已知的配置键，按已知键匹配变量名以消除歧义。
+//!
+//! 默认遍历整个进程环境；已知配置结构时可通过 [`EnvScanScope::Keys`] 只查询对应的变量，
+//! 监视重载场景可通过 [`EnvSnapshot`] 在同一代内复用环境变量快照。
+//! 变量默认从进程环境读取，测试中可通过 [`QuantumConfigEnvProvider::with_env_source`] 注入
+//! 私有的 [`EnvSource`]（例如 [`super::MapEnv`]），避免并行测试修改全局的进程环境。
+
+use super::env_source::EnvSource;
+use super::key_trie::KeyTrie;
+use super::secrets_provider::trim_trailing_newline;
 use crate::error::QuantumConfigError;
+use crate::logging::trace_debug;
 use figment::{value::{Map, Value}, Error, Metadata, Profile, Provider};
-use std::collections::HashMap;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
 use std::env;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// 环境变量值的默认最大长度（字节）
+pub const DEFAULT_MAX_ENV_VALUE_LEN: usize = 8192;
+
+/// 环境变量的读取范围
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EnvScanScope {
+    /// 遍历整个进程环境，再按前缀过滤
+    #[default]
+    Full,
+    /// 只查询给定配置键（以 `.` 分隔）对应的环境变量
+    Keys(Vec<String>),
+}
+
+impl EnvScanScope {
+    /// 从配置值（通常是 `T::default()`）的结构推导需要查询的配置键
+    ///
+    /// 只收集叶子键；默认值为空的映射或列表无法推导子键，此类字段应使用 [`EnvScanScope::Full`]。
+    pub fn from_value<T: Serialize>(value: &T) -> Result<Self, QuantumConfigError> {
+        let value = serde_json::to_value(value).map_err(|e| {
+            QuantumConfigError::Internal(format!("Failed to derive environment keys from schema: {}", e))
+        })?;
+        let mut keys = Vec::new();
+        collect_leaf_keys(&value, &mut Vec::new(), &mut keys);
+        Ok(EnvScanScope::Keys(keys))
+    }
+}
+
+/// 收集 JSON 对象中所有叶子节点的键路径
+fn collect_leaf_keys(value: &JsonValue, path: &mut Vec<String>, keys: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                path.push(key.clone());
+                collect_leaf_keys(child, path, keys);
+                path.pop();
+            }
+        }
+        _ if !path.is_empty() => keys.push(path.join(".")),
+        _ => {}
+    }
+}
+
+/// 环境变量名与配置键之间的大小写转换规则
+///
+/// 规则作用于去掉前缀后按分隔符拆分出的每一段，同时决定由配置键推导变量名的方式
+/// （用于错误信息与 [`EnvScanScope::Keys`]）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EnvCase {
+    /// 转为小写：`MAX_CONNS` -> `max_conns`，对应 snake_case 字段
+    #[default]
+    Snake,
+    /// 转为大写：`MAX_CONNS` -> `MAX_CONNS`，对应 `#[serde(rename_all = "SCREAMING_SNAKE_CASE")]`
+    ScreamingSnake,
+    /// 转为小写并以 `-` 连接单词：`MAX_CONNS` -> `max-conns`，对应 `#[serde(rename_all = "kebab-case")]`
+    Kebab,
+    /// 小驼峰：`MAX_CONNS` -> `maxConns`，对应 `#[serde(rename_all = "camelCase")]`
+    Camel,
+    /// 保持原样
+    Preserve,
+}
+
+impl EnvCase {
+    /// 从属性值解析：`"snake"`、`"screaming_snake"`、`"kebab"`、`"camel"` 或 `"preserve"`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().replace('-', "_").as_str() {
+            "snake" => Some(Self::Snake),
+            "screaming_snake" => Some(Self::ScreamingSnake),
+            "kebab" => Some(Self::Kebab),
+            "camel" => Some(Self::Camel),
+            "preserve" => Some(Self::Preserve),
+            _ => None,
+        }
+    }
+
+    /// 将变量名中的一段转换为配置键
+    pub fn key_segment(self, env_segment: &str) -> String {
+        match self {
+            Self::Snake => env_segment.to_lowercase(),
+            Self::ScreamingSnake => env_segment.to_uppercase(),
+            Self::Kebab => env_segment.to_lowercase().replace('_', "-"),
+            Self::Camel => {
+                let mut key = String::with_capacity(env_segment.len());
+                for (i, word) in env_segment.split('_').filter(|word| !word.is_empty()).enumerate() {
+                    let word = word.to_lowercase();
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) if i > 0 => {
+                            key.extend(first.to_uppercase());
+                            key.push_str(chars.as_str());
+                        }
+                        _ => key.push_str(&word),
+                    }
+                }
+                key
+            }
+            Self::Preserve => env_segment.to_string(),
+        }
+    }
+
+    /// 将配置键中的一段转换为变量名
+    pub fn env_segment(self, key_segment: &str) -> String {
+        match self {
+            Self::Snake | Self::ScreamingSnake => key_segment.to_uppercase(),
+            Self::Kebab => key_segment.replace('-', "_").to_uppercase(),
+            Self::Camel => {
+                let mut name = String::with_capacity(key_segment.len() + 4);
+                for c in key_segment.chars() {
+                    if c.is_uppercase() && !name.is_empty() {
+                        name.push('_');
+                    }
+                    name.extend(c.to_uppercase());
+                }
+                name
+            }
+            Self::Preserve => key_segment.to_string(),
+        }
+    }
+}
+
+/// 当前快照代数，[`EnvSnapshot::invalidate`] 会使其递增
+static SNAPSHOT_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 按前缀缓存的快照
+static SNAPSHOT_CACHE: OnceLock<Mutex<HashMap<String, EnvSnapshot>>> = OnceLock::new();
+
+/// 环境变量快照
+///
+/// 只保存匹配前缀的变量。`cached` 在同一代内返回同一份快照，
+/// 适合文件变化触发的重复重载；环境变量可能已变化时调用 `invalidate` 开启新一代。
+#[derive(Debug, Clone)]
+pub struct EnvSnapshot {
+    /// 快照所属的代数
+    generation: u64,
+    /// 匹配前缀的环境变量
+    vars: Arc<HashMap<String, String>>,
+}
+
+impl EnvSnapshot {
+    /// 立即读取匹配前缀的环境变量
+    pub fn capture(prefix: &str) -> Self {
+        let vars = env::vars().filter(|(key, _)| key.starts_with(prefix)).collect();
+        Self {
+            generation: SNAPSHOT_GENERATION.load(Ordering::SeqCst),
+            vars: Arc::new(vars),
+        }
+    }
+
+    /// 返回当前代内缓存的快照，不存在或已过期时重新读取
+    pub fn cached(prefix: &str) -> Self {
+        let generation = SNAPSHOT_GENERATION.load(Ordering::SeqCst);
+        let cache = SNAPSHOT_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        match cache.get(prefix) {
+            Some(snapshot) if snapshot.generation == generation => snapshot.clone(),
+            _ => {
+                let snapshot = Self::capture(prefix);
+                cache.insert(prefix.to_string(), snapshot.clone());
+                snapshot
+            }
+        }
+    }
+
+    /// 基于给定的变量创建快照，例如从 `.env` 文件读取的变量，重复的键以后出现的为准
+    pub fn from_vars<I: IntoIterator<Item = (String, String)>>(vars: I) -> Self {
+        Self {
+            generation: SNAPSHOT_GENERATION.load(Ordering::SeqCst),
+            vars: Arc::new(vars.into_iter().collect()),
+        }
+    }
+
+    /// 使所有缓存的快照过期，下一次 `cached` 将重新读取环境变量
+    pub fn invalidate() {
+        SNAPSHOT_GENERATION.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 快照所属的代数
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// 快照中的变量数量
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    /// 快照是否为空
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    /// 获取快照中的变量值
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vars.get(name).map(String::as_str)
+    }
+}
 
 /// 环境变量配置提供者
 ///
@@ -19,8 +234,34 @@ pub struct QuantumConfigEnvProvider {
     separator: String,
     /// 是否忽略空值
     ignore_empty: bool,
-    /// 是否转换键名为小写
-    lowercase_keys: bool,
+    /// 键名的大小写转换规则
+    case: EnvCase,
+    /// 已知的配置键（以 `.` 分隔），用于按键匹配变量名
+    key_paths: Vec<String>,
+    /// 文件间接引用后缀（例如 "_FILE"），为 `None` 时不启用
+    file_suffix: Option<String>,
+    /// 环境变量值的最大长度（字节）
+    max_value_len: usize,
+    /// 按配置键（以 `.` 分隔）覆盖的最大长度
+    key_max_value_lens: HashMap<String, usize>,
+    /// 环境变量读取范围
+    scan_scope: EnvScanScope,
+    /// 环境变量快照，设置后不再读取环境变量来源
+    snapshot: Option<EnvSnapshot>,
+    /// 环境变量来源，默认为加载上下文的来源（通常为进程环境），参见 [`crate::context`]
+    source: Arc<dyn EnvSource>,
+    /// 列表分隔符，为 `None` 时不拆分列表
+    list_separator: Option<String>,
+    /// 按配置键（以 `.` 分隔）覆盖的列表分隔符
+    key_list_separators: HashMap<String, String>,
+    /// 是否把 JSON 对象与数组字面量解析为结构化值
+    json_values: bool,
+    /// 按配置键（以 `.` 分隔）启用的 JSON 字面量解析
+    json_keys: HashSet<String>,
+    /// 按配置键（以 `.` 分隔）保留原文的键，值不推断为布尔值或数字
+    raw_string_keys: HashSet<String>,
+    /// 跳过的变量名前缀，例如由租户层读取的变量
+    excluded_prefixes: Vec<String>,
 }
 
 impl QuantumConfigEnvProvider {
@@ -41,7 +282,20 @@ impl QuantumConfigEnvProvider {
             prefix: prefix.into(),
             separator: separator.into(),
             ignore_empty,
-            lowercase_keys,
+            case: if lowercase_keys { EnvCase::Snake } else { EnvCase::Preserve },
+            key_paths: Vec::new(),
+            file_suffix: None,
+            max_value_len: DEFAULT_MAX_ENV_VALUE_LEN,
+            key_max_value_lens: HashMap::new(),
+            scan_scope: EnvScanScope::Full,
+            snapshot: None,
+            source: crate::context::env_source(),
+            list_separator: None,
+            key_list_separators: HashMap::new(),
+            json_values: false,
+            json_keys: HashSet::new(),
+            raw_string_keys: HashSet::new(),
+            excluded_prefixes: Vec::new(),
         }
     }
 
@@ -59,10 +313,146 @@ impl QuantumConfigEnvProvider {
             prefix: prefix.into(),
             separator: "__".to_string(),
             ignore_empty: true,
-            lowercase_keys: true,
+            case: EnvCase::Snake,
+            key_paths: Vec::new(),
+            file_suffix: None,
+            max_value_len: DEFAULT_MAX_ENV_VALUE_LEN,
+            key_max_value_lens: HashMap::new(),
+            scan_scope: EnvScanScope::Full,
+            snapshot: None,
+            source: crate::context::env_source(),
+            list_separator: None,
+            key_list_separators: HashMap::new(),
+            json_values: false,
+            json_keys: HashSet::new(),
+            raw_string_keys: HashSet::new(),
+            excluded_prefixes: Vec::new(),
         }
     }
 
+    /// 以相同的设置读取另一个前缀下的变量
+    pub(crate) fn with_replaced_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.prefix = prefix.into();
+        self.excluded_prefixes.clear();
+        self
+    }
+
+    /// 跳过以 `prefix` 开头的变量，例如 `APP__TENANT_A__PORT` 由租户层读取，不属于基础前缀 `APP_`
+    pub fn with_excluded_prefix<S: Into<String>>(mut self, prefix: S) -> Self {
+        self.excluded_prefixes.push(prefix.into());
+        self
+    }
+
+    /// 设置嵌套键的分隔符，默认为 `__`
+    ///
+    /// 分隔符同时出现在字段名中（例如单个 `_`）时，应通过 [`Self::with_key_paths`] 提供已知的配置键。
+    pub fn with_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// 设置键名的大小写转换规则，默认为 [`EnvCase::Snake`]
+    pub fn with_case(mut self, case: EnvCase) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// 提供已知的配置键（以 `.` 分隔）
+    ///
+    /// 变量名与某个已知键推导出的变量名一致时按该键插入，例如分隔符为 `_` 时
+    /// `APP_DATABASE_MAX_CONNS` 对应 `database.max_conns` 而不是 `database.max.conns`；
+    /// 不匹配任何已知键的变量仍按分隔符拆分。
+    pub fn with_key_paths<I, S>(mut self, key_paths: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.key_paths = key_paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 启用文件间接引用
+    ///
+    /// 以 `suffix` 结尾的环境变量（例如 `MYAPP_TLS_CERT_FILE`）的值被视为文件路径，
+    /// 配置值从该文件读取（去除末尾换行），键名为去掉后缀后的部分。
+    /// 文件内容不受环境变量值长度限制，适合证书等较大的值。
+    /// 同时设置 `X` 与 `X{suffix}` 时返回错误。
+    pub fn with_file_suffix<S: Into<String>>(mut self, suffix: S) -> Self {
+        let suffix = suffix.into();
+        self.file_suffix = (!suffix.is_empty()).then_some(suffix);
+        self
+    }
+
+    /// 设置环境变量值的最大长度（字节），默认为 [`DEFAULT_MAX_ENV_VALUE_LEN`]
+    pub fn with_max_value_len(mut self, max_value_len: usize) -> Self {
+        self.max_value_len = max_value_len;
+        self
+    }
+
+    /// 为指定配置键（以 `.` 分隔，例如 `tls.cert`）单独设置值的最大长度（字节）
+    pub fn with_key_max_value_len<S: Into<String>>(mut self, key_path: S, max_value_len: usize) -> Self {
+        self.key_max_value_lens.insert(key_path.into(), max_value_len);
+        self
+    }
+
+    /// 设置环境变量读取范围
+    pub fn with_scan_scope(mut self, scan_scope: EnvScanScope) -> Self {
+        self.scan_scope = scan_scope;
+        self
+    }
+
+    /// 从快照而非进程环境读取变量
+    pub fn with_snapshot(mut self, snapshot: EnvSnapshot) -> Self {
+        self.snapshot = Some(snapshot);
+        self
+    }
+
+    /// 从给定来源而非进程环境读取变量，参见 [`EnvSource`]；设置了快照时仍以快照为准
+    pub fn with_env_source<E: EnvSource + 'static>(mut self, source: E) -> Self {
+        self.source = Arc::new(source);
+        self
+    }
+
+    /// 启用列表解析
+    ///
+    /// 包含 `separator` 的值（例如 `APP_FEATURES="a,b,c"`）与 JSON 数组字面量（例如 `["a","b"]`）
+    /// 解析为数组，各元素按标量规则解析。不含分隔符的值仍是标量，
+    /// 单元素列表需使用 JSON 语法或 [`Self::with_key_list_separator`]。
+    pub fn with_list_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        let separator = separator.into();
+        self.list_separator = (!separator.is_empty()).then_some(separator);
+        self
+    }
+
+    /// 为指定配置键（以 `.` 分隔）设置列表分隔符，该键的值总是解析为数组（空值为空数组）
+    pub fn with_key_list_separator<K: Into<String>, S: Into<String>>(mut self, key_path: K, separator: S) -> Self {
+        self.key_list_separators.insert(key_path.into(), separator.into());
+        self
+    }
+
+    /// 设置是否把 JSON 对象与数组字面量（例如 `APP_LABELS='{"region":"eu"}'`）解析为结构化值
+    ///
+    /// 默认关闭，以免值恰好是 JSON 文本的字符串字段被误解析。映射字段也可以不用 JSON，
+    /// 而是按嵌套键逐项设置，例如 `APP_LABELS__REGION=eu`（键名按提供器的规则转为小写）。
+    pub fn with_json_values(mut self, enabled: bool) -> Self {
+        self.json_values = enabled;
+        self
+    }
+
+    /// 为指定配置键（以 `.` 分隔）启用 JSON 字面量解析
+    pub fn with_key_json_values<K: Into<String>>(mut self, key_path: K) -> Self {
+        self.json_keys.insert(key_path.into());
+        self
+    }
+
+    /// 指定配置键（以 `.` 分隔）的值按原文作为字符串，不推断为布尔值或数字
+    ///
+    /// 用于密码等必须逐字保留的值：`007` 不会变成 `7`，`yes` 不会变成 `true`。
+    pub fn with_key_raw_string<K: Into<String>>(mut self, key_path: K) -> Self {
+        self.raw_string_keys.insert(key_path.into());
+        self
+    }
+
     /// 验证环境变量键名的安全性
     pub fn validate_env_key(key: &str) -> Result<(), QuantumConfigError> {
         // 检查键名长度（防止过长的键名）
@@ -82,13 +472,19 @@ impl QuantumConfigEnvProvider {
         Ok(())
     }
     
-    /// 验证环境变量值的安全性
+    /// 验证环境变量值的安全性（使用默认长度限制）
     pub fn validate_env_value(value: &str) -> Result<(), QuantumConfigError> {
+        Self::validate_env_value_with_limit(value, DEFAULT_MAX_ENV_VALUE_LEN)
+    }
+
+    /// 验证环境变量值的安全性，使用给定的长度限制
+    pub fn validate_env_value_with_limit(value: &str, max_value_len: usize) -> Result<(), QuantumConfigError> {
         // 检查值长度（防止过长的值）
-        if value.len() > 8192 {
-            return Err(QuantumConfigError::ValidationError(
-                "Environment variable value too long (max 8192 characters)".to_string()
-            ));
+        if value.len() > max_value_len {
+            return Err(QuantumConfigError::ValidationError(format!(
+                "Environment variable value too long (max {} characters)",
+                max_value_len
+            )));
         }
         
         // 检查是否包含空字节
@@ -103,18 +499,22 @@ impl QuantumConfigEnvProvider {
 
     /// 读取并处理环境变量
     fn read_env_vars(&self) -> Result<Map<String, Value>, QuantumConfigError> {
-        let mut env_map = Map::new();
+        Ok(self.key_trie()?.into_dict())
+    }
 
-        // 获取所有环境变量
-        let env_vars: HashMap<String, String> = env::vars().collect();
+    /// 读取环境变量并构建配置键前缀树，叶子记录提供该值的环境变量名，可用于来源查询
+    pub fn key_trie(&self) -> Result<KeyTrie, QuantumConfigError> {
+        let mut trie = KeyTrie::new();
 
-        for (key, value) in env_vars {
-            // 验证环境变量键名和值的安全性
-            Self::validate_env_key(&key)?;
-            Self::validate_env_value(&value)?;
+        let env_vars = self.collect_env_vars();
+        let known_keys = self.known_env_keys();
+
+        for (key, value) in &env_vars {
+            // 验证环境变量键名的安全性
+            Self::validate_env_key(key)?;
             
             // 检查是否匹配前缀
-            if !key.starts_with(&self.prefix) {
+            if !key.starts_with(&self.prefix) || self.excluded_prefixes.iter().any(|prefix| key.starts_with(prefix)) {
                 continue;
             }
 
@@ -123,130 +523,260 @@ impl QuantumConfigEnvProvider {
                 continue;
             }
 
-            // 移除前缀
-            let key_without_prefix = &key[self.prefix.len()..];
+            let env_name = key.clone();
+
+            // 文件间接引用：从文件读取值，并去掉键名后缀
+            let (key, value, from_file) = match self.resolve_file_reference(key, value, &env_vars)? {
+                Some((target_key, content)) => (target_key, content, true),
+                None => (key.as_str(), value.clone(), false),
+            };
 
-            // 处理键名
-            let processed_key = if self.lowercase_keys {
-                key_without_prefix.to_lowercase()
-            } else {
-                key_without_prefix.to_string()
+            // 移除前缀，按已知键或分隔符拆分并转换各段
+            let key_without_prefix = &key[self.prefix.len()..];
+            let segments = match known_keys.get(&self.match_name(key_without_prefix)) {
+                Some(key_path) => key_path.split('.').map(str::to_string).collect(),
+                None => self.key_segments(key_without_prefix),
             };
 
+            // 验证环境变量值的安全性（文件内容不受限制），长度限制可按配置键覆盖
+            if !from_file {
+                let max_value_len = if self.key_max_value_lens.is_empty() {
+                    self.max_value_len
+                } else {
+                    self.key_max_value_lens.get(&segments.join(".")).copied().unwrap_or(self.max_value_len)
+                };
+                Self::validate_env_value_with_limit(&value, max_value_len)?;
+            }
+
             // 构造嵌套键并插入值
-            self.insert_nested_value(&mut env_map, &processed_key, value)?;
+            self.insert_segments(&mut trie, &segments, value, Some(env_name))?;
         }
 
-        Ok(env_map)
+        trace_debug!(
+            prefix = %self.prefix,
+            scanned = env_vars.len(),
+            matched = trie.len(),
+            "collected environment variables"
+        );
+        Ok(trie)
     }
 
-    /// 将值插入到嵌套的映射结构中
-    ///
-    /// # Arguments
-    /// * `map` - 目标映射
-    /// * `key` - 键名（可能包含分隔符）
-    /// * `value` - 要插入的值
-    fn insert_nested_value(
-        &self,
-        map: &mut Map<String, Value>,
-        key: &str,
-        value: String,
-    ) -> Result<(), QuantumConfigError> {
-        let parts: Vec<&str> = key.split(&self.separator).collect();
+    /// 按分隔符拆分变量名（不含前缀）并转换各段
+    fn key_segments(&self, name: &str) -> Vec<String> {
+        name.split(&self.separator).map(|segment| self.case.key_segment(segment)).collect()
+    }
 
-        if parts.is_empty() {
-            return Ok(());
-        }
+    /// 配置键对应的变量名（不含前缀）
+    fn env_key(&self, key_path: &str) -> String {
+        key_path
+            .split('.')
+            .map(|segment| self.case.env_segment(segment))
+            .collect::<Vec<_>>()
+            .join(&self.separator)
+    }
 
-        // 如果只有一个部分，直接插入
-        if parts.len() == 1 {
-            let parsed_value = self.parse_env_value(value)?;
-            map.insert(parts[0].to_string(), parsed_value);
-            return Ok(());
+    /// 比较变量名时使用的形式：除 [`EnvCase::Preserve`] 外不区分大小写
+    fn match_name(&self, name: &str) -> String {
+        match self.case {
+            EnvCase::Preserve => name.to_string(),
+            _ => name.to_uppercase(),
         }
+    }
 
-        // 处理嵌套键
-        let mut current_map = map;
-
-        // 遍历除最后一个部分外的所有部分
-        for part in &parts[..parts.len() - 1] {
-            let part_string = part.to_string();
+    /// 已知配置键按变量名（比较形式，不含前缀）建立的索引
+    fn known_env_keys(&self) -> HashMap<String, &str> {
+        self.key_paths
+            .iter()
+            .map(|key_path| (self.match_name(&self.env_key(key_path)), key_path.as_str()))
+            .collect()
+    }
 
-            // 如果键不存在，创建新的字典
-            if !current_map.contains_key(&part_string) {
-                current_map.insert(
-                    part_string.clone(),
-                    Value::Dict(figment::value::Tag::Default, Map::new()),
-                );
+    /// 按读取范围收集候选环境变量
+    fn collect_env_vars(&self) -> HashMap<String, String> {
+        let names = match &self.scan_scope {
+            EnvScanScope::Full => {
+                return match &self.snapshot {
+                    Some(snapshot) => snapshot.vars.as_ref().clone(),
+                    None => self.source.vars().into_iter().collect(),
+                };
             }
+            EnvScanScope::Keys(keys) => keys.iter().flat_map(|key| self.env_names_for_key(key)),
+        };
+
+        names
+            .filter_map(|name| {
+                let value = match &self.snapshot {
+                    Some(snapshot) => snapshot.get(&name).map(str::to_string),
+                    None => self.source.var(&name),
+                };
+                value.map(|value| (name, value))
+            })
+            .collect()
+    }
 
-            // 获取或创建嵌套字典
-            match current_map.get_mut(&part_string) {
-                Some(Value::Dict(_, nested_map)) => {
-                    current_map = nested_map;
-                }
-                Some(_) => {
-                    // 如果已存在的值不是字典，返回错误
-                    return Err(QuantumConfigError::Internal(
-                        format!(
-                            "Environment variable key conflict: '{}' cannot be both a value and a nested object",
-                            part
-                        )
-                    ));
-                }
-                None => {
-                    // 这种情况不应该发生，因为我们刚刚插入了值
-                    return Err(QuantumConfigError::Internal(
-                        "Unexpected error during nested key insertion".to_string()
-                    ));
-                }
-            }
+    /// 配置键对应的环境变量名（包含文件间接引用变量）
+    fn env_names_for_key(&self, key_path: &str) -> Vec<String> {
+        let name = format!("{}{}", self.prefix, self.env_key(key_path));
+        match &self.file_suffix {
+            Some(suffix) => vec![format!("{}{}", name, suffix), name],
+            None => vec![name],
         }
+    }
 
-        // 插入最终值
-        let final_key = parts[parts.len() - 1].to_string();
-        let parsed_value = self.parse_env_value(value)?;
-        current_map.insert(final_key, parsed_value);
+    /// 解析文件间接引用
+    ///
+    /// 键名不以文件后缀结尾时返回 `None`；否则返回去掉后缀的键名与文件内容。
+    fn resolve_file_reference<'a>(
+        &self,
+        key: &'a str,
+        path: &str,
+        env_vars: &HashMap<String, String>,
+    ) -> Result<Option<(&'a str, String)>, QuantumConfigError> {
+        let Some(suffix) = self.file_suffix.as_deref() else {
+            return Ok(None);
+        };
+        let Some(target_key) = key.strip_suffix(suffix) else {
+            return Ok(None);
+        };
+        if target_key.len() <= self.prefix.len() {
+            return Ok(None);
+        }
 
-        Ok(())
+        if env_vars.contains_key(target_key) {
+            return Err(QuantumConfigError::ValidationError(format!(
+                "Environment variables '{}' and '{}' are both set but are mutually exclusive",
+                target_key, key
+            )));
+        }
+
+        let content = std::fs::read_to_string(path).map_err(|e| QuantumConfigError::FileReadError {
+            path: path.to_string(),
+            source: e,
+        })?;
+        Ok(Some((target_key, trim_trailing_newline(&content).to_string())))
+    }
+
+    /// 解析值并按给定的键路径段插入前缀树，启用列表解析的键解析为数组
+    ///
+    /// # Arguments
+    /// * `trie` - 目标前缀树
+    /// * `segments` - 键路径的各段
+    /// * `value` - 要插入的值
+    /// * `origin` - 提供该值的环境变量名
+    fn insert_segments(
+        &self,
+        trie: &mut KeyTrie,
+        segments: &[String],
+        value: String,
+        origin: Option<String>,
+    ) -> Result<(), QuantumConfigError> {
+        // 只有按键覆盖的规则需要完整的键路径
+        let key_path = if self.key_list_separators.is_empty() && self.json_keys.is_empty() && self.raw_string_keys.is_empty() {
+            String::new()
+        } else {
+            segments.join(".")
+        };
+        if self.raw_string_keys.contains(&key_path) {
+            return trie.insert(segments.iter().map(String::as_str), Value::String(figment::value::Tag::Default, value), origin);
+        }
+        let parsed_value = match (self.key_list_separators.get(&key_path), &self.list_separator) {
+            (Some(separator), _) => parse_list_value(value, separator, true),
+            (None, Some(separator)) => parse_list_value(value, separator, false),
+            (None, None) if self.json_values || self.json_keys.contains(&key_path) => match parse_json_literal(&value) {
+                Some(parsed) => parsed,
+                None => self.parse_env_value(value)?,
+            },
+            (None, None) => self.parse_env_value(value)?,
+        };
+        trie.insert(segments.iter().map(String::as_str), parsed_value, origin)
     }
 
     /// 解析环境变量值
     ///
     /// 尝试将字符串值解析为适当的类型（布尔值、数字或字符串）
     fn parse_env_value(&self, value: String) -> Result<Value, QuantumConfigError> {
-        let tag = figment::value::Tag::Default;
+        Ok(parse_scalar_value(value))
+    }
+}
 
-        // 尝试解析为布尔值
-        match value.to_lowercase().as_str() {
-            "true" | "1" | "yes" | "on" => return Ok(Value::Bool(tag, true)),
-            "false" | "0" | "no" | "off" => return Ok(Value::Bool(tag, false)),
-            _ => {}
-        }
+/// 将值解析为数组：JSON 数组字面量按 JSON 解析，否则按分隔符拆分并去除各元素两端空白
+///
+/// `always` 为 `false` 时不含分隔符的值按标量解析。
+fn parse_list_value(value: String, separator: &str, always: bool) -> Value {
+    if let Some(parsed @ Value::Array(..)) = parse_json_literal(&value) {
+        return parsed;
+    }
+    if !always && !value.contains(separator) {
+        return parse_scalar_value(value);
+    }
+    let items = value
+        .split(separator)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| parse_scalar_value(item.to_string()))
+        .collect();
+    Value::Array(figment::value::Tag::Default, items)
+}
 
-        // 尝试解析为整数
-        if let Ok(int_val) = value.parse::<i64>() {
-            return Ok(Value::Num(tag, figment::value::Num::I64(int_val)));
-        }
+/// 将 JSON 对象或数组字面量解析为结构化值，其他值返回 `None`
+fn parse_json_literal(value: &str) -> Option<Value> {
+    let trimmed = value.trim();
+    let delimited = (trimmed.starts_with('{') && trimmed.ends_with('}')) || (trimmed.starts_with('[') && trimmed.ends_with(']'));
+    if !delimited {
+        return None;
+    }
+    match serde_json::from_str::<JsonValue>(trimmed).ok()? {
+        // 只接受对象与数组，标量仍按环境变量的规则解析
+        value @ (JsonValue::Object(_) | JsonValue::Array(_)) => Value::serialize(value).ok(),
+        _ => None,
+    }
+}
 
-        // 尝试解析为无符号整数
-        if let Ok(uint_val) = value.parse::<u64>() {
-            return Ok(Value::Num(tag, figment::value::Num::U64(uint_val)));
-        }
+/// 将字符串值解析为布尔值、数字或字符串
+///
+/// 环境变量提供器与其他按键读取纯文本值的提供器共用此规则。
+pub(crate) fn parse_scalar_value(value: String) -> Value {
+    let tag = figment::value::Tag::Default;
+
+    // 尝试解析为布尔值（不区分大小写，避免为每个值分配小写副本）
+    let is_any = |words: &[&str]| words.iter().any(|word| value.eq_ignore_ascii_case(word));
+    if is_any(&["true", "1", "yes", "on"]) {
+        return Value::Bool(tag, true);
+    }
+    if is_any(&["false", "0", "no", "off"]) {
+        return Value::Bool(tag, false);
+    }
 
-        // 尝试解析为浮点数
-        if let Ok(float_val) = value.parse::<f64>() {
-            return Ok(Value::Num(tag, figment::value::Num::F64(float_val)));
-        }
+    // 尝试解析为整数
+    if let Ok(int_val) = value.parse::<i64>() {
+        return Value::Num(tag, figment::value::Num::I64(int_val));
+    }
 
-        // 默认作为字符串处理
-        Ok(Value::String(tag, value))
+    // 尝试解析为无符号整数
+    if let Ok(uint_val) = value.parse::<u64>() {
+        return Value::Num(tag, figment::value::Num::U64(uint_val));
     }
+
+    // 尝试解析为浮点数
+    if let Ok(float_val) = value.parse::<f64>() {
+        return Value::Num(tag, figment::value::Num::F64(float_val));
+    }
+
+    // 默认作为字符串处理
+    Value::String(tag, value)
 }
 
 impl Provider for QuantumConfigEnvProvider {
     fn metadata(&self) -> Metadata {
+        // 将键路径还原为环境变量名，便于错误信息指出是哪个变量出错
+        let prefix = self.prefix.clone();
+        let separator = self.separator.clone();
+        let case = self.case;
         Metadata::named(format!("Quantum Config Environment Provider (prefix: {})", self.prefix))
+            .interpolater(move |_profile, keys| {
+                let key = keys.iter().map(|key| case.env_segment(key)).collect::<Vec<_>>().join(&separator);
+                format!("{}{}", prefix, key)
+            })
     }
 
     fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
@@ -272,7 +802,7 @@ mod tests {
         assert_eq!(provider.prefix, "TEST_");
         assert_eq!(provider.separator, "__");
         assert!(provider.ignore_empty);
-        assert!(provider.lowercase_keys);
+        assert_eq!(provider.case, EnvCase::Snake);
     }
 
     #[test]
@@ -282,7 +812,64 @@ mod tests {
         assert_eq!(provider.prefix, "MYAPP_");
         assert_eq!(provider.separator, "__");
         assert!(provider.ignore_empty);
-        assert!(provider.lowercase_keys);
+        assert_eq!(provider.case, EnvCase::Snake);
+    }
+
+    #[test]
+    fn test_env_case_conversions() {
+        assert_eq!(EnvCase::Snake.key_segment("MAX_CONNS"), "max_conns");
+        assert_eq!(EnvCase::ScreamingSnake.key_segment("max_conns"), "MAX_CONNS");
+        assert_eq!(EnvCase::Kebab.key_segment("MAX_CONNS"), "max-conns");
+        assert_eq!(EnvCase::Camel.key_segment("MAX_CONNS"), "maxConns");
+        assert_eq!(EnvCase::Preserve.key_segment("Max_Conns"), "Max_Conns");
+
+        assert_eq!(EnvCase::Kebab.env_segment("max-conns"), "MAX_CONNS");
+        assert_eq!(EnvCase::Camel.env_segment("maxConns"), "MAX_CONNS");
+        assert_eq!(EnvCase::Snake.env_segment("max_conns"), "MAX_CONNS");
+
+        assert_eq!(EnvCase::parse("screaming-snake"), Some(EnvCase::ScreamingSnake));
+        assert_eq!(EnvCase::parse("pascal"), None);
+    }
+
+    #[test]
+    fn test_single_underscore_separator_uses_known_keys() {
+        let snapshot = EnvSnapshot::from_vars([
+            ("APP_DATABASE_MAX_CONNS".to_string(), "9".to_string()),
+            ("APP_WORKER_THREADS".to_string(), "4".to_string()),
+            ("APP_EXTRA_VALUE".to_string(), "x".to_string()),
+        ]);
+        let provider = QuantumConfigEnvProvider::with_prefix("APP_")
+            .with_separator("_")
+            .with_key_paths(["database.max_conns", "database", "worker_threads"])
+            .with_snapshot(snapshot);
+
+        let dict = provider.read_env_vars().unwrap();
+        let value = serde_json::to_value(&dict).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "database": {"max_conns": 9},
+                "worker_threads": 4,
+                // 不匹配已知键的变量按分隔符拆分
+                "extra": {"value": "x"}
+            })
+        );
+
+        // 错误信息按同样的规则还原变量名
+        let metadata = provider.metadata();
+        let name = metadata.interpolate(&Profile::Default, &["database", "max_conns"]);
+        assert_eq!(name, "APP_DATABASE_MAX_CONNS");
+    }
+
+    #[test]
+    fn test_kebab_case_keys() {
+        let snapshot = EnvSnapshot::from_vars([("APP_HTTP_SERVER__BIND_ADDR".to_string(), "0.0.0.0".to_string())]);
+        let provider = QuantumConfigEnvProvider::with_prefix("APP_")
+            .with_case(EnvCase::Kebab)
+            .with_snapshot(snapshot);
+
+        let value = serde_json::to_value(provider.read_env_vars().unwrap()).unwrap();
+        assert_eq!(value, serde_json::json!({"http-server": {"bind-addr": "0.0.0.0"}}));
     }
 
     #[test]
@@ -322,9 +909,9 @@ mod tests {
         }
 
         // 测试浮点数
-        let result = provider.parse_env_value("3.14".to_string()).unwrap();
+        let result = provider.parse_env_value("2.5".to_string()).unwrap();
         match result {
-            Value::Num(_, figment::value::Num::F64(f)) if (f - 3.14).abs() < f64::EPSILON => {}
+            Value::Num(_, figment::value::Num::F64(f)) if (f - 2.5).abs() < f64::EPSILON => {}
             _ => panic!("Expected f64 number"),
         }
     }
@@ -340,12 +927,18 @@ mod tests {
         }
     }
 
+    /// 按默认分隔符拆分的键路径段
+    fn segments(key: &str) -> Vec<String> {
+        key.split("__").map(str::to_string).collect()
+    }
+
     #[test]
-    fn test_insert_nested_value_simple() {
+    fn test_insert_segments_simple() {
         let provider = QuantumConfigEnvProvider::with_prefix("TEST_");
-        let mut map = Map::new();
+        let mut trie = KeyTrie::new();
 
-        provider.insert_nested_value(&mut map, "key", "value".to_string()).unwrap();
+        provider.insert_segments(&mut trie, &segments("key"), "value".to_string(), None).unwrap();
+        let map = trie.into_dict();
 
         assert!(map.contains_key("key"));
         match map.get("key").unwrap() {
@@ -355,11 +948,12 @@ mod tests {
     }
 
     #[test]
-    fn test_insert_nested_value_nested() {
+    fn test_insert_segments_nested() {
         let provider = QuantumConfigEnvProvider::with_prefix("TEST_");
-        let mut map = Map::new();
+        let mut trie = KeyTrie::new();
 
-        provider.insert_nested_value(&mut map, "section__key", "value".to_string()).unwrap();
+        provider.insert_segments(&mut trie, &segments("section__key"), "value".to_string(), None).unwrap();
+        let map = trie.into_dict();
 
         assert!(map.contains_key("section"));
         match map.get("section").unwrap() {
@@ -375,11 +969,12 @@ mod tests {
     }
 
     #[test]
-    fn test_insert_nested_value_deep_nesting() {
+    fn test_insert_segments_deep_nesting() {
         let provider = QuantumConfigEnvProvider::with_prefix("TEST_");
-        let mut map = Map::new();
+        let mut trie = KeyTrie::new();
 
-        provider.insert_nested_value(&mut map, "a__b__c__d", "deep_value".to_string()).unwrap();
+        provider.insert_segments(&mut trie, &segments("a__b__c__d"), "deep_value".to_string(), None).unwrap();
+        let map = trie.into_dict();
 
         // 验证深度嵌套结构
         let a = map.get("a").unwrap();
@@ -408,15 +1003,15 @@ mod tests {
     }
 
     #[test]
-    fn test_insert_nested_value_conflict() {
+    fn test_insert_segments_conflict() {
         let provider = QuantumConfigEnvProvider::with_prefix("TEST_");
-        let mut map = Map::new();
+        let mut trie = KeyTrie::new();
 
         // 先插入一个简单值
-        provider.insert_nested_value(&mut map, "key", "value".to_string()).unwrap();
+        provider.insert_segments(&mut trie, &segments("key"), "value".to_string(), None).unwrap();
 
         // 尝试插入嵌套值，应该失败
-        let result = provider.insert_nested_value(&mut map, "key__nested", "nested_value".to_string());
+        let result = provider.insert_segments(&mut trie, &segments("key__nested"), "nested_value".to_string(), None);
         assert!(result.is_err());
 
         match result.unwrap_err() {
@@ -523,4 +1118,289 @@ mod tests {
         // 清理环境变量
         unsafe { env::remove_var("quantum_config_PRESERVE_UPPER_KEY"); }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_extraction_error_names_env_var() {
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct Server {
+            port: u16,
+        }
+
+        #[derive(Debug, serde::Deserialize)]
+        #[allow(dead_code)]
+        struct AppConfig {
+            server: Server,
+        }
+
+        unsafe { env::set_var("quantum_config_ORIGIN_SERVER__PORT", "not-a-port"); }
+
+        let provider = QuantumConfigEnvProvider::with_prefix("quantum_config_ORIGIN_");
+        let error: QuantumConfigError = figment::Figment::from(provider)
+            .extract::<AppConfig>()
+            .unwrap_err()
+            .into();
+
+        unsafe { env::remove_var("quantum_config_ORIGIN_SERVER__PORT"); }
+
+        match error {
+            QuantumConfigError::Extraction { key_path, origin_key, .. } => {
+                assert_eq!(key_path, "server.port");
+                assert_eq!(origin_key, "quantum_config_ORIGIN_SERVER__PORT");
+            }
+            other => panic!("Expected Extraction error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_file_suffix_reads_value_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let cert_path = dir.path().join("cert.pem");
+        std::fs::write(&cert_path, "-----BEGIN CERTIFICATE-----\nabc\n").unwrap();
+
+        unsafe {
+            env::set_var("QC_FILESUFFIX_TLS__CERT_FILE", &cert_path);
+            env::set_var("QC_FILESUFFIX_NAME", "app");
+        }
+
+        let provider = QuantumConfigEnvProvider::with_prefix("QC_FILESUFFIX_").with_file_suffix("_FILE");
+        let result = provider.read_env_vars();
+
+        unsafe {
+            env::remove_var("QC_FILESUFFIX_TLS__CERT_FILE");
+            env::remove_var("QC_FILESUFFIX_NAME");
+        }
+
+        let map = result.unwrap();
+        match map.get("tls") {
+            Some(Value::Dict(_, tls)) => match tls.get("cert") {
+                Some(Value::String(_, s)) => assert_eq!(s, "-----BEGIN CERTIFICATE-----\nabc"),
+                other => panic!("Expected cert string, got {:?}", other),
+            },
+            other => panic!("Expected tls dictionary, got {:?}", other),
+        }
+        assert!(map.contains_key("name"));
+    }
+
+    #[test]
+    fn test_max_value_len_is_configurable_per_key() {
+        let long_value = "A".repeat(DEFAULT_MAX_ENV_VALUE_LEN + 1);
+        unsafe { env::set_var("QC_MAXLEN_TLS__CERT", &long_value); }
+
+        let default_result = QuantumConfigEnvProvider::with_prefix("QC_MAXLEN_").read_env_vars();
+        let key_result = QuantumConfigEnvProvider::with_prefix("QC_MAXLEN_")
+            .with_key_max_value_len("tls.cert", 16 * 1024)
+            .read_env_vars();
+        let provider_result = QuantumConfigEnvProvider::with_prefix("QC_MAXLEN_")
+            .with_max_value_len(16)
+            .with_key_max_value_len("tls.cert", 16 * 1024)
+            .read_env_vars();
+        let strict_result = QuantumConfigEnvProvider::with_prefix("QC_MAXLEN_")
+            .with_max_value_len(16)
+            .read_env_vars();
+
+        unsafe { env::remove_var("QC_MAXLEN_TLS__CERT"); }
+
+        assert!(matches!(default_result, Err(QuantumConfigError::ValidationError(_))));
+        assert!(key_result.is_ok());
+        assert!(provider_result.is_ok());
+        assert!(strict_result.is_err());
+    }
+
+    #[test]
+    fn test_keys_scope_only_reads_schema_vars() {
+        #[derive(Serialize, Default)]
+        struct Server {
+            port: u16,
+        }
+
+        #[derive(Serialize, Default)]
+        struct AppConfig {
+            name: String,
+            server: Server,
+        }
+
+        let scope = EnvScanScope::from_value(&AppConfig::default()).unwrap();
+        assert_eq!(scope, EnvScanScope::Keys(vec!["name".to_string(), "server.port".to_string()]));
+
+        unsafe {
+            env::set_var("QC_SCOPE_SERVER__PORT", "8080");
+            env::set_var("QC_SCOPE_UNRELATED", "ignored");
+        }
+        let result = QuantumConfigEnvProvider::with_prefix("QC_SCOPE_")
+            .with_scan_scope(scope)
+            .read_env_vars();
+        unsafe {
+            env::remove_var("QC_SCOPE_SERVER__PORT");
+            env::remove_var("QC_SCOPE_UNRELATED");
+        }
+
+        let map = result.unwrap();
+        assert!(map.contains_key("server"));
+        assert!(!map.contains_key("unrelated"));
+        assert!(!map.contains_key("name"));
+    }
+
+    #[test]
+    fn test_key_trie_records_env_var_origins() {
+        let snapshot = EnvSnapshot::from_vars([
+            ("QC_TRIE_DATABASE__HOST".to_string(), "db".to_string()),
+            ("QC_TRIE_DATABASE__PORT".to_string(), "5432".to_string()),
+            ("QC_TRIE_NAME".to_string(), "app".to_string()),
+        ]);
+        let provider = QuantumConfigEnvProvider::with_prefix("QC_TRIE_").with_snapshot(snapshot);
+
+        let trie = provider.key_trie().unwrap();
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.origin("database.port"), Some("QC_TRIE_DATABASE__PORT"));
+        assert_eq!(trie.origin("name"), Some("QC_TRIE_NAME"));
+        assert!(matches!(trie.get("database.port"), Some(Value::Num(_, figment::value::Num::I64(5432)))));
+    }
+
+    #[test]
+    fn test_env_source_is_used_instead_of_process_env() {
+        let source = super::super::MapEnv::new()
+            .with_var("QC_ENVSOURCE_DATABASE__PORT", "5432")
+            .with_var("OTHER_PORT", "1");
+        let provider = QuantumConfigEnvProvider::with_prefix("QC_ENVSOURCE_").with_env_source(source.clone());
+        let vars = provider.read_env_vars().unwrap();
+        assert_eq!(vars.len(), 1);
+        assert_eq!(vars["database"].find_ref("port").and_then(Value::to_i128), Some(5432));
+
+        let provider = QuantumConfigEnvProvider::with_prefix("QC_ENVSOURCE_")
+            .with_scan_scope(EnvScanScope::Keys(vec!["database.port".to_string(), "host".to_string()]))
+            .with_env_source(source);
+        assert_eq!(provider.read_env_vars().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_snapshot_is_reused_within_generation() {
+        unsafe { env::set_var("QC_SNAPSHOT_PORT", "8080"); }
+        let snapshot = EnvSnapshot::cached("QC_SNAPSHOT_");
+        unsafe { env::set_var("QC_SNAPSHOT_PORT", "9090"); }
+
+        // 同一代内复用快照，看不到新的值
+        let provider = QuantumConfigEnvProvider::with_prefix("QC_SNAPSHOT_").with_snapshot(snapshot.clone());
+        let port = provider.read_env_vars().unwrap().remove("port");
+        assert!(matches!(port, Some(Value::Num(_, figment::value::Num::I64(8080)))));
+        assert_eq!(EnvSnapshot::cached("QC_SNAPSHOT_").get("QC_SNAPSHOT_PORT"), Some("8080"));
+
+        EnvSnapshot::invalidate();
+        let refreshed = EnvSnapshot::cached("QC_SNAPSHOT_");
+        unsafe { env::remove_var("QC_SNAPSHOT_PORT"); }
+
+        assert!(refreshed.generation() > snapshot.generation());
+        assert_eq!(refreshed.get("QC_SNAPSHOT_PORT"), Some("9090"));
+        assert_eq!(refreshed.len(), 1);
+    }
+
+    #[test]
+    fn test_file_suffix_conflicts_and_missing_files() {
+        unsafe {
+            env::set_var("QC_FILECONFLICT_TOKEN", "inline");
+            env::set_var("QC_FILECONFLICT_TOKEN_FILE", "/nonexistent/token");
+        }
+        let result = QuantumConfigEnvProvider::with_prefix("QC_FILECONFLICT_")
+            .with_file_suffix("_FILE")
+            .read_env_vars();
+        unsafe { env::remove_var("QC_FILECONFLICT_TOKEN"); }
+        assert!(matches!(result, Err(QuantumConfigError::ValidationError(_))));
+
+        let result = QuantumConfigEnvProvider::with_prefix("QC_FILECONFLICT_")
+            .with_file_suffix("_FILE")
+            .read_env_vars();
+        assert!(matches!(result, Err(QuantumConfigError::FileReadError { path, .. }) if path == "/nonexistent/token"));
+
+        // 未启用时后缀变量按普通变量处理
+        let result = QuantumConfigEnvProvider::with_prefix("QC_FILECONFLICT_").read_env_vars();
+        unsafe { env::remove_var("QC_FILECONFLICT_TOKEN_FILE"); }
+        assert!(result.unwrap().contains_key("token_file"));
+    }
+
+    #[test]
+    fn test_list_parsing_with_separator_json_and_key_override() {
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        struct ListConfig {
+            features: Vec<String>,
+            ports: Vec<u16>,
+            hosts: Vec<String>,
+            name: String,
+        }
+
+        let snapshot = EnvSnapshot::from_vars([
+            ("QC_LIST_FEATURES".to_string(), "auth, metrics,".to_string()),
+            ("QC_LIST_PORTS".to_string(), "[8080, 9090]".to_string()),
+            ("QC_LIST_HOSTS".to_string(), "db.internal".to_string()),
+            ("QC_LIST_NAME".to_string(), "app".to_string()),
+        ]);
+        let provider = QuantumConfigEnvProvider::with_prefix("QC_LIST_").with_snapshot(snapshot);
+
+        // 未启用时按标量解析
+        assert!(figment::Figment::from(provider.clone()).extract::<ListConfig>().is_err());
+
+        let provider = provider.with_list_separator(",").with_key_list_separator("hosts", ";");
+        let config: ListConfig = figment::Figment::from(provider).extract().unwrap();
+        assert_eq!(
+            config,
+            ListConfig {
+                features: vec!["auth".to_string(), "metrics".to_string()],
+                ports: vec![8080, 9090],
+                hosts: vec!["db.internal".to_string()],
+                name: "app".to_string(),
+            }
+        );
+
+        assert_eq!(parse_list_value(String::new(), ",", true), Value::Array(figment::value::Tag::Default, Vec::new()));
+        assert!(matches!(parse_list_value("[not json".to_string(), ";", false), Value::String(..)));
+    }
+
+    #[test]
+    fn test_raw_string_keys_keep_original_text() {
+        let snapshot = EnvSnapshot::from_vars([
+            ("QC_RAW_PASSWORD".to_string(), "007".to_string()),
+            ("QC_RAW_DB__PIN".to_string(), "on".to_string()),
+            ("QC_RAW_PORT".to_string(), "007".to_string()),
+        ]);
+        let provider = QuantumConfigEnvProvider::with_prefix("QC_RAW_")
+            .with_snapshot(snapshot)
+            .with_key_raw_string("password")
+            .with_key_raw_string("db.pin");
+        let data = provider.read_env_vars().unwrap();
+
+        assert_eq!(data.get("password"), Some(&Value::String(figment::value::Tag::Default, "007".to_string())));
+        let pin = data.get("db").and_then(Value::as_dict).and_then(|db| db.get("pin"));
+        assert_eq!(pin, Some(&Value::String(figment::value::Tag::Default, "on".to_string())));
+        assert_eq!(data.get("port").and_then(Value::to_i128), Some(7));
+    }
+
+    #[test]
+    fn test_map_values_from_nested_vars_and_json_literals() {
+        #[derive(Debug, serde::Deserialize, PartialEq)]
+        struct MapConfig {
+            labels: std::collections::BTreeMap<String, String>,
+            annotations: std::collections::BTreeMap<String, String>,
+            raw: String,
+        }
+
+        let snapshot = EnvSnapshot::from_vars([
+            ("QC_MAP_LABELS__REGION".to_string(), "eu".to_string()),
+            ("QC_MAP_LABELS__TEAM".to_string(), "core".to_string()),
+            ("QC_MAP_ANNOTATIONS".to_string(), r#"{"owner": "ops"}"#.to_string()),
+            ("QC_MAP_RAW".to_string(), r#"{"kept": "as text"}"#.to_string()),
+        ]);
+        let provider = QuantumConfigEnvProvider::with_prefix("QC_MAP_")
+            .with_snapshot(snapshot)
+            .with_key_json_values("annotations");
+        let config: MapConfig = figment::Figment::from(provider.clone()).extract().unwrap();
+        assert_eq!(config.labels.get("region").map(String::as_str), Some("eu"));
+        assert_eq!(config.labels.get("team").map(String::as_str), Some("core"));
+        assert_eq!(config.annotations.get("owner").map(String::as_str), Some("ops"));
+        assert_eq!(config.raw, r#"{"kept": "as text"}"#);
+
+        // 全局启用后所有 JSON 字面量都按结构化值解析
+        let data = provider.with_json_values(true).read_env_vars().unwrap();
+        assert!(matches!(data.get("raw"), Some(Value::Dict(..))));
+        assert_eq!(parse_json_literal("{not json}"), None);
+        assert_eq!(parse_json_literal("\"quoted\""), None);
+    }
+}
@@ -0,0 +1,116 @@
+//! 环境变量来源抽象层
+//!
+//! 环境变量是进程级的全局状态，并行运行的测试互相修改时会相互干扰。
+//! [`QuantumConfigEnvProvider`](super::QuantumConfigEnvProvider) 通过 [`EnvSource`] 读取变量，
+//! 默认使用进程环境（[`ProcessEnv`]）；测试中可以注入 [`MapEnv`]，无需修改真实的进程环境：
+//!
+//! ```ignore
+//! let env = MapEnv::new().with_var("APP_PORT", "9090");
+//! let config: AppConfig = QuantumConfigBuilder::new("app")
+//!     .with_env_prefix("APP_")
+//!     .with_env_source(env)
+//!     .load()?;
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// 环境变量来源trait
+pub trait EnvSource: Send + Sync + fmt::Debug {
+    /// 全部变量，值不是有效 UTF-8 的变量被忽略
+    fn vars(&self) -> Vec<(String, String)>;
+
+    /// 读取单个变量
+    ///
+    /// 默认实现遍历 [`EnvSource::vars`]，实现者可以按需提供更高效的查询。
+    fn var(&self, name: &str) -> Option<String> {
+        self.vars().into_iter().find(|(key, _)| key == name).map(|(_, value)| value)
+    }
+}
+
+impl<S: EnvSource + ?Sized> EnvSource for Arc<S> {
+    fn vars(&self) -> Vec<(String, String)> {
+        (**self).vars()
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        (**self).var(name)
+    }
+}
+
+/// 进程环境
+///
+/// 这是默认的环境变量来源。
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessEnv;
+
+impl EnvSource for ProcessEnv {
+    fn vars(&self) -> Vec<(String, String)> {
+        std::env::vars_os()
+            .filter_map(|(key, value)| Some((key.into_string().ok()?, value.into_string().ok()?)))
+            .collect()
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        std::env::var(name).ok()
+    }
+}
+
+/// 内存中的环境变量
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MapEnv {
+    /// 变量名到值的映射
+    vars: HashMap<String, String>,
+}
+
+impl MapEnv {
+    /// 创建空的环境
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置变量
+    pub fn with_var<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.vars.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for MapEnv {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self {
+            vars: iter.into_iter().map(|(key, value)| (key.into(), value.into())).collect(),
+        }
+    }
+}
+
+impl EnvSource for MapEnv {
+    fn vars(&self) -> Vec<(String, String)> {
+        self.vars.iter().map(|(key, value)| (key.clone(), value.clone())).collect()
+    }
+
+    fn var(&self, name: &str) -> Option<String> {
+        self.vars.get(name).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_env_lookup() {
+        let env: MapEnv = [("APP_PORT", "8080"), ("APP_HOST", "localhost")].into_iter().collect();
+        let env = env.with_var("APP_PORT", "9090");
+        assert_eq!(env.var("APP_PORT").as_deref(), Some("9090"));
+        assert_eq!(env.var("APP_MISSING"), None);
+
+        let mut vars = Arc::new(env).vars();
+        vars.sort();
+        assert_eq!(
+            vars,
+            [("APP_HOST".to_string(), "localhost".to_string()), ("APP_PORT".to_string(), "9090".to_string())]
+        );
+    }
+}
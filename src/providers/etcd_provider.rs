@@ -0,0 +1,531 @@
+//! etcd v3 配置提供器
+//!
+//! 通过 etcd 的 gRPC-gateway JSON 接口（`/v3/kv/range`）读取某个键前缀下的全部键，
+//! 去除前缀后按 `/` 拆分为嵌套键，例如前缀 `/myapp/prod/` 下的 `/myapp/prod/database/host`
+//! 对应 `database.host`。值按环境变量的规则解析为布尔值、数字或字符串。
+//!
+//! [`EtcdProvider::watch`] 订阅前缀下的变更，返回的通知通道可以交给
+//! `ConfigWatcher::from_notifications`（`watch` 特性）驱动热重载。连接断开后后台线程按
+//! [`EtcdProvider::with_reconnect_backoff`] 的退避从最后收到的修订号继续监视：
+//!
+//! ```ignore
+//! use quantum_config::providers::EtcdProvider;
+//! use quantum_config::watch::ConfigWatcher;
+//!
+//! let etcd = EtcdProvider::new("http://127.0.0.1:2379", "/myapp/prod/");
+//! let notifications = etcd.watch()?;
+//! let watcher = ConfigWatcher::<AppConfig>::from_notifications(notifications, move || {
+//!     Figment::from(etcd.clone())
+//! });
+//! ```
+
+use super::env_provider::parse_scalar_value;
+use super::key_trie::KeyTrie;
+use super::remote::{self, describe_error, Backoff};
+use crate::error::QuantumConfigError;
+use crate::logging::{log_info, log_warn};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use figment::value::{Dict, Map};
+use figment::{Error, Metadata, Profile, Provider};
+use serde_json::Value as JsonValue;
+use std::io::{BufRead, BufReader};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+/// 默认请求超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 默认的键层级分隔符
+const DEFAULT_SEPARATOR: &str = "/";
+
+/// etcd v3 配置提供器
+#[derive(Clone)]
+pub struct EtcdProvider {
+    /// etcd 地址，例如 "http://127.0.0.1:2379"
+    endpoint: String,
+    /// 读取的键前缀，例如 "/myapp/prod/"
+    prefix: String,
+    /// 键层级分隔符
+    separator: String,
+    /// 用户名与密码认证
+    credentials: Option<(String, String)>,
+    /// 请求超时
+    timeout: Duration,
+    /// 监视断开后的重连退避
+    reconnect_backoff: Backoff,
+}
+
+impl std::fmt::Debug for EtcdProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密码
+        f.debug_struct("EtcdProvider")
+            .field("endpoint", &self.endpoint)
+            .field("prefix", &self.prefix)
+            .field("separator", &self.separator)
+            .field("user", &self.credentials.as_ref().map(|(user, _)| user))
+            .field("timeout", &self.timeout)
+            .field("reconnect_backoff", &self.reconnect_backoff)
+            .finish()
+    }
+}
+
+/// 一次范围读取的结果
+struct RangeResult {
+    /// 前缀下的键前缀树
+    trie: KeyTrie,
+    /// 读取时的存储修订号
+    revision: i64,
+}
+
+/// 监视流结束的原因
+enum StreamEnd {
+    /// 接收端已丢弃
+    ReceiverDropped,
+    /// 连接断开或监视被取消
+    Disconnected(String),
+    /// 请求的修订号已被压缩，断开期间的变更无法逐条得知
+    Compacted,
+}
+
+impl EtcdProvider {
+    /// 创建读取 `prefix` 下所有键的提供器
+    pub fn new<S: Into<String>, P: Into<String>>(endpoint: S, prefix: P) -> Self {
+        Self {
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            prefix: prefix.into(),
+            separator: DEFAULT_SEPARATOR.to_string(),
+            credentials: None,
+            timeout: DEFAULT_TIMEOUT,
+            reconnect_backoff: Backoff::new(remote::DEFAULT_RECONNECT_BACKOFF, remote::DEFAULT_MAX_RECONNECT_BACKOFF),
+        }
+    }
+
+    /// 设置键层级分隔符
+    pub fn with_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// 使用 etcd 用户名与密码认证
+    pub fn with_credentials<U: Into<String>, P: Into<String>>(mut self, user: U, password: P) -> Self {
+        self.credentials = Some((user.into(), password.into()));
+        self
+    }
+
+    /// 设置请求超时（不限制 [`EtcdProvider::watch`] 的长连接）
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置 [`EtcdProvider::watch`] 断开后的重连退避
+    ///
+    /// 首次重连前等待 `initial`，之后每次失败加倍，不超过 `max`；默认为 1 秒与 60 秒。
+    pub fn with_reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.reconnect_backoff = Backoff::new(initial, max);
+        self
+    }
+
+    /// 读取前缀下的全部键并构建键前缀树，叶子来源为 etcd 中的完整键名
+    pub fn key_trie(&self) -> Result<KeyTrie, QuantumConfigError> {
+        self.range().map(|result| result.trie)
+    }
+
+    /// 订阅前缀下的变更
+    ///
+    /// 先读取一次当前修订号，再从下一个修订号开始监视，每批变更事件发送一条通知。
+    /// 连接断开或监视被取消时记录警告，按重连退避从最后收到的事件的下一个修订号重新监视，断开期间的变更不会丢失；
+    /// 该修订号已被压缩时重新读取当前修订号并发送一条通知。接收端被丢弃后后台线程在下一次发送通知时退出。
+    ///
+    /// # Errors
+    /// 无法读取当前修订号或建立监视连接时返回错误
+    pub fn watch(&self) -> Result<Receiver<()>, QuantumConfigError> {
+        let mut next_revision = self.range()?.revision + 1;
+        let mut response = self.open_watch(next_revision)?;
+
+        let provider = self.clone();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut backoff = provider.reconnect_backoff;
+            loop {
+                let resync = match forward_events(response, &mut next_revision, &sender) {
+                    StreamEnd::ReceiverDropped => return,
+                    StreamEnd::Disconnected(reason) => {
+                        log_warn!("etcd watch on prefix '{}' disconnected, reconnecting: {}", provider.prefix, reason);
+                        false
+                    }
+                    StreamEnd::Compacted => {
+                        log_warn!(
+                            "etcd watch on prefix '{}' cannot resume from compacted revision {}, reloading",
+                            provider.prefix,
+                            next_revision
+                        );
+                        true
+                    }
+                };
+                response = provider.reconnect(&mut backoff, &mut next_revision, resync);
+                if resync && sender.send(()).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(receiver)
+    }
+
+    /// 从 `start_revision` 开始监视前缀，返回流式响应
+    fn open_watch(&self, start_revision: i64) -> Result<ureq::http::Response<ureq::Body>, QuantumConfigError> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_connect(Some(self.timeout))
+            .build()
+            .into();
+        let token = self.token(&agent)?;
+        let (range_key, range_end) = self.range_keys();
+        let payload = serde_json::json!({
+            "create_request": {
+                "key": range_key,
+                "range_end": range_end,
+                "start_revision": start_revision.to_string(),
+            }
+        });
+        self.post(&agent, "watch", &payload, token.as_deref())
+            .map_err(|e| self.error(describe_error(e, STATUS_HINTS)))
+    }
+
+    /// 按退避重新建立监视，直到成功；`resync` 时先重新读取当前修订号
+    fn reconnect(
+        &self,
+        backoff: &mut Backoff,
+        next_revision: &mut i64,
+        resync: bool,
+    ) -> ureq::http::Response<ureq::Body> {
+        loop {
+            std::thread::sleep(backoff.next_delay());
+            let start_revision = if resync {
+                self.range().map(|result| result.revision + 1)
+            } else {
+                Ok(*next_revision)
+            };
+            match start_revision.and_then(|revision| Ok((revision, self.open_watch(revision)?))) {
+                Ok((revision, response)) => {
+                    log_info!("etcd watch on prefix '{}' resumed from revision {}", self.prefix, revision);
+                    backoff.reset();
+                    *next_revision = revision;
+                    return response;
+                }
+                Err(e) => log_warn!("etcd watch reconnect failed: {}", e),
+            }
+        }
+    }
+
+    /// 前缀范围读取的起止键（base64 编码）
+    fn range_keys(&self) -> (String, String) {
+        let key = self.prefix.as_bytes().to_vec();
+        // 前缀的范围终点为最后一个非 0xff 字节加一；前缀为空或全为 0xff 时读取全部键
+        let mut end = key.clone();
+        while let Some(last) = end.pop() {
+            if last < 0xff {
+                end.push(last + 1);
+                break;
+            }
+        }
+        if end.is_empty() {
+            end.push(0);
+        }
+        let key = if key.is_empty() { vec![0] } else { key };
+        (BASE64.encode(key), BASE64.encode(end))
+    }
+
+    /// 读取前缀下的全部键
+    fn range(&self) -> Result<RangeResult, QuantumConfigError> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .into();
+        let token = self.token(&agent)?;
+        let (key, range_end) = self.range_keys();
+        let response = self.request_json(
+            &agent,
+            "kv/range",
+            &serde_json::json!({ "key": key, "range_end": range_end }),
+            token.as_deref(),
+        )?;
+
+        let revision = response
+            .pointer("/header/revision")
+            .and_then(json_i64)
+            .ok_or_else(|| self.error("response does not contain a revision".to_string()))?;
+        let mut trie = KeyTrie::new();
+        for kv in response.get("kvs").and_then(JsonValue::as_array).into_iter().flatten() {
+            let key = decode_field(kv, "key").map_err(|reason| self.error(reason))?;
+            let value = decode_field(kv, "value").map_err(|reason| self.error(reason))?;
+            let Some(relative) = key.strip_prefix(self.prefix.as_str()) else {
+                continue;
+            };
+            // 忽略前缀本身以及由分隔符产生的空段，例如 "/myapp/prod//host"
+            let segments: Vec<&str> = relative.split(self.separator.as_str()).filter(|s| !s.is_empty()).collect();
+            if segments.is_empty() {
+                continue;
+            }
+            trie.insert(segments, parse_scalar_value(value), Some(key.clone()))?;
+        }
+        Ok(RangeResult { trie, revision })
+    }
+
+    /// 配置了认证信息时登录并返回 Token
+    fn token(&self, agent: &ureq::Agent) -> Result<Option<String>, QuantumConfigError> {
+        let Some((user, password)) = &self.credentials else {
+            return Ok(None);
+        };
+        let response = self
+            .request_json(agent, "auth/authenticate", &serde_json::json!({ "name": user, "password": password }), None)
+            .map_err(|e| match e {
+                QuantumConfigError::Remote { service, resource, reason } => QuantumConfigError::Remote {
+                    service,
+                    resource,
+                    reason: format!("authentication failed: {}", reason),
+                },
+                other => other,
+            })?;
+        let token = response
+            .get("token")
+            .and_then(JsonValue::as_str)
+            .ok_or_else(|| self.error("authentication response does not contain a token".to_string()))?;
+        crate::scrub::replace_secret(&format!("etcd:{}:token", self.endpoint), token);
+        Ok(Some(token.to_string()))
+    }
+
+    /// 向 `/v3/{path}` 发送 JSON POST 请求
+    fn post(
+        &self,
+        agent: &ureq::Agent,
+        path: &str,
+        payload: &JsonValue,
+        token: Option<&str>,
+    ) -> Result<ureq::http::Response<ureq::Body>, ureq::Error> {
+        let mut request = agent
+            .post(format!("{}/v3/{}", self.endpoint, path))
+            .header("Content-Type", "application/json");
+        if let Some(token) = token {
+            request = request.header("Authorization", token);
+        }
+        request.send(&payload.to_string())
+    }
+
+    /// 发送 POST 请求并解析 JSON 响应
+    fn request_json(
+        &self,
+        agent: &ureq::Agent,
+        path: &str,
+        payload: &JsonValue,
+        token: Option<&str>,
+    ) -> Result<JsonValue, QuantumConfigError> {
+        let body = self
+            .post(agent, path, payload, token)
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| self.error(describe_error(e, STATUS_HINTS)))?;
+        serde_json::from_str(&body).map_err(|e| self.error(format!("invalid response: {}", e)))
+    }
+
+    /// 构造带有键前缀的错误
+    fn error(&self, reason: String) -> QuantumConfigError {
+        remote::error("etcd", self.prefix.clone(), reason)
+    }
+}
+
+/// 逐行读取监视流，每批变更事件发送一条通知，并把 `next_revision` 推进到最后一个事件的下一个修订号
+fn forward_events(
+    response: ureq::http::Response<ureq::Body>,
+    next_revision: &mut i64,
+    sender: &mpsc::Sender<()>,
+) -> StreamEnd {
+    let reader = BufReader::new(response.into_body().into_reader());
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => return StreamEnd::Disconnected(e.to_string()),
+        };
+        let Ok(message) = serde_json::from_str::<JsonValue>(&line) else {
+            continue;
+        };
+        let Some(result) = message.get("result") else {
+            continue;
+        };
+        if result.get("compact_revision").and_then(json_i64).is_some_and(|revision| revision > 0) {
+            return StreamEnd::Compacted;
+        }
+        if result.get("canceled").and_then(JsonValue::as_bool) == Some(true) {
+            let reason = result.get("cancel_reason").and_then(JsonValue::as_str).unwrap_or("watch canceled");
+            return StreamEnd::Disconnected(reason.to_string());
+        }
+        // 创建确认与进度通知不含事件，只有实际变更才触发重载
+        let events = result.get("events").and_then(JsonValue::as_array).map(Vec::as_slice).unwrap_or_default();
+        if events.is_empty() {
+            continue;
+        }
+        if let Some(revision) = events.iter().filter_map(|event| json_i64(event.pointer("/kv/mod_revision")?)).max() {
+            *next_revision = (*next_revision).max(revision + 1);
+        }
+        if sender.send(()).is_err() {
+            return StreamEnd::ReceiverDropped;
+        }
+    }
+    StreamEnd::Disconnected("connection closed by the server".to_string())
+}
+
+/// gRPC-gateway 把 int64 编码为字符串，兼容两种表示
+fn json_i64(value: &JsonValue) -> Option<i64> {
+    value.as_i64().or_else(|| value.as_str()?.parse().ok())
+}
+
+/// 解码键值对中 base64 编码的字段，缺失的字段（例如空值）视为空字符串
+fn decode_field(kv: &JsonValue, field: &str) -> Result<String, String> {
+    let Some(encoded) = kv.get(field).and_then(JsonValue::as_str) else {
+        return Ok(String::new());
+    };
+    let bytes = BASE64
+        .decode(encoded)
+        .map_err(|e| format!("invalid base64 in {}: {}", field, e))?;
+    String::from_utf8(bytes).map_err(|_| format!("{} is not valid UTF-8", field))
+}
+
+/// 常见状态码的说明
+const STATUS_HINTS: &[(u16, &str)] = &[(401, "unauthenticated"), (403, "permission denied")];
+
+impl Provider for EtcdProvider {
+    fn metadata(&self) -> Metadata {
+        // 错误信息中的键路径还原为 etcd 中的完整键名
+        let prefix = self.prefix.clone();
+        let separator = self.separator.clone();
+        Metadata::named(format!("etcd ({}{})", self.endpoint, self.prefix))
+            .interpolater(move |_profile, keys| format!("{}{}", prefix, keys.join(&separator)))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let trie = self.key_trie().map_err(|e| remote::provider_error(e, None))?;
+        let mut data = Map::new();
+        data.insert(Profile::Default, trie.into_dict());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::Figment;
+    use crate::providers::test_server::serve;
+    use serde::Deserialize;
+
+    fn range_response(revision: i64, kvs: &[(&str, &str)]) -> String {
+        let kvs: Vec<JsonValue> = kvs
+            .iter()
+            .map(|(key, value)| serde_json::json!({ "key": BASE64.encode(key), "value": BASE64.encode(value) }))
+            .collect();
+        serde_json::json!({ "header": { "revision": revision.to_string() }, "kvs": kvs }).to_string()
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        name: String,
+        database: Database,
+    }
+
+    #[test]
+    fn test_reads_prefix_as_nested_config() {
+        let body = range_response(
+            7,
+            &[
+                ("/myapp/prod/name", "etcd-app"),
+                ("/myapp/prod/database/host", "db.internal"),
+                ("/myapp/prod/database/port", "5432"),
+            ],
+        );
+        let (address, requests) = serve(vec![(200, body)]);
+        let provider = EtcdProvider::new(address.as_str(), "/myapp/prod/");
+
+        let config: AppConfig = Figment::from(provider).extract().unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                name: "etcd-app".to_string(),
+                database: Database { host: "db.internal".to_string(), port: 5432 },
+            }
+        );
+
+        let request = requests.recv().unwrap();
+        assert!(request.starts_with("POST /v3/kv/range "));
+        // range_end 为前缀最后一个字节加一："/myapp/prod/" -> "/myapp/prod0"
+        assert!(request.contains(&format!(r#""key":"{}""#, BASE64.encode("/myapp/prod/"))));
+        assert!(request.contains(&format!(r#""range_end":"{}""#, BASE64.encode("/myapp/prod0"))));
+    }
+
+    #[test]
+    fn test_authenticates_before_reading() {
+        let login = r#"{"header":{},"token":"etcd-issued-token"}"#.to_string();
+        let body = range_response(3, &[("app/name", "x"), ("app/database/host", "h"), ("app/database/port", "1")]);
+        let (address, requests) = serve(vec![(200, login), (200, body)]);
+        let provider = EtcdProvider::new(address.as_str(), "app/").with_credentials("root", "etcd-password");
+
+        let trie = provider.key_trie().unwrap();
+        assert_eq!(trie.origin("database.host"), Some("app/database/host"));
+        assert!(!format!("{:?}", provider).contains("etcd-password"));
+
+        let login_request = requests.recv().unwrap();
+        assert!(login_request.starts_with("POST /v3/auth/authenticate "));
+        assert!(login_request.contains(r#""name":"root""#));
+        let read_request = requests.recv().unwrap().to_ascii_lowercase();
+        assert!(read_request.contains("authorization: etcd-issued-token"));
+    }
+
+    #[test]
+    fn test_watch_notifies_on_events() {
+        let stream = concat!(
+            r#"{"result":{"header":{"revision":"7"},"created":true}}"#,
+            "\n",
+            r#"{"result":{"header":{"revision":"8"},"events":[{"kv":{"key":"L215YXBwL25hbWU=","mod_revision":"8"}}]}}"#,
+            "\n",
+        );
+        let compacted = r#"{"result":{"header":{"revision":"20"},"compact_revision":"15","canceled":true}}"#;
+        let (address, requests) = serve(vec![
+            (200, range_response(7, &[])),
+            (200, stream.to_string()),
+            (200, compacted.to_string()),
+            (200, range_response(20, &[])),
+            (200, String::new()),
+        ]);
+        let provider = EtcdProvider::new(address.as_str(), "/myapp/")
+            .with_reconnect_backoff(Duration::from_millis(10), Duration::from_millis(50));
+
+        let notifications = provider.watch().unwrap();
+        notifications.recv_timeout(Duration::from_secs(10)).unwrap();
+        // 修订号被压缩后重新读取并通知
+        notifications.recv_timeout(Duration::from_secs(10)).unwrap();
+
+        requests.recv().unwrap();
+        let watch_request = requests.recv().unwrap();
+        assert!(watch_request.starts_with("POST /v3/watch "));
+        assert!(watch_request.contains(r#""start_revision":"8""#));
+        // 连接断开后从最后一个事件的下一个修订号继续监视
+        assert!(requests.recv().unwrap().contains(r#""start_revision":"9""#));
+        assert!(requests.recv().unwrap().starts_with("POST /v3/kv/range "));
+        assert!(requests.recv().unwrap().contains(r#""start_revision":"21""#));
+    }
+
+    #[test]
+    fn test_reports_etcd_errors() {
+        let (address, _requests) = serve(vec![(401, "{}".to_string())]);
+        let provider = EtcdProvider::new(address.as_str(), "/myapp/");
+        let error = Figment::from(provider).extract::<JsonValue>().unwrap_err();
+        assert!(error.to_string().contains("unauthenticated"));
+
+        let error = EtcdProvider::new("http://127.0.0.1:1", "/myapp/").key_trie().unwrap_err();
+        assert_eq!(error.code(), "etcd");
+        assert_eq!(EtcdProvider::new("", "").range_keys(), (BASE64.encode([0]), BASE64.encode([0])));
+    }
+}
@@ -0,0 +1,319 @@
+//! 命令输出配置提供器
+//!
+//! [`ExecProvider`] 运行配置好的命令（例如 `pass show db/password`），以其标准输出作为配置键的值，
+//! 用于接入密码管理器或自定义的密钥存储：
+//!
+//! ```ignore
+//! use quantum_config::providers::ExecProvider;
+//!
+//! let figment = figment.merge(
+//!     ExecProvider::new()
+//!         .with_allowed_program("pass")
+//!         .with_command("database.password", "pass", ["show", "db/password"]),
+//! );
+//! ```
+//!
+//! - 命令直接执行而不经过 shell，参数原样传递，不会被展开或拆分；
+//! - 只运行允许列表（[`ExecProvider::with_allowed_program`]）中的程序，列表为空时拒绝运行任何命令；
+//! - 每条命令有超时（默认 10 秒），涵盖等待进程结束与读取输出：超时后终止进程并返回错误，
+//!   退出后仍有后台进程占用输出管道时同样在超时后返回错误；
+//! - 命令以非零状态退出时返回错误。`pass`、`vault` 等工具可能把敏感内容写到标准错误输出，
+//!   因此错误信息默认只包含退出状态，[`ExecProvider::with_stderr_in_errors`] 可附带擦除并截断后的标准错误输出；
+//! - 输出去除末尾的换行后作为字符串值，并登记到 [`crate::scrub`]。
+
+use super::key_trie::KeyTrie;
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map, Tag, Value};
+use figment::{Error, Metadata, Profile, Provider};
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// 默认的命令超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 等待命令结束时的轮询间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// 错误信息中附带的标准错误输出的最大字符数，超出部分以 `…` 省略
+const MAX_STDERR_CHARS: usize = 200;
+
+/// 一条命令及其输出写入的配置键
+#[derive(Debug, Clone)]
+struct ExecCommand {
+    /// 以 `.` 分隔的配置键路径
+    key: String,
+    /// 可执行文件名或路径
+    program: String,
+    /// 命令参数
+    args: Vec<String>,
+}
+
+impl ExecCommand {
+    /// 用于错误信息与日志的命令描述
+    fn describe(&self) -> String {
+        std::iter::once(self.program.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// 命令输出配置提供器
+#[derive(Debug, Clone)]
+pub struct ExecProvider {
+    /// 按添加顺序运行的命令
+    commands: Vec<ExecCommand>,
+    /// 允许运行的程序，为空时不运行任何命令
+    allowed_programs: Vec<String>,
+    /// 每条命令的超时
+    timeout: Duration,
+    /// 错误信息是否附带标准错误输出
+    stderr_in_errors: bool,
+}
+
+impl Default for ExecProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExecProvider {
+    /// 创建不包含任何命令的提供器
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            allowed_programs: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+            stderr_in_errors: false,
+        }
+    }
+
+    /// 运行 `program args...`，以其标准输出作为配置键 `key`（以 `.` 分隔）的值
+    pub fn with_command<K, P, I, S>(mut self, key: K, program: P, args: I) -> Self
+    where
+        K: Into<String>,
+        P: Into<String>,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.commands.push(ExecCommand {
+            key: key.into(),
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// 将程序加入允许列表，与命令中的程序名或路径逐字比较
+    ///
+    /// 不在列表中的程序一律拒绝运行，未加入任何程序时所有命令都被拒绝。
+    pub fn with_allowed_program<P: Into<String>>(mut self, program: P) -> Self {
+        self.allowed_programs.push(program.into());
+        self
+    }
+
+    /// 设置每条命令的超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置命令失败时错误信息是否附带标准错误输出（默认不附带）
+    ///
+    /// 附带的内容先擦除已登记的敏感值（参见 [`crate::scrub`]），再截取开头 200 个字符；
+    /// 未登记的敏感内容无法被识别，只应在确认命令不会输出敏感内容时启用。
+    pub fn with_stderr_in_errors(mut self, enabled: bool) -> Self {
+        self.stderr_in_errors = enabled;
+        self
+    }
+
+    /// 运行全部命令并收集输出
+    fn fetch(&self) -> Result<Dict, QuantumConfigError> {
+        let mut trie = KeyTrie::new();
+        for command in &self.commands {
+            let output = self.run(command)?;
+            crate::scrub::replace_secret(&format!("exec:{}", command.describe()), output.clone());
+            trie.insert(command.key.split('.'), Value::String(Tag::Default, output), Some(command.describe()))?;
+        }
+        Ok(trie.into_dict())
+    }
+
+    /// 运行一条命令，返回去除末尾换行的标准输出
+    fn run(&self, command: &ExecCommand) -> Result<String, QuantumConfigError> {
+        let failed = |reason: String| QuantumConfigError::Exec {
+            command: command.describe(),
+            reason,
+        };
+        if self.allowed_programs.is_empty() {
+            return Err(failed("no programs are allowed; add them with with_allowed_program".to_string()));
+        }
+        if !self.allowed_programs.contains(&command.program) {
+            return Err(failed("program is not in the allowlist".to_string()));
+        }
+        let mut child = Command::new(&command.program)
+            .args(&command.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| failed(format!("failed to start: {}", e)))?;
+        // 在单独的线程中读取输出，避免输出填满管道缓冲区时子进程阻塞
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+        let deadline = Instant::now() + self.timeout;
+        let status = wait_until(&mut child, deadline)
+            .map_err(|e| failed(format!("failed to wait for the command: {}", e)))?
+            .ok_or_else(|| failed(format!("timed out after {:?}", self.timeout)))?;
+        // 命令退出后，它启动的后台进程可能仍持有输出管道，读取同样受超时限制
+        let read = |output: mpsc::Receiver<Vec<u8>>| {
+            output.recv_timeout(deadline.saturating_duration_since(Instant::now())).map_err(|_| {
+                failed(format!(
+                    "timed out after {:?} reading the output; a background process may still hold it open",
+                    self.timeout
+                ))
+            })
+        };
+        let stdout = read(stdout)?;
+        let stderr = read(stderr)?;
+        if !status.success() {
+            return Err(failed(self.describe_failure(status, &stderr)));
+        }
+        let output = String::from_utf8(stdout).map_err(|_| failed("output is not valid UTF-8".to_string()))?;
+        Ok(output.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    /// 命令以非零状态退出时的错误原因，按设置附带擦除并截断后的标准错误输出
+    fn describe_failure(&self, status: std::process::ExitStatus, stderr: &[u8]) -> String {
+        if !self.stderr_in_errors {
+            return format!("exited with {} (stderr omitted, see with_stderr_in_errors)", status);
+        }
+        let stderr = crate::scrub::scrub(String::from_utf8_lossy(stderr).trim());
+        let stderr = match stderr.char_indices().nth(MAX_STDERR_CHARS) {
+            Some((end, _)) => format!("{}…", &stderr[..end]),
+            None => stderr,
+        };
+        format!("exited with {}: {}", status, stderr)
+    }
+}
+
+/// 在后台线程中读取管道的全部内容，读完后从返回的通道送出
+///
+/// 管道被其他进程一直持有时线程不会结束，调用方以超时放弃等待。
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> mpsc::Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        let _ = sender.send(buffer);
+    });
+    receiver
+}
+
+/// 等待子进程结束，到达截止时间后终止子进程并返回 `None`
+fn wait_until(child: &mut Child, deadline: Instant) -> std::io::Result<Option<std::process::ExitStatus>> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+impl Provider for ExecProvider {
+    fn metadata(&self) -> Metadata {
+        let programs: Vec<&str> = self.commands.iter().map(|command| command.program.as_str()).collect();
+        Metadata::named(format!("command output ({})", programs.join(", ")))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let dict = self.fetch().map_err(|e| e.into_provider_error(None))?;
+        let mut data = Map::new();
+        data.insert(Profile::Default, dict);
+        Ok(data)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use figment::Figment;
+    use serde_json::json;
+
+    #[test]
+    fn test_exec_provider_maps_output_to_keys() {
+        let provider = ExecProvider::new()
+            .with_allowed_program("printf")
+            .with_allowed_program("echo")
+            .with_allowed_program("sh")
+            .with_command("database.password", "printf", ["exec-pw\\n"])
+            .with_command("database.user", "echo", ["app"])
+            .with_command("token", "sh", ["-c", "printf 'line1\\nline2\\n'"]);
+        let value: serde_json::Value = Figment::from(provider).extract().unwrap();
+        assert_eq!(
+            value,
+            json!({"database": {"password": "exec-pw", "user": "app"}, "token": "line1\nline2"})
+        );
+        #[cfg(feature = "secret-scrub")]
+        assert!(!crate::scrub::scrub("pw=exec-pw").contains("exec-pw"));
+    }
+
+    #[test]
+    fn test_exec_provider_rejects_failures_timeouts_and_unlisted_programs() {
+        let run = |provider: ExecProvider| provider.fetch().unwrap_err();
+
+        let error = run(ExecProvider::new()
+            .with_allowed_program("sh")
+            .with_command("a", "sh", ["-c", "printf 'de%sed' ni >&2; exit 3"]));
+        assert_eq!(error.code(), "exec");
+        assert!(error.to_string().contains("exit status: 3"), "{}", error);
+        assert!(!error.to_string().contains("denied"), "{}", error);
+
+        // 启用后附带截断的标准错误输出
+        let error = run(ExecProvider::new()
+            .with_stderr_in_errors(true)
+            .with_allowed_program("sh")
+            .with_command("a", "sh", ["-c", "printf 'de%sed' ni >&2; printf '%0500d' 0 >&2; exit 3"]));
+        let message = error.to_string();
+        assert!(message.contains("denied"), "{}", message);
+        assert!(message.ends_with('…'), "{}", message);
+        assert!(message.len() < 400, "{}", message);
+
+        let started = Instant::now();
+        let error = run(ExecProvider::new()
+            .with_timeout(Duration::from_millis(100))
+            .with_allowed_program("sleep")
+            .with_command("a", "sleep", ["5"]));
+        assert!(error.to_string().contains("timed out"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // 命令已退出，但后台进程仍持有标准输出
+        let started = Instant::now();
+        let error = run(ExecProvider::new()
+            .with_timeout(Duration::from_millis(200))
+            .with_allowed_program("sh")
+            .with_command("a", "sh", ["-c", "sleep 5 & echo x"]));
+        assert!(error.to_string().contains("timed out"), "{}", error);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        let error = run(ExecProvider::new().with_allowed_program("pass").with_command("a", "echo", ["x"]));
+        assert!(error.to_string().contains("allowlist"), "{}", error);
+
+        // 未设置允许列表时不运行任何命令
+        let error = run(ExecProvider::new().with_command("a", "echo", ["x"]));
+        assert!(error.to_string().contains("no programs are allowed"), "{}", error);
+
+        let error = run(ExecProvider::new()
+            .with_allowed_program("/nonexistent/program")
+            .with_command("a", "/nonexistent/program", Vec::<String>::new()));
+        assert!(error.to_string().contains("failed to start"), "{}", error);
+    }
+}
@@ -1,15 +1,25 @@
 //! 文件配置提供器
 //!
 //! 从配置文件读取数据的 figment Provider 实现。
-//! 支持 TOML、JSON 和 INI 格式，并提供解析深度限制。
+//! 支持 TOML、JSON 和 INI 格式（启用 `json5`、`yaml`、`hcl`、`xml` 特性时另支持 JSON5、YAML、HCL 与 XML），并提供解析深度限制。
+//! TOML 默认使用 `toml` 库解析；启用 `toml-lite` 特性时改用 `quantum_config_core` 的单遍解析器，
+//! 不构造中间语法树，峰值内存约为结果本身的大小，代价是日期时间按原文返回字符串、
+//! 只检查重复键与重复表头、错误信息只含行列号而不含源码片段；数组与内联表的嵌套深度在解析时即受
+//! 解析深度限制约束。
 //! 支持自定义文件读取器，允许用户自定义文件读取行为。
+//! 启用 [`QuantumConfigFileProviderGeneric::with_includes`] 后，顶层的 `include` 指令引入的文件先于当前文件合并。
 
 use crate::error::QuantumConfigError;
+use crate::logging::{trace_debug, trace_span};
+use crate::resolution::ResolutionContext;
 use figment::{value::{Map, Value}, Error, Metadata, Profile, Provider};
-use ini::Ini;
+use ini::{Ini, ParseOption};
 use serde_json::Value as JsonValue;
+use std::borrow::Cow;
 use std::path::{Path, PathBuf};
 use super::file_reader::{FileReader, StandardFileReader};
+#[cfg(feature = "sftp")]
+use super::sftp_reader::SftpFileReader;
 
 /// 配置文件格式枚举
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -20,6 +30,18 @@ pub enum FileFormat {
     Json,
     /// INI 格式
     Ini,
+    /// JSON5 格式，允许注释与尾随逗号
+    #[cfg(feature = "json5")]
+    Json5,
+    /// YAML 格式，扩展名为 `.yaml` 或 `.yml`
+    #[cfg(feature = "yaml")]
+    Yaml,
+    /// HCL 格式（Terraform 风格），块按标签展开为嵌套映射
+    #[cfg(feature = "hcl")]
+    Hcl,
+    /// XML 格式，元素与属性映射为嵌套映射，参见 `xml` 特性
+    #[cfg(feature = "xml")]
+    Xml,
 }
 
 impl FileFormat {
@@ -29,6 +51,14 @@ impl FileFormat {
             "toml" => Some(Self::Toml),
             "json" => Some(Self::Json),
             "ini" => Some(Self::Ini),
+            #[cfg(feature = "json5")]
+            "json5" => Some(Self::Json5),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Some(Self::Yaml),
+            #[cfg(feature = "hcl")]
+            "hcl" => Some(Self::Hcl),
+            #[cfg(feature = "xml")]
+            "xml" => Some(Self::Xml),
             _ => None,
         }
     }
@@ -39,10 +69,27 @@ impl FileFormat {
             Self::Toml => "toml",
             Self::Json => "json",
             Self::Ini => "ini",
+            #[cfg(feature = "json5")]
+            Self::Json5 => "json5",
+            #[cfg(feature = "yaml")]
+            Self::Yaml => "yaml",
+            #[cfg(feature = "hcl")]
+            Self::Hcl => "hcl",
+            #[cfg(feature = "xml")]
+            Self::Xml => "xml",
         }
     }
 }
 
+/// 加密配置文件的扩展名，例如 `config.toml.enc`
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+/// SOPS 加密文件的元数据块所在的顶层键
+pub const SOPS_METADATA_KEY: &str = "sops";
+
+/// 包含指令所在的顶层键
+pub const INCLUDE_KEY: &str = "include";
+
 /// Quantum Config 文件提供器（泛型版本）
 /// 
 /// 实现了 figment Provider trait，用于从配置文件读取数据。
@@ -58,6 +105,16 @@ pub struct QuantumConfigFileProviderGeneric<R: FileReader> {
     is_required: bool,
     /// 解析深度限制
     max_parse_depth: u32,
+    /// 是否展开顶层的 `include` 指令
+    includes: bool,
+    /// 是否将文件视为加密文件（`.enc` 结尾的文件总是视为加密文件）
+    encrypted: bool,
+    /// 解密使用的密钥，为 `None` 时从环境变量读取
+    #[cfg(feature = "encryption")]
+    encryption_key: Option<super::encryption::EncryptionKey>,
+    /// 解密 SOPS 文件使用的 `sops` 可执行文件，为 `None` 时从 `PATH` 中查找
+    #[cfg(feature = "sops")]
+    sops_command: Option<PathBuf>,
     /// 文件读取器
     reader: R,
 }
@@ -88,9 +145,74 @@ impl<R: FileReader> QuantumConfigFileProviderGeneric<R> {
             format,
             is_required,
             max_parse_depth,
+            includes: false,
+            encrypted: false,
+            #[cfg(feature = "encryption")]
+            encryption_key: None,
+            #[cfg(feature = "sops")]
+            sops_command: None,
             reader,
         }
     }
+
+    /// 设置是否展开顶层的 `include` 指令（默认关闭）
+    ///
+    /// `include = ["extra/*.toml", "db.json"]`（或单个字符串）中的路径相对于当前文件所在目录，
+    /// 按列出的顺序合并，当前文件中的键优先于被包含的文件。通配符 `*` 与 `?` 只能出现在文件名部分，
+    /// 匹配结果按路径字典序合并且可以为空；不含通配符的路径必须存在。被包含的文件可以继续包含其他文件，
+    /// 循环包含与过深的嵌套返回错误，参见 [`ResolutionContext`]。被包含的文件沿用当前文件的解密设置：
+    /// 解密密钥、SOPS 可执行文件以及 [`Self::with_encrypted`]（启用后被包含的文件也必须是加密文件）。
+    pub fn with_includes(mut self, enabled: bool) -> Self {
+        self.includes = enabled;
+        self
+    }
+
+    /// 设置是否将文件视为加密文件并在解析前解密，`.enc` 结尾的文件不需要设置
+    ///
+    /// 解密需要启用 `encryption` 特性，参见 [`crate::providers::encryption`]。
+    pub fn with_encrypted(mut self, encrypted: bool) -> Self {
+        self.encrypted = encrypted;
+        self
+    }
+
+    /// 设置解密使用的密钥，未设置时从环境变量读取
+    #[cfg(feature = "encryption")]
+    pub fn with_encryption_key(mut self, key: super::encryption::EncryptionKey) -> Self {
+        self.encryption_key = Some(key);
+        self
+    }
+
+    /// 设置解密 SOPS 文件使用的 `sops` 可执行文件，参见 [`crate::providers::sops`]
+    #[cfg(feature = "sops")]
+    pub fn with_sops_command<P: Into<PathBuf>>(mut self, command: P) -> Self {
+        self.sops_command = Some(command.into());
+        self
+    }
+
+    /// 文件是否需要解密
+    fn is_encrypted(&self) -> bool {
+        self.encrypted || plaintext_name(&self.path) != self.path.as_path()
+    }
+
+    /// 按需解密文件内容
+    fn decrypt_if_needed(&self, bytes: Vec<u8>) -> Result<Vec<u8>, QuantumConfigError> {
+        if !self.is_encrypted() {
+            return Ok(bytes);
+        }
+        #[cfg(feature = "encryption")]
+        {
+            let key = match &self.encryption_key {
+                Some(key) => key.clone(),
+                None => super::encryption::EncryptionKey::from_env()?,
+            };
+            super::encryption::decrypt(&bytes, &key, &self.path)
+        }
+        #[cfg(not(feature = "encryption"))]
+        Err(QuantumConfigError::Decryption {
+            path: self.path.clone(),
+            reason: "encrypted configuration files require the `encryption` feature".to_string(),
+        })
+    }
 }
 
 impl QuantumConfigFileProvider {
@@ -108,18 +230,7 @@ impl QuantumConfigFileProvider {
         is_required: bool,
         max_parse_depth: u32,
     ) -> Result<Self, QuantumConfigError> {
-        let path_ref = path.as_ref();
-        let extension = path_ref
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .ok_or_else(|| QuantumConfigError::UnsupportedFormat {
-                path: path_ref.to_path_buf(),
-            })?;
-
-        let format = FileFormat::from_extension(extension)
-            .ok_or_else(|| QuantumConfigError::UnsupportedFormat {
-                path: path_ref.to_path_buf(),
-            })?;
+        let format = infer_format(path.as_ref())?;
 
         Ok(QuantumConfigFileProviderGeneric::new(
             path,
@@ -131,9 +242,129 @@ impl QuantumConfigFileProvider {
     }
 }
 
+#[cfg(feature = "sftp")]
+impl QuantumConfigFileProviderGeneric<SftpFileReader> {
+    /// 从 `sftp://[user@]host[:port]/path` 地址创建远程文件提供器
+    ///
+    /// 使用 `SftpFileReader` 的默认设置（ssh-agent 认证、known_hosts 校验），
+    /// 文件格式由远程路径的扩展名推断。
+    ///
+    /// # Errors
+    /// URL 格式无效或无法推断格式时返回错误
+    pub fn from_sftp_url(
+        url: &str,
+        is_required: bool,
+        max_parse_depth: u32,
+    ) -> Result<Self, QuantumConfigError> {
+        let (reader, remote_path) = SftpFileReader::from_url(url)?;
+        let format = infer_format(&remote_path)?;
+        Ok(QuantumConfigFileProviderGeneric::new(
+            remote_path,
+            format,
+            is_required,
+            max_parse_depth,
+            reader,
+        ))
+    }
+}
+
+/// 将文件字节解码为文本
+///
+/// - 去除 UTF-8 BOM
+/// - 按 BOM（或无 BOM 时的零字节分布）识别 UTF-16 LE/BE 并转换为 UTF-8
+/// - 将 CRLF 换行统一为 LF
+///
+/// UTF-32 以及无法识别的非 UTF-8 内容返回 `UnsupportedEncoding` 错误。
+fn decode_content(path: &Path, bytes: &[u8]) -> Result<String, QuantumConfigError> {
+    let unsupported = |encoding: &str| QuantumConfigError::UnsupportedEncoding {
+        path: path.to_path_buf(),
+        encoding: encoding.to_string(),
+    };
+
+    // UTF-8 内容直接借用原始字节，常见情况下只在最后复制一次
+    let text: Cow<'_, str> = if bytes.starts_with(&[0x00, 0x00, 0xFE, 0xFF]) {
+        return Err(unsupported("UTF-32BE"));
+    } else if bytes.starts_with(&[0xFF, 0xFE, 0x00, 0x00]) {
+        return Err(unsupported("UTF-32LE"));
+    } else if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        Cow::Borrowed(std::str::from_utf8(rest).map_err(|_| unsupported("invalid UTF-8"))?)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        Cow::Owned(decode_utf16(rest, u16::from_le_bytes).ok_or_else(|| unsupported("invalid UTF-16LE"))?)
+    } else if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        Cow::Owned(decode_utf16(rest, u16::from_be_bytes).ok_or_else(|| unsupported("invalid UTF-16BE"))?)
+    } else {
+        // 无 BOM 的 UTF-16 ASCII 文本同时也是合法的 UTF-8，需先按零字节分布判断
+        match guess_utf16_without_bom(bytes) {
+            Some(true) => Cow::Owned(decode_utf16(bytes, u16::from_le_bytes).ok_or_else(|| unsupported("invalid UTF-16LE"))?),
+            Some(false) => Cow::Owned(decode_utf16(bytes, u16::from_be_bytes).ok_or_else(|| unsupported("invalid UTF-16BE"))?),
+            None => Cow::Borrowed(std::str::from_utf8(bytes).map_err(|_| unsupported("non-UTF-8"))?),
+        }
+    };
+
+    let text = text.trim_start_matches('\u{FEFF}');
+    Ok(if text.contains("\r\n") {
+        text.replace("\r\n", "\n")
+    } else {
+        text.to_string()
+    })
+}
+
+/// 将 UTF-16 字节解码为字符串，字节数为奇数或存在孤立代理项时返回 `None`
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let units = bytes.chunks_exact(2).map(|pair| to_unit([pair[0], pair[1]]));
+    char::decode_utf16(units).collect::<Result<String, _>>().ok()
+}
+
+/// 推断无 BOM 的 UTF-16 字节序：ASCII 文本在 UTF-16 中每隔一个字节为零
+///
+/// 返回 `Some(true)` 表示小端，`Some(false)` 表示大端，无法判断时返回 `None`。
+fn guess_utf16_without_bom(bytes: &[u8]) -> Option<bool> {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let even_zeros = bytes.iter().step_by(2).filter(|b| **b == 0).count();
+    let odd_zeros = bytes.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+    if odd_zeros * 2 > pairs && even_zeros == 0 {
+        Some(true)
+    } else if even_zeros * 2 > pairs && odd_zeros == 0 {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// 加密文件对应的明文文件名（去掉 `.enc` 扩展名），其余文件原样返回
+pub(crate) fn plaintext_name(path: &Path) -> &Path {
+    match (path.extension(), path.file_stem()) {
+        (Some(ext), Some(stem)) if ext.eq_ignore_ascii_case(ENCRYPTED_EXTENSION) => Path::new(stem),
+        _ => path,
+    }
+}
+
+/// 从文件扩展名推断格式，加密文件按去掉 `.enc` 后的扩展名推断
+pub(crate) fn infer_format(path: &Path) -> Result<FileFormat, QuantumConfigError> {
+    plaintext_name(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(FileFormat::from_extension)
+        .ok_or_else(|| QuantumConfigError::UnsupportedFormat {
+            path: path.to_path_buf(),
+        })
+}
+
 impl<R: FileReader> QuantumConfigFileProviderGeneric<R> {
-    /// 读取并解析配置文件
+    /// 读取并解析配置文件（不展开 `include`）
+    #[cfg(test)]
     fn read_and_parse(&self) -> Result<Value, QuantumConfigError> {
+        self.convert_to_figment_value(self.read_json()?)
+    }
+
+    /// 读取并解析配置文件为 JSON 值
+    fn read_json(&self) -> Result<JsonValue, QuantumConfigError> {
         // 检查文件是否存在
         if !self.reader.exists(&self.path) {
             if self.is_required {
@@ -142,119 +373,297 @@ impl<R: FileReader> QuantumConfigFileProviderGeneric<R> {
                 });
             } else {
                 // 可选文件不存在时返回空映射
-                return Ok(Value::Dict(figment::value::Tag::Default, Map::new()));
+                trace_debug!(path = %self.path.display(), "skipped missing optional config file");
+                return Ok(JsonValue::Object(serde_json::Map::new()));
             }
         }
 
-        // 使用文件读取器读取文件内容
-        let content = self.reader.read_content(&self.path)?;
+        let _span = trace_span!("quantum_config.file", path = %self.path.display(), format = ?self.format);
+
+        // 读取原始字节并统一解码为 UTF-8 文本，兼容 BOM、UTF-16 与 CRLF
+        let bytes = self.decrypt_if_needed(self.reader.read_bytes(&self.path)?)?;
+        let content = decode_content(&self.path, &bytes)?;
+
+        // 根据格式解析内容，SOPS 加密文件需要再经过解密
+        #[cfg(feature = "tracing-support")]
+        let started = crate::builder::Stopwatch::start();
+        let value = self.parse_content(&content)?;
+        trace_debug!(bytes = content.len(), elapsed = ?started.elapsed(), "parsed config file");
+        if !is_sops_document(&value) {
+            return Ok(value);
+        }
+        #[cfg(feature = "sops")]
+        {
+            super::sops::decrypt(self.sops_command.as_deref(), &self.path, content.as_bytes(), self.format)
+        }
+        #[cfg(not(feature = "sops"))]
+        Err(QuantumConfigError::Decryption {
+            path: self.path.clone(),
+            reason: "SOPS-encrypted files require the `sops` feature".to_string(),
+        })
+    }
+
+    /// 读取配置文件并展开 `include` 指令，被包含的文件经过同一解析上下文
+    ///
+    /// 各文件按 [`quantum_config_core::merge`] 的规则合并，与 `no_std` 核心的 `Layers` 一致。
+    fn read_with_includes(&self, context: &mut ResolutionContext) -> Result<JsonValue, QuantumConfigError> {
+        context.resolve(ResolutionContext::source_id(&self.path), |context| {
+            let value = self.read_json()?;
+            let JsonValue::Object(mut dict) = value else {
+                return Ok(value);
+            };
+            let include = match dict.remove(INCLUDE_KEY) {
+                Some(include) if self.includes => include,
+                Some(include) => {
+                    dict.insert(INCLUDE_KEY.to_string(), include);
+                    return Ok(JsonValue::Object(dict));
+                }
+                None => return Ok(JsonValue::Object(dict)),
+            };
+
+            let mut merged = JsonValue::Object(serde_json::Map::new());
+            for path in self.include_paths(&include)? {
+                let included = self.included(&path)?.read_with_includes(context)?;
+                if included.is_object() {
+                    quantum_config_core::merge(&mut merged, included);
+                }
+            }
+            quantum_config_core::merge(&mut merged, JsonValue::Object(dict));
+            Ok(merged)
+        })
+    }
 
-        // 根据格式解析内容
-        self.parse_content(&content)
+    /// 被包含文件的提供器，继承解析深度与解密设置
+    ///
+    /// 读取器以 trait 对象传递，避免嵌套包含时的无限泛型实例化。
+    fn included(&self, path: &Path) -> Result<QuantumConfigFileProviderGeneric<&dyn FileReader>, QuantumConfigError> {
+        Ok(QuantumConfigFileProviderGeneric {
+            path: path.to_path_buf(),
+            format: infer_format(path)?,
+            is_required: true,
+            max_parse_depth: self.max_parse_depth,
+            includes: true,
+            encrypted: self.encrypted,
+            #[cfg(feature = "encryption")]
+            encryption_key: self.encryption_key.clone(),
+            #[cfg(feature = "sops")]
+            sops_command: self.sops_command.clone(),
+            reader: &self.reader,
+        })
+    }
+
+    /// 将 `include` 的值展开为文件路径，相对路径基于当前文件所在目录
+    fn include_paths(&self, include: &JsonValue) -> Result<Vec<PathBuf>, QuantumConfigError> {
+        let invalid = |message: &str| QuantumConfigError::InvalidValue {
+            key_path: INCLUDE_KEY.to_string(),
+            message: format!("{} in {}", message, self.path.display()),
+        };
+        let patterns: Vec<&str> = match include {
+            JsonValue::String(pattern) => vec![pattern.as_str()],
+            JsonValue::Array(items) => items
+                .iter()
+                .map(|item| item.as_str().ok_or_else(|| invalid("expected a path or an array of paths")))
+                .collect::<Result<_, _>>()?,
+            _ => return Err(invalid("expected a path or an array of paths")),
+        };
+
+        let base_dir = self.path.parent().unwrap_or_else(|| Path::new(""));
+        let mut paths = Vec::new();
+        for pattern in patterns {
+            let path = base_dir.join(pattern);
+            let dir = path.parent().unwrap_or_else(|| Path::new(""));
+            if has_wildcard(&dir.to_string_lossy()) {
+                return Err(invalid(&format!("wildcards are only supported in file names: '{}'", pattern)));
+            }
+            let file_pattern = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+            if !has_wildcard(&file_pattern) {
+                paths.push(path);
+                continue;
+            }
+            let entries = match self.reader.list_dir(dir) {
+                Ok(entries) => entries,
+                Err(QuantumConfigError::Io { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                Err(e) => return Err(e),
+            };
+            let mut matched: Vec<PathBuf> = entries
+                .into_iter()
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .is_some_and(|name| !name.starts_with('.') && wildcard_match(&file_pattern, name))
+                        && self.reader.exists(entry)
+                })
+                .collect();
+            matched.sort();
+            paths.extend(matched);
+        }
+        Ok(paths)
     }
 
     /// 解析文件内容
-    fn parse_content(&self, content: &str) -> Result<Value, QuantumConfigError> {
+    fn parse_content(&self, content: &str) -> Result<JsonValue, QuantumConfigError> {
         match self.format {
             FileFormat::Toml => self.parse_toml(content),
             FileFormat::Json => self.parse_json(content),
             FileFormat::Ini => self.parse_ini(content),
+            #[cfg(feature = "json5")]
+            FileFormat::Json5 => self.parse_json5(content),
+            #[cfg(feature = "yaml")]
+            FileFormat::Yaml => self.parse_yaml(content),
+            #[cfg(feature = "hcl")]
+            FileFormat::Hcl => self.parse_hcl(content),
+            #[cfg(feature = "xml")]
+            FileFormat::Xml => self.parse_xml(content),
         }
     }
 
     /// 解析 TOML 内容
-    fn parse_toml(&self, content: &str) -> Result<Value, QuantumConfigError> {
-        // 直接使用 toml 库解析为 JsonValue
-        let parsed: JsonValue = toml::from_str(content)
-            .map_err(|e: toml::de::Error| QuantumConfigError::FileParse {
-                path: self.path.clone(),
-                format_name: "TOML".to_string(),
-                source_error: e.to_string(),
-            })?;
+    fn parse_toml(&self, content: &str) -> Result<JsonValue, QuantumConfigError> {
+        // 两种解析器只返回错误文本，错误映射保持一致
+        #[cfg(not(feature = "toml-lite"))]
+        let parsed = parse_toml_full(content);
+        #[cfg(feature = "toml-lite")]
+        let parsed = parse_toml_lite(content, self.max_parse_depth);
 
-        self.convert_to_figment_value(parsed)
+        parsed.map_err(|source_error| QuantumConfigError::FileParse {
+            path: self.path.clone(),
+            format_name: "TOML".to_string(),
+            source_error,
+        })
     }
 
     /// 解析 JSON 内容
-    fn parse_json(&self, content: &str) -> Result<Value, QuantumConfigError> {
-        let json_value: JsonValue = serde_json::from_str(content)
-            .map_err(|e| QuantumConfigError::FileParse {
-                path: self.path.clone(),
-                format_name: "JSON".to_string(),
-                source_error: e.to_string(),
-            })?;
+    fn parse_json(&self, content: &str) -> Result<JsonValue, QuantumConfigError> {
+        serde_json::from_str(content).map_err(|e| QuantumConfigError::FileParse {
+            path: self.path.clone(),
+            format_name: "JSON".to_string(),
+            source_error: e.to_string(),
+        })
+    }
 
-        self.convert_to_figment_value(json_value)
+    /// 解析 JSON5 内容
+    #[cfg(feature = "json5")]
+    fn parse_json5(&self, content: &str) -> Result<JsonValue, QuantumConfigError> {
+        json5::from_str(content).map_err(|e| QuantumConfigError::FileParse {
+            path: self.path.clone(),
+            format_name: "JSON5".to_string(),
+            source_error: e.to_string(),
+        })
     }
 
-    /// 解析 INI 内容
-    fn parse_ini(&self, content: &str) -> Result<Value, QuantumConfigError> {
-        let ini = Ini::load_from_str(content)
-            .map_err(|e| QuantumConfigError::FileParse {
-                path: self.path.clone(),
-                format_name: "INI".to_string(),
-                source_error: e.to_string(),
-            })?;
+    /// 解析 YAML 内容，映射的键必须为字符串；锚点与别名按展开后的值处理
+    #[cfg(feature = "yaml")]
+    fn parse_yaml(&self, content: &str) -> Result<JsonValue, QuantumConfigError> {
+        serde_yaml_ng::from_str(content).map_err(|e| QuantumConfigError::FileParse {
+            path: self.path.clone(),
+            format_name: "YAML".to_string(),
+            source_error: e.to_string(),
+        })
+    }
 
-        // 将 INI 转换为嵌套的 Map 结构
-        let mut root_map = Map::new();
+    /// 解析 HCL 内容
+    ///
+    /// 属性对应键，块按标识符与标签展开为嵌套映射：`server "web" { port = 80 }` 对应 `server.web.port`；
+    /// 同一位置重复的块组成数组。表达式不求值，引用与函数调用保留为 `${...}` 形式的字符串。
+    #[cfg(feature = "hcl")]
+    fn parse_hcl(&self, content: &str) -> Result<JsonValue, QuantumConfigError> {
+        hcl::from_str(content).map_err(|e| QuantumConfigError::FileParse {
+            path: self.path.clone(),
+            format_name: "HCL".to_string(),
+            source_error: e.to_string(),
+        })
+    }
+
+    /// 解析 XML 内容，映射规则参见 `providers::xml` 模块
+    #[cfg(feature = "xml")]
+    fn parse_xml(&self, content: &str) -> Result<JsonValue, QuantumConfigError> {
+        super::xml::parse(content).map_err(|source_error| QuantumConfigError::FileParse {
+            path: self.path.clone(),
+            format_name: "XML".to_string(),
+            source_error,
+        })
+    }
 
+    /// 解析 INI 内容
+    ///
+    /// - 段名中的 `.` 表示嵌套：`[server.tls]` 对应 `server` 下的 `tls` 映射；
+    /// - 同一段中重复出现的键、以 `[]` 结尾的键（`hosts[] = a`）以及含逗号的值（`hosts = a, b`）解析为数组；
+    /// - 未加引号的值按 TOML 的规则推断类型（布尔值、整数、浮点数），其余为字符串；
+    ///   加引号的值始终为字符串，含逗号的字符串需要加引号（`title = "Hello, world"`）。
+    fn parse_ini(&self, content: &str) -> Result<JsonValue, QuantumConfigError> {
+        let options = ParseOption {
+            enabled_quote: false,
+            ..ParseOption::default()
+        };
+        let ini = Ini::load_from_str_opt(content, options).map_err(|e| self.ini_error(e.to_string()))?;
+
+        let mut root = serde_json::Map::new();
         for (section_name, properties) in ini.iter() {
-            // 处理根级键和有名称的段落
-            match section_name {
-                None => {
-                    // 根级键（无段落），直接添加到根映射
-                    for (key, value) in properties.iter() {
-                        root_map.insert(
-                            key.to_string(),
-                            self.parse_ini_value(value),
-                        );
-                    }
+            let section = match section_name {
+                None => &mut root,
+                Some(section_name) => self.ini_section(&mut root, section_name)?,
+            };
+            let mut keys: Vec<&str> = Vec::new();
+            for (key, _) in properties.iter() {
+                if !keys.contains(&key) {
+                    keys.push(key);
                 }
-                Some(section_name) => {
-                    // 有名称的段落，创建嵌套映射
-                    let mut section_map = Map::new();
-                    for (key, value) in properties.iter() {
-                        section_map.insert(
-                            key.to_string(),
-                            self.parse_ini_value(value),
-                        );
-                    }
-                    root_map.insert(
-                        section_name.to_string(),
-                        Value::Dict(figment::value::Tag::Default, section_map),
-                    );
+            }
+            for key in keys {
+                let values: Vec<&str> = properties.get_all(key).collect();
+                let (name, list) = match key.strip_suffix("[]") {
+                    Some(name) => (name.trim_end(), true),
+                    None => (key, values.len() > 1),
+                };
+                let value = if list {
+                    JsonValue::Array(values.iter().flat_map(|value| ini_items(value)).collect())
+                } else {
+                    ini_value(values[0])
+                };
+                if section.get(name).is_some_and(JsonValue::is_object) {
+                    return Err(self.ini_error(format!("key '{}' conflicts with a section of the same name", name)));
                 }
+                section.insert(name.to_string(), value);
             }
         }
 
-        Ok(Value::Dict(figment::value::Tag::Default, root_map))
+        Ok(JsonValue::Object(root))
     }
 
-    /// 解析 INI 值，支持类型推断（布尔值、数值、字符串）
-    fn parse_ini_value(&self, value: &str) -> Value {
-        let tag = figment::value::Tag::Default;
-        
-        // 移除首尾空白
-        let value = value.trim();
-        
-        // 尝试解析布尔值
-        if let Ok(bool_val) = value.parse::<bool>() {
-            return Value::Bool(tag, bool_val);
-        }
-        
-        // 尝试解析整数
-        if let Ok(int_val) = value.parse::<i64>() {
-            return Value::Num(tag, figment::value::Num::I64(int_val));
+    /// 按 `.` 分隔的段名找到（或创建）嵌套的段
+    fn ini_section<'a>(
+        &self,
+        root: &'a mut serde_json::Map<String, JsonValue>,
+        section_name: &str,
+    ) -> Result<&'a mut serde_json::Map<String, JsonValue>, QuantumConfigError> {
+        let mut section = root;
+        for segment in section_name.split('.').map(str::trim) {
+            if segment.is_empty() {
+                return Err(self.ini_error(format!("invalid section name [{}]", section_name)));
+            }
+            let entry = section
+                .entry(segment.to_string())
+                .or_insert_with(|| JsonValue::Object(serde_json::Map::new()));
+            section = match entry {
+                JsonValue::Object(map) => map,
+                _ => {
+                    return Err(self.ini_error(format!(
+                        "section [{}] conflicts with key '{}'",
+                        section_name, segment
+                    )))
+                }
+            };
         }
-        
-        // 尝试解析浮点数
-        if let Ok(float_val) = value.parse::<f64>() {
-            return Value::Num(tag, figment::value::Num::F64(float_val));
+        Ok(section)
+    }
+
+    fn ini_error(&self, source_error: String) -> QuantumConfigError {
+        QuantumConfigError::FileParse {
+            path: self.path.clone(),
+            format_name: "INI".to_string(),
+            source_error,
         }
-        
-        // 默认当作字符串
-        Value::String(tag, value.to_string())
     }
 
     /// 将 JsonValue 转换为 figment::Value
@@ -296,7 +705,7 @@ impl<R: FileReader> QuantumConfigFileProviderGeneric<R> {
             }
             serde_json::Value::String(s) => Ok(Value::String(tag, s)),
             serde_json::Value::Array(arr) => {
-                let mut figment_array = Vec::new();
+                let mut figment_array = Vec::with_capacity(arr.len());
                 for item in arr {
                     figment_array.push(self.convert_json_value_recursive(item, depth + 1)?);
                 }
@@ -316,14 +725,56 @@ impl<R: FileReader> QuantumConfigFileProviderGeneric<R> {
     }
 }
 
+/// 是否为 SOPS 加密文件：顶层 `sops` 元数据块中包含 `mac` 与 `version`
+fn is_sops_document(value: &JsonValue) -> bool {
+    matches!(
+        value.get(SOPS_METADATA_KEY),
+        Some(JsonValue::Object(metadata)) if metadata.contains_key("mac") && metadata.contains_key("version")
+    )
+}
+
+/// 包含路径中是否含有通配符
+fn has_wildcard(pattern: &str) -> bool {
+    pattern.contains(['*', '?'])
+}
+
+/// 按 `*`（任意个字符）与 `?`（单个字符）匹配文件名
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut p, mut n) = (0, 0);
+    // 最近一个 `*` 的位置及其当前匹配到的名称位置，失配时回溯让它多匹配一个字符
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            p = star_p + 1;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
 impl<R: FileReader> Provider for QuantumConfigFileProviderGeneric<R> {
     fn metadata(&self) -> Metadata {
+        // 文件中的键与配置键路径一致，错误信息直接使用 `server.port` 形式
         Metadata::named(format!("Quantum Config File Provider ({})", self.path.display()))
+            .interpolater(|_profile, keys| keys.join("."))
     }
 
     fn data(&self) -> Result<Map<Profile, Map<String, Value>>, Error> {
-        let value = self.read_and_parse()
-            .map_err(|e| Error::from(format!("File provider error: {}", e)))?;
+        let value = self
+            .read_with_includes(&mut ResolutionContext::new())
+            .and_then(|value| self.convert_to_figment_value(value))
+            .map_err(|e| e.into_provider_error(Some("File provider error")))?;
 
         let mut profile_map = Map::new();
         if let Value::Dict(_, dict) = value {
@@ -339,6 +790,144 @@ impl<R: FileReader> Provider for QuantumConfigFileProviderGeneric<R> {
     }
 }
 
+/// INI 值：加引号的值为字符串，含逗号的值为数组，其余按 TOML 的规则推断类型
+fn ini_value(raw: &str) -> JsonValue {
+    let raw = raw.trim();
+    if unquote(raw).is_none() && split_ini_list(raw).len() > 1 {
+        return JsonValue::Array(ini_items(raw));
+    }
+    ini_scalar(raw)
+}
+
+/// 数组元素：按逗号拆分（引号内的逗号除外），忽略空元素
+fn ini_items(raw: &str) -> Vec<JsonValue> {
+    split_ini_list(raw.trim())
+        .into_iter()
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(ini_scalar)
+        .collect()
+}
+
+fn split_ini_list(raw: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in raw.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, ',') => {
+                items.push(&raw[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&raw[start..]);
+    items
+}
+
+fn unquote(raw: &str) -> Option<&str> {
+    ['"', '\''].into_iter().find_map(|quote| {
+        raw.strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+            .filter(|inner| !inner.contains(quote))
+    })
+}
+
+/// 单个值：加引号的值为字符串，其余按 [`infer_scalar`] 推断类型
+fn ini_scalar(raw: &str) -> JsonValue {
+    match unquote(raw) {
+        Some(inner) => JsonValue::String(inner.to_string()),
+        None => infer_scalar(raw),
+    }
+}
+
+/// 按 TOML 的规则推断文本值的类型：布尔值、整数或浮点数，其余为字符串
+pub(super) fn infer_scalar(raw: &str) -> JsonValue {
+    match raw {
+        "true" => return JsonValue::Bool(true),
+        "false" => return JsonValue::Bool(false),
+        _ => {}
+    }
+    parse_toml_integer(raw)
+        .or_else(|| parse_toml_float(raw))
+        .unwrap_or_else(|| JsonValue::String(raw.to_string()))
+}
+
+/// 数字与下划线组成、下划线只出现在数字之间
+fn valid_digits(digits: &str, is_digit: fn(char) -> bool) -> bool {
+    !digits.is_empty()
+        && !digits.starts_with('_')
+        && !digits.ends_with('_')
+        && !digits.contains("__")
+        && digits.chars().all(|c| c == '_' || is_digit(c))
+}
+
+/// 十进制整数部分：不允许前导零
+fn valid_decimal(digits: &str) -> bool {
+    valid_digits(digits, |c| c.is_ascii_digit()) && (digits == "0" || !digits.starts_with('0'))
+}
+
+/// 使用 `toml` 库解析 TOML 文本
+#[cfg(any(not(feature = "toml-lite"), test))]
+fn parse_toml_full(content: &str) -> Result<JsonValue, String> {
+    toml::from_str(content).map_err(|e: toml::de::Error| e.to_string())
+}
+
+/// 使用 `quantum_config_core` 的单遍轻量解析器解析 TOML 文本，数组与内联表最多嵌套 `max_depth` 层
+#[cfg(any(feature = "toml-lite", test))]
+fn parse_toml_lite(content: &str, max_depth: u32) -> Result<JsonValue, String> {
+    quantum_config_core::toml::parse_with_max_depth(content, max_depth as usize).map_err(|e| e.to_string())
+}
+
+fn parse_toml_integer(raw: &str) -> Option<JsonValue> {
+    for (prefix, radix) in [("0x", 16), ("0o", 8), ("0b", 2)] {
+        if let Some(digits) = raw.strip_prefix(prefix) {
+            if !valid_digits(digits, |c| c.is_ascii_hexdigit()) {
+                return None;
+            }
+            return u64::from_str_radix(&digits.replace('_', ""), radix).ok().map(JsonValue::from);
+        }
+    }
+    let unsigned = raw.strip_prefix(['+', '-']).unwrap_or(raw);
+    if !valid_decimal(unsigned) {
+        return None;
+    }
+    let cleaned = raw.replace('_', "");
+    cleaned
+        .parse::<i64>()
+        .map(JsonValue::from)
+        .or_else(|_| cleaned.trim_start_matches('+').parse::<u64>().map(JsonValue::from))
+        .ok()
+}
+
+fn parse_toml_float(raw: &str) -> Option<JsonValue> {
+    let unsigned = raw.strip_prefix(['+', '-']).unwrap_or(raw);
+    let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, Some(exponent)),
+        None => (unsigned, None),
+    };
+    let (integer, fraction) = match mantissa.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let digits = |digits: &str| valid_digits(digits, |c| c.is_ascii_digit());
+    let valid = valid_decimal(integer)
+        && (fraction.is_some() || exponent.is_some())
+        && fraction.is_none_or(digits)
+        && exponent.is_none_or(|exponent| digits(exponent.strip_prefix(['+', '-']).unwrap_or(exponent)));
+    if !valid {
+        return None;
+    }
+    raw.replace('_', "")
+        .parse::<f64>()
+        .ok()
+        .and_then(serde_json::Number::from_f64)
+        .map(JsonValue::Number)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -479,6 +1068,29 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_toml_backends_agree() {
+        let valid = "title = \"app\"\nports = [80, 443]\nratio = 0.5\n\n[database]\nhost = \"localhost\"\n\n[[servers]]\nname = \"a\"\nlimits = { cpu = 2, debug = true }\n";
+        assert_eq!(parse_toml_lite(valid, 32).unwrap(), parse_toml_full(valid).unwrap());
+
+        // 深度嵌套的数组在两个解析器中都返回错误，而不是耗尽调用栈
+        let deep = format!("a = {}{}\n", "[".repeat(200_000), "]".repeat(200_000));
+        for invalid in ["key = ", "a = 1\na = 2", "[table\nkey = 1", "key = \"unterminated", deep.as_str()] {
+            assert!(parse_toml_full(invalid).is_err(), "{}", &invalid[..invalid.len().min(40)]);
+            assert!(parse_toml_lite(invalid, 32).is_err(), "{}", &invalid[..invalid.len().min(40)]);
+        }
+
+        // 无论启用哪个解析器，错误都映射为同样的 FileParse
+        let mut temp_file = NamedTempFile::new().unwrap();
+        write!(temp_file, "key = ").unwrap();
+        let provider =
+            QuantumConfigFileProviderGeneric::new(temp_file.path(), FileFormat::Toml, true, 100, StandardFileReader::new());
+        assert!(matches!(
+            provider.read_and_parse(),
+            Err(QuantumConfigError::FileParse { path, format_name, .. }) if path == temp_file.path() && format_name == "TOML"
+        ));
+    }
+
     #[test]
     fn test_parse_ini_content() -> Result<(), Box<dyn std::error::Error>> {
         let mut temp_file = NamedTempFile::new()?;
@@ -498,6 +1110,174 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_ini_nested_sections_and_arrays() -> Result<(), Box<dyn std::error::Error>> {
+        let mut temp_file = NamedTempFile::new()?;
+        writeln!(
+            temp_file,
+            "name = app\ntags = a, b, \"c, d\"\ntitle = \"Hello, world\"\n\n[server]\nport = 8080\nallow = 10.0.0.1\nallow = 10.0.0.2\n\n[server.tls]\nenabled = true\nciphers[] = TLS_AES_128_GCM_SHA256\n"
+        )?;
+
+        let provider =
+            QuantumConfigFileProviderGeneric::new(temp_file.path(), FileFormat::Ini, true, 100, StandardFileReader::new());
+        let value: JsonValue = provider.read_and_parse()?.deserialize()?;
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "name": "app",
+                "tags": ["a", "b", "c, d"],
+                "title": "Hello, world",
+                "server": {
+                    "port": 8080,
+                    "allow": ["10.0.0.1", "10.0.0.2"],
+                    "tls": {"enabled": true, "ciphers": ["TLS_AES_128_GCM_SHA256"]}
+                }
+            })
+        );
+
+        // 段与同名键冲突时报错
+        let mut temp_file = NamedTempFile::new()?;
+        writeln!(temp_file, "[server]\ntls = off\n\n[server.tls]\nenabled = true")?;
+        let provider =
+            QuantumConfigFileProviderGeneric::new(temp_file.path(), FileFormat::Ini, true, 100, StandardFileReader::new());
+        let error = provider.read_and_parse().unwrap_err();
+        assert!(error.to_string().contains("section [server.tls] conflicts with key 'tls'"), "{}", error);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ini_scalars_follow_toml_rules() {
+        assert_eq!(ini_scalar("true"), JsonValue::Bool(true));
+        assert_eq!(ini_scalar("True"), JsonValue::from("True"));
+        assert_eq!(ini_scalar("-42"), JsonValue::from(-42));
+        assert_eq!(ini_scalar("1_000"), JsonValue::from(1000));
+        assert_eq!(ini_scalar("0xff"), JsonValue::from(255));
+        assert_eq!(ini_scalar("18446744073709551615"), JsonValue::from(u64::MAX));
+        assert_eq!(ini_scalar("3.5"), JsonValue::from(3.5));
+        assert_eq!(ini_scalar("1e3"), JsonValue::from(1000.0));
+        // TOML 中不是数值的写法保持为字符串
+        assert_eq!(ini_scalar("007"), JsonValue::from("007"));
+        assert_eq!(ini_scalar("1."), JsonValue::from("1."));
+        assert_eq!(ini_scalar("0.0.0.0"), JsonValue::from("0.0.0.0"));
+        assert_eq!(ini_scalar("nan"), JsonValue::from("nan"));
+        assert_eq!(ini_scalar("\"42\""), JsonValue::from("42"));
+        assert_eq!(ini_value("a,"), serde_json::json!(["a"]));
+    }
+
+    #[cfg(feature = "hcl")]
+    #[test]
+    fn test_parse_hcl_blocks_into_nested_values() -> Result<(), Box<dyn std::error::Error>> {
+        let mut temp_file = tempfile::Builder::new().suffix(".hcl").tempfile()?;
+        writeln!(
+            temp_file,
+            "# 由运维维护\nname = \"app\"\nregions = [\"eu\", \"us\"]\n\nserver \"web\" {{\n  port = 8080\n  tls {{\n    enabled = true\n  }}\n}}\n\ndatabase {{\n  url = \"pg://db\"\n  pool = 4.5\n}}"
+        )?;
+
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 100)?;
+        assert_eq!(provider.format, FileFormat::Hcl);
+        let value: JsonValue = provider.read_and_parse()?.deserialize()?;
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "name": "app",
+                "regions": ["eu", "us"],
+                "server": {"web": {"port": 8080, "tls": {"enabled": true}}},
+                "database": {"url": "pg://db", "pool": 4.5}
+            })
+        );
+
+        let mut temp_file = tempfile::Builder::new().suffix(".hcl").tempfile()?;
+        writeln!(temp_file, "server {{\n  port = \n}}")?;
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 100)?;
+        assert!(matches!(provider.read_and_parse(), Err(QuantumConfigError::FileParse { format_name, .. }) if format_name == "HCL"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn test_parse_xml_into_nested_values() -> Result<(), Box<dyn std::error::Error>> {
+        let mut temp_file = tempfile::Builder::new().suffix(".xml").tempfile()?;
+        writeln!(
+            temp_file,
+            "<config name=\"app\">\n  <server port=\"8080\"><host>0.0.0.0</host></server>\n  <regions>eu</regions>\n  <regions>us</regions>\n</config>"
+        )?;
+
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 100)?;
+        assert_eq!(provider.format, FileFormat::Xml);
+        let value: JsonValue = provider.read_and_parse()?.deserialize()?;
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "name": "app",
+                "server": {"port": 8080, "host": "0.0.0.0"},
+                "regions": ["eu", "us"]
+            })
+        );
+
+        let mut temp_file = tempfile::Builder::new().suffix(".xml").tempfile()?;
+        writeln!(temp_file, "<config><server></config>")?;
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 100)?;
+        assert!(matches!(provider.read_and_parse(), Err(QuantumConfigError::FileParse { format_name, .. }) if format_name == "XML"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_parse_yaml_into_nested_values() -> Result<(), Box<dyn std::error::Error>> {
+        let mut temp_file = tempfile::Builder::new().suffix(".yml").tempfile()?;
+        writeln!(
+            temp_file,
+            "# 由运维维护\nname: app\nserver:\n  port: 8080\n  host: &host 0.0.0.0\nadmin_host: *host\nregions:\n  - eu\n  - us\n"
+        )?;
+
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 100)?;
+        assert_eq!(provider.format, FileFormat::Yaml);
+        let value: JsonValue = provider.read_and_parse()?.deserialize()?;
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "name": "app",
+                "server": {"port": 8080, "host": "0.0.0.0"},
+                "admin_host": "0.0.0.0",
+                "regions": ["eu", "us"]
+            })
+        );
+
+        let mut temp_file = tempfile::Builder::new().suffix(".yaml").tempfile()?;
+        writeln!(temp_file, "server: [unclosed")?;
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 100)?;
+        assert!(matches!(provider.read_and_parse(), Err(QuantumConfigError::FileParse { format_name, .. }) if format_name == "YAML"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn test_parse_json5_allows_comments_and_trailing_commas() -> Result<(), Box<dyn std::error::Error>> {
+        let mut temp_file = tempfile::Builder::new().suffix(".json5").tempfile()?;
+        writeln!(
+            temp_file,
+            "// 由运维维护\n{{\n  key: 'value', /* 行内注释 */\n  number: 42,\n  list: [1, 2,],\n}}"
+        )?;
+
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 100)?;
+        assert_eq!(provider.format, FileFormat::Json5);
+        let value = provider.read_and_parse()?;
+        let dict = value.as_dict().expect("expected a dict");
+        assert_eq!(dict.get("key").and_then(|v| v.as_str()), Some("value"));
+        assert_eq!(dict.get("list").and_then(|v| v.as_array()).map(|a| a.len()), Some(2));
+
+        // 纯 JSON 格式仍然拒绝注释
+        let json_provider =
+            QuantumConfigFileProviderGeneric::new(temp_file.path(), FileFormat::Json, true, 100, StandardFileReader::new());
+        assert!(matches!(json_provider.read_and_parse(), Err(QuantumConfigError::FileParse { .. })));
+
+        Ok(())
+    }
+
     #[test]
     fn test_depth_limit_enforcement() {
         let provider = QuantumConfigFileProviderGeneric::new(
@@ -529,6 +1309,275 @@ mod tests {
             _ => panic!("Expected Internal error for depth limit"),
         }
     }
+
+    #[cfg(feature = "sftp")]
+    #[test]
+    fn test_from_sftp_url_infers_format() {
+        let provider = QuantumConfigFileProviderGeneric::from_sftp_url(
+            "sftp://admin@bastion/etc/app/config.json",
+            true,
+            32,
+        )
+        .unwrap();
+        assert_eq!(provider.format, FileFormat::Json);
+        assert_eq!(provider.path, PathBuf::from("/etc/app/config.json"));
+
+        let result = QuantumConfigFileProviderGeneric::from_sftp_url("sftp://bastion/etc/app/config", true, 32);
+        assert!(matches!(result, Err(QuantumConfigError::UnsupportedFormat { .. })));
+    }
+
+    fn utf16_bytes(text: &str, little_endian: bool, with_bom: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if with_bom {
+            bytes.extend_from_slice(if little_endian { &[0xFF, 0xFE] } else { &[0xFE, 0xFF] });
+        }
+        for unit in text.encode_utf16() {
+            let pair = if little_endian { unit.to_le_bytes() } else { unit.to_be_bytes() };
+            bytes.extend_from_slice(&pair);
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_decode_content_handles_bom_and_crlf() {
+        let path = Path::new("config.toml");
+
+        let mut bytes = vec![0xEF, 0xBB, 0xBF];
+        bytes.extend_from_slice(b"name = \"app\"\r\nport = 8080\r\n");
+        assert_eq!(decode_content(path, &bytes).unwrap(), "name = \"app\"\nport = 8080\n");
+
+        let text = "name = \"应用\"\r\n";
+        assert_eq!(decode_content(path, &utf16_bytes(text, true, true)).unwrap(), "name = \"应用\"\n");
+        assert_eq!(decode_content(path, &utf16_bytes(text, false, true)).unwrap(), "name = \"应用\"\n");
+        assert_eq!(decode_content(path, &utf16_bytes("port = 1", true, false)).unwrap(), "port = 1");
+        assert_eq!(decode_content(path, &utf16_bytes("port = 1", false, false)).unwrap(), "port = 1");
+    }
+
+    #[test]
+    fn test_decode_content_rejects_unsupported_encodings() {
+        let path = Path::new("config.toml");
+
+        let utf32 = [0xFF, 0xFE, 0x00, 0x00, b'a', 0x00, 0x00, 0x00];
+        match decode_content(path, &utf32) {
+            Err(QuantumConfigError::UnsupportedEncoding { encoding, .. }) => assert_eq!(encoding, "UTF-32LE"),
+            other => panic!("Expected UnsupportedEncoding, got {:?}", other),
+        }
+
+        let latin1 = b"name = \"caf\xe9\"";
+        assert!(matches!(
+            decode_content(path, latin1),
+            Err(QuantumConfigError::UnsupportedEncoding { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_utf16_file_with_bom() -> Result<(), Box<dyn std::error::Error>> {
+        let mut temp_file = NamedTempFile::with_suffix(".json")?;
+        temp_file.write_all(&utf16_bytes("{\r\n  \"port\": 9090\r\n}", true, true))?;
+
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 32)?;
+        let value = provider.read_and_parse()?;
+        let dict = value.into_dict().unwrap();
+        assert_eq!(dict.get("port").and_then(|v| v.to_i128()), Some(9090));
+        Ok(())
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_files_are_decrypted() {
+        use super::super::encryption::{encrypt, EncryptionKey};
+
+        let dir = tempfile::tempdir().unwrap();
+        let key = EncryptionKey::generate().unwrap();
+        let path = dir.path().join("config.toml.enc");
+        std::fs::write(&path, encrypt(b"port = 8443\n", &key).unwrap()).unwrap();
+
+        let provider = QuantumConfigFileProvider::from_path(&path, true, 32).unwrap();
+        assert_eq!(provider.format, FileFormat::Toml);
+        let dict = provider.with_encryption_key(key.clone()).read_and_parse().unwrap().into_dict().unwrap();
+        assert_eq!(dict.get("port").and_then(|v| v.to_i128()), Some(8443));
+
+        // 未使用 `.enc` 扩展名的加密文件需要显式声明
+        let forced = dir.path().join("secrets.json");
+        std::fs::write(&forced, encrypt(br#"{"token": "t"}"#, &key).unwrap()).unwrap();
+        let provider = QuantumConfigFileProvider::from_path(&forced, true, 32).unwrap().with_encryption_key(key);
+        assert_eq!(provider.clone().read_and_parse().unwrap_err().code(), "file_parse");
+        assert!(provider.with_encrypted(true).read_and_parse().is_ok());
+
+        let wrong = QuantumConfigFileProvider::from_path(&path, true, 32)
+            .unwrap()
+            .with_encryption_key(EncryptionKey::generate().unwrap());
+        assert_eq!(wrong.read_and_parse().unwrap_err().code(), "decryption");
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_included_files_use_provider_encryption_settings() {
+        use super::super::encryption::{encrypt, EncryptionKey};
+
+        let dir = tempfile::tempdir().unwrap();
+        let key = EncryptionKey::generate().unwrap();
+        let root = dir.path().join("config.toml");
+        std::fs::write(&root, "include = \"secrets.toml.enc\"\nname = \"app\"\n").unwrap();
+        std::fs::write(dir.path().join("secrets.toml.enc"), encrypt(b"[database]\npassword = \"s3cret\"\n", &key).unwrap())
+            .unwrap();
+
+        let provider = QuantumConfigFileProvider::from_path(&root, true, 32)
+            .unwrap()
+            .with_includes(true)
+            .with_encryption_key(key.clone());
+        let data = provider.data().unwrap();
+        let database = data[&Profile::Default].get("database").and_then(|v| v.as_dict()).unwrap();
+        assert_eq!(database.get("password").and_then(|v| v.as_str()), Some("s3cret"));
+
+        // 显式声明的加密文件包含的文件同样按加密文件解密
+        let forced = dir.path().join("app.json");
+        std::fs::write(&forced, encrypt(br#"{"include": "db.json", "name": "app"}"#, &key).unwrap()).unwrap();
+        std::fs::write(dir.path().join("db.json"), encrypt(br#"{"token": "t"}"#, &key).unwrap()).unwrap();
+        let provider = QuantumConfigFileProvider::from_path(&forced, true, 32)
+            .unwrap()
+            .with_includes(true)
+            .with_encrypted(true)
+            .with_encryption_key(key);
+        let data = provider.data().unwrap();
+        assert_eq!(data[&Profile::Default].get("token").and_then(|v| v.as_str()), Some("t"));
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    #[test]
+    fn test_encrypted_files_require_feature() {
+        let mut temp_file = NamedTempFile::with_suffix(".toml.enc").unwrap();
+        temp_file.write_all(b"UUNFTkMx").unwrap();
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 32).unwrap();
+        assert_eq!(provider.format, FileFormat::Toml);
+        let error = provider.read_and_parse().unwrap_err();
+        assert_eq!(error.code(), "decryption");
+        assert!(error.to_string().contains("`encryption` feature"));
+    }
+
+    const SOPS_JSON: &str = r#"{"password": "ENC[AES256_GCM,data:abc,type:str]", "sops": {"mac": "ENC[...]", "version": "3.8.1"}}"#;
+
+    #[cfg(all(feature = "sops", unix))]
+    #[test]
+    fn test_sops_files_are_decrypted_with_command() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, SOPS_JSON).unwrap();
+        let command = dir.path().join("sops");
+        std::fs::write(&command, "#!/bin/sh\necho '{\"password\": \"s3cret\"}'\n").unwrap();
+        std::fs::set_permissions(&command, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let provider = QuantumConfigFileProvider::from_path(&path, true, 32).unwrap().with_sops_command(&command);
+        let dict = provider.read_and_parse().unwrap().into_dict().unwrap();
+        assert_eq!(dict.get("password").and_then(|v| v.as_str()), Some("s3cret"));
+        assert!(!dict.contains_key(SOPS_METADATA_KEY));
+    }
+
+    #[cfg(all(feature = "sops", feature = "yaml", unix))]
+    #[test]
+    fn test_sops_yaml_files_are_decrypted_from_content() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(&path, "password: ENC[AES256_GCM,data:abc,type:str]\nsops:\n  mac: ENC[...]\n  version: 3.8.1\n").unwrap();
+        let command = dir.path().join("sops");
+        // 只在输入类型与标准输入中的密文都正确时输出明文
+        std::fs::write(
+            &command,
+            "#!/bin/sh\n[ \"$3\" = yaml ] && grep -q '^sops:' && echo '{\"password\": \"s3cret\"}'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&command, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let provider = QuantumConfigFileProvider::from_path(&path, true, 32).unwrap().with_sops_command(&command);
+        let dict = provider.read_and_parse().unwrap().into_dict().unwrap();
+        assert_eq!(dict.get("password").and_then(|v| v.as_str()), Some("s3cret"));
+    }
+
+    #[cfg(not(feature = "sops"))]
+    #[test]
+    fn test_sops_files_require_feature() {
+        let mut temp_file = NamedTempFile::with_suffix(".json").unwrap();
+        temp_file.write_all(SOPS_JSON.as_bytes()).unwrap();
+        let provider = QuantumConfigFileProvider::from_path(temp_file.path(), true, 32).unwrap();
+        let error = provider.read_and_parse().unwrap_err();
+        assert_eq!(error.code(), "decryption");
+        assert!(error.to_string().contains("`sops` feature"));
+
+        // 仅有同名普通键时按普通文件处理
+        let mut plain = NamedTempFile::with_suffix(".json").unwrap();
+        plain.write_all(br#"{"sops": {"enabled": true}}"#).unwrap();
+        assert!(QuantumConfigFileProvider::from_path(plain.path(), true, 32).unwrap().read_and_parse().is_ok());
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*.toml", "db.toml"));
+        assert!(wildcard_match("db-?.toml", "db-1.toml"));
+        assert!(wildcard_match("a*b*c", "aXbYbZc"));
+        assert!(!wildcard_match("*.toml", "db.toml.bak"));
+        assert!(!wildcard_match("db-?.toml", "db-10.toml"));
+    }
+
+    #[test]
+    fn test_includes_merge_before_including_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("config.toml");
+        std::fs::create_dir_all(dir.path().join("extra/nested")).unwrap();
+        std::fs::write(&root, "include = [\"extra/*.toml\", \"db.json\"]\nname = \"root\"\n[server]\nport = 80\n").unwrap();
+        std::fs::write(dir.path().join("extra/10-server.toml"), "[server]\nhost = \"a\"\nport = 1\n").unwrap();
+        std::fs::write(dir.path().join("extra/20-server.toml"), "include = \"nested/log.toml\"\n[server]\nhost = \"b\"\n").unwrap();
+        std::fs::write(dir.path().join("extra/notes.txt"), "ignored").unwrap();
+        std::fs::write(dir.path().join("extra/nested/log.toml"), "level = \"debug\"\n").unwrap();
+        std::fs::write(dir.path().join("db.json"), r#"{"name": "db", "database": {"url": "postgres://db"}}"#).unwrap();
+
+        let provider = QuantumConfigFileProvider::from_path(&root, true, 32).unwrap().with_includes(true);
+        let data = provider.data().unwrap();
+        let dict = data.get(&Profile::Default).unwrap();
+        assert!(dict.get(INCLUDE_KEY).is_none());
+        assert_eq!(dict.get("name").and_then(|v| v.as_str()), Some("root"));
+        assert_eq!(dict.get("level").and_then(|v| v.as_str()), Some("debug"));
+        let server = dict.get("server").and_then(|v| v.as_dict()).unwrap();
+        assert_eq!(server.get("host").and_then(|v| v.as_str()), Some("b"));
+        assert_eq!(server.get("port").and_then(|v| v.to_i128()), Some(80));
+        assert!(dict.get("database").is_some());
+
+        // 未启用时 `include` 作为普通键保留
+        let provider = QuantumConfigFileProvider::from_path(&root, true, 32).unwrap();
+        assert!(provider.data().unwrap()[&Profile::Default].contains_key(INCLUDE_KEY));
+    }
+
+    #[test]
+    fn test_include_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let include = |name: &str, content: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, content).unwrap();
+            QuantumConfigFileProvider::from_path(&path, true, 32)
+                .unwrap()
+                .with_includes(true)
+                .read_with_includes(&mut ResolutionContext::new())
+        };
+
+        std::fs::write(dir.path().join("b.toml"), "include = \"a.toml\"\n").unwrap();
+        let error = include("a.toml", "include = \"b.toml\"\n").unwrap_err();
+        assert_eq!(error.code(), "source_cycle");
+        assert!(error.to_string().contains("a.toml → "));
+
+        assert_eq!(include("missing.toml", "include = \"nope.toml\"\n").unwrap_err().code(), "specified_file_not_found");
+        assert_eq!(include("number.toml", "include = 1\n").unwrap_err().code(), "invalid_value");
+        assert_eq!(include("dirglob.toml", "include = \"*/x.toml\"\n").unwrap_err().code(), "invalid_value");
+        assert!(include("empty.toml", "include = \"none/*.toml\"\n").is_ok());
+
+        for i in 0..10 {
+            std::fs::write(dir.path().join(format!("deep{}.toml", i)), format!("include = \"deep{}.toml\"\n", i + 1)).unwrap();
+        }
+        std::fs::write(dir.path().join("deep10.toml"), "").unwrap();
+        assert_eq!(include("deep.toml", "include = \"deep0.toml\"\n").unwrap_err().code(), "source_depth_exceeded");
+    }
 }
 
 // 向后兼容的类型别名
\ No newline at end of file
@@ -2,8 +2,12 @@
 //!
 //! 提供文件读取的通用trait，允许用户自定义文件读取行为。
 
-use std::path::Path;
+use std::io::{IsTerminal, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use crate::error::QuantumConfigError;
+#[cfg(feature = "async")]
+use std::{future::Future, pin::Pin};
 
 /// 文件读取器trait
 /// 
@@ -31,6 +35,42 @@ pub trait FileReader: Send + Sync {
     /// 
     /// 如果文件存在返回true，否则返回false
     fn exists(&self, path: &Path) -> bool;
+
+    /// 读取指定路径的原始字节
+    ///
+    /// 文件提供器通过该方法读取内容，以便识别 BOM 与 UTF-16 编码。
+    /// 默认实现基于 `read_content`，能够直接读取字节的读取器应覆盖此方法。
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>, QuantumConfigError> {
+        self.read_content(path).map(String::into_bytes)
+    }
+
+    /// 列出目录中的条目，供 `include` 指令展开通配符
+    ///
+    /// 默认实现返回错误，即该读取器只支持不含通配符的包含路径。
+    fn list_dir(&self, dir: &Path) -> Result<Vec<PathBuf>, QuantumConfigError> {
+        Err(QuantumConfigError::Internal(format!(
+            "This file reader cannot list directory {}",
+            dir.display()
+        )))
+    }
+}
+
+impl<R: FileReader + ?Sized> FileReader for &R {
+    fn read_content(&self, path: &Path) -> Result<String, QuantumConfigError> {
+        (**self).read_content(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        (**self).exists(path)
+    }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>, QuantumConfigError> {
+        (**self).read_bytes(path)
+    }
+
+    fn list_dir(&self, dir: &Path) -> Result<Vec<PathBuf>, QuantumConfigError> {
+        (**self).list_dir(dir)
+    }
 }
 
 /// 标准文件系统读取器
@@ -59,11 +99,184 @@ impl FileReader for StandardFileReader {
     fn exists(&self, path: &Path) -> bool {
         path.exists() && path.is_file()
     }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>, QuantumConfigError> {
+        std::fs::read(path)
+            .map_err(|e| QuantumConfigError::FileReadError {
+                path: path.to_string_lossy().to_string(),
+                source: e,
+            })
+    }
+
+    fn list_dir(&self, dir: &Path) -> Result<Vec<PathBuf>, QuantumConfigError> {
+        let io_error = |source| QuantumConfigError::Io { source, path: dir.to_path_buf() };
+        std::fs::read_dir(dir)
+            .map_err(io_error)?
+            .map(|entry| entry.map(|entry| entry.path()).map_err(io_error))
+            .collect()
+    }
+}
+
+/// 环境变量读取器
+///
+/// 将"路径"视为环境变量名，读取该变量的值作为配置内容，
+/// 用于 `--config env://APP_CONFIG` 这类整段配置通过环境变量注入的场景。
+#[derive(Debug, Clone, Default)]
+pub struct EnvVarFileReader;
+
+impl EnvVarFileReader {
+    /// 创建新的环境变量读取器实例
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl FileReader for EnvVarFileReader {
+    fn read_content(&self, path: &Path) -> Result<String, QuantumConfigError> {
+        let name = path.to_string_lossy();
+        std::env::var(&*name).map_err(|e| QuantumConfigError::FileReadError {
+            path: format!("env://{}", name),
+            source: std::io::Error::new(std::io::ErrorKind::NotFound, e),
+        })
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        std::env::var_os(path).is_some()
+    }
+}
+
+/// 标准输入读取器
+///
+/// 首次读取时消费整个标准输入并缓存，之后的读取返回相同内容，
+/// 用于 `--config stdin:-` 场景。标准输入为终端（没有通过管道或重定向提供内容）时视为不存在，
+/// 加载报告来源缺失而不是等待交互输入。
+#[derive(Debug, Clone, Default)]
+pub struct StdinFileReader {
+    /// 缓存的标准输入内容
+    content: Arc<OnceLock<String>>,
+}
+
+impl StdinFileReader {
+    /// 创建新的标准输入读取器实例
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl FileReader for StdinFileReader {
+    fn read_content(&self, _path: &Path) -> Result<String, QuantumConfigError> {
+        if let Some(content) = self.content.get() {
+            return Ok(content.clone());
+        }
+
+        let mut content = String::new();
+        std::io::stdin()
+            .lock()
+            .read_to_string(&mut content)
+            .map_err(|e| QuantumConfigError::FileReadError {
+                path: "stdin:-".to_string(),
+                source: e,
+            })?;
+        Ok(self.content.get_or_init(|| content).clone())
+    }
+
+    fn exists(&self, _path: &Path) -> bool {
+        self.content.get().is_some() || !std::io::stdin().is_terminal()
+    }
+}
+
+/// 异步读取器返回的 Future
+#[cfg(feature = "async")]
+pub type ReadFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, QuantumConfigError>> + Send + 'a>>;
+
+/// 异步文件读取器trait
+///
+/// 供远程或较慢的来源（HTTP、对象存储等）在异步运行时中读取配置内容，
+/// 通过 [`crate::QuantumConfigBuilder::with_async_file`] 注册，由 `load_async` 在合并前读取。
+#[cfg(feature = "async")]
+pub trait AsyncFileReader: Send + Sync {
+    /// 读取指定路径的原始字节，文件不存在时返回错误
+    fn read_bytes_async<'a>(&'a self, path: &'a Path) -> ReadFuture<'a, Vec<u8>>;
+}
+
+#[cfg(feature = "async")]
+impl AsyncFileReader for StandardFileReader {
+    fn read_bytes_async<'a>(&'a self, path: &'a Path) -> ReadFuture<'a, Vec<u8>> {
+        Box::pin(async move {
+            tokio::fs::read(path).await.map_err(|e| QuantumConfigError::FileReadError {
+                path: path.to_string_lossy().to_string(),
+                source: e,
+            })
+        })
+    }
+}
+
+/// 将同步读取器适配为异步读取器，读取在 tokio 阻塞线程池中执行
+#[cfg(feature = "async")]
+#[derive(Debug, Clone, Default)]
+pub struct BlockingFileReader<R> {
+    /// 被适配的同步读取器
+    inner: Arc<R>,
+}
+
+#[cfg(feature = "async")]
+impl<R: FileReader + 'static> BlockingFileReader<R> {
+    /// 适配同步读取器
+    pub fn new(inner: R) -> Self {
+        Self { inner: Arc::new(inner) }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: FileReader + 'static> AsyncFileReader for BlockingFileReader<R> {
+    fn read_bytes_async<'a>(&'a self, path: &'a Path) -> ReadFuture<'a, Vec<u8>> {
+        let inner = Arc::clone(&self.inner);
+        let path = path.to_path_buf();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || inner.read_bytes(&path))
+                .await
+                .map_err(|e| QuantumConfigError::Internal(format!("File reading task failed: {}", e)))?
+        })
+    }
+}
+
+/// 已读取内容的读取器，异步读取的内容通过它交给文件提供器解析
+#[cfg(feature = "async")]
+#[derive(Debug, Clone)]
+pub(crate) struct PrefetchedFileReader {
+    /// 读取到的原始字节
+    bytes: Arc<Vec<u8>>,
+}
+
+#[cfg(feature = "async")]
+impl PrefetchedFileReader {
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes: Arc::new(bytes) }
+    }
+}
+
+#[cfg(feature = "async")]
+impl FileReader for PrefetchedFileReader {
+    fn read_content(&self, path: &Path) -> Result<String, QuantumConfigError> {
+        String::from_utf8(self.bytes.to_vec()).map_err(|e| QuantumConfigError::FileReadError {
+            path: path.to_string_lossy().to_string(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })
+    }
+
+    fn exists(&self, _path: &Path) -> bool {
+        true
+    }
+
+    fn read_bytes(&self, _path: &Path) -> Result<Vec<u8>, QuantumConfigError> {
+        Ok(self.bytes.to_vec())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::EnvGuard;
     use std::fs;
     use tempfile::tempdir;
     
@@ -121,6 +334,38 @@ mod tests {
         assert!(!reader.exists(dir.path()));
     }
     
+    #[test]
+    fn test_env_var_file_reader() {
+        let mut env = EnvGuard::new();
+        env.set("QUANTUM_CONFIG_TEST_ENV_READER", "port = 8080");
+        let reader = EnvVarFileReader::new();
+        let path = Path::new("QUANTUM_CONFIG_TEST_ENV_READER");
+
+        assert!(reader.exists(path));
+        assert_eq!(reader.read_content(path).unwrap(), "port = 8080");
+        env.remove("QUANTUM_CONFIG_TEST_ENV_READER");
+        assert!(!reader.exists(path));
+
+        let missing = Path::new("QUANTUM_CONFIG_TEST_ENV_READER_MISSING");
+        assert!(!reader.exists(missing));
+        match reader.read_content(missing).unwrap_err() {
+            QuantumConfigError::FileReadError { path, .. } => {
+                assert_eq!(path, "env://QUANTUM_CONFIG_TEST_ENV_READER_MISSING");
+            }
+            _ => panic!("Expected FileReadError"),
+        }
+    }
+
+    #[test]
+    fn test_stdin_file_reader_returns_cached_content() {
+        let reader = StdinFileReader::new();
+        reader.content.set("port = 8080".to_string()).unwrap();
+
+        assert!(reader.exists(Path::new("-")));
+        assert_eq!(reader.read_content(Path::new("-")).unwrap(), "port = 8080");
+        assert_eq!(reader.clone().read_content(Path::new("-")).unwrap(), "port = 8080");
+    }
+
     // 测试自定义FileReader实现
     #[derive(Debug)]
     struct MockFileReader {
@@ -163,4 +408,23 @@ mod tests {
         assert!(!reader.exists(Path::new("any_path")));
         assert!(reader.read_content(Path::new("any_path")).is_err());
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_async_readers_read_bytes() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.toml");
+        fs::write(&file_path, "key = 1").unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+        runtime.block_on(async {
+            let bytes = StandardFileReader::new().read_bytes_async(&file_path).await.unwrap();
+            assert_eq!(bytes, b"key = 1");
+
+            let blocking = BlockingFileReader::new(StandardFileReader::new());
+            assert_eq!(blocking.read_bytes_async(&file_path).await.unwrap(), b"key = 1");
+            let missing = blocking.read_bytes_async(Path::new("nonexistent.toml")).await;
+            assert!(matches!(missing, Err(QuantumConfigError::FileReadError { .. })));
+        });
+    }
+}
@@ -0,0 +1,151 @@
+//! HTTP(S) 远程文件读取器
+//!
+//! 基于 ureq 实现的 `FileReader`，将"路径"视为完整的 `http://` 或 `https://` 地址，
+//! 用于 `--config https://config.example.com/myapp/config.toml` 这类集中分发配置的场景。
+//!
+//! 启用 `ETag` 缓存时（[`LoadOptions::with_cache`](crate::LoadOptions::with_cache)），响应正文缓存在进程内，
+//! 再次读取时发送 `If-None-Match`，服务器返回 `304 Not Modified` 时直接使用缓存的正文。
+
+use super::file_reader::FileReader;
+use crate::error::QuantumConfigError;
+use std::path::Path;
+use std::time::Duration;
+
+/// 默认请求超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HTTP(S) 文件读取器
+#[derive(Debug, Clone)]
+pub struct HttpFileReader {
+    /// 请求超时
+    timeout: Duration,
+    /// 是否按 `ETag` 缓存响应正文
+    etag_cache: bool,
+}
+
+impl Default for HttpFileReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpFileReader {
+    /// 创建新的 HTTP 文件读取器，默认超时为 10 秒
+    pub fn new() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            etag_cache: false,
+        }
+    }
+
+    /// 设置请求超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置是否按 `ETag` 缓存响应正文，缓存在进程内共享
+    pub fn with_etag_cache(mut self, enabled: bool) -> Self {
+        self.etag_cache = enabled;
+        self
+    }
+
+    /// 发送 GET 请求并读取正文，启用 `ETag` 缓存时先校验缓存的正文是否仍然有效
+    fn fetch(&self, url: &str) -> Result<Vec<u8>, QuantumConfigError> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .into();
+        let cached = if self.etag_cache { crate::cache::cached_response(url) } else { None };
+        let mut request = agent.get(url);
+        if let Some(cached) = &cached {
+            request = request.header("If-None-Match", &cached.etag);
+        }
+        let mut response = request.call().map_err(|e| self.http_error(url, e))?;
+        if response.status() == 304 {
+            if let Some(cached) = cached {
+                return Ok(cached.body);
+            }
+        }
+
+        let body = response
+            .body_mut()
+            .read_to_vec()
+            .map_err(|e| self.http_error(url, e))?;
+        if self.etag_cache {
+            if let Some(etag) = response.headers().get("etag").and_then(|value| value.to_str().ok()) {
+                crate::cache::store_response(url, etag.to_string(), body.clone());
+            }
+        }
+        Ok(body)
+    }
+
+    /// 将 ureq 错误转换为文件读取错误
+    fn http_error(&self, url: &str, error: ureq::Error) -> QuantumConfigError {
+        let kind = match error {
+            ureq::Error::StatusCode(404) => std::io::ErrorKind::NotFound,
+            ureq::Error::StatusCode(401) | ureq::Error::StatusCode(403) => {
+                std::io::ErrorKind::PermissionDenied
+            }
+            ureq::Error::Timeout(_) => std::io::ErrorKind::TimedOut,
+            _ => std::io::ErrorKind::Other,
+        };
+        QuantumConfigError::FileReadError {
+            path: url.to_string(),
+            source: std::io::Error::new(kind, error.to_string()),
+        }
+    }
+}
+
+impl FileReader for HttpFileReader {
+    fn read_content(&self, path: &Path) -> Result<String, QuantumConfigError> {
+        let url = path.to_string_lossy();
+        String::from_utf8(self.fetch(&url)?).map_err(|e| QuantumConfigError::FileReadError {
+            path: url.to_string(),
+            source: std::io::Error::new(std::io::ErrorKind::InvalidData, e),
+        })
+    }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>, QuantumConfigError> {
+        self.fetch(&path.to_string_lossy())
+    }
+
+    fn exists(&self, _path: &Path) -> bool {
+        // 远程资源是否存在由 GET 请求的状态码决定，避免额外的 HEAD 请求
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::test_server::serve_with_headers;
+
+    #[test]
+    fn test_unreachable_host_reports_read_error() {
+        let reader = HttpFileReader::new().with_timeout(Duration::from_millis(200));
+        let result = reader.read_content(Path::new("http://127.0.0.1:1/config.toml"));
+
+        match result {
+            Err(QuantumConfigError::FileReadError { path, .. }) => {
+                assert_eq!(path, "http://127.0.0.1:1/config.toml");
+            }
+            other => panic!("Expected FileReadError, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_etag_cache_revalidates_with_if_none_match() {
+        let (address, requests) = serve_with_headers(vec![
+            (200, vec![("ETag", "\"v1\"".to_string())], "port = 8080".to_string()),
+            (304, Vec::new(), String::new()),
+        ]);
+        let url = format!("{}/etag/config.toml", address);
+        let reader = HttpFileReader::new().with_etag_cache(true);
+
+        assert_eq!(reader.read_content(Path::new(&url)).unwrap(), "port = 8080");
+        assert!(!requests.recv().unwrap().to_ascii_lowercase().contains("if-none-match"));
+        assert_eq!(reader.read_content(Path::new(&url)).unwrap(), "port = 8080");
+        assert!(requests.recv().unwrap().to_ascii_lowercase().contains("if-none-match: \"v1\""));
+    }
+}
@@ -0,0 +1,222 @@
+//! 配置键前缀树
+//!
+//! 环境变量与命令行参数提供器把扁平的键（例如 `MYAPP_DATABASE__HOST`、`--database.host`）
+//! 拆分为路径段后插入 [`KeyTrie`]，最后一次性转换为嵌套字典。
+//! 每个叶子还记录提供该值的原始名称（环境变量名或命令行参数），可用于来源查询。
+
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Tag, Value};
+use std::collections::BTreeMap;
+
+/// 同一键既是值又是嵌套对象时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// 返回错误（环境变量提供器使用）
+    #[default]
+    Reject,
+    /// 后插入的值覆盖先前的值或嵌套对象（命令行参数提供器使用）
+    Replace,
+}
+
+/// 前缀树节点
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    /// 叶子值
+    value: Option<Value>,
+    /// 提供该值的原始名称
+    origin: Option<String>,
+    /// 子节点
+    children: BTreeMap<String, TrieNode>,
+}
+
+impl TrieNode {
+    /// 转换为 figment 值：叶子返回其值，其余返回嵌套字典
+    fn into_value(self) -> Value {
+        match self.value {
+            Some(value) => value,
+            None => Value::Dict(Tag::Default, Self::into_dict(self.children)),
+        }
+    }
+
+    /// 将子节点转换为字典
+    fn into_dict(children: BTreeMap<String, TrieNode>) -> Dict {
+        children.into_iter().map(|(key, node)| (key, node.into_value())).collect()
+    }
+
+    /// 按深度优先顺序收集叶子的键路径与来源
+    fn collect_origins<'a>(&'a self, path: &mut Vec<&'a str>, origins: &mut Vec<(String, &'a str)>) {
+        if let Some(origin) = &self.origin {
+            origins.push((path.join("."), origin.as_str()));
+        }
+        for (key, child) in &self.children {
+            path.push(key);
+            child.collect_origins(path, origins);
+            path.pop();
+        }
+    }
+
+    /// 统计叶子数量
+    fn leaf_count(&self) -> usize {
+        usize::from(self.value.is_some()) + self.children.values().map(TrieNode::leaf_count).sum::<usize>()
+    }
+}
+
+/// 检查冲突策略是否允许覆盖
+fn check_policy(policy: ConflictPolicy, key: Option<&str>) -> Result<(), QuantumConfigError> {
+    match policy {
+        ConflictPolicy::Replace => Ok(()),
+        ConflictPolicy::Reject => Err(QuantumConfigError::Internal(format!(
+            "Configuration key conflict: '{}' cannot be both a value and a nested object",
+            key.unwrap_or_default()
+        ))),
+    }
+}
+
+/// 配置键前缀树
+#[derive(Debug, Clone, Default)]
+pub struct KeyTrie {
+    /// 根节点（只有子节点）
+    root: TrieNode,
+    /// 冲突处理策略
+    policy: ConflictPolicy,
+}
+
+impl KeyTrie {
+    /// 创建使用 [`ConflictPolicy::Reject`] 的前缀树
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建使用指定冲突策略的前缀树
+    pub fn with_policy(policy: ConflictPolicy) -> Self {
+        Self {
+            root: TrieNode::default(),
+            policy,
+        }
+    }
+
+    /// 按路径段插入值，`origin` 为提供该值的原始名称（例如环境变量名）
+    pub fn insert<'a, I>(&mut self, path: I, value: Value, origin: Option<String>) -> Result<(), QuantumConfigError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let policy = self.policy;
+        let mut node = &mut self.root;
+        let mut current: Option<&str> = None;
+        for segment in path {
+            if node.value.is_some() {
+                check_policy(policy, current)?;
+                node.value = None;
+                node.origin = None;
+            }
+            node = node.children.entry(segment.to_string()).or_default();
+            current = Some(segment);
+        }
+
+        let Some(key) = current else {
+            return Err(QuantumConfigError::Internal(
+                "Empty key provided for nested value insertion".to_string(),
+            ));
+        };
+        if !node.children.is_empty() {
+            check_policy(policy, Some(key))?;
+            node.children.clear();
+        }
+        node.value = Some(value);
+        node.origin = origin;
+        Ok(())
+    }
+
+    /// 按 `.` 分隔的键路径查找节点
+    fn node(&self, key_path: &str) -> Option<&TrieNode> {
+        key_path.split('.').try_fold(&self.root, |node, segment| node.children.get(segment))
+    }
+
+    /// 获取叶子值
+    pub fn get(&self, key_path: &str) -> Option<&Value> {
+        self.node(key_path)?.value.as_ref()
+    }
+
+    /// 获取提供叶子值的原始名称
+    pub fn origin(&self, key_path: &str) -> Option<&str> {
+        self.node(key_path)?.origin.as_deref()
+    }
+
+    /// 所有带来源的叶子，按键路径排序
+    pub fn origins(&self) -> Vec<(String, &str)> {
+        let mut origins = Vec::new();
+        self.root.collect_origins(&mut Vec::new(), &mut origins);
+        origins
+    }
+
+    /// 叶子数量
+    pub fn len(&self) -> usize {
+        self.root.leaf_count()
+    }
+
+    /// 是否没有任何值
+    pub fn is_empty(&self) -> bool {
+        self.root.children.is_empty()
+    }
+
+    /// 转换为嵌套字典
+    pub fn into_dict(self) -> Dict {
+        TrieNode::into_dict(self.root.children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(s: &str) -> Value {
+        Value::from(s.to_string())
+    }
+
+    #[test]
+    fn test_insert_builds_nested_dict_with_origins() {
+        let mut trie = KeyTrie::new();
+        trie.insert("database.host".split('.'), string("db"), Some("APP_DATABASE__HOST".to_string())).unwrap();
+        trie.insert("database.port".split('.'), Value::from(5432), Some("APP_DATABASE__PORT".to_string())).unwrap();
+        trie.insert(["name"], string("app"), None).unwrap();
+
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.get("database.host"), Some(&string("db")));
+        assert_eq!(trie.get("database"), None);
+        assert_eq!(trie.origin("database.port"), Some("APP_DATABASE__PORT"));
+        assert_eq!(
+            trie.origins(),
+            vec![
+                ("database.host".to_string(), "APP_DATABASE__HOST"),
+                ("database.port".to_string(), "APP_DATABASE__PORT"),
+            ]
+        );
+
+        let dict = trie.into_dict();
+        let database = dict.get("database").and_then(Value::as_dict).unwrap();
+        assert_eq!(database.get("host"), Some(&string("db")));
+        assert_eq!(dict.get("name"), Some(&string("app")));
+    }
+
+    #[test]
+    fn test_conflict_policies() {
+        let mut reject = KeyTrie::new();
+        reject.insert(["key"], string("value"), None).unwrap();
+        let error = reject.insert(["key", "nested"], string("nested"), None).unwrap_err();
+        assert!(error.to_string().contains("key conflict"));
+        // 反向顺序同样报错
+        let mut reject = KeyTrie::new();
+        reject.insert(["key", "nested"], string("nested"), None).unwrap();
+        assert!(reject.insert(["key"], string("value"), None).is_err());
+
+        let mut replace = KeyTrie::with_policy(ConflictPolicy::Replace);
+        replace.insert(["key"], string("value"), None).unwrap();
+        replace.insert(["key", "nested"], string("nested"), None).unwrap();
+        assert_eq!(replace.get("key.nested"), Some(&string("nested")));
+        replace.insert(["key"], string("value"), None).unwrap();
+        assert_eq!(replace.get("key"), Some(&string("value")));
+        assert_eq!(replace.len(), 1);
+
+        assert!(KeyTrie::new().insert(std::iter::empty(), string("x"), None).is_err());
+    }
+}
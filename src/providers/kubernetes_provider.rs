@@ -0,0 +1,262 @@
+//! Kubernetes 挂载目录提供器
+//!
+//! Kubernetes 把 ConfigMap / Secret 以卷的形式挂载为目录，每个键对应一个文件，
+//! 文件名即键名，文件内容即值。本提供器把这样的目录作为一个配置层，
+//! 文件名按分隔符（默认 `__`）拆分为嵌套键，例如 `db__password` 对应 `db.password`。
+//!
+//! Kubernetes 更新挂载内容时先写入带时间戳的隐藏目录（`..2024_01_01_00_00_00.123`），
+//! 再原子地替换 `..data` 符号链接，键文件本身是指向 `..data/<key>` 的符号链接。
+//! 提供器跳过所有以 `.` 开头的条目，并在每次读取时重新解析符号链接，
+//! 因此重新构建 figment 即可读到替换后的内容；配合 `ConfigWatcher` 使用时，
+//! 以 [`KubernetesDirProvider::watch_paths`] 作为监视路径即可在替换发生时触发重载。
+
+use super::env_provider::parse_scalar_value;
+use super::key_trie::KeyTrie;
+use super::secrets_provider::trim_trailing_newline;
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map, Tag, Value};
+use figment::{Error, Metadata, Profile, Provider, Source};
+use std::path::{Path, PathBuf};
+
+/// 默认的嵌套键分隔符
+pub const DEFAULT_KEY_SEPARATOR: &str = "__";
+
+/// Kubernetes 原子更新时被替换的符号链接名
+pub const DATA_LINK: &str = "..data";
+
+/// Kubernetes 挂载目录提供器
+#[derive(Debug, Clone)]
+pub struct KubernetesDirProvider {
+    /// 挂载目录
+    dir: PathBuf,
+    /// 文件名中的嵌套键分隔符
+    separator: String,
+    /// 是否把值解析为布尔值与数字
+    parse_values: bool,
+    /// 目录不存在时是否报错
+    required: bool,
+}
+
+impl KubernetesDirProvider {
+    /// 创建提供器，目录不存在时返回错误
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            separator: DEFAULT_KEY_SEPARATOR.to_string(),
+            parse_values: true,
+            required: true,
+        }
+    }
+
+    /// 设置文件名中的嵌套键分隔符
+    pub fn with_separator<S: Into<String>>(mut self, separator: S) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// 设置是否按环境变量的规则把值解析为布尔值与数字
+    ///
+    /// 默认启用；Secret 中的纯数字密码等需要保持字符串时关闭。
+    pub fn with_value_parsing(mut self, enabled: bool) -> Self {
+        self.parse_values = enabled;
+        self
+    }
+
+    /// 设置目录不存在时是否报错，不要求时缺失的目录视为空配置层
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    /// 挂载目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 供 `ConfigWatcher` 监视的路径
+    ///
+    /// 包含 `..data` 符号链接以捕获 Kubernetes 的原子替换，以及当前的各个键文件以捕获直接写入。
+    pub fn watch_paths(&self) -> Vec<PathBuf> {
+        let mut paths = vec![self.dir.join(DATA_LINK)];
+        if let Ok(entries) = self.key_files() {
+            paths.extend(entries.into_iter().map(|(_, path)| path));
+        }
+        paths
+    }
+
+    /// 列出键文件，按文件名排序，跳过隐藏条目与子目录
+    fn key_files(&self) -> Result<Vec<(String, PathBuf)>, QuantumConfigError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !self.required => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(QuantumConfigError::FileReadError {
+                    path: self.dir.display().to_string(),
+                    source: e,
+                })
+            }
+        };
+
+        let mut files = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| QuantumConfigError::FileReadError {
+                path: self.dir.display().to_string(),
+                source: e,
+            })?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            // `..data` 与时间戳目录以 `..` 开头，其余隐藏文件同样不是配置键
+            if name.starts_with('.') {
+                continue;
+            }
+            // 跟随符号链接判断类型，悬空链接在替换过程中可能短暂出现，直接跳过
+            let path = entry.path();
+            if std::fs::metadata(&path).is_ok_and(|metadata| metadata.is_file()) {
+                files.push((name, path));
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// 读取目录并构建键前缀树，叶子来源为文件名
+    pub fn key_trie(&self) -> Result<KeyTrie, QuantumConfigError> {
+        let mut trie = KeyTrie::new();
+        for (name, path) in self.key_files()? {
+            let content = std::fs::read_to_string(&path).map_err(|e| QuantumConfigError::FileReadError {
+                path: path.display().to_string(),
+                source: e,
+            })?;
+            let content = trim_trailing_newline(&content).to_string();
+            let value = if self.parse_values {
+                parse_scalar_value(content)
+            } else {
+                Value::String(Tag::Default, content)
+            };
+            let segments: Vec<&str> = name.split(self.separator.as_str()).collect();
+            if segments.iter().any(|segment| segment.is_empty()) {
+                return Err(QuantumConfigError::InvalidValue {
+                    key_path: name.clone(),
+                    message: format!("file name contains an empty key segment (separator '{}')", self.separator),
+                });
+            }
+            trie.insert(segments, value, Some(name.clone()))?;
+        }
+        Ok(trie)
+    }
+}
+
+impl Provider for KubernetesDirProvider {
+    fn metadata(&self) -> Metadata {
+        // 错误信息中的键路径还原为挂载目录中的文件名
+        let separator = self.separator.clone();
+        let mut metadata = Metadata::named(format!("Quantum Config Kubernetes Directory Provider ({})", self.dir.display()))
+            .interpolater(move |_profile, keys| keys.join(&separator));
+        metadata.source = Some(Source::File(self.dir.clone()));
+        metadata
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let trie = self
+            .key_trie()
+            .map_err(|e| Error::from(format!("Kubernetes directory provider error: {}", e)))?;
+        let mut profile_map = Map::new();
+        profile_map.insert(Profile::Default, trie.into_dict());
+        Ok(profile_map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::Figment;
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        password: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        name: String,
+        db: Database,
+    }
+
+    /// 按 Kubernetes 的布局写入一个版本目录
+    fn write_version(dir: &Path, version: &str, files: &[(&str, &str)]) {
+        let version_dir = dir.join(version);
+        fs::create_dir(&version_dir).unwrap();
+        for (name, content) in files {
+            fs::write(version_dir.join(name), content).unwrap();
+        }
+    }
+
+    /// 原子替换 `..data` 符号链接，与 kubelet 的做法一致
+    #[cfg(unix)]
+    fn swap_data_link(dir: &Path, version: &str) {
+        let tmp = dir.join("..data_tmp");
+        std::os::unix::fs::symlink(version, &tmp).unwrap();
+        fs::rename(&tmp, dir.join(DATA_LINK)).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reads_symlinked_layout_and_follows_atomic_updates() {
+        let dir = tempdir().unwrap();
+        let files = [("name", "app\n"), ("db__password", "s3cret\n"), ("db__port", "5432")];
+        write_version(dir.path(), "..2024_01_01_00_00_00.1", &files);
+        swap_data_link(dir.path(), "..2024_01_01_00_00_00.1");
+        for (name, _) in files {
+            std::os::unix::fs::symlink(Path::new(DATA_LINK).join(name), dir.path().join(name)).unwrap();
+        }
+
+        let provider = KubernetesDirProvider::new(dir.path());
+        let config: AppConfig = Figment::from(provider.clone()).extract().unwrap();
+        assert_eq!(
+            config,
+            AppConfig {
+                name: "app".to_string(),
+                db: Database { password: "s3cret".to_string(), port: 5432 },
+            }
+        );
+        assert_eq!(provider.key_trie().unwrap().origin("db.password"), Some("db__password"));
+        assert!(provider.watch_paths().contains(&dir.path().join(DATA_LINK)));
+
+        write_version(dir.path(), "..2024_01_02_00_00_00.2", &[("name", "app"), ("db__password", "rotated"), ("db__port", "6432")]);
+        swap_data_link(dir.path(), "..2024_01_02_00_00_00.2");
+        let config: AppConfig = Figment::from(provider).extract().unwrap();
+        assert_eq!(config.db, Database { password: "rotated".to_string(), port: 6432 });
+    }
+
+    #[test]
+    fn test_separator_value_parsing_and_missing_dir() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("db.password"), "1234\n").unwrap();
+        fs::write(dir.path().join("db.port"), "5432").unwrap();
+        fs::write(dir.path().join("name"), "app").unwrap();
+        fs::write(dir.path().join(".hidden"), "ignored").unwrap();
+        fs::create_dir(dir.path().join("subdir")).unwrap();
+
+        let provider = KubernetesDirProvider::new(dir.path()).with_separator(".");
+        assert!(Figment::from(provider.clone()).extract::<AppConfig>().is_err());
+
+        // 纯数字密码需要关闭值解析才能保持字符串
+        let raw = provider.with_value_parsing(false);
+        let trie = raw.key_trie().unwrap();
+        assert_eq!(trie.len(), 3);
+        assert_eq!(trie.get("db.password"), Some(&Value::from("1234".to_string())));
+
+        let missing = KubernetesDirProvider::new(dir.path().join("missing"));
+        assert!(Figment::from(missing.clone()).extract::<AppConfig>().is_err());
+        assert!(missing.with_required(false).key_trie().unwrap().is_empty());
+
+        fs::write(dir.path().join("a____b"), "x").unwrap();
+        let error = KubernetesDirProvider::new(dir.path()).key_trie().unwrap_err();
+        assert!(error.to_string().contains("empty key segment"));
+    }
+}
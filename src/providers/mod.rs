@@ -2,15 +2,112 @@
 //!
 //! 包含各种配置数据源的提供器实现。
 
+#[cfg(feature = "aws")]
+pub mod aws_provider;
+#[cfg(feature = "azure")]
+pub mod azure_provider;
+#[cfg(feature = "cli")]
 pub mod clap_provider;
+#[cfg(feature = "consul")]
+pub mod consul_provider;
+pub mod dotenv_provider;
+#[cfg(feature = "encryption")]
+pub mod encrypted_values;
+#[cfg(feature = "encryption")]
+pub mod encryption;
 pub mod env_provider;
+pub mod env_source;
+#[cfg(feature = "etcd")]
+pub mod etcd_provider;
+#[cfg(feature = "exec")]
+pub mod exec_provider;
+#[cfg(feature = "gcp")]
+pub mod gcp_provider;
 pub mod file_provider;
 pub mod file_reader;
+pub mod key_trie;
+pub mod kubernetes_provider;
+#[cfg(feature = "http")]
+pub mod http_reader;
+pub mod secrets_provider;
+#[cfg(feature = "sftp")]
+pub mod sftp_reader;
+#[cfg(any(
+    feature = "vault",
+    feature = "etcd",
+    feature = "consul",
+    feature = "aws",
+    feature = "gcp",
+    feature = "azure",
+    feature = "zookeeper"
+))]
+mod remote;
+#[cfg(feature = "aws")]
+pub mod s3_reader;
+#[cfg(feature = "sops")]
+pub mod sops;
+pub mod source;
+pub mod string_provider;
+#[cfg(all(
+    test,
+    any(
+        feature = "vault",
+        feature = "etcd",
+        feature = "consul",
+        feature = "aws",
+        feature = "gcp",
+        feature = "azure",
+        feature = "http"
+    )
+))]
+mod test_server;
+#[cfg(feature = "vault")]
+pub mod vault_provider;
+#[cfg(feature = "xml")]
+pub(crate) mod xml;
+#[cfg(feature = "zookeeper")]
+pub mod zookeeper_provider;
 
+#[cfg(feature = "aws")]
+pub use aws_provider::{AwsCredentials, SecretsManagerProvider, SsmParameterProvider};
+#[cfg(feature = "azure")]
+pub use azure_provider::{AzureAuth, KeyVaultProvider};
+#[cfg(feature = "cli")]
 pub use clap_provider::QuantumConfigClapProvider;
-pub use env_provider::QuantumConfigEnvProvider;
+#[cfg(feature = "consul")]
+pub use consul_provider::ConsulProvider;
+pub use dotenv_provider::DotenvProvider;
+#[cfg(feature = "encryption")]
+pub use encrypted_values::EncryptedValuesProvider;
+#[cfg(feature = "encryption")]
+pub use encryption::{EncryptionKey, KeyChain};
+pub use env_provider::{EnvCase, EnvScanScope, EnvSnapshot, QuantumConfigEnvProvider};
+pub use env_source::{EnvSource, MapEnv, ProcessEnv};
+#[cfg(feature = "etcd")]
+pub use etcd_provider::EtcdProvider;
+#[cfg(feature = "exec")]
+pub use exec_provider::ExecProvider;
+#[cfg(feature = "gcp")]
+pub use gcp_provider::{GcpAuth, GcpSecretManagerProvider};
 pub use file_provider::{QuantumConfigFileProvider, QuantumConfigFileProviderGeneric};
-pub use file_reader::{FileReader, StandardFileReader};
+pub use file_reader::{EnvVarFileReader, FileReader, StandardFileReader, StdinFileReader};
+#[cfg(feature = "async")]
+pub use file_reader::{AsyncFileReader, BlockingFileReader, ReadFuture};
+pub use key_trie::{ConflictPolicy, KeyTrie};
+pub use kubernetes_provider::KubernetesDirProvider;
+#[cfg(feature = "http")]
+pub use http_reader::HttpFileReader;
+#[cfg(feature = "aws")]
+pub use s3_reader::S3FileReader;
+#[cfg(feature = "sftp")]
+pub use sftp_reader::{HostKeyCheck, SftpAuth, SftpFileReader};
+pub use secrets_provider::SecretsProvider;
+pub use source::ConfigSource;
+pub use string_provider::StringProvider;
+#[cfg(feature = "vault")]
+pub use vault_provider::{VaultAuth, VaultProvider};
+#[cfg(feature = "zookeeper")]
+pub use zookeeper_provider::ZookeeperProvider;
 
 // 向后兼容的类型别名（内部使用）
 // 注意：这些类型别名仅用于内部兼容，不对外暴露
@@ -0,0 +1,135 @@
+//! 远程配置服务提供器共用的错误处理
+//!
+//! Vault、etcd、Consul、云密钥服务与 ZooKeeper 提供器的错误统一为 [`QuantumConfigError::Remote`]，
+//! 请求失败时的描述与转换为 figment 错误的方式在这里实现，各提供器只给出服务名称与常见状态码的说明。
+//! 监视连接断开后的重连退避也在这里实现。
+
+use crate::error::QuantumConfigError;
+use figment::Error;
+#[cfg(any(feature = "etcd", feature = "zookeeper"))]
+use std::time::Duration;
+
+/// 监视连接断开后首次重连前的默认等待
+#[cfg(any(feature = "etcd", feature = "zookeeper"))]
+pub(crate) const DEFAULT_RECONNECT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// 重连等待的默认上限
+#[cfg(any(feature = "etcd", feature = "zookeeper"))]
+pub(crate) const DEFAULT_MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// 重连退避：首次等待 `initial`，每次失败加倍，不超过 `max`
+#[cfg(any(feature = "etcd", feature = "zookeeper"))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Backoff {
+    initial: Duration,
+    max: Duration,
+    failures: u32,
+}
+
+#[cfg(any(feature = "etcd", feature = "zookeeper"))]
+impl Backoff {
+    pub(crate) fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            failures: 0,
+        }
+    }
+
+    /// 下一次重连前的等待，并记录一次失败
+    pub(crate) fn next_delay(&mut self) -> Duration {
+        let delay = self.initial.saturating_mul(2u32.saturating_pow(self.failures)).min(self.max);
+        self.failures = self.failures.saturating_add(1);
+        delay
+    }
+
+    /// 重连成功后恢复初始等待
+    pub(crate) fn reset(&mut self) {
+        self.failures = 0;
+    }
+}
+
+/// 构造远程服务错误
+pub(crate) fn error(service: &'static str, resource: impl Into<String>, reason: impl Into<String>) -> QuantumConfigError {
+    QuantumConfigError::Remote {
+        service,
+        resource: resource.into(),
+        reason: reason.into(),
+    }
+}
+
+/// 将 ureq 错误转换为描述文本，`hints` 为常见状态码的说明，例如 `(403, "permission denied")`
+#[cfg(any(
+    feature = "vault",
+    feature = "etcd",
+    feature = "consul",
+    feature = "aws",
+    feature = "gcp",
+    feature = "azure"
+))]
+pub(crate) fn describe_error(error: ureq::Error, hints: &[(u16, &str)]) -> String {
+    match error {
+        ureq::Error::StatusCode(code) => match hints.iter().find(|(status, _)| *status == code) {
+            Some((_, hint)) => format!("{} (HTTP {})", hint, code),
+            None => format!("HTTP status {}", code),
+        },
+        other => other.to_string(),
+    }
+}
+
+/// 转换为提供器返回的 figment 错误
+///
+/// `key_path` 为提供器的值在配置中的位置（例如 `with_nested_key` 指定的键），错误本身不带键路径时用于定位出错的键。
+pub(crate) fn provider_error(error: QuantumConfigError, key_path: Option<&str>) -> Error {
+    let mut error = error.into_provider_error(None);
+    if let Some(key_path) = key_path.filter(|_| error.path.is_empty()) {
+        error.path = key_path.split('.').map(str::to_string).collect();
+    }
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_provider_error_keeps_key_path() {
+        let denied = provider_error(error("vault", "secret/app", "permission denied (HTTP 403)"), Some("database.auth"));
+        assert_eq!(denied.path, ["database", "auth"]);
+        assert_eq!(denied.to_string(), "vault error for 'secret/app': permission denied (HTTP 403)");
+
+        // 错误自身的键路径优先
+        let violation = QuantumConfigError::ConstraintViolation {
+            key_path: "api.key".to_string(),
+            message: "too long".to_string(),
+            origin: None,
+        };
+        assert_eq!(provider_error(violation, Some("database")).path, ["api", "key"]);
+        assert!(provider_error(error("etcd", "/app/", "timeout"), None).path.is_empty());
+    }
+
+    #[cfg(any(feature = "etcd", feature = "zookeeper"))]
+    #[test]
+    fn test_backoff_doubles_up_to_max_and_resets() {
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+        let delays: Vec<u64> = (0..5).map(|_| backoff.next_delay().as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 5, 5]);
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[cfg(any(
+        feature = "vault",
+        feature = "etcd",
+        feature = "consul",
+        feature = "aws",
+        feature = "gcp",
+        feature = "azure"
+    ))]
+    #[test]
+    fn test_describe_error_uses_status_hints() {
+        let hints = [(403, "permission denied"), (404, "secret not found")];
+        assert_eq!(describe_error(ureq::Error::StatusCode(404), &hints), "secret not found (HTTP 404)");
+        assert_eq!(describe_error(ureq::Error::StatusCode(500), &hints), "HTTP status 500");
+    }
+}
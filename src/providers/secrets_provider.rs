@@ -0,0 +1,207 @@
+//! 密钥引用解析提供器
+//!
+//! 包装任意 figment Provider，在加载时把字符串值中的密钥文件引用替换为文件内容，
+//! 便于直接使用 Docker / Kubernetes 挂载的密钥文件：
+//!
+//! - 整个值为 `secret_file:/run/secrets/db_password` 时，替换为该文件的内容
+//! - 值中的 `${file:/path}` 占位符替换为对应文件的内容
+//!
+//! 文件内容去除末尾换行，并登记到 [`crate::scrub`] 以免出现在日志或 panic 信息中。
+
+use super::file_reader::{FileReader, StandardFileReader};
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map, Value};
+use figment::{Error, Metadata, Profile, Provider};
+use std::path::Path;
+
+/// 整值引用前缀
+const SECRET_FILE_PREFIX: &str = "secret_file:";
+
+/// 占位符起始标记
+const PLACEHOLDER_START: &str = "${file:";
+
+/// 密钥引用解析提供器
+#[derive(Debug, Clone)]
+pub struct SecretsProvider<P, R = StandardFileReader> {
+    /// 被包装的提供器
+    inner: P,
+    /// 读取密钥文件的读取器
+    reader: R,
+}
+
+impl<P: Provider> SecretsProvider<P> {
+    /// 包装提供器，使用标准文件系统读取密钥文件
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            reader: StandardFileReader::new(),
+        }
+    }
+}
+
+impl<P: Provider, R: FileReader> SecretsProvider<P, R> {
+    /// 使用自定义文件读取器读取密钥文件
+    pub fn with_reader<R2: FileReader>(self, reader: R2) -> SecretsProvider<P, R2> {
+        SecretsProvider {
+            inner: self.inner,
+            reader,
+        }
+    }
+
+    /// 递归解析值中的密钥引用，`path` 为值所在的键路径
+    fn resolve_value(&self, path: &str, value: Value) -> Result<Value, QuantumConfigError> {
+        match value {
+            Value::String(tag, s) => {
+                // 密钥文件暂时不可用的错误保持原样，其余错误附带键路径
+                let resolved = self.resolve_string(s).map_err(|e| match e {
+                    e if e.is_unavailable() => e,
+                    e => QuantumConfigError::InvalidValue {
+                        key_path: path.to_string(),
+                        message: e.to_string(),
+                    },
+                })?;
+                Ok(Value::String(tag, resolved))
+            }
+            Value::Dict(tag, dict) => Ok(Value::Dict(tag, self.resolve_dict(path, dict)?)),
+            Value::Array(tag, items) => {
+                let items = items
+                    .into_iter()
+                    .map(|item| self.resolve_value(path, item))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::Array(tag, items))
+            }
+            other => Ok(other),
+        }
+    }
+
+    /// 解析字典中的密钥引用
+    fn resolve_dict(&self, prefix: &str, dict: Dict) -> Result<Dict, QuantumConfigError> {
+        dict.into_iter()
+            .map(|(name, value)| {
+                let path = if prefix.is_empty() { name.clone() } else { format!("{}.{}", prefix, name) };
+                Ok((name, self.resolve_value(&path, value)?))
+            })
+            .collect()
+    }
+
+    /// 解析单个字符串中的密钥引用
+    fn resolve_string(&self, s: String) -> Result<String, QuantumConfigError> {
+        if let Some(path) = s.strip_prefix(SECRET_FILE_PREFIX) {
+            return self.read_secret(path.trim());
+        }
+        if !s.contains(PLACEHOLDER_START) {
+            return Ok(s);
+        }
+
+        let mut resolved = String::with_capacity(s.len());
+        let mut rest = s.as_str();
+        while let Some(start) = rest.find(PLACEHOLDER_START) {
+            resolved.push_str(&rest[..start]);
+            let after = &rest[start + PLACEHOLDER_START.len()..];
+            // 值中可能包含其他敏感内容，错误信息不回显原值
+            let end = after
+                .find('}')
+                .ok_or_else(|| QuantumConfigError::ValidationError("Unterminated secret placeholder".to_string()))?;
+            resolved.push_str(&self.read_secret(after[..end].trim())?);
+            rest = &after[end + 1..];
+        }
+        resolved.push_str(rest);
+        Ok(resolved)
+    }
+
+    /// 读取密钥文件并登记为敏感值
+    fn read_secret(&self, path: &str) -> Result<String, QuantumConfigError> {
+        if path.is_empty() {
+            return Err(QuantumConfigError::ValidationError(
+                "Secret file reference has an empty path".to_string(),
+            ));
+        }
+        let content = self.reader.read_content(Path::new(path))?;
+        let secret = trim_trailing_newline(&content).to_string();
+        crate::scrub::replace_secret(&format!("file:{}", path), secret.clone());
+        Ok(secret)
+    }
+}
+
+/// 去除一个末尾换行（`\n` 或 `\r\n`），密钥文件通常以换行结尾
+pub(crate) fn trim_trailing_newline(content: &str) -> &str {
+    let content = content.strip_suffix('\n').unwrap_or(content);
+    content.strip_suffix('\r').unwrap_or(content)
+}
+
+impl<P: Provider, R: FileReader> Provider for SecretsProvider<P, R> {
+    fn metadata(&self) -> Metadata {
+        // 保留被包装提供器的来源信息，错误信息仍指向原始来源
+        self.inner.metadata()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let mut resolved = Map::new();
+        for (profile, dict) in self.inner.data()? {
+            let dict = self
+                .resolve_dict("", dict)
+                .map_err(|e| e.into_provider_error(Some("Secret reference error")))?;
+            resolved.insert(profile, dict);
+        }
+        Ok(resolved)
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.inner.profile()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::providers::Serialized;
+    use figment::Figment;
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct DatabaseConfig {
+        password: String,
+        url: String,
+        user: String,
+    }
+
+    #[test]
+    fn test_resolves_secret_file_and_placeholders() {
+        let dir = tempdir().unwrap();
+        let password_path = dir.path().join("db_password");
+        fs::write(&password_path, "secrets-provider-pw\n").unwrap();
+
+        let inner = Serialized::defaults(serde_json::json!({
+            "password": format!("secret_file:{}", password_path.display()),
+            "url": format!("postgres://app:${{file:{}}}@db/app", password_path.display()),
+            "user": "app",
+        }));
+        let config: DatabaseConfig = Figment::from(SecretsProvider::new(inner)).extract().unwrap();
+
+        assert_eq!(
+            config,
+            DatabaseConfig {
+                password: "secrets-provider-pw".to_string(),
+                url: "postgres://app:secrets-provider-pw@db/app".to_string(),
+                user: "app".to_string(),
+            }
+        );
+        #[cfg(feature = "secret-scrub")]
+        assert!(!crate::scrub::scrub("pw=secrets-provider-pw").contains("secrets-provider-pw"));
+    }
+
+    #[test]
+    fn test_reports_missing_and_malformed_references() {
+        let missing = Serialized::defaults(serde_json::json!({ "password": "secret_file:/nonexistent/secret" }));
+        let result = Figment::from(SecretsProvider::new(missing)).extract::<serde_json::Value>();
+        assert!(result.unwrap_err().to_string().contains("/nonexistent/secret"));
+
+        let unterminated = Serialized::defaults(serde_json::json!({ "db": { "url": "postgres://app:inline-pw@${file:/run/secret" } }));
+        let error = Figment::from(SecretsProvider::new(unterminated)).extract::<serde_json::Value>().unwrap_err();
+        assert_eq!(error.path, ["db", "url"]);
+        let message = error.to_string();
+        assert!(message.contains("Unterminated") && !message.contains("inline-pw"), "{}", message);
+    }
+}
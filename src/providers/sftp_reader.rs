@@ -0,0 +1,432 @@
+//! SFTP 远程文件读取器
+//!
+//! 基于 ssh2 实现的 `FileReader`，用于从堡垒机等集中管理配置的远程主机读取配置文件，
+//! 例如 `--config sftp://admin@bastion:22/etc/myapp/config.toml`。
+//! 支持主机密钥校验策略与连接超时设置。
+
+use super::file_reader::FileReader;
+use crate::error::QuantumConfigError;
+use ssh2::{CheckResult, HashType, KnownHostFileKind, Session};
+use std::fmt;
+use std::io::Read;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// SFTP URL 前缀
+pub const SFTP_SCHEME: &str = "sftp://";
+
+/// 默认 SSH 端口
+const DEFAULT_SSH_PORT: u16 = 22;
+
+/// 默认连接与读取超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// SFTP 认证方式
+#[derive(Clone)]
+pub enum SftpAuth {
+    /// 使用 ssh-agent 中的身份
+    Agent,
+    /// 使用密码认证
+    Password(String),
+    /// 使用私钥文件认证
+    PublicKey {
+        /// 私钥文件路径
+        private_key: PathBuf,
+        /// 私钥口令
+        passphrase: Option<String>,
+    },
+}
+
+impl fmt::Debug for SftpAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 避免在日志或错误信息中泄露凭据
+        match self {
+            SftpAuth::Agent => write!(f, "Agent"),
+            SftpAuth::Password(_) => write!(f, "Password(<redacted>)"),
+            SftpAuth::PublicKey { private_key, passphrase } => f
+                .debug_struct("PublicKey")
+                .field("private_key", private_key)
+                .field("passphrase", &passphrase.as_ref().map(|_| "<redacted>"))
+                .finish(),
+        }
+    }
+}
+
+/// 主机密钥校验策略
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HostKeyCheck {
+    /// 使用 known_hosts 文件严格校验（默认为 `~/.ssh/known_hosts`）
+    KnownHosts(PathBuf),
+    /// 与给定的 SHA256 指纹（十六进制，不区分大小写，可包含冒号）比对
+    Fingerprint(String),
+    /// 不校验主机密钥（仅用于测试环境，存在中间人攻击风险）
+    AcceptAny,
+}
+
+impl Default for HostKeyCheck {
+    fn default() -> Self {
+        let known_hosts = directories::BaseDirs::new()
+            .map(|dirs| dirs.home_dir().join(".ssh").join("known_hosts"))
+            .unwrap_or_else(|| PathBuf::from(".ssh/known_hosts"));
+        HostKeyCheck::KnownHosts(known_hosts)
+    }
+}
+
+/// SFTP 文件读取器
+///
+/// 同一个读取器实例会复用已建立的 SSH 会话，避免 `exists` 与 `read_content`
+/// 各自建立连接。
+#[derive(Clone)]
+pub struct SftpFileReader {
+    /// 远程主机名
+    host: String,
+    /// 远程端口
+    port: u16,
+    /// 登录用户名
+    username: String,
+    /// 认证方式
+    auth: SftpAuth,
+    /// 主机密钥校验策略
+    host_key_check: HostKeyCheck,
+    /// 连接与读写超时
+    timeout: Duration,
+    /// 缓存的 SSH 会话
+    session: Arc<Mutex<Option<Session>>>,
+}
+
+impl fmt::Debug for SftpFileReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SftpFileReader")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("username", &self.username)
+            .field("auth", &self.auth)
+            .field("host_key_check", &self.host_key_check)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl SftpFileReader {
+    /// 创建新的 SFTP 文件读取器
+    ///
+    /// 默认使用当前系统用户名、ssh-agent 认证、`~/.ssh/known_hosts` 校验主机密钥，
+    /// 超时为 10 秒。
+    ///
+    /// # Arguments
+    /// * `host` - 远程主机名
+    pub fn new<S: Into<String>>(host: S) -> Self {
+        let username = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_else(|_| "root".to_string());
+        Self {
+            host: host.into(),
+            port: DEFAULT_SSH_PORT,
+            username,
+            auth: SftpAuth::Agent,
+            host_key_check: HostKeyCheck::default(),
+            timeout: DEFAULT_TIMEOUT,
+            session: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// 从 `sftp://[user@]host[:port]/path` 形式的 URL 创建读取器
+    ///
+    /// # Returns
+    /// 返回读取器以及远程文件路径
+    ///
+    /// # Errors
+    /// URL 格式不正确时返回 `InvalidSourceUrl` 错误
+    pub fn from_url(url: &str) -> Result<(Self, PathBuf), QuantumConfigError> {
+        let invalid = |reason: &str| QuantumConfigError::InvalidSourceUrl {
+            url: url.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let rest = url.strip_prefix(SFTP_SCHEME).ok_or_else(|| invalid("expected sftp:// scheme"))?;
+        let slash = rest.find('/').ok_or_else(|| invalid("missing remote file path"))?;
+        let (authority, remote_path) = rest.split_at(slash);
+        if remote_path.len() <= 1 {
+            return Err(invalid("missing remote file path"));
+        }
+
+        let (user, host_port) = match authority.rsplit_once('@') {
+            Some((user, host_port)) => (Some(user), host_port),
+            None => (None, authority),
+        };
+        let (host, port) = match host_port.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port.parse::<u16>().map_err(|_| invalid("invalid port"))?;
+                (host, port)
+            }
+            None => (host_port, DEFAULT_SSH_PORT),
+        };
+        if host.is_empty() {
+            return Err(invalid("missing host"));
+        }
+
+        let mut reader = Self::new(host).with_port(port);
+        if let Some(user) = user.filter(|u| !u.is_empty()) {
+            reader = reader.with_username(user);
+        }
+        Ok((reader, PathBuf::from(remote_path)))
+    }
+
+    /// 设置远程端口
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// 设置登录用户名
+    pub fn with_username<S: Into<String>>(mut self, username: S) -> Self {
+        self.username = username.into();
+        self
+    }
+
+    /// 设置认证方式
+    pub fn with_auth(mut self, auth: SftpAuth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// 设置主机密钥校验策略
+    pub fn with_host_key_check(mut self, check: HostKeyCheck) -> Self {
+        self.host_key_check = check;
+        self
+    }
+
+    /// 设置连接与读写超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 构造带远程位置信息的 I/O 错误
+    fn io_error(&self, path: &Path, source: std::io::Error) -> QuantumConfigError {
+        QuantumConfigError::FileReadError {
+            path: format!("{}{}:{}{}", SFTP_SCHEME, self.host, self.port, path.display()),
+            source,
+        }
+    }
+
+    /// 将 ssh2 错误转换为 I/O 错误
+    fn ssh_error(&self, path: &Path, err: ssh2::Error) -> QuantumConfigError {
+        self.io_error(path, std::io::Error::other(err.to_string()))
+    }
+
+    /// 建立 TCP 连接、校验主机密钥并完成认证
+    fn connect(&self, path: &Path) -> Result<Session, QuantumConfigError> {
+        let addr = (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .map_err(|e| self.io_error(path, e))?
+            .next()
+            .ok_or_else(|| {
+                self.io_error(
+                    path,
+                    std::io::Error::new(std::io::ErrorKind::NotFound, "host did not resolve"),
+                )
+            })?;
+        let tcp = TcpStream::connect_timeout(&addr, self.timeout).map_err(|e| self.io_error(path, e))?;
+        tcp.set_read_timeout(Some(self.timeout)).map_err(|e| self.io_error(path, e))?;
+        tcp.set_write_timeout(Some(self.timeout)).map_err(|e| self.io_error(path, e))?;
+
+        let mut session = Session::new().map_err(|e| self.ssh_error(path, e))?;
+        session.set_timeout(self.timeout.as_millis().min(u32::MAX as u128) as u32);
+        session.set_tcp_stream(tcp);
+        session.handshake().map_err(|e| self.ssh_error(path, e))?;
+
+        self.verify_host_key(&session)?;
+        self.authenticate(&session, path)?;
+        Ok(session)
+    }
+
+    /// 按照配置的策略校验远程主机密钥
+    fn verify_host_key(&self, session: &Session) -> Result<(), QuantumConfigError> {
+        let violation = |message: String| QuantumConfigError::SecurityViolation { message };
+
+        match &self.host_key_check {
+            HostKeyCheck::AcceptAny => Ok(()),
+            HostKeyCheck::Fingerprint(expected) => {
+                let actual = session
+                    .host_key_hash(HashType::Sha256)
+                    .ok_or_else(|| violation(format!("SFTP host key for {} unavailable", self.host)))?;
+                let actual_hex: String = actual.iter().map(|b| format!("{:02x}", b)).collect();
+                let expected_hex = expected.replace(':', "").to_lowercase();
+                if actual_hex == expected_hex {
+                    Ok(())
+                } else {
+                    Err(violation(format!("SFTP host key fingerprint mismatch for {}", self.host)))
+                }
+            }
+            HostKeyCheck::KnownHosts(file) => {
+                let (key, _) = session
+                    .host_key()
+                    .ok_or_else(|| violation(format!("SFTP host key for {} unavailable", self.host)))?;
+                let mut known_hosts = session
+                    .known_hosts()
+                    .map_err(|e| violation(format!("Cannot initialize known_hosts: {}", e)))?;
+                known_hosts
+                    .read_file(file, KnownHostFileKind::OpenSSH)
+                    .map_err(|e| violation(format!("Cannot read known_hosts file: {}", e)))?;
+                match known_hosts.check_port(&self.host, self.port, key) {
+                    CheckResult::Match => Ok(()),
+                    CheckResult::Mismatch => Err(violation(format!(
+                        "SFTP host key for {} does not match known_hosts entry",
+                        self.host
+                    ))),
+                    CheckResult::NotFound => Err(violation(format!(
+                        "SFTP host {} not found in known_hosts",
+                        self.host
+                    ))),
+                    CheckResult::Failure => Err(violation(format!(
+                        "SFTP host key check failed for {}",
+                        self.host
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// 使用配置的认证方式登录
+    fn authenticate(&self, session: &Session, path: &Path) -> Result<(), QuantumConfigError> {
+        let result = match &self.auth {
+            SftpAuth::Agent => session.userauth_agent(&self.username),
+            SftpAuth::Password(password) => session.userauth_password(&self.username, password),
+            SftpAuth::PublicKey { private_key, passphrase } => session.userauth_pubkey_file(
+                &self.username,
+                None,
+                private_key,
+                passphrase.as_deref(),
+            ),
+        };
+        result.map_err(|e| self.ssh_error(path, e))?;
+
+        if !session.authenticated() {
+            return Err(QuantumConfigError::SecurityViolation {
+                message: format!("SFTP authentication failed for {}@{}", self.username, self.host),
+            });
+        }
+        Ok(())
+    }
+
+    /// 获取（必要时建立）会话后执行操作
+    fn with_session<T>(
+        &self,
+        path: &Path,
+        op: impl FnOnce(&Session) -> Result<T, QuantumConfigError>,
+    ) -> Result<T, QuantumConfigError> {
+        let mut guard = self.session.lock().map_err(|_| {
+            self.io_error(path, std::io::Error::other("SFTP session lock poisoned"))
+        })?;
+        if guard.is_none() {
+            *guard = Some(self.connect(path)?);
+        }
+        let session = guard.as_ref().expect("session initialized above");
+        let result = op(session);
+        if result.is_err() {
+            // 出错后丢弃会话，下次调用重新连接
+            *guard = None;
+        }
+        result
+    }
+}
+
+impl FileReader for SftpFileReader {
+    fn read_content(&self, path: &Path) -> Result<String, QuantumConfigError> {
+        let bytes = self.read_bytes(path)?;
+        String::from_utf8(bytes).map_err(|e| {
+            self.io_error(path, std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        })
+    }
+
+    fn read_bytes(&self, path: &Path) -> Result<Vec<u8>, QuantumConfigError> {
+        self.with_session(path, |session| {
+            let sftp = session.sftp().map_err(|e| self.ssh_error(path, e))?;
+            let mut file = sftp.open(path).map_err(|e| self.ssh_error(path, e))?;
+            let mut content = Vec::new();
+            file.read_to_end(&mut content).map_err(|e| self.io_error(path, e))?;
+            Ok(content)
+        })
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.with_session(path, |session| {
+            let sftp = session.sftp().map_err(|e| self.ssh_error(path, e))?;
+            let stat = sftp.stat(path).map_err(|e| self.ssh_error(path, e))?;
+            Ok(stat.is_file())
+        })
+        .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_url_full() {
+        let (reader, path) = SftpFileReader::from_url("sftp://admin@bastion:2222/etc/app/config.toml").unwrap();
+        assert_eq!(reader.host, "bastion");
+        assert_eq!(reader.port, 2222);
+        assert_eq!(reader.username, "admin");
+        assert_eq!(path, PathBuf::from("/etc/app/config.toml"));
+    }
+
+    #[test]
+    fn test_from_url_defaults() {
+        let (reader, path) = SftpFileReader::from_url("sftp://bastion/srv/config.json").unwrap();
+        assert_eq!(reader.host, "bastion");
+        assert_eq!(reader.port, DEFAULT_SSH_PORT);
+        assert_eq!(reader.timeout, DEFAULT_TIMEOUT);
+        assert!(matches!(reader.host_key_check, HostKeyCheck::KnownHosts(_)));
+        assert_eq!(path, PathBuf::from("/srv/config.json"));
+    }
+
+    #[test]
+    fn test_from_url_invalid() {
+        let cases = [
+            "http://bastion/config.toml",
+            "sftp://bastion",
+            "sftp://bastion/",
+            "sftp:///config.toml",
+            "sftp://bastion:notaport/config.toml",
+        ];
+        for url in cases {
+            match SftpFileReader::from_url(url) {
+                Err(QuantumConfigError::InvalidSourceUrl { url: reported, .. }) => assert_eq!(reported, url),
+                other => panic!("Expected InvalidSourceUrl for {}: {:?}", url, other.map(|(_, p)| p)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_debug_redacts_credentials() {
+        let reader = SftpFileReader::new("bastion").with_auth(SftpAuth::Password("hunter2".to_string()));
+        let debug = format!("{:?}", reader);
+        assert!(!debug.contains("hunter2"));
+        assert!(debug.contains("<redacted>"));
+
+        let auth = SftpAuth::PublicKey {
+            private_key: PathBuf::from("/home/admin/.ssh/id_ed25519"),
+            passphrase: Some("secret-phrase".to_string()),
+        };
+        assert!(!format!("{:?}", auth).contains("secret-phrase"));
+    }
+
+    #[test]
+    fn test_unreachable_host_reports_missing_file() {
+        let reader = SftpFileReader::new("127.0.0.1")
+            .with_port(1)
+            .with_timeout(Duration::from_millis(200));
+        assert!(!reader.exists(Path::new("/config.toml")));
+        match reader.read_content(Path::new("/config.toml")) {
+            Err(QuantumConfigError::FileReadError { path, .. }) => {
+                assert!(path.starts_with("sftp://127.0.0.1:1"));
+            }
+            other => panic!("Expected FileReadError, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! SOPS 加密文件
+//!
+//! 启用 `sops` 特性后，文件提供器识别带有顶层 `sops` 元数据块的文件（由 [Mozilla SOPS](https://github.com/getsops/sops)
+//! 加密），调用本机的 `sops` 命令解密后再合并，已经使用 SOPS 管理密钥的团队无需额外步骤：
+//!
+//! ```text
+//! sops --encrypt --age age1... config.json > config.enc.json
+//! ```
+//!
+//! 密钥（age、PGP、云 KMS 等）由 `sops` 按其自身的规则查找，例如 `SOPS_AGE_KEY_FILE`。
+//! 支持 SOPS 能处理的 JSON、YAML（需要 `yaml` 特性）与 INI 格式。Unix 平台上已读取的密文经标准输入交给 `sops`，
+//! 因此同样适用于 HTTP、SFTP 等远程来源；其它平台以文件路径调用 `sops`，只适用于本地文件。
+
+use super::file_provider::FileFormat;
+use crate::error::QuantumConfigError;
+use serde_json::Value as JsonValue;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// 默认的 `sops` 可执行文件，从 `PATH` 中查找
+pub const DEFAULT_SOPS_COMMAND: &str = "sops";
+
+/// 格式对应的 `sops --input-type` 取值，SOPS 不支持的格式（例如 TOML）返回 `None`
+pub fn input_type(format: FileFormat) -> Option<&'static str> {
+    match format {
+        FileFormat::Json => Some("json"),
+        FileFormat::Ini => Some("ini"),
+        #[cfg(feature = "yaml")]
+        FileFormat::Yaml => Some("yaml"),
+        _ => None,
+    }
+}
+
+/// 调用 `sops --decrypt` 解密 `path` 的内容 `ciphertext`，返回 JSON 形式的明文
+///
+/// `command` 为 `None` 时使用 [`DEFAULT_SOPS_COMMAND`]；`path` 只用于错误信息，以及非 Unix 平台上传给 `sops`。
+pub fn decrypt(
+    command: Option<&Path>,
+    path: &Path,
+    ciphertext: &[u8],
+    format: FileFormat,
+) -> Result<JsonValue, QuantumConfigError> {
+    let command = command.unwrap_or_else(|| Path::new(DEFAULT_SOPS_COMMAND));
+    let failed = |reason: String| QuantumConfigError::Decryption {
+        path: path.to_path_buf(),
+        reason,
+    };
+    let input_type = input_type(format).ok_or_else(|| failed(format!("SOPS does not support {:?} files", format)))?;
+    let mut process = Command::new(command);
+    process.args(["--decrypt", "--input-type", input_type, "--output-type", "json"]);
+    #[cfg(unix)]
+    process.arg("/dev/stdin").stdin(Stdio::piped());
+    #[cfg(not(unix))]
+    process.arg(path).stdin(Stdio::null());
+    let mut child = process
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| failed(format!("failed to run {}: {}", command.display(), e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // `sops` 读完全部输入后才开始输出，先写入再等待输出不会阻塞；
+        // 未读取输入就退出时以退出状态与错误输出为准
+        match stdin.write_all(ciphertext) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => {
+                return Err(failed(format!("failed to write to {}: {}", command.display(), e)));
+            }
+            _ => {}
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = ciphertext;
+    let output = child
+        .wait_with_output()
+        .map_err(|e| failed(format!("failed to run {}: {}", command.display(), e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(failed(format!("{} exited with {}: {}", command.display(), output.status, stderr.trim())));
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| QuantumConfigError::FileParse {
+        format_name: "SOPS".to_string(),
+        path: path.to_path_buf(),
+        source_error: e.to_string(),
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    /// 写入一个模拟 `sops` 的脚本
+    fn fake_sops(dir: &Path, script: &str) -> std::path::PathBuf {
+        let path = dir.join("sops");
+        std::fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_decrypt_runs_command_and_reports_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.json");
+        let decrypt = |command: &Path| decrypt(Some(command), &file, b"{}", FileFormat::Json);
+
+        let ok = fake_sops(
+            dir.path(),
+            r#"[ "$*" = "--decrypt --input-type json --output-type json /dev/stdin" ] && echo '{"password": "s3cret"}'"#,
+        );
+        assert_eq!(decrypt(&ok).unwrap(), serde_json::json!({ "password": "s3cret" }));
+
+        let failing = fake_sops(dir.path(), "echo 'no matching keys' >&2; exit 128");
+        let error = decrypt(&failing).unwrap_err();
+        assert_eq!(error.code(), "decryption");
+        assert!(error.to_string().contains("no matching keys"));
+
+        let missing = dir.path().join("missing-sops");
+        assert_eq!(decrypt(&missing).unwrap_err().code(), "decryption");
+
+        let error = super::decrypt(Some(&ok), &file, b"", FileFormat::Toml).unwrap_err();
+        assert!(error.to_string().contains("does not support"), "{}", error);
+    }
+
+    #[test]
+    fn test_decrypt_reads_ciphertext_from_stdin() {
+        let dir = tempfile::tempdir().unwrap();
+        // 原样输出标准输入，文件路径不存在也不影响
+        let echo = fake_sops(dir.path(), "cat");
+        let plaintext = decrypt(Some(&echo), Path::new("https://config.internal/app.json"), br#"{"a": 1}"#, FileFormat::Json);
+        assert_eq!(plaintext.unwrap(), serde_json::json!({ "a": 1 }));
+    }
+}
@@ -0,0 +1,557 @@
+//! 配置来源地址解析
+//!
+//! 将 `--config` 的取值按协议分派到对应的提供器，使一个参数即可统一处理各类来源：
+//!
+//! - 普通路径或 `file:///etc/app/config.toml`：本地文件
+//! - `http://` / `https://`：远程 HTTP 资源（需启用 `http` 特性）
+//! - `sftp://[user@]host[:port]/path`：SFTP 远程文件（需启用 `sftp` 特性）
+//! - `s3://bucket/key`：S3 对象（需启用 `aws` 特性，凭证、区域与端点读取自标准的 AWS 环境变量）
+//! - `env://NAME`：从环境变量 `NAME` 读取整段配置
+//! - `stdin:-`：从标准输入读取配置
+//!
+//! 除本地文件外，均可通过 `?format=json` 显式指定格式；
+//! 否则按路径扩展名推断，`env://` 与 `stdin:-` 无扩展名时默认为 TOML。
+
+use super::file_provider::{FileFormat, QuantumConfigFileProvider, QuantumConfigFileProviderGeneric};
+use super::file_reader::{EnvVarFileReader, StdinFileReader};
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map};
+use figment::{Figment, Metadata, Profile, Provider};
+use std::path::{Path, PathBuf};
+
+/// 标准输入来源标识
+const STDIN_SPEC: &str = "stdin:-";
+
+/// `--config` 指定的配置来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// 本地文件
+    File(PathBuf),
+    /// HTTP(S) 地址，保留 `format` 以外的查询参数（例如预签名 URL 的签名）
+    Http {
+        url: String,
+        format: Option<FileFormat>,
+    },
+    /// SFTP 地址
+    Sftp {
+        url: String,
+        format: Option<FileFormat>,
+    },
+    /// S3 对象
+    S3 {
+        bucket: String,
+        key: String,
+        format: Option<FileFormat>,
+    },
+    /// 环境变量
+    Env {
+        name: String,
+        format: Option<FileFormat>,
+    },
+    /// 标准输入
+    Stdin { format: Option<FileFormat> },
+}
+
+/// 延迟读取的来源提供器
+///
+/// 与 [`ConfigSource::into_provider`] 返回的 Figment 不同，创建时不会读取来源，
+/// 直到被合并或调用 `data()` 时才读取，便于并行加载多个来源。
+pub struct SourceProvider(Box<dyn Provider + Send + Sync>);
+
+impl SourceProvider {
+    /// 包装任意提供器
+    pub fn new<P: Provider + Send + Sync + 'static>(provider: P) -> Self {
+        Self(Box::new(provider))
+    }
+}
+
+impl std::fmt::Debug for SourceProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SourceProvider").field(&self.0.metadata().name).finish()
+    }
+}
+
+impl Provider for SourceProvider {
+    fn metadata(&self) -> Metadata {
+        self.0.metadata()
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, figment::Error> {
+        self.0.data()
+    }
+
+    fn profile(&self) -> Option<Profile> {
+        self.0.profile()
+    }
+}
+
+impl ConfigSource {
+    /// 解析 `--config` 的取值
+    ///
+    /// 不含 `scheme://` 前缀的取值一律视为本地路径。
+    ///
+    /// # Errors
+    /// 地址格式无效、`format` 参数无法识别或协议未知时返回错误
+    pub fn parse(spec: &str) -> Result<Self, QuantumConfigError> {
+        let invalid = |reason: &str| QuantumConfigError::InvalidSourceUrl {
+            url: spec.to_string(),
+            reason: reason.to_string(),
+        };
+
+        if let Some(rest) = spec.strip_prefix(STDIN_SPEC) {
+            let query = match rest {
+                "" => "",
+                _ => rest
+                    .strip_prefix('?')
+                    .ok_or_else(|| invalid("expected stdin:- or stdin:-?format=..."))?,
+            };
+            let format = parse_format_query(spec, query)?;
+            return Ok(ConfigSource::Stdin { format });
+        }
+
+        let Some((scheme, rest)) = split_scheme(spec) else {
+            return Ok(ConfigSource::File(PathBuf::from(spec)));
+        };
+
+        match scheme.to_ascii_lowercase().as_str() {
+            "file" => {
+                // 仅接受 file:///abs/path 或 file://localhost/abs/path
+                let path = rest.strip_prefix("localhost").unwrap_or(rest);
+                if !path.starts_with('/') {
+                    return Err(invalid("file URLs must use an absolute path (file:///path)"));
+                }
+                Ok(ConfigSource::File(PathBuf::from(path)))
+            }
+            "http" | "https" => {
+                let (base, query) = split_query(spec);
+                if rest.trim_start_matches('/').is_empty() {
+                    return Err(invalid("missing host"));
+                }
+                // 预签名或携带令牌的 URL 依赖其余的查询参数，只取出 `format`
+                let (format, query) = take_format_query(spec, query)?;
+                Ok(ConfigSource::Http {
+                    url: if query.is_empty() { base.to_string() } else { format!("{}?{}", base, query) },
+                    format,
+                })
+            }
+            "sftp" => {
+                let (base, query) = split_query(spec);
+                Ok(ConfigSource::Sftp {
+                    url: base.to_string(),
+                    format: parse_format_query(spec, query)?,
+                })
+            }
+            "s3" => {
+                let (location, query) = split_query(rest);
+                let (bucket, key) = location
+                    .split_once('/')
+                    .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+                    .ok_or_else(|| invalid("expected s3://bucket/key"))?;
+                Ok(ConfigSource::S3 {
+                    bucket: bucket.to_string(),
+                    key: key.to_string(),
+                    format: parse_format_query(spec, query)?,
+                })
+            }
+            "env" => {
+                let (name, query) = split_query(rest);
+                if name.is_empty() {
+                    return Err(invalid("missing environment variable name"));
+                }
+                Ok(ConfigSource::Env {
+                    name: name.to_string(),
+                    format: parse_format_query(spec, query)?,
+                })
+            }
+            other => Err(QuantumConfigError::UnsupportedScheme {
+                scheme: other.to_string(),
+                reason: "supported schemes are file, http, https, sftp, s3, env and stdin".to_string(),
+            }),
+        }
+    }
+
+    /// 是否为本地文件来源
+    pub fn is_local_file(&self) -> bool {
+        matches!(self, ConfigSource::File(_))
+    }
+
+    /// 创建对应的配置提供器
+    ///
+    /// 通过 `--config` 指定的来源始终视为必需来源。
+    ///
+    /// # Errors
+    /// 协议所需的特性未启用、或无法确定格式时返回错误
+    pub fn into_provider(self, max_parse_depth: u32) -> Result<Figment, QuantumConfigError> {
+        Ok(Figment::from(self.into_lazy_provider(max_parse_depth)?))
+    }
+
+    /// 创建启用加载缓存的延迟读取提供器，HTTP(S) 来源按 `ETag` 缓存响应，参见 [`crate::cache`]
+    pub(crate) fn into_cached_provider(self, max_parse_depth: u32) -> Result<SourceProvider, QuantumConfigError> {
+        match self {
+            ConfigSource::Http { url, format } => http_provider(url, format, max_parse_depth, true),
+            source => source.into_lazy_provider(max_parse_depth),
+        }
+    }
+
+    /// 创建对应的延迟读取提供器，不支持的协议或特性未启用时立即返回错误
+    pub fn into_lazy_provider(self, max_parse_depth: u32) -> Result<SourceProvider, QuantumConfigError> {
+        match self {
+            ConfigSource::File(path) => {
+                let provider = QuantumConfigFileProvider::from_path(path, true, max_parse_depth)?;
+                Ok(SourceProvider::new(provider))
+            }
+            ConfigSource::Http { url, format } => http_provider(url, format, max_parse_depth, false),
+            ConfigSource::Sftp { url, format } => sftp_provider(url, format, max_parse_depth),
+            ConfigSource::S3 { bucket, key, format } => s3_provider(bucket, key, format, max_parse_depth),
+            ConfigSource::Env { name, format } => {
+                let path = PathBuf::from(name);
+                let format = resolve_format(&path, format, FileFormat::Toml);
+                Ok(SourceProvider::new(QuantumConfigFileProviderGeneric::new(
+                    path,
+                    format,
+                    true,
+                    max_parse_depth,
+                    EnvVarFileReader::new(),
+                )))
+            }
+            ConfigSource::Stdin { format } => {
+                let path = PathBuf::from("-");
+                let format = format.unwrap_or(FileFormat::Toml);
+                Ok(SourceProvider::new(QuantumConfigFileProviderGeneric::new(
+                    path,
+                    format,
+                    true,
+                    max_parse_depth,
+                    StdinFileReader::new(),
+                )))
+            }
+        }
+    }
+}
+
+/// 拆分 `scheme://rest`，要求 scheme 以字母开头且仅包含字母、数字、`+`、`-`、`.`
+fn split_scheme(spec: &str) -> Option<(&str, &str)> {
+    let (scheme, rest) = spec.split_once("://")?;
+    let mut chars = scheme.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+    valid.then_some((scheme, rest))
+}
+
+/// 拆分地址与查询字符串
+fn split_query(spec: &str) -> (&str, &str) {
+    match spec.split_once('?') {
+        Some((base, query)) => (base, query),
+        None => (spec, ""),
+    }
+}
+
+/// 解析查询字符串中的 `format` 参数
+fn parse_format_query(spec: &str, query: &str) -> Result<Option<FileFormat>, QuantumConfigError> {
+    let invalid = |reason: String| QuantumConfigError::InvalidSourceUrl {
+        url: spec.to_string(),
+        reason,
+    };
+
+    let mut format = None;
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        match pair.split_once('=') {
+            Some(("format", value)) => {
+                let parsed = FileFormat::from_extension(value)
+                    .ok_or_else(|| invalid(format!("unknown format '{}'", value)))?;
+                format = Some(parsed);
+            }
+            _ => return Err(invalid(format!("unsupported query parameter '{}'", pair))),
+        }
+    }
+    Ok(format)
+}
+
+/// 取出查询字符串中的 `format` 参数，其余参数按原顺序保留
+fn take_format_query(spec: &str, query: &str) -> Result<(Option<FileFormat>, String), QuantumConfigError> {
+    let (format, rest): (Vec<&str>, Vec<&str>) = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .partition(|pair| pair.starts_with("format="));
+    Ok((parse_format_query(spec, &format.join("&"))?, rest.join("&")))
+}
+
+/// 按显式格式、扩展名、默认值的顺序确定格式
+fn resolve_format(path: &Path, explicit: Option<FileFormat>, default: FileFormat) -> FileFormat {
+    explicit
+        .or_else(|| path.extension().and_then(|ext| ext.to_str()).and_then(FileFormat::from_extension))
+        .unwrap_or(default)
+}
+
+/// 按显式格式或扩展名确定格式，无法确定时报错
+#[cfg(any(feature = "http", feature = "sftp", feature = "aws"))]
+fn require_format(url: &str, path: &Path, explicit: Option<FileFormat>) -> Result<FileFormat, QuantumConfigError> {
+    explicit
+        .or_else(|| path.extension().and_then(|ext| ext.to_str()).and_then(FileFormat::from_extension))
+        .ok_or_else(|| QuantumConfigError::UnsupportedFormat {
+            path: PathBuf::from(url),
+        })
+}
+
+#[cfg(feature = "http")]
+fn http_provider(
+    url: String,
+    format: Option<FileFormat>,
+    max_parse_depth: u32,
+    etag_cache: bool,
+) -> Result<SourceProvider, QuantumConfigError> {
+    // 扩展名取自查询字符串之前的路径
+    let format = require_format(&url, Path::new(split_query(&url).0), format)?;
+    Ok(SourceProvider::new(QuantumConfigFileProviderGeneric::new(
+        PathBuf::from(&url),
+        format,
+        true,
+        max_parse_depth,
+        super::http_reader::HttpFileReader::new().with_etag_cache(etag_cache),
+    )))
+}
+
+#[cfg(not(feature = "http"))]
+fn http_provider(
+    url: String,
+    format: Option<FileFormat>,
+    max_parse_depth: u32,
+    etag_cache: bool,
+) -> Result<SourceProvider, QuantumConfigError> {
+    let _ = (format, max_parse_depth, etag_cache);
+    let scheme = url.split_once("://").map(|(s, _)| s).unwrap_or("http");
+    Err(QuantumConfigError::UnsupportedScheme {
+        scheme: scheme.to_string(),
+        reason: "HTTP support requires the `http` feature of quantum_config".to_string(),
+    })
+}
+
+#[cfg(feature = "sftp")]
+fn sftp_provider(url: String, format: Option<FileFormat>, max_parse_depth: u32) -> Result<SourceProvider, QuantumConfigError> {
+    let (reader, remote_path) = super::sftp_reader::SftpFileReader::from_url(&url)?;
+    let format = require_format(&url, &remote_path, format)?;
+    Ok(SourceProvider::new(QuantumConfigFileProviderGeneric::new(
+        remote_path,
+        format,
+        true,
+        max_parse_depth,
+        reader,
+    )))
+}
+
+#[cfg(not(feature = "sftp"))]
+fn sftp_provider(url: String, format: Option<FileFormat>, max_parse_depth: u32) -> Result<SourceProvider, QuantumConfigError> {
+    let _ = (url, format, max_parse_depth);
+    Err(QuantumConfigError::UnsupportedScheme {
+        scheme: "sftp".to_string(),
+        reason: "SFTP support requires the `sftp` feature of quantum_config".to_string(),
+    })
+}
+
+#[cfg(feature = "aws")]
+fn s3_provider(
+    bucket: String,
+    key: String,
+    format: Option<FileFormat>,
+    max_parse_depth: u32,
+) -> Result<SourceProvider, QuantumConfigError> {
+    let url = format!("s3://{}/{}", bucket, key);
+    let path = PathBuf::from(&url);
+    let format = require_format(&url, &path, format)?;
+    Ok(SourceProvider::new(QuantumConfigFileProviderGeneric::new(
+        path,
+        format,
+        true,
+        max_parse_depth,
+        super::s3_reader::S3FileReader::from_env(),
+    )))
+}
+
+#[cfg(not(feature = "aws"))]
+fn s3_provider(
+    bucket: String,
+    key: String,
+    format: Option<FileFormat>,
+    max_parse_depth: u32,
+) -> Result<SourceProvider, QuantumConfigError> {
+    let _ = (bucket, key, format, max_parse_depth);
+    Err(QuantumConfigError::UnsupportedScheme {
+        scheme: "s3".to_string(),
+        reason: "S3 support requires the `aws` feature of quantum_config".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct TestConfig {
+        port: u16,
+    }
+
+    #[test]
+    fn test_parse_local_paths() {
+        assert_eq!(
+            ConfigSource::parse("config.toml").unwrap(),
+            ConfigSource::File(PathBuf::from("config.toml"))
+        );
+        assert_eq!(
+            ConfigSource::parse("file:///etc/app/config.toml").unwrap(),
+            ConfigSource::File(PathBuf::from("/etc/app/config.toml"))
+        );
+        assert_eq!(
+            ConfigSource::parse("file://localhost/etc/app/config.toml").unwrap(),
+            ConfigSource::File(PathBuf::from("/etc/app/config.toml"))
+        );
+        assert!(matches!(
+            ConfigSource::parse("file://relative/config.toml"),
+            Err(QuantumConfigError::InvalidSourceUrl { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_remote_sources() {
+        assert_eq!(
+            ConfigSource::parse("https://cfg.example.com/app.json?format=toml").unwrap(),
+            ConfigSource::Http {
+                url: "https://cfg.example.com/app.json".to_string(),
+                format: Some(FileFormat::Toml),
+            }
+        );
+        // 预签名 URL 的其余查询参数原样保留
+        assert_eq!(
+            ConfigSource::parse("https://b.s3.amazonaws.com/app.cfg?X-Amz-Signature=abc%2F&format=json&token=t").unwrap(),
+            ConfigSource::Http {
+                url: "https://b.s3.amazonaws.com/app.cfg?X-Amz-Signature=abc%2F&token=t".to_string(),
+                format: Some(FileFormat::Json),
+            }
+        );
+        assert_eq!(
+            ConfigSource::parse("s3://bucket/app/config?format=json").unwrap(),
+            ConfigSource::S3 {
+                bucket: "bucket".to_string(),
+                key: "app/config".to_string(),
+                format: Some(FileFormat::Json),
+            }
+        );
+        assert_eq!(
+            ConfigSource::parse("env://APP_CONFIG?format=json").unwrap(),
+            ConfigSource::Env {
+                name: "APP_CONFIG".to_string(),
+                format: Some(FileFormat::Json),
+            }
+        );
+        assert_eq!(
+            ConfigSource::parse("stdin:-").unwrap(),
+            ConfigSource::Stdin { format: None }
+        );
+        assert_eq!(
+            ConfigSource::parse("stdin:-?format=ini").unwrap(),
+            ConfigSource::Stdin { format: Some(FileFormat::Ini) }
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            ConfigSource::parse("ftp://host/config.toml"),
+            Err(QuantumConfigError::UnsupportedScheme { scheme, .. }) if scheme == "ftp"
+        ));
+        assert!(matches!(
+            ConfigSource::parse("env://"),
+            Err(QuantumConfigError::InvalidSourceUrl { .. })
+        ));
+        assert!(matches!(
+            ConfigSource::parse("s3://bucket"),
+            Err(QuantumConfigError::InvalidSourceUrl { .. })
+        ));
+        assert!(matches!(
+            ConfigSource::parse("s3://bucket/"),
+            Err(QuantumConfigError::InvalidSourceUrl { .. })
+        ));
+        assert!(matches!(
+            ConfigSource::parse("env://APP_CONFIG?format=csv"),
+            Err(QuantumConfigError::InvalidSourceUrl { .. })
+        ));
+    }
+
+    #[test]
+    fn test_env_source_loads_config() {
+        let mut env = crate::testing::EnvGuard::new();
+        env.set("QUANTUM_CONFIG_TEST_SOURCE_ENV", r#"{"port": 9090}"#);
+        let figment = ConfigSource::parse("env://QUANTUM_CONFIG_TEST_SOURCE_ENV?format=json")
+            .unwrap()
+            .into_provider(32)
+            .unwrap();
+        let config: TestConfig = figment.extract().unwrap();
+
+        assert_eq!(config.port, 9090);
+    }
+
+    #[cfg(feature = "aws")]
+    #[test]
+    fn test_s3_source_loads_config() {
+        let (address, requests) = crate::providers::test_server::serve(vec![(200, "port = 9191".to_string())]);
+        let mut env = crate::testing::EnvGuard::new();
+        env.set("AWS_ENDPOINT_URL_S3", &address);
+        env.set("AWS_ACCESS_KEY_ID", "AKIDEXAMPLE");
+        env.set("AWS_SECRET_ACCESS_KEY", "aws-secret-access-key");
+        let figment = ConfigSource::parse("s3://config-bucket/myapp/config.toml")
+            .unwrap()
+            .into_provider(32)
+            .unwrap();
+        let config: TestConfig = figment.extract().unwrap();
+
+        assert_eq!(config.port, 9191);
+        assert!(requests.recv().unwrap().starts_with("GET /config-bucket/myapp/config.toml "));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_http_source_keeps_query_parameters() {
+        let (address, requests) = crate::providers::test_server::serve(vec![(200, r#"{"port": 9292}"#.to_string())]);
+        let figment = ConfigSource::parse(&format!("{}/app.json?X-Amz-Expires=300&token=abc", address))
+            .unwrap()
+            .into_provider(32)
+            .unwrap();
+        let config: TestConfig = figment.extract().unwrap();
+
+        assert_eq!(config.port, 9292);
+        assert!(requests.recv().unwrap().starts_with("GET /app.json?X-Amz-Expires=300&token=abc "));
+    }
+
+    #[cfg(not(feature = "aws"))]
+    #[test]
+    fn test_s3_requires_feature() {
+        let source = ConfigSource::parse("s3://bucket/config.toml").unwrap();
+        match source.into_provider(32) {
+            Err(QuantumConfigError::UnsupportedScheme { scheme, reason }) => {
+                assert_eq!(scheme, "s3");
+                assert!(reason.contains("`aws` feature"), "{}", reason);
+            }
+            other => panic!("Expected UnsupportedScheme, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[cfg(not(feature = "sftp"))]
+    #[test]
+    fn test_sftp_requires_feature() {
+        let source = ConfigSource::parse("sftp://bastion/etc/app/config.toml").unwrap();
+        assert!(matches!(
+            source.into_provider(32),
+            Err(QuantumConfigError::UnsupportedScheme { scheme, .. }) if scheme == "sftp"
+        ));
+    }
+
+    #[cfg(not(feature = "http"))]
+    #[test]
+    fn test_http_requires_feature() {
+        let source = ConfigSource::parse("https://cfg.example.com/app.toml").unwrap();
+        assert!(matches!(
+            source.into_provider(32),
+            Err(QuantumConfigError::UnsupportedScheme { scheme, .. }) if scheme == "https"
+        ));
+    }
+}
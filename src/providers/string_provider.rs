@@ -0,0 +1,197 @@
+//! 字符串配置提供器
+//!
+//! 测试或嵌入场景中直接以字符串提供配置内容，无需实现自定义的 [`super::FileReader`]：
+//!
+//! ```ignore
+//! let figment = Figment::new()
+//!     .merge(StringProvider::toml("port = 8080\n[database]\nurl = \"pg://db\""))
+//!     .merge(StringProvider::json(r#"{"debug": true}"#));
+//! ```
+//!
+//! 内容按与配置文件相同的规则解析（包括解析深度限制与类型推断），支持的格式与
+//! [`FileFormat`] 一致；`json5`、`yaml`、`hcl`、`xml` 构造函数需要启用同名特性。
+
+use super::file_provider::{FileFormat, QuantumConfigFileProviderGeneric};
+use super::file_reader::FileReader;
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map};
+use figment::{Error, Metadata, Profile, Provider};
+use std::path::{Path, PathBuf};
+
+/// 默认的解析深度限制，与配置文件一致
+const DEFAULT_MAX_PARSE_DEPTH: u32 = 128;
+
+/// 默认的来源名称
+const DEFAULT_NAME: &str = "<string>";
+
+/// 字符串配置提供器
+#[derive(Debug, Clone)]
+pub struct StringProvider {
+    /// 配置内容
+    content: String,
+    /// 内容格式
+    format: FileFormat,
+    /// 错误信息与元数据中使用的来源名称
+    name: String,
+    /// 解析深度限制
+    max_parse_depth: u32,
+}
+
+/// 总是返回同一段内容的读取器
+#[derive(Debug, Clone)]
+struct StringReader(String);
+
+impl FileReader for StringReader {
+    fn read_content(&self, _path: &Path) -> Result<String, QuantumConfigError> {
+        Ok(self.0.clone())
+    }
+
+    fn exists(&self, _path: &Path) -> bool {
+        true
+    }
+}
+
+impl StringProvider {
+    /// 以给定格式解析 `content`
+    pub fn new<S: Into<String>>(content: S, format: FileFormat) -> Self {
+        Self {
+            content: content.into(),
+            format,
+            name: DEFAULT_NAME.to_string(),
+            max_parse_depth: DEFAULT_MAX_PARSE_DEPTH,
+        }
+    }
+
+    /// TOML 内容
+    pub fn toml<S: Into<String>>(content: S) -> Self {
+        Self::new(content, FileFormat::Toml)
+    }
+
+    /// JSON 内容
+    pub fn json<S: Into<String>>(content: S) -> Self {
+        Self::new(content, FileFormat::Json)
+    }
+
+    /// INI 内容
+    pub fn ini<S: Into<String>>(content: S) -> Self {
+        Self::new(content, FileFormat::Ini)
+    }
+
+    /// JSON5 内容
+    #[cfg(feature = "json5")]
+    pub fn json5<S: Into<String>>(content: S) -> Self {
+        Self::new(content, FileFormat::Json5)
+    }
+
+    /// YAML 内容
+    #[cfg(feature = "yaml")]
+    pub fn yaml<S: Into<String>>(content: S) -> Self {
+        Self::new(content, FileFormat::Yaml)
+    }
+
+    /// HCL 内容
+    #[cfg(feature = "hcl")]
+    pub fn hcl<S: Into<String>>(content: S) -> Self {
+        Self::new(content, FileFormat::Hcl)
+    }
+
+    /// XML 内容
+    #[cfg(feature = "xml")]
+    pub fn xml<S: Into<String>>(content: S) -> Self {
+        Self::new(content, FileFormat::Xml)
+    }
+
+    /// 设置错误信息与元数据中使用的来源名称，默认为 `<string>`
+    pub fn with_name<S: Into<String>>(mut self, name: S) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// 设置解析深度限制
+    pub fn with_max_parse_depth(mut self, max_parse_depth: u32) -> Self {
+        self.max_parse_depth = max_parse_depth;
+        self
+    }
+
+    /// 内容格式
+    pub fn format(&self) -> FileFormat {
+        self.format
+    }
+
+    /// 以文件提供器解析内容，来源名称作为路径出现在错误信息中
+    fn file_provider(&self) -> QuantumConfigFileProviderGeneric<StringReader> {
+        QuantumConfigFileProviderGeneric::new(
+            PathBuf::from(&self.name),
+            self.format,
+            true,
+            self.max_parse_depth,
+            StringReader(self.content.clone()),
+        )
+    }
+}
+
+impl Provider for StringProvider {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(format!("Quantum Config String Provider ({})", self.name))
+            .interpolater(|_profile, keys| keys.join("."))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        self.file_provider().data()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::Figment;
+    use serde_json::{json, Value as JsonValue};
+
+    #[test]
+    fn test_string_providers_merge_like_files() {
+        let value: JsonValue = Figment::new()
+            .merge(StringProvider::toml("port = 8080\n[database]\nurl = \"pg://db\"\npool = 4"))
+            .merge(StringProvider::json(r#"{"database": {"pool": 8}, "debug": true}"#))
+            .merge(StringProvider::ini("[server]\nhosts = a, b"))
+            .extract()
+            .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "port": 8080,
+                "database": {"url": "pg://db", "pool": 8},
+                "debug": true,
+                "server": {"hosts": ["a", "b"]}
+            })
+        );
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_content_merges_like_files() {
+        let value: JsonValue = Figment::new()
+            .merge(StringProvider::toml("port = 8080\n[database]\nurl = \"pg://db\""))
+            .merge(StringProvider::yaml("database:\n  pool: 8\nhosts:\n  - a\n  - b\n"))
+            .extract()
+            .unwrap();
+        assert_eq!(value, json!({ "port": 8080, "database": {"url": "pg://db", "pool": 8}, "hosts": ["a", "b"] }));
+
+        let error = Figment::from(StringProvider::yaml("port: [1").with_name("inline.yaml"))
+            .extract::<JsonValue>()
+            .unwrap_err();
+        assert!(error.to_string().contains("inline.yaml"), "{}", error);
+    }
+
+    #[test]
+    fn test_parse_errors_use_source_name() {
+        let error = Figment::from(StringProvider::toml("port = ").with_name("inline.toml"))
+            .extract::<JsonValue>()
+            .unwrap_err();
+        assert!(error.to_string().contains("inline.toml"), "{}", error);
+
+        let nested = "[a.b.c.d]\nkey = 1";
+        assert!(Figment::from(StringProvider::toml(nested).with_max_parse_depth(2))
+            .extract::<JsonValue>()
+            .is_err());
+    }
+}
@@ -0,0 +1,56 @@
+//! 远程提供器测试使用的最小 HTTP 服务器
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+
+/// 测试响应：状态码、额外的头部与正文
+pub(crate) type Response = (u16, Vec<(&'static str, String)>, String);
+
+/// 依次响应给定数量的请求，并把收到的请求（请求行、头部与正文）发回测试线程
+pub(crate) fn serve(responses: Vec<(u16, String)>) -> (String, mpsc::Receiver<String>) {
+    serve_with_headers(responses.into_iter().map(|(status, body)| (status, Vec::new(), body)).collect())
+}
+
+/// 与 [`serve`] 相同，每个响应额外带上给定的头部
+pub(crate) fn serve_with_headers(responses: Vec<Response>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for (status, headers, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut payload = vec![0; content_length];
+            reader.read_exact(&mut payload).unwrap();
+            request.push_str(&String::from_utf8_lossy(&payload));
+            sender.send(request).unwrap();
+
+            let headers: String = headers.iter().map(|(name, value)| format!("{}: {}\r\n", name, value)).collect();
+            let response = format!(
+                "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}Connection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                headers,
+                body
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        }
+    });
+
+    (address, receiver)
+}
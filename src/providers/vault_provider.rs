@@ -0,0 +1,320 @@
+//! HashiCorp Vault 配置提供器
+//!
+//! 从 Vault KV v2 引擎读取密钥并作为 figment 配置层合并，生产环境的密钥无需落盘。
+//! 支持 Token 与 AppRole 两种认证方式；读取到的值会登记到 [`crate::scrub`]。
+//!
+//! ```ignore
+//! use quantum_config::providers::{VaultAuth, VaultProvider};
+//!
+//! let vault = VaultProvider::new("https://vault.example.com:8200", "secret", "myapp/prod")
+//!     .with_auth(VaultAuth::approle(role_id, secret_id))
+//!     .with_nested_key("database");
+//! let figment = figment.merge(vault);
+//! ```
+
+use super::remote::{self, describe_error};
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map, Value};
+use figment::{Error, Metadata, Profile, Provider};
+use serde_json::Value as JsonValue;
+use std::time::Duration;
+
+/// 默认请求超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 未设置 `VAULT_ADDR` 时使用的默认地址
+const DEFAULT_ADDRESS: &str = "http://127.0.0.1:8200";
+
+/// AppRole 认证的默认挂载路径
+const DEFAULT_APPROLE_MOUNT: &str = "approle";
+
+/// Vault 认证方式
+#[derive(Clone)]
+pub enum VaultAuth {
+    /// 直接使用 Vault Token
+    Token(String),
+    /// AppRole 登录后使用返回的 Token
+    AppRole {
+        /// AppRole 挂载路径（默认 "approle"）
+        mount: String,
+        /// Role ID
+        role_id: String,
+        /// Secret ID
+        secret_id: String,
+    },
+}
+
+impl VaultAuth {
+    /// 使用默认挂载路径的 AppRole 认证
+    pub fn approle<S: Into<String>>(role_id: S, secret_id: S) -> Self {
+        VaultAuth::AppRole {
+            mount: DEFAULT_APPROLE_MOUNT.to_string(),
+            role_id: role_id.into(),
+            secret_id: secret_id.into(),
+        }
+    }
+
+    /// 从环境变量读取认证信息：优先 `VAULT_TOKEN`，其次 `VAULT_ROLE_ID` 与 `VAULT_SECRET_ID`
+    pub fn from_env() -> Option<Self> {
+        if let Ok(token) = std::env::var("VAULT_TOKEN") {
+            return Some(VaultAuth::Token(token));
+        }
+        match (std::env::var("VAULT_ROLE_ID"), std::env::var("VAULT_SECRET_ID")) {
+            (Ok(role_id), Ok(secret_id)) => Some(VaultAuth::approle(role_id, secret_id)),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for VaultAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出 Token 与 Secret ID
+        match self {
+            VaultAuth::Token(_) => f.debug_tuple("Token").field(&"<redacted>").finish(),
+            VaultAuth::AppRole { mount, role_id, .. } => f
+                .debug_struct("AppRole")
+                .field("mount", mount)
+                .field("role_id", role_id)
+                .field("secret_id", &"<redacted>")
+                .finish(),
+        }
+    }
+}
+
+/// Vault KV v2 配置提供器
+#[derive(Debug, Clone)]
+pub struct VaultProvider {
+    /// Vault 地址，例如 "https://vault.example.com:8200"
+    address: String,
+    /// KV v2 引擎挂载路径，例如 "secret"
+    mount: String,
+    /// 密钥路径，例如 "myapp/prod"
+    path: String,
+    /// 认证方式
+    auth: Option<VaultAuth>,
+    /// Vault Enterprise 命名空间
+    namespace: Option<String>,
+    /// 将密钥嵌套到的配置键，为 `None` 时合并到顶层
+    nested_key: Option<String>,
+    /// 请求超时
+    timeout: Duration,
+}
+
+impl VaultProvider {
+    /// 创建读取 `{address}/v1/{mount}/data/{path}` 的提供器
+    pub fn new<S: Into<String>>(address: S, mount: S, path: S) -> Self {
+        Self {
+            address: address.into().trim_end_matches('/').to_string(),
+            mount: mount.into().trim_matches('/').to_string(),
+            path: path.into().trim_matches('/').to_string(),
+            auth: None,
+            namespace: None,
+            nested_key: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// 使用 `VAULT_ADDR`、`VAULT_NAMESPACE` 与 [`VaultAuth::from_env`] 创建提供器
+    pub fn from_env<S: Into<String>>(mount: S, path: S) -> Self {
+        let address = std::env::var("VAULT_ADDR").unwrap_or_else(|_| DEFAULT_ADDRESS.to_string());
+        let mut provider = Self::new(address, mount.into(), path.into());
+        provider.auth = VaultAuth::from_env();
+        provider.namespace = std::env::var("VAULT_NAMESPACE").ok();
+        provider
+    }
+
+    /// 设置认证方式
+    pub fn with_auth(mut self, auth: VaultAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// 设置 Vault Enterprise 命名空间
+    pub fn with_namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// 将密钥嵌套到指定配置键下（以 `.` 分隔），例如 "database"
+    pub fn with_nested_key<S: Into<String>>(mut self, key: S) -> Self {
+        self.nested_key = Some(key.into());
+        self
+    }
+
+    /// 设置请求超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 读取密钥数据
+    fn fetch(&self) -> Result<Dict, QuantumConfigError> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(self.timeout))
+            .build()
+            .into();
+        let token = self.token(&agent)?;
+
+        let url = format!("{}/v1/{}/data/{}", self.address, self.mount, self.path);
+        let mut request = agent.get(&url).header("X-Vault-Token", &token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let body = request
+            .call()
+            .and_then(|mut response| response.body_mut().read_to_string())
+            .map_err(|e| self.error(describe_error(e, STATUS_HINTS)))?;
+
+        let response: JsonValue = serde_json::from_str(&body)
+            .map_err(|e| self.error(format!("invalid response: {}", e)))?;
+        let secrets = response
+            .pointer("/data/data")
+            .filter(|data| data.is_object())
+            .ok_or_else(|| self.error("response does not contain KV v2 data".to_string()))?;
+
+        let mut registered = crate::scrub::Secrets::new();
+        registered.add_value(secrets);
+        crate::scrub::replace_secrets(&format!("vault:{}/{}/{}", self.address, self.mount, self.path), registered);
+        let value = Value::serialize(secrets).map_err(|e| self.error(e.to_string()))?;
+        let mut dict = value.into_dict().unwrap_or_default();
+
+        if let Some(key) = &self.nested_key {
+            for part in key.split('.').rev() {
+                let mut parent = Dict::new();
+                parent.insert(part.to_string(), Value::from(dict));
+                dict = parent;
+            }
+        }
+        Ok(dict)
+    }
+
+    /// 获取访问 Token，AppRole 认证时先登录
+    fn token(&self, agent: &ureq::Agent) -> Result<String, QuantumConfigError> {
+        match &self.auth {
+            Some(VaultAuth::Token(token)) => Ok(token.clone()),
+            Some(VaultAuth::AppRole { mount, role_id, secret_id }) => {
+                let url = format!("{}/v1/auth/{}/login", self.address, mount.trim_matches('/'));
+                let payload = serde_json::json!({ "role_id": role_id, "secret_id": secret_id }).to_string();
+                let mut request = agent.post(&url).header("Content-Type", "application/json");
+                if let Some(namespace) = &self.namespace {
+                    request = request.header("X-Vault-Namespace", namespace);
+                }
+                let body = request
+                    .send(&payload)
+                    .and_then(|mut response| response.body_mut().read_to_string())
+                    .map_err(|e| self.error(format!("AppRole login failed: {}", describe_error(e, STATUS_HINTS))))?;
+
+                let response: JsonValue = serde_json::from_str(&body)
+                    .map_err(|e| self.error(format!("invalid AppRole login response: {}", e)))?;
+                let token = response
+                    .pointer("/auth/client_token")
+                    .and_then(JsonValue::as_str)
+                    .ok_or_else(|| self.error("AppRole login response does not contain a client token".to_string()))?;
+                crate::scrub::replace_secret(&format!("vault:{}:token", self.address), token);
+                Ok(token.to_string())
+            }
+            None => Err(self.error("no authentication configured".to_string())),
+        }
+    }
+
+    /// 构造带有密钥路径的错误
+    fn error(&self, reason: String) -> QuantumConfigError {
+        remote::error("vault", format!("{}/{}", self.mount, self.path), reason)
+    }
+}
+
+/// 常见状态码的说明
+const STATUS_HINTS: &[(u16, &str)] = &[(403, "permission denied"), (404, "secret not found")];
+
+impl Provider for VaultProvider {
+    fn metadata(&self) -> Metadata {
+        Metadata::named(format!("Vault ({}/{})", self.mount, self.path))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let dict = self.fetch().map_err(|e| remote::provider_error(e, self.nested_key.as_deref()))?;
+        let mut data = Map::new();
+        data.insert(Profile::Default, dict);
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::test_server::serve;
+    use figment::Figment;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        password: String,
+        pool_size: u32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct AppConfig {
+        database: Database,
+    }
+
+    const KV_RESPONSE: &str = r#"{"data":{"data":{"password":"vault-db-password","pool_size":8},"metadata":{"version":3}}}"#;
+
+    #[test]
+    fn test_reads_kv_v2_secret_with_token() {
+        let (address, requests) = serve(vec![(200, KV_RESPONSE.to_string())]);
+        let provider = VaultProvider::new(address.as_str(), "secret", "myapp/prod")
+            .with_auth(VaultAuth::Token("root-token".to_string()))
+            .with_namespace("team-a")
+            .with_nested_key("database");
+
+        let config: AppConfig = Figment::from(provider).extract().unwrap();
+        assert_eq!(config.database, Database { password: "vault-db-password".to_string(), pool_size: 8 });
+
+        let request = requests.recv().unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("get /v1/secret/data/myapp/prod "));
+        assert!(request.contains("x-vault-token: root-token"));
+        assert!(request.contains("x-vault-namespace: team-a"));
+        #[cfg(feature = "secret-scrub")]
+        assert!(!crate::scrub::scrub("vault-db-password").contains("vault-db-password"));
+    }
+
+    #[test]
+    fn test_approle_login_precedes_read() {
+        let login = r#"{"auth":{"client_token":"approle-issued-token"}}"#;
+        let (address, requests) = serve(vec![(200, login.to_string()), (200, KV_RESPONSE.to_string())]);
+        let provider = VaultProvider::new(address.as_str(), "secret", "myapp/prod")
+            .with_auth(VaultAuth::approle("my-role", "my-secret-id"))
+            .with_nested_key("database");
+
+        let config: AppConfig = Figment::from(provider).extract().unwrap();
+        assert_eq!(config.database.pool_size, 8);
+
+        let login_request = requests.recv().unwrap();
+        assert!(login_request.starts_with("POST /v1/auth/approle/login "));
+        assert!(login_request.contains(r#""role_id":"my-role""#));
+        let read_request = requests.recv().unwrap().to_ascii_lowercase();
+        assert!(read_request.contains("x-vault-token: approle-issued-token"));
+    }
+
+    #[test]
+    fn test_reports_vault_errors() {
+        let (address, _requests) = serve(vec![(403, "{}".to_string())]);
+        let provider = VaultProvider::new(address.as_str(), "secret", "myapp/prod")
+            .with_auth(VaultAuth::Token("bad-token".to_string()));
+        let error = Figment::from(provider).extract::<JsonValue>().unwrap_err();
+        assert!(error.to_string().contains("permission denied"));
+
+        // 错误定位到密钥所在的配置键
+        let (address, _requests) = serve(vec![(403, "{}".to_string())]);
+        let provider = VaultProvider::new(address.as_str(), "secret", "myapp/prod")
+            .with_auth(VaultAuth::Token("bad-token".to_string()))
+            .with_nested_key("database");
+        let error = Figment::from(provider).extract::<JsonValue>().unwrap_err();
+        assert_eq!(error.path, ["database"]);
+
+        let provider = VaultProvider::new("http://127.0.0.1:1", "secret", "myapp/prod");
+        let error = provider.fetch().unwrap_err();
+        assert!(matches!(error, QuantumConfigError::Remote { service: "vault", ref resource, .. } if resource == "secret/myapp/prod"));
+        assert!(format!("{:?}", VaultAuth::Token("hidden-token".to_string())).contains("redacted"));
+    }
+}
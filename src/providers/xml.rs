@@ -0,0 +1,201 @@
+//! XML 配置解析
+//!
+//! 启用 `xml` 特性时，[`FileFormat::Xml`](super::file_provider::FileFormat::Xml) 使用本模块把 XML 文档
+//! 映射为与其他格式相同的嵌套值：
+//!
+//! - 根元素本身不对应任何键，它的属性与子元素构成顶层键；
+//! - 只含文本的元素为标量，文本按 TOML 的规则推断类型（`8080` 为整数、`true` 为布尔值）；
+//! - 带属性或子元素的元素为映射，属性与子元素都成为键；此时元素自身的文本写入 `$text` 键；
+//! - 同一元素下重复出现的同名子元素组成数组（只出现一次时仍为标量或映射）；
+//! - 命名空间前缀被忽略，注释、处理指令与 DOCTYPE 被跳过，不展开自定义实体。
+//!
+//! ```xml
+//! <config name="app">
+//!   <server port="8080"><host>0.0.0.0</host></server>
+//!   <allow>10.0.0.1</allow>
+//!   <allow>10.0.0.2</allow>
+//! </config>
+//! ```
+//!
+//! 对应 `name = "app"`、`server.port = 8080`、`server.host = "0.0.0.0"`、`allow = ["10.0.0.1", "10.0.0.2"]`。
+//!
+//! 解析错误映射为 `format_name` 为 `XML` 的 [`QuantumConfigError::FileParse`](crate::QuantumConfigError::FileParse)。
+
+use super::file_provider::infer_scalar;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+
+/// 元素文本所在的键
+pub(crate) const TEXT_KEY: &str = "$text";
+
+/// 正在解析的元素
+struct Element {
+    name: String,
+    entries: Map<String, Value>,
+    text: String,
+}
+
+/// 解析 XML 文本，错误信息包含出错位置的字节偏移
+pub(crate) fn parse(content: &str) -> Result<Value, String> {
+    let mut reader = Reader::from_str(content);
+    reader.config_mut().trim_text(true);
+    let located = |reader: &Reader<&[u8]>, message: String| {
+        format!("XML parse error at byte {}: {}", reader.error_position(), message)
+    };
+
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root: Option<Element> = None;
+    loop {
+        let event = reader.read_event().map_err(|e| located(&reader, e.to_string()))?;
+        match event {
+            Event::Start(start) => {
+                stack.push(open_element(&start).map_err(|message| located(&reader, message))?);
+            }
+            Event::Empty(start) => {
+                let element = open_element(&start).map_err(|message| located(&reader, message))?;
+                close_element(element, &mut stack, &mut root).map_err(|message| located(&reader, message))?;
+            }
+            Event::End(_) => {
+                let element = stack.pop().ok_or_else(|| located(&reader, "unexpected closing tag".to_string()))?;
+                close_element(element, &mut stack, &mut root).map_err(|message| located(&reader, message))?;
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| located(&reader, e.to_string()))?;
+                append_text(&mut stack, &text).map_err(|message| located(&reader, message))?;
+            }
+            Event::CData(data) => {
+                let text = data.decode().map_err(|e| located(&reader, e.to_string()))?;
+                append_text(&mut stack, &text).map_err(|message| located(&reader, message))?;
+            }
+            Event::Eof => break,
+            Event::Comment(_) | Event::Decl(_) | Event::PI(_) | Event::DocType(_) => {}
+        }
+    }
+
+    if let Some(element) = stack.last() {
+        return Err(located(&reader, format!("element <{}> is not closed", element.name)));
+    }
+    let root = root.ok_or_else(|| "XML document has no root element".to_string())?;
+    if !root.text.trim().is_empty() {
+        return Err(format!(
+            "root element <{}> must contain attributes or child elements, not text",
+            root.name
+        ));
+    }
+    Ok(Value::Object(root.entries))
+}
+
+/// 读取元素名与属性
+fn open_element(start: &BytesStart<'_>) -> Result<Element, String> {
+    let name = String::from_utf8_lossy(start.local_name().as_ref()).into_owned();
+    let mut entries = Map::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| e.to_string())?;
+        let key = attribute.key.local_name();
+        // 命名空间声明不是配置值
+        if attribute.key.as_ref().starts_with(b"xmlns") {
+            continue;
+        }
+        let value = attribute.unescape_value().map_err(|e| e.to_string())?;
+        insert_entry(&mut entries, String::from_utf8_lossy(key.as_ref()).into_owned(), infer_scalar(&value));
+    }
+    Ok(Element {
+        name,
+        entries,
+        text: String::new(),
+    })
+}
+
+fn append_text(stack: &mut [Element], text: &str) -> Result<(), String> {
+    let element = stack
+        .last_mut()
+        .ok_or_else(|| "text outside of the root element".to_string())?;
+    element.text.push_str(text);
+    Ok(())
+}
+
+/// 元素结束：转换为值并写入父元素，没有父元素时作为根元素
+fn close_element(element: Element, stack: &mut [Element], root: &mut Option<Element>) -> Result<(), String> {
+    let Some(parent) = stack.last_mut() else {
+        if root.is_some() {
+            return Err(format!("multiple root elements, found another <{}>", element.name));
+        }
+        *root = Some(element);
+        return Ok(());
+    };
+    let Element {
+        name,
+        mut entries,
+        text,
+    } = element;
+    let value = if entries.is_empty() {
+        infer_scalar(text.trim())
+    } else {
+        if !text.trim().is_empty() {
+            entries.insert(TEXT_KEY.to_string(), infer_scalar(text.trim()));
+        }
+        Value::Object(entries)
+    };
+    insert_entry(&mut parent.entries, name, value);
+    Ok(())
+}
+
+/// 写入键，重复的键组成数组
+fn insert_entry(entries: &mut Map<String, Value>, key: String, value: Value) {
+    match entries.get_mut(&key) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+        None => {
+            entries.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_elements_attributes_and_repeats() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- 由运维维护 -->
+<app:config xmlns:app="urn:example" name="app" debug="false">
+  <server port="8080">
+    <host>0.0.0.0</host>
+    <tls enabled="true"/>
+  </server>
+  <allow>10.0.0.1</allow>
+  <allow>10.0.0.2</allow>
+  <motd><![CDATA[Hello <world> & friends]]></motd>
+  <label lang="en">Main &amp; only</label>
+  <empty/>
+</app:config>"#;
+
+        assert_eq!(
+            parse(content).unwrap(),
+            json!({
+                "name": "app",
+                "debug": false,
+                "server": {"port": 8080, "host": "0.0.0.0", "tls": {"enabled": true}},
+                "allow": ["10.0.0.1", "10.0.0.2"],
+                "motd": "Hello <world> & friends",
+                "label": {"lang": "en", "$text": "Main & only"},
+                "empty": ""
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = parse("<config><server></config>").unwrap_err();
+        assert!(error.starts_with("XML parse error at byte"), "{}", error);
+        assert!(parse("<config>text</config>").unwrap_err().contains("must contain attributes or child elements"));
+        assert!(parse("<a/><b/>").unwrap_err().contains("multiple root elements"));
+        assert_eq!(parse("").unwrap_err(), "XML document has no root element");
+    }
+}
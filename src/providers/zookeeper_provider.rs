@@ -0,0 +1,1005 @@
+//! ZooKeeper 配置提供器
+//!
+//! 递归读取根节点下的 znode 树：有子节点的 znode 映射为表，叶子 znode 的数据按环境变量的规则
+//! 解析为布尔值、数字或字符串。例如根节点 `/myapp/prod` 下的 `/myapp/prod/database/host`
+//! 对应 `database.host`。连接串格式与 ZooKeeper 客户端相同（`host1:2181,host2:2181[/chroot]`），
+//! 依次尝试每个地址直到建立会话。
+//!
+//! 提供器直接实现所需的 ZooKeeper 客户端协议子集（建立会话、digest 认证、getData、getChildren
+//! 与监视），不依赖额外的客户端库。figment 的 `Provider::data` 是同步调用，其他远程提供器同样只使用
+//! 阻塞 I/O；现有的 Rust 客户端库要么需要异步运行时，要么自带事件循环线程与较重的依赖树，而这里只需要
+//! 只读的一小部分协议（不含写入、ACL 管理、事务与会话恢复）。协议处理按以下规则实现，并由测试覆盖：
+//!
+//! - 帧以 4 字节长度前缀读取，跨多个 TCP 分段到达的帧会被完整拼接；超过 `jute.maxbuffer`
+//!   默认值的长度、字段被截断的响应与读到一半断开的连接返回错误；
+//! - `ZNONODE` 表示节点在读取期间被删除，子节点直接跳过；其余错误码（`ZNOAUTH`、`ZAUTHFAILED`、
+//!   `ZSESSIONEXPIRED` 等）转为错误；
+//! - 会话过期（过期状态的事件或 `ZSESSIONEXPIRED` 响应）与服务端拒绝会话时返回错误，
+//!   [`ZookeeperProvider::watch`] 随后建立新会话，不尝试恢复旧会话。
+//!
+//! [`ZookeeperProvider::watch`] 监视树中的全部 znode，返回的通知通道可以交给
+//! `ConfigWatcher::from_notifications`（`watch` 特性）驱动热重载。连接断开或会话过期后后台线程按
+//! [`ZookeeperProvider::with_reconnect_backoff`] 的退避重新建立会话：
+//!
+//! ```ignore
+//! use quantum_config::providers::ZookeeperProvider;
+//! use quantum_config::watch::ConfigWatcher;
+//!
+//! let zk = ZookeeperProvider::new("zk1:2181,zk2:2181", "/myapp/prod");
+//! let notifications = zk.watch()?;
+//! let watcher = ConfigWatcher::<AppConfig>::from_notifications(notifications, move || {
+//!     Figment::from(zk.clone())
+//! });
+//! ```
+
+use super::env_provider::parse_scalar_value;
+use super::key_trie::KeyTrie;
+use super::remote::{self, Backoff};
+use crate::logging::{log_info, log_warn};
+use crate::error::QuantumConfigError;
+use figment::value::{Dict, Map};
+use figment::{Error, Metadata, Profile, Provider};
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+/// 默认的连接与请求超时
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 默认请求的会话超时
+const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 单个响应帧的长度上限，与服务端默认的 `jute.maxbuffer` 相同
+const MAX_FRAME_LEN: usize = 0xfffff;
+
+/// 请求操作码
+const OP_GET_DATA: i32 = 4;
+const OP_GET_CHILDREN: i32 = 8;
+const OP_PING: i32 = 11;
+const OP_AUTH: i32 = 100;
+const OP_CLOSE: i32 = -11;
+
+/// 服务端推送的监视事件、心跳与认证响应使用的保留 xid
+const WATCH_XID: i32 = -1;
+const PING_XID: i32 = -2;
+const AUTH_XID: i32 = -4;
+
+/// 会话状态变化事件的事件类型
+const EVENT_NONE: i32 = -1;
+
+/// 服务端错误码
+const ZNONODE: i32 = -101;
+const ZNOAUTH: i32 = -102;
+const ZSESSIONEXPIRED: i32 = -112;
+const ZAUTHFAILED: i32 = -115;
+
+/// 会话过期的会话状态
+const STATE_EXPIRED: i32 = -112;
+
+/// ZooKeeper 配置提供器
+#[derive(Clone)]
+pub struct ZookeeperProvider {
+    /// 服务器地址，例如 "zk1:2181"
+    hosts: Vec<String>,
+    /// 读取的根节点的绝对路径（已包含 chroot）
+    root: String,
+    /// digest 认证的用户名与密码
+    auth: Option<(String, String)>,
+    /// 连接与请求超时
+    timeout: Duration,
+    /// 请求的会话超时
+    session_timeout: Duration,
+    /// 监视断开后的重连退避
+    reconnect_backoff: Backoff,
+}
+
+impl std::fmt::Debug for ZookeeperProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // 不输出密码
+        f.debug_struct("ZookeeperProvider")
+            .field("hosts", &self.hosts)
+            .field("root", &self.root)
+            .field("user", &self.auth.as_ref().map(|(user, _)| user))
+            .field("timeout", &self.timeout)
+            .field("session_timeout", &self.session_timeout)
+            .field("reconnect_backoff", &self.reconnect_backoff)
+            .finish()
+    }
+}
+
+impl ZookeeperProvider {
+    /// 创建读取 `root` 下 znode 树的提供器
+    ///
+    /// 连接串中的 chroot 后缀（例如 `zk1:2181/apps`）会加在 `root` 之前。
+    pub fn new<S: Into<String>, P: Into<String>>(connect_string: S, root: P) -> Self {
+        let connect_string = connect_string.into();
+        let (hosts, chroot) = match connect_string.find('/') {
+            Some(index) => connect_string.split_at(index),
+            None => (connect_string.as_str(), ""),
+        };
+        Self {
+            hosts: hosts
+                .split(',')
+                .map(str::trim)
+                .filter(|host| !host.is_empty())
+                .map(str::to_string)
+                .collect(),
+            root: normalize_path(&format!("{}/{}", chroot, root.into())),
+            auth: None,
+            timeout: DEFAULT_TIMEOUT,
+            session_timeout: DEFAULT_SESSION_TIMEOUT,
+            reconnect_backoff: Backoff::new(remote::DEFAULT_RECONNECT_BACKOFF, remote::DEFAULT_MAX_RECONNECT_BACKOFF),
+        }
+    }
+
+    /// 使用 digest 方案认证（`user:password`）
+    pub fn with_digest_auth<U: Into<String>, P: Into<String>>(mut self, user: U, password: P) -> Self {
+        self.auth = Some((user.into(), password.into()));
+        self
+    }
+
+    /// 设置连接与请求超时
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// 设置请求的会话超时，实际值由服务端协商决定
+    ///
+    /// [`ZookeeperProvider::watch`] 按协商结果的三分之一间隔发送心跳以保持会话。
+    pub fn with_session_timeout(mut self, timeout: Duration) -> Self {
+        self.session_timeout = timeout;
+        self
+    }
+
+    /// 设置 [`ZookeeperProvider::watch`] 断开后的重连退避
+    ///
+    /// 首次重连前等待 `initial`，之后每次失败加倍，不超过 `max`；默认为 1 秒与 60 秒。
+    pub fn with_reconnect_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.reconnect_backoff = Backoff::new(initial, max);
+        self
+    }
+
+    /// 读取 znode 树并构建键前缀树，叶子来源为 znode 的完整路径
+    pub fn key_trie(&self) -> Result<KeyTrie, QuantumConfigError> {
+        let mut session = self.connect()?;
+        self.read_tree(&mut session, false)
+    }
+
+    /// 监视 znode 树的变更
+    ///
+    /// 先读取一次整棵树并在每个 znode 上注册监视，之后每次变更发送一条通知。ZooKeeper 的监视只触发一次，
+    /// 后台线程收到事件后重新读取整棵树以重新注册监视，再发送通知。
+    /// 连接断开、会话过期或重新读取失败时记录警告，按重连退避重新建立会话并注册监视；断开期间的变更无法得知，
+    /// 重连成功后发送一条通知。接收端被丢弃后后台线程在下一次发送通知时退出。
+    ///
+    /// # Errors
+    /// 无法建立会话或读取 znode 树时返回错误
+    pub fn watch(&self) -> Result<Receiver<()>, QuantumConfigError> {
+        let mut session = self.connect()?;
+        self.read_tree(&mut session, true)?;
+
+        let provider = self.clone();
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut backoff = provider.reconnect_backoff;
+            loop {
+                let result = if session.notified {
+                    session.notified = false;
+                    // 先重新注册监视再通知，重新读取期间的变更会再次触发事件而不会丢失
+                    provider.read_tree(&mut session, true).map(|_| true)
+                } else {
+                    session
+                        .wait_for_event()
+                        .map(|_| false)
+                        .map_err(|reason| provider.error(&provider.root, reason))
+                };
+                match result {
+                    Ok(false) => continue,
+                    Ok(true) => {}
+                    Err(e) => {
+                        log_warn!("ZooKeeper watch lost its session, reconnecting: {}", e);
+                        session = provider.reconnect(&mut backoff);
+                    }
+                }
+                if sender.send(()).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(receiver)
+    }
+
+    /// 按退避重新建立会话并重新注册监视，直到成功
+    fn reconnect(&self, backoff: &mut Backoff) -> Session {
+        loop {
+            std::thread::sleep(backoff.next_delay());
+            let result = self.connect().and_then(|mut session| {
+                self.read_tree(&mut session, true)?;
+                Ok(session)
+            });
+            match result {
+                Ok(session) => {
+                    log_info!("ZooKeeper watch on '{}' reconnected", self.root);
+                    backoff.reset();
+                    return session;
+                }
+                Err(e) => log_warn!("ZooKeeper watch reconnect failed: {}", e),
+            }
+        }
+    }
+
+    /// 依次尝试每个地址建立会话，配置了认证信息时完成认证
+    fn connect(&self) -> Result<Session, QuantumConfigError> {
+        let mut last_error = "no ZooKeeper hosts configured".to_string();
+        for host in &self.hosts {
+            let mut session = match Session::connect(host, self.session_timeout, self.timeout) {
+                Ok(session) => session,
+                Err(e) => {
+                    last_error = format!("failed to connect to {}: {}", host, e);
+                    continue;
+                }
+            };
+            if let Some((user, password)) = &self.auth {
+                session
+                    .authenticate(user, password)
+                    .map_err(|reason| self.error(&self.root, format!("authentication failed: {}", reason)))?;
+            }
+            return Ok(session);
+        }
+        Err(self.error(&self.root, last_error))
+    }
+
+    /// 读取根节点下的整棵树
+    fn read_tree(&self, session: &mut Session, watch: bool) -> Result<KeyTrie, QuantumConfigError> {
+        let mut trie = KeyTrie::new();
+        self.read_node(session, &self.root, &mut Vec::new(), watch, &mut trie)?;
+        Ok(trie)
+    }
+
+    /// 递归读取 znode：有子节点时读取子节点，否则读取数据作为叶子值
+    fn read_node(
+        &self,
+        session: &mut Session,
+        path: &str,
+        segments: &mut Vec<String>,
+        watch: bool,
+        trie: &mut KeyTrie,
+    ) -> Result<(), QuantumConfigError> {
+        let Some(mut children) = session.get_children(path, watch).map_err(|reason| self.error(path, reason))? else {
+            // 读取期间被删除的子节点直接跳过，根节点不存在则视为错误
+            return if segments.is_empty() {
+                Err(self.error(path, "node does not exist".to_string()))
+            } else {
+                Ok(())
+            };
+        };
+        if !children.is_empty() {
+            children.sort();
+            for child in children {
+                let child_path = join_path(path, &child);
+                segments.push(child);
+                self.read_node(session, &child_path, segments, watch, trie)?;
+                segments.pop();
+            }
+            return Ok(());
+        }
+        // 根节点本身的数据不对应任何键
+        if segments.is_empty() {
+            return Ok(());
+        }
+        let Some(data) = session.get_data(path, watch).map_err(|reason| self.error(path, reason))? else {
+            return Ok(());
+        };
+        let value = String::from_utf8(data).map_err(|_| self.error(path, "data is not valid UTF-8".to_string()))?;
+        trie.insert(segments.iter().map(String::as_str), parse_scalar_value(value), Some(path.to_string()))
+    }
+
+    /// 构造带有 znode 路径的错误
+    fn error(&self, path: &str, reason: String) -> QuantumConfigError {
+        remote::error("zookeeper", path, reason)
+    }
+}
+
+/// 规范化 znode 路径：去除空段与末尾的 `/`，空路径为 `/`
+fn normalize_path(path: &str) -> String {
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    format!("/{}", segments.join("/"))
+}
+
+/// 拼接父节点路径与子节点名
+fn join_path(parent: &str, child: &str) -> String {
+    if parent == "/" {
+        format!("/{}", child)
+    } else {
+        format!("{}/{}", parent, child)
+    }
+}
+
+/// 服务端错误码的描述
+fn describe_code(code: i32) -> String {
+    match code {
+        ZNOAUTH => "not authorized to read the node".to_string(),
+        ZAUTHFAILED => "authentication rejected by the server".to_string(),
+        ZSESSIONEXPIRED => "session expired".to_string(),
+        code => format!("server error code {}", code),
+    }
+}
+
+/// 与一台 ZooKeeper 服务器之间的会话
+struct Session {
+    /// 连接
+    stream: TcpStream,
+    /// 请求超时
+    timeout: Duration,
+    /// 心跳间隔
+    ping_interval: Duration,
+    /// 下一个请求的 xid
+    next_xid: i32,
+    /// 是否收到尚未处理的监视事件
+    notified: bool,
+}
+
+impl Session {
+    /// 连接服务器并建立新会话
+    fn connect(host: &str, session_timeout: Duration, timeout: Duration) -> io::Result<Self> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, "address did not resolve");
+        let mut stream = None;
+        for address in host.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, timeout) {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(e) => last_error = e,
+            }
+        }
+        let stream = stream.ok_or(last_error)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        stream.set_nodelay(true)?;
+
+        let mut request = Vec::new();
+        put_i32(&mut request, 0); // 协议版本
+        put_i64(&mut request, 0); // 最后看到的 zxid
+        put_i32(&mut request, i32::try_from(session_timeout.as_millis()).unwrap_or(i32::MAX));
+        put_i64(&mut request, 0); // 会话 ID，0 表示新会话
+        put_buffer(&mut request, &[0; 16]); // 会话密码
+        request.push(0); // 只读模式
+        let mut session = Self {
+            stream,
+            timeout,
+            ping_interval: session_timeout / 3,
+            next_xid: 1,
+            notified: false,
+        };
+        session.write_frame(&request)?;
+        let response = session.read_frame()?;
+        let mut decoder = Decoder::new(&response);
+        let negotiated = decoder.i32().and_then(|_| decoder.i32()).map_err(io::Error::other)?;
+        if negotiated <= 0 {
+            return Err(io::Error::other("session was rejected by the server"));
+        }
+        session.ping_interval = Duration::from_millis(negotiated as u64 / 3);
+        Ok(session)
+    }
+
+    /// 使用 digest 方案认证
+    fn authenticate(&mut self, user: &str, password: &str) -> Result<(), String> {
+        let mut body = Vec::new();
+        put_i32(&mut body, 0);
+        put_string(&mut body, "digest");
+        put_buffer(&mut body, format!("{}:{}", user, password).as_bytes());
+        match self.call(AUTH_XID, OP_AUTH, &body)? {
+            (0, _) => Ok(()),
+            (code, _) => Err(describe_code(code)),
+        }
+    }
+
+    /// 读取子节点名，节点不存在时返回 `None`
+    fn get_children(&mut self, path: &str, watch: bool) -> Result<Option<Vec<String>>, String> {
+        let Some(body) = self.path_request(OP_GET_CHILDREN, path, watch)? else {
+            return Ok(None);
+        };
+        let mut decoder = Decoder::new(&body);
+        let count = decoder.i32()?;
+        (0..count.max(0)).map(|_| decoder.string()).collect::<Result<_, _>>().map(Some)
+    }
+
+    /// 读取节点数据，节点不存在时返回 `None`
+    fn get_data(&mut self, path: &str, watch: bool) -> Result<Option<Vec<u8>>, String> {
+        let Some(body) = self.path_request(OP_GET_DATA, path, watch)? else {
+            return Ok(None);
+        };
+        // 数据之后的节点状态不需要
+        Ok(Some(Decoder::new(&body).buffer()?.unwrap_or_default()))
+    }
+
+    /// 发送以路径与监视标志为参数的请求，节点不存在时返回 `None`
+    fn path_request(&mut self, op: i32, path: &str, watch: bool) -> Result<Option<Vec<u8>>, String> {
+        let mut body = Vec::new();
+        put_string(&mut body, path);
+        body.push(u8::from(watch));
+        let xid = self.next_xid;
+        self.next_xid = self.next_xid.wrapping_add(1).max(1);
+        match self.call(xid, op, &body)? {
+            (0, response) => Ok(Some(response)),
+            (ZNONODE, _) => Ok(None),
+            (code, _) => Err(describe_code(code)),
+        }
+    }
+
+    /// 发送请求并等待对应的响应，返回错误码与响应体；期间收到的监视事件只做记录
+    fn call(&mut self, xid: i32, op: i32, body: &[u8]) -> Result<(i32, Vec<u8>), String> {
+        let mut request = Vec::with_capacity(body.len() + 8);
+        put_i32(&mut request, xid);
+        put_i32(&mut request, op);
+        request.extend_from_slice(body);
+        self.write_frame(&request).map_err(|e| e.to_string())?;
+        loop {
+            let frame = self.read_frame().map_err(|e| e.to_string())?;
+            let (reply_xid, code, response) = self.handle_frame(&frame)?;
+            if reply_xid == xid {
+                return Ok((code, response));
+            }
+        }
+    }
+
+    /// 等待下一条服务端消息，空闲超过心跳间隔时发送心跳
+    fn wait_for_event(&mut self) -> Result<(), String> {
+        self.stream.set_read_timeout(Some(self.ping_interval)).map_err(|e| e.to_string())?;
+        let ready = self.stream.peek(&mut [0; 1]);
+        self.stream.set_read_timeout(Some(self.timeout)).map_err(|e| e.to_string())?;
+        match ready {
+            Ok(0) => Err("connection closed by the server".to_string()),
+            Ok(_) => {
+                let frame = self.read_frame().map_err(|e| e.to_string())?;
+                self.handle_frame(&frame).map(|_| ())
+            }
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                let mut ping = Vec::new();
+                put_i32(&mut ping, PING_XID);
+                put_i32(&mut ping, OP_PING);
+                self.write_frame(&ping).map_err(|e| e.to_string())
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    /// 解析响应头；监视事件记录到 `notified`，会话过期时返回错误
+    fn handle_frame(&mut self, frame: &[u8]) -> Result<(i32, i32, Vec<u8>), String> {
+        let mut decoder = Decoder::new(frame);
+        let xid = decoder.i32()?;
+        let _zxid = decoder.i64()?;
+        let code = decoder.i32()?;
+        if xid == WATCH_XID {
+            let event_type = decoder.i32()?;
+            let state = decoder.i32()?;
+            if event_type == EVENT_NONE && state == STATE_EXPIRED {
+                return Err(describe_code(ZSESSIONEXPIRED));
+            }
+            if event_type != EVENT_NONE {
+                self.notified = true;
+            }
+        }
+        Ok((xid, code, decoder.rest().to_vec()))
+    }
+
+    /// 写入带长度前缀的帧
+    fn write_frame(&mut self, payload: &[u8]) -> io::Result<()> {
+        let mut frame = Vec::with_capacity(payload.len() + 4);
+        put_i32(&mut frame, payload.len() as i32);
+        frame.extend_from_slice(payload);
+        self.stream.write_all(&frame)
+    }
+
+    /// 读取带长度前缀的帧
+    fn read_frame(&mut self) -> io::Result<Vec<u8>> {
+        let mut length = [0; 4];
+        self.stream.read_exact(&mut length)?;
+        let length = usize::try_from(i32::from_be_bytes(length))
+            .ok()
+            .filter(|length| *length <= MAX_FRAME_LEN)
+            .ok_or_else(|| io::Error::other("invalid frame length"))?;
+        let mut payload = vec![0; length];
+        self.stream.read_exact(&mut payload)?;
+        Ok(payload)
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        // 主动关闭会话，服务端无需等待会话超时
+        let mut close = Vec::new();
+        put_i32(&mut close, self.next_xid);
+        put_i32(&mut close, OP_CLOSE);
+        let _ = self.write_frame(&close);
+    }
+}
+
+fn put_i32(buffer: &mut Vec<u8>, value: i32) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_i64(buffer: &mut Vec<u8>, value: i64) {
+    buffer.extend_from_slice(&value.to_be_bytes());
+}
+
+fn put_buffer(buffer: &mut Vec<u8>, value: &[u8]) {
+    put_i32(buffer, value.len() as i32);
+    buffer.extend_from_slice(value);
+}
+
+fn put_string(buffer: &mut Vec<u8>, value: &str) {
+    put_buffer(buffer, value.as_bytes());
+}
+
+/// 按 jute 编码读取响应字段
+struct Decoder<'a> {
+    buffer: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.buffer.len() < length {
+            return Err("truncated response".to_string());
+        }
+        let (head, rest) = self.buffer.split_at(length);
+        self.buffer = rest;
+        Ok(head)
+    }
+
+    fn i32(&mut self) -> Result<i32, String> {
+        let bytes = self.take(4)?;
+        Ok(i32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn i64(&mut self) -> Result<i64, String> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.take(8)?);
+        Ok(i64::from_be_bytes(bytes))
+    }
+
+    /// 长度为 -1 的缓冲区表示空值
+    fn buffer(&mut self) -> Result<Option<Vec<u8>>, String> {
+        let length = self.i32()?;
+        if length < 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.take(length as usize)?.to_vec()))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let bytes = self.buffer()?.unwrap_or_default();
+        String::from_utf8(bytes).map_err(|_| "node name is not valid UTF-8".to_string())
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        self.buffer
+    }
+}
+
+impl Provider for ZookeeperProvider {
+    fn metadata(&self) -> Metadata {
+        // 错误信息中的键路径还原为 znode 路径
+        let root = self.root.clone();
+        Metadata::named(format!("ZooKeeper ({}{})", self.hosts.join(","), self.root))
+            .interpolater(move |_profile, keys| keys.iter().fold(root.clone(), |path, key| join_path(&path, key)))
+    }
+
+    fn data(&self) -> Result<Map<Profile, Dict>, Error> {
+        let trie = self.key_trie().map_err(|e| remote::provider_error(e, None))?;
+        let mut data = Map::new();
+        data.insert(Profile::Default, trie.into_dict());
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::Figment;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// 实现测试所需协议子集的 ZooKeeper 服务器
+    #[derive(Clone)]
+    struct FakeServer {
+        address: String,
+        /// znode 路径到数据
+        nodes: Arc<Mutex<BTreeMap<String, Vec<u8>>>>,
+        /// 已注册监视的连接
+        watchers: Arc<Mutex<Vec<TcpStream>>>,
+    }
+
+    impl FakeServer {
+        fn start(nodes: &[(&str, &str)], digest: Option<&'static str>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let server = Self {
+                address: listener.local_addr().unwrap().to_string(),
+                nodes: Arc::new(Mutex::new(BTreeMap::new())),
+                watchers: Arc::new(Mutex::new(Vec::new())),
+            };
+            for (path, data) in nodes {
+                server.set(path, data);
+            }
+            let handler = server.clone();
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let handler = handler.clone();
+                    std::thread::spawn(move || handler.handle(stream.unwrap(), digest));
+                }
+            });
+            server
+        }
+
+        /// 写入节点并补齐父节点
+        fn set(&self, path: &str, data: &str) {
+            let mut nodes = self.nodes.lock().unwrap();
+            let mut parent = String::new();
+            for segment in path.split('/').filter(|s| !s.is_empty()) {
+                parent = format!("{}/{}", parent, segment);
+                nodes.entry(parent.clone()).or_default();
+            }
+            nodes.insert(path.to_string(), data.as_bytes().to_vec());
+        }
+
+        /// 向所有注册了监视的连接推送数据变更事件
+        fn notify(&self, path: &str) {
+            let mut event = Vec::new();
+            put_i32(&mut event, WATCH_XID);
+            put_i64(&mut event, -1);
+            put_i32(&mut event, 0);
+            put_i32(&mut event, 3); // NodeDataChanged
+            put_i32(&mut event, 3); // SyncConnected
+            put_string(&mut event, path);
+            for stream in self.watchers.lock().unwrap().iter_mut() {
+                let _ = write_frame(stream, &event);
+            }
+        }
+
+        /// 向所有注册了监视的连接推送会话过期事件
+        fn expire(&self) {
+            let mut event = Vec::new();
+            put_i32(&mut event, WATCH_XID);
+            put_i64(&mut event, -1);
+            put_i32(&mut event, 0);
+            put_i32(&mut event, EVENT_NONE);
+            put_i32(&mut event, STATE_EXPIRED);
+            put_string(&mut event, "");
+            for mut stream in self.watchers.lock().unwrap().drain(..) {
+                let _ = write_frame(&mut stream, &event);
+            }
+        }
+
+        /// 断开所有注册了监视的连接
+        fn disconnect(&self) {
+            for stream in self.watchers.lock().unwrap().drain(..) {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+            }
+        }
+
+        fn handle(&self, mut stream: TcpStream, digest: Option<&str>) {
+            let Ok(connect) = read_frame(&mut stream) else {
+                return;
+            };
+            let mut decoder = Decoder::new(&connect);
+            decoder.i32().unwrap();
+            decoder.i64().unwrap();
+            let timeout = decoder.i32().unwrap();
+            let mut response = Vec::new();
+            put_i32(&mut response, 0);
+            put_i32(&mut response, timeout);
+            put_i64(&mut response, 1);
+            put_buffer(&mut response, &[0; 16]);
+            write_frame(&mut stream, &response).unwrap();
+
+            let mut authenticated = digest.is_none();
+            let mut watching = false;
+            while let Ok(request) = read_frame(&mut stream) {
+                let mut decoder = Decoder::new(&request);
+                let xid = decoder.i32().unwrap();
+                let op = decoder.i32().unwrap();
+                let (code, body) = match op {
+                    OP_AUTH => {
+                        decoder.i32().unwrap();
+                        decoder.string().unwrap();
+                        authenticated = decoder.buffer().unwrap().as_deref() == digest.map(str::as_bytes);
+                        (if authenticated { 0 } else { ZAUTHFAILED }, Vec::new())
+                    }
+                    OP_GET_DATA | OP_GET_CHILDREN if !authenticated => (ZNOAUTH, Vec::new()),
+                    OP_GET_DATA | OP_GET_CHILDREN => {
+                        let path = decoder.string().unwrap();
+                        if decoder.take(1).unwrap()[0] == 1 && !watching {
+                            watching = true;
+                            self.watchers.lock().unwrap().push(stream.try_clone().unwrap());
+                        }
+                        self.read(op, &path)
+                    }
+                    OP_PING => (0, Vec::new()),
+                    _ => return,
+                };
+                let mut reply = Vec::new();
+                put_i32(&mut reply, xid);
+                put_i64(&mut reply, 1);
+                put_i32(&mut reply, code);
+                reply.extend_from_slice(&body);
+                write_frame(&mut stream, &reply).unwrap();
+            }
+        }
+
+        fn read(&self, op: i32, path: &str) -> (i32, Vec<u8>) {
+            let nodes = self.nodes.lock().unwrap();
+            let Some(data) = nodes.get(path) else {
+                return (ZNONODE, Vec::new());
+            };
+            let mut body = Vec::new();
+            if op == OP_GET_DATA {
+                put_buffer(&mut body, data);
+                body.extend_from_slice(&[0; 68]); // 节点状态
+            } else {
+                let prefix = join_path(path, "");
+                let children: Vec<&str> = nodes
+                    .keys()
+                    .filter_map(|key| key.strip_prefix(prefix.as_str()))
+                    .filter(|name| !name.is_empty() && !name.contains('/'))
+                    .collect();
+                put_i32(&mut body, children.len() as i32);
+                for child in children {
+                    put_string(&mut body, child);
+                }
+            }
+            (0, body)
+        }
+    }
+
+    fn write_frame(stream: &mut TcpStream, payload: &[u8]) -> io::Result<()> {
+        let mut frame = Vec::new();
+        put_buffer(&mut frame, payload);
+        stream.write_all(&frame)
+    }
+
+    fn read_frame(stream: &mut TcpStream) -> io::Result<Vec<u8>> {
+        let mut length = [0; 4];
+        stream.read_exact(&mut length)?;
+        let mut payload = vec![0; i32::from_be_bytes(length) as usize];
+        stream.read_exact(&mut payload)?;
+        Ok(payload)
+    }
+
+    /// 建立会话后由 `script` 接管服务端连接，用于构造异常的协议交互
+    fn scripted_session(script: impl FnOnce(TcpStream) + Send + 'static) -> io::Result<Session> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_frame(&mut stream).unwrap();
+            let mut response = Vec::new();
+            put_i32(&mut response, 0);
+            put_i32(&mut response, 30_000);
+            put_i64(&mut response, 1);
+            put_buffer(&mut response, &[0; 16]);
+            write_frame(&mut stream, &response).unwrap();
+            script(stream);
+        });
+        Session::connect(&address, Duration::from_secs(30), Duration::from_secs(2))
+    }
+
+    /// 读取一个请求并返回其 xid
+    fn read_request(stream: &mut TcpStream) -> i32 {
+        Decoder::new(&read_frame(stream).unwrap()).i32().unwrap()
+    }
+
+    /// 响应帧的内容
+    fn reply(xid: i32, code: i32, body: &[u8]) -> Vec<u8> {
+        let mut reply = Vec::new();
+        put_i32(&mut reply, xid);
+        put_i64(&mut reply, 1);
+        put_i32(&mut reply, code);
+        reply.extend_from_slice(body);
+        reply
+    }
+
+    /// 以 `code` 响应一次 getChildren 请求
+    fn children_with_code(code: i32) -> Result<Option<Vec<String>>, String> {
+        let mut session = scripted_session(move |mut stream| {
+            let xid = read_request(&mut stream);
+            write_frame(&mut stream, &reply(xid, code, &[])).unwrap();
+        })
+        .unwrap();
+        session.get_children("/app", false)
+    }
+
+    #[test]
+    fn test_zookeeper_error_codes() {
+        assert_eq!(children_with_code(ZNONODE), Ok(None));
+        assert_eq!(children_with_code(ZNOAUTH), Err("not authorized to read the node".to_string()));
+        assert_eq!(children_with_code(ZAUTHFAILED), Err("authentication rejected by the server".to_string()));
+        assert_eq!(children_with_code(-999), Err("server error code -999".to_string()));
+
+        // 未认证时读取受保护的树
+        let server = FakeServer::start(&[("/myapp/port", "8080")], Some("app:secret"));
+        let error = ZookeeperProvider::new(&server.address, "/myapp").key_trie().unwrap_err();
+        assert_eq!(error.code(), "zookeeper");
+        assert!(error.to_string().contains("not authorized"), "{}", error);
+    }
+
+    #[test]
+    fn test_zookeeper_session_expiry() {
+        // 等待响应期间收到会话过期事件
+        let mut session = scripted_session(|mut stream| {
+            read_request(&mut stream);
+            let mut event = reply(WATCH_XID, 0, &[]);
+            put_i32(&mut event, EVENT_NONE);
+            put_i32(&mut event, STATE_EXPIRED);
+            put_string(&mut event, "");
+            write_frame(&mut stream, &event).unwrap();
+        })
+        .unwrap();
+        assert_eq!(session.get_children("/app", false), Err("session expired".to_string()));
+        assert_eq!(children_with_code(ZSESSIONEXPIRED), Err("session expired".to_string()));
+
+        // 服务端以非正的会话超时拒绝会话
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            read_frame(&mut stream).unwrap();
+            let mut response = Vec::new();
+            put_i32(&mut response, 0);
+            put_i32(&mut response, 0);
+            put_i64(&mut response, 0);
+            put_buffer(&mut response, &[0; 16]);
+            write_frame(&mut stream, &response).unwrap();
+        });
+        let error = Session::connect(&address, Duration::from_secs(30), Duration::from_secs(2)).err().unwrap();
+        assert!(error.to_string().contains("rejected"), "{}", error);
+
+        // 监视在会话过期后建立新会话并通知
+        let server = FakeServer::start(&[("/myapp/port", "8080")], None);
+        let provider = ZookeeperProvider::new(&server.address, "/myapp")
+            .with_reconnect_backoff(Duration::from_millis(10), Duration::from_millis(50));
+        let notifications = provider.watch().unwrap();
+        server.set("/myapp/port", "9090");
+        server.expire();
+        notifications.recv_timeout(Duration::from_secs(5)).unwrap();
+        server.set("/myapp/port", "9191");
+        server.notify("/myapp/port");
+        notifications.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    #[test]
+    fn test_zookeeper_partial_reads() {
+        // 响应逐字节到达
+        let mut session = scripted_session(|mut stream| {
+            let xid = read_request(&mut stream);
+            let mut body = Vec::new();
+            put_i32(&mut body, 2);
+            put_string(&mut body, "database");
+            put_string(&mut body, "port");
+            let mut frame = Vec::new();
+            put_buffer(&mut frame, &reply(xid, 0, &body));
+            stream.set_nodelay(true).unwrap();
+            for byte in frame {
+                stream.write_all(&[byte]).unwrap();
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        })
+        .unwrap();
+        let children = session.get_children("/app", false).unwrap();
+        assert_eq!(children, Some(vec!["database".to_string(), "port".to_string()]));
+
+        // 帧完整但字段被截断
+        let mut session = scripted_session(|mut stream| {
+            let xid = read_request(&mut stream);
+            let mut body = Vec::new();
+            put_i32(&mut body, 2);
+            put_string(&mut body, "database");
+            write_frame(&mut stream, &reply(xid, 0, &body)).unwrap();
+        })
+        .unwrap();
+        assert_eq!(session.get_children("/app", false), Err("truncated response".to_string()));
+
+        // 帧读到一半连接断开
+        let mut session = scripted_session(|mut stream| {
+            read_request(&mut stream);
+            let mut frame = Vec::new();
+            put_i32(&mut frame, 100);
+            frame.extend_from_slice(&[0; 10]);
+            stream.write_all(&frame).unwrap();
+        })
+        .unwrap();
+        assert!(session.get_children("/app", false).is_err());
+
+        // 帧长度超过上限
+        let mut session = scripted_session(|mut stream| {
+            read_request(&mut stream);
+            let mut frame = Vec::new();
+            put_i32(&mut frame, MAX_FRAME_LEN as i32 + 1);
+            stream.write_all(&frame).unwrap();
+        })
+        .unwrap();
+        assert_eq!(session.get_children("/app", false), Err("invalid frame length".to_string()));
+    }
+
+    #[test]
+    fn test_zookeeper_provider_reads_znode_tree() {
+        let server = FakeServer::start(
+            &[
+                ("/apps/myapp/prod", "ignored root data"),
+                ("/apps/myapp/prod/database/host", "db.internal"),
+                ("/apps/myapp/prod/database/port", "5432"),
+                ("/apps/myapp/prod/debug", "true"),
+                ("/apps/other/key", "x"),
+            ],
+            Some("app:secret"),
+        );
+        let connect_string = format!("127.0.0.1:1,{}/apps", server.address);
+        let provider = ZookeeperProvider::new(&connect_string, "myapp/prod/")
+            .with_timeout(Duration::from_secs(2))
+            .with_digest_auth("app", "secret");
+        assert_eq!(provider.root, "/apps/myapp/prod");
+        assert!(!format!("{:?}", provider).contains("secret"));
+
+        let value: serde_json::Value = Figment::from(provider.clone()).extract().unwrap();
+        assert_eq!(value, json!({"database": {"host": "db.internal", "port": 5432}, "debug": true}));
+
+        let error = ZookeeperProvider::new(&connect_string, "myapp/prod")
+            .with_digest_auth("app", "wrong")
+            .key_trie()
+            .unwrap_err();
+        assert_eq!(error.code(), "zookeeper");
+        assert!(error.to_string().contains("authentication failed"), "{}", error);
+
+        let error = ZookeeperProvider::new(&server.address, "/missing").key_trie().unwrap_err();
+        assert!(error.to_string().contains("'/missing'"), "{}", error);
+    }
+
+    #[test]
+    fn test_zookeeper_watch_notifies_and_reregisters() {
+        let server = FakeServer::start(&[("/myapp/port", "8080")], None);
+        let provider = ZookeeperProvider::new(&server.address, "/myapp").with_session_timeout(Duration::from_millis(300));
+        let notifications = provider.watch().unwrap();
+
+        for port in [9090, 9191] {
+            server.set("/myapp/port", &port.to_string());
+            server.notify("/myapp/port");
+            notifications.recv_timeout(Duration::from_secs(5)).unwrap();
+            let value: serde_json::Value = Figment::from(provider.clone()).extract().unwrap();
+            assert_eq!(value, json!({ "port": port }));
+        }
+
+        // 空闲期间发送心跳保持会话
+        std::thread::sleep(Duration::from_millis(400));
+        server.set("/myapp/port", "9292");
+        server.notify("/myapp/port");
+        notifications.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    #[test]
+    fn test_zookeeper_watch_reconnects_after_disconnect() {
+        let server = FakeServer::start(&[("/myapp/port", "8080")], None);
+        let provider = ZookeeperProvider::new(&server.address, "/myapp")
+            .with_reconnect_backoff(Duration::from_millis(10), Duration::from_millis(50));
+        let notifications = provider.watch().unwrap();
+
+        // 断开期间的变更在重连后通知
+        server.set("/myapp/port", "9090");
+        server.disconnect();
+        notifications.recv_timeout(Duration::from_secs(5)).unwrap();
+        let value: serde_json::Value = Figment::from(provider.clone()).extract().unwrap();
+        assert_eq!(value, json!({ "port": 9090 }));
+
+        // 重连后重新注册了监视
+        server.set("/myapp/port", "9191");
+        server.notify("/myapp/port");
+        notifications.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+}
@@ -0,0 +1,122 @@
+//! 敏感字段脱敏
+//!
+//! 标注了 `#[config(secret)]` 的字段（以及 `#[config(secret_keys("password"))]` 列出的嵌套键）
+//! 由 [`crate::Config::secret_keys`] 给出。[`crate::Config::redacted`] 返回的 [`Redacted`] 视图在
+//! `Debug` 与 `Display` 中将这些键的值替换为 [`REDACTED`]，可以直接写入日志：
+//!
+//! ```ignore
+//! log::info!("loaded configuration: {}", config.redacted());
+//! ```
+//!
+//! 生成模板与写回文件时，这些键会被省略。
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::scrub::REDACTED;
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+/// 敏感键已脱敏的配置视图
+pub struct Redacted<'a, T> {
+    config: &'a T,
+}
+
+impl<'a, T: Config> Redacted<'a, T> {
+    /// 创建配置的脱敏视图
+    pub fn new(config: &'a T) -> Self {
+        Self { config }
+    }
+
+    /// 敏感键替换为 [`REDACTED`] 后的结构化数据
+    pub fn to_value(&self) -> Result<JsonValue, QuantumConfigError> {
+        let mut value = serde_json::to_value(self.config)
+            .map_err(|e| QuantumConfigError::Internal(format!("Failed to serialize configuration: {}", e)))?;
+        for key in T::secret_keys() {
+            redact_key(&mut value, &key);
+        }
+        Ok(value)
+    }
+
+    fn render(&self, f: &mut fmt::Formatter<'_>, pretty: bool) -> fmt::Result {
+        let value = self.to_value().map_err(|_| fmt::Error)?;
+        let rendered = if pretty {
+            serde_json::to_string_pretty(&value)
+        } else {
+            serde_json::to_string(&value)
+        };
+        f.write_str(&rendered.map_err(|_| fmt::Error)?)
+    }
+}
+
+impl<T: Config> fmt::Debug for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = std::any::type_name::<T>().rsplit("::").next().unwrap_or_default();
+        write!(f, "{} ", name)?;
+        self.render(f, f.alternate())
+    }
+}
+
+impl<T: Config> fmt::Display for Redacted<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, f.alternate())
+    }
+}
+
+/// 将以 `.` 分隔的键路径上的值替换为 [`REDACTED`]，键不存在或值为 `null` 时不做修改
+pub fn redact_key(value: &mut JsonValue, key_path: &str) {
+    if let Some(target) = lookup_mut(value, key_path) {
+        if !target.is_null() {
+            *target = JsonValue::String(REDACTED.to_string());
+        }
+    }
+}
+
+/// 移除以 `.` 分隔的键路径上的条目
+pub fn remove_key(value: &mut JsonValue, key_path: &str) {
+    let (parent, last) = match key_path.rsplit_once('.') {
+        Some((parent, last)) => (lookup_mut(value, parent), last),
+        None => (Some(value), key_path),
+    };
+    if let Some(JsonValue::Object(map)) = parent {
+        map.remove(last);
+    }
+}
+
+/// 将以 `.` 分隔的键路径上的值加入待登记的敏感值，参见 [`crate::scrub`]
+pub fn collect_secret_key<T: serde::Serialize>(config: &T, key_path: &str, secrets: &mut crate::scrub::Secrets) {
+    if let Ok(mut value) = serde_json::to_value(config) {
+        if let Some(secret) = lookup_mut(&mut value, key_path) {
+            secrets.add_json(secret);
+        }
+        // 序列化结果中还有其他敏感字段的明文
+        crate::scrub::zeroize_json(&mut value);
+    }
+}
+
+fn lookup_mut<'a>(value: &'a mut JsonValue, key_path: &str) -> Option<&'a mut JsonValue> {
+    key_path.split('.').try_fold(value, |value, segment| value.get_mut(segment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_and_remove_nested_keys() {
+        let mut value = json!({ "token": "t", "database": { "password": "p", "host": "h" }, "api_key": null });
+        redact_key(&mut value, "token");
+        redact_key(&mut value, "database.password");
+        redact_key(&mut value, "api_key");
+        redact_key(&mut value, "missing.key");
+        assert_eq!(
+            value,
+            json!({ "token": REDACTED, "database": { "password": REDACTED, "host": "h" }, "api_key": null })
+        );
+
+        remove_key(&mut value, "database.password");
+        remove_key(&mut value, "token");
+        remove_key(&mut value, "missing.key");
+        assert_eq!(value, json!({ "database": { "host": "h" }, "api_key": null }));
+    }
+}
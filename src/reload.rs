@@ -0,0 +1,144 @@
+//! 可重载的配置句柄
+//!
+//! [`Reloadable`] 保存当前生效的配置，由 [`crate::watch::ConfigWatcher::reloadable`] 在每次成功重载后更新：
+//!
+//! - [`Reloadable::load`] 返回当前配置的 `Arc` 快照，开销很小，可以在每个请求中调用；
+//!   持有的快照不受之后重载的影响；
+//! - [`Reloadable::generation`] 是单调递增的代数，每次更新加一，便于判断缓存的派生数据是否过期；
+//! - [`Reloadable::subscribe`] 返回接收每次更新的通道，用于重建连接池等需要主动响应变更的场景。
+//!
+//! 句柄可以廉价克隆，所有克隆共享同一份配置。
+
+use arc_swap::ArcSwap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// 一次更新：新配置及其代数
+#[derive(Debug)]
+pub struct Reloaded<T> {
+    /// 更新后的代数
+    pub generation: u64,
+    /// 更新后的配置
+    pub config: Arc<T>,
+}
+
+impl<T> Clone for Reloaded<T> {
+    fn clone(&self) -> Self {
+        Self {
+            generation: self.generation,
+            config: Arc::clone(&self.config),
+        }
+    }
+}
+
+/// 可重载的配置句柄
+pub struct Reloadable<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// 各句柄共享的状态
+struct Shared<T> {
+    current: ArcSwap<T>,
+    generation: AtomicU64,
+    subscribers: Mutex<Vec<Sender<Reloaded<T>>>>,
+}
+
+impl<T> Reloadable<T> {
+    /// 以初始配置创建句柄，代数为 0
+    pub fn new(initial: T) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                current: ArcSwap::from_pointee(initial),
+                generation: AtomicU64::new(0),
+                subscribers: Mutex::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// 当前配置的快照
+    pub fn load(&self) -> Arc<T> {
+        self.shared.current.load_full()
+    }
+
+    /// 当前代数，每次 [`Reloadable::store`] 加一
+    pub fn generation(&self) -> u64 {
+        self.shared.generation.load(Ordering::Acquire)
+    }
+
+    /// 替换当前配置并通知订阅者，返回新的代数
+    ///
+    /// 接收端已丢弃的订阅会被移除。
+    pub fn store(&self, config: T) -> u64 {
+        let config = Arc::new(config);
+        // 持有订阅者锁完成替换与通知，保证订阅者按代数顺序收到更新
+        let mut subscribers = self.shared.subscribers.lock().unwrap_or_else(|e| e.into_inner());
+        self.shared.current.store(Arc::clone(&config));
+        let generation = self.shared.generation.fetch_add(1, Ordering::AcqRel) + 1;
+        let update = Reloaded { generation, config };
+        subscribers.retain(|subscriber| subscriber.send(update.clone()).is_ok());
+        generation
+    }
+
+    /// 订阅之后的每次更新
+    pub fn subscribe(&self) -> Receiver<Reloaded<T>> {
+        let (sender, receiver) = mpsc::channel();
+        self.shared
+            .subscribers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(sender);
+        receiver
+    }
+}
+
+impl<T> Clone for Reloadable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Reloadable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reloadable")
+            .field("generation", &self.generation())
+            .field("current", &self.load())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_updates_snapshot_and_generation() {
+        let config = Reloadable::new(8080u16);
+        let before = config.load();
+        assert_eq!(config.generation(), 0);
+
+        let handle = config.clone();
+        assert_eq!(handle.store(9090), 1);
+        assert_eq!(*config.load(), 9090);
+        assert_eq!(config.generation(), 1);
+        // 已取得的快照不受更新影响
+        assert_eq!(*before, 8080);
+    }
+
+    #[test]
+    fn test_subscribers_receive_updates_in_order() {
+        let config = Reloadable::new(String::from("a"));
+        let first = config.subscribe();
+        let second = config.subscribe();
+        drop(second);
+
+        config.store("b".to_string());
+        config.store("c".to_string());
+        let updates: Vec<_> = first.try_iter().map(|update| (update.generation, (*update.config).clone())).collect();
+        assert_eq!(updates, [(1, "b".to_string()), (2, "c".to_string())]);
+        assert_eq!(config.shared.subscribers.lock().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,524 @@
+//! 配置加载报告
+//!
+//! - [`LoadReport`]：[`crate::QuantumConfigBuilder::load_with_report`] 返回的加载报告，
+//!   按合并顺序记录每个来源的读取耗时与结果，用于排查启动阶段的延迟。
+//! - [`ConfigReport`]：[`crate::Config::load_with_report`] 返回的来源报告，
+//!   记录每个键的最终值由哪一层（默认值、系统级/用户级/`--config` 文件、环境变量、命令行参数）提供，
+//!   用于排查优先级问题；[`ConfigReport::to_mermaid`] 与 [`ConfigReport::to_dot`] 将合并顺序渲染为图。
+
+use crate::deprecated::DeprecationWarning;
+use crate::error::QuantumConfigError;
+use figment::{Figment, Metadata};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 单个来源的加载记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLoad {
+    /// 来源名称（取自 figment 提供器的元数据）
+    pub name: String,
+    /// 读取该来源的耗时
+    pub elapsed: Duration,
+    /// 是否读取成功
+    pub succeeded: bool,
+}
+
+/// 配置加载报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// 按合并顺序（低 -> 高优先级）排列的来源记录
+    pub sources: Vec<SourceLoad>,
+    /// 读取全部来源的总耗时
+    pub total: Duration,
+    /// 是否并行读取来源
+    pub parallel: bool,
+}
+
+impl LoadReport {
+    /// 耗时最长的来源
+    pub fn slowest(&self) -> Option<&SourceLoad> {
+        self.sources.iter().max_by_key(|source| source.elapsed)
+    }
+
+    /// 读取失败的来源
+    pub fn failed(&self) -> impl Iterator<Item = &SourceLoad> {
+        self.sources.iter().filter(|source| !source.succeeded)
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Loaded {} source(s) in {:?} ({})",
+            self.sources.len(),
+            self.total,
+            if self.parallel { "parallel" } else { "sequential" }
+        )?;
+        for source in &self.sources {
+            let status = if source.succeeded { "ok" } else { "failed" };
+            writeln!(f, "  {:>10?}  {:<6}  {}", source.elapsed, status, source.name)?;
+        }
+        Ok(())
+    }
+}
+
+/// 配置值的来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    /// 默认值（类型的 `Default` 实现或 `with_defaults` 提供的值）
+    Default,
+    /// 系统级配置文件
+    SystemFile(PathBuf),
+    /// 用户级配置文件
+    UserFile(PathBuf),
+    /// `--config` 或 `with_file` 指定的本地文件
+    ConfigFile(PathBuf),
+    /// 远程来源，值为来源名称
+    Remote(String),
+    /// `.env` 文件中的变量
+    Dotenv {
+        /// `.env` 文件路径
+        path: PathBuf,
+        /// 变量名
+        variable: String,
+    },
+    /// 环境变量，值为变量名
+    Env(String),
+    /// 命令行参数，值为参数名（例如 `--port`）
+    Cli(String),
+    /// 无法归类的来源，值为提供器名称
+    Other(String),
+}
+
+impl ValueSource {
+    /// 提供该值的层的名称，同一层的不同变量或参数属于同一层
+    fn layer_label(&self) -> String {
+        match self {
+            ValueSource::Default => DEFAULT_LAYER.to_string(),
+            ValueSource::Dotenv { path, .. } => format!(".env file {}", path.display()),
+            ValueSource::Env(_) => "environment variables".to_string(),
+            ValueSource::Cli(_) => "command-line arguments".to_string(),
+            other => other.to_string(),
+        }
+    }
+
+    /// 值在来源中的原始名称（环境变量名或命令行参数）
+    fn origin_name(&self) -> Option<&str> {
+        match self {
+            ValueSource::Dotenv { variable, .. } | ValueSource::Env(variable) | ValueSource::Cli(variable) => {
+                Some(variable)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::Default => write!(f, "default"),
+            ValueSource::SystemFile(path) => write!(f, "system file {}", path.display()),
+            ValueSource::UserFile(path) => write!(f, "user file {}", path.display()),
+            ValueSource::ConfigFile(path) => write!(f, "config file {}", path.display()),
+            ValueSource::Remote(name) => write!(f, "remote source {}", name),
+            ValueSource::Dotenv { path, variable } => write!(f, "{} in {}", variable, path.display()),
+            ValueSource::Env(variable) => write!(f, "environment variable {}", variable),
+            ValueSource::Cli(arg) => write!(f, "command-line argument {}", arg),
+            ValueSource::Other(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// 单个键的来源
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySource {
+    /// 以 `.` 分隔的键路径
+    pub key: String,
+    /// 提供最终值的来源
+    pub source: ValueSource,
+}
+
+/// 配置来源报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigReport {
+    /// 按键路径排序的来源记录
+    pub keys: Vec<KeySource>,
+    /// 按合并顺序（低 -> 高优先级）排列的各层名称，首层始终为 `default`
+    pub layers: Vec<String>,
+    /// 由默认值以外的来源提供的已弃用键，参见 [`crate::deprecated`]
+    pub deprecations: Vec<DeprecationWarning>,
+}
+
+impl ConfigReport {
+    /// 查询键的来源
+    pub fn source(&self, key: &str) -> Option<&ValueSource> {
+        self.keys.iter().find(|entry| entry.key == key).map(|entry| &entry.source)
+    }
+
+    /// 覆盖了默认值的键
+    pub fn overridden(&self) -> impl Iterator<Item = &KeySource> {
+        self.keys.iter().filter(|entry| entry.source != ValueSource::Default)
+    }
+
+    /// 以 Mermaid 流程图描述合并顺序，以及每个键的最终值由哪一层提供
+    ///
+    /// 层按优先级从左到右以粗箭头相连；键连到提供其值的层，环境变量、命令行参数等以原始名称作为边的标签。
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        let layers = self.graph_layers();
+        for (i, layer) in layers.iter().enumerate() {
+            out.push_str(&format!("    L{}[\"{}\"]\n", i, mermaid_escape(layer)));
+        }
+        for i in 1..layers.len() {
+            out.push_str(&format!("    L{} ==> L{}\n", i - 1, i));
+        }
+        for (i, (entry, layer)) in self.graph_edges(&layers).into_iter().enumerate() {
+            out.push_str(&format!("    K{}([\"{}\"])\n", i, mermaid_escape(&entry.key)));
+            match entry.source.origin_name() {
+                Some(name) => out.push_str(&format!("    L{} -->|\"{}\"| K{}\n", layer, mermaid_escape(name), i)),
+                None => out.push_str(&format!("    L{} --> K{}\n", layer, i)),
+            }
+        }
+        out
+    }
+
+    /// 以 Graphviz DOT 描述合并顺序，以及每个键的最终值由哪一层提供，内容与 [`ConfigReport::to_mermaid`] 一致
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph config {\n    rankdir=LR;\n    node [shape=box];\n");
+        let layers = self.graph_layers();
+        for (i, layer) in layers.iter().enumerate() {
+            out.push_str(&format!("    layer{} [label=\"{}\"];\n", i, dot_escape(layer)));
+        }
+        for i in 1..layers.len() {
+            out.push_str(&format!("    layer{} -> layer{} [style=bold];\n", i - 1, i));
+        }
+        for (i, (entry, layer)) in self.graph_edges(&layers).into_iter().enumerate() {
+            out.push_str(&format!("    key{} [label=\"{}\", shape=ellipse];\n", i, dot_escape(&entry.key)));
+            match entry.source.origin_name() {
+                Some(name) => out.push_str(&format!("    layer{} -> key{} [label=\"{}\"];\n", layer, i, dot_escape(name))),
+                None => out.push_str(&format!("    layer{} -> key{};\n", layer, i)),
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// 图中的层：报告记录的各层，加上只出现在键来源中的层（例如手工构造的报告）
+    fn graph_layers(&self) -> Vec<String> {
+        let mut layers = self.layers.clone();
+        if layers.first().map(String::as_str) != Some(DEFAULT_LAYER) {
+            layers.insert(0, DEFAULT_LAYER.to_string());
+        }
+        for entry in &self.keys {
+            let label = entry.source.layer_label();
+            if !layers.contains(&label) {
+                layers.push(label);
+            }
+        }
+        layers
+    }
+
+    /// 每个键与提供其值的层的下标
+    fn graph_edges<'a>(&'a self, layers: &[String]) -> Vec<(&'a KeySource, usize)> {
+        self.keys
+            .iter()
+            .map(|entry| {
+                let label = entry.source.layer_label();
+                (entry, layers.iter().position(|layer| *layer == label).unwrap_or(0))
+            })
+            .collect()
+    }
+}
+
+/// 默认值层的名称
+const DEFAULT_LAYER: &str = "default";
+
+/// 转义 Mermaid 标签中的双引号
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+/// 转义 DOT 标签中的反斜杠与双引号
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.keys.iter().map(|entry| entry.key.len()).max().unwrap_or(0);
+        for entry in &self.keys {
+            writeln!(f, "{:<width$}  <- {}", entry.key, entry.source, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+/// 合并层的类别
+#[derive(Debug, Clone)]
+pub(crate) enum LayerKind {
+    Default,
+    SystemFile(PathBuf),
+    UserFile(PathBuf),
+    ConfigFile(PathBuf),
+    Remote,
+    Dotenv(PathBuf),
+    Env,
+    #[cfg_attr(not(feature = "cli"), allow(dead_code))]
+    Cli,
+}
+
+/// 合并各层时记录的来源信息，用于为提取出的配置生成 [`ConfigReport`]
+#[derive(Debug, Clone)]
+pub struct ConfigSources {
+    /// 应用值转换之前的合并结果，值转换不改变来源
+    figment: Figment,
+    /// 按合并顺序排列的各层元数据与类别
+    layers: Vec<(Metadata, LayerKind)>,
+}
+
+impl ConfigSources {
+    pub(crate) fn new(figment: Figment, layers: Vec<(Metadata, LayerKind)>) -> Self {
+        Self { figment, layers }
+    }
+
+    /// 查询键的最终值来源，未被任何层提供的键视为默认值
+    pub fn source(&self, key: &str) -> ValueSource {
+        let Some(metadata) = self.figment.find_metadata(key) else {
+            return ValueSource::Default;
+        };
+        let Some((_, kind)) = self.layers.iter().rev().find(|(layer, _)| layer == metadata) else {
+            return ValueSource::Other(metadata.name.to_string());
+        };
+        let keys: Vec<&str> = key.split('.').collect();
+        let origin_key = || metadata.interpolate(self.figment.profile(), &keys);
+        match kind {
+            LayerKind::Default => ValueSource::Default,
+            LayerKind::SystemFile(path) => ValueSource::SystemFile(path.clone()),
+            LayerKind::UserFile(path) => ValueSource::UserFile(path.clone()),
+            LayerKind::ConfigFile(path) => ValueSource::ConfigFile(path.clone()),
+            LayerKind::Remote => ValueSource::Remote(metadata.name.to_string()),
+            LayerKind::Dotenv(path) => ValueSource::Dotenv {
+                path: path.clone(),
+                variable: origin_key(),
+            },
+            LayerKind::Env => ValueSource::Env(origin_key()),
+            LayerKind::Cli => ValueSource::Cli(origin_key()),
+        }
+    }
+
+    /// 键本身或其下任一子键由默认值以外的来源提供时，返回（该键路径，来源）
+    pub fn provided_source(&self, key: &str) -> Option<(String, ValueSource)> {
+        self.provided_sources(key).next()
+    }
+
+    /// 键本身及其下各子键中由默认值以外的来源提供的（键路径，来源）
+    pub fn provided_sources(&self, key: &str) -> impl Iterator<Item = (String, ValueSource)> + '_ {
+        let mut keys = Vec::new();
+        if let Some(value) = self.figment.find_value(key).ok().and_then(|value| value.deserialize::<JsonValue>().ok()) {
+            collect_keys(&value, &mut key.to_string(), &mut keys);
+            if keys.is_empty() {
+                keys.push(key.to_string());
+            }
+        }
+        keys.into_iter().filter_map(|key| match self.source(&key) {
+            ValueSource::Default => None,
+            source => Some((key, source)),
+        })
+    }
+
+    /// 按合并顺序排列的各层名称，与 [`ValueSource`] 的层名称一致
+    fn layer_labels(&self) -> Vec<String> {
+        let mut labels = vec![DEFAULT_LAYER.to_string()];
+        for (metadata, kind) in &self.layers {
+            let source = match kind {
+                LayerKind::Default => continue,
+                LayerKind::SystemFile(path) => ValueSource::SystemFile(path.clone()),
+                LayerKind::UserFile(path) => ValueSource::UserFile(path.clone()),
+                LayerKind::ConfigFile(path) => ValueSource::ConfigFile(path.clone()),
+                LayerKind::Remote => ValueSource::Remote(metadata.name.to_string()),
+                LayerKind::Dotenv(path) => ValueSource::Dotenv {
+                    path: path.clone(),
+                    variable: String::new(),
+                },
+                LayerKind::Env => ValueSource::Env(String::new()),
+                LayerKind::Cli => ValueSource::Cli(String::new()),
+            };
+            labels.push(source.layer_label());
+        }
+        labels
+    }
+
+    /// 键在各环境变量层（含 `.env` 文件）中对应的变量名，按合并顺序去重
+    pub fn env_var_names(&self, key: &str) -> Vec<String> {
+        let keys: Vec<&str> = key.split('.').collect();
+        let mut names: Vec<String> = Vec::new();
+        for (metadata, kind) in &self.layers {
+            if matches!(kind, LayerKind::Env | LayerKind::Dotenv(_)) {
+                let name = metadata.interpolate(self.figment.profile(), &keys);
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+    }
+
+    /// 除默认值外是否有来源提供了值，例如找到了配置文件或设置了对应的环境变量
+    pub fn has_values(&self) -> bool {
+        let Ok(value) = self.figment.extract::<JsonValue>() else {
+            return true;
+        };
+        let mut keys = Vec::new();
+        collect_keys(&value, &mut String::new(), &mut keys);
+        keys.iter().any(|key| self.source(key) != ValueSource::Default)
+    }
+
+    /// 按合并顺序列出参与合并的来源（不含默认值），用于在错误信息中说明查找过的位置
+    ///
+    /// 文件与 `.env` 文件列出路径，环境变量等其余来源使用提供器名称（包含环境变量前缀）。
+    pub fn searched_locations(&self) -> Vec<String> {
+        self.layers
+            .iter()
+            .filter_map(|(metadata, kind)| match kind {
+                LayerKind::Default => None,
+                LayerKind::SystemFile(path) => Some(ValueSource::SystemFile(path.clone()).to_string()),
+                LayerKind::UserFile(path) => Some(ValueSource::UserFile(path.clone()).to_string()),
+                LayerKind::ConfigFile(path) => Some(ValueSource::ConfigFile(path.clone()).to_string()),
+                LayerKind::Dotenv(path) => Some(format!(".env file {}", path.display())),
+                LayerKind::Remote | LayerKind::Env | LayerKind::Cli => Some(metadata.name.to_string()),
+            })
+            .collect()
+    }
+
+    /// 为提取出的配置生成来源报告，覆盖序列化结果中的每个叶子键（数组视为整体）
+    pub fn report<T: Serialize>(&self, config: &T) -> Result<ConfigReport, QuantumConfigError> {
+        let value = serde_json::to_value(config).map_err(|e| {
+            QuantumConfigError::Internal(format!("Failed to serialize configuration for source report: {}", e))
+        })?;
+        let mut keys = Vec::new();
+        collect_keys(&value, &mut String::new(), &mut keys);
+        keys.sort();
+        Ok(ConfigReport {
+            keys: keys
+                .into_iter()
+                .map(|key| KeySource {
+                    source: self.source(&key),
+                    key,
+                })
+                .collect(),
+            layers: self.layer_labels(),
+            deprecations: Vec::new(),
+        })
+    }
+}
+
+/// 收集 JSON 值中的叶子键路径
+fn collect_keys(value: &JsonValue, prefix: &mut String, keys: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let len = prefix.len();
+                if !prefix.is_empty() {
+                    prefix.push('.');
+                }
+                prefix.push_str(key);
+                collect_keys(child, prefix, keys);
+                prefix.truncate(len);
+            }
+        }
+        _ if !prefix.is_empty() => keys.push(prefix.clone()),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_summaries() {
+        let report = LoadReport {
+            sources: vec![
+                SourceLoad { name: "file".to_string(), elapsed: Duration::from_millis(2), succeeded: true },
+                SourceLoad { name: "remote".to_string(), elapsed: Duration::from_millis(40), succeeded: false },
+            ],
+            total: Duration::from_millis(41),
+            parallel: true,
+        };
+
+        assert_eq!(report.slowest().map(|s| s.name.as_str()), Some("remote"));
+        assert_eq!(report.failed().count(), 1);
+
+        let rendered = report.to_string();
+        assert!(rendered.contains("Loaded 2 source(s)"));
+        assert!(rendered.contains("parallel"));
+        assert!(rendered.contains("failed"));
+    }
+
+    #[test]
+    fn test_config_sources_cover_nested_leaves() {
+        use figment::providers::Serialized;
+        use figment::Provider;
+
+        let file = Serialized::defaults(serde_json::json!({"database": {"host": "db"}}));
+        let figment = Figment::new().merge(file.clone());
+        let sources = ConfigSources::new(figment, vec![(file.metadata(), LayerKind::ConfigFile("app.toml".into()))]);
+
+        let config = serde_json::json!({
+            "database": {"host": "db", "port": 5432},
+            "tags": ["a", "b"],
+            "extra": {},
+        });
+        let report = sources.report(&config).unwrap();
+        let keys: Vec<_> = report.keys.iter().map(|entry| entry.key.as_str()).collect();
+        assert_eq!(keys, ["database.host", "database.port", "extra", "tags"]);
+        assert_eq!(report.source("database.host"), Some(&ValueSource::ConfigFile("app.toml".into())));
+        assert_eq!(report.source("database.port"), Some(&ValueSource::Default));
+        assert_eq!(report.overridden().count(), 1);
+        assert!(report.to_string().contains("database.host  <- config file app.toml"));
+    }
+
+    #[test]
+    fn test_report_renders_mermaid_and_dot() {
+        let report = ConfigReport {
+            keys: vec![
+                KeySource { key: "host".to_string(), source: ValueSource::ConfigFile("app.toml".into()) },
+                KeySource { key: "log_level".to_string(), source: ValueSource::Default },
+                KeySource { key: "port".to_string(), source: ValueSource::Env("APP_PORT".to_string()) },
+            ],
+            layers: vec![
+                "default".to_string(),
+                "config file app.toml".to_string(),
+                "environment variables".to_string(),
+                "command-line arguments".to_string(),
+            ],
+            deprecations: Vec::new(),
+        };
+
+        let mermaid = report.to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n    L0[\"default\"]\n"));
+        assert!(mermaid.contains("    L2 ==> L3\n"));
+        assert!(mermaid.contains("    K0([\"host\"])\n    L1 --> K0\n"));
+        assert!(mermaid.contains("    L2 -->|\"APP_PORT\"| K2\n"));
+
+        let dot = report.to_dot();
+        assert!(dot.starts_with("digraph config {\n"));
+        assert!(dot.contains("    layer3 [label=\"command-line arguments\"];\n"));
+        assert!(dot.contains("    layer0 -> key1;\n"));
+        assert!(dot.contains("    layer2 -> key2 [label=\"APP_PORT\"];\n"));
+        assert!(dot.ends_with("}\n"));
+
+        // 手工构造、未记录各层的报告从键来源推导层
+        let report = ConfigReport {
+            keys: vec![KeySource { key: "a\"b".to_string(), source: ValueSource::Cli("--a".to_string()) }],
+            layers: Vec::new(),
+            deprecations: Vec::new(),
+        };
+        assert!(report.to_dot().contains("key0 [label=\"a\\\"b\", shape=ellipse]"));
+        assert!(report.to_mermaid().contains("L1[\"command-line arguments\"]"));
+    }
+}
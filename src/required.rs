@@ -0,0 +1,204 @@
+//! 必需键检查
+//!
+//! serde 在遇到第一个缺失字段时即停止，figment 因此每次只报告一个缺失的键，
+//! 运维人员需要反复修改、重启才能补齐配置。提取失败且原因是缺少字段时，
+//! 本模块用默认值中同一路径的值暂时填补缺失的键并重新提取，直到不再缺少字段，
+//! 从而一次性以 [`QuantumConfigError::MissingKeys`] 报告全部缺失的键以及查找过的来源。
+//!
+//! 填补的值只用于继续提取以发现后续缺失的键，不会出现在加载结果中。
+//!
+//! 对每个缺失的键，还会在当前环境中查找与其期望变量名（如 `APP_DATABASE__PORT`）
+//! 编辑距离很小的环境变量，在错误信息中提示“是否想写”，便于发现拼错的变量名。
+//! 命令行参数的拼写错误由 clap 在解析参数时提示。
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::report::ConfigSources;
+use crate::unknown;
+use figment::providers::Serialized;
+use figment::Figment;
+use serde::de::DeserializeOwned;
+use serde_json::Value as JsonValue;
+
+/// 单次检查最多报告的缺失键数量
+const MAX_MISSING_KEYS: usize = 256;
+
+/// 视为疑似拼写错误的最大编辑距离
+const MAX_TYPO_DISTANCE: usize = 2;
+
+/// 将提取错误转换为 [`QuantumConfigError`]，缺少字段时收集全部缺失的必需键
+///
+/// 其余错误与 `QuantumConfigError::from(error)` 相同。
+pub fn explain_extract_error<T: Config>(
+    figment: &Figment,
+    error: figment::Error,
+    sources: &ConfigSources,
+) -> QuantumConfigError {
+    let Some(first) = missing_key(&error) else {
+        return error.into();
+    };
+    let shape = T::defaults_as_value().unwrap_or(JsonValue::Null);
+    let keys = collect_missing_keys::<T>(figment, &shape, first);
+    // 缺失的是嵌套结构时，按其下的叶子键查找，例如 `database` -> `database.port`
+    let mut leaf_keys = Vec::new();
+    for key in &keys {
+        collect_leaf_keys(lookup(&shape, key).unwrap_or(&JsonValue::Null), key.clone(), &mut leaf_keys);
+    }
+    let env_names: Vec<String> = crate::context::env_source().vars().into_iter().map(|(name, _)| name).collect();
+    let hints = env_typo_hints(&leaf_keys, sources, &env_names);
+    QuantumConfigError::MissingKeys {
+        keys,
+        searched: sources.searched_locations(),
+        hints,
+    }
+}
+
+/// 为缺失的键查找疑似拼错的环境变量，返回形如
+/// `environment variable APP_PROT is set, did you mean APP_PORT?` 的提示
+fn env_typo_hints(keys: &[String], sources: &ConfigSources, env_names: &[String]) -> Vec<String> {
+    let mut hints = Vec::new();
+    for key in keys {
+        for expected in sources.env_var_names(key) {
+            let closest = env_names
+                .iter()
+                .filter(|name| **name != expected)
+                .map(|name| (unknown::edit_distance(&name.to_uppercase(), &expected.to_uppercase()), name))
+                .filter(|(distance, _)| *distance <= MAX_TYPO_DISTANCE)
+                .min_by_key(|(distance, _)| *distance);
+            if let Some((_, name)) = closest {
+                let hint = format!("environment variable {} is set, did you mean {}?", name, expected);
+                if !hints.contains(&hint) {
+                    hints.push(hint);
+                }
+            }
+        }
+    }
+    hints
+}
+
+/// 缺少字段错误对应的键路径，其余错误返回 `None`
+fn missing_key(error: &figment::Error) -> Option<String> {
+    match &error.kind {
+        figment::error::Kind::MissingField(field) => Some(
+            error
+                .path
+                .iter()
+                .map(String::as_str)
+                .chain(std::iter::once(field.as_ref()))
+                .collect::<Vec<_>>()
+                .join("."),
+        ),
+        _ => None,
+    }
+}
+
+/// 从 `first` 开始逐个填补缺失的键并重新提取，按键路径排序返回全部缺失的键
+///
+/// `shape` 提供填补值（通常为默认值的序列化结果）；某个键在其中没有值、
+/// 或填补后出现其他错误时停止，返回已经发现的键。
+fn collect_missing_keys<T: DeserializeOwned>(figment: &Figment, shape: &JsonValue, first: String) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut figment = figment.clone();
+    let mut next = Some(first);
+    while let Some(key) = next.take() {
+        if keys.contains(&key) || keys.len() >= MAX_MISSING_KEYS {
+            break;
+        }
+        let placeholder = lookup(shape, &key).filter(|value| !value.is_null()).cloned();
+        keys.push(key.clone());
+        let Some(placeholder) = placeholder else {
+            break;
+        };
+        figment = figment.merge(Serialized::default(&key, placeholder));
+        if let Err(error) = figment.extract::<T>() {
+            next = missing_key(&error);
+        }
+    }
+    keys.sort();
+    keys
+}
+
+/// 收集 `value` 下的全部叶子键路径，`value` 不是映射时即为 `path` 本身
+fn collect_leaf_keys(value: &JsonValue, path: String, keys: &mut Vec<String>) {
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                collect_leaf_keys(child, format!("{}.{}", path, key), keys);
+            }
+        }
+        _ => keys.push(path),
+    }
+}
+
+/// 按以 `.` 分隔的键路径查找 JSON 值
+fn lookup<'a>(value: &'a JsonValue, key: &str) -> Option<&'a JsonValue> {
+    key.split('.').try_fold(value, |value, segment| value.get(segment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct Database {
+        url: String,
+        pool_size: u32,
+    }
+
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct AppConfig {
+        name: String,
+        token: Option<String>,
+        #[serde(default)]
+        workers: u32,
+        port: u16,
+        database: Database,
+    }
+
+    #[test]
+    fn test_collects_every_missing_key() {
+        let figment = Figment::from(Serialized::defaults(json!({ "database": { "pool_size": 4 } })));
+        let error = figment.extract::<AppConfig>().unwrap_err();
+        let first = missing_key(&error).unwrap();
+        let shape = serde_json::to_value(AppConfig::default()).unwrap();
+
+        let keys = collect_missing_keys::<AppConfig>(&figment, &shape, first);
+        assert_eq!(keys, vec!["database.url", "name", "port"]);
+    }
+
+    #[test]
+    fn test_env_typo_hints_suggest_expected_names() {
+        use crate::providers::QuantumConfigEnvProvider;
+        use crate::report::LayerKind;
+        use figment::Provider;
+
+        let env = QuantumConfigEnvProvider::new("APP_", "__", true, true);
+        let sources = ConfigSources::new(Figment::new(), vec![(env.metadata(), LayerKind::Env)]);
+        let keys = vec!["database.url".to_string(), "port".to_string(), "name".to_string()];
+        let env_names = vec![
+            "APP_DATABSE__URL".to_string(),
+            "APP_PROT".to_string(),
+            "HOME".to_string(),
+            "APP_NAME".to_string(),
+        ];
+
+        let hints = env_typo_hints(&keys, &sources, &env_names);
+        assert_eq!(
+            hints,
+            vec![
+                "environment variable APP_DATABSE__URL is set, did you mean APP_DATABASE__URL?",
+                "environment variable APP_PROT is set, did you mean APP_PORT?",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stops_when_no_placeholder_is_available() {
+        let figment = Figment::new();
+        let error = figment.extract::<AppConfig>().unwrap_err();
+        let keys = collect_missing_keys::<AppConfig>(&figment, &JsonValue::Null, missing_key(&error).unwrap());
+        assert_eq!(keys, vec!["name"]);
+    }
+}
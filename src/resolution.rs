@@ -0,0 +1,171 @@
+//! 配置来源解析上下文
+//!
+//! 包含（include）、继承（extends）与远程引用都会让一个来源引入其他来源。
+//! [`ResolutionContext`] 集中处理这类递归解析的两类保护：
+//! - 循环引用：记录当前的引用链，再次进入链上已有的来源时返回
+//!   [`QuantumConfigError::SourceCycle`]，错误信息形如 `a.toml → b.toml → a.toml`
+//! - 来源总数：整个加载过程中进入的来源数量超过上限时返回 [`QuantumConfigError::TooManySources`]
+//! - 嵌套层数：引用链长度超过上限时返回 [`QuantumConfigError::SourceDepthExceeded`]
+
+use crate::error::QuantumConfigError;
+use std::path::Path;
+
+/// 默认允许加载的来源总数
+pub const DEFAULT_MAX_SOURCES: usize = 64;
+
+/// 默认允许的来源嵌套层数（顶层来源计为第一层）
+pub const DEFAULT_MAX_DEPTH: usize = 8;
+
+/// 来源解析上下文
+#[derive(Debug, Clone)]
+pub struct ResolutionContext {
+    /// 从顶层到当前来源的引用链
+    chain: Vec<String>,
+    /// 已进入的来源总数
+    loaded: usize,
+    /// 允许加载的来源总数
+    max_sources: usize,
+    /// 允许的来源嵌套层数
+    max_depth: usize,
+}
+
+impl Default for ResolutionContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResolutionContext {
+    /// 创建上下文，来源总数上限为 [`DEFAULT_MAX_SOURCES`]，嵌套层数上限为 [`DEFAULT_MAX_DEPTH`]
+    pub fn new() -> Self {
+        Self {
+            chain: Vec::new(),
+            loaded: 0,
+            max_sources: DEFAULT_MAX_SOURCES,
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// 设置允许加载的来源总数
+    pub fn with_max_sources(mut self, max_sources: usize) -> Self {
+        self.max_sources = max_sources;
+        self
+    }
+
+    /// 设置允许的来源嵌套层数
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// 进入来源并在其中执行 `resolve`，返回后离开该来源
+    ///
+    /// `source` 为来源的唯一标识，本地文件应使用 [`ResolutionContext::source_id`] 以便不同写法的同一路径被识别为同一来源。
+    /// `resolve` 中解析到的嵌套来源应继续通过传入的上下文调用本方法。
+    pub fn resolve<T, F>(&mut self, source: impl Into<String>, resolve: F) -> Result<T, QuantumConfigError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, QuantumConfigError>,
+    {
+        let source = source.into();
+        if self.chain.contains(&source) {
+            return Err(QuantumConfigError::SourceCycle { chain: self.chain_with(source) });
+        }
+        if self.chain.len() >= self.max_depth {
+            return Err(QuantumConfigError::SourceDepthExceeded {
+                limit: self.max_depth,
+                chain: self.chain_with(source),
+            });
+        }
+        if self.loaded >= self.max_sources {
+            return Err(QuantumConfigError::TooManySources {
+                limit: self.max_sources,
+                chain: self.chain_with(source),
+            });
+        }
+        self.loaded += 1;
+        self.chain.push(source);
+        let result = resolve(self);
+        self.chain.pop();
+        result
+    }
+
+    /// 当前的引用链
+    pub fn chain(&self) -> &[String] {
+        &self.chain
+    }
+
+    /// 已进入的来源总数
+    pub fn loaded(&self) -> usize {
+        self.loaded
+    }
+
+    /// 本地文件的来源标识：文件存在时使用规范化后的绝对路径
+    pub fn source_id(path: &Path) -> String {
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf()).display().to_string()
+    }
+
+    fn chain_with(&self, source: String) -> Vec<String> {
+        let mut chain = self.chain.clone();
+        chain.push(source);
+        chain
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_reports_full_chain() {
+        let mut context = ResolutionContext::new();
+        let error = context
+            .resolve("a.toml", |context| {
+                context.resolve("b.toml", |context| context.resolve("a.toml", |_| Ok(())))
+            })
+            .unwrap_err();
+
+        assert_eq!(error.code(), "source_cycle");
+        assert_eq!(error.to_string(), "Configuration source cycle detected: a.toml → b.toml → a.toml");
+        // 离开来源后引用链恢复，兄弟来源可以重复出现
+        assert!(context.chain().is_empty());
+        context.resolve("a.toml", |_| Ok(())).unwrap();
+        assert_eq!(context.loaded(), 3);
+    }
+
+    #[test]
+    fn test_total_sources_are_capped() {
+        let mut context = ResolutionContext::new().with_max_sources(2);
+        let error = context
+            .resolve("root.toml", |context| {
+                for i in 0..3 {
+                    context.resolve(format!("part{}.toml", i), |_| Ok(()))?;
+                }
+                Ok(())
+            })
+            .unwrap_err();
+
+        match error {
+            QuantumConfigError::TooManySources { limit, chain } => {
+                assert_eq!(limit, 2);
+                assert_eq!(chain, ["root.toml", "part1.toml"]);
+            }
+            other => panic!("Expected TooManySources, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_nesting_depth_is_capped() {
+        let mut context = ResolutionContext::new().with_max_depth(2);
+        context
+            .resolve("a.toml", |context| context.resolve("b.toml", |_| Ok(())))
+            .unwrap();
+        let error = context
+            .resolve("a.toml", |context| {
+                context.resolve("b.toml", |context| context.resolve("c.toml", |_| Ok(())))
+            })
+            .unwrap_err();
+
+        assert_eq!(error.code(), "source_depth_exceeded");
+        assert_eq!(error.to_string(), "Configuration sources nested too deeply (limit 2): a.toml → b.toml → c.toml");
+    }
+}
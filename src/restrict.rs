@@ -0,0 +1,130 @@
+//! 字段级来源限制
+//!
+//! 字段上的 `#[config(sources(env, file))]` 限制该字段的值只能来自列出的来源类别（`file`、`remote`、`env`、`cli`），
+//! `#[config(no_cli)]` 允许除命令行参数以外的全部类别，适合密码等不应出现在 `ps` 输出中的值：
+//!
+//! ```ignore
+//! #[derive(Config, Serialize, Deserialize, Default)]
+//! struct AppConfig {
+//!     #[config(secret, no_cli)]
+//!     password: String,
+//!     #[config(sources(file))]
+//!     tls_cert_path: PathBuf,
+//! }
+//! ```
+//!
+//! - 不允许命令行参数的字段不生成对应的参数；
+//! - 合并全部来源之后、提取之前，键（或其任一子键）的最终值来自不允许的来源时，加载以
+//!   [`QuantumConfigError::SourceNotAllowed`] 失败，错误说明提供该值的变量、参数或文件，不包含值本身。
+//!
+//! 嵌套结构体字段上的限制需要在外层字段上标注 `#[config(nested)]`（嵌套类型同样派生 `Config`），键路径以外层字段的键为前缀。
+//!
+//! 默认值不受限制，`.env` 文件属于 `env` 类别；无法归类的来源（例如只有提供器名称的自定义层）不做检查。
+
+use crate::builder::SourceKind;
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::meta::StructMeta;
+use crate::report::{ConfigSources, ValueSource};
+
+/// 检查限制了来源的键（键路径，允许的来源类别），返回第一个违反限制的键
+pub fn check(restrictions: &[(String, &[SourceKind])], sources: &ConfigSources) -> Result<(), QuantumConfigError> {
+    for (key, allowed) in restrictions {
+        for (key_path, source) in sources.provided_sources(key) {
+            if source_kind(&source).is_some_and(|kind| !allowed.contains(&kind)) {
+                return Err(QuantumConfigError::SourceNotAllowed {
+                    key_path,
+                    origin: source.to_string(),
+                    allowed: allowed.iter().map(|kind| kind_name(*kind)).collect::<Vec<_>>().join(", "),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 结构体字段上声明的来源限制（键路径，允许的来源类别）
+pub fn field_restrictions(meta: &StructMeta) -> Vec<(String, &'static [SourceKind])> {
+    meta.restricted_fields()
+        .filter_map(|field| Some((field.config_key_name().to_string(), field.allowed_sources?)))
+        .collect()
+}
+
+/// 嵌套结构体 `T` 的来源限制，键路径加上外层字段的键 `prefix`（`#[serde(flatten)]` 字段为空）
+pub fn nested_restrictions<T: Config>(prefix: &str) -> Vec<(String, &'static [SourceKind])> {
+    T::source_restrictions()
+        .into_iter()
+        .map(|(key, allowed)| (crate::meta::nested_key_path(prefix, &key), allowed))
+        .collect()
+}
+
+/// 键 `key` 是否受 `restricted` 的限制，即两者相同或 `key` 是其子键
+#[cfg(feature = "cli")]
+pub(crate) fn covers(restricted: &str, key: &str) -> bool {
+    key.strip_prefix(restricted).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// 检查 `T` 的来源限制（[`Config::source_restrictions`]）
+pub fn enforce<T: Config>(sources: &ConfigSources) -> Result<(), QuantumConfigError> {
+    check(&T::source_restrictions(), sources)
+}
+
+/// 值来源所属的类别，默认值与无法归类的来源返回 `None`
+fn source_kind(source: &ValueSource) -> Option<SourceKind> {
+    match source {
+        ValueSource::SystemFile(_) | ValueSource::UserFile(_) | ValueSource::ConfigFile(_) => Some(SourceKind::File),
+        ValueSource::Remote(_) => Some(SourceKind::Remote),
+        ValueSource::Dotenv { .. } | ValueSource::Env(_) => Some(SourceKind::Env),
+        ValueSource::Cli(_) => Some(SourceKind::Cli),
+        ValueSource::Default | ValueSource::Other(_) => None,
+    }
+}
+
+/// 来源类别在属性与错误信息中使用的名称
+fn kind_name(kind: SourceKind) -> &'static str {
+    match kind {
+        SourceKind::File => "file",
+        SourceKind::Remote => "remote",
+        SourceKind::Env => "env",
+        SourceKind::Cli => "cli",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::QuantumConfigBuilder;
+    use crate::providers::MapEnv;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_rejects_values_from_disallowed_sources() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("app.toml");
+        fs::write(&path, "host = \"file\"\n\n[database]\nurl = \"postgres://db\"\n").unwrap();
+        let (_, sources) = QuantumConfigBuilder::<serde_json::Value>::new("qc-restrict-test")
+            .with_default_paths(false)
+            .with_defaults(serde_json::json!({ "password": "", "database": { "url": "", "password": "" } }))
+            .with_file(&path)
+            .with_env_prefix("QC_RESTRICT_")
+            .with_env_source(MapEnv::new().with_var("QC_RESTRICT_DATABASE__PASSWORD", "hunter2"))
+            .figment_with_sources()
+            .unwrap();
+
+        let env_or_file: &[SourceKind] = &[SourceKind::Env, SourceKind::File];
+        let file_only: &[SourceKind] = &[SourceKind::File];
+        // 只来自默认值的键不受限制
+        let allowed = [("host".to_string(), env_or_file), ("password".to_string(), file_only)];
+        assert!(check(&allowed, &sources).is_ok());
+
+        let error = check(&[("database".to_string(), file_only)], &sources).unwrap_err();
+        assert_eq!(error.code(), "source_not_allowed");
+        assert_eq!(
+            error.to_string(),
+            "Configuration key 'database.password' must not be set by environment variable QC_RESTRICT_DATABASE__PASSWORD; \
+             allowed sources: file"
+        );
+        assert!(!error.to_string().contains("hunter2"));
+    }
+}
@@ -0,0 +1,606 @@
+//! Quantum Config 敏感值擦除模块
+//!
+//! 启用 `secret-scrub` 特性后，加载配置时标注了 `#[config(secret)]` 的字段值，以及远程密钥服务、
+//! 密钥文件与加密值等来源读取到的明文会被登记到注册表中，[`scrub`] 把文本中的这些值替换为 `[REDACTED]`。
+//! 未启用该特性时不登记任何值，[`scrub`] 原样返回文本。
+//!
+//! 注册表按作用域保存：每个配置类型的敏感字段占一个作用域，每次加载（包括热重载）都会整体替换，
+//! 旧值随之移除；来源读取到的值以来源（例如密钥名称）为作用域，重新读取时同样被替换。
+//! 通过 [`register_secret`] 直接登记的值最多保留 [`MAX_UNSCOPED_SECRETS`] 个，超出时移除最早登记的值。
+//! 注册表中的副本使用 [`Zeroizing`] 保存，被移除时内存同样会被清零。
+//!
+//! 另外可以通过 [`install_guard`] 安装全局防护：panic 信息中出现的敏感值会被替换为 `[REDACTED]`。
+//! 日志输出不会被自动擦除，需要显式包装输出端：同时启用 `tracing-support` 时使用 [`scrubbing_layer`]
+//! 或 [`ScrubMakeWriter`] 擦除 tracing 的输出；同时启用 `log-facade` 时使用 [`ScrubLogger`]
+//! 包装 `log` 门面的日志实现。
+
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+#[cfg(feature = "secret-scrub")]
+use std::collections::HashMap;
+#[cfg(feature = "secret-scrub")]
+use std::sync::{LazyLock, RwLock};
+use zeroize::{Zeroize, Zeroizing};
+
+/// 替换敏感值使用的占位符
+pub const REDACTED: &str = "[REDACTED]";
+
+/// 参与擦除的最小值长度
+///
+/// 过短的值（如 `"1"`、`"on"`）极易误伤普通文本，因此不予登记。
+pub const MIN_SECRET_LEN: usize = 4;
+
+/// 通过 [`register_secret`] 直接登记的值的数量上限
+pub const MAX_UNSCOPED_SECRETS: usize = 256;
+
+/// 直接登记的值所在的作用域
+#[cfg(feature = "secret-scrub")]
+const UNSCOPED: &str = "";
+
+/// 按作用域登记的敏感值
+#[cfg(feature = "secret-scrub")]
+static SECRETS: LazyLock<RwLock<HashMap<String, Vec<Zeroizing<String>>>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// 一组待登记的敏感值，参见 [`replace_secrets`]
+///
+/// 长度小于 [`MIN_SECRET_LEN`] 的值与重复值会被忽略；未启用 `secret-scrub` 特性时不收集任何值。
+#[derive(Default)]
+pub struct Secrets(Vec<Zeroizing<String>>);
+
+impl std::fmt::Debug for Secrets {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Secrets").field("len", &self.0.len()).finish()
+    }
+}
+
+impl Secrets {
+    /// 创建空集合
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加一个敏感值
+    pub fn add<S: Into<String>>(&mut self, value: S) {
+        let value = Zeroizing::new(value.into());
+        if cfg!(feature = "secret-scrub") && value.chars().count() >= MIN_SECRET_LEN && !self.0.contains(&value) {
+            self.0.push(value);
+        }
+    }
+
+    /// 添加一个可序列化字段中的全部敏感值
+    ///
+    /// 字符串与数字叶子节点都会被添加，适用于 `String`、`Option<String>`、`SecretString`、
+    /// 字符串列表以及嵌套结构等类型。序列化得到的明文直接移入集合，不会留下未清零的副本。
+    pub fn add_value<T: Serialize + ?Sized>(&mut self, value: &T) {
+        if !cfg!(feature = "secret-scrub") {
+            return;
+        }
+        if let Ok(mut json) = serde_json::to_value(value) {
+            self.add_json(&mut json);
+        }
+    }
+
+    /// 添加 JSON 值中的全部叶子节点，字符串被移入集合，原位置留下空字符串
+    pub(crate) fn add_json(&mut self, value: &mut JsonValue) {
+        match value {
+            JsonValue::String(s) => self.add(std::mem::take(s)),
+            JsonValue::Number(n) => self.add(n.to_string()),
+            JsonValue::Array(items) => items.iter_mut().for_each(|item| self.add_json(item)),
+            JsonValue::Object(map) => map.values_mut().for_each(|item| self.add_json(item)),
+            JsonValue::Null | JsonValue::Bool(_) => {}
+        }
+    }
+
+    /// 集合是否为空
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// 用 `secrets` 整体替换作用域 `scope` 中的敏感值，`secrets` 为空时移除该作用域
+pub fn replace_secrets(scope: &str, secrets: Secrets) {
+    #[cfg(feature = "secret-scrub")]
+    {
+        let mut registry = SECRETS.write().unwrap_or_else(|e| e.into_inner());
+        if secrets.is_empty() {
+            registry.remove(scope);
+        } else {
+            registry.insert(scope.to_string(), secrets.0);
+        }
+    }
+    #[cfg(not(feature = "secret-scrub"))]
+    let _ = (scope, secrets);
+}
+
+/// 用单个值替换作用域 `scope` 中的敏感值，参见 [`replace_secrets`]
+pub fn replace_secret<S: Into<String>>(scope: &str, value: S) {
+    let mut secrets = Secrets::new();
+    secrets.add(value);
+    replace_secrets(scope, secrets);
+}
+
+/// 登记一个敏感值
+///
+/// 长度小于 [`MIN_SECRET_LEN`] 的值与重复值会被忽略；直接登记的值最多保留 [`MAX_UNSCOPED_SECRETS`] 个。
+/// 值会随来源更新时，优先使用 [`replace_secrets`] 按作用域登记。
+pub fn register_secret<S: Into<String>>(value: S) {
+    let mut secrets = Secrets::new();
+    secrets.add(value);
+    append_unscoped(secrets);
+}
+
+/// 登记一个可序列化字段中的全部敏感值，参见 [`Secrets::add_value`] 与 [`register_secret`]
+pub fn register_secret_value<T: Serialize + ?Sized>(value: &T) {
+    let mut secrets = Secrets::new();
+    secrets.add_value(value);
+    append_unscoped(secrets);
+}
+
+/// 追加直接登记的值，超出上限时移除最早登记的值
+fn append_unscoped(secrets: Secrets) {
+    #[cfg(feature = "secret-scrub")]
+    {
+        if secrets.is_empty() {
+            return;
+        }
+        let mut registry = SECRETS.write().unwrap_or_else(|e| e.into_inner());
+        append_bounded(registry.entry(UNSCOPED.to_string()).or_default(), secrets);
+    }
+    #[cfg(not(feature = "secret-scrub"))]
+    let _ = secrets;
+}
+
+/// 追加不重复的值，只保留最近的 [`MAX_UNSCOPED_SECRETS`] 个
+#[cfg(feature = "secret-scrub")]
+fn append_bounded(list: &mut Vec<Zeroizing<String>>, secrets: Secrets) {
+    for secret in secrets.0 {
+        if !list.contains(&secret) {
+            list.push(secret);
+        }
+    }
+    let excess = list.len().saturating_sub(MAX_UNSCOPED_SECRETS);
+    list.drain(..excess);
+}
+
+/// 清零 JSON 值中的全部字符串，用于丢弃包含敏感值的序列化结果
+pub(crate) fn zeroize_json(value: &mut JsonValue) {
+    match value {
+        JsonValue::String(s) => s.zeroize(),
+        JsonValue::Array(items) => items.iter_mut().for_each(zeroize_json),
+        JsonValue::Object(map) => map.values_mut().for_each(zeroize_json),
+        JsonValue::Null | JsonValue::Bool(_) | JsonValue::Number(_) => {}
+    }
+}
+
+/// 将文本中所有已登记的敏感值替换为 [`REDACTED`]；未启用 `secret-scrub` 特性时原样返回
+pub fn scrub(text: &str) -> String {
+    #[cfg(feature = "secret-scrub")]
+    {
+        let registry = SECRETS.read().unwrap_or_else(|e| e.into_inner());
+        let mut secrets: Vec<&str> = registry.values().flatten().map(|secret| secret.as_str()).collect();
+        // 先替换较长的值，避免某个敏感值是另一个的子串时留下残余
+        secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+        let mut scrubbed = text.to_string();
+        for secret in secrets {
+            if scrubbed.contains(secret) {
+                scrubbed = scrubbed.replace(secret, REDACTED);
+            }
+        }
+        scrubbed
+    }
+    #[cfg(not(feature = "secret-scrub"))]
+    text.to_string()
+}
+
+/// 清空已登记的敏感值
+pub fn clear_secrets() {
+    #[cfg(feature = "secret-scrub")]
+    SECRETS.write().unwrap_or_else(|e| e.into_inner()).clear();
+}
+
+#[cfg(feature = "secret-scrub")]
+pub use guard::{install_guard, ScrubGuard};
+
+#[cfg(all(feature = "secret-scrub", feature = "log-facade"))]
+pub use guard::ScrubLogger;
+
+#[cfg(all(feature = "secret-scrub", feature = "tracing-support"))]
+pub use guard::{scrubbing_layer, ScrubMakeWriter, ScrubWriter, ScrubbingLayer};
+
+#[cfg(feature = "secret-scrub")]
+mod guard {
+    use super::scrub;
+    use std::panic::{self, PanicHookInfo};
+    use std::sync::Arc;
+
+    /// panic 钩子类型
+    type PanicHook = Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+    /// 全局擦除防护
+    ///
+    /// 存活期间 panic 信息会经过擦除；被丢弃时恢复安装前的 panic 钩子。
+    #[must_use = "the previous panic hook is restored when the guard is dropped"]
+    pub struct ScrubGuard {
+        /// 安装前的 panic 钩子
+        previous_hook: Arc<PanicHook>,
+    }
+
+    impl std::fmt::Debug for ScrubGuard {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ScrubGuard").finish_non_exhaustive()
+        }
+    }
+
+    impl Drop for ScrubGuard {
+        fn drop(&mut self) {
+            let previous = Arc::clone(&self.previous_hook);
+            // panic 过程中不能修改钩子
+            if !std::thread::panicking() {
+                panic::set_hook(Box::new(move |info| previous(info)));
+            }
+        }
+    }
+
+    /// 安装擦除敏感值的 panic 钩子
+    ///
+    /// 消息中没有敏感值时直接调用原有钩子，保留其输出格式、回溯与自定义处理（例如错误上报）。
+    /// 消息中含有敏感值时，由于无法构造替换了消息的 [`PanicHookInfo`]，新钩子不调用原有钩子，
+    /// 而是按标准库默认格式输出位置与擦除后的消息，并按 `RUST_BACKTRACE` 输出调用栈回溯。
+    pub fn install_guard() -> ScrubGuard {
+        let previous_hook: Arc<PanicHook> = Arc::new(panic::take_hook());
+        let chained = Arc::clone(&previous_hook);
+        panic::set_hook(Box::new(move |info| match redacted_message(info) {
+            Some(message) => report(info, &message),
+            None => chained(info),
+        }));
+        ScrubGuard { previous_hook }
+    }
+
+    /// 擦除后的 panic 消息，消息中没有敏感值时返回 `None`
+    fn redacted_message(info: &PanicHookInfo<'_>) -> Option<String> {
+        let message = if let Some(s) = info.payload().downcast_ref::<&str>() {
+            *s
+        } else if let Some(s) = info.payload().downcast_ref::<String>() {
+            s.as_str()
+        } else {
+            return None;
+        };
+        let scrubbed = scrub(message);
+        (scrubbed != message).then_some(scrubbed)
+    }
+
+    /// 按标准库默认格式输出 panic 信息与调用栈回溯
+    fn report(info: &PanicHookInfo<'_>, message: &str) {
+        let thread = std::thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        match info.location() {
+            Some(location) => eprintln!("thread '{}' panicked at {}:\n{}", name, location, message),
+            None => eprintln!("thread '{}' panicked:\n{}", name, message),
+        }
+        if std::env::var_os("RUST_BACKTRACE").is_some_and(|value| value != "0") {
+            eprintln!("stack backtrace:\n{}", std::backtrace::Backtrace::force_capture());
+        } else {
+            eprintln!("note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace");
+        }
+    }
+
+    #[cfg(feature = "log-facade")]
+    pub use log_logger::ScrubLogger;
+
+    #[cfg(feature = "log-facade")]
+    mod log_logger {
+        use super::scrub;
+        use log::{Log, Metadata, Record};
+
+        /// 擦除敏感值的 `log` 日志实现包装器
+        ///
+        /// 每条记录的消息先格式化并擦除，再连同级别、目标与位置转交给内部的日志实现：
+        ///
+        /// ```ignore
+        /// log::set_boxed_logger(Box::new(ScrubLogger::new(env_logger::Logger::from_default_env())))?;
+        /// ```
+        #[derive(Debug)]
+        pub struct ScrubLogger<L> {
+            /// 内部的日志实现
+            inner: L,
+        }
+
+        impl<L: Log> ScrubLogger<L> {
+            /// 包装内部的日志实现
+            pub fn new(inner: L) -> Self {
+                Self { inner }
+            }
+        }
+
+        impl<L: Log> Log for ScrubLogger<L> {
+            fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+                self.inner.enabled(metadata)
+            }
+
+            fn log(&self, record: &Record<'_>) {
+                if !self.inner.enabled(record.metadata()) {
+                    return;
+                }
+                let message = scrub(&record.args().to_string());
+                self.inner.log(
+                    &Record::builder()
+                        .metadata(record.metadata().clone())
+                        .args(format_args!("{}", message))
+                        .module_path(record.module_path())
+                        .file(record.file())
+                        .line(record.line())
+                        .build(),
+                );
+            }
+
+            fn flush(&self) {
+                self.inner.flush();
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing-support")]
+    pub use tracing_writer::{scrubbing_layer, ScrubMakeWriter, ScrubWriter, ScrubbingLayer};
+
+    #[cfg(feature = "tracing-support")]
+    mod tracing_writer {
+        use super::scrub;
+        use std::io::{self, Write};
+        use tracing_subscriber::fmt::MakeWriter;
+
+        /// 擦除敏感值的 `MakeWriter` 包装器
+        ///
+        /// 每条日志事件先写入缓冲区，在写出到底层 writer 前整体擦除，
+        /// 因此跨多次 `write` 调用的敏感值同样会被替换。
+        #[derive(Debug, Clone)]
+        pub struct ScrubMakeWriter<M> {
+            /// 底层 writer 工厂
+            inner: M,
+        }
+
+        impl<M> ScrubMakeWriter<M> {
+            /// 包装底层 writer 工厂
+            pub fn new(inner: M) -> Self {
+                Self { inner }
+            }
+        }
+
+        impl<'a, M> MakeWriter<'a> for ScrubMakeWriter<M>
+        where
+            M: MakeWriter<'a>,
+        {
+            type Writer = ScrubWriter<M::Writer>;
+
+            fn make_writer(&'a self) -> Self::Writer {
+                ScrubWriter {
+                    inner: self.inner.make_writer(),
+                    buffer: Vec::new(),
+                }
+            }
+        }
+
+        /// 缓冲并擦除单条日志事件的 writer
+        #[derive(Debug)]
+        pub struct ScrubWriter<W: Write> {
+            /// 底层 writer
+            inner: W,
+            /// 尚未写出的内容
+            buffer: Vec<u8>,
+        }
+
+        impl<W: Write> ScrubWriter<W> {
+            /// 擦除缓冲区并写出到底层 writer
+            fn flush_buffer(&mut self) -> io::Result<()> {
+                if self.buffer.is_empty() {
+                    return Ok(());
+                }
+                let text = String::from_utf8_lossy(&self.buffer);
+                let scrubbed = scrub(&text);
+                self.buffer.clear();
+                self.inner.write_all(scrubbed.as_bytes())
+            }
+        }
+
+        impl<W: Write> Write for ScrubWriter<W> {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.buffer.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.flush_buffer()?;
+                self.inner.flush()
+            }
+        }
+
+        impl<W: Write> Drop for ScrubWriter<W> {
+            fn drop(&mut self) {
+                let _ = self.flush_buffer();
+            }
+        }
+
+        /// 输出到标准错误并擦除敏感值的 fmt 日志层
+        pub type ScrubbingLayer<S> = tracing_subscriber::fmt::Layer<
+            S,
+            tracing_subscriber::fmt::format::DefaultFields,
+            tracing_subscriber::fmt::format::Format,
+            ScrubMakeWriter<fn() -> io::Stderr>,
+        >;
+
+        /// 创建输出到标准错误、并擦除敏感值的 fmt 日志层
+        pub fn scrubbing_layer<S>() -> ScrubbingLayer<S>
+        where
+            S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+        {
+            tracing_subscriber::fmt::layer().with_writer(ScrubMakeWriter::new(io::stderr as fn() -> io::Stderr))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "secret-scrub")]
+    #[test]
+    fn test_register_and_scrub() {
+        register_secret("s3cr3t-token-for-scrub-test");
+        let text = "connecting with token s3cr3t-token-for-scrub-test failed";
+        assert_eq!(scrub(text), "connecting with token [REDACTED] failed");
+    }
+
+    #[cfg(feature = "secret-scrub")]
+    #[test]
+    fn test_short_values_are_ignored() {
+        register_secret("ab");
+        assert_eq!(scrub("ab cd"), "ab cd");
+    }
+
+    #[cfg(feature = "secret-scrub")]
+    #[test]
+    fn test_register_secret_value_collects_leaves() {
+        #[derive(Serialize)]
+        struct Credentials {
+            user: String,
+            password: Option<String>,
+            pins: Vec<u32>,
+        }
+
+        register_secret_value(&Credentials {
+            user: "scrub-test-admin".to_string(),
+            password: Some("scrub-test-hunter2".to_string()),
+            pins: vec![987654],
+        });
+
+        let scrubbed = scrub("login scrub-test-admin:scrub-test-hunter2 pin 987654");
+        assert_eq!(scrubbed, "login [REDACTED]:[REDACTED] pin [REDACTED]");
+    }
+
+    #[cfg(feature = "secret-scrub")]
+    #[test]
+    fn test_add_json_moves_strings_out() {
+        let mut value = serde_json::json!({ "token": "scrub-test-moved-token", "nested": ["scrub-test-moved-item"] });
+        let mut secrets = Secrets::new();
+        secrets.add_json(&mut value);
+        assert_eq!(value, serde_json::json!({ "token": "", "nested": [""] }));
+        replace_secrets("scrub-test-moved", secrets);
+        assert_eq!(scrub("scrub-test-moved-token scrub-test-moved-item"), "[REDACTED] [REDACTED]");
+
+        register_secret_value(&crate::SecretString::from("scrub-test-secret-string"));
+        assert_eq!(scrub("pw=scrub-test-secret-string"), "pw=[REDACTED]");
+    }
+
+    #[cfg(feature = "secret-scrub")]
+    #[test]
+    fn test_replace_secrets_drops_previous_values() {
+        replace_secret("scrub-test-scope", "scrub-test-old-password");
+        assert_eq!(scrub("pw=scrub-test-old-password"), "pw=[REDACTED]");
+
+        replace_secret("scrub-test-scope", "scrub-test-new-password");
+        assert_eq!(scrub("pw=scrub-test-old-password"), "pw=scrub-test-old-password");
+        assert_eq!(scrub("pw=scrub-test-new-password"), "pw=[REDACTED]");
+
+        replace_secrets("scrub-test-scope", Secrets::new());
+        assert_eq!(scrub("pw=scrub-test-new-password"), "pw=scrub-test-new-password");
+    }
+
+    #[cfg(feature = "secret-scrub")]
+    #[test]
+    fn test_unscoped_secrets_are_bounded() {
+        let mut list = Vec::new();
+        for i in 0..=MAX_UNSCOPED_SECRETS {
+            let mut secrets = Secrets::new();
+            secrets.add(format!("bounded-{}", i));
+            append_bounded(&mut list, secrets);
+        }
+        assert_eq!(list.len(), MAX_UNSCOPED_SECRETS);
+        assert_eq!(list[0].as_str(), "bounded-1");
+    }
+
+    #[cfg(feature = "secret-scrub")]
+    #[test]
+    fn test_longer_secret_replaced_first() {
+        register_secret("scrub-test-prefix");
+        register_secret("scrub-test-prefix-and-suffix");
+        assert_eq!(scrub("value=scrub-test-prefix-and-suffix"), "value=[REDACTED]");
+    }
+
+    #[cfg(not(feature = "secret-scrub"))]
+    #[test]
+    fn test_nothing_is_registered_without_feature() {
+        register_secret("scrub-test-disabled-secret");
+        assert_eq!(scrub("pw=scrub-test-disabled-secret"), "pw=scrub-test-disabled-secret");
+    }
+
+    #[cfg(all(feature = "secret-scrub", feature = "tracing-support"))]
+    #[test]
+    fn test_scrub_writer_buffers_until_flush() {
+        use std::io::Write;
+        use tracing_subscriber::fmt::MakeWriter;
+
+        register_secret("scrub-test-writer-secret");
+        let output = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = output.clone();
+        let make_writer = ScrubMakeWriter::new(move || SharedBuffer(sink.clone()));
+        {
+            let mut writer = make_writer.make_writer();
+            writer.write_all(b"password=scrub-test-").unwrap();
+            writer.write_all(b"writer-secret\n").unwrap();
+        }
+
+        let written = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+        assert_eq!(written, "password=[REDACTED]\n");
+    }
+
+    #[cfg(all(feature = "secret-scrub", feature = "log-facade"))]
+    #[test]
+    fn test_scrub_logger_scrubs_messages() {
+        use log::Log;
+        use std::sync::{Arc, Mutex};
+
+        struct Capture(Arc<Mutex<Vec<String>>>);
+
+        impl Log for Capture {
+            fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+                metadata.level() <= log::Level::Info
+            }
+
+            fn log(&self, record: &log::Record<'_>) {
+                self.0.lock().unwrap().push(format!("{} {}: {}", record.level(), record.target(), record.args()));
+            }
+
+            fn flush(&self) {}
+        }
+
+        register_secret("scrub-test-log-secret");
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let logger = ScrubLogger::new(Capture(lines.clone()));
+        let record = |level, secret| {
+            logger.log(
+                &log::Record::builder()
+                    .level(level)
+                    .target("app")
+                    .args(format_args!("token={}", secret))
+                    .build(),
+            )
+        };
+        record(log::Level::Info, "scrub-test-log-secret");
+        record(log::Level::Debug, "ignored");
+        assert_eq!(*lines.lock().unwrap(), vec!["INFO app: token=[REDACTED]".to_string()]);
+    }
+
+    #[cfg(all(feature = "secret-scrub", feature = "tracing-support"))]
+    struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+    #[cfg(all(feature = "secret-scrub", feature = "tracing-support"))]
+    impl std::io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+}
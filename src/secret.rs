@@ -0,0 +1,179 @@
+//! Quantum Config 敏感字符串类型
+//!
+//! [`SecretString`] 用于承载密码、API 密钥等敏感配置值：
+//! - 释放时通过 `zeroize` 将内存清零，避免敏感值残留在堆上；
+//! - `Debug` 输出固定为 `[REDACTED]`，不会经由日志泄露；
+//! - 只能通过 [`SecretString::expose_secret`] 显式读取明文。
+//!
+//! 它可以从任意配置源反序列化，配置文件中的数字与布尔值（例如 TOML 的 `api_key = 987654`）转为字符串。
+//! 环境变量与命令行参数中的值会被推断为布尔值或数字，转回字符串时会丢失原文（`007` 变成 `7`、`yes` 变成 `true`），
+//! 因此派生宏将 `SecretString` 字段登记到 [`crate::Config::raw_string_keys`]，这两类来源中的值按原文读取；
+//! 不使用派生宏时用 [`crate::QuantumConfigBuilder::with_raw_string_key`] 登记。
+//!
+//! 注意：序列化时输出的是明文，以便默认值层、校验与回写正常工作。
+//! 派生宏会将 `SecretString` 类型的字段自动视为 `#[config(secret)]`，
+//! 因此它们不会出现在模板、保存的文件与 `redacted()` 视图中；加载时登记到 [`crate::scrub`] 的副本
+//! 同样在移除时清零。
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use zeroize::Zeroize;
+
+/// 释放时清零内存、`Debug` 输出脱敏的字符串
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    /// 由明文创建
+    pub fn new<S: Into<String>>(value: S) -> Self {
+        Self(value.into())
+    }
+
+    /// 显式读取明文
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+
+    /// 明文是否为空
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Drop for SecretString {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretString({})", crate::scrub::REDACTED)
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl std::str::FromStr for SecretString {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+impl Serialize for SecretString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for SecretString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SecretStringVisitor)
+    }
+}
+
+/// 接受字符串、数字与布尔值的访问器，数字与布尔值按其文本形式转为字符串
+struct SecretStringVisitor;
+
+impl Visitor<'_> for SecretStringVisitor {
+    type Value = SecretString;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a secret string")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(SecretString::from(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(SecretString(v))
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(SecretString(v.to_string()))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(SecretString(v.to_string()))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(SecretString(v.to_string()))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(SecretString(v.to_string()))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(SecretString(v.to_string()))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(SecretString(v.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let secret = SecretString::new("hunter2-password");
+        let debug = format!("{:?}", secret);
+        assert_eq!(debug, "SecretString([REDACTED])");
+        assert!(!debug.contains("hunter2"));
+        assert_eq!(secret.expose_secret(), "hunter2-password");
+    }
+
+    #[test]
+    fn test_deserialize_from_string_and_scalars() {
+        let secret: SecretString = serde_json::from_value(serde_json::json!("s3cr3t")).unwrap();
+        assert_eq!(secret.expose_secret(), "s3cr3t");
+
+        let secret: SecretString = serde_json::from_value(serde_json::json!(123456)).unwrap();
+        assert_eq!(secret.expose_secret(), "123456");
+
+        let secret: SecretString = serde_json::from_value(serde_json::json!(true)).unwrap();
+        assert_eq!(secret.expose_secret(), "true");
+
+        assert!(serde_json::from_value::<SecretString>(serde_json::json!({"a": 1})).is_err());
+    }
+
+    #[test]
+    fn test_serialize_exposes_plaintext() {
+        let secret = SecretString::from("token-value");
+        assert_eq!(serde_json::to_value(&secret).unwrap(), serde_json::json!("token-value"));
+    }
+
+    #[test]
+    fn test_deserialize_through_figment_number() {
+        use figment::providers::Format;
+
+        #[derive(serde::Deserialize)]
+        struct Settings {
+            api_key: SecretString,
+        }
+
+        let settings: Settings = figment::Figment::new()
+            .merge(figment::providers::Toml::string("api_key = 987654"))
+            .extract()
+            .unwrap();
+        assert_eq!(settings.api_key.expose_secret(), "987654");
+    }
+}
@@ -0,0 +1,99 @@
+//! SIGHUP 触发重载（Unix）
+//!
+//! 守护进程的惯例是收到 `SIGHUP` 时重新读取配置。启用 `signals` 特性后：
+//!
+//! - [`sighup_notifications`] 安装 `SIGHUP` 处理器，每收到一次信号就向返回的通道发送一条通知，
+//!   可以直接传给 [`ConfigWatcher::from_notifications`] 或 [`ConfigWatcher::reloadable_from_notifications`]；
+//! - [`reload_on_sighup`] 在每次 `SIGHUP` 后调用 [`Config::load`] 重新加载派生类型的配置，
+//!   并写入 [`Reloadable`] 句柄：
+//!
+//! ```ignore
+//! let (_watcher, config) = quantum_config::signals::reload_on_sighup(AppConfig::load()?)?;
+//! // kill -HUP <pid> 之后，config.load() 返回重新加载的配置
+//! ```
+//!
+//! 安装处理器后进程不再因 `SIGHUP` 退出。短时间内的连续信号合并为一次重载；
+//! 重载失败时保留当前配置并输出警告日志。
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::reload::Reloadable;
+use crate::watch::{reloadable_sink, ConfigWatcher};
+use signal_hook::consts::SIGHUP;
+use signal_hook::iterator::Signals;
+use std::sync::mpsc::{self, Receiver};
+
+/// 安装 `SIGHUP` 处理器，返回每收到一次信号就产生一条通知的通道
+///
+/// 接收端被丢弃后，下一次收到信号时处理线程退出。
+///
+/// # Errors
+/// 无法安装信号处理器时返回 [`QuantumConfigError::Watch`]
+pub fn sighup_notifications() -> Result<Receiver<()>, QuantumConfigError> {
+    let mut signals = Signals::new([SIGHUP])
+        .map_err(|e| QuantumConfigError::Watch(format!("Failed to install SIGHUP handler: {}", e)))?;
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for _ in signals.forever() {
+            if sender.send(()).is_err() {
+                signals.handle().close();
+                return;
+            }
+        }
+    });
+    Ok(receiver)
+}
+
+/// 在每次收到 `SIGHUP` 后调用 [`Config::load`] 重新加载配置，写入以 `initial` 开始的句柄
+///
+/// 返回的监视器需要在使用期间保持存活。
+///
+/// # Errors
+/// 无法安装信号处理器时返回 [`QuantumConfigError::Watch`]
+pub fn reload_on_sighup<T>(initial: T) -> Result<(ConfigWatcher<T>, Reloadable<T>), QuantumConfigError>
+where
+    T: Config + Send + Sync + 'static,
+{
+    let reloadable = Reloadable::new(initial);
+    let watcher = ConfigWatcher::from_loader(sighup_notifications()?, T::load, reloadable_sink(&reloadable));
+    Ok((watcher, reloadable))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use std::sync::atomic::{AtomicU16, Ordering};
+    use std::time::Duration;
+
+    /// 每次加载返回当前端口的配置
+    static PORT: AtomicU16 = AtomicU16::new(8080);
+
+    #[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+    struct SignalConfig {
+        port: u16,
+    }
+
+    impl Config for SignalConfig {
+        fn load() -> Result<Self, QuantumConfigError> {
+            Ok(Self { port: PORT.load(Ordering::SeqCst) })
+        }
+
+        fn load_with_args(_args: Vec<String>) -> Result<Self, QuantumConfigError> {
+            Self::load()
+        }
+    }
+
+    #[test]
+    fn test_sighup_triggers_reload() {
+        let (_watcher, config) = reload_on_sighup(SignalConfig::load().unwrap()).unwrap();
+        let updates = config.subscribe();
+        assert_eq!(config.load().port, 8080);
+
+        PORT.store(9090, Ordering::SeqCst);
+        signal_hook::low_level::raise(SIGHUP).unwrap();
+        let update = updates.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(*update.config, SignalConfig { port: 9090 });
+        assert_eq!(config.generation(), update.generation);
+    }
+}
@@ -0,0 +1,434 @@
+//! 配置模板渲染
+//!
+//! 将配置值渲染为指定格式的模板文本，供 [`crate::Config::generate_template_as`] 使用。
+//! 字段文档（键路径 -> 文本，通常来自字段的 `///` 注释）按各格式的注释语法写在对应键之前：
+//! - TOML：`# 注释`
+//! - JSON5：`// 注释`
+//! - YAML（`yaml` 特性）：`# 注释`；字符串一律加双引号
+//! - INI：`# 注释`
+//! - XML（`xml` 特性）：`<!-- 注释 -->`；根元素为 `<config>`，数组写为重复的同名元素
+//! - JSON 不支持注释，文档被忽略；HCL 模板（`hcl` 特性）同样不写入文档
+//!
+//! JSON5、YAML 与 INI 模板中的键按字母顺序排列。INI 只能表示一层分组：顶层标量写在开头，顶层对象写为 `[section]`；
+//! 更深的嵌套或数组无法表示，返回 [`QuantumConfigError::TemplateGeneration`]。
+
+use crate::error::{QuantumConfigError, TemplateFormat};
+use crate::providers::file_provider::FileFormat;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use toml_edit::{Decor, DocumentMut, Item, Table};
+
+/// 将配置值渲染为模板文本
+pub fn render_template<T: Serialize>(
+    value: &T,
+    format: FileFormat,
+    docs: &[(&str, &str)],
+) -> Result<String, QuantumConfigError> {
+    match format {
+        FileFormat::Toml => render_toml(value, docs),
+        FileFormat::Json => serde_json::to_string_pretty(value).map_err(|e| template_error(TemplateFormat::Json, e)),
+        #[cfg(feature = "json5")]
+        FileFormat::Json5 => {
+            let value = to_json(value, TemplateFormat::Json)?;
+            let mut out = String::new();
+            render_json5(&value, "", docs, 0, &mut out);
+            out.push('\n');
+            Ok(out)
+        }
+        FileFormat::Ini => render_ini(&to_json(value, TemplateFormat::Ini)?, docs),
+        #[cfg(feature = "yaml")]
+        FileFormat::Yaml => Ok(render_yaml(&to_json(value, TemplateFormat::Yaml)?, docs)),
+        #[cfg(feature = "hcl")]
+        FileFormat::Hcl => hcl::to_string(value).map_err(|e| template_error(TemplateFormat::Hcl, e)),
+        #[cfg(feature = "xml")]
+        FileFormat::Xml => {
+            let value = to_json(value, TemplateFormat::Xml)?;
+            let JsonValue::Object(root) = &value else {
+                return Err(template_error(TemplateFormat::Xml, "configuration must serialize to a table"));
+            };
+            let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<config>\n");
+            for (key, child) in root {
+                render_xml(key, key, child, docs, 1, &mut out);
+            }
+            out.push_str("</config>\n");
+            Ok(out)
+        }
+    }
+}
+
+fn template_error<E: std::fmt::Display>(format: TemplateFormat, e: E) -> QuantumConfigError {
+    QuantumConfigError::TemplateGeneration {
+        format,
+        reason: e.to_string(),
+    }
+}
+
+fn to_json<T: Serialize>(value: &T, format: TemplateFormat) -> Result<JsonValue, QuantumConfigError> {
+    serde_json::to_value(value).map_err(|e| template_error(format, e))
+}
+
+/// 按注释前缀格式化多行文档
+fn comment_lines(marker: &str, indent: &str, doc: &str) -> String {
+    doc.lines()
+        .map(|line| if line.is_empty() { format!("{}{}\n", indent, marker) } else { format!("{}{} {}\n", indent, marker, line) })
+        .collect()
+}
+
+fn find_doc<'a>(docs: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    docs.iter().find(|(path, _)| *path == key).map(|(_, doc)| *doc)
+}
+
+/// 渲染 TOML：保留 `toml::to_string_pretty` 的排版，再为文档化的键添加注释
+fn render_toml<T: Serialize>(value: &T, docs: &[(&str, &str)]) -> Result<String, QuantumConfigError> {
+    let content = toml::to_string_pretty(value).map_err(|e| template_error(TemplateFormat::Toml, e))?;
+    if docs.is_empty() {
+        return Ok(content);
+    }
+    let mut document: DocumentMut = content.parse().map_err(|e| template_error(TemplateFormat::Toml, e))?;
+    for (path, doc) in docs {
+        let segments: Vec<&str> = path.split('.').collect();
+        annotate_toml(document.as_table_mut(), &segments, &comment_lines("#", "", doc));
+    }
+    Ok(document.to_string())
+}
+
+/// 沿键路径找到 TOML 条目并添加注释，内联表与数组中的键无法注释，直接跳过
+fn annotate_toml(table: &mut Table, segments: &[&str], comment: &str) {
+    match segments {
+        [] => {}
+        [last] => {
+            if table.get(last).is_some_and(Item::is_value) {
+                if let Some(mut key) = table.key_mut(last) {
+                    prepend_comment(key.leaf_decor_mut(), comment);
+                }
+                return;
+            }
+            match table.get_mut(last) {
+                Some(Item::Table(child)) => prepend_comment(child.decor_mut(), comment),
+                Some(Item::ArrayOfTables(array)) => {
+                    if let Some(first) = array.get_mut(0) {
+                        prepend_comment(first.decor_mut(), comment);
+                    }
+                }
+                _ => {}
+            }
+        }
+        [first, rest @ ..] => {
+            if let Some(Item::Table(child)) = table.get_mut(first) {
+                annotate_toml(child, rest, comment);
+            }
+        }
+    }
+}
+
+/// 在已有前缀（例如表之间的空行）之后插入注释
+fn prepend_comment(decor: &mut Decor, comment: &str) {
+    let existing = decor.prefix().and_then(|prefix| prefix.as_str()).unwrap_or_default().to_string();
+    decor.set_prefix(format!("{}{}", existing, comment));
+}
+
+/// 渲染 JSON5：与 `serde_json::to_string_pretty` 相同的缩进，文档写为 `//` 注释
+#[cfg(feature = "json5")]
+fn render_json5(value: &JsonValue, path: &str, docs: &[(&str, &str)], depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth + 1);
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            out.push_str("{\n");
+            for (i, (key, child)) in map.iter().enumerate() {
+                let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                if let Some(doc) = find_doc(docs, &child_path) {
+                    out.push_str(&comment_lines("//", &indent, doc));
+                }
+                out.push_str(&indent);
+                out.push_str(&JsonValue::String(key.clone()).to_string());
+                out.push_str(": ");
+                render_json5(child, &child_path, docs, depth + 1, out);
+                if i + 1 < map.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push_str(&"  ".repeat(depth));
+            out.push('}');
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            out.push_str("[\n");
+            for (i, item) in items.iter().enumerate() {
+                out.push_str(&indent);
+                render_json5(item, path, docs, depth + 1, out);
+                if i + 1 < items.len() {
+                    out.push(',');
+                }
+                out.push('\n');
+            }
+            out.push_str(&"  ".repeat(depth));
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// 以块样式渲染 YAML，文档写为 `#` 注释
+///
+/// 字符串一律加双引号（JSON 字符串即合法的 YAML 双引号标量），避免 `yes`、`1.0` 等被误读；
+/// 不依赖 `yaml` 特性，`--print-config=yaml` 同样使用本函数。
+pub(crate) fn render_yaml(value: &JsonValue, docs: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    write_yaml(value, "", docs, 0, &mut out);
+    out
+}
+
+fn write_yaml(value: &JsonValue, path: &str, docs: &[(&str, &str)], indent: usize, out: &mut String) {
+    let pad = " ".repeat(indent);
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            for (key, child) in map {
+                let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                if let Some(doc) = find_doc(docs, &child_path) {
+                    out.push_str(&comment_lines("#", &pad, doc));
+                }
+                out.push_str(&format!("{}{}:", pad, yaml_key(key)));
+                write_yaml_child(child, &child_path, docs, indent, out);
+            }
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            for item in items {
+                out.push_str(&format!("{}-", pad));
+                write_yaml_child(item, path, &[], indent, out);
+            }
+        }
+        scalar => out.push_str(&format!("{}{}\n", pad, yaml_scalar(scalar))),
+    }
+}
+
+/// 渲染映射的值或序列的元素：非空容器换行并缩进，其余写在同一行
+fn write_yaml_child(value: &JsonValue, path: &str, docs: &[(&str, &str)], indent: usize, out: &mut String) {
+    match value {
+        JsonValue::Object(map) if !map.is_empty() => {
+            out.push('\n');
+            write_yaml(value, path, docs, indent + 2, out);
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            out.push('\n');
+            write_yaml(value, path, docs, indent + 2, out);
+        }
+        scalar => out.push_str(&format!(" {}\n", yaml_scalar(scalar))),
+    }
+}
+
+/// 键按原样写出的前提：以字母或 `_` 开头、只含字母数字与 `_`、`-`，且不是 YAML 1.1 中的布尔值或空值，
+/// 否则加双引号（例如 `"yes"`、`"on"`、`"null"`、`"1"`）
+fn yaml_key(key: &str) -> String {
+    const RESERVED: &[&str] = &["y", "n", "yes", "no", "on", "off", "true", "false", "null"];
+    let plain = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        && !RESERVED.contains(&key.to_ascii_lowercase().as_str());
+    if plain { key.to_string() } else { JsonValue::from(key).to_string() }
+}
+
+fn yaml_scalar(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Object(_) => "{}".to_string(),
+        JsonValue::Array(_) => "[]".to_string(),
+        scalar => scalar.to_string(),
+    }
+}
+
+/// 渲染 INI：顶层标量在前，顶层对象写为分组
+/// 渲染 XML 元素：对象写为子元素，数组写为重复的同名元素，文档写为 `<!-- -->` 注释
+#[cfg(feature = "xml")]
+fn render_xml(key: &str, path: &str, value: &JsonValue, docs: &[(&str, &str)], depth: usize, out: &mut String) {
+    let indent = "  ".repeat(depth);
+    if let Some(doc) = find_doc(docs, path) {
+        // 注释中不允许出现 `--`
+        for line in doc.lines() {
+            out.push_str(&format!("{}<!-- {} -->\n", indent, line.replace("--", "- -")));
+        }
+    }
+    match value {
+        // 未设置的可选值不写入
+        JsonValue::Null => {}
+        JsonValue::Array(items) => {
+            for item in items {
+                render_xml(key, path, item, &[], depth, out);
+            }
+        }
+        JsonValue::Object(map) if !map.is_empty() => {
+            out.push_str(&format!("{}<{}>\n", indent, key));
+            for (child_key, child) in map {
+                render_xml(child_key, &format!("{}.{}", path, child_key), child, docs, depth + 1, out);
+            }
+            out.push_str(&format!("{}</{}>\n", indent, key));
+        }
+        JsonValue::Object(_) => out.push_str(&format!("{}<{}/>\n", indent, key)),
+        JsonValue::String(s) => {
+            out.push_str(&format!("{}<{}>{}</{}>\n", indent, key, quick_xml::escape::escape(s.as_str()), key))
+        }
+        other => out.push_str(&format!("{}<{}>{}</{}>\n", indent, key, other, key)),
+    }
+}
+
+fn render_ini(value: &JsonValue, docs: &[(&str, &str)]) -> Result<String, QuantumConfigError> {
+    let JsonValue::Object(root) = value else {
+        return Err(template_error(TemplateFormat::Ini, "configuration must serialize to a table"));
+    };
+    let mut out = String::new();
+    for (key, child) in root.iter().filter(|(_, child)| !child.is_object()) {
+        write_ini_entry(&mut out, key, key, child, docs)?;
+    }
+    for (section, child) in root {
+        let JsonValue::Object(entries) = child else {
+            continue;
+        };
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        if let Some(doc) = find_doc(docs, section) {
+            out.push_str(&comment_lines("#", "", doc));
+        }
+        out.push_str(&format!("[{}]\n", section));
+        for (key, entry) in entries {
+            write_ini_entry(&mut out, &format!("{}.{}", section, key), key, entry, docs)?;
+        }
+    }
+    Ok(out)
+}
+
+fn write_ini_entry(
+    out: &mut String,
+    path: &str,
+    key: &str,
+    value: &JsonValue,
+    docs: &[(&str, &str)],
+) -> Result<(), QuantumConfigError> {
+    let rendered = match value {
+        // 未设置的可选值不写入
+        JsonValue::Null => return Ok(()),
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Bool(_) | JsonValue::Number(_) => value.to_string(),
+        JsonValue::Array(_) | JsonValue::Object(_) => {
+            return Err(template_error(
+                TemplateFormat::Ini,
+                format!("'{}' is nested too deeply to be represented in INI", path),
+            ))
+        }
+    };
+    if let Some(doc) = find_doc(docs, path) {
+        out.push_str(&comment_lines("#", "", doc));
+    }
+    out.push_str(&format!("{} = {}\n", key, rendered));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Serialize)]
+    struct AppConfig {
+        name: String,
+        debug: bool,
+        database: Database,
+    }
+
+    fn sample() -> AppConfig {
+        AppConfig {
+            name: "app".to_string(),
+            debug: false,
+            database: Database { host: "localhost".to_string(), port: 5432 },
+        }
+    }
+
+    const DOCS: &[(&str, &str)] = &[("name", "Application name"), ("database", "Database connection\nsettings")];
+
+    #[test]
+    fn test_toml_template_includes_docs() {
+        let rendered = render_template(&sample(), FileFormat::Toml, DOCS).unwrap();
+        assert!(rendered.starts_with("# Application name\nname = \"app\"\n"));
+        assert!(rendered.contains("\n# Database connection\n# settings\n[database]\n"));
+        let parsed: toml::Value = toml::from_str(&rendered).unwrap();
+        assert_eq!(parsed["database"]["port"].as_integer(), Some(5432));
+
+        let plain = render_template(&sample(), FileFormat::Toml, &[]).unwrap();
+        assert_eq!(plain, toml::to_string_pretty(&sample()).unwrap());
+    }
+
+    #[test]
+    fn test_json_and_ini_templates() {
+        let json = render_template(&sample(), FileFormat::Json, DOCS).unwrap();
+        assert_eq!(json, serde_json::to_string_pretty(&sample()).unwrap());
+
+        let ini = render_template(&sample(), FileFormat::Ini, DOCS).unwrap();
+        assert_eq!(
+            ini,
+            "debug = false\n# Application name\nname = app\n\n# Database connection\n# settings\n[database]\nhost = localhost\nport = 5432\n"
+        );
+
+        let nested = serde_json::json!({"a": {"b": {"c": 1}}});
+        let error = render_template(&nested, FileFormat::Ini, &[]).unwrap_err();
+        assert!(error.to_string().contains("'a.b' is nested too deeply"));
+    }
+
+    #[cfg(feature = "hcl")]
+    #[test]
+    fn test_hcl_template_round_trips() {
+        let rendered = render_template(&sample(), FileFormat::Hcl, DOCS).unwrap();
+        let parsed: JsonValue = hcl::from_str(&rendered).unwrap();
+        assert_eq!(parsed, serde_json::to_value(sample()).unwrap());
+    }
+
+    #[cfg(feature = "xml")]
+    #[test]
+    fn test_xml_template_round_trips() {
+        let rendered = render_template(&sample(), FileFormat::Xml, DOCS).unwrap();
+        assert!(rendered.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<config>\n"));
+        assert!(rendered.contains("  <!-- Application name -->\n  <name>app</name>\n"));
+        assert!(rendered.contains("  <database>\n    <host>localhost</host>\n"));
+        let parsed = crate::providers::xml::parse(&rendered).unwrap();
+        assert_eq!(parsed, serde_json::to_value(sample()).unwrap());
+
+        let escaped = render_template(&serde_json::json!({"motd": "a < b & c", "hosts": ["x", "y"]}), FileFormat::Xml, &[]).unwrap();
+        assert!(escaped.contains("<motd>a &lt; b &amp; c</motd>"));
+        assert!(escaped.contains("  <hosts>x</hosts>\n  <hosts>y</hosts>\n"));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_template_round_trips() {
+        let rendered = render_template(&sample(), FileFormat::Yaml, DOCS).unwrap();
+        assert_eq!(
+            rendered,
+            "# Database connection\n# settings\ndatabase:\n  host: \"localhost\"\n  port: 5432\ndebug: false\n# Application name\nname: \"app\"\n"
+        );
+        let parsed: JsonValue = serde_yaml_ng::from_str(&rendered).unwrap();
+        assert_eq!(parsed, serde_json::to_value(sample()).unwrap());
+
+        let nested = serde_json::json!({
+            "hosts": ["a", "b"],
+            "servers": [{ "host": "x", "port": 1 }],
+            "motd": "line one\nline two",
+            "empty": {},
+            "tls": { "ciphers": [] }
+        });
+        let rendered = render_template(&nested, FileFormat::Yaml, &[("tls.ciphers", "Allowed ciphers")]).unwrap();
+        assert!(rendered.contains("tls:\n  # Allowed ciphers\n  ciphers: []\n"), "{}", rendered);
+        let parsed: JsonValue = serde_yaml_ng::from_str(&rendered).unwrap();
+        assert_eq!(parsed, nested);
+    }
+
+    #[cfg(feature = "json5")]
+    #[test]
+    fn test_json5_template_round_trips() {
+        let rendered = render_template(&sample(), FileFormat::Json5, DOCS).unwrap();
+        assert!(rendered.contains("  // Application name\n  \"name\": \"app\"\n"));
+        let parsed: JsonValue = json5::from_str(&rendered).unwrap();
+        assert_eq!(parsed, serde_json::to_value(sample()).unwrap());
+    }
+}
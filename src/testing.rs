@@ -0,0 +1,520 @@
+//! 测试辅助工具
+//!
+//! ## 隔离的加载
+//!
+//! 测试中直接调用 `load` 会读取真实的系统级/用户级配置目录与进程环境变量，结果依赖运行测试的机器，
+//! 并行测试之间还会互相干扰。本模块提供：
+//!
+//! - [`EnvGuard`]：在作用域内设置或删除环境变量，释放时（包括 panic 展开时）恢复原值；
+//!   所有守卫共享一把进程级的锁，修改环境变量的测试因此串行执行；
+//! - [`TempConfig`]：在临时目录中写入配置文件，释放时删除目录，[`TempConfig::args`] 返回以 `--config`
+//!   指定该文件的命令行参数；
+//! - [`load_isolated`] / [`load_isolated_with_args`]：加载配置时不查找系统级与用户级配置目录，
+//!   只使用默认值、显式指定的文件、环境变量与命令行参数。
+//!
+//! ```ignore
+//! use quantum_config::testing::{load_isolated_with_args, EnvGuard, TempConfig};
+//!
+//! #[test]
+//! fn env_overrides_file() {
+//!     let mut env = EnvGuard::new();
+//!     env.set("APP_PORT", "9090").remove("APP_HOST");
+//!     let file = TempConfig::toml("port = 8080\nhost = \"localhost\"");
+//!
+//!     let config: AppConfig = load_isolated_with_args(file.args()).unwrap();
+//!     assert_eq!(config.port, 9090);
+//! }
+//! ```
+//!
+//! ## 配置样例语料
+//!
+//! 为配置兼容性回归测试提供语料运行器：
+//!
+//! ```ignore
+//! #[test]
+//! fn config_corpus() {
+//!     quantum_config::testing::check_corpus::<AppConfig>("tests/configs/**");
+//! }
+//! ```
+//!
+//! 语料目录中每个受支持格式的文件都通过 [`Config::load_from_file`] 加载（包含字段约束校验）：
+//! - 路径中不含 `invalid` 目录的文件必须加载成功
+//! - `invalid/` 下的文件必须加载失败；存在同名的 `.error` 附属文件（例如 `bad_port.toml.error`）时，
+//!   错误代码（[`QuantumConfigError::code`]）还必须与文件内容一致
+
+use crate::config::Config;
+use crate::error::QuantumConfigError;
+use crate::providers::file_provider::FileFormat;
+use std::cell::Cell;
+use std::ffi::{OsStr, OsString};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+
+/// 无效样例目录名
+const INVALID_DIR: &str = "invalid";
+
+/// 期望错误代码附属文件的扩展名
+const EXPECTED_ERROR_EXTENSION: &str = "error";
+
+/// 隔离加载时传给 clap 的程序名
+const PROGRAM_NAME: &str = "quantum-config-test";
+
+/// 修改环境变量的守卫共享的锁
+static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+/// 临时配置目录的序号
+static TEMP_CONFIG_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// 当前线程持有的 [`EnvGuard`] 数量，嵌套的守卫不再重复加锁
+    static ENV_GUARD_DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// 当前线程是否处于隔离加载中
+    static ISOLATED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 作用域内的环境变量修改
+///
+/// 创建时获取进程级的环境变量锁，释放时恢复被修改的变量并释放锁。同一线程中可以嵌套创建守卫，
+/// 嵌套的守卫需按创建的相反顺序释放（按作用域使用时自然满足）。
+#[must_use = "environment variables are restored when the guard is dropped"]
+pub struct EnvGuard {
+    /// 被修改的变量及其原值，每个变量只记录第一次修改前的值
+    saved: Vec<(OsString, Option<OsString>)>,
+    /// 最外层守卫持有的锁
+    _lock: Option<MutexGuard<'static, ()>>,
+}
+
+impl EnvGuard {
+    /// 获取环境变量锁；前一个持有者 panic 时锁仍然可用
+    pub fn new() -> Self {
+        let lock = ENV_GUARD_DEPTH.with(|depth| {
+            let lock = (depth.get() == 0).then(|| ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner()));
+            depth.set(depth.get() + 1);
+            lock
+        });
+        Self {
+            saved: Vec::new(),
+            _lock: lock,
+        }
+    }
+
+    /// 设置环境变量，守卫释放时恢复原值
+    pub fn set<K: AsRef<OsStr>, V: AsRef<OsStr>>(&mut self, key: K, value: V) -> &mut Self {
+        self.save(key.as_ref());
+        std::env::set_var(key, value);
+        self
+    }
+
+    /// 删除环境变量，守卫释放时恢复原值
+    pub fn remove<K: AsRef<OsStr>>(&mut self, key: K) -> &mut Self {
+        self.save(key.as_ref());
+        std::env::remove_var(key);
+        self
+    }
+
+    fn save(&mut self, key: &OsStr) {
+        if !self.saved.iter().any(|(saved, _)| saved == key) {
+            self.saved.push((key.to_os_string(), std::env::var_os(key)));
+        }
+    }
+}
+
+impl Default for EnvGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for EnvGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnvGuard").field("saved", &self.saved).finish()
+    }
+}
+
+impl Drop for EnvGuard {
+    fn drop(&mut self) {
+        for (key, value) in self.saved.drain(..).rev() {
+            match value {
+                Some(value) => std::env::set_var(&key, value),
+                None => std::env::remove_var(&key),
+            }
+        }
+        ENV_GUARD_DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+/// 临时目录中的配置文件，释放时删除整个目录
+///
+/// 创建失败时 panic，适合在测试中直接使用。
+#[derive(Debug)]
+pub struct TempConfig {
+    /// 临时目录
+    dir: PathBuf,
+    /// 主配置文件
+    path: PathBuf,
+}
+
+impl TempConfig {
+    /// 以给定格式写入主配置文件 `config.{ext}`
+    pub fn new(content: &str, format: FileFormat) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "quantum-config-test-{}-{}",
+            std::process::id(),
+            TEMP_CONFIG_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        if let Err(e) = fs::create_dir_all(&dir) {
+            panic!("Failed to create temporary config directory {}: {}", dir.display(), e);
+        }
+        let path = dir.join(format!("config.{}", format.extension()));
+        let config = Self { dir, path };
+        write_file(&config.path, content);
+        config
+    }
+
+    /// TOML 配置文件
+    pub fn toml(content: &str) -> Self {
+        Self::new(content, FileFormat::Toml)
+    }
+
+    /// JSON 配置文件
+    pub fn json(content: &str) -> Self {
+        Self::new(content, FileFormat::Json)
+    }
+
+    /// INI 配置文件
+    pub fn ini(content: &str) -> Self {
+        Self::new(content, FileFormat::Ini)
+    }
+
+    /// 在同一目录中写入附加文件（例如被包含的文件或剖面文件），`relative` 相对于临时目录
+    pub fn with_file(self, relative: &str, content: &str) -> Self {
+        write_file(&self.dir.join(relative), content);
+        self
+    }
+
+    /// 主配置文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 临时目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 以 `--config` 指定主配置文件的命令行参数（包含程序名）
+    pub fn args(&self) -> Vec<String> {
+        vec![
+            PROGRAM_NAME.to_string(),
+            "--config".to_string(),
+            self.path.display().to_string(),
+        ]
+    }
+}
+
+impl Drop for TempConfig {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn write_file(path: &Path, content: &str) {
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Err(e) = fs::write(path, content) {
+        panic!("Failed to write temporary config file {}: {}", path.display(), e);
+    }
+}
+
+/// 不查找系统级与用户级配置目录，以空的命令行参数加载配置
+pub fn load_isolated<T: Config>() -> Result<T, QuantumConfigError> {
+    load_isolated_with_args(vec![PROGRAM_NAME.to_string()])
+}
+
+/// 不查找系统级与用户级配置目录，以给定的命令行参数（包含程序名）加载配置
+pub fn load_isolated_with_args<T: Config>(args: Vec<String>) -> Result<T, QuantumConfigError> {
+    /// 加载结束（包括 panic 展开）时恢复原状态
+    struct Reset(bool);
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            ISOLATED.with(|isolated| isolated.set(self.0));
+        }
+    }
+
+    let _reset = Reset(ISOLATED.with(|isolated| isolated.replace(true)));
+    T::load_with_args(args)
+}
+
+/// 当前线程是否处于隔离加载中，此时不查找默认配置目录
+pub(crate) fn is_isolated() -> bool {
+    ISOLATED.with(Cell::get)
+}
+
+/// 未通过的样例
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusFailure {
+    /// 样例文件路径
+    pub path: PathBuf,
+    /// 失败原因
+    pub message: String,
+}
+
+/// 语料运行报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusReport {
+    /// 检查过的有效样例
+    pub valid: Vec<PathBuf>,
+    /// 检查过的无效样例
+    pub invalid: Vec<PathBuf>,
+    /// 未通过的样例
+    pub failures: Vec<CorpusFailure>,
+}
+
+impl CorpusReport {
+    /// 是否全部样例通过
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// 检查过的样例总数
+    pub fn total(&self) -> usize {
+        self.valid.len() + self.invalid.len()
+    }
+}
+
+impl fmt::Display for CorpusReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Checked {} sample(s) ({} valid, {} invalid), {} failure(s)",
+            self.total(),
+            self.valid.len(),
+            self.invalid.len(),
+            self.failures.len()
+        )?;
+        for failure in &self.failures {
+            writeln!(f, "  {}: {}", failure.path.display(), failure.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// 运行语料并断言全部样例通过，用于 `#[test]` 函数
+///
+/// 语料为空或存在未通过的样例时 panic，panic 信息列出每个未通过的样例。
+pub fn check_corpus<T: Config>(pattern: &str) -> CorpusReport {
+    let report = match run_corpus::<T>(pattern) {
+        Ok(report) => report,
+        Err(e) => panic!("Failed to read config corpus '{}': {}", pattern, e),
+    };
+    assert!(report.total() > 0, "No configuration samples found in corpus '{}'", pattern);
+    assert!(report.passed(), "Config corpus '{}' failed:\n{}", pattern, report);
+    report
+}
+
+/// 运行语料并返回报告，不做断言
+///
+/// `pattern` 为目录，或以 `/**`（递归）、`/*`（仅当前目录）结尾的目录模式。
+pub fn run_corpus<T: Config>(pattern: &str) -> Result<CorpusReport, QuantumConfigError> {
+    let (root, recursive) = if let Some(root) = pattern.strip_suffix("/**") {
+        (root, true)
+    } else if let Some(root) = pattern.strip_suffix("/*") {
+        (root, false)
+    } else {
+        (pattern, true)
+    };
+    let root = Path::new(root);
+
+    let mut samples = Vec::new();
+    collect_samples(root, recursive, &mut samples)?;
+    samples.sort();
+
+    let mut report = CorpusReport::default();
+    for path in samples {
+        let is_invalid = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .parent()
+            .is_some_and(|parent| parent.components().any(|c| c.as_os_str() == INVALID_DIR));
+        let failure = if is_invalid {
+            let failure = check_invalid::<T>(&path)?;
+            report.invalid.push(path.clone());
+            failure
+        } else {
+            let failure = T::load_from_file(&path).err().map(|e| format!("expected to load, got: {}", e));
+            report.valid.push(path.clone());
+            failure
+        };
+        if let Some(message) = failure {
+            report.failures.push(CorpusFailure { path, message });
+        }
+    }
+    Ok(report)
+}
+
+/// 检查无效样例，返回失败原因
+fn check_invalid<T: Config>(path: &Path) -> Result<Option<String>, QuantumConfigError> {
+    let mut sidecar = path.as_os_str().to_owned();
+    sidecar.push(".");
+    sidecar.push(EXPECTED_ERROR_EXTENSION);
+    let sidecar = PathBuf::from(sidecar);
+    let expected_code = if sidecar.is_file() {
+        let content = fs::read_to_string(&sidecar).map_err(|e| QuantumConfigError::Io {
+            source: e,
+            path: sidecar.clone(),
+        })?;
+        Some(content.trim().to_string())
+    } else {
+        None
+    };
+
+    Ok(match (T::load_from_file(path), expected_code) {
+        (Ok(_), _) => Some("expected to fail, but loaded successfully".to_string()),
+        (Err(e), Some(expected)) if e.code() != expected => {
+            Some(format!("expected error code '{}', got '{}': {}", expected, e.code(), e))
+        }
+        (Err(_), _) => None,
+    })
+}
+
+/// 收集目录中受支持格式的样例文件
+fn collect_samples(dir: &Path, recursive: bool, samples: &mut Vec<PathBuf>) -> Result<(), QuantumConfigError> {
+    let entries = fs::read_dir(dir).map_err(|e| QuantumConfigError::Io {
+        source: e,
+        path: dir.to_path_buf(),
+    })?;
+    for entry in entries {
+        let path = entry
+            .map_err(|e| QuantumConfigError::Io {
+                source: e,
+                path: dir.to_path_buf(),
+            })?
+            .path();
+        if path.is_dir() {
+            if recursive {
+                collect_samples(&path, recursive, samples)?;
+            }
+        } else if path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(FileFormat::from_extension)
+            .is_some()
+        {
+            samples.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    use tempfile::tempdir;
+
+    #[derive(crate::Config, Serialize, Deserialize, Debug, Default)]
+    struct CorpusTestConfig {
+        host: String,
+        port: u16,
+        #[config(max_len = 8)]
+        name: Option<String>,
+    }
+
+    fn write(root: &Path, relative: &str, content: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn test_env_guard_restores_variables() {
+        std::env::set_var("QC_TESTING_GUARD_KEEP", "original");
+        {
+            let mut env = EnvGuard::new();
+            env.set("QC_TESTING_GUARD_KEEP", "changed")
+                .set("QC_TESTING_GUARD_NEW", "1")
+                .set("QC_TESTING_GUARD_NEW", "2");
+            {
+                // 同一线程中嵌套的守卫不会死锁
+                let mut inner = EnvGuard::new();
+                inner.remove("QC_TESTING_GUARD_KEEP");
+                assert!(std::env::var_os("QC_TESTING_GUARD_KEEP").is_none());
+            }
+            assert_eq!(std::env::var("QC_TESTING_GUARD_KEEP").unwrap(), "changed");
+            assert_eq!(std::env::var("QC_TESTING_GUARD_NEW").unwrap(), "2");
+        }
+        assert_eq!(std::env::var("QC_TESTING_GUARD_KEEP").unwrap(), "original");
+        assert!(std::env::var_os("QC_TESTING_GUARD_NEW").is_none());
+        std::env::remove_var("QC_TESTING_GUARD_KEEP");
+
+        // panic 展开时同样恢复，锁不会因中毒而失效
+        let result = std::panic::catch_unwind(|| {
+            let mut env = EnvGuard::new();
+            env.set("QC_TESTING_GUARD_PANIC", "1");
+            panic!("test panic");
+        });
+        assert!(result.is_err());
+        assert!(std::env::var_os("QC_TESTING_GUARD_PANIC").is_none());
+        drop(EnvGuard::new());
+    }
+
+    #[test]
+    fn test_temp_config_writes_files_and_cleans_up() {
+        let config = TempConfig::json(r#"{"port": 8080}"#).with_file("config.d/10-port.toml", "port = 9090\n");
+        let dir = config.dir().to_path_buf();
+        assert_eq!(config.path(), dir.join("config.json"));
+        assert_eq!(fs::read_to_string(dir.join("config.d/10-port.toml")).unwrap(), "port = 9090\n");
+        assert_eq!(config.args()[1..], ["--config".to_string(), config.path().display().to_string()]);
+        assert_ne!(TempConfig::ini("").dir(), dir);
+
+        drop(config);
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn test_corpus_checks_valid_and_invalid_samples() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(root, "basic.toml", "host = \"localhost\"\nport = 8080\n");
+        write(root, "nested/service.json", r#"{"host": "db", "port": 5432}"#);
+        write(root, "README.md", "not a sample");
+        write(root, "invalid/missing_port.toml", "host = \"localhost\"\n");
+        write(root, "invalid/missing_port.toml.error", "missing_value\n");
+        write(root, "invalid/long_name.toml", "host = \"h\"\nport = 1\nname = \"far-too-long\"\n");
+        write(root, "invalid/long_name.toml.error", "constraint_violation");
+        write(root, "invalid/any_error.toml", "port = \"not a number\"\n");
+
+        let pattern = format!("{}/**", root.display());
+        let report = check_corpus::<CorpusTestConfig>(&pattern);
+        assert_eq!(report.valid.len(), 2);
+        assert_eq!(report.invalid.len(), 3);
+
+        let shallow = run_corpus::<CorpusTestConfig>(&format!("{}/*", root.display())).unwrap();
+        assert_eq!(shallow.total(), 1);
+    }
+
+    #[test]
+    fn test_corpus_reports_unexpected_results() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(root, "broken.toml", "host = \n");
+        write(root, "invalid/actually_valid.toml", "host = \"h\"\nport = 1\n");
+        write(root, "invalid/wrong_code.toml", "host = \"h\"\n");
+        write(root, "invalid/wrong_code.toml.error", "file_parse");
+
+        let report = run_corpus::<CorpusTestConfig>(&root.display().to_string()).unwrap();
+        assert!(!report.passed());
+        let messages: Vec<_> = report.failures.iter().map(|f| f.message.as_str()).collect();
+        assert_eq!(messages.len(), 3);
+        assert!(messages[0].starts_with("expected to load"));
+        assert!(messages[1].contains("loaded successfully"));
+        assert!(messages[2].contains("expected error code 'file_parse', got 'missing_value'"));
+
+        assert!(run_corpus::<CorpusTestConfig>("/nonexistent/corpus/**").is_err());
+    }
+}
@@ -0,0 +1,364 @@
+//! Quantum Config 值转换模块
+//!
+//! 在所有来源合并之后、反序列化之前，对匹配键模式的值执行转换，
+//! 例如去除所有字符串两端空白、把枚举类键统一为小写、去掉 URL 末尾的斜杠。
+//!
+//! 键模式使用 `.` 分隔的段：`*` 匹配单个段，`**` 匹配零个或多个段，
+//! 例如 `server.*`、`*.url`、`**`。
+//!
+//! 转换规则可以通过代码注册（[`TransformRegistry`] 或全局的 [`register`]），
+//! 也可以在字段上使用 `#[config(transform = "trim")]` 声明。
+
+use crate::error::QuantumConfigError;
+use figment::providers::Serialized;
+use figment::value::{Dict, Value};
+use figment::Figment;
+use std::fmt;
+use std::sync::{Arc, LazyLock, RwLock};
+
+/// 值转换器
+pub trait ValueTransformer: Send + Sync {
+    /// 转换单个叶子值
+    fn transform(&self, value: Value) -> Value;
+}
+
+impl<F> ValueTransformer for F
+where
+    F: Fn(Value) -> Value + Send + Sync,
+{
+    fn transform(&self, value: Value) -> Value {
+        self(value)
+    }
+}
+
+/// 内置转换器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinTransform {
+    /// 去除字符串两端空白
+    Trim,
+    /// 字符串转为小写
+    Lowercase,
+    /// 字符串转为大写
+    Uppercase,
+    /// 去除字符串末尾的 `/`
+    StripTrailingSlash,
+}
+
+impl BuiltinTransform {
+    /// 全部内置转换器的名称
+    pub const NAMES: &'static [&'static str] = &["trim", "lowercase", "uppercase", "strip_trailing_slash"];
+
+    /// 按名称查找内置转换器
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "trim" => Some(Self::Trim),
+            "lowercase" => Some(Self::Lowercase),
+            "uppercase" => Some(Self::Uppercase),
+            "strip_trailing_slash" => Some(Self::StripTrailingSlash),
+            _ => None,
+        }
+    }
+}
+
+impl ValueTransformer for BuiltinTransform {
+    fn transform(&self, value: Value) -> Value {
+        match value {
+            Value::String(tag, s) => {
+                let transformed = match self {
+                    Self::Trim => s.trim().to_string(),
+                    Self::Lowercase => s.to_lowercase(),
+                    Self::Uppercase => s.to_uppercase(),
+                    Self::StripTrailingSlash => s.trim_end_matches('/').to_string(),
+                };
+                Value::String(tag, transformed)
+            }
+            other => other,
+        }
+    }
+}
+
+/// 以 `.` 分隔的键模式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPattern {
+    /// 模式的各个段
+    segments: Vec<String>,
+}
+
+impl KeyPattern {
+    /// 解析键模式
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            segments: pattern.split('.').filter(|s| !s.is_empty()).map(str::to_string).collect(),
+        }
+    }
+
+    /// 判断键路径是否匹配
+    pub fn matches(&self, key: &str) -> bool {
+        let key: Vec<&str> = key.split('.').collect();
+        let pattern: Vec<&str> = self.segments.iter().map(String::as_str).collect();
+        matches_segments(&pattern, &key)
+    }
+}
+
+impl fmt::Display for KeyPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.segments.join("."))
+    }
+}
+
+/// 逐段匹配，`**` 可匹配零个或多个段
+fn matches_segments(pattern: &[&str], key: &[&str]) -> bool {
+    match (pattern.first(), key.first()) {
+        (None, None) => true,
+        (Some(&"**"), _) => {
+            matches_segments(&pattern[1..], key) || (!key.is_empty() && matches_segments(pattern, &key[1..]))
+        }
+        (Some(p), Some(k)) => (*p == "*" || p == k) && matches_segments(&pattern[1..], &key[1..]),
+        _ => false,
+    }
+}
+
+/// 转换规则注册表
+///
+/// 规则按注册顺序依次作用于匹配的键。
+#[derive(Clone, Default)]
+pub struct TransformRegistry {
+    /// 已注册的规则
+    rules: Vec<(KeyPattern, Arc<dyn ValueTransformer>)>,
+}
+
+impl fmt::Debug for TransformRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransformRegistry")
+            .field("patterns", &self.rules.iter().map(|(p, _)| p.to_string()).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// 全局转换规则
+static GLOBAL: LazyLock<RwLock<TransformRegistry>> = LazyLock::new(|| RwLock::new(TransformRegistry::new()));
+
+/// 注册一条全局转换规则，派生宏生成的加载方法都会应用全局规则
+pub fn register<T: ValueTransformer + 'static>(pattern: &str, transformer: T) {
+    GLOBAL
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register(pattern, transformer);
+}
+
+/// 清空全局转换规则
+pub fn clear_global() {
+    *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = TransformRegistry::new();
+}
+
+impl TransformRegistry {
+    /// 创建空的注册表
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 以全局规则为起点创建注册表
+    pub fn from_global() -> Self {
+        GLOBAL.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// 注册一条规则
+    pub fn register<T: ValueTransformer + 'static>(&mut self, pattern: &str, transformer: T) {
+        self.rules.push((KeyPattern::new(pattern), Arc::new(transformer)));
+    }
+
+    /// 注册一条规则（构建器风格）
+    pub fn with<T: ValueTransformer + 'static>(mut self, pattern: &str, transformer: T) -> Self {
+        self.register(pattern, transformer);
+        self
+    }
+
+    /// 按名称注册内置转换器
+    ///
+    /// # Errors
+    /// 名称不是内置转换器时返回错误
+    pub fn register_builtin(&mut self, pattern: &str, name: &str) -> Result<(), QuantumConfigError> {
+        let transformer = BuiltinTransform::from_name(name).ok_or_else(|| QuantumConfigError::InvalidValue {
+            key_path: pattern.to_string(),
+            message: format!(
+                "unknown transform '{}', expected one of: {}",
+                name,
+                BuiltinTransform::NAMES.join(", ")
+            ),
+        })?;
+        self.register(pattern, transformer);
+        Ok(())
+    }
+
+    /// 是否没有任何规则
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// 对整棵值树应用转换规则
+    pub fn apply(&self, value: Value) -> Value {
+        if self.is_empty() {
+            return value;
+        }
+        self.apply_at("", value)
+    }
+
+    /// 对合并后的 figment 应用转换规则
+    ///
+    /// 只有被转换改变的值会被覆盖，其余值保留原有的来源信息。
+    ///
+    /// # Errors
+    /// figment 中存在无法读取的来源时返回错误
+    pub fn apply_to_figment(&self, figment: Figment) -> Result<Figment, QuantumConfigError> {
+        if self.is_empty() {
+            return Ok(figment);
+        }
+        let value: Value = figment.extract()?;
+        let transformed = self.apply(value.clone());
+        match changed_values(&value, &transformed) {
+            Some(changed) => Ok(figment.merge(Serialized::defaults(changed))),
+            None => Ok(figment),
+        }
+    }
+
+    /// 递归应用规则，数组元素沿用数组自身的键
+    fn apply_at(&self, key: &str, value: Value) -> Value {
+        match value {
+            Value::Dict(tag, dict) => {
+                let dict: Dict = dict
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let child = if key.is_empty() { k.clone() } else { format!("{}.{}", key, k) };
+                        let v = self.apply_at(&child, v);
+                        (k, v)
+                    })
+                    .collect();
+                Value::Dict(tag, dict)
+            }
+            Value::Array(tag, items) => {
+                Value::Array(tag, items.into_iter().map(|v| self.apply_at(key, v)).collect())
+            }
+            leaf => self
+                .rules
+                .iter()
+                .filter(|(pattern, _)| pattern.matches(key))
+                .fold(leaf, |v, (_, transformer)| transformer.transform(v)),
+        }
+    }
+}
+
+/// 提取转换前后发生变化的部分，未变化时返回 `None`
+fn changed_values(original: &Value, transformed: &Value) -> Option<Value> {
+    match (original, transformed) {
+        (Value::Dict(_, before), Value::Dict(tag, after)) => {
+            let changed: Dict = after
+                .iter()
+                .filter_map(|(k, v)| {
+                    let diff = match before.get(k) {
+                        Some(old) => changed_values(old, v)?,
+                        None => v.clone(),
+                    };
+                    Some((k.clone(), diff))
+                })
+                .collect();
+            (!changed.is_empty()).then_some(Value::Dict(*tag, changed))
+        }
+        (before, after) if before == after => None,
+        (_, after) => Some(after.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use figment::providers::{Format, Toml};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Server {
+        name: String,
+        url: String,
+        mode: String,
+        tags: Vec<String>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestConfig {
+        title: String,
+        server: Server,
+    }
+
+    const CONTENT: &str = r#"
+title = "  demo  "
+[server]
+name = "  api  "
+url = "https://example.com/api/"
+mode = "Production"
+tags = [" a ", " b "]
+"#;
+
+    #[test]
+    fn test_key_pattern_matching() {
+        assert!(KeyPattern::new("**").matches("server.url"));
+        assert!(KeyPattern::new("server.*").matches("server.url"));
+        assert!(!KeyPattern::new("server.*").matches("server.tls.cert"));
+        assert!(KeyPattern::new("*.url").matches("server.url"));
+        assert!(KeyPattern::new("server.**").matches("server"));
+        assert!(KeyPattern::new("server.**").matches("server.tls.cert"));
+        assert!(!KeyPattern::new("server").matches("server.url"));
+    }
+
+    #[test]
+    fn test_apply_to_figment() {
+        let registry = TransformRegistry::new()
+            .with("**", BuiltinTransform::Trim)
+            .with("*.url", BuiltinTransform::StripTrailingSlash)
+            .with("server.mode", BuiltinTransform::Lowercase);
+
+        let figment = registry.apply_to_figment(Figment::from(Toml::string(CONTENT))).unwrap();
+        let config: TestConfig = figment.extract().unwrap();
+
+        assert_eq!(config.title, "demo");
+        assert_eq!(config.server.name, "api");
+        assert_eq!(config.server.url, "https://example.com/api");
+        assert_eq!(config.server.mode, "production");
+        assert_eq!(config.server.tags, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_closure_transformer_and_builtin_names() {
+        let mut registry = TransformRegistry::new();
+        registry.register_builtin("server.mode", "uppercase").unwrap();
+        registry.register("title", |value: Value| match value {
+            Value::String(tag, s) => Value::String(tag, s.replace(' ', "")),
+            other => other,
+        });
+
+        let figment = registry.apply_to_figment(Figment::from(Toml::string(CONTENT))).unwrap();
+        let config: TestConfig = figment.extract().unwrap();
+        assert_eq!(config.title, "demo");
+        assert_eq!(config.server.mode, "PRODUCTION");
+
+        let result = registry.register_builtin("title", "reverse");
+        assert!(matches!(result, Err(QuantumConfigError::InvalidValue { .. })));
+    }
+
+    #[test]
+    fn test_unchanged_values_keep_metadata() {
+        let registry = TransformRegistry::new().with("server.mode", BuiltinTransform::Lowercase);
+        let source = Figment::from(Toml::string(CONTENT));
+        let original = source.find_metadata("server.url").unwrap().name.clone();
+
+        let figment = registry.apply_to_figment(source).unwrap();
+        assert_eq!(figment.find_metadata("server.url").unwrap().name, original);
+        assert_eq!(figment.extract_inner::<String>("server.mode").unwrap(), "production");
+    }
+
+    #[test]
+    fn test_empty_registry_keeps_figment() {
+        let registry = TransformRegistry::new();
+        let figment = registry.apply_to_figment(Figment::from(Toml::string(CONTENT))).unwrap();
+        assert!(figment.find_metadata("title").is_some());
+        assert_eq!(figment.extract_inner::<String>("title").unwrap(), "  demo  ");
+    }
+}
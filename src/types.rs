@@ -0,0 +1,492 @@
+//! Quantum Config 配置值类型模块
+//!
+//! 提供可直接用作配置字段的值类型，在任意来源（文件、环境变量、命令行）中
+//! 都可以使用人类可读的写法：
+//! - [`Duration`]：`timeout = "30s"`、`"1h 30m"`、`"500ms"`，纯数字按秒处理；
+//! - [`ByteSize`]：`max_body_size = "16MB"`、`"512KiB"`，纯数字按字节处理。
+//!
+//! 不想更换字段类型时，也可以在 `std::time::Duration` 与 `u64` 字段上使用
+//! `#[serde(with = "quantum_config::types::duration")]` 或
+//! `#[serde(with = "quantum_config::types::byte_size")]`。
+//!
+//! `SocketAddr`、`IpAddr` 以及启用 `url` 特性后的 [`Url`] 可以直接从字符串反序列化；
+//! 在这些字段上使用 `#[serde(with = "quantum_config::types::parse")]`，
+//! 解析失败时错误信息会包含期望的类型与实际收到的值，并照常带上字段路径。
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+#[cfg(feature = "url")]
+pub use url::Url;
+
+/// 值解析失败
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct ParseValueError(String);
+
+/// 人类可读的时长，如 `"30s"`、`"1h 30m"`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(std::time::Duration);
+
+impl Duration {
+    /// 由标准库时长创建
+    pub const fn new(duration: std::time::Duration) -> Self {
+        Self(duration)
+    }
+
+    /// 转为标准库时长
+    pub const fn as_std(&self) -> std::time::Duration {
+        self.0
+    }
+}
+
+impl Deref for Duration {
+    type Target = std::time::Duration;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<std::time::Duration> for Duration {
+    fn from(duration: std::time::Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<Duration> for std::time::Duration {
+    fn from(duration: Duration) -> Self {
+        duration.0
+    }
+}
+
+impl FromStr for Duration {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(secs) = s.parse::<u64>() {
+            return Ok(Self(std::time::Duration::from_secs(secs)));
+        }
+        humantime::parse_duration(s)
+            .map(Self)
+            .map_err(|e| ParseValueError(format!("invalid duration '{}': {}", s, e)))
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", humantime::format_duration(self.0))
+    }
+}
+
+impl Serialize for Duration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Duration {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(DurationVisitor)
+    }
+}
+
+/// 接受时长字符串与秒数的访问器
+struct DurationVisitor;
+
+impl Visitor<'_> for DurationVisitor {
+    type Value = Duration;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a duration such as \"30s\" or a number of seconds")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Duration(std::time::Duration::from_secs(v)))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v)
+            .map(|secs| Duration(std::time::Duration::from_secs(secs)))
+            .map_err(|_| E::custom(format!("invalid duration '{}': must not be negative", v)))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        std::time::Duration::try_from_secs_f64(v)
+            .map(Duration)
+            .map_err(|e| E::custom(format!("invalid duration '{}': {}", v, e)))
+    }
+}
+
+/// 人类可读的字节数，如 `"16MB"`、`"512KiB"`
+///
+/// 十进制单位（`KB`、`MB`、`GB`、`TB`、`PB`）按 1000 进位，
+/// 二进制单位（`KiB`、`MiB`、`GiB`、`TiB`、`PiB`）按 1024 进位，单位不区分大小写。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ByteSize(u64);
+
+/// 字节单位及其倍数，按倍数从大到小排列
+const BYTE_UNITS: &[(&str, u64)] = &[
+    ("PiB", 1 << 50),
+    ("PB", 1_000_000_000_000_000),
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("KB", 1_000),
+];
+
+impl ByteSize {
+    /// 由字节数创建
+    pub const fn new(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    /// 字节数
+    pub const fn as_u64(&self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for ByteSize {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+/// 单位后缀对应的倍数
+fn unit_multiplier(unit: &str) -> Option<u64> {
+    let unit = unit.to_ascii_lowercase();
+    match unit.as_str() {
+        "" | "b" => Some(1),
+        // 省略末尾 `B` 的简写，如 `16M`、`512Ki`
+        "k" | "m" | "g" | "t" | "p" | "ki" | "mi" | "gi" | "ti" | "pi" => unit_multiplier(&format!("{}b", unit)),
+        _ => BYTE_UNITS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(&unit))
+            .map(|(_, multiplier)| *multiplier),
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ParseValueError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = |reason: &str| ParseValueError(format!("invalid byte size '{}': {}", s, reason));
+        let split = s.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(s.len());
+        let (number, unit) = s.split_at(split);
+        if number.is_empty() {
+            return Err(invalid("expected a number"));
+        }
+        let multiplier = unit_multiplier(unit.trim()).ok_or_else(|| invalid("unknown unit"))?;
+
+        if let Ok(value) = number.parse::<u64>() {
+            return value.checked_mul(multiplier).map(Self).ok_or_else(|| invalid("value too large"));
+        }
+        let value: f64 = number.parse().map_err(|_| invalid("expected a number"))?;
+        let bytes = (value * multiplier as f64).round();
+        if bytes >= u64::MAX as f64 {
+            return Err(invalid("value too large"));
+        }
+        Ok(Self(bytes as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    /// 使用能整除的最大单位输出，便于回读
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 > 0 {
+            if let Some((name, multiplier)) = BYTE_UNITS.iter().find(|(_, m)| self.0.is_multiple_of(*m)) {
+                return write!(f, "{}{}", self.0 / multiplier, name);
+            }
+        }
+        write!(f, "{}B", self.0)
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ByteSizeVisitor)
+    }
+}
+
+/// 接受字节数字符串与整数的访问器
+struct ByteSizeVisitor;
+
+impl Visitor<'_> for ByteSizeVisitor {
+    type Value = ByteSize;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a byte size such as \"16MB\" or a number of bytes")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(ByteSize(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v)
+            .map(ByteSize)
+            .map_err(|_| E::custom(format!("invalid byte size '{}': must not be negative", v)))
+    }
+}
+
+/// 用于 `std::time::Duration` 字段的 serde 辅助模块
+///
+/// ```ignore
+/// #[serde(with = "quantum_config::types::duration")]
+/// timeout: std::time::Duration,
+/// ```
+pub mod duration {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// 以人类可读字符串序列化
+    pub fn serialize<S: Serializer>(value: &std::time::Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        super::Duration(*value).serialize(serializer)
+    }
+
+    /// 从时长字符串或秒数反序列化
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<std::time::Duration, D::Error> {
+        super::Duration::deserialize(deserializer).map(|d| d.0)
+    }
+}
+
+/// 用于以 `u64` 表示字节数的字段的 serde 辅助模块
+///
+/// ```ignore
+/// #[serde(with = "quantum_config::types::byte_size")]
+/// max_body_size: u64,
+/// ```
+pub mod byte_size {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// 以人类可读字符串序列化
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        super::ByteSize(*value).serialize(serializer)
+    }
+
+    /// 从字节数字符串或整数反序列化
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        super::ByteSize::deserialize(deserializer).map(|size| size.0)
+    }
+}
+
+/// 用于任意实现了 `FromStr` 的字段的 serde 辅助模块
+///
+/// 适用于 `SocketAddr`、`IpAddr`、`Url` 等类型；数字与布尔值会先转为字符串再解析，
+/// 命令行与环境变量中的值因此可以无差别地使用。
+///
+/// ```ignore
+/// #[serde(with = "quantum_config::types::parse")]
+/// listen: std::net::SocketAddr,
+/// ```
+pub mod parse {
+    use serde::de::{self, Deserializer, Visitor};
+    use serde::Serializer;
+    use std::fmt::{self, Display};
+    use std::marker::PhantomData;
+    use std::str::FromStr;
+
+    /// 以 `Display` 输出序列化
+    pub fn serialize<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    /// 从字符串解析反序列化
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(ParseVisitor(PhantomData))
+    }
+
+    /// 类型名的最后一段，如 `SocketAddr`
+    fn short_type_name<T>() -> &'static str {
+        let name = std::any::type_name::<T>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// 先取得字符串再调用 `FromStr` 的访问器
+    struct ParseVisitor<T>(PhantomData<T>);
+
+    impl<T> ParseVisitor<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        fn parse<E: de::Error>(v: &str) -> Result<T, E> {
+            v.parse()
+                .map_err(|e| E::custom(format!("expected {}, found '{}': {}", short_type_name::<T>(), v, e)))
+        }
+    }
+
+    impl<T> Visitor<'_> for ParseVisitor<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        type Value = T;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "a string containing {}", short_type_name::<T>())
+        }
+
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+            Self::parse(v)
+        }
+
+        fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+            Self::parse(&v.to_string())
+        }
+
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+            Self::parse(&v.to_string())
+        }
+
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+            Self::parse(&v.to_string())
+        }
+
+        fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+            Self::parse(&v.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration as StdDuration;
+
+    #[test]
+    fn test_duration_parsing() {
+        assert_eq!("30s".parse::<Duration>().unwrap().as_std(), StdDuration::from_secs(30));
+        assert_eq!("1h 30m".parse::<Duration>().unwrap().as_std(), StdDuration::from_secs(5400));
+        assert_eq!("500ms".parse::<Duration>().unwrap().as_std(), StdDuration::from_millis(500));
+        assert_eq!("45".parse::<Duration>().unwrap().as_std(), StdDuration::from_secs(45));
+        let error = "soon".parse::<Duration>().unwrap_err();
+        assert!(error.to_string().starts_with("invalid duration 'soon'"));
+    }
+
+    #[test]
+    fn test_duration_serde_round_trip() {
+        let duration: Duration = serde_json::from_value(json!("1m 5s")).unwrap();
+        assert_eq!(*duration, StdDuration::from_secs(65));
+        assert_eq!(serde_json::to_value(duration).unwrap(), json!("1m 5s"));
+
+        let duration: Duration = serde_json::from_value(json!(10)).unwrap();
+        assert_eq!(duration.as_secs(), 10);
+        let duration: Duration = serde_json::from_value(json!(1.5)).unwrap();
+        assert_eq!(duration.as_millis(), 1500);
+        assert!(serde_json::from_value::<Duration>(json!(-1)).is_err());
+    }
+
+    #[test]
+    fn test_byte_size_parsing() {
+        assert_eq!("16MB".parse::<ByteSize>().unwrap().as_u64(), 16_000_000);
+        assert_eq!("16MiB".parse::<ByteSize>().unwrap().as_u64(), 16 * 1024 * 1024);
+        assert_eq!("512 kib".parse::<ByteSize>().unwrap().as_u64(), 512 * 1024);
+        assert_eq!("2k".parse::<ByteSize>().unwrap().as_u64(), 2_000);
+        assert_eq!("1.5GB".parse::<ByteSize>().unwrap().as_u64(), 1_500_000_000);
+        assert_eq!("1024".parse::<ByteSize>().unwrap().as_u64(), 1024);
+        assert_eq!("7B".parse::<ByteSize>().unwrap().as_u64(), 7);
+        assert!("MB".parse::<ByteSize>().is_err());
+        assert!("10 parsecs".parse::<ByteSize>().is_err());
+        assert!("99999999PB".parse::<ByteSize>().is_err());
+    }
+
+    #[test]
+    fn test_byte_size_display_uses_exact_unit() {
+        assert_eq!(ByteSize::new(16_000_000).to_string(), "16MB");
+        assert_eq!(ByteSize::new(16 * 1024 * 1024).to_string(), "16MiB");
+        assert_eq!(ByteSize::new(1_500).to_string(), "1500B");
+        assert_eq!(ByteSize::new(0).to_string(), "0B");
+        for size in [ByteSize::new(1 << 30), ByteSize::new(3_000), ByteSize::new(123)] {
+            assert_eq!(size.to_string().parse::<ByteSize>().unwrap(), size);
+        }
+    }
+
+    #[test]
+    fn test_parse_helper_reports_type_and_value() {
+        use std::net::{IpAddr, SocketAddr};
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Listen {
+            #[serde(with = "crate::types::parse")]
+            addr: SocketAddr,
+            #[serde(with = "crate::types::parse")]
+            ip: IpAddr,
+        }
+
+        let listen: Listen = serde_json::from_value(json!({"addr": "[::1]:8080", "ip": "10.0.0.1"})).unwrap();
+        assert_eq!(listen.addr, "[::1]:8080".parse::<SocketAddr>().unwrap());
+        assert_eq!(serde_json::to_value(&listen).unwrap(), json!({"addr": "[::1]:8080", "ip": "10.0.0.1"}));
+
+        let error = serde_json::from_value::<Listen>(json!({"addr": "localhost", "ip": "10.0.0.1"}))
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.starts_with("expected SocketAddr, found 'localhost'"), "{}", error);
+        let error = serde_json::from_value::<Listen>(json!({"addr": "[::1]:1", "ip": 42})).err().unwrap().to_string();
+        assert!(error.starts_with("expected IpAddr, found '42'"), "{}", error);
+    }
+
+    #[cfg(feature = "url")]
+    #[test]
+    fn test_url_deserializes_from_string() {
+        let url: Url = serde_json::from_value(json!("https://example.com/api")).unwrap();
+        assert_eq!(url.host_str(), Some("example.com"));
+        assert!(serde_json::from_value::<Url>(json!("not a url")).is_err());
+    }
+
+    #[test]
+    fn test_serde_with_helpers() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Limits {
+            #[serde(with = "crate::types::duration")]
+            timeout: StdDuration,
+            #[serde(with = "crate::types::byte_size")]
+            max_body_size: u64,
+        }
+
+        let limits: Limits = serde_json::from_value(json!({"timeout": "2m", "max_body_size": "4KiB"})).unwrap();
+        assert_eq!(limits.timeout, StdDuration::from_secs(120));
+        assert_eq!(limits.max_body_size, 4096);
+        assert_eq!(
+            serde_json::to_value(&limits).unwrap(),
+            json!({"timeout": "2m", "max_body_size": "4KiB"})
+        );
+    }
+}
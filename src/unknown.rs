@@ -0,0 +1,296 @@
+//! 未知键检测
+//!
+//! serde 默认忽略结构体中不存在的键，拼错的键（如 `pool_szie`）因此会被静默丢弃，
+//! 字段悄悄使用默认值。结构体标注 `#[config(deny_unknown)]` 后，
+//! 生成的 `load()` 在合并全部来源之后、提取之前检查合并结果中无法对应到任何字段的键：
+//!
+//! - `#[config(deny_unknown)]`：以 [`QuantumConfigError::UnknownKeys`] 报错；
+//! - `#[config(deny_unknown = "warn")]`：仅输出警告日志，照常加载。
+//!
+//! 每个未知键都附带来源（文件、环境变量等），并在编辑距离足够小时给出“是否想写”的建议。
+//! 已知键以 [`Config::key_shape`] 为准：默认值的序列化结果按字段元数据修正，
+//! 序列化时省略的字段（如 `skip_serializing_if = "Option::is_none"`）同样是已知键。
+//! 值为 `null`（未设置的 `Option`）或空映射（`HashMap` 等）的键接受任意子键。
+//! 内置命令行参数对应的键（如 `--config` 写入的 `config_file`）不视为未知键。
+
+use crate::config::Config;
+use crate::error::{describe_origin, QuantumConfigError};
+use crate::logging::log_warn;
+use crate::meta::StructMeta;
+#[cfg(feature = "cli")]
+use crate::providers::clap_provider::COMMON_MAPPINGS;
+use figment::Figment;
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+/// 给出建议时允许的最大编辑距离
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// 发现未知键时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnknownKeyPolicy {
+    /// 输出警告日志，照常加载
+    Warn,
+    /// 加载失败
+    #[default]
+    Deny,
+}
+
+impl UnknownKeyPolicy {
+    /// 从属性值解析：`"warn"` 或 `"deny"`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "warn" => Some(Self::Warn),
+            "deny" => Some(Self::Deny),
+            _ => None,
+        }
+    }
+}
+
+/// 单个未知键
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnknownKey {
+    /// 以 `.` 分隔的键路径
+    pub key_path: String,
+    /// 该键的来源，如 `APP_POOL_SIZ from environment variables`
+    pub origin: Option<String>,
+    /// 编辑距离最近的已知键
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for UnknownKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}'", self.key_path)?;
+        if let Some(origin) = &self.origin {
+            write!(f, " ({})", origin)?;
+        }
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean '{}'?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// 按 `policy` 检查合并结果中的未知键
+///
+/// 合并结果本身无法读取时不做检查，由随后的提取报告错误。
+///
+/// # Errors
+/// `policy` 为 [`UnknownKeyPolicy::Deny`] 且存在未知键时返回 [`QuantumConfigError::UnknownKeys`]
+pub fn enforce<T: Config>(figment: &Figment, policy: UnknownKeyPolicy) -> Result<(), QuantumConfigError> {
+    let keys = detect::<T>(figment)?;
+    if keys.is_empty() {
+        return Ok(());
+    }
+    match policy {
+        UnknownKeyPolicy::Deny => Err(QuantumConfigError::UnknownKeys { keys }),
+        UnknownKeyPolicy::Warn => {
+            for key in &keys {
+                log_warn!("Ignoring unknown configuration key {}", key);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 列出合并结果中无法对应到 `T` 任何字段的键
+///
+/// # Errors
+/// 默认值无法序列化时返回错误
+pub fn detect<T: Config>(figment: &Figment) -> Result<Vec<UnknownKey>, QuantumConfigError> {
+    let Ok(value) = figment.extract::<JsonValue>() else {
+        return Ok(Vec::new());
+    };
+    let shape = T::key_shape()?;
+    let known = known_keys(&shape);
+    Ok(find_unknown_keys(&value, &shape)
+        .into_iter()
+        .filter(|key_path| !is_builtin_cli_key(key_path))
+        .map(|key_path| {
+            let origin = figment.find_metadata(&key_path).map(|metadata| {
+                let path: Vec<&str> = key_path.split('.').collect();
+                let (origin, origin_key) = describe_origin(metadata, figment.profile(), &path);
+                format!("{} from {}", origin_key, origin)
+            });
+            let suggestion = suggest(&key_path, &known).map(str::to_string);
+            UnknownKey { key_path, origin, suggestion }
+        })
+        .collect())
+}
+
+/// 是否为内置命令行参数写入的键（或其上级键，如 `output`）
+#[cfg(feature = "cli")]
+fn is_builtin_cli_key(key_path: &str) -> bool {
+    COMMON_MAPPINGS.iter().any(|(_, key)| {
+        key.strip_prefix(key_path)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// 未启用 `cli` 特性时没有内置命令行参数
+#[cfg(not(feature = "cli"))]
+fn is_builtin_cli_key(key_path: &str) -> bool {
+    let _ = key_path;
+    false
+}
+
+/// 按字段元数据补全键结构：序列化时省略的字段补为 `null`
+///
+/// `#[serde(flatten)]` 字段没有自己的键，保持不变。
+pub fn apply_field_meta(shape: &mut JsonValue, meta: &StructMeta) {
+    let JsonValue::Object(map) = shape else {
+        return;
+    };
+    for field in meta.fields.iter().filter(|field| !field.is_flatten) {
+        map.entry(field.config_key_name()).or_insert(JsonValue::Null);
+    }
+}
+
+/// 以嵌套结构体自身的键结构替换 `prefix` 键下的结构，`prefix` 为空时（`#[serde(flatten)]`）并入顶层
+pub fn merge_nested_shape(shape: &mut JsonValue, prefix: &str, nested: JsonValue) {
+    let JsonValue::Object(map) = shape else {
+        return;
+    };
+    if !prefix.is_empty() {
+        map.insert(prefix.to_string(), nested);
+    } else if let JsonValue::Object(nested) = nested {
+        map.extend(nested);
+    }
+}
+
+/// 收集 `value` 中存在而 `shape` 中不存在的键路径
+///
+/// `shape` 中值为 `null` 或空映射的键接受任意子键；数组不逐项比较。
+pub fn find_unknown_keys(value: &JsonValue, shape: &JsonValue) -> Vec<String> {
+    let mut unknown = Vec::new();
+    collect_unknown_keys(value, shape, "", &mut unknown);
+    unknown
+}
+
+fn collect_unknown_keys(value: &JsonValue, shape: &JsonValue, prefix: &str, unknown: &mut Vec<String>) {
+    let (JsonValue::Object(map), JsonValue::Object(shape_map)) = (value, shape) else {
+        return;
+    };
+    if shape_map.is_empty() {
+        return;
+    }
+    for (key, child) in map {
+        let path = join_path(prefix, key);
+        match shape_map.get(key) {
+            Some(child_shape) => collect_unknown_keys(child, child_shape, &path, unknown),
+            None => unknown.push(path),
+        }
+    }
+}
+
+/// `shape` 中的全部键路径（含中间节点）
+pub fn known_keys(shape: &JsonValue) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_known_keys(shape, "", &mut paths);
+    paths
+}
+
+fn collect_known_keys(shape: &JsonValue, prefix: &str, paths: &mut Vec<String>) {
+    if let JsonValue::Object(map) = shape {
+        for (key, child) in map {
+            let path = join_path(prefix, key);
+            collect_known_keys(child, &path, paths);
+            paths.push(path);
+        }
+    }
+}
+
+fn join_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+/// 在候选项中查找与 `key` 编辑距离最近且不超过上限的一个
+pub fn suggest<'a, S: AsRef<str>>(key: &str, candidates: &'a [S]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(key, candidate.as_ref()), candidate.as_ref()))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// 两个字符串之间的 Levenshtein 编辑距离（按字符计）
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_find_unknown_keys() {
+        let shape = json!({"port": 0, "server": {"host": ""}, "labels": {}, "tls": null});
+        let value = json!({
+            "port": 1,
+            "prot": 2,
+            "server": {"host": "h", "hots": "x"},
+            "labels": {"any": "thing"},
+            "tls": {"cert": "c"}
+        });
+        assert_eq!(find_unknown_keys(&value, &shape), ["prot", "server.hots"]);
+        assert_eq!(known_keys(&shape), ["labels", "port", "server.host", "server", "tls"]);
+    }
+
+    #[test]
+    fn test_apply_field_meta_and_nested_shapes() {
+        use crate::meta::FieldMeta;
+
+        let mut meta = StructMeta::new("Shape", true);
+        let mut skipped = FieldMeta::new("token", "String");
+        skipped.is_skipped = true;
+        meta.add_field(skipped);
+        meta.add_field(FieldMeta::new("port", "u16"));
+
+        let mut shape = json!({"port": 0});
+        apply_field_meta(&mut shape, &meta);
+        assert_eq!(shape, json!({"port": 0, "token": null}));
+
+        merge_nested_shape(&mut shape, "tls", json!({"cert": ""}));
+        merge_nested_shape(&mut shape, "", json!({"extra": 1}));
+        assert_eq!(shape, json!({"port": 0, "token": null, "tls": {"cert": ""}, "extra": 1}));
+    }
+
+    #[test]
+    fn test_suggest_nearest_key() {
+        let known = ["port", "server.host"];
+        assert_eq!(suggest("prot", &known), Some("port"));
+        assert_eq!(suggest("server.hots", &known), Some("server.host"));
+        assert_eq!(suggest("database", &known), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_unknown_key_display() {
+        let key = UnknownKey {
+            key_path: "pool_siz".to_string(),
+            origin: Some("APP_POOL_SIZ from environment".to_string()),
+            suggestion: Some("pool_size".to_string()),
+        };
+        assert_eq!(key.to_string(), "'pool_siz' (APP_POOL_SIZ from environment), did you mean 'pool_size'?");
+        assert_eq!(UnknownKeyPolicy::parse("warn"), Some(UnknownKeyPolicy::Warn));
+        assert_eq!(UnknownKeyPolicy::parse("error"), None);
+    }
+}
@@ -0,0 +1,439 @@
+//! 字段约束校验
+//!
+//! `#[derive(Config)]` 为带有约束属性的字段生成对本模块校验函数的调用，
+//! 在配置提取完成后由 [`crate::Config::validate`] 执行：
+//!
+//! | 字段属性 | 校验函数 |
+//! |---|---|
+//! | `#[config(max_len = N)]` | [`check_max_len`] |
+//! | `#[config(range(min = 1, max = 65535))]` | [`check_range`] |
+//! | `#[config(regex = "^[a-z]+$")]`（需要 `regex` 特性） | `check_regex` |
+//! | `#[config(one_of("json", "text"))]` | [`check_one_of`] |
+//! | `#[config(non_empty)]` | [`check_non_empty`] |
+//!
+//! 各校验器的错误全部收集后由 [`collect_errors`] 汇总，一次报告所有出错字段。
+//! 对于 `#[config(secret)]` 字段，错误信息不回显字段值。
+//! 通过 `load()` 加载时，错误还会指出出错值的来源，参见 [`QuantumConfigError::with_origin`]。
+//!
+//! 正则表达式的语法在编译期由派生宏检查，编译结果缓存在生成代码的静态变量中，每次校验不再重新编译。
+//!
+//! 跨字段的业务规则通过实现 [`Validate`] 表达；结构体标注 `#[config(validate)]` 后，
+//! 生成的 `load()` 与 [`crate::Config::validate`] 在字段约束之后调用它，
+//! 并以 [`QuantumConfigError::ValidationFailed`] 返回全部错误。
+
+use crate::error::QuantumConfigError;
+use serde::Serialize;
+#[cfg(feature = "regex")]
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use std::fmt;
+
+/// 单个字段的校验错误
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationError {
+    /// 以 `.` 分隔的字段路径
+    pub field: String,
+    /// 错误描述
+    pub message: String,
+    /// 出错值的来源，由 [`QuantumConfigError::with_origin`] 补充
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<String>,
+}
+
+impl ValidationError {
+    /// 创建字段校验错误
+    pub fn new<F: Into<String>, M: Into<String>>(field: F, message: M) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+            origin: None,
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)?;
+        if let Some(origin) = &self.origin {
+            write!(f, " ({})", origin)?;
+        }
+        Ok(())
+    }
+}
+
+/// 自定义配置校验
+///
+/// 返回全部校验错误而不是遇到第一个就停止，便于一次修正所有问题。
+/// 方法名与 [`crate::Config::validate`] 不同，两个 trait 同时在作用域内时调用不会产生歧义。
+pub trait Validate {
+    /// 校验业务规则，失败时返回每个出错字段的错误
+    fn validate_rules(&self) -> Result<(), Vec<ValidationError>>;
+}
+
+/// 校验字段中所有字符串的长度（按字符计）不超过 `max_len`
+///
+/// 字段可以是字符串、`Option<String>` 或字符串集合；非字符串的值不参与校验。
+pub fn check_max_len<V: Serialize + ?Sized>(
+    key_path: &str,
+    value: &V,
+    max_len: usize,
+    secret: bool,
+) -> Result<(), QuantumConfigError> {
+    let value = to_json(key_path, value)?;
+    check_strings(key_path, &value, max_len, secret)
+}
+
+/// 校验字段中所有数值位于 `[min, max]` 区间内，未指定的边界不做限制
+///
+/// 字段可以是数值、`Option` 或数值集合；非数值的值不参与校验。
+pub fn check_range<V: Serialize + ?Sized>(
+    key_path: &str,
+    value: &V,
+    min: Option<f64>,
+    max: Option<f64>,
+    secret: bool,
+) -> Result<(), QuantumConfigError> {
+    let value = to_json(key_path, value)?;
+    for leaf in leaves(&value) {
+        let Some(number) = leaf.as_f64() else {
+            continue;
+        };
+        if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+            let bounds = format!(
+                "[{}, {}]",
+                min.map_or("-inf".to_string(), |min| min.to_string()),
+                max.map_or("inf".to_string(), |max| max.to_string())
+            );
+            return Err(violation(key_path, format!("{} is out of range {}", display_value(leaf, secret), bounds)));
+        }
+    }
+    Ok(())
+}
+
+/// `#[config(regex = "...")]` 的正则表达式：自动锚定首尾，并保留原始写法用于错误信息
+#[cfg(feature = "regex")]
+#[derive(Debug, Clone)]
+pub struct FieldRegex {
+    pattern: String,
+    regex: Regex,
+}
+
+#[cfg(feature = "regex")]
+impl FieldRegex {
+    /// 编译正则表达式，字符串须完整匹配
+    pub fn new(pattern: &str) -> Result<Self, QuantumConfigError> {
+        let regex = Regex::new(&format!("^(?:{})$", pattern))
+            .map_err(|e| QuantumConfigError::ValidationError(format!("invalid regex '{}': {}", pattern, e)))?;
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex,
+        })
+    }
+
+    /// 原始写法（未锚定）
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+}
+
+/// 校验字段中所有字符串完整匹配正则表达式
+#[cfg(feature = "regex")]
+pub fn check_regex<V: Serialize + ?Sized>(
+    key_path: &str,
+    value: &V,
+    regex: &FieldRegex,
+    secret: bool,
+) -> Result<(), QuantumConfigError> {
+    let value = to_json(key_path, value)?;
+    for leaf in leaves(&value) {
+        if let Some(s) = leaf.as_str() {
+            if !regex.regex.is_match(s) {
+                return Err(violation(
+                    key_path,
+                    format!("{} does not match pattern '{}'", display_value(leaf, secret), regex.pattern),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 校验字段中的每个值都是允许的取值之一（数值与布尔值按其文本形式比较）
+pub fn check_one_of<V: Serialize + ?Sized>(
+    key_path: &str,
+    value: &V,
+    allowed: &[&str],
+    secret: bool,
+) -> Result<(), QuantumConfigError> {
+    let value = to_json(key_path, value)?;
+    for leaf in leaves(&value) {
+        let text = match leaf {
+            JsonValue::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        if !allowed.contains(&text.as_str()) {
+            return Err(violation(
+                key_path,
+                format!("{} is not one of: {}", display_value(leaf, secret), allowed.join(", ")),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 校验字段非空：字符串不全为空白，集合与映射不为空，`Option` 不为 `None`
+pub fn check_non_empty<V: Serialize + ?Sized>(key_path: &str, value: &V) -> Result<(), QuantumConfigError> {
+    let empty = match to_json(key_path, value)? {
+        JsonValue::Null => true,
+        JsonValue::String(s) => s.trim().is_empty(),
+        JsonValue::Array(items) => items.is_empty(),
+        JsonValue::Object(map) => map.is_empty(),
+        _ => false,
+    };
+    if empty {
+        return Err(violation(key_path, "must not be empty".to_string()));
+    }
+    Ok(())
+}
+
+/// 将字段值序列化为 JSON 以便统一校验
+fn to_json<V: Serialize + ?Sized>(key_path: &str, value: &V) -> Result<JsonValue, QuantumConfigError> {
+    serde_json::to_value(value).map_err(|e| {
+        QuantumConfigError::Internal(format!("Failed to serialize field '{}' for validation: {}", key_path, e))
+    })
+}
+
+/// 收集 JSON 值中的标量叶子节点（忽略 `null`）
+fn leaves(value: &JsonValue) -> Vec<&JsonValue> {
+    match value {
+        JsonValue::Null => Vec::new(),
+        JsonValue::Array(items) => items.iter().flat_map(leaves).collect(),
+        JsonValue::Object(map) => map.values().flat_map(leaves).collect(),
+        leaf => vec![leaf],
+    }
+}
+
+/// 汇总各校验器的错误：没有错误时返回 `Ok`，只有一个时原样返回，
+/// 多个时合并为 [`QuantumConfigError::ValidationFailed`]
+///
+/// 校验以外的错误（例如字段无法序列化）优先返回。
+pub fn collect_errors(errors: Vec<QuantumConfigError>) -> Result<(), QuantumConfigError> {
+    if errors.len() <= 1 {
+        return errors.into_iter().next().map_or(Ok(()), Err);
+    }
+    let mut collected = Vec::new();
+    for error in errors {
+        match error {
+            QuantumConfigError::ConstraintViolation { key_path, message, origin } => collected.push(ValidationError {
+                field: key_path,
+                message,
+                origin,
+            }),
+            QuantumConfigError::ValidationFailed { errors } => collected.extend(errors),
+            other => return Err(other),
+        }
+    }
+    Err(QuantumConfigError::ValidationFailed { errors: collected })
+}
+
+/// 嵌套结构体的校验错误，键路径加上外层字段的键 `prefix`（`#[serde(flatten)]` 字段为空）
+pub fn nested_error(prefix: &str, error: QuantumConfigError) -> QuantumConfigError {
+    let nest = |key_path: String| crate::meta::nested_key_path(prefix, &key_path);
+    match error {
+        QuantumConfigError::ConstraintViolation { key_path, message, origin } => QuantumConfigError::ConstraintViolation {
+            key_path: nest(key_path),
+            message,
+            origin,
+        },
+        QuantumConfigError::InvalidValue { key_path, message } => QuantumConfigError::InvalidValue {
+            key_path: nest(key_path),
+            message,
+        },
+        QuantumConfigError::ValidationFailed { errors } => QuantumConfigError::ValidationFailed {
+            errors: errors
+                .into_iter()
+                .map(|error| ValidationError {
+                    field: nest(error.field),
+                    ..error
+                })
+                .collect(),
+        },
+        other => other,
+    }
+}
+
+/// 构造字段约束错误，来源由调用方通过 [`QuantumConfigError::with_origin`] 补充
+fn violation(key_path: &str, message: String) -> QuantumConfigError {
+    QuantumConfigError::ConstraintViolation {
+        key_path: key_path.to_string(),
+        message,
+        origin: None,
+    }
+}
+
+/// 错误信息中显示的字符串值的最大字符数，超出部分以 `…` 省略
+const MAX_DISPLAYED_CHARS: usize = 32;
+
+/// 敏感字段的值以占位符显示，过长的字符串只显示开头
+fn display_value(value: &JsonValue, secret: bool) -> String {
+    match value {
+        _ if secret => "value (redacted)".to_string(),
+        JsonValue::String(s) => format!("value \"{}\"", preview(s)),
+        other => format!("value {}", other),
+    }
+}
+
+/// 截取字符串开头的 [`MAX_DISPLAYED_CHARS`] 个字符
+fn preview(s: &str) -> String {
+    match s.char_indices().nth(MAX_DISPLAYED_CHARS) {
+        Some((end, _)) => format!("{}…", &s[..end]),
+        None => s.to_string(),
+    }
+}
+
+/// 递归校验 JSON 值中的字符串叶子节点
+fn check_strings(key_path: &str, value: &JsonValue, max_len: usize, secret: bool) -> Result<(), QuantumConfigError> {
+    match value {
+        JsonValue::String(s) => {
+            let len = s.chars().count();
+            if len <= max_len {
+                return Ok(());
+            }
+            let message = if secret {
+                format!("length {} exceeds maximum of {} (value redacted)", len, max_len)
+            } else {
+                format!("value \"{}\" has length {}, exceeding maximum of {}", preview(s), len, max_len)
+            };
+            Err(violation(key_path, message))
+        }
+        JsonValue::Array(items) => items
+            .iter()
+            .try_for_each(|item| check_strings(key_path, item, max_len, secret)),
+        JsonValue::Object(map) => map
+            .values()
+            .try_for_each(|item| check_strings(key_path, item, max_len, secret)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_max_len_accepts_short_values() {
+        assert!(check_max_len("name", "short", 5, false).is_ok());
+        assert!(check_max_len("name", &None::<String>, 5, false).is_ok());
+        assert!(check_max_len("tags", &vec!["a", "bb"], 2, false).is_ok());
+        // 按字符而非字节计算长度
+        assert!(check_max_len("name", "配置", 2, false).is_ok());
+        // 非字符串值不参与校验
+        assert!(check_max_len("port", &65535u16, 1, false).is_ok());
+    }
+
+    struct Limits {
+        min: u32,
+        max: u32,
+    }
+
+    impl Validate for Limits {
+        fn validate_rules(&self) -> Result<(), Vec<ValidationError>> {
+            let mut errors = Vec::new();
+            if self.max == 0 {
+                errors.push(ValidationError::new("limits.max", "must be greater than 0"));
+            }
+            if self.min > self.max {
+                errors.push(ValidationError::new("limits.min", "must not exceed limits.max"));
+            }
+            if errors.is_empty() { Ok(()) } else { Err(errors) }
+        }
+    }
+
+    #[test]
+    fn test_validation_errors_convert_to_structured_error() {
+        assert!(Limits { min: 1, max: 2 }.validate_rules().is_ok());
+
+        let errors = Limits { min: 3, max: 0 }.validate_rules().unwrap_err();
+        let error = QuantumConfigError::from(errors);
+        assert_eq!(error.code(), "validation_failed");
+        let message = error.to_string();
+        assert!(message.contains("limits.max: must be greater than 0"));
+        assert!(message.contains("limits.min: must not exceed limits.max"));
+    }
+
+    #[test]
+    fn test_declarative_validators() {
+        assert!(check_range("port", &8080u16, Some(1.0), Some(65535.0), false).is_ok());
+        assert!(check_range("ratio", &Some(0.5), None, Some(1.0), false).is_ok());
+        let error = check_range("port", &0u16, Some(1.0), Some(65535.0), false).unwrap_err();
+        assert!(error.to_string().contains("value 0 is out of range [1, 65535]"));
+
+        assert!(check_one_of("format", "json", &["json", "text"], false).is_ok());
+        assert!(check_one_of("levels", &vec![1, 2], &["1", "2"], false).is_ok());
+        let error = check_one_of("format", "xml", &["json", "text"], false).unwrap_err();
+        assert!(error.to_string().contains("is not one of: json, text"));
+
+        assert!(check_non_empty("hosts", &vec!["a"]).is_ok());
+        assert!(check_non_empty("name", "  ").is_err());
+        assert!(check_non_empty("token", &None::<String>).is_err());
+        assert!(check_non_empty("hosts", &Vec::<String>::new()).is_err());
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_check_regex_matches_whole_value() {
+        let regex = FieldRegex::new("[a-z]+-[0-9]+").unwrap();
+        assert!(check_regex("name", "svc-1", &regex, false).is_ok());
+        // 正则自动锚定首尾
+        let error = check_regex("name", "xsvc-1x", &regex, false).unwrap_err();
+        assert!(error.to_string().contains("does not match pattern '[a-z]+-[0-9]+'"), "{}", error);
+        assert!(FieldRegex::new("(").unwrap_err().to_string().contains("invalid regex"));
+
+        let digits = FieldRegex::new("[0-9]+").unwrap();
+        let error = check_regex("api_key", "hunter2", &digits, true).unwrap_err();
+        assert!(!error.to_string().contains("hunter2"));
+    }
+
+    #[test]
+    fn test_collect_errors_reports_every_field() {
+        assert!(collect_errors(Vec::new()).is_ok());
+
+        // 单个错误原样返回
+        let single = check_non_empty("name", "").unwrap_err();
+        assert!(matches!(collect_errors(vec![single]), Err(QuantumConfigError::ConstraintViolation { .. })));
+
+        let errors = vec![
+            check_non_empty("name", "").unwrap_err(),
+            check_range("port", &0u16, Some(1.0), None, false).unwrap_err(),
+            QuantumConfigError::from(vec![ValidationError::new("limits.max", "must be greater than 0")]),
+        ];
+        match collect_errors(errors) {
+            Err(QuantumConfigError::ValidationFailed { errors }) => {
+                let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+                assert_eq!(fields, ["name", "port", "limits.max"]);
+            }
+            other => panic!("expected ValidationFailed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_max_len_redacts_secret_values() {
+        let error = check_max_len("api_token", &Some("super-secret-token"), 8, true).unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("api_token"));
+        assert!(message.contains("redacted"));
+        assert!(!message.contains("super-secret-token"));
+
+        let error = check_max_len("name", "too-long-name", 8, false).unwrap_err();
+        assert!(matches!(&error, QuantumConfigError::ConstraintViolation { key_path, .. } if key_path == "name"));
+        assert!(error.to_string().contains("too-long-name"));
+
+        // 过长的值只显示开头
+        let long_value = format!("{}{}", "a".repeat(32), "b".repeat(1000));
+        let message = check_max_len("name", &long_value, 8, false).unwrap_err().to_string();
+        assert!(message.contains(&format!("\"{}…\" has length 1032", "a".repeat(32))), "{}", message);
+        assert!(!message.contains('b'), "{}", message);
+        let message = check_one_of("format", &"配".repeat(40), &["json"], false).unwrap_err().to_string();
+        assert!(message.contains(&format!("value \"{}…\"", "配".repeat(32))), "{}", message);
+    }
+}
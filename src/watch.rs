@@ -0,0 +1,503 @@
+//! Quantum Config 配置监视模块
+//!
+//! 监视已解析的配置文件，文件变化后重新构建 figment 并提取配置，
+//! 通过通道或回调把新的配置值交给调用方，免去手写的轮询重载循环。
+//!
+//! 为了兼容编辑器"写入临时文件再重命名"的保存方式，监视的是配置文件所在的目录，
+//! 并只处理与被监视文件相关的事件；短时间内的连续事件会被合并为一次重载。
+//!
+//! 远程来源（例如 etcd）可以通过 [`ConfigWatcher::from_notifications`] 以变更通知驱动同样的重载流程。
+//!
+//! [`ConfigWatcher::reloadable`] 把重载结果写入 [`Reloadable`] 句柄，调用方随时读取最新配置的快照，
+//! 无需自行维护共享状态。
+
+use crate::error::QuantumConfigError;
+use crate::logging::log_warn;
+use crate::meta::QuantumConfigAppMeta;
+use crate::paths::resolve_config_files;
+use crate::providers::{EnvSnapshot, QuantumConfigEnvProvider, QuantumConfigFileProvider};
+use crate::reload::Reloadable;
+use figment::Figment;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::time::Duration;
+
+/// 合并连续文件事件的静默时间
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// 重载结果的投递方式
+pub(crate) enum Sink<T> {
+    /// 通过通道发送
+    Channel(Sender<Result<T, QuantumConfigError>>),
+    /// 调用回调函数
+    Callback(Box<dyn FnMut(Result<T, QuantumConfigError>) + Send>),
+}
+
+impl<T> Sink<T> {
+    /// 投递一次重载结果，接收方已关闭时返回 false
+    pub(crate) fn deliver(&mut self, result: Result<T, QuantumConfigError>) -> bool {
+        match self {
+            Sink::Channel(sender) => sender.send(result).is_ok(),
+            Sink::Callback(callback) => {
+                callback(result);
+                true
+            }
+        }
+    }
+}
+
+/// 配置监视器
+///
+/// 监视器被丢弃时停止监视，后台重载线程随之退出。
+pub struct ConfigWatcher<T> {
+    /// 底层文件监视器，由变更通知驱动时为 `None`
+    _watcher: Option<RecommendedWatcher>,
+    /// 被监视的配置文件
+    paths: Vec<PathBuf>,
+    /// 通道模式下的接收端
+    receiver: Option<Receiver<Result<T, QuantumConfigError>>>,
+    /// 后台轮询线程的停止信号，监视器被丢弃时关闭
+    _stop: Option<Sender<()>>,
+}
+
+impl<T> std::fmt::Debug for ConfigWatcher<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConfigWatcher")
+            .field("paths", &self.paths)
+            .field("has_receiver", &self.receiver.is_some())
+            .finish()
+    }
+}
+
+impl<T> ConfigWatcher<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    /// 创建通过通道投递新配置的监视器
+    ///
+    /// # Arguments
+    /// * `paths` - 需要监视的配置文件
+    /// * `figment_fn` - 每次重载时用于构建 figment 的函数
+    ///
+    /// # Errors
+    /// 无法建立文件监视时返回错误
+    pub fn new<F>(paths: Vec<PathBuf>, figment_fn: F) -> Result<Self, QuantumConfigError>
+    where
+        F: Fn() -> Figment + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = Self::start(paths, figment_fn, Sink::Channel(sender))?;
+        watcher.receiver = Some(receiver);
+        Ok(watcher)
+    }
+
+    /// 创建在每次重载后调用回调的监视器
+    ///
+    /// 回调在后台线程中执行，提取失败时收到的是错误。
+    ///
+    /// # Errors
+    /// 无法建立文件监视时返回错误
+    pub fn with_callback<F, C>(paths: Vec<PathBuf>, figment_fn: F, callback: C) -> Result<Self, QuantumConfigError>
+    where
+        F: Fn() -> Figment + Send + 'static,
+        C: FnMut(Result<T, QuantumConfigError>) + Send + 'static,
+    {
+        Self::start(paths, figment_fn, Sink::Callback(Box::new(callback)))
+    }
+
+    /// 创建把每次成功重载的配置写入 [`Reloadable`] 句柄的监视器
+    ///
+    /// 句柄以 `initial`（通常是启动时加载的配置）开始；重载失败时保留当前配置并输出警告日志。
+    /// 监视器被丢弃后句柄不再更新，但仍可读取最后一次的配置。
+    ///
+    /// # Errors
+    /// 无法建立文件监视时返回错误
+    pub fn reloadable<F>(paths: Vec<PathBuf>, figment_fn: F, initial: T) -> Result<(Self, Reloadable<T>), QuantumConfigError>
+    where
+        F: Fn() -> Figment + Send + 'static,
+        T: Sync,
+    {
+        let reloadable = Reloadable::new(initial);
+        let watcher = Self::start(paths, figment_fn, reloadable_sink(&reloadable))?;
+        Ok((watcher, reloadable))
+    }
+
+    /// 根据应用元数据解析配置文件并创建通道模式的监视器
+    ///
+    /// 每次重载按"文件 -> 环境变量"的顺序合并，与派生宏的加载顺序一致（不含命令行参数）。
+    /// 环境变量通过 [`EnvSnapshot::cached`] 读取，同一代内的重载复用同一份快照，
+    /// 需要重新读取环境变量时调用 [`EnvSnapshot::invalidate`]。
+    ///
+    /// # Errors
+    /// 路径解析失败或无法建立文件监视时返回错误
+    pub fn from_app_meta(app_meta: &QuantumConfigAppMeta) -> Result<Self, QuantumConfigError> {
+        let config_files = match resolve_config_files(app_meta) {
+            Ok(files) => files,
+            Err(QuantumConfigError::NoConfigFilesFoundInDir { .. })
+            | Err(QuantumConfigError::ConfigDirNotFound { .. }) => Vec::new(),
+            Err(e) => return Err(e),
+        };
+
+        let paths = config_files.iter().map(|cfg| cfg.path.clone()).collect();
+        let env_prefix = app_meta.env_prefix.clone();
+        let max_parse_depth = app_meta.max_parse_depth;
+
+        Self::new(paths, move || {
+            let mut figment = Figment::new();
+            for cfg in &config_files {
+                match QuantumConfigFileProvider::from_path(&cfg.path, cfg.is_required, max_parse_depth) {
+                    Ok(provider) => figment = figment.merge(provider),
+                    Err(e) => figment = figment.merge(FailingProvider(e.to_string())),
+                }
+            }
+            if let Some(prefix) = env_prefix.as_deref() {
+                let snapshot = EnvSnapshot::cached(prefix);
+                figment = figment.merge(QuantumConfigEnvProvider::with_prefix(prefix).with_snapshot(snapshot));
+            }
+            figment
+        })
+    }
+
+    /// 创建由变更通知驱动、通过通道投递新配置的监视器
+    ///
+    /// 每收到一条通知（短时间内的连续通知合并为一次）就重新构建 figment 并提取配置，
+    /// 通知的发送端全部关闭后监视停止。
+    pub fn from_notifications<N, F>(notifications: Receiver<N>, figment_fn: F) -> Self
+    where
+        N: Send + 'static,
+        F: Fn() -> Figment + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = Self::from_loader(notifications, extract_with(figment_fn), Sink::Channel(sender));
+        watcher.receiver = Some(receiver);
+        watcher
+    }
+
+    /// 创建由变更通知驱动、把每次成功重载的配置写入 [`Reloadable`] 句柄的监视器
+    ///
+    /// 重载失败时的行为与 [`ConfigWatcher::reloadable`] 相同。
+    pub fn reloadable_from_notifications<N, F>(notifications: Receiver<N>, figment_fn: F, initial: T) -> (Self, Reloadable<T>)
+    where
+        N: Send + 'static,
+        F: Fn() -> Figment + Send + 'static,
+        T: Sync,
+    {
+        let reloadable = Reloadable::new(initial);
+        let watcher = Self::from_loader(notifications, extract_with(figment_fn), reloadable_sink(&reloadable));
+        (watcher, reloadable)
+    }
+
+    /// 每收到一条通知就调用 `load` 重新加载配置
+    pub(crate) fn from_loader<N, L>(notifications: Receiver<N>, load: L, sink: Sink<T>) -> Self
+    where
+        N: Send + 'static,
+        L: Fn() -> Result<T, QuantumConfigError> + Send + 'static,
+    {
+        spawn_reloader(notifications, |_| true, load, sink);
+        Self {
+            _watcher: None,
+            paths: Vec::new(),
+            receiver: None,
+            _stop: None,
+        }
+    }
+
+    /// 包装由调用方启动的后台轮询线程，监视器被丢弃时关闭 `stop` 通道
+    pub(crate) fn from_poller(stop: Sender<()>, receiver: Option<Receiver<Result<T, QuantumConfigError>>>) -> Self {
+        Self {
+            _watcher: None,
+            paths: Vec::new(),
+            receiver,
+            _stop: Some(stop),
+        }
+    }
+
+    /// 建立文件监视并启动后台重载线程
+    fn start<F>(paths: Vec<PathBuf>, figment_fn: F, sink: Sink<T>) -> Result<Self, QuantumConfigError>
+    where
+        F: Fn() -> Figment + Send + 'static,
+    {
+        let (event_sender, event_receiver) = mpsc::channel::<notify::Result<Event>>();
+        let mut watcher = notify::recommended_watcher(event_sender)
+            .map_err(|e| QuantumConfigError::Watch(e.to_string()))?;
+
+        // 监视配置文件所在目录，以捕获替换式保存
+        let mut directories = HashSet::new();
+        for path in &paths {
+            let directory = watch_directory(path);
+            if directory.is_dir() && directories.insert(directory.clone()) {
+                watcher
+                    .watch(&directory, RecursiveMode::NonRecursive)
+                    .map_err(|e| QuantumConfigError::Watch(e.to_string()))?;
+            }
+        }
+
+        let watched: HashSet<PathBuf> = paths.iter().map(|p| normalize(p)).collect();
+        spawn_reloader(event_receiver, move |event| is_relevant(event, &watched), extract_with(figment_fn), sink);
+
+        Ok(Self {
+            _watcher: Some(watcher),
+            paths,
+            receiver: None,
+            _stop: None,
+        })
+    }
+}
+
+impl<T> ConfigWatcher<T> {
+    /// 被监视的配置文件
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// 阻塞等待下一次重载结果
+    ///
+    /// 回调模式下或监视已停止时返回 `None`。
+    pub fn recv(&self) -> Option<Result<T, QuantumConfigError>> {
+        self.receiver.as_ref()?.recv().ok()
+    }
+
+    /// 在超时时间内等待下一次重载结果
+    pub fn recv_timeout(&self, timeout: Duration) -> Option<Result<T, QuantumConfigError>> {
+        self.receiver.as_ref()?.recv_timeout(timeout).ok()
+    }
+
+    /// 非阻塞地获取一次重载结果
+    pub fn try_recv(&self) -> Option<Result<T, QuantumConfigError>> {
+        match self.receiver.as_ref()?.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => None,
+        }
+    }
+}
+
+/// 每次重载时构建 figment 并提取配置
+fn extract_with<T, F>(figment_fn: F) -> impl Fn() -> Result<T, QuantumConfigError> + Send + 'static
+where
+    T: DeserializeOwned,
+    F: Fn() -> Figment + Send + 'static,
+{
+    move || figment_fn().extract::<T>().map_err(QuantumConfigError::from)
+}
+
+/// 把成功重载的配置写入句柄，重载失败时保留当前配置
+pub(crate) fn reloadable_sink<T: Send + Sync + 'static>(reloadable: &Reloadable<T>) -> Sink<T> {
+    let handle = reloadable.clone();
+    Sink::Callback(Box::new(move |result| match result {
+        Ok(config) => {
+            handle.store(config);
+        }
+        Err(e) => log_warn!("Configuration reload failed, keeping the current configuration: {}", e),
+    }))
+}
+
+/// 启动后台重载线程：收到相关事件并等待事件平息后重新加载配置
+fn spawn_reloader<E, P, L, T>(events: Receiver<E>, relevant: P, load: L, mut sink: Sink<T>)
+where
+    E: Send + 'static,
+    P: Fn(&E) -> bool + Send + 'static,
+    L: Fn() -> Result<T, QuantumConfigError> + Send + 'static,
+    T: Send + 'static,
+{
+    std::thread::spawn(move || {
+        while let Ok(event) = events.recv() {
+            if !relevant(&event) {
+                continue;
+            }
+
+            // 等待事件平息后再重载；事件源在此期间关闭时仍完成这一次重载
+            let closed = loop {
+                match events.recv_timeout(DEBOUNCE) {
+                    Ok(_) => continue,
+                    Err(RecvTimeoutError::Timeout) => break false,
+                    Err(RecvTimeoutError::Disconnected) => break true,
+                }
+            };
+
+            if !sink.deliver(load()) || closed {
+                return;
+            }
+        }
+    });
+}
+
+/// 在重载时报告文件提供器创建失败的占位提供器
+struct FailingProvider(String);
+
+impl figment::Provider for FailingProvider {
+    fn metadata(&self) -> figment::Metadata {
+        figment::Metadata::named("Quantum Config Watch")
+    }
+
+    fn data(&self) -> Result<figment::value::Map<figment::Profile, figment::value::Dict>, figment::Error> {
+        Err(figment::Error::from(self.0.clone()))
+    }
+}
+
+/// 获取需要监视的目录
+fn watch_directory(path: &Path) -> PathBuf {
+    let absolute = normalize(path);
+    absolute
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or(absolute)
+}
+
+/// 规范化路径以便与事件路径比较
+fn normalize(path: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+
+    // 目录可能是符号链接（例如 macOS 的 /var），以规范化后的父目录为准
+    match (absolute.parent(), absolute.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or(absolute),
+        _ => absolute,
+    }
+}
+
+/// 判断事件是否涉及被监视的文件
+fn is_relevant(event: &notify::Result<Event>, watched: &HashSet<PathBuf>) -> bool {
+    match event {
+        Ok(event) => {
+            !matches!(event.kind, EventKind::Access(_))
+                && event.paths.iter().any(|p| watched.contains(&normalize(p)))
+        }
+        // 监视出错时重载一次，让调用方获得最新状态
+        Err(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct TestConfig {
+        port: u16,
+    }
+
+    fn file_figment(path: PathBuf) -> impl Fn() -> Figment + Send + 'static {
+        move || Figment::new().merge(QuantumConfigFileProvider::from_path(&path, true, 32).unwrap())
+    }
+
+    #[test]
+    fn test_watcher_delivers_updated_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "port = 8080").unwrap();
+
+        let watcher = ConfigWatcher::<TestConfig>::new(vec![path.clone()], file_figment(path.clone())).unwrap();
+        assert_eq!(watcher.paths(), std::slice::from_ref(&path));
+        assert!(watcher.try_recv().is_none());
+
+        fs::write(&path, "port = 9090").unwrap();
+        let config = watcher.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(config, TestConfig { port: 9090 });
+    }
+
+    #[test]
+    fn test_watcher_reports_invalid_config() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "port = 8080").unwrap();
+
+        let watcher = ConfigWatcher::<TestConfig>::new(vec![path.clone()], file_figment(path.clone())).unwrap();
+        fs::write(&path, "port = \"not a number\"").unwrap();
+
+        let result = watcher.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert!(matches!(result, Err(QuantumConfigError::Extraction { key_path, .. }) if key_path == "port"));
+    }
+
+    #[test]
+    fn test_watcher_invokes_callback() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "port = 8080").unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let watcher = ConfigWatcher::<TestConfig>::with_callback(vec![path.clone()], file_figment(path.clone()), move |result| {
+            let _ = sender.send(result.map(|config| config.port));
+        })
+        .unwrap();
+        assert!(watcher.recv().is_none());
+
+        fs::write(&path, "port = 7070").unwrap();
+        let port = receiver.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(port, 7070);
+    }
+
+    #[test]
+    fn test_reloadable_tracks_successful_reloads() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "port = 8080").unwrap();
+
+        let (_watcher, config) =
+            ConfigWatcher::reloadable(vec![path.clone()], file_figment(path.clone()), TestConfig { port: 8080 }).unwrap();
+        let updates = config.subscribe();
+        assert_eq!(config.generation(), 0);
+
+        fs::write(&path, "port = 9090").unwrap();
+        let update = updates.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(update.generation, 1);
+        assert_eq!(*config.load(), TestConfig { port: 9090 });
+
+        // 无效的配置不替换当前值
+        fs::write(&path, "port = \"not a number\"").unwrap();
+        assert!(updates.recv_timeout(Duration::from_millis(800)).is_err());
+        assert_eq!(config.load().port, 9090);
+        assert_eq!(config.generation(), 1);
+    }
+
+    #[test]
+    fn test_unrelated_files_are_ignored() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "port = 8080").unwrap();
+
+        let watcher = ConfigWatcher::<TestConfig>::new(vec![path.clone()], file_figment(path)).unwrap();
+        fs::write(dir.path().join("other.toml"), "port = 1").unwrap();
+
+        assert!(watcher.recv_timeout(Duration::from_millis(800)).is_none());
+    }
+
+    #[test]
+    fn test_notifications_drive_reloads() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "port = 8080").unwrap();
+
+        let (notify, notifications) = mpsc::channel();
+        let watcher = ConfigWatcher::<TestConfig>::from_notifications(notifications, file_figment(path.clone()));
+        assert!(watcher.paths().is_empty());
+        assert!(watcher.try_recv().is_none());
+
+        fs::write(&path, "port = 9090").unwrap();
+        notify.send(()).unwrap();
+        notify.send(()).unwrap();
+        let config = watcher.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(config, TestConfig { port: 9090 });
+
+        // 通知源关闭前的最后一次变更仍会被投递
+        fs::write(&path, "port = 7070").unwrap();
+        notify.send(()).unwrap();
+        drop(notify);
+        let config = watcher.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(config, TestConfig { port: 7070 });
+        assert!(watcher.recv_timeout(Duration::from_millis(500)).is_none());
+    }
+}
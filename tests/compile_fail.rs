@@ -0,0 +1,7 @@
+//! 派生宏的编译期错误
+
+#[test]
+fn derive_rejects_invalid_attributes() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
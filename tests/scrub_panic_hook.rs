@@ -0,0 +1,27 @@
+//! panic 钩子是进程级的全局状态，放在单独的测试程序中，不影响其他并行运行的测试
+#![cfg(feature = "secret-scrub")]
+
+use quantum_config::scrub::{install_guard, register_secret};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn guard_chains_to_previous_hook_without_secrets() {
+    register_secret("scrub-test-panic-secret");
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let recorded = seen.clone();
+    let original = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info.payload().downcast_ref::<&str>().map(|s| s.to_string());
+        recorded.lock().unwrap().push(message.unwrap_or_default());
+    }));
+
+    let guard = install_guard();
+    let _ = std::panic::catch_unwind(|| panic!("scrub-test-plain-panic"));
+    let _ = std::panic::catch_unwind(|| panic!("scrub-test-panic-secret"));
+    drop(guard);
+    // 防护被丢弃后恢复安装前的钩子
+    let _ = std::panic::catch_unwind(|| panic!("scrub-test-after-guard"));
+    std::panic::set_hook(original);
+
+    assert_eq!(*seen.lock().unwrap(), ["scrub-test-plain-panic", "scrub-test-after-guard"]);
+}
@@ -0,0 +1,10 @@
+use quantum_config::Config;
+use serde::{Deserialize, Serialize};
+
+#[derive(Config, Serialize, Deserialize, Debug, Default)]
+struct AppConfig {
+    #[config(secrte)]
+    password: String,
+}
+
+fn main() {}
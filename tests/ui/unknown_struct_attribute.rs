@@ -0,0 +1,10 @@
+use quantum_config::Config;
+use serde::{Deserialize, Serialize};
+
+#[derive(Config, Serialize, Deserialize, Debug, Default)]
+#[config(source = "file")]
+struct AppConfig {
+    name: String,
+}
+
+fn main() {}